@@ -0,0 +1,142 @@
+// Limit change dry-run (`--dry-run` on memory-max, cpu-max, pids-max, io-max)
+// Lesson: docs/02-cgroups/09-dry-run.md
+// Tests: tests/dryrun_test.rs
+//
+// A dry run does everything a real write does except the write itself:
+// parse and validate the value, read what is there now, and compare the new
+// limit with the current usage. The kernel accepts most limits that are
+// harmful, so the comparison is where a dry run earns its keep:
+//
+//   $ cgroup-tool memory-max lab/web 20971520 --dry-run
+//   would write "20971520" to /sys/fs/cgroup/lab/web/memory.max (currently "max")
+//   warning: memory.current is 52428800 (50.0 MiB), above the new limit:
+//            the kernel reclaims immediately and OOM-kills if it cannot
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+/// Smallest quota `cpu.max` accepts, in microseconds.
+#[allow(dead_code)]
+pub const CPU_QUOTA_MIN_US: u64 = 1_000;
+
+/// Largest period `cpu.max` accepts, in microseconds (one second).
+#[allow(dead_code)]
+pub const CPU_PERIOD_MAX_US: u64 = 1_000_000;
+
+/// Keys accepted in an `io.max` line after "MAJ:MIN".
+#[allow(dead_code)]
+pub const IO_MAX_KEYS: &[&str] = &["rbps", "wbps", "riops", "wiops"];
+
+/// One limit write, planned but not applied.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlannedWrite {
+    /// Full path of the control file (e.g. /sys/fs/cgroup/lab/web/memory.max)
+    pub file: PathBuf,
+    /// Exactly what would be written
+    pub value: String,
+    /// Current contents (trimmed), or None if the file does not exist yet
+    pub current: Option<String>,
+    /// Impact of the change given the current usage, one sentence each
+    pub warnings: Vec<String>,
+}
+
+impl PlannedWrite {
+    /// Human-readable plan, as printed by `--dry-run`.
+    #[allow(dead_code)]
+    pub fn render(&self) -> String {
+        // TODO: Implement plan rendering
+        // Lesson: docs/02-cgroups/09-dry-run.md
+        //
+        // Implementation hints:
+        // - First line: would write "{value}" to {file} (currently "{current}")
+        // - "(file does not exist: is the controller enabled?)" instead of
+        //   the current value when `current` is None
+        // - One "warning: ..." line per entry in `warnings`
+        // - Same value as now: say "no change" so nobody wonders what happens
+        todo!("Implement plan rendering")
+    }
+}
+
+/// Validate `value` for `file` and compare it with the cgroup's current usage.
+///
+/// Errors are for values the kernel would reject (EINVAL); things that would
+/// be accepted but hurt, such as `memory.max` below `memory.current`, become
+/// warnings. Nothing is written.
+#[allow(dead_code)]
+pub fn plan(cgroup_dir: &Path, file: &str, value: &str) -> Result<PlannedWrite> {
+    // TODO: Implement the dry-run plan
+    // Lesson: docs/02-cgroups/09-dry-run.md
+    //
+    // Implementation hints:
+    // - Fail if cgroup_dir does not exist: a dry run of a typo should fail
+    //   just like the real write would
+    // - Validate first: validate_cpu_max(), validate_io_max(); memory and
+    //   pids values are already u64s from clap
+    // - Read `current` with read_trimmed(); Ok(None) for a missing file
+    // - Collect warnings with impact(cgroup_dir, file, value)
+    let _ = (cgroup_dir, file, value);
+    todo!("Implement dry-run plan")
+}
+
+/// Warnings for writing `value` to `file`, based on the live usage files.
+#[allow(dead_code)]
+pub fn impact(cgroup_dir: &Path, file: &str, value: &str) -> Result<Vec<String>> {
+    // TODO: Implement impact estimation
+    // Lesson: docs/02-cgroups/09-dry-run.md
+    //
+    // Implementation hints:
+    // - "max" never has an impact: return no warnings
+    // - memory.max below memory.current: immediate reclaim, OOM kill if the
+    //   memory cannot be reclaimed (anonymous memory without swap)
+    // - memory.max below memory.high: memory.high can never be reached
+    // - pids.max below pids.current: nothing is killed, but every fork() and
+    //   clone() fails with EAGAIN until enough tasks exit
+    // - cpu.max quota above period * nr_cpus (std::thread::available_parallelism):
+    //   the limit can never throttle
+    // - io.max for a device that does not exist in /sys/dev/block/MAJ:MIN:
+    //   the kernel rejects it with ENODEV, so this one is an error
+    let _ = (cgroup_dir, file, value);
+    todo!("Implement impact estimation")
+}
+
+/// Check a `cpu.max` value: "max", "QUOTA" or "QUOTA PERIOD" in microseconds.
+#[allow(dead_code)]
+pub fn validate_cpu_max(value: &str) -> Result<()> {
+    // TODO: Implement cpu.max validation
+    // Lesson: docs/02-cgroups/09-dry-run.md
+    //
+    // Implementation hints:
+    // - Split on whitespace: 1 or 2 fields
+    // - Quota is "max" or an integer >= CPU_QUOTA_MIN_US
+    // - Period (default 100000) must be between CPU_QUOTA_MIN_US and CPU_PERIOD_MAX_US
+    // - anyhow::bail! with the rule that was broken, e.g.
+    //   "quota 500 is below the 1000us minimum"
+    let _ = value;
+    todo!("Implement cpu.max validation")
+}
+
+/// Check an `io.max` line: "MAJ:MIN key=value..." with keys from `IO_MAX_KEYS`.
+#[allow(dead_code)]
+pub fn validate_io_max(device: &str, limit: &str) -> Result<()> {
+    // TODO: Implement io.max validation
+    // Lesson: docs/02-cgroups/09-dry-run.md
+    //
+    // Implementation hints:
+    // - device is "MAJ:MIN", both integers
+    // - Each limit token is key=value, the key in IO_MAX_KEYS and the value
+    //   "max" or an integer > 0
+    // - Reject duplicate keys: the kernel keeps the last one silently
+    let _ = (device, limit);
+    todo!("Implement io.max validation")
+}
+
+/// Read a control file, trimmed; Ok(None) if it does not exist.
+#[allow(dead_code)]
+fn read_trimmed(path: &Path) -> Result<Option<String>> {
+    // TODO: Implement in lesson 09 (used by plan() and impact())
+    // Hint: match std::fs::read_to_string and map ErrorKind::NotFound to None
+    let _ = path;
+    todo!("Implement control file read")
+}
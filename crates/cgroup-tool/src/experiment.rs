@@ -0,0 +1,177 @@
+// Measured CPU experiments (`experiment cpu`)
+// Lesson: docs/02-cgroups/10-cpu-experiment.md
+// Tests: tests/experiment_test.rs
+//
+// An experiment creates a scratch cgroup, applies cpuset.cpus and cpu.max
+// together, runs one workload in it and compares the CPU time it got with
+// the most it could have got. The two limits interact: the ceiling is the
+// lower of "CPUs in the set" and "quota / period", so 150% on a 1-CPU set
+// is still 1 CPU.
+//
+//   $ cgroup-tool experiment cpu --cpus 0-1 --quota 50% -- stress-ng -c 4 -t 10
+//   cpus:      0-1 (2 CPUs)
+//   quota:     50% (50000 100000)
+//   ceiling:   0.50 CPU (the quota)
+//   achieved:  0.49 CPU (4.93s of CPU in 10.02s, 98% of the ceiling)
+//              user 4.91s, system 0.02s
+//   throttled: 99 of 100 periods, 5.04s in total
+//   exit:      0
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// `cpu.max` period used for a percentage quota, in microseconds.
+#[allow(dead_code)]
+pub const DEFAULT_PERIOD_US: u64 = 100_000;
+
+/// A `cpu.max` limit as the experiment applies it.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuQuota {
+    pub quota_us: u64,
+    pub period_us: u64,
+}
+
+#[allow(dead_code)]
+impl CpuQuota {
+    /// CPUs' worth of time per period, e.g. 0.5 for "50000 100000".
+    pub fn cpus(&self) -> f64 {
+        self.quota_us as f64 / self.period_us as f64
+    }
+
+    /// The line written to cpu.max: "QUOTA PERIOD".
+    pub fn cpu_max_value(&self) -> String {
+        format!("{} {}", self.quota_us, self.period_us)
+    }
+}
+
+/// Parse `--quota`: a percentage of one CPU ("50%", "150%") or a raw
+/// cpu.max value ("50000 100000").
+#[allow(dead_code)]
+pub fn parse_quota(spec: &str) -> Result<CpuQuota> {
+    // TODO: Implement quota parsing
+    // Lesson: docs/02-cgroups/10-cpu-experiment.md
+    //
+    // Implementation hints:
+    // - "N%" is N percent of ONE CPU, like top: 150% = 1.5 CPUs =
+    //   "150000 100000" with DEFAULT_PERIOD_US. Allow decimals ("12.5%")
+    // - Anything else must pass dryrun::validate_cpu_max(), then split it;
+    //   "max" is not a quota for an experiment: bail and suggest leaving
+    //   --quota out
+    // - Reject 0% and quotas below dryrun::CPU_QUOTA_MIN_US with the same
+    //   message as cpu-max
+    let _ = spec;
+    todo!("Implement quota parsing")
+}
+
+/// Parse a cpuset list like "0-1,4" into sorted, de-duplicated CPU numbers.
+#[allow(dead_code)]
+pub fn parse_cpu_list(list: &str) -> Result<Vec<u32>> {
+    // TODO: Implement cpu list parsing
+    // Lesson: docs/02-cgroups/10-cpu-experiment.md
+    //
+    // Implementation hints:
+    // - Comma-separated items, each "N" or "A-B" with A <= B
+    // - Same format as cpuset.cpus and /sys/devices/system/cpu/online, so
+    //   the same function reads cpuset.cpus.effective of the parent
+    // - An empty list is an error: it would mean "no CPUs"
+    let _ = list;
+    todo!("Implement cpu list parsing")
+}
+
+/// Most CPUs' worth of time the workload can get: the smaller of the CPU
+/// count and the quota, whichever limits are set.
+#[allow(dead_code)]
+pub fn ceiling(cpus: usize, quota: Option<CpuQuota>) -> f64 {
+    // TODO: Implement the ceiling
+    // Lesson: docs/02-cgroups/10-cpu-experiment.md
+    //
+    // Implementation hints:
+    // - `cpus` is the size of the CPU set, or of all online CPUs without
+    //   --cpus (std::thread::available_parallelism is not it: it already
+    //   honours the caller's own affinity)
+    // - No quota: cpus as f64. Otherwise cpus.min(quota.cpus())
+    let _ = (cpus, quota);
+    todo!("Implement ceiling")
+}
+
+/// The counters of `cpu.stat` the report uses, in microseconds.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuStat {
+    pub usage_usec: u64,
+    pub user_usec: u64,
+    pub system_usec: u64,
+    pub nr_periods: u64,
+    pub nr_throttled: u64,
+    pub throttled_usec: u64,
+}
+
+/// Parse the "key value" lines of cpu.stat.
+#[allow(dead_code)]
+pub fn parse_cpu_stat(text: &str) -> Result<CpuStat> {
+    // TODO: Implement cpu.stat parsing
+    // Lesson: docs/02-cgroups/10-cpu-experiment.md
+    //
+    // Implementation hints:
+    // - usage_usec, user_usec and system_usec are always there; the
+    //   nr_* and throttled_usec lines only exist when the cpu controller is
+    //   enabled for the cgroup: default them to 0
+    // - Ignore unknown keys (nr_bursts, burst_usec on newer kernels)
+    let _ = text;
+    todo!("Implement cpu.stat parsing")
+}
+
+/// Read and parse `<cgroup_dir>/cpu.stat`.
+#[allow(dead_code)]
+pub fn read_cpu_stat(cgroup_dir: &Path) -> Result<CpuStat> {
+    // TODO: Implement in lesson 10 (read after the workload exits)
+    // Hint: std::fs::read_to_string(cgroup_dir.join("cpu.stat")), then
+    // parse_cpu_stat(); add the path to the error with anyhow::Context
+    let _ = cgroup_dir;
+    todo!("Implement cpu.stat read")
+}
+
+/// Result of one experiment.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuReport {
+    /// CPUs the workload was allowed to use
+    pub cpus: Vec<u32>,
+    pub quota: Option<CpuQuota>,
+    /// Wall-clock time from spawn to exit (or to the --duration kill)
+    pub wall: Duration,
+    /// cpu.stat of the scratch cgroup after the workload exited
+    pub stat: CpuStat,
+    /// Exit code, or None if the workload was killed by a signal
+    pub exit_code: Option<i32>,
+}
+
+#[allow(dead_code)]
+impl CpuReport {
+    /// CPUs' worth of time actually used: usage_usec / wall.
+    pub fn achieved(&self) -> f64 {
+        // TODO: Implement in lesson 10
+        // Hint: 0.0 for a zero wall time rather than NaN
+        todo!("Implement achieved CPU")
+    }
+
+    /// The report, as printed at the end of `experiment cpu`.
+    pub fn render(&self) -> String {
+        // TODO: Implement report rendering
+        // Lesson: docs/02-cgroups/10-cpu-experiment.md
+        //
+        // Implementation hints:
+        // - Lines as in the example at the top of this file
+        // - "ceiling: ... (the quota)" or "(the CPU set)": say which limit
+        //   binds, so a surprising result explains itself
+        // - Achieved as a share of the ceiling; far below 100% means the
+        //   workload did not try to use it (single-threaded, or I/O bound)
+        // - throttled is only meaningful with a quota: leave the line out
+        //   without one
+        // - exit: the code, or "killed" when exit_code is None
+        todo!("Implement report rendering")
+    }
+}
@@ -0,0 +1,233 @@
+// Lesson mode (`--explain` on every subcommand)
+// Lesson: docs/02-cgroups/12-explain.md
+// Tests: tests/explain_test.rs
+//
+// Every change a subcommand makes to /sys/fs/cgroup goes through an
+// ActionLog instead of calling std::fs directly. The log performs the
+// operation, reads the file back, and records which events files changed,
+// so `--explain` can tell the whole story once the command is done:
+//
+//   $ sudo cgroup-tool --explain pids-max lab/web 1
+//   [1] wrote "1" to /sys/fs/cgroup/lab/web/pids.max
+//       why:       cap the number of tasks (processes + threads) in the cgroup
+//       read back: "1"
+//       kernel:    nothing yet; pids.events "max" counts the forks refused from now on
+//       lesson:    docs/02-cgroups/05-pids.md, "Background: The Fork Bomb Problem"
+//
+// The log is filled with or without --explain; only rendering depends on
+// the flag. That keeps one code path, and the log itself is what the tests
+// look at through the --explain output.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+/// What a control file does and where the lessons cover it.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlFile {
+    /// File name inside the cgroup directory, e.g. "memory.max"
+    pub name: &'static str,
+    /// Why a subcommand writes it, one line
+    pub why: &'static str,
+    /// Events or stats file whose changes show the kernel's reaction
+    pub events: Option<&'static str>,
+    /// Lesson and section that explain it
+    pub lesson: &'static str,
+}
+
+/// The control files cgroup-tool writes.
+#[allow(dead_code)]
+pub const CONTROL_FILES: &[ControlFile] = &[
+    ControlFile {
+        name: "cgroup.procs",
+        why: "move a process (all its threads) into the cgroup",
+        events: Some("cgroup.events"),
+        lesson: "docs/02-cgroups/01-cgv2-basics.md, \"Step 2: Implement `attach`\"",
+    },
+    ControlFile {
+        name: "cgroup.subtree_control",
+        why: "enable controllers for the children of this cgroup",
+        events: None,
+        lesson: "docs/02-cgroups/06-multi-resource.md",
+    },
+    ControlFile {
+        name: "cgroup.kill",
+        why: "SIGKILL every process in the cgroup and its descendants",
+        events: Some("cgroup.events"),
+        lesson: "docs/02-cgroups/10-cpu-experiment.md",
+    },
+    ControlFile {
+        name: "memory.max",
+        why: "hard memory limit: reclaim, then OOM kill above it",
+        events: Some("memory.events"),
+        lesson: "docs/02-cgroups/02-memory.md, \"Background: The Memory Controller\"",
+    },
+    ControlFile {
+        name: "cpu.max",
+        why: "CPU bandwidth: quota microseconds per period",
+        events: Some("cpu.stat"),
+        lesson: "docs/02-cgroups/03-cpu.md, \"The cpu.max File Format\"",
+    },
+    ControlFile {
+        name: "cpuset.cpus",
+        why: "the CPUs the cgroup's tasks may run on",
+        events: None,
+        lesson: "docs/02-cgroups/10-cpu-experiment.md",
+    },
+    ControlFile {
+        name: "pids.max",
+        why: "cap the number of tasks (processes + threads) in the cgroup",
+        events: Some("pids.events"),
+        lesson: "docs/02-cgroups/05-pids.md, \"Background: The Fork Bomb Problem\"",
+    },
+    ControlFile {
+        name: "io.max",
+        why: "per-device bandwidth and IOPS limits",
+        events: Some("io.stat"),
+        lesson: "docs/02-cgroups/04-io.md, \"The io.max Format\"",
+    },
+];
+
+/// Look up a control file by name.
+#[allow(dead_code)]
+pub fn control_file(name: &str) -> Option<&'static ControlFile> {
+    CONTROL_FILES.iter().find(|f| f.name == name)
+}
+
+/// One operation on the cgroup filesystem.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// mkdir of a cgroup directory
+    Create,
+    /// rmdir of a cgroup directory
+    Remove,
+    /// A value written to a control file
+    Write { value: String },
+    /// A write that --dry-run planned but did not perform
+    Planned { value: String },
+}
+
+/// An operation and what the kernel did in response.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Action {
+    pub step: Step,
+    /// The directory (Create, Remove) or the control file (Write, Planned)
+    pub path: PathBuf,
+    /// Why the subcommand did it, from CONTROL_FILES or the caller
+    pub why: String,
+    /// The file's contents after the write, trimmed
+    pub read_back: Option<String>,
+    /// Changes in the events file, e.g. "populated 0 -> 1"
+    pub kernel: Vec<String>,
+    /// Lesson section to read next
+    pub lesson: String,
+}
+
+/// Everything a subcommand did, in order.
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct ActionLog {
+    /// `--explain`: render() the log when the command is done
+    pub explain: bool,
+    pub actions: Vec<Action>,
+}
+
+#[allow(dead_code)]
+impl ActionLog {
+    pub fn new(explain: bool) -> Self {
+        Self {
+            explain,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Create the cgroup directory `dir` and record what appeared in it.
+    pub fn create(&mut self, dir: &Path) -> Result<()> {
+        // TODO: Implement in lesson 12 (lesson mode)
+        // Hints:
+        // - std::fs::create_dir(dir)?, then push Step::Create with
+        //   why "new cgroup: the kernel fills the directory with its
+        //   interface files" and the 01-cgv2-basics.md lesson
+        // - kernel: the number of files the kernel created and the
+        //   controllers in cgroup.controllers ("12 files, controllers: cpu
+        //   memory pids")
+        let _ = dir;
+        todo!("Implement ActionLog::create")
+    }
+
+    /// Remove the cgroup directory `dir`.
+    pub fn remove(&mut self, dir: &Path) -> Result<()> {
+        // TODO: Implement in lesson 12 (lesson mode)
+        // Hints:
+        // - std::fs::remove_dir(dir)?; the interface files go with it,
+        //   remove_dir_all would fail on them
+        // - EBUSY: the error stays, the log records nothing
+        let _ = dir;
+        todo!("Implement ActionLog::remove")
+    }
+
+    /// Write `value` to `file` in `dir`, read it back, and record the
+    /// changes in the file's events file.
+    pub fn write(&mut self, dir: &Path, file: &str, value: &str) -> Result<()> {
+        // TODO: Implement in lesson 12 (lesson mode)
+        // Hints:
+        // - control_file(file) for why, events and lesson; unknown files
+        //   still work, with why "" and no events
+        // - Read the events file before the write (read_events), write,
+        //   then read it again and diff_events(before, after)
+        // - read_back: the file trimmed. It can differ from the value:
+        //   "50000" in cpu.max reads back as "50000 100000", and
+        //   cgroup.procs lists every PID, not the one written
+        // - Some reactions are not instant (an OOM kill after reclaim
+        //   failed): a short sleep before the second read catches most
+        let _ = (dir, file, value);
+        todo!("Implement ActionLog::write")
+    }
+
+    /// Record a write that --dry-run only planned.
+    pub fn planned(&mut self, dir: &Path, file: &str, value: &str) {
+        // TODO: Implement in lesson 12 (lesson mode)
+        // Hint: Step::Planned with why and lesson from control_file(), no
+        // read back and no kernel reaction
+        let _ = (dir, file, value);
+        todo!("Implement ActionLog::planned")
+    }
+
+    /// The numbered explanation printed by --explain.
+    pub fn render(&self) -> String {
+        // TODO: Implement in lesson 12 (lesson mode)
+        // Hints:
+        // - One block per action, numbered from 1, as in the header
+        // - "kernel:" lines from `kernel`, or the note "nothing yet" when
+        //   the events file did not change; no line without an events file
+        // - An empty log (top, tui, export only read): "nothing was
+        //   written", so --explain never prints nothing
+        todo!("Implement action log rendering")
+    }
+}
+
+/// Read a flat-keyed events file ("populated 1\nfrozen 0") into pairs.
+#[allow(dead_code)]
+fn read_events(path: &Path) -> Result<Vec<(String, u64)>> {
+    // TODO: Implement in lesson 12 (used by ActionLog::write)
+    // Hints:
+    // - Missing file (controller not enabled): Ok(vec![])
+    // - io.stat has one "MAJ:MIN key=value ..." line per device: use
+    //   "MAJ:MIN rbytes" and so on as the keys
+    let _ = path;
+    todo!("Implement events file read")
+}
+
+/// What changed between two reads of an events file, e.g. "oom_kill 0 -> 1".
+#[allow(dead_code)]
+pub fn diff_events(before: &[(String, u64)], after: &[(String, u64)]) -> Vec<String> {
+    // TODO: Implement in lesson 12 (lesson mode)
+    // Hints:
+    // - One line per key whose value differs, in the order of `after`
+    // - A key only in `after`: "key (new) = value"
+    let _ = (before, after);
+    todo!("Implement events diff")
+}
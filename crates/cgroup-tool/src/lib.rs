@@ -0,0 +1,445 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+mod dryrun;
+mod experiment;
+mod explain;
+mod snapshot;
+mod top;
+mod tui;
+
+#[derive(Parser)]
+#[command(name = "cgroup-tool")]
+#[command(about = "Cgroup v2 tool (Rust-first rewrite)")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// After the command, explain each file it wrote and how the kernel reacted (see src/explain.rs)
+    #[arg(long, global = true)]
+    explain: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Create {
+        path: String,
+    },
+    Delete {
+        path: String,
+    },
+    Attach {
+        path: String,
+        pid: u32,
+    },
+    MemoryMax {
+        path: String,
+        bytes: u64,
+        /// Validate and show what would be written, without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    CpuMax {
+        path: String,
+        quota: String,
+        /// Validate and show what would be written, without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    PidsMax {
+        path: String,
+        max: u64,
+        /// Validate and show what would be written, without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Set I/O bandwidth/IOPS limits for a device
+    IoMax {
+        path: String,
+        /// Device major:minor (e.g., "8:0" for /dev/sda)
+        device: String,
+        /// I/O limit specification (e.g., "rbps=1048576 wbps=1048576")
+        limit: String,
+        /// Validate and show what would be written, without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export a cgroup's configured limits to a TOML snapshot
+    Export {
+        path: String,
+        /// Output file (e.g., "snapshot.toml")
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Re-apply limits from a TOML snapshot to a cgroup
+    Import {
+        /// Snapshot file produced by `export`
+        snapshot: PathBuf,
+        /// Target cgroup path (created if missing)
+        #[arg(long)]
+        to: String,
+    },
+    /// Browse the cgroup tree interactively (live usage, freeze/kill/edit)
+    Tui {
+        /// Cgroup to use as the tree root (default: the whole hierarchy)
+        root: Option<String>,
+        /// Refresh interval in seconds
+        #[arg(short, long, default_value = "1")]
+        interval: u64,
+    },
+    /// List the processes in a cgroup with their memory, CPU time and threads
+    Top {
+        path: String,
+        /// Column to sort by, biggest first
+        #[arg(long, value_enum, default_value_t = top::SortKey::Mem)]
+        sort: top::SortKey,
+        /// Also list the processes of descendant cgroups
+        #[arg(short, long)]
+        recursive: bool,
+    },
+    /// Run a workload under limits and measure what it got
+    Experiment {
+        #[command(subcommand)]
+        kind: ExperimentCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExperimentCommand {
+    /// Pin to CPUs (cpuset.cpus) and cap bandwidth (cpu.max), then compare
+    /// the CPU time used with the ceiling
+    Cpu {
+        /// CPUs to run on, in cpuset format (e.g., "0-1,4"; default: all)
+        #[arg(long, value_name = "LIST")]
+        cpus: Option<String>,
+        /// Bandwidth limit: percent of one CPU ("50%", "150%") or a cpu.max value
+        #[arg(long)]
+        quota: Option<String>,
+        /// Scratch cgroup to create, run in and remove
+        #[arg(long, default_value = "cgroup-tool-experiment")]
+        cgroup: String,
+        /// Stop the workload after this many seconds (0 = when it exits)
+        #[arg(short, long, default_value = "0")]
+        duration: u64,
+        /// Workload to run, after `--`
+        #[arg(required = true, last = true)]
+        command: Vec<String>,
+    },
+}
+
+/// Run cgroup-tool with parsed arguments: `main.rs` and the `isolation` CLI both
+/// end up here.
+pub fn run(cli: Cli) -> Result<()> {
+    // Every mkdir, rmdir and control file write below goes through `log`
+    // (docs/02-cgroups/12-explain.md), never through std::fs directly
+    let log = explain::ActionLog::new(cli.explain);
+    let _ = &log; // Used by the match arms once they are implemented
+
+    match cli.command {
+        // TODO: Implement cgroup creation
+        // Lesson: docs/02-cgroups/01-cgv2-basics.md
+        // Tests: tests/create_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/create_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Cgroup v2 root is typically at /sys/fs/cgroup
+        // - Create cgroup by creating directory: /sys/fs/cgroup/{path}
+        // - Use std::fs::create_dir or create_dir_all for nested paths
+        // - Verify cgroup.procs file exists after creation
+        // - --explain: create with log.create(&dir)? instead of create_dir
+        Command::Create { path } => {
+            todo!("Implement cgroup creation - write tests first! (path: {path})")
+        }
+
+        // TODO: Implement cgroup deletion
+        // Lesson: docs/02-cgroups/01-cgv2-basics.md
+        // Tests: tests/delete_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/delete_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Remove cgroup by removing directory: std::fs::remove_dir
+        // - Cgroup must be empty (no processes, no child cgroups) to delete
+        // - Returns EBUSY if not empty
+        // - --explain: log.remove(&dir)?
+        Command::Delete { path } => {
+            todo!("Implement cgroup deletion - write tests first! (path: {path})")
+        }
+
+        // TODO: Implement process attachment
+        // Lesson: docs/02-cgroups/01-cgv2-basics.md
+        // Tests: tests/attach_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/attach_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Write PID to /sys/fs/cgroup/{path}/cgroup.procs
+        // - Format: write PID as string (e.g., "12345\n")
+        // - Verify by reading cgroup.procs after write
+        // - Can also check /proc/{pid}/cgroup
+        // - --explain: log.write(&dir, "cgroup.procs", &pid.to_string())?
+        //   reads back the whole PID list and shows "populated 0 -> 1"
+        Command::Attach { path, pid } => {
+            todo!("Implement process attachment - write tests first! (path: {path}, pid: {pid})")
+        }
+
+        // TODO: Implement memory limit setting
+        // Lesson: docs/02-cgroups/02-memory.md
+        // Tests: tests/memory_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/memory_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Write bytes to /sys/fs/cgroup/{path}/memory.max
+        // - Format: write number as string (e.g., "104857600" for 100MB)
+        // - Can write "max" to remove limit
+        // - Verify by reading memory.max after write
+        // - --dry-run (docs/02-cgroups/09-dry-run.md): print
+        //   dryrun::plan(&dir, "memory.max", &value)?.render() and return before writing
+        //   (log.planned(&dir, "memory.max", &value) first, for --explain)
+        // - Write with log.write(&dir, "memory.max", &value)? (docs/02-cgroups/12-explain.md)
+        Command::MemoryMax {
+            path,
+            bytes,
+            dry_run,
+        } => {
+            todo!("Implement memory limit - write tests first! (path: {path}, bytes: {bytes}, dry_run: {dry_run})")
+        }
+
+        // TODO: Implement CPU quota setting
+        // Lesson: docs/02-cgroups/03-cpu.md
+        // Tests: tests/cpu_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/cpu_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Write quota to /sys/fs/cgroup/{path}/cpu.max
+        // - Format: "quota period" (both in microseconds)
+        // - Example: "50000 100000" = 50% CPU
+        // - Can write "max" to remove limit
+        // - --dry-run (docs/02-cgroups/09-dry-run.md): print
+        //   dryrun::plan(&dir, "cpu.max", &value)?.render() and return before writing
+        //   (log.planned(&dir, "cpu.max", &value) first, for --explain)
+        // - Write with log.write(&dir, "cpu.max", &value)? (docs/02-cgroups/12-explain.md)
+        Command::CpuMax {
+            path,
+            quota,
+            dry_run,
+        } => {
+            todo!("Implement CPU quota - write tests first! (path: {path}, quota: {quota}, dry_run: {dry_run})")
+        }
+
+        // TODO: Implement PIDs limit setting
+        // Lesson: docs/02-cgroups/05-pids.md
+        // Tests: tests/pids_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/pids_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Write max to /sys/fs/cgroup/{path}/pids.max
+        // - Format: write number as string
+        // - Can write "max" to remove limit
+        // - Verify by reading pids.max after write
+        // - --dry-run (docs/02-cgroups/09-dry-run.md): print
+        //   dryrun::plan(&dir, "pids.max", &value)?.render() and return before writing
+        //   (log.planned(&dir, "pids.max", &value) first, for --explain)
+        // - Write with log.write(&dir, "pids.max", &value)? (docs/02-cgroups/12-explain.md)
+        Command::PidsMax { path, max, dry_run } => {
+            todo!("Implement PIDs limit - write tests first! (path: {path}, max: {max}, dry_run: {dry_run})")
+        }
+
+        // TODO: Implement I/O limit setting
+        // Lesson: docs/02-cgroups/04-io.md
+        // Tests: tests/io_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/io_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Write to /sys/fs/cgroup/{path}/io.max
+        // - Format: "MAJ:MIN rbps=X wbps=X riops=X wiops=X"
+        // - Example: "8:0 rbps=1048576 wbps=1048576"
+        // - Can use "max" for unlimited
+        // - Verify io controller is enabled in subtree_control
+        // - --dry-run (docs/02-cgroups/09-dry-run.md): the value is
+        //   format!("{device} {limit}"); dryrun::plan() validates it with
+        //   dryrun::validate_io_max() and checks that the device exists
+        // - Write with log.write(&dir, "io.max", &value)?, log.planned() on
+        //   --dry-run (docs/02-cgroups/12-explain.md)
+        Command::IoMax {
+            path,
+            device,
+            limit,
+            dry_run,
+        } => {
+            todo!("Implement I/O limit - write tests first! (path: {path}, device: {device}, limit: {limit}, dry_run: {dry_run})")
+        }
+
+        // TODO: Implement snapshot export
+        // Lesson: docs/02-cgroups/07-snapshots.md
+        // Tests: tests/snapshot_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/snapshot_test.rs (RED)
+        // 2. Implement snapshot::capture() and this match arm (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Build the cgroup directory: /sys/fs/cgroup/{path}
+        // - let snap = snapshot::capture(&dir, &path)?;
+        // - Serialize with toml::to_string_pretty(&snap)?
+        // - Write to `output` and print "Exported {n} limits to {output}"
+        Command::Export { path, output } => {
+            todo!(
+                "Implement snapshot export - write tests first! (path: {path}, output: {})",
+                output.display()
+            )
+        }
+
+        // TODO: Implement snapshot import
+        // Lesson: docs/02-cgroups/07-snapshots.md
+        // Tests: tests/snapshot_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/snapshot_test.rs (RED)
+        // 2. Implement snapshot::apply() and this match arm (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Read and parse: toml::from_str::<snapshot::CgroupSnapshot>(&text)?
+        // - Create /sys/fs/cgroup/{to} if it doesn't exist (like `create`)
+        // - snapshot::apply(&snap, &dir, &mut log)? (make `log` mut)
+        // - Print each file written so learners can see what changed
+        // - --explain: snapshot::apply() takes &mut ActionLog and writes
+        //   through it, so every restored limit gets its own entry
+        Command::Import { snapshot, to } => {
+            todo!(
+                "Implement snapshot import - write tests first! (snapshot: {}, to: {to})",
+                snapshot.display()
+            )
+        }
+
+        // TODO: Implement the interactive hierarchy browser
+        // Lesson: docs/02-cgroups/08-tui.md
+        // Tests: tests/tui_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/tui_test.rs (RED)
+        // 2. Implement tui::scan_tree(), tui::read_usage() and tui::run() (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Root directory: /sys/fs/cgroup/{root}, or /sys/fs/cgroup itself
+        // - Fail before touching the terminal if the root does not exist
+        // - tui::run(&dir, Duration::from_secs(interval))
+        Command::Tui { root, interval } => {
+            todo!(
+                "Implement cgroup TUI - write tests first! (root: {}, interval: {interval})",
+                root.as_deref().unwrap_or("/")
+            )
+        }
+
+        // TODO: Implement the per-process breakdown
+        // Lesson: docs/02-cgroups/11-top.md
+        // Tests: tests/top_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/top_test.rs (RED)
+        // 2. Implement the top.rs helpers and this match arm (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Build the cgroup directory: /sys/fs/cgroup/{path}
+        // - top::list_pids(&dir, recursive)?, then top::read_process() for
+        //   each; skip the None ones (the process exited in between)
+        // - top::sort_rows(&mut rows, sort)
+        // - memory.current and memory.max of the cgroup itself for the
+        //   summary: missing file or "max" -> None
+        // - Print top::render(&path, current, max, &rows)
+        // - Reading /proc/PID/status of other users' processes works without
+        //   root; only cgroup.procs of the cgroup has to be readable
+        Command::Top {
+            path,
+            sort,
+            recursive,
+        } => {
+            todo!("Implement cgroup top - write tests first! (path: {path}, sort: {sort:?}, recursive: {recursive})")
+        }
+
+        // TODO: Implement the CPU experiment
+        // Lesson: docs/02-cgroups/10-cpu-experiment.md
+        // Tests: tests/experiment_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/experiment_test.rs (RED)
+        // 2. Implement the experiment.rs helpers and this match arm (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Parse everything before creating anything: parse_cpu_list(),
+        //   parse_quota(). Check the CPUs against the root's
+        //   cpuset.cpus.effective and name the ones that are missing
+        // - Enable the controllers: "+cpu +cpuset" in the root's
+        //   cgroup.subtree_control (as in 06-multi-resource.md)
+        // - Create /sys/fs/cgroup/{cgroup}; bail if it already exists, it may
+        //   be a previous experiment that is still running
+        // - Write cpuset.cpus first, then cpu.max (quota.cpu_max_value())
+        // - Spawn the workload already inside the cgroup:
+        //   ns_tool::spawn::spawn(&SpawnOptions::new().cgroup(&dir), ..)
+        //   with a child that only execvp()s (build the CStrings first).
+        //   clone3 creates it in the cgroup; the fork fallback writes "0"
+        //   to cgroup.procs in the child. Attaching after it started would
+        //   let it run unlimited for a moment and miss its first CPU time
+        // - Time from spawn to child.wait() with Instant; with --duration,
+        //   kill the whole cgroup on time by writing "1" to cgroup.kill
+        // - read_cpu_stat() after the workload and its children are gone
+        //   (cgroup.procs is empty), then rmdir the cgroup, also on errors
+        // - Print CpuReport { .. }.render()
+        // - --explain: the mkdir, the controller and limit writes, and the
+        //   rmdir go through `log`. Joining the cgroup cannot (clone3 does
+        //   it, or the child): record it as a log.planned() entry
+        Command::Experiment { kind } => match kind {
+            ExperimentCommand::Cpu {
+                cpus,
+                quota,
+                cgroup,
+                duration,
+                command,
+            } => {
+                todo!(
+                    "Implement CPU experiment - write tests first! (cpus: {}, quota: {}, cgroup: {cgroup}, duration: {duration}, command: {command:?})",
+                    cpus.as_deref().unwrap_or("all"),
+                    quota.as_deref().unwrap_or("none")
+                )
+            }
+        },
+    }
+
+    if log.explain {
+        print!("{}", log.render());
+    }
+
+    Ok(())
+}
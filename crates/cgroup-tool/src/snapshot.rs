@@ -0,0 +1,98 @@
+// Cgroup configuration snapshots (export/import)
+// Lesson: docs/02-cgroups/07-snapshots.md
+// Tests: tests/snapshot_test.rs
+//
+// A snapshot records the *configured limits* of a cgroup (the writable
+// controller files), not its live usage. That makes it portable: you can
+// export a lab setup on one machine and re-apply it on another.
+//
+// Example snapshot.toml:
+//
+//   source = "lab/web"
+//
+//   [limits]
+//   "cpu.max" = "50000 100000"
+//   "memory.max" = "104857600"
+//   "pids.max" = "20"
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::explain::ActionLog;
+
+/// Controller files captured by `export` and restored by `import`.
+///
+/// Only writable limit/weight files belong here. Read-only statistics such as
+/// `memory.current` or `cpu.stat` describe usage, not configuration.
+///
+/// The order matters for `import`: `cpuset.mems` must be written before tasks
+/// can be scheduled, and `memory.high` should not exceed `memory.max`.
+#[allow(dead_code)]
+pub const SNAPSHOT_FILES: &[&str] = &[
+    "cpuset.cpus",
+    "cpuset.mems",
+    "cpu.max",
+    "cpu.weight",
+    "memory.max",
+    "memory.high",
+    "memory.low",
+    "memory.min",
+    "memory.swap.max",
+    "pids.max",
+    "io.max",
+    "io.weight",
+];
+
+/// A serializable snapshot of a cgroup's configured limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CgroupSnapshot {
+    /// Cgroup path (relative to /sys/fs/cgroup) the snapshot was taken from
+    pub source: String,
+
+    /// Controller file name -> raw file contents (trimmed)
+    ///
+    /// A BTreeMap keeps the TOML output sorted, so two snapshots of the same
+    /// configuration produce identical files and diff cleanly.
+    pub limits: BTreeMap<String, String>,
+}
+
+/// Read every file in `SNAPSHOT_FILES` that exists under `cgroup_dir`.
+///
+/// Files belonging to controllers that are not enabled for this cgroup do not
+/// exist at all, so missing files are skipped rather than treated as errors.
+#[allow(dead_code)]
+pub fn capture(cgroup_dir: &Path, source: &str) -> Result<CgroupSnapshot> {
+    // TODO: Implement snapshot capture
+    // Lesson: docs/02-cgroups/07-snapshots.md
+    //
+    // Implementation hints:
+    // - Iterate over SNAPSHOT_FILES
+    // - Skip files that don't exist (controller not enabled)
+    // - std::fs::read_to_string + trim_end() for each file
+    // - Multi-line files (io.max has one line per device) should keep their
+    //   newlines so they can be re-applied line by line
+    let _ = (cgroup_dir, source);
+    todo!("Implement snapshot capture")
+}
+
+/// Write every limit in `snapshot` into `cgroup_dir`, in `SNAPSHOT_FILES` order.
+///
+/// The writes go through `log`, so `import --explain` shows each of them.
+#[allow(dead_code)]
+pub fn apply(snapshot: &CgroupSnapshot, cgroup_dir: &Path, log: &mut ActionLog) -> Result<()> {
+    // TODO: Implement snapshot apply
+    // Lesson: docs/02-cgroups/07-snapshots.md
+    //
+    // Implementation hints:
+    // - Walk SNAPSHOT_FILES (not the map) so writes happen in a safe order
+    // - io.max accepts one "MAJ:MIN key=value..." line per write
+    // - A missing target file means the controller is not enabled in the
+    //   parent's cgroup.subtree_control: report that with anyhow::Context
+    //   instead of a bare "No such file or directory"
+    // - log.write(cgroup_dir, file, value) for each write, never std::fs::write
+    let _ = (snapshot, cgroup_dir, log);
+    todo!("Implement snapshot apply")
+}
@@ -0,0 +1,149 @@
+// Per-process breakdown of a cgroup (`cgroup-tool top`)
+// Lesson: docs/02-cgroups/11-top.md
+// Tests: tests/top_test.rs
+//
+// memory.current says how much a cgroup uses, not who uses it. `top` lists
+// the processes in cgroup.procs with what /proc knows about each of them,
+// biggest first:
+//
+//   $ cgroup-tool top lab/web
+//   lab/web: memory 212M / 256M (83%), 3 processes, 11 threads
+//       PID      RSS   CPU TIME  THREADS  COMMAND
+//      4242     180M     12.40s        9  node server.js
+//      4250      28M      0.31s        1  sh -c ./worker.sh
+//      4251     3.1M      0.00s        1  sleep 600
+//
+// RSS does not add up to memory.current: the cgroup is also charged for
+// page cache and kernel memory, and shared pages count once in the cgroup
+// but in the RSS of every process that maps them.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// Column `top` sorts by, biggest first (`--sort`).
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Resident memory (VmRSS)
+    #[default]
+    Mem,
+    /// CPU time used so far (utime + stime)
+    Cpu,
+    /// Number of threads
+    Threads,
+}
+
+/// One process of the cgroup, as /proc shows it.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    /// Cgroup of the process relative to the listed one ("" for the cgroup
+    /// itself); only differs with --recursive
+    pub cgroup: String,
+    /// VmRSS from /proc/PID/status, in bytes (0 for kernel threads)
+    pub rss_bytes: u64,
+    /// utime + stime from /proc/PID/stat
+    pub cpu_time: Duration,
+    /// Threads from /proc/PID/status
+    pub threads: u64,
+    /// /proc/PID/cmdline with spaces, or "[comm]" when it is empty
+    pub command: String,
+}
+
+/// The numbers `top` needs from /proc/PID/status.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusFields {
+    pub rss_bytes: u64,
+    pub threads: u64,
+}
+
+/// PIDs in `<cgroup_dir>/cgroup.procs`, with the cgroup they are in
+/// relative to `cgroup_dir`. With `recursive`, also those of every
+/// descendant cgroup.
+#[allow(dead_code)]
+pub fn list_pids(cgroup_dir: &Path, recursive: bool) -> Result<Vec<(u32, String)>> {
+    // TODO: Implement in lesson 11
+    // Hints:
+    // - cgroup.procs has one PID per line (cgroup.threads would list TIDs)
+    // - In cgroup v2 only leaves hold processes once controllers are
+    //   enabled (the "no internal processes" rule), so a parent such as
+    //   "lab" usually lists nothing: --recursive walks the subdirectories
+    // - A missing cgroup.procs means the path is not a cgroup: say so with
+    //   anyhow::Context instead of a bare "No such file or directory"
+    let _ = (cgroup_dir, recursive);
+    todo!("Implement cgroup PID listing")
+}
+
+/// Parse the VmRSS and Threads lines of /proc/PID/status.
+#[allow(dead_code)]
+pub fn parse_status(text: &str) -> Result<StatusFields> {
+    // TODO: Implement in lesson 11
+    // Hints:
+    // - "VmRSS:\t  184320 kB": the unit is always kB (1024 bytes)
+    // - Kernel threads and zombies have no VmRSS line: 0, not an error
+    // - Threads is always there; a status without it is an error
+    let _ = text;
+    todo!("Implement /proc/PID/status parsing")
+}
+
+/// utime + stime from /proc/PID/stat, in clock ticks.
+#[allow(dead_code)]
+pub fn parse_stat_ticks(text: &str) -> Result<u64> {
+    // TODO: Implement in lesson 11
+    // Hints:
+    // - "4242 (node server) S 1 ...": the comm in parentheses may contain
+    //   spaces and parentheses itself. Split after the LAST ')'
+    // - After the ')', utime and stime are fields 12 and 13 (0-based
+    //   11 and 12 of the rest: state is the first)
+    let _ = text;
+    todo!("Implement /proc/PID/stat parsing")
+}
+
+/// Read one process, or None if it exited since cgroup.procs was read.
+#[allow(dead_code)]
+pub fn read_process(pid: u32, cgroup: &str) -> Result<Option<ProcessUsage>> {
+    // TODO: Implement in lesson 11
+    // Hints:
+    // - /proc/PID/status, /proc/PID/stat and /proc/PID/cmdline; ENOENT or
+    //   ESRCH on any of them means the process is gone: Ok(None)
+    // - Ticks to a Duration with libc::sysconf(libc::_SC_CLK_TCK), usually 100
+    // - cmdline is NUL-separated with a trailing NUL: join with spaces.
+    //   Empty for kernel threads: use "[" + comm + "]" like ps
+    let _ = (pid, cgroup);
+    todo!("Implement process reading")
+}
+
+/// Sort `rows` by `key`, biggest first, then by PID so the order is stable.
+#[allow(dead_code)]
+pub fn sort_rows(rows: &mut [ProcessUsage], key: SortKey) {
+    // TODO: Implement in lesson 11
+    // Hint: sort_by with a tuple key, e.g. (Reverse(rss_bytes), pid)
+    let _ = (rows, key);
+    todo!("Implement sorting")
+}
+
+/// The table as printed by `top`: a summary line, then one row per process.
+///
+/// `memory_current` and `memory_max` are the cgroup's own files (`None`
+/// for "max" or a cgroup without the memory controller).
+#[allow(dead_code)]
+pub fn render(
+    path: &str,
+    memory_current: Option<u64>,
+    memory_max: Option<u64>,
+    rows: &[ProcessUsage],
+) -> String {
+    // TODO: Implement in lesson 11
+    // Hints:
+    // - Summary as in the example at the top of this file; "memory 212M"
+    //   without a limit, and no memory part without memory.current
+    // - Sizes in binary units with one decimal below 10 ("3.1M", "180M")
+    // - Add a CGROUP column when any row has a non-empty cgroup (--recursive)
+    // - No processes: the summary and "(no processes)", not an empty table
+    let _ = (path, memory_current, memory_max, rows);
+    todo!("Implement top rendering")
+}
@@ -0,0 +1,210 @@
+// Interactive cgroup hierarchy browser (`cgroup-tool tui`)
+// Lesson: docs/02-cgroups/08-tui.md
+// Tests: tests/tui_test.rs
+//
+// The TUI is a live view of the same files the earlier lessons read and
+// write by hand. Every refresh re-reads the tree; every action is a single
+// write to a control file:
+//
+//   f  freeze / thaw   -> echo 1|0 > cgroup.freeze
+//   K  kill (confirm)  -> echo 1   > cgroup.kill      (Linux 5.14+)
+//   e  edit limit      -> echo ... > memory.max | cpu.max | pids.max
+//
+// Screen layout:
+//
+//   ┌ /sys/fs/cgroup ───────────────────────────────────────────────┐
+//   │ ▾ lab                    mem [█████░░░░░] 52M/100M  pids 3/20 │
+//   │   ▸ lab/web              mem [██░░░░░░░░] 21M/max   pids 2/max│
+//   │   ❄ lab/batch  (frozen)  mem [█░░░░░░░░░] 4M/50M    pids 1/10 │
+//   │ ▸ system.slice           mem [███████░░░] 1.2G/max  pids 211  │
+//   └───────────────────────────────────────────────────────────────┘
+//    ↑/↓ move  ←/→ collapse/expand  f freeze  K kill  e edit  q quit
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use ratatui::crossterm::event::KeyCode;
+
+/// Limit files that the `e` key can edit, in the order the prompt cycles
+/// through them.
+#[allow(dead_code)]
+pub const EDITABLE_LIMITS: &[&str] = &["memory.max", "memory.high", "cpu.max", "pids.max"];
+
+/// Live usage of one cgroup, read on every refresh.
+///
+/// Limits are `None` when the file contains "max" or does not exist (the
+/// controller is not enabled for this cgroup).
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeUsage {
+    /// memory.current
+    pub memory_current: u64,
+    /// memory.max
+    pub memory_max: Option<u64>,
+    /// usage_usec from cpu.stat (cumulative; the CPU % is the delta between refreshes)
+    pub cpu_usage_usec: u64,
+    /// pids.current
+    pub pids_current: u64,
+    /// pids.max
+    pub pids_max: Option<u64>,
+    /// "frozen 1" in cgroup.events
+    pub frozen: bool,
+}
+
+/// One visible row in the tree.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CgroupNode {
+    /// Path relative to the browser root (e.g. "lab/web")
+    pub path: String,
+    /// Nesting level, used for indentation
+    pub depth: usize,
+    /// Whether the cgroup has child cgroups (shows ▸/▾)
+    pub has_children: bool,
+    pub usage: NodeUsage,
+}
+
+/// What a key press asks the browser to do.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Expand,
+    Collapse,
+    ToggleFreeze,
+    Kill,
+    EditLimit,
+    Refresh,
+    Quit,
+}
+
+impl Action {
+    /// Map a key to an action. Unbound keys return `None`.
+    #[allow(dead_code)]
+    pub fn from_key(code: KeyCode) -> Option<Action> {
+        // TODO: Implement in lesson 08
+        // Hints:
+        // - Arrows and vim keys: Up/k, Down/j, Right/l/Enter, Left/h
+        // - 'f' freeze, 'K' kill (uppercase, so it is hard to hit by
+        //   accident), 'e' edit, 'r' refresh, 'q'/Esc quit
+        let _ = code;
+        todo!("Implement key bindings")
+    }
+}
+
+/// Prompt state while the user edits a limit or confirms a kill.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prompt {
+    /// Editing `file` of the selected cgroup; `input` is the text typed so far
+    EditLimit { file: &'static str, input: String },
+    /// "Kill every process in <path>? (y/N)"
+    ConfirmKill { path: String },
+}
+
+/// Browser state: the tree, the selection, and an optional prompt.
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct App {
+    /// Directory shown as the tree root (default /sys/fs/cgroup)
+    pub root: PathBuf,
+    /// Relative paths of expanded nodes; survives refreshes
+    pub expanded: BTreeSet<String>,
+    /// Visible rows, rebuilt on every refresh
+    pub nodes: Vec<CgroupNode>,
+    /// Index into `nodes`
+    pub selected: usize,
+    /// Active prompt, if any (keys go to the prompt first)
+    pub prompt: Option<Prompt>,
+    /// Result of the last action, shown in the status line
+    pub status: String,
+}
+
+impl App {
+    /// Apply one action to the selected cgroup.
+    ///
+    /// Returns `Ok(false)` when the browser should exit.
+    #[allow(dead_code)]
+    pub fn handle(&mut self, action: Action) -> Result<bool> {
+        // TODO: Implement in lesson 08
+        // Hints:
+        // - Keep `selected` in bounds after moving and after a refresh
+        //   (a cgroup can disappear between two refreshes)
+        // - ToggleFreeze: write "1" or "0" to cgroup.freeze depending on
+        //   usage.frozen; freezing is asynchronous, so show "freezing..."
+        //   until cgroup.events says "frozen 1"
+        // - Kill: only open Prompt::ConfirmKill; the write to cgroup.kill
+        //   happens when the user answers 'y'
+        // - EditLimit: open Prompt::EditLimit with the current value, and
+        //   on Enter write the input to the file. Show the kernel's error
+        //   (EINVAL, EBUSY) in `status` instead of exiting
+        // - Never act on the root cgroup itself: it has no cgroup.freeze
+        //   or limit files, and cgroup.kill there would kill everything
+        let _ = action;
+        todo!("Implement action handling")
+    }
+}
+
+/// Walk `root` and return the visible rows: every child of an expanded node,
+/// in sorted order, depth-first.
+#[allow(dead_code)]
+pub fn scan_tree(root: &Path, expanded: &BTreeSet<String>) -> Result<Vec<CgroupNode>> {
+    // TODO: Implement in lesson 08
+    // Hints:
+    // - A cgroup is a directory; its children are its subdirectories
+    // - Sort children by name so the tree does not jump between refreshes
+    // - Only descend into paths in `expanded`; still set has_children for
+    //   collapsed nodes (one read_dir is enough)
+    // - Fill usage with read_usage() for every visible row
+    let _ = (root, expanded);
+    todo!("Implement cgroup tree scan")
+}
+
+/// Read the usage files of one cgroup. Missing files become 0 / None.
+#[allow(dead_code)]
+pub fn read_usage(cgroup_dir: &Path) -> NodeUsage {
+    // TODO: Implement in lesson 08
+    // Hints:
+    // - memory.current, pids.current: one number each
+    // - memory.max, pids.max: a number or "max" (-> None)
+    // - cpu.stat: the "usage_usec <n>" line
+    // - cgroup.events: the "frozen <0|1>" line
+    let _ = cgroup_dir;
+    todo!("Implement usage reading")
+}
+
+/// Render a fixed-width usage bar such as "[█████░░░░░]".
+///
+/// With no limit the bar is empty: usage relative to "max" is meaningless.
+#[allow(dead_code)]
+pub fn usage_bar(used: u64, limit: Option<u64>, width: usize) -> String {
+    // TODO: Implement in lesson 08
+    // Hints:
+    // - filled = used * width / limit, capped at width
+    // - Use saturating math: memory.current can briefly exceed memory.max
+    let _ = (used, limit, width);
+    todo!("Implement usage bar")
+}
+
+/// Run the browser until the user quits.
+#[allow(dead_code)]
+pub fn run(root: &Path, interval: Duration) -> Result<()> {
+    // TODO: Implement in lesson 08
+    // Hints:
+    // - Refuse to start when stdout is not a terminal
+    //   (std::io::IsTerminal), with an error that suggests `cat`-ing the
+    //   files instead; this is what the non-interactive test checks
+    // - let mut terminal = ratatui::init(); ... ratatui::restore();
+    //   restore on every exit path, including errors, or the shell is left
+    //   in raw mode
+    // - Loop: scan_tree(), terminal.draw(|frame| ...), then
+    //   crossterm::event::poll(interval) so the view refreshes even
+    //   without key presses
+    // - Draw the rows with ratatui::widgets::List and the prompt/status
+    //   line with a Paragraph below it
+    let _ = (root, interval);
+    todo!("Implement TUI event loop")
+}
@@ -0,0 +1,76 @@
+// Tests for `--dry-run` on the set-limit subcommands
+// Lesson: docs/02-cgroups/09-dry-run.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs and src/dryrun.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// NOTE: These tests require cgroup v2 and appropriate permissions.
+// Run with: sudo -E cargo test -p cgroup-tool --test dryrun_test
+
+#[test]
+fn test_dry_run_does_not_write() {
+    // TODO: Write a test that verifies --dry-run leaves the cgroup untouched
+    //
+    // Hints:
+    // - Create a test cgroup; memory.max starts as "max"
+    // - The plan is printed on stdout, so assert on it as well
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-dryrun"
+    // 2. Run `cgroup-tool memory-max test-dryrun 104857600 --dry-run`
+    // 3. Verify stdout contains "would write" and "104857600"
+    // 4. Verify /sys/fs/cgroup/test-dryrun/memory.max still contains "max"
+    // 5. Clean up
+
+    todo!("Implement test for dry-run not writing")
+}
+
+#[test]
+fn test_dry_run_rejects_invalid_cpu_max() {
+    // TODO: Write a test that verifies invalid values fail without a write
+    //
+    // Hints:
+    // - cpu.max quotas below 1000us are rejected by the kernel with EINVAL;
+    //   the dry run must reject them too, with the rule in the message
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-dryrun-cpu"
+    // 2. Run `cgroup-tool cpu-max test-dryrun-cpu "500 100000" --dry-run`
+    // 3. Assert failure, and stderr mentions the 1000us minimum
+    // 4. Run the same with "50000 100000" and assert success
+    // 5. Clean up
+
+    todo!("Implement test for rejecting an invalid cpu.max")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_dry_run_warns_memory_below_current() {
+    // TODO: Write a test that verifies the memory impact warning
+    //
+    // Hints:
+    // - Attach a process that holds some memory (e.g. `sh -c 'x=$(head -c
+    //   20000000 /dev/zero | tr "\0" a); sleep 30'`) to the cgroup
+    // - Wait until memory.current is above 10MB
+    // - A dry run of memory-max 1048576 should print a "warning:" line that
+    //   mentions memory.current, and exit successfully (it is a warning,
+    //   not an error)
+    // - Kill the process before deleting the cgroup
+
+    todo!("Implement test for memory impact warning")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_dry_run_warns_pids_below_current() {
+    // TODO: Write a test that verifies the pids impact warning
+    //
+    // Hints:
+    // - Attach two `sleep 30` processes to the cgroup (pids.current = 2)
+    // - A dry run of pids-max 1 should warn that forks will fail with EAGAIN
+    // - pids.max must still be "max" afterwards
+
+    todo!("Implement test for pids impact warning")
+}
@@ -0,0 +1,80 @@
+// Tests for the `experiment cpu` subcommand (cpuset + cpu.max, measured)
+// Lesson: docs/02-cgroups/10-cpu-experiment.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs and src/experiment.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// Use a busy loop as the workload: `sh -c 'while :; do :; done'` burns one
+// CPU without any extra tools. Keep --duration short (2-3 seconds) and the
+// tolerances wide: the tests share the machine with everything else.
+//
+// NOTE: These tests require cgroup v2 and appropriate permissions.
+// Run with: sudo -E cargo test -p cgroup-tool --test experiment_test
+
+#[test]
+fn test_experiment_rejects_bad_limits() {
+    // TODO: Write a test that verifies invalid limits fail before anything
+    // is created
+    //
+    // Hints:
+    // - --quota 0%, --quota "500 100000" and --cpus 3-1 must all fail
+    // - The scratch cgroup must not exist afterwards
+    //
+    // Test approach:
+    // 1. Run `cgroup-tool experiment cpu --cgroup test-exp-bad --quota 0% -- true`
+    // 2. Assert failure, and stderr explains the rule
+    // 3. Repeat for the other two values
+    // 4. Verify /sys/fs/cgroup/test-exp-bad does not exist
+
+    todo!("Implement test for rejecting bad experiment limits")
+}
+
+#[test]
+fn test_experiment_quota_caps_usage() {
+    // TODO: Write a test that verifies the quota is the ceiling
+    //
+    // Hints:
+    // - Two busy loops want 2 CPUs; a 50% quota allows 0.5
+    //
+    // Test approach:
+    // 1. Run `cgroup-tool experiment cpu --cgroup test-exp-quota --quota 50% -d 3
+    //    -- sh -c 'while :; do :; done & while :; do :; done'`
+    // 2. Verify stdout has "ceiling:   0.50 CPU (the quota)"
+    // 3. Parse the "achieved:" value: between 0.35 and 0.55
+    // 4. Verify a "throttled:" line is printed
+    // 5. Verify the scratch cgroup was removed
+
+    todo!("Implement test for the quota ceiling")
+}
+
+#[test]
+fn test_experiment_cpuset_caps_usage() {
+    // TODO: Write a test that verifies the CPU set is the ceiling when it
+    // is the smaller limit
+    //
+    // Hints:
+    // - Skip if the machine has fewer than 2 CPUs
+    //
+    // Test approach:
+    // 1. Run with --cpus 0 --quota 150% and two busy loops, -d 3
+    // 2. Verify "ceiling:   1.00 CPU (the CPU set)"
+    // 3. Achieved is at most ~1.05 even though the quota allows 1.5
+
+    todo!("Implement test for the cpuset ceiling")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_experiment_reports_exit_code() {
+    // TODO: Write a test that verifies the workload's exit is reported
+    //
+    // Hints:
+    // - `-- sh -c 'exit 3'` prints "exit:      3"
+    // - With -d 1 and `-- sleep 30`, the workload is killed: "exit:      killed",
+    //   and the command returns after ~1s, not 30
+    // - The scratch cgroup is gone in both cases
+
+    todo!("Implement test for exit reporting")
+}
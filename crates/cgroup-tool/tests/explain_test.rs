@@ -0,0 +1,77 @@
+// Tests for `--explain` (lesson mode: what was written and how the kernel reacted)
+// Lesson: docs/02-cgroups/12-explain.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/explain.rs and the ActionLog calls in
+//    src/lib.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// NOTE: These tests require cgroup v2 and appropriate permissions.
+// Run with: sudo -E cargo test -p cgroup-tool --test explain_test
+
+#[test]
+fn test_explain_is_global() {
+    // TODO: Write a test that verifies --explain is accepted by every subcommand
+    //
+    // Hints:
+    // - No root needed: only --help is run
+    // - `cgroup-tool --help` lists --explain, and so does
+    //   `cgroup-tool memory-max --help` (clap's global = true)
+    // - `cgroup-tool top --explain --help` parses, before or after the
+    //   subcommand
+
+    todo!("Implement test for the global --explain flag")
+}
+
+#[test]
+fn test_explain_memory_max() {
+    // TODO: Write a test that verifies the explanation of a limit write
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-explain"
+    // 2. Run `cgroup-tool --explain memory-max test-explain 104857600`
+    // 3. Verify stdout contains "wrote \"104857600\"", the full path of
+    //    memory.max, "read back: \"104857600\"" and "02-memory.md"
+    // 4. Run it again without --explain: none of those lines appear
+    // 5. Clean up
+
+    todo!("Implement test for --explain on memory-max")
+}
+
+#[test]
+fn test_explain_attach_shows_kernel_reaction() {
+    // TODO: Write a test that verifies the events diff is shown
+    //
+    // Hints:
+    // - Attaching the first process to an empty cgroup flips
+    //   "populated" in cgroup.events from 0 to 1
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-explain-attach", spawn `sleep 30`
+    // 2. Run `cgroup-tool --explain attach test-explain-attach <pid>`
+    // 3. Verify a "kernel:" line contains "populated 0 -> 1"
+    // 4. Verify the read back lists the PID
+    // 5. Kill the sleep and clean up
+
+    todo!("Implement test for the kernel reaction in --explain")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_explain_dry_run_writes_nothing() {
+    // TODO: Write a test that verifies --explain with --dry-run
+    //
+    // Hints:
+    // - The planned write is explained ("would write"), with the lesson,
+    //   but has no "read back:" and no "kernel:" line
+    // - pids.max must still be "max" afterwards
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-explain-dry"
+    // 2. Run `cgroup-tool --explain pids-max test-explain-dry 5 --dry-run`
+    // 3. Verify stdout and the unchanged pids.max
+    // 4. Clean up
+
+    todo!("Implement test for --explain with --dry-run")
+}
@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write the test(s) below FIRST (RED - they will fail)
-// 2. Implement the code in src/main.rs to make tests pass (GREEN)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
 // 3. Refactor as needed
 //
 // NOTE: These tests require cgroup v2 and appropriate permissions.
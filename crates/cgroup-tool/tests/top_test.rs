@@ -0,0 +1,80 @@
+// Tests for the `top` subcommand (per-process breakdown of a cgroup)
+// Lesson: docs/02-cgroups/11-top.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs and src/top.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// Start the processes yourself and move them into the test cgroup by
+// writing their PIDs to cgroup.procs, so the expected rows are known.
+// Kill them and remove the cgroup at the end, also when an assert fails.
+//
+// NOTE: These tests require cgroup v2 and appropriate permissions.
+// Run with: sudo -E cargo test -p cgroup-tool --test top_test
+
+#[test]
+fn test_top_lists_cgroup_processes() {
+    // TODO: Write a test that verifies every process of the cgroup is listed
+    //
+    // Hints:
+    // - Two `sleep 600` processes are enough; their RSS is small but not 0
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-top" and move two `sleep 600` into it
+    // 2. Run `cgroup-tool top test-top`
+    // 3. Verify the summary says "2 processes"
+    // 4. Verify both PIDs appear, each on a line ending in "sleep 600"
+    // 5. Verify the test's own PID does not appear
+    // 6. Kill the sleeps and clean up
+
+    todo!("Implement test for listing cgroup processes")
+}
+
+#[test]
+fn test_top_sorts_by_memory() {
+    // TODO: Write a test that verifies the biggest process comes first
+    //
+    // Hints:
+    // - `python3 -c 'b = bytearray(64 << 20); import time; time.sleep(600)'`
+    //   holds 64M resident; `sleep 600` holds almost nothing
+    //
+    // Test approach:
+    // 1. Create test cgroup "test-top-sort" with one sleep and one python3
+    // 2. Run `cgroup-tool top test-top-sort`
+    // 3. Verify the first row is the python3 PID, with an RSS of at least 64M
+    // 4. Run with `--sort threads` and verify the command succeeds
+    // 5. Clean up
+
+    todo!("Implement test for sorting by memory")
+}
+
+#[test]
+fn test_top_recursive() {
+    // TODO: Write a test that verifies --recursive includes child cgroups
+    //
+    // Hints:
+    // - Processes live in the leaves: "test-top-tree/a" and "test-top-tree/b"
+    //
+    // Test approach:
+    // 1. Create test-top-tree/a and test-top-tree/b with one sleep each
+    // 2. `cgroup-tool top test-top-tree` prints "(no processes)"
+    // 3. `cgroup-tool top test-top-tree -r` lists both, with a CGROUP column
+    //    showing "a" and "b"
+    // 4. Clean up (children first)
+
+    todo!("Implement test for recursive listing")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_top_missing_cgroup() {
+    // TODO: Write a test that verifies a missing cgroup is a clear error
+    //
+    // Test approach:
+    // 1. Run `cgroup-tool top does-not-exist`
+    // 2. Verify it fails and stderr names /sys/fs/cgroup/does-not-exist and
+    //    says it is not a cgroup
+
+    todo!("Implement test for a missing cgroup")
+}
@@ -0,0 +1,86 @@
+// Tests for the `tui` subcommand (interactive cgroup hierarchy browser)
+// Lesson: docs/02-cgroups/08-tui.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs and src/tui.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// A full-screen UI is hard to drive from a test, so these tests cover the
+// parts that do not need a terminal: argument handling, a clean refusal when
+// stdout is not a TTY, and the control-file writes behind the key bindings.
+//
+// NOTE: The freeze/kill tests require cgroup v2 and appropriate permissions.
+// Run with: sudo -E cargo test -p cgroup-tool --test tui_test
+
+#[test]
+fn test_tui_refuses_without_terminal() {
+    // TODO: Write a test that verifies `tui` fails cleanly when not on a TTY
+    //
+    // Hints:
+    // - assert_cmd captures stdout, so the binary never sees a terminal
+    // - The command must exit non-zero *without* switching to raw mode or
+    //   the alternate screen (no escape sequences like "\x1b[?1049h" in stdout)
+    // - stderr should explain that a terminal is required
+    //
+    // Test approach:
+    // 1. Run `cgroup-tool tui`
+    // 2. Assert failure
+    // 3. Assert stdout is empty and stderr mentions "terminal"
+
+    todo!("Implement test for non-interactive refusal")
+}
+
+#[test]
+fn test_tui_rejects_missing_root() {
+    // TODO: Write a test that verifies a nonexistent root cgroup is rejected
+    //
+    // Hints:
+    // - The root must be checked before the terminal is touched
+    //
+    // Test approach:
+    // 1. Run `cgroup-tool tui does-not-exist`
+    // 2. Assert failure and stderr mentions "does-not-exist"
+
+    todo!("Implement test for missing root cgroup")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_tui_freeze_writes_cgroup_freeze() {
+    // TODO: Write a test for the write behind the 'f' key binding
+    //
+    // Hints:
+    // - Key presses cannot be sent through assert_cmd; test the effect
+    //   the binding relies on instead
+    // - Create "test-tui", spawn `sleep 30` and attach it
+    // - Write "1" to cgroup.freeze, then poll cgroup.events until it
+    //   contains "frozen 1" (freezing is asynchronous)
+    // - /proc/<pid>/stat should show the task stopped; write "0" to thaw
+    //
+    // Test approach:
+    // 1. Create the cgroup and attach a sleeping child
+    // 2. Freeze, wait for "frozen 1", thaw, wait for "frozen 0"
+    // 3. Kill the child and delete the cgroup
+
+    todo!("Implement test for freeze/thaw")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_tui_kill_empties_cgroup() {
+    // TODO: Write a test for the write behind the 'K' key binding
+    //
+    // Hints:
+    // - cgroup.kill needs Linux 5.14+; skip if the file does not exist
+    // - After writing "1", cgroup.procs becomes empty and the child's
+    //   wait() reports SIGKILL
+    //
+    // Test approach:
+    // 1. Create "test-tui-kill" and attach a `sleep 30` child
+    // 2. Write "1" to cgroup.kill
+    // 3. Assert the child was killed and cgroup.procs is empty
+    // 4. Delete the cgroup
+
+    todo!("Implement test for cgroup.kill")
+}
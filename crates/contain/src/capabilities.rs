@@ -0,0 +1,159 @@
+// Capability sets for the contain CLI (fast-track lesson 21)
+// `contain run --cap-drop/--cap-add` decides which of root's powers the
+// container keeps, and drops the rest before exec:
+//
+//   Docker's default set (security::DOCKER_DEFAULT_CAPS)
+//     - --cap-drop    NET_RAW, or ALL
+//     + --cap-add     NET_ADMIN, or ALL
+//     = keep          bounding, permitted, effective, inheritable
+//
+// The bounding set is what makes it stick: exec as root gives a process
+// every capability in it, so a cap dropped there cannot come back, not
+// even through a setuid-root or file-capability binary.
+// PR_SET_NO_NEW_PRIVS closes the other way up: setuid bits and file
+// capabilities are ignored by every later exec.
+//
+// The ns-tool `check-caps` lesson (docs/00-foundations/04-permissions-and-sudo.md)
+// reads these sets; this module writes them.
+
+use anyhow::Result;
+
+/// Every capability contain knows by name (linux/capability.h), by number.
+/// A kernel may know more: /proc/sys/kernel/cap_last_cap.
+#[allow(dead_code)]
+pub const CAP_NAMES: &[(u32, &str)] = &[
+    (0, "CAP_CHOWN"),
+    (1, "CAP_DAC_OVERRIDE"),
+    (2, "CAP_DAC_READ_SEARCH"),
+    (3, "CAP_FOWNER"),
+    (4, "CAP_FSETID"),
+    (5, "CAP_KILL"),
+    (6, "CAP_SETGID"),
+    (7, "CAP_SETUID"),
+    (8, "CAP_SETPCAP"),
+    (9, "CAP_LINUX_IMMUTABLE"),
+    (10, "CAP_NET_BIND_SERVICE"),
+    (11, "CAP_NET_BROADCAST"),
+    (12, "CAP_NET_ADMIN"),
+    (13, "CAP_NET_RAW"),
+    (14, "CAP_IPC_LOCK"),
+    (15, "CAP_IPC_OWNER"),
+    (16, "CAP_SYS_MODULE"),
+    (17, "CAP_SYS_RAWIO"),
+    (18, "CAP_SYS_CHROOT"),
+    (19, "CAP_SYS_PTRACE"),
+    (20, "CAP_SYS_PACCT"),
+    (21, "CAP_SYS_ADMIN"),
+    (22, "CAP_SYS_BOOT"),
+    (23, "CAP_SYS_NICE"),
+    (24, "CAP_SYS_RESOURCE"),
+    (25, "CAP_SYS_TIME"),
+    (26, "CAP_SYS_TTY_CONFIG"),
+    (27, "CAP_MKNOD"),
+    (28, "CAP_LEASE"),
+    (29, "CAP_AUDIT_WRITE"),
+    (30, "CAP_AUDIT_CONTROL"),
+    (31, "CAP_SETFCAP"),
+    (32, "CAP_MAC_OVERRIDE"),
+    (33, "CAP_MAC_ADMIN"),
+    (34, "CAP_SYSLOG"),
+    (35, "CAP_WAKE_ALARM"),
+    (36, "CAP_BLOCK_SUSPEND"),
+    (37, "CAP_AUDIT_READ"),
+    (38, "CAP_PERFMON"),
+    (39, "CAP_BPF"),
+    (40, "CAP_CHECKPOINT_RESTORE"),
+];
+
+/// One --cap-add or --cap-drop value.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapArg {
+    /// "ALL": every capability the kernel has
+    All,
+    /// One capability by number
+    Cap(u32),
+}
+
+/// A set of capabilities, bit N for capability N.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CapSet(pub u64);
+
+/// Parse a capability for clap: "NET_ADMIN", "cap_net_admin" or "ALL".
+#[allow(dead_code)]
+pub fn parse_cap(s: &str) -> Result<CapArg, String> {
+    // TODO: Implement in fast-track lesson 21
+    // Hints:
+    // - Case-insensitive, CAP_ prefix optional: the spellings of Docker's
+    //   --cap-add and of capsh
+    // - Look the name up in CAP_NAMES; unknown: "unknown capability: FOO"
+    // - A bare number is not accepted: names are what shows up in the
+    //   lesson's `check-caps` and in `capsh --decode`
+    let _ = s; // Suppress unused warning
+    todo!("Implement capability parsing")
+}
+
+impl CapSet {
+    /// The set from a list like security::DOCKER_DEFAULT_CAPS.
+    #[allow(dead_code)]
+    pub fn from_list(caps: &[(u32, &str)]) -> CapSet {
+        // TODO: Implement in fast-track lesson 21
+        let _ = caps; // Suppress unused warning
+        todo!("Implement capability set from list")
+    }
+
+    /// What a container keeps: Docker's default set, minus `drop`, plus `add`.
+    #[allow(dead_code)]
+    pub fn resolve(add: &[CapArg], drop: &[CapArg], last_cap: u32) -> CapSet {
+        // TODO: Implement in fast-track lesson 21
+        // Hints:
+        // - Start from CapSet::from_list(security::DOCKER_DEFAULT_CAPS)
+        // - Drop first, then add, as Docker does: `--cap-drop ALL --cap-add
+        //   NET_BIND_SERVICE` keeps exactly one
+        // - ALL is caps 0..=last_cap (read_last_cap()), not a u64 of ones:
+        //   the kernel rejects bits it does not know
+        let _ = (add, drop, last_cap); // Suppress unused warning
+        todo!("Implement capability resolution")
+    }
+
+    /// Capability names in the set, in number order; a number CAP_NAMES
+    /// does not have shows as "CAP_41".
+    #[allow(dead_code)]
+    pub fn names(&self) -> Vec<String> {
+        // TODO: Implement in fast-track lesson 21
+        let _ = self.0; // Suppress unused warning
+        todo!("Implement capability names")
+    }
+}
+
+/// The highest capability number of the running kernel.
+#[allow(dead_code)]
+pub fn read_last_cap() -> Result<u32> {
+    // TODO: Implement in fast-track lesson 21
+    // Hints:
+    // - /proc/sys/kernel/cap_last_cap, e.g. "40\n"
+    // - Read it on the host, before pivot_root(): the container's /proc
+    //   has it too, but the rootfs may not mount one
+    todo!("Implement cap_last_cap")
+}
+
+/// Reduce the calling process to `keep` and set no_new_privs; the last
+/// step before exec (and before seccomp::apply, which may deny capset).
+#[allow(dead_code)]
+pub fn apply(keep: CapSet, last_cap: u32) -> Result<()> {
+    // TODO: Implement in fast-track lesson 21
+    // Hints:
+    // - Bounding set: prctl(PR_CAPBSET_DROP, cap) for each cap 0..=last_cap
+    //   not in keep. Needs CAP_SETPCAP, so do it before the capset below
+    // - Then libc::syscall(SYS_capset) with _LINUX_CAPABILITY_VERSION_3:
+    //   effective = permitted = inheritable = keep, two u32 words each
+    // - Ambient: PR_CAP_AMBIENT_CLEAR_ALL, then PR_CAP_AMBIENT_RAISE for
+    //   each kept cap, so a command that is not uid 0 keeps them too
+    // - prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) last: setuid binaries in
+    //   the rootfs can no longer gain what was dropped
+    // - EPERM from PR_CAPBSET_DROP: the process lacks CAP_SETPCAP. Inside
+    //   contain run's user namespace it has it
+    let _ = (keep, last_cap); // Suppress unused warning
+    todo!("Implement capability drop")
+}
@@ -0,0 +1,114 @@
+// Running a command in an existing container (fast-track lesson 23)
+// `contain exec <id> -- cmd` is `docker exec` / `nsenter`: no new
+// namespaces, the running container's own, found through its init:
+//
+//   contain exec (host)
+//     ├─ state::load(id)              pid 4242, still running?
+//     ├─ open /proc/4242/ns/*         all of them, BEFORE the first setns
+//     ├─ join its cgroup              still on the host's mount namespace
+//     ├─ setns() user, mnt, pid, net, uts, ipc
+//     └─ fork ─► cmd                  PID N in the container's namespace
+//
+// setns(CLONE_NEWPID) only changes the namespace of the caller's future
+// children, so the fork is not optional: without it the command would run
+// in the host's PID namespace with everything else from the container.
+
+use anyhow::Result;
+use clap::Args;
+use ns_tool::NamespaceKind;
+
+#[derive(Args)]
+pub struct ExecArgs {
+    /// Container ID (from `contain ps`)
+    pub id: String,
+
+    /// Command to run inside the container
+    #[arg(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+/// The namespaces exec joins, in order: the user namespace first, so the
+/// others are entered with its capabilities.
+#[allow(dead_code)]
+pub const ENTERED_NAMESPACES: &[NamespaceKind] = &[
+    NamespaceKind::User,
+    NamespaceKind::Mount,
+    NamespaceKind::Pid,
+    NamespaceKind::Net,
+    NamespaceKind::Uts,
+    NamespaceKind::Ipc,
+];
+
+impl ExecArgs {
+    pub fn run(&self) -> Result<()> {
+        // TODO: Enter a running container and run a command in it
+        // Lesson: docs/fast-track/23-exec.md
+        // Tests: tests/exec_test.rs
+        //
+        // Implementation hints:
+        // - state::load(&self.id): a stopped container is an error
+        //   ("container web is not running"), not a setns() into nothing
+        // - open_namespaces(pid) and join_cgroup(pid) first, then
+        //   enter(&namespaces)
+        // - Then fork (ns_tool::spawn::spawn() with default options): the
+        //   child is the first process born in the container's PID
+        //   namespace. In it: chdir("/"), execvp(command)
+        // - Parent: run::forward_signals(&child), child.wait(), exit with
+        //   its code (128 + signal if killed), as `contain run` does
+        // - The command is not a child of the container's init: when it
+        //   exits, its parent is contain exec, outside. Its own orphans
+        //   go to the init, which reaps them
+        //
+        // Expected output:
+        //   $ sudo contain exec web -- /bin/sh -c 'hostname; echo $$'
+        //   box
+        //   7
+        let _ = (&self.id, &self.command); // Suppress unused warning
+        todo!("Implement contain exec - see docs/fast-track/23-exec.md")
+    }
+}
+
+/// Open /proc/<pid>/ns/<kind> for each of ENTERED_NAMESPACES that differs
+/// from ours: the fds stay valid after the first setns() changes /proc.
+#[allow(dead_code)]
+pub fn open_namespaces(pid: i32) -> Result<Vec<(NamespaceKind, std::fs::File)>> {
+    // TODO: Implement in fast-track lesson 23
+    // Hints:
+    // - ns_tool::NamespaceId::of_pid(pid, kind) and ::current(kind):
+    //   skip a namespace the container shares with us. setns() into your
+    //   own user namespace fails with EINVAL, and --share-net containers
+    //   share the net namespace on purpose
+    // - File::open(format!("/proc/{pid}/ns/{}", kind.proc_name()))
+    let _ = pid; // Suppress unused warning
+    todo!("Implement namespace opening")
+}
+
+/// Move this process into the cgroup of container init `pid`.
+#[allow(dead_code)]
+pub fn join_cgroup(pid: i32) -> Result<()> {
+    // TODO: Implement in fast-track lesson 23
+    // Hints:
+    // - /proc/<pid>/cgroup: "0::/contain/run-4240" on cgroup v2
+    // - Write "0" (this process) to /sys/fs/cgroup/<path>/cgroup.procs,
+    //   before setns(CLONE_NEWNS): afterwards /sys/fs/cgroup is the
+    //   container's view, if it has one at all
+    // - The command is then charged to the container's memory.max and
+    //   pids.max, as docker exec does: it cannot escape the limits
+    let _ = pid; // Suppress unused warning
+    todo!("Implement cgroup join")
+}
+
+/// setns() into each namespace, in the order given.
+#[allow(dead_code)]
+pub fn enter(namespaces: &[(NamespaceKind, std::fs::File)]) -> Result<()> {
+    // TODO: Implement in fast-track lesson 23
+    // Hints:
+    // - nix::sched::setns(&file, CloneFlags::from_bits_retain(kind.clone_flag() as i32))
+    // - The error names the namespace: "setns(mnt): Operation not
+    //   permitted". EPERM on mnt after user: the container's user
+    //   namespace does not own its mount namespace
+    // - Joining a mount namespace resets root and cwd to the
+    //   namespace's: chdir("/") after it
+    let _ = namespaces; // Suppress unused warning
+    todo!("Implement namespace entry")
+}
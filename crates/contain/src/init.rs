@@ -0,0 +1,88 @@
+// Minimal init for the contain CLI (fast-track lesson 13)
+// `contain ns container` execs this as PID 1 of the new PID namespace, and it
+// runs the real command as its only child:
+//
+//   contain (host) ── contain init (PID 1) ── /bin/sh (PID 2) ── ...
+//
+// PID 1 has two jobs nobody else does:
+// - Reap orphans: every process whose parent exits is re-parented to PID 1,
+//   and stays a zombie until PID 1 calls wait() on it
+// - Pass on signals: the kernel drops signals sent to PID 1 from inside its
+//   namespace unless PID 1 installed a handler, so a shell or server running
+//   as PID 1 often ignores SIGTERM
+//
+// The init is compiled into contain (a hidden `init` subcommand), so there is
+// no separate binary to copy into the container. ns_tool::init is a finished
+// version of the same loop (used by `ns-tool pid -- COMMAND`): write yours
+// first, then compare.
+
+use anyhow::Result;
+use clap::Args;
+use nix::sys::wait::WaitStatus;
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Command to run as the init's child
+    #[arg(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+/// Signals passed on to the child; everything else keeps its default action.
+///
+/// SIGCHLD is handled by init itself (it means "reap"), and SIGKILL/SIGSTOP
+/// cannot be caught at all. Shared with ns_tool::init, so both inits forward
+/// the same set.
+#[allow(unused_imports)]
+pub use ns_tool::init::FORWARDED_SIGNALS;
+
+impl InitArgs {
+    pub fn run(&self) -> Result<()> {
+        // TODO: Implement the init shim
+        // Lesson: docs/fast-track/13-init.md
+        // Tests: tests/init_test.rs
+        //
+        // Implementation hints:
+        // - Block FORWARDED_SIGNALS and SIGCHLD with sigprocmask(SIG_BLOCK)
+        //   BEFORE fork(), so none arrives between fork() and the loop
+        // - Child: restore the signal mask (SIG_SETMASK to the old one),
+        //   then execvp(command[0], command). Stay in init's session and
+        //   process group, so an interactive shell keeps the terminal
+        // - Parent: loop on SigSet::wait() (sigwait):
+        //   - SIGCHLD: waitpid(Pid::from_raw(-1), Some(WaitPidFlag::WNOHANG))
+        //     until StillAlive or ECHILD; remember the status of the main child
+        //   - Anything else: kill(child, sig)
+        // - Once the main child has exited, reap whatever is left
+        //   (WNOHANG) and std::process::exit(exit_code(status))
+        // - Stuck? std::process::exit(ns_tool::init::run(&self.command)?)
+        //   is the reference version; it also puts the command in its own
+        //   process group and forwards each signal to the whole group
+        let _ = &self.command; // Suppress unused warning
+        todo!("Implement init shim - see docs/fast-track/13-init.md")
+    }
+}
+
+/// The exit code init reports for its main child, like a shell does.
+#[allow(dead_code)]
+pub fn exit_code(status: WaitStatus) -> i32 {
+    // TODO: Implement in fast-track lesson 13
+    // Hints:
+    // - WaitStatus::Exited(_, code) => code
+    // - WaitStatus::Signaled(_, sig, _) => 128 + sig as i32 (SIGTERM -> 143)
+    // - Anything else (stopped, continued) is not an exit: 1
+    let _ = status; // Suppress unused warning
+    todo!("Implement exit code mapping")
+}
+
+/// The argv `ns container` execs as PID 1: the init wrapping `command`,
+/// or `command` itself with `--no-init`.
+#[allow(dead_code)]
+pub fn init_argv(command: &[String], no_init: bool) -> Vec<String> {
+    // TODO: Implement in fast-track lesson 13
+    // Hints:
+    // - Empty command means /bin/sh, with or without init
+    // - With init: ["/proc/self/exe", "init", "--", command...]. /proc/self/exe
+    //   is contain itself, so the init needs no copy in the container. If you
+    //   later pivot_root(), open it first and exec the fd (fexecve) instead
+    let _ = (command, no_init); // Suppress unused warning
+    todo!("Implement init argv")
+}
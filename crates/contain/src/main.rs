@@ -7,6 +7,10 @@
 //   contain ns pid          - PID namespace isolation
 //   contain ns mount        - Mount namespace isolation
 //   contain ns container    - Combined namespaces (mini-container)
+//                             --share-net/--share-ipc join a named container
+//                             --no-init runs the command as PID 1 directly
+//                             --with-tracing exposes ebpf-tool inside it
+//                             --rootfs pivots into a directory of its own
 //   contain net create      - Create network namespace
 //   contain net delete      - Delete network namespace
 //   contain net veth        - Create veth pair
@@ -16,31 +20,67 @@
 //   contain cgroup memory   - Set memory limit
 //   contain cgroup cpu      - Set CPU limit
 //   contain oci init        - Initialize OCI bundle
-//   contain oci run         - Run container with runc
+//   contain oci run         - Run container with runc (--builtin: without it)
 //   contain trace check     - Check eBPF support
 //   contain trace syscalls  - Trace syscalls with eBPF
-//   contain trace events    - Trace container events
+//   contain trace events    - Trace a container's fork, exec and exit
+//   contain trace remote    - Ask the host to trace (inside --with-tracing proxy)
+//   contain metrics         - Serve Prometheus metrics for managed containers
+//   contain security        - Scored security report for a running container
+//   contain stop            - Stop a named container: signal, wait, then SIGKILL
+//   contain run             - Full mini-container: rootfs, namespaces, limits
+//                             ns container and run work without sudo (rootless)
+//                             --seccomp/--seccomp-deny filter its syscalls
+//                             --cap-drop/--cap-add choose its capabilities
+//   contain rootfs overlay  - Run on a writable overlay of a read-only image
+//   contain ps              - List the containers contain run started
+//   contain inspect         - Full recorded state of one container, as JSON
+//   contain exec            - Run a command inside a running container
+//   contain init            - (internal) PID 1 shim exec'd by ns container
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod capabilities;
 mod cgroup;
+mod exec;
+mod init;
+mod inspect;
+mod metrics;
 mod net;
 mod ns;
 mod oci;
+mod ps;
+mod rootfs;
+mod rootless;
+mod run;
+mod seccomp;
+mod security;
+mod state;
+mod stop;
 mod trace;
+mod tracing;
 
 #[derive(Parser)]
 #[command(name = "contain")]
 #[command(version = "0.1.0")]
 #[command(about = "Learn container internals hands-on")]
-#[command(long_about = "A unified CLI for the fast-track container tutorials.\n\n\
+#[command(
+    long_about = "A unified CLI for the fast-track container tutorials.\n\n\
     Each subcommand teaches a core container concept:\n\
     - ns: Linux namespaces (PID, mount, network)\n\
     - net: Network namespace management\n\
     - cgroup: Resource limits (memory, CPU)\n\
     - oci: OCI bundle format and runc\n\
-    - trace: eBPF observability")]
+    - trace: eBPF observability\n\
+    - metrics: Prometheus endpoint for container resource usage\n\
+    - security: Audit a running container's isolation\n\
+    - stop: Stop a container with a signal and a timeout\n\
+    - run: All of the above in one command\n\
+    - rootfs: Writable container roots on read-only images\n\
+    - ps, inspect: What is running, from the state store\n\
+    - exec: Enter a running container"
+)]
 struct Cli {
     #[command(subcommand)]
     command: Command,
@@ -82,6 +122,46 @@ enum Command {
         #[command(subcommand)]
         cmd: trace::TraceCommand,
     },
+
+    /// Serve read-only Prometheus metrics for managed containers
+    /// Lesson: 11-metrics
+    Metrics(metrics::MetricsArgs),
+
+    /// Audit a running container's isolation and print a scored report
+    /// Lesson: 15-security-report
+    Security(security::SecurityArgs),
+
+    /// Stop a named container: the stop signal, then SIGKILL after a timeout
+    /// Lesson: 16-stop
+    Stop(stop::StopArgs),
+
+    /// Run a command in its own root filesystem, namespaces and cgroup
+    /// Lesson: 17-run
+    Run(run::RunArgs),
+
+    /// Container root filesystems: overlay layers on a read-only image
+    /// Lesson: 19-overlay
+    Rootfs {
+        #[command(subcommand)]
+        cmd: rootfs::RootfsCommand,
+    },
+
+    /// List containers started by `contain run`
+    /// Lesson: 22-ps-inspect
+    Ps(ps::PsArgs),
+
+    /// Print the recorded state of a container as JSON
+    /// Lesson: 22-ps-inspect
+    Inspect(inspect::InspectArgs),
+
+    /// Run a command in a running container's namespaces and cgroup
+    /// Lesson: 23-exec
+    Exec(exec::ExecArgs),
+
+    /// Minimal init: reap zombies and forward signals to COMMAND
+    /// Lesson: 13-init (exec'd by `ns container`, not run by hand)
+    #[command(hide = true)]
+    Init(init::InitArgs),
 }
 
 fn main() -> Result<()> {
@@ -93,5 +173,14 @@ fn main() -> Result<()> {
         Command::Cgroup { cmd } => cmd.run(),
         Command::Oci { cmd } => cmd.run(),
         Command::Trace { cmd } => cmd.run(),
+        Command::Metrics(args) => args.run(),
+        Command::Security(args) => args.run(),
+        Command::Stop(args) => args.run(),
+        Command::Run(args) => args.run(),
+        Command::Rootfs { cmd } => cmd.run(),
+        Command::Ps(args) => args.run(),
+        Command::Inspect(args) => args.run(),
+        Command::Exec(args) => args.run(),
+        Command::Init(args) => args.run(),
     }
 }
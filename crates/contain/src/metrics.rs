@@ -0,0 +1,88 @@
+// Metrics endpoint for the contain CLI
+// Exposes per-container resource usage in Prometheus text format (fast-track lesson 11).
+//
+// A "managed container" is any child directory of --cgroup-root, e.g. the
+// cgroups created with `contain cgroup create /sys/fs/cgroup/contain/<name>`.
+
+use anyhow::Result;
+use clap::Args;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct MetricsArgs {
+    /// Address to serve GET /metrics on
+    #[arg(long, default_value = "127.0.0.1:9300")]
+    pub listen: SocketAddr,
+
+    /// Parent cgroup whose children are treated as containers
+    #[arg(long, default_value = "/sys/fs/cgroup/contain")]
+    pub cgroup_root: PathBuf,
+}
+
+/// One scrape worth of data for a single container.
+#[allow(dead_code)]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ContainerSample {
+    /// Container name (the cgroup directory name)
+    pub name: String,
+    /// usage_usec from cpu.stat
+    pub cpu_usage_usec: u64,
+    /// memory.current
+    pub memory_bytes: u64,
+    /// pids.current
+    pub pids: u64,
+    /// Restart count (0 until contain supervises restarts)
+    pub restarts: u64,
+    /// rx_bytes summed over non-loopback interfaces in the container's netns
+    pub net_rx_bytes: u64,
+    /// tx_bytes summed over non-loopback interfaces in the container's netns
+    pub net_tx_bytes: u64,
+}
+
+impl MetricsArgs {
+    pub fn run(&self) -> Result<()> {
+        // TODO: Serve Prometheus metrics over HTTP
+        // Lesson: docs/fast-track/11-metrics.md
+        // Tests: tests/metrics_test.rs
+        //
+        // Implementation hints:
+        // - std::net::TcpListener::bind(self.listen) - no HTTP crate needed
+        // - For each connection, read the request line; answer "GET /metrics"
+        //   with "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\r\n"
+        //   followed by render_prometheus(), anything else with 404
+        // - Collect fresh samples on every request so values are never stale
+        // - The endpoint is read-only: never write to cgroup files here
+        let _ = (&self.listen, &self.cgroup_root); // Suppress unused warning
+        todo!("Implement metrics endpoint - see docs/fast-track/11-metrics.md")
+    }
+}
+
+/// Read one container's cgroup (and network namespace) into a sample.
+#[allow(dead_code)]
+pub fn collect_sample(cgroup_dir: &Path) -> Result<ContainerSample> {
+    // TODO: Implement in fast-track lesson 11
+    // Hints:
+    // - cpu.stat: line "usage_usec <n>"
+    // - memory.current and pids.current: a single number each
+    // - Network: take the first PID in cgroup.procs and parse
+    //   /proc/<pid>/net/dev - it shows the interfaces of *that process's*
+    //   network namespace. Skip "lo". An empty cgroup reports 0.
+    let _ = cgroup_dir; // Suppress unused warning
+    todo!("Implement container sample collection")
+}
+
+/// Render samples in the Prometheus text exposition format.
+#[allow(dead_code)]
+pub fn render_prometheus(samples: &[ContainerSample]) -> String {
+    // TODO: Implement in fast-track lesson 11
+    // Hints:
+    // - One "# HELP" and "# TYPE" line per metric family, then one line per
+    //   container: contain_memory_bytes{container="web"} 1048576
+    // - Families: contain_cpu_usage_seconds_total (counter, usec / 1e6),
+    //   contain_memory_bytes (gauge), contain_pids (gauge),
+    //   contain_restarts_total (counter),
+    //   contain_network_receive_bytes_total / _transmit_bytes_total (counter)
+    let _ = samples; // Suppress unused warning
+    todo!("Implement Prometheus rendering")
+}
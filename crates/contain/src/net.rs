@@ -66,6 +66,9 @@ impl NetCommand {
                 // - Create veth pair with `ip link add`
                 // - Move one end to namespace with `ip link set netns`
                 // - Assign IP addresses to both ends
+                // - If --ns is a container from `contain ps`, put the host
+                //   end in its state.json (veth), so `contain stop` removes
+                //   it with the rest
                 let _ = (host, ns); // Suppress unused warning
                 todo!("Implement veth pair creation - see docs/fast-track/03-network-namespace.md")
             }
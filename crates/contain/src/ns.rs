@@ -3,6 +3,9 @@
 
 use anyhow::Result;
 use clap::Subcommand;
+use std::path::PathBuf;
+
+use crate::tracing::TracingMode;
 
 #[derive(Subcommand)]
 pub enum NsCommand {
@@ -16,7 +19,47 @@ pub enum NsCommand {
 
     /// Create a mini-container with combined namespaces (PID + mount + UTS)
     /// Lesson: docs/fast-track/04-combine.md
-    Container,
+    Container {
+        /// Hostname inside the container
+        #[arg(long, default_value = "container")]
+        hostname: String,
+
+        /// Record the container in the state store under this name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Use DIR as the container's root filesystem instead of the host's
+        #[arg(long, value_name = "DIR")]
+        rootfs: Option<PathBuf>,
+
+        /// Join the network namespace of a running named container
+        /// instead of creating one
+        #[arg(long, value_name = "OTHER_ID")]
+        share_net: Option<String>,
+
+        /// Join the IPC namespace of a running named container
+        #[arg(long, value_name = "OTHER_ID")]
+        share_ipc: Option<String>,
+
+        /// Run the command as PID 1 directly, without the init shim that
+        /// reaps zombies and forwards signals
+        #[arg(long)]
+        no_init: bool,
+
+        /// Make ebpf-tool usable inside the container: `direct` (the default)
+        /// bind-mounts it in with CAP_BPF/CAP_PERFMON, `proxy` forwards
+        /// requests to the host scoped to this container
+        #[arg(long, value_enum, value_name = "MODE", num_args = 0..=1, default_missing_value = "direct")]
+        with_tracing: Option<TracingMode>,
+
+        /// ebpf-tool binary to use with --with-tracing (default: next to contain, then $PATH)
+        #[arg(long, value_name = "PATH", requires = "with_tracing")]
+        ebpf_tool: Option<PathBuf>,
+
+        /// Command to run (default: /bin/sh)
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
 }
 
 impl NsCommand {
@@ -44,15 +87,101 @@ impl NsCommand {
                 // - Files created inside are invisible to host
                 todo!("Implement mount namespace - see docs/fast-track/02-mount-namespace.md")
             }
-            NsCommand::Container => {
+            NsCommand::Container {
+                hostname,
+                name,
+                rootfs,
+                share_net,
+                share_ipc,
+                no_init,
+                with_tracing,
+                ebpf_tool,
+                command,
+            } => {
                 // TODO: Implement combined namespace container
                 // Lesson: docs/fast-track/04-combine.md
                 // Tests: tests/ns_test.rs
                 //
                 // Implementation hints:
-                // - Combine CLONE_NEWPID | CLONE_NEWNS | CLONE_NEWUTS
+                // - Combine CLONE_NEWPID | CLONE_NEWNS | CLONE_NEWUTS: either
+                //   unshare() + fork() as in the lesson, or one
+                //   ns_tool::spawn::spawn() with .namespace(NamespaceKind::Pid)
+                //   and so on (docs/01-namespaces/17-clone3.md). spawn() also
+                //   returns a pidfd, so child.wait() and child.signal() can
+                //   never hit a reused PID
                 // - Set hostname inside container
                 // - Mount private /proc
+                // - --rootfs: rootfs::validate(dir, command) before creating
+                //   anything, then rootfs::enter(dir, DevSetup::for_current_user())
+                //   in the child instead of the /proc mount: it mounts /proc,
+                //   /sys and /dev inside the new root. The init is
+                //   /proc/self/exe, gone after the pivot: open it first
+                //
+                // Shared namespaces (docs/fast-track/12-shared-namespaces.md,
+                // tests/ns_share_test.rs):
+                // - Resolve --share-net/--share-ipc with state::ns_path(other, "net"/"ipc")
+                //   and open both files BEFORE unshare(), so a missing container
+                //   fails before anything is created
+                // - setns(fd, CloneFlags::CLONE_NEWNET / CLONE_NEWIPC), and leave
+                //   that flag out of unshare(): joined, not created
+                // - --name: after fork() or spawn(), the parent calls
+                //   state::record(name, child) and state::remove(name) once
+                //   waitpid() (child.wait()) returns
+                // - Graceful stop (docs/fast-track/16-stop.md, tests/stop_test.rs):
+                //   before remove(), state::stop_reason(name); if Some, print
+                //   "<name>: stopped (<reason>)" on stderr, then
+                //   state::record_exit(name, code) instead of remove():
+                //   `contain stop` reports the code and removes the entry.
+                //   The exit code stays the init's (143 for SIGTERM, 137 for
+                //   SIGKILL)
+                //
+                // Init shim (docs/fast-track/13-init.md, tests/init_test.rs):
+                // - Exec init::init_argv(command, *no_init) instead of the
+                //   command itself: by default PID 1 is `contain init`, and the
+                //   command runs as PID 2
+                // - The parent's waitpid() then sees init's exit code, which is
+                //   the command's, so nothing changes for callers
+                // - Keep `ip link set lo up` and the /proc mount before the exec:
+                //   init only reaps and forwards, it sets nothing up
+                //
+                // Rootless (docs/fast-track/18-rootless.md, tests/rootless_test.rs):
+                // - rootless::is_rootless(): create the user namespace FIRST.
+                //   With unshare() + fork(): the child does
+                //   unshare(CLONE_NEWUSER), tells the parent over a pipe and
+                //   waits; the parent writes the maps; only then does the
+                //   child unshare() the other namespaces. With spawn(): add
+                //   NamespaceKind::User, the kernel creates it before the
+                //   others, and the child waits for the maps the same way
+                // - The maps: rootless::plan(uid, &parse_subids(SUBUID_FILE
+                //   contents, user, uid)), the same for gids, then
+                //   rootless::write_maps(child, &uids, &gids) in the parent
+                // - Anything in the rootless path failing (EPERM from unshare,
+                //   a refused map): eprint!("{}", render_report(&check(..))),
+                //   then the error. The report says what to change
+                // - --name and --with-tracing need root (/run/contain, CAP_BPF):
+                //   bail before creating anything, with what to run instead
+                //
+                // Tracing (docs/fast-track/14-container-tracing.md,
+                // tests/tracing_test.rs):
+                // - Before fork(), in both modes: tracing::find_ebpf_tool(ebpf_tool),
+                //   so a missing binary fails before anything is created
+                // - Direct: tracing::check_static() too. In the child, after the
+                //   /proc mount, tracing::setup_direct(); tracing::drop_to_tracing_caps()
+                //   comes last, right before the exec (the mounts need CAP_SYS_ADMIN)
+                // - Proxy: tracing::proxy_listen(std::process::id()) before fork().
+                //   The parent creates PROXY_CGROUP_ROOT/<contain's PID> and
+                //   spawns the child straight into it with
+                //   SpawnOptions::cgroup(): without that, the child's first
+                //   syscalls would escape the scope (with plain fork(), make the
+                //   child wait on a pipe until the parent has written its PID to
+                //   cgroup.procs). Then tracing::serve_proxy() in a thread, and
+                //   rmdir the cgroup after the wait. The child bind-mounts the
+                //   socket onto tracing::CONTAINER_SOCKET and keeps no extra
+                //   capabilities
+                let _ = (
+                    hostname, name, rootfs, share_net, share_ipc, no_init, command,
+                ); // Suppress unused warning
+                let _ = (with_tracing, ebpf_tool); // Suppress unused warning
                 todo!("Implement mini-container - see docs/fast-track/04-combine.md")
             }
         }
@@ -22,6 +22,11 @@ pub enum OciCommand {
         /// Container ID
         #[arg(long, default_value = "mycontainer")]
         id: String,
+
+        /// Run the bundle with contain's own namespaces and rootfs setup
+        /// instead of runc
+        #[arg(long)]
+        builtin: bool,
     },
 }
 
@@ -40,7 +45,7 @@ impl OciCommand {
                 let _ = path; // Suppress unused warning
                 todo!("Implement OCI bundle init - see docs/fast-track/08-oci-bundle.md")
             }
-            OciCommand::Run { path, id } => {
+            OciCommand::Run { path, id, builtin } => {
                 // TODO: Run container using runc
                 // Lesson: docs/fast-track/09-runc-run.md
                 // Tests: tests/oci_test.rs
@@ -48,7 +53,17 @@ impl OciCommand {
                 // Implementation hints:
                 // - Invoke `runc run` with bundle path
                 // - Handle container lifecycle
-                let _ = (path, id); // Suppress unused warning
+                //
+                // Without runc (--builtin, docs/fast-track/17-run.md):
+                // - Read config.json: root.path (relative to the bundle),
+                //   process.args, hostname
+                // - The same steps as `contain run`: spawn() into new
+                //   namespaces, rootfs::validate() and rootfs::enter() on
+                //   root.path, exec process.args
+                // - Only what config.json says that contain supports: bail
+                //   on a mount, seccomp or capability list you cannot apply,
+                //   rather than running less isolated than the bundle asked
+                let _ = (path, id, builtin); // Suppress unused warning
                 todo!("Implement OCI run - see docs/fast-track/09-runc-run.md")
             }
         }
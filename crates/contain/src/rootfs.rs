@@ -0,0 +1,333 @@
+// Container root filesystems for the contain CLI
+// The pivot_root dance shared by `ns container --rootfs`, `run` and
+// `oci run --builtin`: turn a directory into the root of a mount namespace
+// that looks like a real system.
+//
+//   host /                          container /  (= rootfs)
+//   ├─ home, etc, ...               ├─ bin, etc, ...   the directory's own files
+//   └─ srv/rootfs  ──pivot_root──►  ├─ proc            proc of the new PID namespace
+//                                   ├─ sys             sysfs, read-only
+//                                   └─ dev             tmpfs + DEV_NODES
+//
+// Every step runs in the child, inside its new mount namespace, in this
+// order (enter() does them all):
+//
+//   1. mount / MS_REC | MS_PRIVATE     nothing below propagates to the host
+//   2. bind rootfs onto itself         pivot_root() needs a mount point
+//   3. /dev, /proc, /sys under rootfs  while the host's are still visible
+//   4. pivot_root + detach old root    the host's files are gone
+//
+// The fast-track mount lesson (docs/fast-track/02-mount-namespace.md) only
+// isolates /tmp; this is the rest of the way to chroot-like isolation that
+// cannot be escaped with `cd ..`.
+//
+// `contain rootfs overlay` (fast-track lesson 19) puts a writable layer on
+// top of a read-only image before entering it:
+//
+//   merged/   what the container sees as /
+//     = upper/  (writable: new files, copies of changed ones, whiteouts)
+//     + lower/  (the image, never written)
+
+use anyhow::Result;
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand)]
+pub enum RootfsCommand {
+    /// Run a command in an overlay of a read-only image and a writable dir
+    /// Lesson: docs/fast-track/19-overlay.md
+    Overlay {
+        /// Read-only image directory; repeat for more layers, top first
+        #[arg(long, value_name = "IMAGE_DIR", required = true)]
+        lower: Vec<PathBuf>,
+
+        /// Writable directory that receives the container's changes
+        #[arg(long, value_name = "DIR")]
+        upper: PathBuf,
+
+        /// Overlayfs work directory (default: a fresh one next to --upper)
+        #[arg(long, value_name = "DIR")]
+        work: Option<PathBuf>,
+
+        /// Hostname inside the container
+        #[arg(long, default_value = "container")]
+        hostname: String,
+
+        /// Command to run (default: /bin/sh)
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
+}
+
+impl RootfsCommand {
+    pub fn run(&self) -> Result<()> {
+        match self {
+            RootfsCommand::Overlay {
+                lower,
+                upper,
+                work,
+                hostname,
+                command,
+            } => {
+                // TODO: Run a container on an overlay root filesystem
+                // Lesson: docs/fast-track/19-overlay.md
+                // Tests: tests/rootfs_test.rs
+                //
+                // Implementation hints:
+                // - create_dir_all(upper); the lowers must exist already
+                // - mount_overlay(lower, upper, work) on the host, then the
+                //   same steps as `contain run --rootfs <merged>`:
+                //   run::RunArgs { rootfs: overlay.merged.clone(), .. }
+                // - Cleanup on exit, also when the container failed: unmount
+                //   the overlay (Overlay::unmount), remove merged/ and the
+                //   work dir. upper/ stays: it is the container's changes
+                // - RunArgs::run() ends in std::process::exit(), which skips
+                //   destructors: take the exit code back from it and exit
+                //   after the cleanup instead
+                //
+                // Expected output:
+                //   $ sudo contain rootfs overlay --lower ./rootfs --upper ./changes \
+                //       -- /bin/sh -c 'echo hi > /hello; rm /bin/ls'
+                //   $ ls ./changes ./changes/bin
+                //   hello  bin
+                //   ls      (a whiteout: c--------- 0,0)
+                //   $ ls ./rootfs/bin/ls
+                //   ./rootfs/bin/ls
+                let _ = (lower, upper, work, hostname, command); // Suppress unused warning
+                todo!("Implement overlay rootfs - see docs/fast-track/19-overlay.md")
+            }
+        }
+    }
+}
+
+/// A device node every container gets: /dev/<name>, char device major:minor.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DevNode {
+    pub name: &'static str,
+    pub major: u64,
+    pub minor: u64,
+}
+
+/// The device nodes nearly every program expects (the same set as runc's
+/// default devices); disks and the rest of /dev stay on the host.
+#[allow(dead_code)]
+pub const DEV_NODES: &[DevNode] = &[
+    DevNode {
+        name: "null",
+        major: 1,
+        minor: 3,
+    },
+    DevNode {
+        name: "zero",
+        major: 1,
+        minor: 5,
+    },
+    DevNode {
+        name: "full",
+        major: 1,
+        minor: 7,
+    },
+    DevNode {
+        name: "random",
+        major: 1,
+        minor: 8,
+    },
+    DevNode {
+        name: "urandom",
+        major: 1,
+        minor: 9,
+    },
+    DevNode {
+        name: "tty",
+        major: 5,
+        minor: 0,
+    },
+];
+
+/// How the DEV_NODES get into the container's /dev.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevSetup {
+    /// mknod() each node: needs CAP_MKNOD in the initial user namespace
+    Mknod,
+    /// Bind-mount the host's nodes: works in a user namespace (rootless)
+    Bind,
+}
+
+impl DevSetup {
+    /// Mknod as real root, Bind in a user namespace, where mknod() of a
+    /// device always fails with EPERM.
+    #[allow(dead_code)]
+    pub fn for_current_user() -> DevSetup {
+        // TODO: Implement with enter()
+        // Hints:
+        // - Real root is euid 0 AND the initial user namespace:
+        //   /proc/self/uid_map is "0 0 4294967295"
+        // - Under sudo that is the case; in a rootless container
+        //   (docs/fast-track/18-rootless.md) it is not, even as uid 0 inside
+        todo!("Implement device setup choice")
+    }
+}
+
+/// Check that `rootfs` can become a root: a directory with `command` in it.
+#[allow(dead_code)]
+pub fn validate(rootfs: &Path, command: &str) -> Result<()> {
+    // TODO: Implement with enter()
+    // Hints:
+    // - metadata(rootfs)?.is_dir(), with the path in the error
+    // - `command` is looked up inside: rootfs.join(command without the
+    //   leading /). A symlink (busybox) counts if it resolves *within*
+    //   the rootfs: read_link() and join relative targets by hand, since
+    //   canonicalize() would follow absolute ones on the host
+    // - Not a relative name like "sh": $PATH is the container's, so only
+    //   check those after pivot_root, or require an absolute path
+    // - Runs BEFORE any namespace is created: a typo fails cheaply
+    let _ = (rootfs, command); // Suppress unused warning
+    todo!("Implement rootfs validation")
+}
+
+/// Steps 1 to 4: make `rootfs` the root of this mount namespace.
+#[allow(dead_code)]
+pub fn enter(rootfs: &Path, dev: DevSetup) -> Result<()> {
+    // TODO: Implement with the fast-track lessons 17 and 18
+    // Hints:
+    // - mount(None, "/", None, MS_REC | MS_PRIVATE): step 1
+    // - Bind-mount rootfs onto itself (MS_BIND | MS_REC): step 2
+    // - mount_dev(), mount_proc() and mount_sys() on rootfs, then
+    //   pivot_root(rootfs)
+    // - The caller is already in the new PID and network namespaces (a
+    //   child of spawn() or fork()): the proc and sysfs it mounts show them
+    // - Each step's error says which step failed ("pivot_root(/srv/rootfs):
+    //   Invalid argument"): EINVAL from pivot_root has several causes
+    let _ = (rootfs, dev); // Suppress unused warning
+    todo!("Implement rootfs setup")
+}
+
+/// Make `rootfs` (already a mount point) the root and drop the old one.
+#[allow(dead_code)]
+pub fn pivot_root(rootfs: &Path) -> Result<()> {
+    // TODO: Implement with enter()
+    // Hints:
+    // - chdir(rootfs), nix::unistd::pivot_root(".", "."): the old root is
+    //   stacked under the new one, no put_old directory needed
+    // - umount2(".", MNT_DETACH) removes the old root, then chdir("/")
+    // - EINVAL: rootfs is not a mount point (step 2 missing), or / is
+    //   shared (step 1 missing), or the process runs in a chroot
+    let _ = rootfs; // Suppress unused warning
+    todo!("Implement pivot_root")
+}
+
+/// Mount a proc showing the caller's PID namespace on rootfs/proc.
+#[allow(dead_code)]
+pub fn mount_proc(rootfs: &Path) -> Result<()> {
+    // TODO: Implement with enter()
+    // Hints:
+    // - create_dir_all(rootfs/proc), then mount("proc", rootfs/proc, "proc",
+    //   MS_NOSUID | MS_NODEV | MS_NOEXEC)
+    // - Inside the new PID namespace only: from the parent, it would show
+    //   the host's processes
+    // - Before pivot_root(): in a user namespace the kernel allows a new
+    //   proc only while a fully visible one is mounted in the namespace,
+    //   and the host's /proc goes away with the old root
+    let _ = rootfs; // Suppress unused warning
+    todo!("Implement /proc mount")
+}
+
+/// Mount sysfs read-only on rootfs/sys.
+#[allow(dead_code)]
+pub fn mount_sys(rootfs: &Path) -> Result<()> {
+    // TODO: Implement with enter()
+    // Hints:
+    // - mount("sysfs", rootfs/sys, "sysfs", MS_RDONLY | MS_NOSUID |
+    //   MS_NODEV | MS_NOEXEC): read-only, so the container cannot change
+    //   host devices
+    // - sysfs shows the network devices of the mounting process's network
+    //   namespace: mount it after CLONE_NEWNET, or /sys/class/net is the
+    //   host's
+    // - In a user namespace without its own network namespace, EPERM: skip
+    //   /sys with a warning rather than failing the container
+    let _ = rootfs; // Suppress unused warning
+    todo!("Implement /sys mount")
+}
+
+/// A minimal /dev under `rootfs`: a tmpfs with the DEV_NODES.
+#[allow(dead_code)]
+pub fn mount_dev(rootfs: &Path, dev: DevSetup) -> Result<()> {
+    // TODO: Implement with enter()
+    // Hints:
+    // - Mount a tmpfs (mode=755, MS_NOSUID) on rootfs/dev. Bind needs the
+    //   host's /dev, which is gone after pivot_root()
+    // - Mknod: mknod(rootfs/dev/<name>, S_IFCHR, 0o666,
+    //   makedev(major, minor)); the tmpfs is not MS_NODEV for that reason
+    // - Bind: create an empty file per node and bind-mount /dev/<name> on it
+    // - Symlinks: fd -> /proc/self/fd, stdin/stdout/stderr -> fd/0..2,
+    //   ptmx -> pts/ptmx
+    // - mkdir pts and shm; a devpts mount with "newinstance,ptmxmode=0666"
+    //   gives the container its own terminals
+    let _ = (rootfs, dev); // Suppress unused warning
+    todo!("Implement /dev setup")
+}
+
+/// A mounted overlayfs, until unmount() removes it and its scratch
+/// directories.
+#[allow(dead_code)]
+pub struct Overlay {
+    /// Mount point of the combined tree: the container's rootfs
+    pub merged: PathBuf,
+    /// Overlayfs scratch space; must be empty and on upper's filesystem
+    pub work: PathBuf,
+    /// Whether `work` was created by mount_overlay() and is removed with it
+    pub owns_work: bool,
+}
+
+/// The mount data of an overlay of `lower` (top first) under `upper`:
+/// "lowerdir=a:b,upperdir=u,workdir=w".
+#[allow(dead_code)]
+pub fn overlay_options(lower: &[PathBuf], upper: &Path, work: &Path) -> Result<String> {
+    // TODO: Implement in fast-track lesson 19
+    // Hints:
+    // - Join the lowers with ':', the first one is the top layer
+    // - Absolute paths: canonicalize() each, since the kernel resolves them
+    //   from the mounting process's cwd
+    // - ',' and ':' separate options and layers, and the kernel has no
+    //   escaping for them here: bail on a path that contains one
+    let _ = (lower, upper, work); // Suppress unused warning
+    todo!("Implement overlay mount options")
+}
+
+/// Mount an overlay of `lower` and `upper` on a new merged directory.
+#[allow(dead_code)]
+pub fn mount_overlay(lower: &[PathBuf], upper: &Path, work: Option<&Path>) -> Result<Overlay> {
+    // TODO: Implement in fast-track lesson 19
+    // Hints:
+    // - No --work: create "<upper>.work-<pid>" next to upper. overlayfs
+    //   refuses a work dir on another filesystem than upper (EXDEV) or
+    //   inside it
+    // - merged: a fresh directory, e.g. "<upper>.merged-<pid>"
+    // - mount("overlay", merged, "overlay", MsFlags::empty(),
+    //   overlay_options(..)). EINVAL usually means upper is itself on an
+    //   overlay (docker, some CI): overlayfs cannot be its upper layer
+    // - overlay on `lower` needs nothing but read access: the image is
+    //   never written, a changed file is copied up to upper first
+    // - If the mount fails, remove what was created before returning
+    let _ = (lower, upper, work); // Suppress unused warning
+    todo!("Implement overlay mount")
+}
+
+impl Overlay {
+    /// Unmount the overlay and remove merged/ (and work/ if it was created).
+    #[allow(dead_code)]
+    pub fn unmount(self) -> Result<()> {
+        // TODO: Implement in fast-track lesson 19
+        // Hints:
+        // - umount2(merged, MNT_DETACH): the mount namespace of the
+        //   container is gone, but the host may still have a shell in it
+        // - remove_dir(merged); remove_dir_all(work) only if owns_work. It
+        //   holds a "work" subdirectory owned by root with mode 000
+        // - Also impl Drop with the same steps, ignoring errors: a panic in
+        //   the parent must not leave the mount behind
+        let _ = (&self.merged, &self.work, self.owns_work); // Suppress unused warning
+        todo!("Implement overlay cleanup")
+    }
+}
@@ -0,0 +1,196 @@
+// Rootless containers for the contain CLI (fast-track lesson 18)
+// Without root, `ns container` and `run` create a user namespace FIRST and
+// the other namespaces inside it. Root in the new user namespace owns them,
+// so it may mount, set the hostname and create a PID namespace:
+//
+//   you (uid 1000)
+//     └─ unshare(CLONE_NEWUSER)            child is "nobody" until mapped
+//          parent writes the id maps       0 -> 1000, 1..65536 -> 100000...
+//          └─ unshare(PID | MNT | UTS ...)  allowed: we are root in here
+//
+// One id (0 -> your uid) needs no help: a process may map its own uid.
+// A whole range (/etc/subuid, /etc/subgid) needs the setuid helpers
+// newuidmap/newgidmap from the shadow package, which check the files for
+// us. Without a range the container has one user; `chown` to any other
+// fails with EINVAL.
+
+use anyhow::Result;
+use std::path::Path;
+
+/// Where the subordinate id ranges of each user are listed.
+#[allow(dead_code)]
+pub const SUBUID_FILE: &str = "/etc/subuid";
+#[allow(dead_code)]
+pub const SUBGID_FILE: &str = "/etc/subgid";
+
+/// One line of a uid_map or gid_map: `count` ids from `outside` appear as
+/// `inside`, `inside + 1`, ...
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdRange {
+    pub inside: u32,
+    pub outside: u32,
+    pub count: u32,
+}
+
+/// The uid or gid map of a rootless container.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdMap {
+    pub ranges: Vec<IdRange>,
+}
+
+/// Outcome of one rootless check.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Support {
+    /// Works
+    Ok,
+    /// Works with less: one id instead of a range, no cgroup limits
+    Limited,
+    /// Rootless containers cannot start
+    Missing,
+}
+
+/// One line of the capability report.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capability {
+    /// What was checked, e.g. "user namespaces"
+    pub name: &'static str,
+    pub support: Support,
+    /// What was found, e.g. "kernel.unprivileged_userns_clone = 0"
+    pub detail: String,
+    /// How to fix it; None for Ok
+    pub hint: Option<String>,
+}
+
+/// Whether `ns container` and `run` have to go rootless.
+#[allow(dead_code)]
+pub fn is_rootless() -> bool {
+    // TODO: Implement in fast-track lesson 18
+    // Hints:
+    // - !nix::unistd::Uid::effective().is_root()
+    // - sudo is root: the rootful path, unchanged
+    todo!("Implement rootless detection")
+}
+
+/// The subordinate id ranges of `user` (name or numeric id) in `contents`,
+/// the text of /etc/subuid or /etc/subgid.
+#[allow(dead_code)]
+pub fn parse_subids(contents: &str, user: &str, id: u32) -> Vec<(u32, u32)> {
+    // TODO: Implement in fast-track lesson 18
+    // Hints:
+    // - One "name:start:count" per line, e.g. "alice:100000:65536"
+    // - The first field is a user name OR a numeric id: match either
+    //   `user` or `id.to_string()`
+    // - Skip blank lines, "#" comments and lines that do not parse; a user
+    //   may have several lines, keep them all in file order
+    let _ = (contents, user, id); // Suppress unused warning
+    todo!("Implement subordinate id parsing")
+}
+
+/// The map for a rootless container: root inside is `id` outside, and the
+/// `subids` ranges follow from 1.
+#[allow(dead_code)]
+pub fn plan(id: u32, subids: &[(u32, u32)]) -> IdMap {
+    // TODO: Implement in fast-track lesson 18
+    // Hints:
+    // - First IdRange { inside: 0, outside: id, count: 1 }
+    // - Then each (start, count) at the next free inside id: 1, 1 + count, ...
+    // - No subids: the single range only, which needs no helper
+    let _ = (id, subids); // Suppress unused warning
+    todo!("Implement id map planning")
+}
+
+impl IdMap {
+    /// The map as the kernel wants it in /proc/<pid>/uid_map: one
+    /// "inside outside count" line per range.
+    #[allow(dead_code)]
+    pub fn to_proc_format(&self) -> String {
+        // TODO: Implement in fast-track lesson 18
+        // Hints:
+        // - "0 1000 1\n1 100000 65536\n"
+        let _ = &self.ranges; // Suppress unused warning
+        todo!("Implement id map formatting")
+    }
+
+    /// The arguments of newuidmap/newgidmap after the PID: the ranges
+    /// flattened, "0 1000 1 1 100000 65536".
+    #[allow(dead_code)]
+    pub fn to_helper_args(&self) -> Vec<String> {
+        // TODO: Implement in fast-track lesson 18
+        let _ = &self.ranges; // Suppress unused warning
+        todo!("Implement helper arguments")
+    }
+}
+
+/// Write the uid and gid maps of the child `pid`, which is blocked until
+/// they are in place.
+#[allow(dead_code)]
+pub fn write_maps(pid: i32, uids: &IdMap, gids: &IdMap) -> Result<()> {
+    // TODO: Implement in fast-track lesson 18
+    // Hints:
+    // - One range each: write to /proc/<pid>/uid_map, then "deny" to
+    //   /proc/<pid>/setgroups, then gid_map. Without the "deny" the kernel
+    //   refuses an unprivileged gid_map (a process could otherwise drop a
+    //   group that denies it access)
+    // - More ranges: find_helper("newuidmap") and run
+    //   `newuidmap <pid> <to_helper_args()>`, the same with newgidmap.
+    //   The helpers are setuid root and check /etc/subuid for us; setgroups
+    //   stays "allow", so the container can use setgroups()
+    // - Running as root: write the files directly, any map is allowed
+    // - A helper that fails is an error with its stderr: it explains well
+    //   ("newuidmap: write to uid_map failed: Operation not permitted")
+    let _ = (pid, uids, gids); // Suppress unused warning
+    todo!("Implement id map writing")
+}
+
+/// The path of a setuid id map helper, if it is installed and usable.
+#[allow(dead_code)]
+pub fn find_helper(name: &str) -> Option<std::path::PathBuf> {
+    // TODO: Implement in fast-track lesson 18
+    // Hints:
+    // - Look in each $PATH directory
+    // - Usable means setuid root (mode & 0o4000 and uid 0) or file
+    //   capabilities (the security.capability xattr): a plain copy cannot
+    //   write anyone else's map
+    let _ = name; // Suppress unused warning
+    todo!("Implement helper lookup")
+}
+
+/// Everything rootless mode depends on, in report order.
+#[allow(dead_code)]
+pub fn check(proc_sys: &Path) -> Vec<Capability> {
+    // TODO: Implement in fast-track lesson 18
+    // Hints (proc_sys is /proc/sys, a parameter so it can point elsewhere):
+    // - "user namespaces": user/max_user_namespaces > 0, and, where they
+    //   exist, kernel/unprivileged_userns_clone = 1 (Debian, older Ubuntu)
+    //   and kernel/apparmor_restrict_unprivileged_userns = 0 (Ubuntu
+    //   23.10+). Any of them off: Missing, with the sysctl to change
+    // - "id ranges": /etc/subuid and /etc/subgid entries for the user.
+    //   None: Limited ("only uid 0 inside"), hint `usermod --add-subuids
+    //   100000-165535 --add-subgids 100000-165535 <user>`
+    // - "newuidmap": find_helper() for both; missing with ranges: Limited,
+    //   hint to install uidmap (Debian) or shadow-utils (Fedora)
+    // - "cgroup delegation": cgroup.subtree_control of the user's systemd
+    //   slice lists memory and cpu (/sys/fs/cgroup/user.slice/
+    //   user-<uid>.slice/user@<uid>.service). No: Limited, --memory and
+    //   --cpu need root
+    let _ = proc_sys; // Suppress unused warning
+    todo!("Implement rootless capability checks")
+}
+
+/// The report printed when rootless mode cannot start a container.
+#[allow(dead_code)]
+pub fn render_report(capabilities: &[Capability]) -> String {
+    // TODO: Implement in fast-track lesson 18
+    // Hints:
+    // - Header "Rootless mode:"
+    // - One line per check: {name:<18} {ok|limited|MISSING:<7} {detail},
+    //   then "    -> {hint}" for the hint
+    // - Last line: "rootless containers need: <missing names>" or, with
+    //   nothing Missing, "rootless containers will work (see limits above)"
+    let _ = capabilities; // Suppress unused warning
+    todo!("Implement rootless report")
+}
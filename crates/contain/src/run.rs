@@ -0,0 +1,207 @@
+// Full mini-container runtime for the contain CLI (fast-track lesson 17)
+// `contain run` puts the earlier lessons together into one command:
+//
+//   contain run (host)
+//     ├─ cgroup /sys/fs/cgroup/contain/run-<pid>: memory.max, cpu.max
+//     └─ spawn(): user + pid + mount + uts + net namespaces, in the cgroup
+//          └─ child: wait for uid_map ─► hostname ─► rootfs::enter()
+//                    ─► lo up ─► drop caps ─► seccomp
+//                    ─► exec `contain init -- cmd`
+//                         └─ cmd (PID 2)
+//
+// The parent stays on the host: it writes the child's uid/gid maps
+// (rootless.rs), records the container in the state store (state.rs),
+// forwards the signals it gets to the container's init, waits, removes the
+// cgroup and the state entry and exits with the command's exit code.
+
+use crate::capabilities::{self, CapArg};
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Directory to use as the container's root filesystem
+    #[arg(long, value_name = "DIR")]
+    pub rootfs: PathBuf,
+
+    /// Memory limit (e.g. "100M", "1G")
+    #[arg(long, value_parser = parse_memory)]
+    pub memory: Option<u64>,
+
+    /// CPU limit in percent of one CPU (e.g. "50%", "150%")
+    #[arg(long, value_parser = parse_cpu)]
+    pub cpu: Option<u32>,
+
+    /// Hostname inside the container
+    #[arg(long, default_value = "container")]
+    pub hostname: String,
+
+    /// Container ID in the state store (default: run-<contain's PID>)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Seccomp profile in OCI JSON format (e.g. profiles/seccomp-starter.json)
+    #[arg(long, value_name = "PROFILE", conflicts_with = "seccomp_deny")]
+    pub seccomp: Option<PathBuf>,
+
+    /// Allow every syscall except these (comma-separated, e.g. "mount,reboot")
+    #[arg(long, value_name = "SYSCALLS", value_delimiter = ',')]
+    pub seccomp_deny: Vec<String>,
+
+    /// Capabilities to remove from the default set (e.g. "NET_RAW", "ALL")
+    #[arg(long, value_name = "CAPS", value_delimiter = ',', value_parser = capabilities::parse_cap)]
+    pub cap_drop: Vec<CapArg>,
+
+    /// Capabilities to add to the default set, after --cap-drop
+    #[arg(long, value_name = "CAPS", value_delimiter = ',', value_parser = capabilities::parse_cap)]
+    pub cap_add: Vec<CapArg>,
+
+    /// Command to run (default: /bin/sh)
+    #[arg(last = true)]
+    pub command: Vec<String>,
+}
+
+/// Parent of the per-run cgroups: /sys/fs/cgroup/contain/run-<pid>.
+#[allow(dead_code)]
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup/contain";
+
+/// The CFS period cpu.max is written with, in microseconds.
+#[allow(dead_code)]
+pub const CPU_PERIOD_US: u64 = 100_000;
+
+impl RunArgs {
+    pub fn run(&self) -> Result<()> {
+        // TODO: Implement the mini-container runtime
+        // Lesson: docs/fast-track/17-run.md
+        // Tests: tests/run_test.rs
+        //
+        // Implementation hints:
+        // - Check early, before anything is created:
+        //   rootfs::validate(&self.rootfs, command), /bin/sh by default
+        // - Limits (docs/fast-track/06-memory-limits.md, 07-cpu-limits.md):
+        //   with --memory or --cpu, create_cgroup(); without them, no cgroup
+        // - ns_tool::spawn::spawn() with .namespace(NamespaceKind::User),
+        //   Pid, Mount, Uts and Net, and .cgroup(dir) if there is one: the
+        //   child is limited from its first instruction
+        // - A pipe for the user namespace: the child blocks on read() until
+        //   the parent has called rootless::write_maps(child.pid(), ..)
+        //   with the maps of rootless::plan(). Before that it is nobody
+        //   (65534) and cannot mount anything
+        // - Rootless (docs/fast-track/18-rootless.md): the same steps work
+        //   without sudo, except the cgroup. --memory/--cpu without root
+        //   need cgroup delegation; check it first, and print
+        //   rootless::render_report() when it is missing
+        // - Seccomp (docs/fast-track/20-seccomp.md): before spawn(),
+        //   seccomp::build_filter() of Profile::load(--seccomp) or
+        //   Profile::deny_list(--seccomp-deny). Neither: no filter. A bad
+        //   profile fails here, with nothing created yet
+        // - Capabilities (docs/fast-track/21-capabilities.md): before
+        //   spawn(), CapSet::resolve(&self.cap_add, &self.cap_drop,
+        //   read_last_cap()?). Without flags that is Docker's default set
+        // - Child, in order: sethostname();
+        //   rootfs::enter(rootfs, DevSetup::for_current_user()); `lo` up
+        //   (ip link set lo up, or SIOCSIFFLAGS); capabilities::apply(keep,
+        //   last_cap), which also sets no_new_privs; seccomp::apply(&filter)
+        //   last, since it may deny what the steps before need; then exec
+        //   init::init_argv(command, false). Return
+        //   ns_tool::spawn::EXIT_SETUP_FAILED (125) on a failed step,
+        //   after printing it
+        // - The init is /proc/self/exe, which is gone after pivot_root():
+        //   open it first (File::open("/proc/self/exe")) and exec the fd
+        //   with fexecve(), or exec "/proc/self/fd/<n>"
+        // - State (docs/fast-track/22-ps-inspect.md): once the child runs,
+        //   state::save(&ContainerState { id, pid: child.pid(), .. }) with
+        //   id = --name or "run-<pid>", the cgroup's name; a taken --name
+        //   fails before spawn(). state::remove(id) after the wait, on
+        //   every path out, so `contain ps` only shows what runs
+        // - Stopped by `contain stop` (state::stop_reason() is Some): print
+        //   the reason, state::record_exit(id, code) and leave the entry
+        //   and cgroup to stop's teardown(), which reports the code
+        // - Parent: forward_signals(&child) in a thread, then child.wait()
+        // - Remove the cgroup once the child was reaped (rmdir; it is empty
+        //   by then, PID 1 took every process with it)
+        // - std::process::exit() with the init's code: status.code(), which
+        //   is already 128 + signal for a killed command (init::exit_code).
+        //   Only a killed init has no code: 128 + status.signal()
+        //   (ExitStatusExt)
+        //
+        // Expected output:
+        //   $ sudo contain run --rootfs ./rootfs --hostname box -- /bin/sh -c 'hostname; echo $$'
+        //   box
+        //   2
+        let _ = (&self.rootfs, self.memory, self.cpu); // Suppress unused warning
+        let _ = (&self.hostname, &self.name, &self.command); // Suppress unused warning
+        let _ = (&self.seccomp, &self.seccomp_deny); // Suppress unused warning
+        let _ = (&self.cap_drop, &self.cap_add); // Suppress unused warning
+        todo!("Implement contain run - see docs/fast-track/17-run.md")
+    }
+}
+
+/// Parse a memory limit: "100M", "1G", "512K", or a bare number of bytes.
+#[allow(dead_code)]
+pub fn parse_memory(s: &str) -> Result<u64, String> {
+    // TODO: Implement in fast-track lesson 17
+    // Hints:
+    // - The same units as `contain cgroup memory`: K, M and G are powers of
+    //   1024, upper or lower case
+    // - Refuse 0 and anything below 1M: the container would be OOM-killed
+    //   before the command even starts ("memory limit too small: 4K")
+    // - Use checked_mul(): "99999999999G" is an error, not a wrapped value
+    let _ = s; // Suppress unused warning
+    todo!("Implement memory limit parsing")
+}
+
+/// Parse a CPU limit in percent of one CPU: "50%", "150%", or "50".
+#[allow(dead_code)]
+pub fn parse_cpu(s: &str) -> Result<u32, String> {
+    // TODO: Implement in fast-track lesson 17
+    // Hints:
+    // - Strip an optional trailing '%', then parse a u32
+    // - 0 is an error; above 100% means more than one CPU (200% = two)
+    let _ = s; // Suppress unused warning
+    todo!("Implement CPU limit parsing")
+}
+
+/// The cpu.max line for `percent` of one CPU: "50000 100000" for 50%.
+#[allow(dead_code)]
+pub fn cpu_max(percent: u32) -> String {
+    // TODO: Implement in fast-track lesson 17
+    // Hints:
+    // - quota = CPU_PERIOD_US * percent / 100, in the same period
+    // - The kernel wants at least 1000us of quota: 1% is the smallest limit
+    let _ = percent; // Suppress unused warning
+    todo!("Implement cpu.max formatting")
+}
+
+/// Create this run's cgroup and write its limits; returns the directory.
+#[allow(dead_code)]
+pub fn create_cgroup(memory: Option<u64>, cpu: Option<u32>) -> Result<PathBuf> {
+    // TODO: Implement in fast-track lesson 17
+    // Hints:
+    // - CGROUP_ROOT/run-<contain's PID>, after enabling the controllers:
+    //   "+memory +cpu" to /sys/fs/cgroup/cgroup.subtree_control and to
+    //   CGROUP_ROOT/cgroup.subtree_control
+    // - Write memory.max and cpu.max (cpu_max()) BEFORE spawning: limits
+    //   written later would let the first allocations through
+    // - memory.swap.max = 0 too, or the limit only moves memory to swap
+    let _ = (memory, cpu); // Suppress unused warning
+    todo!("Implement run cgroup creation")
+}
+
+/// Pass the signals contain run gets on to the container's init.
+#[allow(dead_code)]
+pub fn forward_signals(child: &ns_tool::spawn::Child) -> Result<()> {
+    // TODO: Implement in fast-track lesson 17
+    // Hints:
+    // - Block init::FORWARDED_SIGNALS in the parent before spawn(), and
+    //   unblock them in the child before the exec
+    // - SigSet::wait() in a loop, then child.signal(sig): the pidfd never
+    //   reaches another process, even once the child is gone
+    // - Ctrl+C in a terminal sends SIGINT to the whole foreground group,
+    //   the container included: init then gets it twice. Forward SIGINT
+    //   only when it did not come from the terminal, or put the child in
+    //   its own process group
+    let _ = child; // Suppress unused warning
+    todo!("Implement signal forwarding")
+}
@@ -0,0 +1,302 @@
+// Security report for a running container (fast-track lesson 15)
+// `contain security <id>` audits what the kernel says about the container's
+// init process and scores it out of 100, with a hint per weak spot.
+//
+// Everything comes from the host's /proc, so the audit needs no help from
+// inside the container and cannot be fooled by it:
+//
+//   /proc/<pid>/status      CapEff, Seccomp, NoNewPrivs, Uid
+//   /proc/<pid>/uid_map     user namespace mapping
+//   /proc/<pid>/mountinfo   the container's mounts, as it sees them
+//   /proc/<pid>/            owner: root when the process is not dumpable
+//
+// The target is a name from the state store (lesson 12) or a host PID, so
+// containers started by runc or Docker can be audited too:
+//   contain security web
+//   contain security $(docker inspect -f '{{.State.Pid}}' web)
+
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct SecurityArgs {
+    /// Container name (from `ns container --name`) or host PID of its init
+    pub id: String,
+
+    /// Exit non-zero when the score is below this (for CI)
+    #[arg(long, value_name = "SCORE")]
+    pub min_score: Option<u32>,
+}
+
+/// Outcome of one check.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Full weight
+    Pass,
+    /// Half the weight: weaker than a default Docker container
+    Warn,
+    /// No weight: an escape or host-wide read is one step away
+    Fail,
+}
+
+/// One line of the report.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// Check name, one of CHECKS
+    pub check: &'static str,
+    pub status: Status,
+    /// What was found, e.g. "2 (filter)"
+    pub detail: String,
+    /// How to fix it; None for Pass
+    pub hint: Option<String>,
+}
+
+/// Checks in report order, with their weight out of 100.
+#[allow(dead_code)]
+pub const CHECKS: &[(&str, u32)] = &[
+    ("capabilities", 25),
+    ("seccomp", 20),
+    ("user namespace", 20),
+    ("no_new_privs", 10),
+    ("writable mounts", 10),
+    ("/proc masking", 10),
+    ("dumpable", 5),
+];
+
+/// Capabilities that are enough on their own to escape or to read the
+/// whole host (numbers from linux/capability.h).
+#[allow(dead_code)]
+pub const DANGEROUS_CAPS: &[(u32, &str)] = &[
+    (21, "CAP_SYS_ADMIN"),
+    (19, "CAP_SYS_PTRACE"),
+    (16, "CAP_SYS_MODULE"),
+    (17, "CAP_SYS_RAWIO"),
+    (2, "CAP_DAC_READ_SEARCH"),
+    (39, "CAP_BPF"),
+    (38, "CAP_PERFMON"),
+    (12, "CAP_NET_ADMIN"),
+];
+
+/// Docker's default capability set: anything beyond it is a Warn.
+#[allow(dead_code)]
+pub const DOCKER_DEFAULT_CAPS: &[(u32, &str)] = &[
+    (0, "CAP_CHOWN"),
+    (1, "CAP_DAC_OVERRIDE"),
+    (3, "CAP_FOWNER"),
+    (4, "CAP_FSETID"),
+    (5, "CAP_KILL"),
+    (6, "CAP_SETGID"),
+    (7, "CAP_SETUID"),
+    (8, "CAP_SETPCAP"),
+    (10, "CAP_NET_BIND_SERVICE"),
+    (13, "CAP_NET_RAW"),
+    (18, "CAP_SYS_CHROOT"),
+    (27, "CAP_MKNOD"),
+    (29, "CAP_AUDIT_WRITE"),
+    (31, "CAP_SETFCAP"),
+];
+
+/// Paths the OCI runtime spec's defaults hide behind /dev/null or an empty
+/// read-only tmpfs (runc's "maskedPaths"); they leak host kernel state.
+#[allow(dead_code)]
+pub const MASKED_PATHS: &[&str] = &[
+    "/proc/kcore",
+    "/proc/keys",
+    "/proc/latency_stats",
+    "/proc/timer_list",
+    "/proc/sched_debug",
+    "/proc/acpi",
+    "/proc/scsi",
+    "/sys/firmware",
+];
+
+/// Paths the defaults remount read-only ("readonlyPaths"): writing to them
+/// changes the host kernel, not the container.
+#[allow(dead_code)]
+pub const READONLY_PATHS: &[&str] = &[
+    "/proc/sys",
+    "/proc/sysrq-trigger",
+    "/proc/irq",
+    "/proc/bus",
+    "/proc/fs",
+];
+
+impl SecurityArgs {
+    pub fn run(&self) -> Result<()> {
+        // TODO: Audit a running container
+        // Lesson: docs/fast-track/15-security-report.md
+        // Tests: tests/security_test.rs
+        //
+        // Implementation hints:
+        // - resolve_pid(&self.id), then collect(pid)
+        // - print!("{}", render_report(&self.id, pid, &findings))
+        // - With --min-score, bail!("score {score} is below {min}") after
+        //   printing, so CI logs still show why
+        let _ = (&self.id, self.min_score); // Suppress unused warning
+        todo!("Implement security report - see docs/fast-track/15-security-report.md")
+    }
+}
+
+/// Host PID of the container to audit.
+#[allow(dead_code)]
+pub fn resolve_pid(id: &str) -> Result<i32> {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - All digits: a host PID; check that /proc/<pid> exists
+    // - Otherwise a name: crate::state::lookup(id)
+    let _ = id; // Suppress unused warning
+    todo!("Implement container lookup")
+}
+
+/// Run every check against host PID `pid`, in CHECKS order.
+#[allow(dead_code)]
+pub fn collect(pid: i32) -> Result<Vec<Finding>> {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - Read /proc/<pid>/status, uid_map and mountinfo once each and pass
+    //   the text to the check functions below: they stay pure and testable
+    // - The process may exit between reads; a vanished /proc/<pid> is an
+    //   error ("container exited during the audit"), not a Fail
+    let _ = pid; // Suppress unused warning
+    todo!("Implement check collection")
+}
+
+/// Capabilities: Fail with any DANGEROUS_CAPS, Warn beyond
+/// DOCKER_DEFAULT_CAPS, Pass otherwise.
+#[allow(dead_code)]
+pub fn check_capabilities(status: &str) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - "CapEff:\t000001ffffffffff": u64::from_str_radix(hex, 16)
+    // - Detail: the count and the dangerous names,
+    //   e.g. "41 held, incl. CAP_SYS_ADMIN, CAP_SYS_PTRACE"
+    // - Inside a user namespace (see check_user_ns) the caps only apply to
+    //   resources that namespace owns; still report them, as Docker does
+    // - Hint: drop them before exec (capset + PR_CAPBSET_DROP, as
+    //   tracing::drop_to_tracing_caps does), or `--cap-drop ALL` in Docker
+    //   and in `contain run` (docs/fast-track/21-capabilities.md)
+    let _ = status; // Suppress unused warning
+    todo!("Implement capability check")
+}
+
+/// Seccomp: Pass for mode 2 (filter), Fail for 0 (disabled).
+#[allow(dead_code)]
+pub fn check_seccomp(status: &str) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - "Seccomp:\t2": 0 = disabled, 1 = strict, 2 = filter
+    // - "Seccomp_filters:\t1" (5.9+) says how many filters are stacked
+    // - A missing line means a kernel without CONFIG_SECCOMP: Fail too
+    // - Hint: docs/03-runc/05-seccomp.md, or Docker's default profile;
+    //   `contain run --seccomp` (docs/fast-track/20-seccomp.md)
+    let _ = status; // Suppress unused warning
+    todo!("Implement seccomp check")
+}
+
+/// no_new_privs: Pass when set, so setuid binaries and file caps cannot
+/// raise privileges after exec.
+#[allow(dead_code)]
+pub fn check_no_new_privs(status: &str) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - "NoNewPrivs:\t1"
+    // - Not set is a Warn, not a Fail: with no setuid binaries in the
+    //   rootfs there is nothing to gain
+    // - Hint: prctl(PR_SET_NO_NEW_PRIVS, 1) before exec; OCI
+    //   "noNewPrivileges": true; Docker --security-opt no-new-privileges
+    let _ = status; // Suppress unused warning
+    todo!("Implement no_new_privs check")
+}
+
+/// User namespace: Fail when container root is host root.
+#[allow(dead_code)]
+pub fn check_user_ns(uid_map: &str) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - uid_map lines are "<inside> <outside> <count>"
+    // - "0 0 4294967295" is the initial namespace: no user namespace, so
+    //   root in the container is root on the host -> Fail
+    // - Inside 0 mapped to outside 0 in a smaller range: still host root -> Fail
+    // - Inside 0 mapped elsewhere (e.g. "0 100000 65536") -> Pass, detail
+    //   "root is host uid 100000"
+    // - Hint: docs/01-namespaces/11-idmapped-mounts.md, or rootless Docker/Podman
+    let _ = uid_map; // Suppress unused warning
+    todo!("Implement user namespace check")
+}
+
+/// Writable mounts: Warn for each world-writable mount without the sticky
+/// bit, Fail for host paths mounted read-write.
+#[allow(dead_code)]
+pub fn check_writable_mounts(mountinfo: &str, pid: i32) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - mountinfo fields (proc(5)): 4 = mount point, 5 = per-mount options
+    //   ("rw,nosuid,..."); the optional fields end with a single "-"
+    // - Stat each rw mount point through /proc/<pid>/root/<mount point>,
+    //   which resolves it in the container's mount namespace
+    // - mode & 0o002 without 0o1000 (sticky): anyone can replace anyone's
+    //   files; /tmp with 1777 is fine
+    // - Fail: a rw mount whose root (field 3) is "/" of a host filesystem,
+    //   or /proc/sys, /sys or /var/run/docker.sock mounted rw
+    let _ = (mountinfo, pid); // Suppress unused warning
+    todo!("Implement writable mount check")
+}
+
+/// /proc masking: Pass when every MASKED_PATHS entry is masked and every
+/// READONLY_PATHS entry is read-only.
+#[allow(dead_code)]
+pub fn check_proc_masking(mountinfo: &str) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - Masked: a mount point equal to the path (a /dev/null bind for
+    //   files, a ro tmpfs for directories); paths that do not exist on this
+    //   kernel count as masked
+    // - Read-only: a mount at or above the path with "ro" in its options
+    // - Detail: the unmasked paths, e.g. "/proc/kcore, /proc/sys rw"
+    // - The mini-container (lesson 04) masks nothing: expect a Fail
+    let _ = mountinfo; // Suppress unused warning
+    todo!("Implement /proc masking check")
+}
+
+/// Dumpable: Pass when the process is not dumpable, so other processes of
+/// the same user cannot ptrace it or read its /proc files.
+#[allow(dead_code)]
+pub fn check_dumpable(pid: i32, status: &str) -> Finding {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - PR_GET_DUMPABLE only works on the calling process; from outside,
+    //   proc(5) says /proc/<pid>/* is owned by root when the process is
+    //   not dumpable, and by its effective uid when it is
+    // - Compare the owner of /proc/<pid>/environ with the effective uid
+    //   (second field of the "Uid:" line of `status`)
+    // - Effective uid 0 cannot be told apart: Warn with "runs as root"
+    // - Hint: prctl(PR_SET_DUMPABLE, 0) in the init before exec
+    let _ = (pid, status); // Suppress unused warning
+    todo!("Implement dumpable check")
+}
+
+/// Score out of 100: full weight for Pass, half for Warn, none for Fail.
+#[allow(dead_code)]
+pub fn score(findings: &[Finding]) -> u32 {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - Look up each finding's weight in CHECKS by name
+    // - Round half weights down, so 4 Warns never add up to a Pass
+    let _ = findings; // Suppress unused warning
+    todo!("Implement scoring")
+}
+
+/// The printed report.
+#[allow(dead_code)]
+pub fn render_report(id: &str, pid: i32, findings: &[Finding]) -> String {
+    // TODO: Implement in fast-track lesson 15
+    // Hints:
+    // - Header "Security report for <id> (PID <pid>)"
+    // - One line per finding: {check:<16} {PASS|WARN|FAIL:<5} {detail},
+    //   then "    -> {hint}" for the hint
+    // - Last line "Score: <n>/100"
+    let _ = (id, pid, findings); // Suppress unused warning
+    todo!("Implement report rendering")
+}
@@ -0,0 +1,194 @@
+// Container state store for the contain CLI
+// Records which PID runs each named container, so later commands can find it
+// (fast-track lesson 12).
+//
+// Layout: one directory per container under STATE_DIR, holding a `pid`
+// file with the container's init PID as seen from the host, a `stop` file
+// once `contain stop` asked it to exit and an `exit` file with the status
+// it exited with (lesson 16), and `state.json` with everything `contain ps`
+// and `contain inspect` show (lesson 22):
+//
+//   /run/contain/web/pid         -> "4242"
+//   /run/contain/web/stop        -> "SIGTERM"
+//   /run/contain/web/exit        -> "143"
+//   /run/contain/web/state.json  -> {"id": "web", "pid": 4242, ...}
+//
+// /run is a tmpfs, so the store is empty after a reboot, just like the
+// containers it describes.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::stop::ShutdownReason;
+
+/// Root of the state store
+#[allow(dead_code)]
+pub const STATE_DIR: &str = "/run/contain";
+
+/// What `contain run` records about a container in STATE_DIR/<id>/state.json.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerState {
+    pub id: String,
+    /// Host PID of the container's init
+    pub pid: i32,
+    pub rootfs: PathBuf,
+    pub command: Vec<String>,
+    pub hostname: String,
+    /// Seconds since the Unix epoch
+    pub created: u64,
+    /// None without --memory/--cpu
+    pub cgroup: Option<PathBuf>,
+    /// Host end of a veth pair into the container (`net veth`), if any
+    #[serde(default)]
+    pub veth: Option<String>,
+}
+
+/// Whether a recorded container's init still runs.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Running,
+    /// The entry is left over: the container exited without cleaning up
+    Stopped,
+}
+
+/// Record that container `id` is running as host PID `pid`.
+#[allow(dead_code)]
+pub fn record(id: &str, pid: i32) -> Result<()> {
+    // TODO: Implement in fast-track lesson 12
+    // Hints:
+    // - Reject ids that are empty or contain '/' (they become path components)
+    // - Fail if STATE_DIR/<id>/pid exists and that PID is still alive:
+    //   two containers must not share a name
+    // - create_dir_all(STATE_DIR/<id>), then write the PID as text
+    let _ = (id, pid); // Suppress unused warning
+    todo!("Implement state recording")
+}
+
+/// Look up the host PID of a running container.
+#[allow(dead_code)]
+pub fn lookup(id: &str) -> Result<i32> {
+    // TODO: Implement in fast-track lesson 12
+    // Hints:
+    // - Read and parse STATE_DIR/<id>/pid
+    // - Missing file: bail!("no such container: {id}")
+    // - Check that /proc/<pid> still exists; a stale entry means the
+    //   container exited without cleaning up: bail!("container {id} is not running")
+    let _ = id; // Suppress unused warning
+    todo!("Implement state lookup")
+}
+
+/// Write the state.json of a container recorded with record().
+#[allow(dead_code)]
+pub fn save(state: &ContainerState) -> Result<()> {
+    // TODO: Implement in fast-track lesson 22
+    // Hints:
+    // - record(&state.id, state.pid) first: the same id checks, and the pid
+    //   file stays what lookup() reads
+    // - serde_json::to_string_pretty(state), written to state.json.tmp and
+    //   rename()d, so `contain ps` never reads half a file
+    let _ = state; // Suppress unused warning
+    todo!("Implement state saving")
+}
+
+/// The state.json of container `id`, and whether it still runs.
+#[allow(dead_code)]
+pub fn load(id: &str) -> Result<(ContainerState, Status)> {
+    // TODO: Implement in fast-track lesson 22
+    // Hints:
+    // - Missing state.json but a pid file: a `ns container --name`
+    //   container, which only records its PID. bail!("no state for
+    //   container {id}") or build a minimal state from the pid file
+    // - Running if /proc/<pid> exists and started before `created` was
+    //   written: field 22 of /proc/<pid>/stat, in clock ticks after boot
+    //   (btime in /proc/stat). A PID reused since then is Stopped
+    let _ = id; // Suppress unused warning
+    todo!("Implement state loading")
+}
+
+/// Every container with a state.json, sorted by creation time.
+#[allow(dead_code)]
+pub fn list() -> Result<Vec<(ContainerState, Status)>> {
+    // TODO: Implement in fast-track lesson 22
+    // Hints:
+    // - read_dir(STATE_DIR); a missing STATE_DIR is an empty list, not an
+    //   error: nothing ever ran
+    // - load() each entry; skip (with a warning on stderr) one that fails
+    //   to parse rather than failing the whole listing
+    todo!("Implement state listing")
+}
+
+/// Remove a container's entry (called when its init process exits).
+#[allow(dead_code)]
+pub fn remove(id: &str) -> Result<()> {
+    // TODO: Implement in fast-track lesson 12
+    // Hints:
+    // - remove_dir_all(STATE_DIR/<id>); a missing directory is not an error
+    // - This removes state.json too: `contain ps` only lists what runs, or
+    //   what exited without reaching remove()
+    let _ = id; // Suppress unused warning
+    todo!("Implement state removal")
+}
+
+/// Record why container `id` is being stopped (fast-track lesson 16).
+#[allow(dead_code)]
+pub fn record_stop(id: &str, reason: &ShutdownReason) -> Result<()> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Write reason.to_string() to STATE_DIR/<id>/stop, replacing an
+    //   earlier reason: SIGKILL after the timeout overwrites "SIGTERM"
+    // - Write to stop.tmp and rename() it, so the reaper never reads half
+    let _ = (id, reason); // Suppress unused warning
+    todo!("Implement stop reason recording")
+}
+
+/// Why container `id` stopped: None if nobody ran `contain stop`, i.e. the
+/// command exited by itself.
+#[allow(dead_code)]
+pub fn stop_reason(id: &str) -> Result<Option<String>> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Read STATE_DIR/<id>/stop; NotFound is Ok(None)
+    // - Called by the `ns container` parent after waitpid() and before
+    //   remove(), which deletes the file with the rest of the entry
+    let _ = id; // Suppress unused warning
+    todo!("Implement stop reason lookup")
+}
+
+/// Record the exit code of a stopped container's init, for `contain stop`
+/// to report (fast-track lesson 16).
+#[allow(dead_code)]
+pub fn record_exit(id: &str, code: i32) -> Result<()> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Written by the parent that reaped the init (`run`, `ns container`)
+    //   and only when stop_reason() is Some: then `contain stop` is waiting
+    //   for it, and removes the entry itself once it has read the code
+    // - exit.tmp + rename(), as in record_stop()
+    let _ = (id, code); // Suppress unused warning
+    todo!("Implement exit code recording")
+}
+
+/// The exit code record_exit() wrote, None while nobody has reaped the init.
+#[allow(dead_code)]
+pub fn exit_code(id: &str) -> Result<Option<i32>> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Read and parse STATE_DIR/<id>/exit; NotFound is Ok(None)
+    let _ = id; // Suppress unused warning
+    todo!("Implement exit code lookup")
+}
+
+/// Path of one of a running container's namespaces, e.g. kind = "net".
+#[allow(dead_code)]
+pub fn ns_path(id: &str, kind: &str) -> Result<PathBuf> {
+    // TODO: Implement in fast-track lesson 12
+    // Hints:
+    // - Ok(PathBuf::from(format!("/proc/{}/ns/{kind}", lookup(id)?)))
+    // - Open the returned path once and keep the File: setns() takes the fd,
+    //   and the open fd keeps the namespace alive even if the PID exits
+    let _ = (id, kind); // Suppress unused warning
+    todo!("Implement namespace path lookup")
+}
@@ -0,0 +1,207 @@
+// Graceful shutdown for the contain CLI (fast-track lesson 16)
+// `contain stop <id>` does what `docker stop` and `runc kill` + `runc delete`
+// do, instead of a raw `kill`:
+//
+//   1. Send the stop signal (SIGTERM) to the container's init
+//   2. Wait up to --timeout for it to exit
+//   3. Still running: SIGKILL every process in its cgroup at once
+//   4. Tear down what the container leaves on the host (teardown()) and
+//      report the init's exit status
+//
+// The reason is written to the state store BEFORE each step, so the
+// `ns container` parent that reaps the init can say why it stopped:
+//
+//   /run/contain/web/stop    -> "SIGTERM"
+//                            -> "SIGKILL after 10s (SIGTERM ignored)"
+//
+// The init from lesson 13 forwards SIGTERM to the command. Without it
+// (--no-init), a PID 1 that installed no handler ignores SIGTERM and every
+// stop ends in step 3.
+
+use anyhow::Result;
+use clap::Args;
+use nix::sys::signal::Signal;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Args)]
+pub struct StopArgs {
+    /// Container name (from `ns container --name`, or an ID from `contain ps`)
+    pub id: String,
+
+    /// Signal sent to the container's init first (SIGTERM, TERM or 15)
+    #[arg(long, default_value = "SIGTERM", value_parser = parse_signal)]
+    pub signal: Signal,
+
+    /// How long to wait for the init to exit before SIGKILL (10s, 500ms, 1m)
+    #[arg(long, default_value = "10s", value_parser = parse_timeout)]
+    pub timeout: Duration,
+}
+
+/// Why a container stopped, as recorded in the state store.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// Exited after the stop signal, within the timeout
+    Signal(Signal),
+    /// Still running after the timeout: the whole cgroup got SIGKILL
+    Killed { signal: Signal, timeout: Duration },
+}
+
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShutdownReason::Signal(signal) => write!(f, "{signal}"),
+            ShutdownReason::Killed { signal, timeout } => {
+                write!(f, "SIGKILL after {timeout:?} ({signal} ignored)")
+            }
+        }
+    }
+}
+
+impl StopArgs {
+    pub fn run(&self) -> Result<()> {
+        // TODO: Stop a running container gracefully
+        // Lesson: docs/fast-track/16-stop.md
+        // Tests: tests/stop_test.rs
+        //
+        // Implementation hints:
+        // - state::lookup(&self.id), then open a pidfd for the PID right
+        //   away (open_pidfd): from here on, a recycled PID cannot be hit
+        // - state::record_stop(id, &ShutdownReason::Signal(self.signal)),
+        //   THEN send_signal(), so the reaper never sees an exit without
+        //   a reason
+        // - wait_exit(pidfd.as_fd(), self.timeout): true means done
+        // - Otherwise record ShutdownReason::Killed first, then kill_cgroup(pid)
+        //   and wait_exit() again (a short timeout: SIGKILL cannot be ignored)
+        // - Exit status: the parent that reaped the init writes it with
+        //   state::record_exit() because a reason was recorded.
+        //   wait_reaped(id, 1s); None means there is no parent any more
+        //   (it was killed): the status is lost, say "exit status unknown"
+        // - teardown(id) in every case, after the exit: the parent left the
+        //   entry for us, or there is no parent to remove it
+        // - Print "<id>: stopped (<reason>), exit status <code>" and exit 0;
+        //   a container that was not running is an error from lookup()
+        //
+        // Expected output:
+        //   web: stopped (SIGTERM), exit status 143
+        //   web: stopped (SIGKILL after 10s (SIGTERM ignored)), exit status 137
+        let _ = (&self.id, self.signal, self.timeout); // Suppress unused warning
+        todo!("Implement graceful stop - see docs/fast-track/16-stop.md")
+    }
+}
+
+/// Wait up to `timeout` for the parent of container `id` to record the
+/// init's exit code; None if nobody did.
+#[allow(dead_code)]
+pub fn wait_reaped(id: &str, timeout: Duration) -> Result<Option<i32>> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Poll state::exit_code(id) every 10ms until Some or the deadline
+    // - The pidfd said the init exited; its parent's waitpid() returns at
+    //   the same moment, so anything beyond a few ms means no parent
+    let _ = (id, timeout); // Suppress unused warning
+    todo!("Implement exit code wait")
+}
+
+/// Remove what a stopped container leaves on the host: its veth, its
+/// cgroup and its state entry. Each step runs even if an earlier one
+/// failed.
+#[allow(dead_code)]
+pub fn teardown(id: &str) -> Result<()> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Read state.json first (state::load); a `ns container --name`
+    //   container has none and only gets step 3
+    // - 1. veth: `ip link del <veth>` if it still exists. Usually it is
+    //   gone already: its peer was in the container's network namespace,
+    //   which died with the last process, and a veth pair goes as a pair
+    // - 2. cgroup: rmdir(); EBUSY means processes are left (kill_cgroup()
+    //   once more, then retry), ENOENT that the parent removed it
+    // - 3. state::remove(id)
+    // - Collect the errors and report them all at the end: a failed rmdir
+    //   must not leave the state entry behind as well
+    let _ = id; // Suppress unused warning
+    todo!("Implement container teardown")
+}
+
+/// Parse a signal name or number: "SIGTERM", "term", "15".
+#[allow(dead_code)]
+pub fn parse_signal(s: &str) -> Result<Signal, String> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - All digits: Signal::try_from(n)
+    // - Otherwise uppercase it, add the "SIG" prefix if missing, and use
+    //   Signal::from_str (std::str::FromStr)
+    // - Refuse SIGKILL and SIGSTOP: the signal is the polite step, and
+    //   SIGKILL is what the timeout is for
+    // - The String error is shown by clap: "unknown signal: TREM"
+    let _ = s; // Suppress unused warning
+    todo!("Implement signal parsing")
+}
+
+/// Parse a timeout: "10s", "500ms", "1m", or a bare number of seconds.
+#[allow(dead_code)]
+pub fn parse_timeout(s: &str) -> Result<Duration, String> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - Split at the first non-digit: the number, then "", "ms", "s" or "m"
+    // - "0" is allowed and means "SIGKILL right after the signal", like
+    //   `docker stop -t 0`
+    let _ = s; // Suppress unused warning
+    todo!("Implement timeout parsing")
+}
+
+/// A pidfd for host PID `pid`, which refers to that process even after
+/// the PID is reused.
+#[allow(dead_code)]
+pub fn open_pidfd(pid: i32) -> Result<std::os::fd::OwnedFd> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - nix::libc::syscall(nix::libc::SYS_pidfd_open, pid, 0) (Linux 5.3+)
+    // - The container is not our child, so waitpid() cannot wait for it;
+    //   the pidfd can: it becomes readable when the process exits
+    // - A negative return is Errno::last(); ESRCH means it already exited
+    let _ = pid; // Suppress unused warning
+    todo!("Implement pidfd open")
+}
+
+/// Send `signal` to the process behind `pidfd`.
+#[allow(dead_code)]
+pub fn send_signal(pidfd: std::os::fd::BorrowedFd, signal: Signal) -> Result<()> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - nix::libc::syscall(SYS_pidfd_send_signal, fd, signal as i32, null, 0)
+    // - Unlike kill(pid), this cannot reach a new process that got the PID
+    let _ = (pidfd, signal); // Suppress unused warning
+    todo!("Implement pidfd signal")
+}
+
+/// Wait until the process behind `pidfd` exits; false on timeout.
+#[allow(dead_code)]
+pub fn wait_exit(pidfd: std::os::fd::BorrowedFd, timeout: Duration) -> Result<bool> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - nix::poll::poll() with PollFlags::POLLIN and the timeout in ms
+    // - poll() returns 1 when the process exited, 0 on timeout
+    // - Retry on EINTR with the time that is left
+    let _ = (pidfd, timeout); // Suppress unused warning
+    todo!("Implement exit wait")
+}
+
+/// SIGKILL every process in the cgroup of host PID `pid`.
+#[allow(dead_code)]
+pub fn kill_cgroup(pid: i32) -> Result<()> {
+    // TODO: Implement in fast-track lesson 16
+    // Hints:
+    // - /proc/<pid>/cgroup: the "0::<path>" line, under /sys/fs/cgroup
+    // - Never kill the root cgroup or one that holds contain itself
+    //   (compare with /proc/self/cgroup): a container started without its
+    //   own cgroup shares the caller's
+    // - cgroup v2 (5.14+): write "1" to cgroup.kill; the kernel kills every
+    //   process, including ones forked while it runs
+    // - Otherwise: SIGKILL the init. PID 1 of a PID namespace takes every
+    //   process of the namespace with it
+    let _ = pid; // Suppress unused warning
+    todo!("Implement cgroup kill")
+}
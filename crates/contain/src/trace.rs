@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use clap::Subcommand;
+use std::path::PathBuf;
 
 #[derive(Subcommand)]
 pub enum TraceCommand {
@@ -13,14 +14,34 @@ pub enum TraceCommand {
     /// Trace system calls in a container using eBPF
     /// Lesson: docs/fast-track/10-ebpf-tracing.md
     Syscalls {
-        /// Process ID to trace (optional, traces all if not specified)
-        #[arg(long)]
+        /// Host PID of a container's init: traces every process in its PID
+        /// namespace (optional, traces all if not specified)
+        #[arg(long, conflicts_with = "name")]
         pid: Option<u32>,
+
+        /// Container name (from `ns container --name`), instead of --pid
+        #[arg(long)]
+        name: Option<String>,
     },
 
-    /// Trace container events (clone, execve, exit)
+    /// Trace the fork, exec and exit of every process in a container
     /// Lesson: docs/fast-track/10-ebpf-tracing.md
-    Events,
+    Events {
+        /// Container name (from `ns container --name`) or host PID of its init
+        id: String,
+    },
+
+    /// From inside a `--with-tracing proxy` container: run ebpf-tool on the host
+    /// Lesson: docs/fast-track/14-container-tracing.md
+    Remote {
+        /// Proxy socket, as bind-mounted into the container
+        #[arg(long, default_value = crate::tracing::CONTAINER_SOCKET)]
+        socket: PathBuf,
+
+        /// ebpf-tool arguments, e.g. `-- trace -s openat -d 5`
+        #[arg(last = true, required = true)]
+        args: Vec<String>,
+    },
 }
 
 impl TraceCommand {
@@ -35,30 +56,79 @@ impl TraceCommand {
                 // - Check /sys/fs/bpf exists
                 // - Check kernel version supports eBPF
                 // - Check CAP_BPF or root privileges
+                // - Or all of the above and more, from the ebpf-tool library:
+                //   ebpf_tool::run(ebpf_tool::Cli::parse_from(["ebpf-tool", "check"]))
                 todo!("Implement eBPF check - see docs/fast-track/10-ebpf-tracing.md")
             }
-            TraceCommand::Syscalls { pid } => {
-                // TODO: Attach eBPF program to trace syscalls
+            TraceCommand::Syscalls { pid, name } => {
+                // TODO: Trace the syscalls of a container with the ebpf-tool library
                 // Lesson: docs/fast-track/10-ebpf-tracing.md
                 // Tests: tests/trace_test.rs
                 //
                 // Implementation hints:
-                // - Load eBPF program for syscall tracing
-                // - Filter by PID if specified
-                // - Print syscall name and arguments
-                let _ = pid; // Suppress unused warning
+                // - Use the ebpf-tool library instead of loading programs here:
+                //   ebpf_tool::Tracer::builder().duration(Duration::ZERO)
+                //   (until Ctrl+C)
+                // - --name: crate::state::lookup(name); --pid: check that
+                //   /proc/<pid> exists. Neither: no filter, the whole host
+                // - Filter on the container, not the PID: .pidns(pidns_inum(pid)?)
+                //   keeps every process in its PID namespace, including the
+                //   ones init starts after the trace began. `pid == N` would
+                //   show init alone
+                // - .run() prints one line per syscall, as `ebpf-tool trace` does,
+                //   with the host's PIDs
+                let _ = (pid, name); // Suppress unused warning
                 todo!("Implement syscall tracing - see docs/fast-track/10-ebpf-tracing.md")
             }
-            TraceCommand::Events => {
+            TraceCommand::Events { id } => {
                 // TODO: Trace container lifecycle events
                 // Lesson: docs/fast-track/10-ebpf-tracing.md
                 // Tests: tests/trace_test.rs
                 //
                 // Implementation hints:
-                // - Attach to clone, execve, exit tracepoints
-                // - Show container process creation and termination
+                // - crate::security::resolve_pid(id): a name or a host PID
+                // - The container is its PID namespace: pidns_inum(pid)?
+                // - ebpf_tool::Tracer::builder().syscalls(false)
+                //   .proc_events(true).pidns(inum).duration(Duration::ZERO)
+                //   .run(): the kernel drops the events of every other
+                //   namespace, so the host's processes never show up
+                // - Print nothing yourself: one line per fork, exec and exit,
+                //   until Ctrl+C. Init already runs, so the first lines are
+                //   whatever it starts next
+                let _ = id; // Suppress unused warning
                 todo!("Implement event tracing - see docs/fast-track/10-ebpf-tracing.md")
             }
+            TraceCommand::Remote { socket, args } => {
+                // TODO: Send a request to the host's tracing proxy
+                // Lesson: docs/fast-track/14-container-tracing.md
+                // Tests: tests/tracing_test.rs
+                //
+                // Implementation hints:
+                // - UnixStream::connect(socket); a missing socket means the
+                //   container was not started with `--with-tracing proxy`: say so
+                // - Write args joined by NUL bytes, then shutdown(Shutdown::Write)
+                //   (the format tracing::read_request() expects)
+                // - std::io::copy() the stream to stdout until EOF
+                // - A reply starting with "error: " is a refused request: print it
+                //   to stderr and exit non-zero
+                let _ = (socket, args); // Suppress unused warning
+                todo!("Implement proxy client - see docs/fast-track/14-container-tracing.md")
+            }
         }
     }
 }
+
+/// Inode of the PID namespace of host PID `pid`: what the ebpf-tool tracer
+/// compares with each task's namespace (`Tracer::builder().pidns()`).
+#[allow(dead_code)]
+pub fn pidns_inum(pid: i32) -> Result<u32> {
+    // TODO: Implement in fast-track lesson 10
+    // Hints:
+    // - std::fs::metadata(format!("/proc/{pid}/ns/pid"))?.ino() as u32
+    //   (std::os::unix::fs::MetadataExt): the number in
+    //   `readlink /proc/<pid>/ns/pid` ("pid:[4026532451]")
+    // - Not ns/pid_for_children: that is the namespace of its next child
+    // - A vanished /proc/<pid>: bail!("process {pid} exited")
+    let _ = pid; // Suppress unused warning
+    todo!("Implement PID namespace lookup")
+}
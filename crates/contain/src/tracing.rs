@@ -0,0 +1,206 @@
+// In-container tracing for `ns container --with-tracing` (fast-track lesson 14)
+//
+// Two ways to let a container observe itself with ebpf-tool:
+//
+//   direct  ebpf-tool runs INSIDE the container. Its binary is bind-mounted
+//           in, bpffs and tracefs are mounted, and the command keeps only
+//           the capabilities eBPF tracing needs. It sees the whole host:
+//           kernel tracing has no notion of containers.
+//
+//   proxy   ebpf-tool runs on the HOST. The container gets a Unix socket;
+//           `contain trace remote` sends a request through it, and the host
+//           runs it scoped to the container's cgroup and streams the output
+//           back. The container needs no capabilities at all.
+//
+//   container                          host
+//   contain trace remote -- trace ...  ──/run/ebpf-tool.sock──►  proxy thread
+//                                                                ebpf-tool trace ... --cgroup <ctr>
+//
+// The mini-container still sees the host's files, so the bind mount mostly
+// gives ebpf-tool a fixed path. It matters once a container has its own root
+// (docs/03-runc): a dynamically linked binary would not find its libc there,
+// which is why direct mode insists on a static one.
+
+use anyhow::Result;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+/// How `--with-tracing` exposes ebpf-tool to the container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TracingMode {
+    /// Run ebpf-tool inside the container with CAP_BPF and CAP_PERFMON
+    Direct,
+    /// Forward requests to ebpf-tool on the host, scoped to the container
+    Proxy,
+}
+
+/// Where direct mode bind-mounts the host's ebpf-tool.
+#[allow(dead_code)]
+pub const CONTAINER_EBPF_TOOL: &str = "/usr/local/bin/ebpf-tool";
+
+/// Where proxy mode bind-mounts the request socket.
+#[allow(dead_code)]
+pub const CONTAINER_SOCKET: &str = "/run/ebpf-tool.sock";
+
+/// Host directory of the proxy sockets, one per container: <pid>.sock
+#[allow(dead_code)]
+pub const PROXY_DIR: &str = "/run/contain/tracing";
+
+/// Parent cgroup of the containers started in proxy mode, so each request
+/// can be scoped with `--cgroup`.
+#[allow(dead_code)]
+pub const PROXY_CGROUP_ROOT: &str = "/sys/fs/cgroup/contain";
+
+/// Capabilities a direct-mode container keeps (numbers from
+/// linux/capability.h; CAP_BPF and CAP_PERFMON need kernel 5.8+).
+///
+/// - CAP_BPF (39): create maps, load programs
+/// - CAP_PERFMON (38): attach kprobes, tracepoints and perf events
+/// - CAP_SYS_RESOURCE (24): raise RLIMIT_MEMLOCK, which maps are charged
+///   against before kernel 5.11
+#[allow(dead_code)]
+pub const TRACING_CAPS: &[(u32, &str)] = &[
+    (39, "CAP_BPF"),
+    (38, "CAP_PERFMON"),
+    (24, "CAP_SYS_RESOURCE"),
+];
+
+/// `trace` flags a proxy request may not use: they would reach outside the
+/// container's scope, or write on the host.
+#[allow(dead_code)]
+pub const PROXY_DENIED_FLAGS: &[&str] = &[
+    "--cgroup",
+    "--pin",
+    "--detach",
+    "--record",
+    "--otlp-endpoint",
+];
+
+/// Find the host's ebpf-tool for direct mode.
+#[allow(dead_code)]
+pub fn find_ebpf_tool(explicit: Option<&Path>) -> Result<PathBuf> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - An explicit --ebpf-tool path wins; it must exist
+    // - Otherwise look next to contain itself (std::env::current_exe()):
+    //   `cargo build` puts both in target/debug
+    // - Then every directory in $PATH
+    // - Not found: bail and suggest
+    //   `cargo build -p ebpf-tool --target x86_64-unknown-linux-musl`
+    let _ = explicit; // Suppress unused warning
+    todo!("Implement ebpf-tool lookup")
+}
+
+/// Refuse a dynamically linked binary for direct mode.
+#[allow(dead_code)]
+pub fn check_static(binary: &Path) -> Result<()> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - A dynamically linked ELF has a PT_INTERP program header (the path of
+    //   ld.so); a static one has none
+    // - 64-bit ELF header: e_phoff is the u64 at offset 32, e_phentsize the
+    //   u16 at 54, e_phnum the u16 at 56 (little-endian on x86_64/aarch64)
+    // - Each program header starts with p_type (u32); PT_INTERP is 3
+    // - Or shell out: `file <binary>` says "statically linked"
+    // - Bail with the musl build command from find_ebpf_tool()
+    let _ = binary; // Suppress unused warning
+    todo!("Implement static binary check")
+}
+
+/// Direct mode, in the container after unshare(CLONE_NEWNS) and before
+/// exec: mount what ebpf-tool needs.
+#[allow(dead_code)]
+pub fn setup_direct(binary: &Path) -> Result<()> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - The mount namespace must be private first (MS_REC | MS_PRIVATE on
+    //   "/"), or these mounts propagate back to the host
+    // - Bind-mount `binary` onto CONTAINER_EBPF_TOOL: create an empty file
+    //   there first, mount(binary, target, None, MS_BIND), then remount
+    //   with MS_BIND | MS_REMOUNT | MS_RDONLY (a plain MS_BIND ignores
+    //   MS_RDONLY)
+    // - mount("bpf", "/sys/fs/bpf", "bpf", 0): a new bpffs instance, so pins
+    //   made inside do not show up in the host's /sys/fs/bpf
+    // - mount("tracefs", "/sys/kernel/tracing", "tracefs", 0): tracepoint
+    //   ids and kprobe_events live there
+    let _ = binary; // Suppress unused warning
+    todo!("Implement direct-mode mounts")
+}
+
+/// Direct mode, last thing before exec: keep only TRACING_CAPS.
+#[allow(dead_code)]
+pub fn drop_to_tracing_caps() -> Result<()> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - capabilities::apply(CapSet::from_list(TRACING_CAPS), last_cap) does
+    //   all of the steps below; or write them here with libc (nix has no
+    //   capset())
+    // - Bounding set: prctl(PR_CAPBSET_DROP, cap) for every cap from 0 to
+    //   the last one (/proc/sys/kernel/cap_last_cap) not in TRACING_CAPS
+    // - Effective/permitted/inheritable: libc::syscall(SYS_capset) with
+    //   _LINUX_CAPABILITY_VERSION_3 and two u32 words per set
+    // - Ambient: prctl(PR_CAP_AMBIENT, PR_CAP_AMBIENT_RAISE, cap) for each
+    //   kept cap, so they survive the exec of a non-root-owned binary
+    // - Verify with `grep Cap /proc/self/status` and `capsh --decode=...`
+    todo!("Implement capability drop")
+}
+
+/// Proxy mode, on the host before fork(): the socket the container will
+/// connect to. Created first, so it exists when the child bind-mounts it.
+#[allow(dead_code)]
+pub fn proxy_listen(pid_hint: u32) -> Result<(UnixListener, PathBuf)> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - create_dir_all(PROXY_DIR), then UnixListener::bind(PROXY_DIR/<pid_hint>.sock)
+    //   (contain's own PID: the container's is not known before fork())
+    // - Remove a stale socket file first; bind() fails with EADDRINUSE on it
+    // - Mode 0600 is fine: the container runs as root
+    let _ = pid_hint; // Suppress unused warning
+    todo!("Implement proxy socket")
+}
+
+/// Turn a proxy request into ebpf-tool arguments, scoped to `cgroup`.
+#[allow(dead_code)]
+pub fn proxy_args(request: &[String], cgroup: &Path) -> Result<Vec<String>> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - The first argument must be "trace"; anything else (stats, unpin,
+    //   lsm, ...) is refused: it is not scoped to a cgroup
+    // - Refuse every PROXY_DENIED_FLAGS entry, also in its `--flag=value`
+    //   form: the request comes from inside the container and is untrusted
+    // - Append ["--cgroup", cgroup]
+    // - Example: ["trace", "-s", "openat", "-d", "5"] ->
+    //   ["trace", "-s", "openat", "-d", "5", "--cgroup", "/sys/fs/cgroup/contain/4242"]
+    let _ = (request, cgroup); // Suppress unused warning
+    todo!("Implement proxy request validation")
+}
+
+/// Proxy mode, on the host: answer requests until the container exits.
+#[allow(dead_code)]
+pub fn serve_proxy(listener: UnixListener, ebpf_tool: &Path, cgroup: &Path) -> Result<()> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - Run this in a thread started by the `ns container` parent; it ends
+    //   with the process after waitpid() returns
+    // - Per connection: read_request(), proxy_args(), then spawn ebpf_tool
+    //   with stdout and stderr set to clones of the stream
+    //   (Stdio::from(OwnedFd::from(stream.try_clone()?)))
+    // - A refused request: write "error: ...\n" to the stream and close it
+    // - One request at a time is fine; a second client waits
+    let _ = (listener, ebpf_tool, cgroup); // Suppress unused warning
+    todo!("Implement tracing proxy")
+}
+
+/// Read one request: arguments separated by NUL, ended by the client's
+/// shutdown(Write).
+#[allow(dead_code)]
+pub fn read_request(stream: &mut UnixStream) -> Result<Vec<String>> {
+    // TODO: Implement in fast-track lesson 14
+    // Hints:
+    // - read_to_end() into a Vec with a size cap (e.g. 64 KiB, via
+    //   Read::take): the peer is untrusted
+    // - split(|b| *b == 0), String::from_utf8 each part
+    // - `contain trace remote` writes the same format
+    let _ = stream; // Suppress unused warning
+    todo!("Implement proxy request parsing")
+}
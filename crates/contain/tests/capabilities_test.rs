@@ -0,0 +1,55 @@
+// Tests for `contain run --cap-drop` and `--cap-add`
+// Lesson: docs/fast-track/21-capabilities.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/capabilities.rs and src/run.rs (GREEN)
+//
+// The root tests need the busybox rootfs of tests/run_test.rs.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_cap_flags_reject_unknown_names() {
+    // TODO: Test that capability names are checked by clap
+    //
+    // Steps:
+    // 1. `contain run --rootfs /tmp --cap-drop NOT_A_CAP` fails, stderr
+    //    contains "unknown capability"
+    // 2. `--cap-add 21` fails too: names only
+    //
+    // Hints:
+    // - No root needed: the parser runs before anything else
+
+    todo!("Implement test - see docs/fast-track/21-capabilities.md")
+}
+
+#[test]
+fn test_cap_drop_all_empties_sets() {
+    // TODO: Test that --cap-drop ALL leaves root without capabilities
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. `contain run --rootfs <dir> --cap-drop ALL -- /bin/sh -c
+    //    'grep -E "^Cap(Eff|Bnd)|NoNewPrivs" /proc/self/status'`
+    // 3. Assert CapEff and CapBnd are 0000000000000000, NoNewPrivs is 1
+    // 4. `... --cap-drop ALL -- /bin/sh -c 'hostname x'` fails: setting
+    //    the hostname needs CAP_SYS_ADMIN
+
+    todo!("Implement test - see docs/fast-track/21-capabilities.md")
+}
+
+#[test]
+fn test_cap_add_after_drop() {
+    // TODO: Test that --cap-add applies after --cap-drop
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. `--cap-drop ALL --cap-add NET_BIND_SERVICE`: CapEff is
+    //    0000000000000400 (bit 10 only)
+    // 3. No flags: CapEff matches security::DOCKER_DEFAULT_CAPS
+    //    (00000000a80425fb)
+
+    todo!("Implement test - see docs/fast-track/21-capabilities.md")
+}
@@ -0,0 +1,59 @@
+// Tests for `contain exec <id> -- cmd`
+// Lesson: docs/fast-track/23-exec.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/exec.rs (GREEN)
+//
+// The root tests start a container with `contain run --name` in the
+// background, as in tests/ps_test.rs, and stop it at the end.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_exec_unknown_container_fails() {
+    // TODO: Test that exec refuses a container that is not in the store
+    //
+    // Steps:
+    // 1. `contain exec no-such-container -- /bin/true` fails, stderr
+    //    contains "no such container"
+    // 2. `contain exec web` without a command is rejected by clap
+
+    todo!("Implement test - see docs/fast-track/23-exec.md")
+}
+
+#[test]
+fn test_exec_joins_namespaces() {
+    // TODO: Test that the command runs in the container's namespaces
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. Start `contain run --rootfs <dir> --name exec-test --hostname box
+    //    -- /bin/sleep 30` and wait until `contain ps -q` lists it
+    // 3. `contain exec exec-test -- /bin/sh -c 'hostname; echo $$; ls /'`
+    //    prints "box", a PID greater than 2 (init is 1, sleep is 2), and
+    //    the rootfs's files, not the host's
+    // 4. `contain exec exec-test -- readlink /proc/self/ns/pid` prints the
+    //    same target as fs::read_link("/proc/<init>/ns/pid") on the host
+    //    (<init> is "pid" from `contain inspect exec-test`)
+    // 5. `contain stop exec-test`
+
+    todo!("Implement test - see docs/fast-track/23-exec.md")
+}
+
+#[test]
+fn test_exec_exit_code_and_cgroup() {
+    // TODO: Test that exec reports the command's exit code and puts it in
+    // the container's cgroup
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. Start `contain run ... --name exec-cg --memory 50M -- /bin/sleep 30`
+    // 3. `contain exec exec-cg -- /bin/sh -c 'exit 4'` exits with code 4
+    // 4. `contain exec exec-cg -- cat /proc/self/cgroup` prints the same
+    //    line as /proc/<init>/cgroup read from the host
+    // 5. `contain stop exec-cg`
+
+    todo!("Implement test - see docs/fast-track/23-exec.md")
+}
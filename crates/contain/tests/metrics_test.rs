@@ -0,0 +1,44 @@
+// Tests for the `metrics` subcommand (Prometheus endpoint)
+// Lesson: docs/fast-track/11-metrics.md
+//
+// TDD Workflow:
+// 1. Write the test below FIRST (RED)
+// 2. Implement code in src/metrics.rs (GREEN)
+
+use assert_cmd::Command;
+
+#[test]
+fn test_metrics_help_shows_listen() {
+    // TODO: Test that `contain metrics --help` documents --listen
+    //
+    // Steps:
+    // 1. Run `contain metrics --help`
+    // 2. Assert success
+    // 3. Check stdout contains "--listen" and "127.0.0.1:9300"
+    //
+    // Hints:
+    // - Use Command::cargo_bin("contain")
+    // - Does not need root
+
+    todo!("Implement test - see docs/fast-track/11-metrics.md")
+}
+
+#[test]
+fn test_metrics_endpoint_reports_container() {
+    // TODO: Test that a managed container shows up in /metrics
+    //
+    // Steps:
+    // 1. Skip if not root (requires creating cgroups)
+    // 2. Create /sys/fs/cgroup/contain/test-metrics with `contain cgroup create`
+    // 3. Spawn `contain metrics --listen 127.0.0.1:19300` with
+    //    std::process::Command (it runs until killed)
+    // 4. Connect with std::net::TcpStream, send "GET /metrics HTTP/1.1\r\n\r\n"
+    // 5. Assert the body contains contain_memory_bytes{container="test-metrics"}
+    // 6. Kill the server and delete the cgroup
+    //
+    // Hints:
+    // - Retry the connect for ~2s while the server starts
+    // - Use a non-default port so a running instance doesn't interfere
+
+    todo!("Implement test - see docs/fast-track/11-metrics.md")
+}
@@ -17,7 +17,8 @@ fn test_container_isolation() {
     // Steps:
     // 1. Skip if not root (requires CAP_SYS_ADMIN)
     // 2. Read /proc/self/ns/pid to get parent namespace
-    // 3. Run `contain ns container -- /bin/sh -c 'echo PID:$$ && hostname'`
+    // 3. Run `contain ns container --no-init -- /bin/sh -c 'echo PID:$$ && hostname'`
+    //    (--no-init: the shell itself is PID 1; see tests/init_test.rs)
     // 4. Assert success and output contains "PID:1" and "container"
     // 5. Verify parent namespace unchanged
     //
@@ -0,0 +1,68 @@
+// Tests for the state store commands (`contain ps`, `contain inspect`)
+// Lesson: docs/fast-track/22-ps-inspect.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/state.rs, src/ps.rs, src/inspect.rs and
+//    src/run.rs (GREEN)
+//
+// The root tests need the busybox rootfs of tests/run_test.rs.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_inspect_unknown_container_fails() {
+    // TODO: Test that inspect names the container it could not find
+    //
+    // Steps:
+    // 1. Run `contain inspect no-such-container`
+    // 2. Assert failure and stderr contains "no such container"
+    //
+    // Hints:
+    // - No root needed: reading a missing /run/contain entry fails first
+
+    todo!("Implement test - see docs/fast-track/22-ps-inspect.md")
+}
+
+#[test]
+fn test_ps_lists_running_container() {
+    // TODO: Test that a running container shows up in ps and inspect, and
+    // is gone after it exits
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. Start `contain run --rootfs <dir> --name ps-test -- /bin/sleep 30`
+    //    with std::process::Command::spawn() (it blocks until the end)
+    // 3. Poll `contain ps -q` until it lists "ps-test" (give up after 5s)
+    // 4. `contain inspect ps-test`: parse stdout with serde_json, assert
+    //    "status" is "running", "rootfs" is <dir>, "command" is
+    //    ["/bin/sleep", "30"], and "namespaces" has a "pid" entry
+    // 5. `contain stop ps-test --timeout 1s`, wait for the run process,
+    //    then `contain ps -q` no longer lists it
+    //
+    // Hints:
+    // - serde_json is a dependency of contain, add it to [dev-dependencies]
+    //   to use it here
+
+    todo!("Implement test - see docs/fast-track/22-ps-inspect.md")
+}
+
+#[test]
+fn test_ps_prune_removes_stale_entries() {
+    // TODO: Test that --all shows an entry left by a killed `contain run`
+    // and --prune removes it
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. Start `contain run --name ps-stale -- /bin/sleep 30`, wait until
+    //    ps lists it, then SIGKILL the contain run process first and the
+    //    container's init ("pid" from inspect) second: nobody is left to
+    //    remove the entry
+    // 3. `contain ps` no longer lists ps-stale; `contain ps --all` shows it
+    //    as "stopped"
+    // 4. `contain ps --prune` prints "ps-stale", and `--all` no longer
+    //    lists it
+
+    todo!("Implement test - see docs/fast-track/22-ps-inspect.md")
+}
@@ -0,0 +1,98 @@
+// Tests for `ns container --rootfs` and `rootfs overlay` (src/rootfs.rs)
+// Lessons: docs/fast-track/17-run.md, docs/fast-track/19-overlay.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/rootfs.rs and src/ns.rs (GREEN)
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_rootfs_missing_directory_fails() {
+    // TODO: Test that a rootfs that does not exist fails before any
+    // namespace is created
+    //
+    // Steps:
+    // 1. Run `contain ns container --rootfs /no/such/dir -- /bin/sh`
+    // 2. Assert failure and stderr contains "/no/such/dir"
+    //
+    // Hints:
+    // - rootfs::validate() runs first, so this needs no root
+
+    todo!("Implement test - see docs/fast-track/17-run.md")
+}
+
+#[test]
+fn test_rootfs_is_the_new_root() {
+    // TODO: Test that the container sees the rootfs as / and has its own
+    // /proc and /dev
+    //
+    // Steps:
+    // 1. Skip if not root
+    // 2. Build a busybox rootfs in a temp dir (see 09-runc-run.md); skip
+    //    if /bin/busybox is missing
+    // 3. Put a marker file in it: rootfs/marker
+    // 4. Run `contain ns container --rootfs <dir> -- /bin/sh -c
+    //    'ls /; ls /proc | grep -c "^[0-9]"; echo x > /dev/null && echo ok'`
+    // 5. Assert success; stdout lists "marker" but not the host's "home",
+    //    and ends with "ok"
+    //
+    // Hints:
+    // - predicate::str::contains("marker"), .not() for "home"
+    // - Only the container's processes are in /proc: init and the shell
+
+    todo!("Implement test - see docs/fast-track/17-run.md")
+}
+
+#[test]
+fn test_overlay_requires_lower() {
+    // TODO: Test that `rootfs overlay` refuses to run without an image
+    //
+    // Steps:
+    // 1. Run `contain rootfs overlay --upper /tmp/x -- /bin/sh`
+    // 2. Assert failure and stderr mentions "--lower"
+    //
+    // Hints:
+    // - clap rejects it, no root needed
+
+    todo!("Implement test - see docs/fast-track/19-overlay.md")
+}
+
+#[test]
+fn test_overlay_writes_land_in_upper() {
+    // TODO: Test that the image stays untouched and the changes land in
+    // the upper dir
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. Create an empty upper dir in a temp dir
+    // 3. Run `contain rootfs overlay --lower <rootfs> --upper <upper> --
+    //    /bin/sh -c 'echo hi > /hello; rm /bin/ls'`
+    // 4. Assert success; <upper>/hello contains "hi"; <rootfs>/hello does
+    //    not exist and <rootfs>/bin/ls still does
+    // 5. <upper>/bin/ls is a whiteout: a character device 0:0
+    //    (FileTypeExt::is_char_device, MetadataExt::rdev() == 0)
+    //
+    // Hints:
+    // - overlayfs cannot use an overlay as its upper layer: skip if the
+    //   temp dir is on one (statfs f_type OVERLAYFS_SUPER_MAGIC)
+
+    todo!("Implement test - see docs/fast-track/19-overlay.md")
+}
+
+#[test]
+fn test_overlay_cleanup_on_exit() {
+    // TODO: Test that nothing is left mounted after the container exits,
+    // even when the command fails
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. Run `contain rootfs overlay ... -- /bin/sh -c 'exit 7'`, assert
+    //    exit code 7
+    // 3. /proc/self/mountinfo has no overlay mount whose source mentions
+    //    the upper dir, and the temp dir holds only upper/ (no merged or
+    //    work dirs)
+
+    todo!("Implement test - see docs/fast-track/19-overlay.md")
+}
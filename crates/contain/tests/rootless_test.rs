@@ -0,0 +1,66 @@
+// Tests for rootless containers (`ns container` and `run` without sudo)
+// Lesson: docs/fast-track/18-rootless.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/rootless.rs, src/ns.rs and src/run.rs (GREEN)
+//
+// These tests are the opposite of the others: they need a NON-root user.
+// Under sudo, skip them, or run them as the invoking user with
+// `sudo -u "$SUDO_USER" -E cargo test -p contain --test rootless_test`.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_rootless_container_is_root_inside() {
+    // TODO: Test that `ns container` works without root
+    //
+    // Steps:
+    // 1. Skip if root, or if /proc/sys/user/max_user_namespaces is 0
+    // 2. `contain ns container -- /bin/sh -c 'id -u; echo $$; hostname'`
+    // 3. Assert success, stdout "0" (root inside), "2" (PID 2 under the
+    //    init) and "container" (the default hostname)
+    //
+    // Hints:
+    // - Outside, the container runs as your uid: check
+    //   /proc/<pid>/status from the host while it runs if you want to see it
+
+    todo!("Implement test - see docs/fast-track/18-rootless.md")
+}
+
+#[test]
+fn test_rootless_maps_subordinate_ids() {
+    // TODO: Test that /etc/subuid ranges end up in the uid_map
+    //
+    // Steps:
+    // 1. Skip if root, if the user has no /etc/subuid entry, or if
+    //    newuidmap is not installed
+    // 2. `contain ns container -- /bin/cat /proc/self/uid_map`
+    // 3. Assert two lines: "0 <your uid> 1" and "1 <subuid start> <count>"
+    //    (columns are padded: split on whitespace)
+    //
+    // Hints:
+    // - Without the range the map has only the first line, and
+    //   `chown 1000 file` inside fails with EINVAL
+
+    todo!("Implement test - see docs/fast-track/18-rootless.md")
+}
+
+#[test]
+fn test_rootless_refusal_prints_report() {
+    // TODO: Test that a rootless start that cannot work explains why
+    //
+    // Steps:
+    // 1. Skip if root
+    // 2. `contain ns container --name web -- true`: --name needs the state
+    //    store under /run, so it fails with a message that names --name
+    // 3. If user namespaces are off on this machine (max_user_namespaces
+    //    0, or unprivileged_userns_clone 0), `contain ns container -- true`
+    //    fails and stderr has "Rootless mode:" and "user namespaces"
+    //
+    // Hints:
+    // - predicate::str::contains for each line; the report order is fixed
+
+    todo!("Implement test - see docs/fast-track/18-rootless.md")
+}
@@ -0,0 +1,86 @@
+// Tests for the mini-container runtime (`contain run`)
+// Lesson: docs/fast-track/17-run.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/run.rs (GREEN)
+//
+// The root tests need a rootfs with a static busybox, as in
+// docs/fast-track/09-runc-run.md: build it once in a temp dir (bin/busybox
+// plus symlinks for sh, hostname, cat, ls and sleep) and skip the test when
+// no busybox is installed.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_run_rejects_bad_arguments() {
+    // TODO: Test that the arguments are checked before anything is created
+    //
+    // Steps:
+    // 1. `contain run -- /bin/sh` fails: --rootfs is required
+    // 2. `contain run --rootfs /tmp --memory 10X` fails, stderr mentions
+    //    the memory limit; `--memory 4K` fails as too small
+    // 3. `contain run --rootfs /tmp --cpu 0%` fails
+    // 4. `contain run --rootfs /no/such/dir` fails with the path in stderr
+    //
+    // Hints:
+    // - Use Command::cargo_bin("contain") and predicate::str::contains
+    // - Does not need root: clap and the rootfs check fail first
+
+    todo!("Implement test - see docs/fast-track/17-run.md")
+}
+
+#[test]
+fn test_run_isolation() {
+    // TODO: Test that the command runs in its own root, PID and UTS namespace
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. `contain run --rootfs <dir> --hostname box -- /bin/sh -c
+    //    'hostname; echo $$; ls /; ls /dev'`
+    // 3. Assert stdout starts with "box", then "2" (PID 1 is the init)
+    // 4. Assert `ls /` shows the rootfs (bin, dev, proc) and not the host's
+    //    home or target directories
+    // 5. Assert /dev has null and zero, and no disks (sda, nvme0n1, vda)
+    //
+    // Hints:
+    // - `cat /proc/net/dev` lists only lo: the network namespace is new
+
+    todo!("Implement test - see docs/fast-track/17-run.md")
+}
+
+#[test]
+fn test_run_exit_code_propagates() {
+    // TODO: Test that contain run exits the way its command did
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. `-- /bin/sh -c 'exit 3'` exits with code 3
+    // 3. `-- /bin/sh -c 'kill -TERM $$'` exits with 143 (128 + SIGTERM)
+    // 4. Spawn `-- /bin/sleep 30` with std::process::Command, send SIGTERM
+    //    to contain run itself after 500ms: it exits with 143 within a
+    //    second, because the signal was forwarded to the container
+
+    todo!("Implement test - see docs/fast-track/17-run.md")
+}
+
+#[test]
+fn test_run_memory_limit() {
+    // TODO: Test that --memory is applied from the first instruction
+    //
+    // Steps:
+    // 1. Skip if not root (cgroup writes), or if no busybox rootfs could be built
+    // 2. Spawn `contain run --rootfs <dir> --memory 20M --cpu 50% --
+    //    /bin/sleep 2` with std::process::Command. The container has no
+    //    /sys, so read the limits on the host, from
+    //    /sys/fs/cgroup/contain/run-<child.id()>/: memory.max is
+    //    "20971520", cpu.max "50000 100000"
+    // 3. After it exits, the run-<pid> cgroup is gone
+    //
+    // Hints:
+    // - Skip when /sys/fs/cgroup/cgroup.controllers has no "memory"
+    //   (the DevContainer case)
+
+    todo!("Implement test - see docs/fast-track/17-run.md")
+}
@@ -0,0 +1,59 @@
+// Tests for `contain run --seccomp` and `--seccomp-deny`
+// Lesson: docs/fast-track/20-seccomp.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/seccomp.rs and src/run.rs (GREEN)
+//
+// The root tests need the busybox rootfs of tests/run_test.rs.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_seccomp_bad_profile_fails_early() {
+    // TODO: Test that a broken profile is refused before the container starts
+    //
+    // Steps:
+    // 1. `contain run --rootfs /tmp --seccomp /no/such.json` fails, stderr
+    //    contains "/no/such.json"
+    // 2. A temp file with `{"defaultAction": "SCMP_ACT_NOTIFY"}` fails with
+    //    the action name in stderr
+    // 3. `--seccomp-deny not_a_syscall` fails with the name in stderr
+    // 4. `--seccomp x.json --seccomp-deny mount` is rejected by clap
+    //
+    // Hints:
+    // - All of these fail in the parent: no root needed
+
+    todo!("Implement test - see docs/fast-track/20-seccomp.md")
+}
+
+#[test]
+fn test_seccomp_starter_profile_loads() {
+    // TODO: Test that the starter profile shipped with contain is valid
+    //
+    // Steps:
+    // 1. Path: concat!(env!("CARGO_MANIFEST_DIR"), "/profiles/seccomp-starter.json")
+    // 2. Skip if not root, or if no busybox rootfs could be built
+    // 3. `contain run --rootfs <dir> --seccomp <path> -- /bin/sh -c
+    //    'echo ok; ls / >/dev/null'` succeeds and prints "ok"
+
+    todo!("Implement test - see docs/fast-track/20-seccomp.md")
+}
+
+#[test]
+fn test_seccomp_deny_list_blocks_syscall() {
+    // TODO: Test that a denied syscall fails with EPERM inside the container
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built
+    // 2. `contain run --rootfs <dir> -- /bin/sh -c 'mount -t tmpfs t /mnt && echo mounted'`
+    //    prints "mounted" (root in the container may mount)
+    // 3. The same with `--seccomp-deny mount` fails, and stderr contains
+    //    "Operation not permitted"
+    //
+    // Hints:
+    // - /proc/self/status in the container has "Seccomp:\t2"
+
+    todo!("Implement test - see docs/fast-track/20-seccomp.md")
+}
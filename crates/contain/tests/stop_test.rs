@@ -0,0 +1,114 @@
+// Tests for graceful shutdown (`contain stop <id>`)
+// Lesson: docs/fast-track/16-stop.md
+//
+// TDD Workflow:
+// 1. Write the tests below FIRST (RED)
+// 2. Implement code in src/stop.rs, src/state.rs and src/ns.rs (GREEN)
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_stop_rejects_bad_arguments() {
+    // TODO: Test that --signal and --timeout are checked before any lookup
+    //
+    // Steps:
+    // 1. `contain stop web --signal TREM` fails, stderr mentions "unknown signal"
+    // 2. `contain stop web --signal KILL` fails: SIGKILL is what the
+    //    timeout escalates to, not the polite step
+    // 3. `contain stop web --timeout soon` fails
+    // 4. `contain stop no-such-container --signal term --timeout 500ms`
+    //    fails with "no such container": the arguments parsed
+    //
+    // Hints:
+    // - Use Command::cargo_bin("contain") and predicate::str::contains
+    // - Does not need root: clap and the state store lookup fail first
+
+    todo!("Implement test - see docs/fast-track/16-stop.md")
+}
+
+#[test]
+fn test_stop_with_signal() {
+    // TODO: Test that a container handling SIGTERM stops without SIGKILL
+    //
+    // Steps:
+    // 1. Skip if not root (requires CAP_SYS_ADMIN)
+    // 2. Spawn `contain ns container --name test-stop -- /bin/sh -c
+    //    'trap "exit 0" TERM; while true; do sleep 0.1; done'` with
+    //    std::process::Command, wait for /run/contain/test-stop/pid
+    // 3. `contain stop test-stop` succeeds within a second, stdout contains
+    //    "test-stop: stopped (SIGTERM)"
+    // 4. The `ns container` process exits 0 (the trap's status), its stderr
+    //    also says "stopped (SIGTERM)", and /run/contain/test-stop is gone
+    //
+    // Hints:
+    // - Time step 3 with std::time::Instant: well under the 10s default
+    // - Stdio::piped() on stderr of the spawned container to read its message
+
+    todo!("Implement test - see docs/fast-track/16-stop.md")
+}
+
+#[test]
+fn test_stop_escalates_after_timeout() {
+    // TODO: Test that an ignored stop signal ends in SIGKILL
+    //
+    // Steps:
+    // 1. Skip if not root
+    // 2. Spawn `contain ns container --name test-stop -- /bin/sh -c
+    //    'trap "" TERM; while true; do sleep 0.1; done'`: SIGTERM is ignored
+    // 3. `contain stop test-stop --timeout 1s` succeeds after ~1s, stdout
+    //    contains "SIGKILL after 1s (SIGTERM ignored)"
+    // 4. The container exits with 137 (128 + SIGKILL)
+    //
+    // Hints:
+    // - The init forwards SIGTERM to the shell, which ignores it; SIGKILL
+    //   cannot be caught by either
+    // - Assert the elapsed time is >= 1s and < 3s
+
+    todo!("Implement test - see docs/fast-track/16-stop.md")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_stop_kills_the_whole_cgroup() {
+    // TODO: Test that escalation leaves no process of the container behind
+    //
+    // Steps:
+    // 1. Skip if not root or without cgroup v2 (/sys/fs/cgroup/cgroup.controllers)
+    // 2. Start a container in its own cgroup (ns container spawned into
+    //    /sys/fs/cgroup/test-stop) running `sh -c 'trap "" TERM;
+    //    setsid sleep 300 & wait'`: the sleep is in a new session but still
+    //    in the cgroup
+    // 3. `contain stop test-stop --timeout 0`
+    // 4. cgroup.procs of /sys/fs/cgroup/test-stop is empty, then rmdir it
+    //
+    // Hints:
+    // - `--timeout 0` goes straight to SIGKILL after the signal
+    // - cgroup.kill needs Linux 5.14; older kernels fall back to killing
+    //   the init, which also works here because the sleep shares its PID
+    //   namespace
+
+    todo!("Implement test - see docs/fast-track/16-stop.md")
+}
+
+#[test]
+fn test_stop_reports_exit_status_and_tears_down() {
+    // TODO: Test that stop reports the init's exit code and removes what
+    // the container left behind
+    //
+    // Steps:
+    // 1. Skip if not root, or if no busybox rootfs could be built (see
+    //    tests/run_test.rs)
+    // 2. Start `contain run --rootfs <dir> --name test-teardown --memory 50M
+    //    -- /bin/sh -c 'trap "exit 5" TERM; while true; do sleep 0.1; done'`
+    //    and wait until `contain ps -q` lists it
+    // 3. `contain stop test-teardown --timeout 5` prints "exit status 5"
+    // 4. /run/contain/test-teardown and the cgroup directory from
+    //    `contain inspect` (read before the stop) are gone
+    //
+    // Hints:
+    // - A second case: SIGKILL the `contain run` process before the stop.
+    //   Stop then prints "exit status unknown" and still removes the entry
+
+    todo!("Implement test - see docs/fast-track/16-stop.md")
+}
@@ -0,0 +1,149 @@
+//! Build script for ebpf-tool-common
+//!
+//! Generates the syscall number table for the target architecture into
+//! `$OUT_DIR/syscall_table.rs`, included by `src/lib.rs` as `SYSCALLS`.
+//!
+//! # Where the Numbers Come From
+//!
+//! 1. `EBPF_TOOL_SYSCALL_HEADER`, if set: a `unistd` header with one
+//!    `#define __NR_<name> <number>` per syscall
+//! 2. The installed kernel headers (`asm/unistd_64.h`), when building for
+//!    the machine itself. Only x86_64 has a header with plain numbers;
+//!    aarch64 uses `asm-generic/unistd.h`, which needs the preprocessor
+//! 3. The vendored copy in `syscalls/<arch>.tbl`
+//!
+//! A header wins over the vendored copy, and the vendored copy adds the
+//! syscalls an older header does not know yet. Other architectures (and the
+//! BPF target itself) get an empty table: names then show as `syscall_<nr>`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const HEADER_ENV: &str = "EBPF_TOOL_SYSCALL_HEADER";
+
+/// Installed headers with plain `#define __NR_x N` lines, per architecture.
+fn installed_headers(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "x86_64" => &[
+            "/usr/include/x86_64-linux-gnu/asm/unistd_64.h",
+            "/usr/include/asm/unistd_64.h",
+        ],
+        _ => &[],
+    }
+}
+
+fn main() {
+    let manifest_dir =
+        PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=syscalls");
+    println!("cargo:rerun-if-env-changed={}", HEADER_ENV);
+
+    let mut table = BTreeMap::new();
+    let mut sources = Vec::new();
+
+    if let Some(header) = find_header(&arch) {
+        println!("cargo:rerun-if-changed={}", header.display());
+        match fs::read_to_string(&header) {
+            Ok(text) => {
+                table = parse_header(&text);
+                sources.push(header.display().to_string());
+            }
+            Err(e) => println!("cargo:warning=cannot read {}: {}", header.display(), e),
+        }
+    }
+
+    let vendored = manifest_dir.join("syscalls").join(format!("{}.tbl", arch));
+    if let Ok(text) = fs::read_to_string(&vendored) {
+        let before = table.len();
+        for (nr, name) in parse_table(&text) {
+            table.entry(nr).or_insert(name);
+        }
+        if table.len() > before {
+            sources.push(format!("syscalls/{}.tbl", arch));
+        }
+    }
+
+    let source = if sources.is_empty() {
+        "none".to_string()
+    } else {
+        sources.join(" + ")
+    };
+    fs::write(
+        out_dir.join("syscall_table.rs"),
+        render(&arch, &source, &table),
+    )
+    .expect("cannot write syscall_table.rs");
+}
+
+/// The header to read for `arch`, if any. Installed headers describe the
+/// build machine, so they are only used when not cross-compiling.
+fn find_header(arch: &str) -> Option<PathBuf> {
+    if let Ok(path) = env::var(HEADER_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    if env::var("HOST").ok() != env::var("TARGET").ok() {
+        return None;
+    }
+    installed_headers(arch)
+        .iter()
+        .map(Path::new)
+        .find(|p| p.is_file())
+        .map(Path::to_path_buf)
+}
+
+/// `#define __NR_read 0` lines; anything else (aliases, `__NR_syscalls`
+/// in some headers) is skipped.
+fn parse_header(text: &str) -> BTreeMap<u64, String> {
+    let mut table = BTreeMap::new();
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("#define") {
+            continue;
+        }
+        let (Some(name), Some(nr)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let Some(name) = name.strip_prefix("__NR_") else {
+            continue;
+        };
+        if name == "syscalls" {
+            continue;
+        }
+        if let Ok(nr) = nr.parse() {
+            table.insert(nr, name.to_string());
+        }
+    }
+    table
+}
+
+/// `NUMBER NAME` lines, `#` comments.
+fn parse_table(text: &str) -> Vec<(u64, String)> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let nr = words.next()?.parse().ok()?;
+            Some((nr, words.next()?.to_string()))
+        })
+        .collect()
+}
+
+fn render(arch: &str, source: &str, table: &BTreeMap<u64, String>) -> String {
+    let mut out = String::new();
+    writeln!(out, "// Generated by build.rs from: {}", source).unwrap();
+    writeln!(out, "pub const SYSCALL_TABLE_ARCH: &str = {:?};", arch).unwrap();
+    writeln!(out, "pub const SYSCALL_TABLE_SOURCE: &str = {:?};", source).unwrap();
+    writeln!(out, "pub const SYSCALLS: &[(u64, &str)] = &[").unwrap();
+    for (nr, name) in table {
+        writeln!(out, "    ({}, {:?}),", nr, name).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
@@ -9,85 +9,2089 @@
 //! - **No heap**: Use fixed-size types only (`u32`, `u64`, `[u8; N]`)
 //! - **`#[repr(C)]`**: Required for consistent memory layout across boundaries
 //! - **`Copy`**: Events are passed by value through perf buffers
+//! - **`abi`**: Every event carries [`EVENT_ABI`], so userspace can tell an
+//!   event of another build (or no event at all) from a real one
+//!
+//! # Features
+//!
+//! - **`user`** (userspace only): derives `serde::Serialize`/`Deserialize`
+//!   and `bytemuck::Pod` for every struct, so `ebpf-tool` can print events
+//!   as JSON and read them from perf buffer bytes without `unsafe`. The
+//!   eBPF programs build without it
+
+#![no_std]
+
+#[cfg(feature = "user")]
+mod serde_array;
+
+/// Maximum length of process command name (TASK_COMM_LEN in Linux kernel).
+pub const COMM_LEN: usize = 16;
+
+/// Maximum entries in syscall counter maps.
+///
+/// The counting maps are LRU maps: once full, inserting a new key evicts the
+/// least recently used one instead of failing.
+pub const MAX_MAP_ENTRIES: u32 = 10240;
+
+// =============================================================================
+// Event ABI (Lesson 36)
+// =============================================================================
+
+/// High half of [`EVENT_ABI`]: marks bytes as an event of this crate.
+pub const EVENT_MAGIC: u16 = 0xEB7E;
+
+/// Low half of [`EVENT_ABI`]. Bump it whenever an event struct in this file
+/// changes, so a userspace and an eBPF build of different versions refuse
+/// each other's events instead of misreading them.
+pub const EVENT_VERSION: u16 = 2;
+
+/// The `abi` field of every event: `EVENT_MAGIC << 16 | EVENT_VERSION`.
+///
+/// Every event's `new()` sets it, so eBPF programs that start from `new()`
+/// get it for free. Userspace checks it before using an event
+/// (`ebpf-tool`'s `events::EventParser`).
+pub const EVENT_ABI: u32 = ((EVENT_MAGIC as u32) << 16) | EVENT_VERSION as u32;
+
+// =============================================================================
+// Strings (Lesson 37)
+// =============================================================================
+
+/// Bytes kept of a path (`ExecEvent::filename`, open paths), with its NUL.
+///
+/// A power of two, so the eBPF programs can bound a length with
+/// `len & (PATH_LEN - 1)`, which the verifier understands, instead of a
+/// comparison it may lose track of. Paths longer than this are cut and
+/// flagged, never dropped.
+pub const PATH_LEN: usize = 256;
+
+/// Bytes kept of each argv entry, with its NUL. A power of two, like
+/// [`PATH_LEN`].
+pub const ARG_LEN: usize = 64;
+
+/// argv entries kept per exec; `ExecEvent::argc` still counts all of them.
+pub const MAX_ARGS: usize = 8;
+
+/// Bytes kept of a probe's string argument (`ProbeEvent::arg`).
+pub const PROBE_ARG_LEN: usize = 64;
+
+/// String flag: a path did not fit and was cut to `PATH_LEN - 1` bytes.
+pub const STR_PATH_TRUNCATED: u32 = 1 << 0;
+
+/// String flag: at least one argv entry was cut to `ARG_LEN - 1` bytes.
+pub const STR_ARG_TRUNCATED: u32 = 1 << 1;
+
+/// String flag: there were more than `MAX_ARGS` arguments.
+pub const STR_ARGS_DROPPED: u32 = 1 << 2;
+
+/// String flag: a string could not be read at all (a bad or paged-out
+/// pointer); the field is empty.
+pub const STR_READ_FAILED: u32 = 1 << 3;
+
+/// String flag: a lookup name (`DnsEvent::name`) was cut to
+/// `DNS_NAME_LEN - 1` bytes. A valid DNS name always fits.
+pub const STR_NAME_TRUNCATED: u32 = 1 << 4;
+
+/// The bytes of a NUL-padded string field, up to its first NUL.
+///
+/// Every string the eBPF programs write is NUL-terminated within its field
+/// (the `bpf_probe_read_*_str` helpers guarantee it), but a field from a
+/// corrupt record may not be: then the whole field is returned.
+pub fn c_str(field: &[u8]) -> &[u8] {
+    match field.iter().position(|&b| b == 0) {
+        Some(end) => &field[..end],
+        None => field,
+    }
+}
+
+/// Entry `index` of a packed argv field (`ExecEvent::argv`), slot by slot:
+/// entry `i` starts at `i * ARG_LEN`. `None` past `MAX_ARGS`.
+pub fn argv_entry(argv: &[u8; MAX_ARGS * ARG_LEN], index: usize) -> Option<&[u8]> {
+    let start = index.checked_mul(ARG_LEN)?;
+    argv.get(start..start + ARG_LEN).map(c_str)
+}
+
+// =============================================================================
+// Syscall Event (Lessons 02-04, 08)
+// =============================================================================
+
+/// Event generated when a system call is invoked.
+///
+/// Used in kprobe-based syscall tracing. The eBPF program populates this
+/// struct and sends it to userspace via a perf event array.
+///
+/// `ppid` and the namespace inodes come from the kernel struct readers
+/// (Lesson 31); they are 0 when the running kernel has no BTF. They are
+/// read in the kernel because the process may be gone by the time
+/// userspace looks at `/proc`.
+///
+/// `uid` and `gid` come from `bpf_get_current_uid_gid()`: the real ids as
+/// seen from the initial user namespace, so a container's root is its
+/// host uid, not 0 (Lesson 34).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct SyscallEvent {
+    /// Process ID (tgid in kernel terms)
+    pub pid: u32,
+    /// Thread ID (pid in kernel terms)
+    pub tid: u32,
+    /// System call number (architecture-dependent)
+    pub syscall_nr: u64,
+    /// cgroup v2 id of the calling task (from bpf_get_current_cgroup_id)
+    pub cgroup_id: u64,
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// Parent process ID (`real_parent->tgid`)
+    pub ppid: u32,
+    /// PID namespace inode (matches `/proc/<pid>/ns/pid`)
+    pub pidns_inum: u32,
+    /// Mount namespace inode (matches `/proc/<pid>/ns/mnt`)
+    pub mntns_inum: u32,
+    /// Real user ID (low 32 bits of bpf_get_current_uid_gid)
+    pub uid: u32,
+    /// Real group ID (high 32 bits of bpf_get_current_uid_gid)
+    pub gid: u32,
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+}
+
+impl SyscallEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            syscall_nr: 0,
+            cgroup_id: 0,
+            timestamp_ns: 0,
+            comm: [0u8; COMM_LEN],
+            ppid: 0,
+            pidns_inum: 0,
+            mntns_inum: 0,
+            uid: 0,
+            gid: 0,
+            abi: EVENT_ABI,
+        }
+    }
+}
+
+impl Default for SyscallEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Open Arguments (Lesson 02, `kprobe do_sys_openat2`)
+// =============================================================================
+
+/// `OpenEvent::dfd` for a path relative to the current directory.
+pub const AT_FDCWD: i32 = -100;
+
+/// The arguments of one `do_sys_openat2(dfd, filename, how)` call.
+///
+/// Every open path of the kernel goes through this function: open(2),
+/// openat(2) and openat2(2) fill a `struct open_how` and call it, so one
+/// kprobe sees them all with the same three arguments:
+///
+/// - `dfd`: the directory a relative `filename` starts from, or `AT_FDCWD`
+/// - `filename`: a user pointer, copied with `bpf_probe_read_user_str`
+/// - `how`: a kernel pointer to `open_how { flags, mode, resolve }`, read
+///   at offsets from the running kernel's BTF (`KernelOffsets::open_how_*`)
+///
+/// `how_status` is 0 when the `open_how` fields were read, or a negative
+/// errno: -ENOENT when the kernel's BTF has no `struct open_how`. The
+/// fields are 0 then, which is also `O_RDONLY`: userspace prints `?`.
+///
+/// The event is 328 bytes, too big for the BPF stack: build it in a
+/// scratch slot.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct OpenEvent {
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// `open_how.flags`: `O_*` flags, with the `O_LARGEFILE` the kernel adds
+    /// on 64-bit
+    pub open_flags: u64,
+    /// `open_how.mode`: only meaningful with `O_CREAT` or `O_TMPFILE`
+    pub mode: u64,
+    /// `open_how.resolve`: `RESOLVE_*` flags, 0 except from openat2()
+    pub resolve: u64,
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// Thread ID
+    pub tid: u32,
+    /// First argument: a directory fd, or `AT_FDCWD`
+    pub dfd: i32,
+    /// `STR_*` flags for `filename`
+    pub flags: u32,
+    /// 0, or -errno if the `open_how` fields could not be read
+    pub how_status: i32,
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// Second argument: the path as passed, relative to `dfd` unless it
+    /// starts with `/`
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub filename: [u8; PATH_LEN],
+}
+
+impl OpenEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            timestamp_ns: 0,
+            open_flags: 0,
+            mode: 0,
+            resolve: 0,
+            pid: 0,
+            tid: 0,
+            dfd: AT_FDCWD,
+            flags: 0,
+            how_status: 0,
+            abi: EVENT_ABI,
+            comm: [0u8; COMM_LEN],
+            filename: [0u8; PATH_LEN],
+        }
+    }
+}
+
+impl Default for OpenEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Map Key (Lesson 03)
+// =============================================================================
+
+/// Key for the `SYSCALL_COUNTS` LruPerCpuHashMap.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct SyscallKey {
+    /// Process ID (0 for system-wide)
+    pub pid: u32,
+    pub _pad: u32,
+    /// System call number
+    pub syscall_nr: u64,
+}
+
+impl SyscallKey {
+    pub const fn new(pid: u32, syscall_nr: u64) -> Self {
+        Self {
+            pid,
+            _pad: 0,
+            syscall_nr,
+        }
+    }
+}
+
+impl Default for SyscallKey {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+// =============================================================================
+// Map Occupancy (Lesson 03)
+// =============================================================================
+
+/// `MAP_INSERTS` slot counting new keys added to `SYSCALL_COUNTS`.
+///
+/// Keys are never deleted, so inserts minus live entries is the number of
+/// entries the LRU map has evicted.
+pub const INSERTS_SYSCALL_COUNTS: u32 = 0;
+
+/// `MAP_INSERTS` slot counting new keys added to `FAULT_COUNTS`.
+pub const INSERTS_FAULT_COUNTS: u32 = 1;
+
+/// Number of slots in `MAP_INSERTS`.
+pub const INSERTS_SLOTS: u32 = 2;
+
+// =============================================================================
+// Filter Config Keys (Lesson 08)
+// =============================================================================
+
+/// `FILTER_CONFIG` key holding the target cgroup id (0 = all cgroups).
+///
+/// Keys 0 (PID) and 1 (syscall number) are introduced in Lesson 08. The cgroup
+/// id is the inode number of the cgroup v2 directory, which is what
+/// `bpf_get_current_cgroup_id()` returns in the kernel.
+pub const FILTER_KEY_CGROUP: u32 = 2;
+
+/// `FILTER_CONFIG` key: when 1, only PIDs present in `FILTER_PIDS` are traced.
+///
+/// Set by `trace --push-down` from the PIDs a `--filter` expression can
+/// match (Lesson 21).
+pub const FILTER_KEY_PID_SET: u32 = 3;
+
+/// `FILTER_CONFIG` key: when 1, only syscalls present in `FILTER_SYSCALLS`
+/// are traced.
+pub const FILTER_KEY_SYSCALL_SET: u32 = 4;
+
+/// `FILTER_CONFIG` key: when 1, every syscall event sent is also recorded in
+/// `PENDING_SYSCALLS`, so the exit program can report its return value.
+///
+/// Set by `trace --returns` (Lesson 23).
+pub const FILTER_KEY_EXITS: u32 = 5;
+
+/// `FILTER_CONFIG` key holding the only uid to trace (`trace --uid`/`--user`).
+///
+/// Unlike the other keys, 0 is a real value (root): the key is absent
+/// unless a user filter was given, and its presence turns the filter on
+/// (Lesson 34).
+pub const FILTER_KEY_UID: u32 = 6;
+
+/// `FILTER_CONFIG` key holding N of `trace --sample 1/N`: of the syscall
+/// events that pass every filter, each CPU sends about one in N.
+///
+/// Absent, 0 and 1 all mean "every event". `SYSCALL_COUNTS` is updated
+/// before sampling, so its counts stay exact (Lesson 40).
+pub const FILTER_KEY_SAMPLE: u32 = 7;
+
+/// `FILTER_CONFIG` key: when 1, only processes in `FOLLOW_PIDS` are traced:
+/// the ones named by `trace --process` and everything they start
+/// (`--follow-children`, Lesson 44).
+pub const FILTER_KEY_FOLLOW: u32 = 8;
+
+/// `FILTER_CONFIG` key: when 1, the syscall entry program hands every
+/// event that passes the filters to the handler of its category through
+/// `SYSCALL_HANDLERS`, and drops the rest (`trace --category`, Lesson 51).
+pub const FILTER_KEY_TAIL: u32 = 9;
+
+/// `FOLLOW_PIDS` value: the process itself has the `--process` name.
+pub const FOLLOW_MATCHED: u8 = 1;
+
+/// `FOLLOW_PIDS` value: a descendant of a followed process, whatever its
+/// name.
+pub const FOLLOW_CHILD: u8 = 2;
+
+/// Capacity of `FOLLOW_PIDS`: a build or a test suite can start thousands
+/// of processes, and entries are removed when they exit.
+pub const FOLLOW_MAX_ENTRIES: u32 = 16384;
+
+/// Largest N accepted by `--sample 1/N`. Keeps `2 * N` of the random
+/// countdown in the eBPF program far from `u32::MAX`.
+pub const SAMPLE_MAX_RATE: u32 = 1_000_000;
+
+/// Capacity of `FILTER_PIDS` and `FILTER_SYSCALLS`. A larger set is not
+/// pushed down; userspace filters it alone.
+pub const FILTER_SET_MAX_ENTRIES: u32 = 256;
+
+/// The part of a `trace --process` pattern the kernel can check: the first
+/// `len` bytes of the task's comm must equal `bytes[..len]`.
+///
+/// Stored in `FILTER_COMM` (and `FOLLOW_COMM` for `--follow-children`). An
+/// exact name includes its NUL, so `sh` does not match `sshd`; `len` 0
+/// matches every task (Lesson 45).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct CommPrefix {
+    /// The prefix, null-padded
+    pub bytes: [u8; COMM_LEN],
+    /// Bytes to compare, at most `COMM_LEN`
+    pub len: u32,
+}
+
+impl CommPrefix {
+    /// A prefix that matches every task.
+    pub const fn new() -> Self {
+        Self {
+            bytes: [0u8; COMM_LEN],
+            len: 0,
+        }
+    }
+}
+
+impl Default for CommPrefix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Lifecycle Event (Lesson 09)
+// =============================================================================
+
+/// `LifecycleEvent::kind` value for a normal process exit (sched_process_exit).
+pub const LIFECYCLE_EXIT: u32 = 0;
+
+/// `LifecycleEvent::kind` value for an OOM-killer victim (oom/mark_victim).
+pub const LIFECYCLE_OOM_KILL: u32 = 1;
+
+/// Event generated when a process exits or is selected by the OOM killer.
+///
+/// Both tracepoints share one struct so userspace can read a single perf
+/// event array and print OOM kills inline with the exits they cause.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct LifecycleEvent {
+    /// Process ID (tgid) of the exiting task or OOM victim
+    pub pid: u32,
+    /// Thread ID of the exiting task
+    pub tid: u32,
+    /// `LIFECYCLE_EXIT` or `LIFECYCLE_OOM_KILL`
+    pub kind: u32,
+    /// Raw `task->exit_code` (status << 8 | signal); 0 for OOM events
+    pub exit_code: i32,
+    /// Cgroup v2 ID (inode of the cgroup directory, from bpf_get_current_cgroup_id)
+    pub cgroup_id: u64,
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl LifecycleEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            kind: LIFECYCLE_EXIT,
+            exit_code: 0,
+            cgroup_id: 0,
+            timestamp_ns: 0,
+            comm: [0u8; COMM_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for LifecycleEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Page Fault Key (Lesson 07, --event faults)
+// =============================================================================
+
+/// `FaultKey::kind` value for major faults (PERF_COUNT_SW_PAGE_FAULTS_MAJ).
+pub const FAULT_MAJOR: u32 = 0;
+
+/// `FaultKey::kind` value for minor faults (PERF_COUNT_SW_PAGE_FAULTS_MIN).
+pub const FAULT_MINOR: u32 = 1;
+
+/// Key for the `FAULT_COUNTS` LruPerCpuHashMap used by `perf --event faults`.
+///
+/// Faults are aggregated in the kernel per process, fault kind and user
+/// stack, so userspace reads one counter per distinct call path instead of
+/// one event per fault.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct FaultKey {
+    /// Process ID (tgid in kernel terms)
+    pub pid: u32,
+    /// `FAULT_MAJOR` or `FAULT_MINOR`
+    pub kind: u32,
+    /// User stack ID (from STACKS map, -1 if unavailable)
+    pub user_stack_id: i64,
+}
+
+impl FaultKey {
+    pub const fn new(pid: u32, kind: u32, user_stack_id: i64) -> Self {
+        Self {
+            pid,
+            kind,
+            user_stack_id,
+        }
+    }
+}
+
+impl Default for FaultKey {
+    fn default() -> Self {
+        Self::new(0, FAULT_MINOR, -1)
+    }
+}
+
+// =============================================================================
+// Cgroup Network Counters (Lesson 10)
+// =============================================================================
+
+/// `CGROUP_NET_STATS` index for traffic entering the cgroup.
+pub const CGROUP_NET_INGRESS: u32 = 0;
+
+/// `CGROUP_NET_STATS` index for traffic leaving the cgroup.
+pub const CGROUP_NET_EGRESS: u32 = 1;
+
+/// Byte and packet counters for one direction of a cgroup's traffic.
+///
+/// Stored in a per-CPU array so the `cgroup_skb` programs can update it
+/// without atomics; userspace sums the per-CPU values.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct NetCounters {
+    /// Total bytes (skb->len, including L3 headers)
+    pub bytes: u64,
+    /// Total packets
+    pub packets: u64,
+}
+
+impl NetCounters {
+    /// Create zeroed counters.
+    pub const fn new() -> Self {
+        Self {
+            bytes: 0,
+            packets: 0,
+        }
+    }
+}
+
+impl Default for NetCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// CPU Time per Cgroup (Lesson 42)
+// =============================================================================
+
+/// Index in `SCHED_CONFIG`: 1 to also log every switch with `info!`
+/// (`tracepoint sched sched_switch`, Lesson 06); `sched` leaves it 0.
+pub const SCHED_CONFIG_LOG: u32 = 0;
+
+/// On-CPU time and context switches of the tasks in one cgroup, the value
+/// of `CGROUP_CPU` (keyed by cgroup id).
+///
+/// Updated by `sched_tracepoint` on every `sched_switch`, for the task
+/// being switched out. The map is per-CPU, so the counters need no atomics;
+/// userspace sums the per-CPU values. They only grow: userspace reports
+/// the difference between two reads.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct CgroupCpu {
+    /// Nanoseconds on a CPU
+    pub on_cpu_ns: u64,
+    /// Times a task of the cgroup was switched out
+    pub switches: u64,
+    /// Of those, times it was still runnable: preempted, not waiting
+    pub involuntary: u64,
+}
+
+impl CgroupCpu {
+    /// Create zeroed counters.
+    pub const fn new() -> Self {
+        Self {
+            on_cpu_ns: 0,
+            switches: 0,
+            involuntary: 0,
+        }
+    }
+}
+
+impl Default for CgroupCpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Tail Calls (Lesson 51)
+// =============================================================================
+
+/// `SYSCALL_HANDLERS` slot of the file handler (open, read, stat, ...).
+pub const TAIL_FILE: u32 = 0;
+
+/// `SYSCALL_HANDLERS` slot of the network handler (socket, connect, ...).
+pub const TAIL_NET: u32 = 1;
+
+/// `SYSCALL_HANDLERS` slot of the process handler (clone, execve, kill, ...).
+pub const TAIL_PROC: u32 = 2;
+
+/// Number of slots in `SYSCALL_HANDLERS`.
+pub const TAIL_SLOTS: u32 = 3;
+
+/// Capacity of `SYSCALL_CATEGORIES` (syscall number -> slot). Linux has
+/// fewer than 500 syscalls on any architecture.
+pub const TAIL_CATEGORY_MAX_ENTRIES: u32 = 512;
+
+// =============================================================================
+// Syscall Errors (Lesson 50)
+// =============================================================================
+
+/// Capacity of `SYSCALL_ERRORS`. Failing (pid, syscall, errno) triples are
+/// far fewer than syscalls: a storm is one key with a large count.
+pub const ERRORS_MAX_ENTRIES: u32 = 4096;
+
+/// `ERRORS_CONFIG` index holding the cgroup id to count (0 = all cgroups).
+pub const ERRORS_CONFIG_CGROUP: u32 = 0;
+
+/// One kind of failure of one process, the key of `SYSCALL_ERRORS`.
+///
+/// The value is the number of exits of `syscall_nr` by `pid` that returned
+/// `-errno`. Same layout rules as [`SyscallKey`]: the padding is explicit,
+/// so every byte of the key is written by the eBPF program.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct ErrorKey {
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// The error, positive (2 for ENOENT)
+    pub errno: u32,
+    /// Syscall number (architecture-specific)
+    pub syscall_nr: u64,
+}
+
+impl ErrorKey {
+    /// Create a zeroed key.
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            errno: 0,
+            syscall_nr: 0,
+        }
+    }
+}
+
+impl Default for ErrorKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Comm Cache Updates (Lesson 08)
+// =============================================================================
+
+/// `CommUpdateEvent::kind` value for a rename (task/task_rename: prctl(PR_SET_NAME),
+/// writes to /proc/<pid>/comm).
+pub const COMM_UPDATE_RENAME: u32 = 0;
+
+/// `CommUpdateEvent::kind` value for a successful exec (sched/sched_process_exec).
+pub const COMM_UPDATE_EXEC: u32 = 1;
+
+/// Event telling userspace that a task's comm changed.
+///
+/// The tracer keeps a pid -> comm cache so it does not have to copy 16 bytes
+/// into every syscall event. These events keep that cache correct after
+/// exec and renames.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct CommUpdateEvent {
+    /// Process ID (tgid) whose name changed
+    pub pid: u32,
+    /// Thread ID that changed its name (renames are per thread)
+    pub tid: u32,
+    /// `COMM_UPDATE_RENAME` or `COMM_UPDATE_EXEC`
+    pub kind: u32,
+    /// The new command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+}
+
+impl CommUpdateEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            kind: COMM_UPDATE_RENAME,
+            comm: [0u8; COMM_LEN],
+            abi: EVENT_ABI,
+        }
+    }
+}
+
+impl Default for CommUpdateEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// LSM Deny List (Lesson 11)
+// =============================================================================
+
+/// Key for the `DENY_INODES` HashMap used by the `file_open` LSM program.
+///
+/// Paths are resolved to (device, inode) in userspace, so the kernel
+/// compares two integers instead of strings. This also blocks the file
+/// through hard links and bind mounts.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct InodeKey {
+    /// Kernel device number (`inode->i_sb->s_dev`, MAJOR << 20 | MINOR)
+    pub dev: u64,
+    /// Inode number (`inode->i_ino`)
+    pub ino: u64,
+}
+
+impl InodeKey {
+    /// Create a key for a (device, inode) pair.
+    pub const fn new(dev: u64, ino: u64) -> Self {
+        Self { dev, ino }
+    }
+}
+
+impl Default for InodeKey {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Event emitted each time the LSM program denies an open.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct LsmDenyEvent {
+    /// Process ID (tgid) that tried to open the file
+    pub pid: u32,
+    /// Real user ID of that process
+    pub uid: u32,
+    /// The denied file
+    pub inode: InodeKey,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl LsmDenyEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            uid: 0,
+            inode: InodeKey::new(0, 0),
+            comm: [0u8; COMM_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for LsmDenyEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Task Iterator Records (Lesson 12)
+// =============================================================================
+
+/// One record per task, written by the `task` BPF iterator.
+///
+/// Unlike the perf-array events, these are not pushed to userspace. The
+/// iterator writes them back to back into a seq_file with `bpf_seq_write`,
+/// and userspace gets them by calling `read()` on the iterator's fd.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct TaskRecord {
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// Thread ID (the kernel's task pid)
+    pub tid: u32,
+    /// Parent process ID (`real_parent->tgid`)
+    pub ppid: u32,
+    /// PID namespace inode (matches `/proc/<pid>/ns/pid`)
+    pub pidns_inum: u32,
+    /// Mount namespace inode (matches `/proc/<pid>/ns/mnt`)
+    pub mntns_inum: u32,
+    /// Network namespace inode (matches `/proc/<pid>/ns/net`)
+    pub netns_inum: u32,
+    /// Cgroup v2 ID (same value as `bpf_get_current_cgroup_id()`)
+    pub cgroup_id: u64,
+    /// Task command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl TaskRecord {
+    /// Create a zeroed record (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            ppid: 0,
+            pidns_inum: 0,
+            mntns_inum: 0,
+            netns_inum: 0,
+            cgroup_id: 0,
+            comm: [0u8; COMM_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for TaskRecord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Socket Request Latency (Lesson 14)
+// =============================================================================
+
+/// `SockEvent::kind`: accept()/accept4() returned a new connection fd.
+pub const SOCK_ACCEPT: u32 = 0;
+
+/// `SockEvent::kind`: read()/recvfrom() on a tracked connection returned.
+pub const SOCK_READ: u32 = 1;
+
+/// `SockEvent::kind`: write()/sendto() on a tracked connection returned.
+pub const SOCK_WRITE: u32 = 2;
+
+/// `SockEvent::kind`: close() was called on a tracked connection.
+pub const SOCK_CLOSE: u32 = 3;
+
+/// `REQLAT_TARGET` index holding the server PID (0 = any PID).
+pub const REQLAT_TARGET_PID: u32 = 0;
+
+/// `REQLAT_TARGET` index holding the cgroup id (0 = any cgroup).
+pub const REQLAT_TARGET_CGROUP: u32 = 1;
+
+/// Identifies one connection: a file descriptor in one process.
+///
+/// Used as the key of the map of tracked connections, so that reads and
+/// writes on files and pipes are dropped in the kernel.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct SockKey {
+    /// Process ID (tgid); fds are shared by all threads
+    pub pid: u32,
+    /// File descriptor returned by accept()
+    pub fd: u32,
+}
+
+impl SockKey {
+    /// Create a key for `fd` in process `pid`.
+    pub const fn new(pid: u32, fd: u32) -> Self {
+        Self { pid, fd }
+    }
+}
+
+/// One accept/read/write/close on a tracked connection.
+///
+/// The kernel side only reports what happened and when. Pairing the events
+/// into requests is left to userspace, where the state is easy to inspect.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct SockEvent {
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// Thread ID (worker threads of one server share the connection fds)
+    pub tid: u32,
+    /// Connection file descriptor
+    pub fd: u32,
+    /// `SOCK_ACCEPT`, `SOCK_READ`, `SOCK_WRITE` or `SOCK_CLOSE`
+    pub kind: u32,
+    /// Syscall return value: bytes for read/write (0 = peer closed, <0 = -errno)
+    pub ret: i64,
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl SockEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            fd: 0,
+            kind: SOCK_ACCEPT,
+            ret: 0,
+            timestamp_ns: 0,
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for SockEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Mount Events (Lesson 17)
+// =============================================================================
+
+/// `MountEvent::kind`: mount(2), including bind mounts and propagation changes.
+pub const MOUNT_KIND_MOUNT: u32 = 0;
+
+/// `MountEvent::kind`: umount2(2).
+pub const MOUNT_KIND_UMOUNT: u32 = 1;
+
+/// `MountEvent::kind`: move_mount(2), which attaches mounts made with the
+/// new mount API (fsopen/fsmount) and moves existing ones.
+pub const MOUNT_KIND_MOVE: u32 = 2;
+
+/// Bytes kept of the mount source (device, "none", or the bind source).
+pub const MOUNT_SOURCE_LEN: usize = 64;
+
+/// Bytes kept of the mount target path.
+pub const MOUNT_TARGET_LEN: usize = 128;
+
+/// Bytes kept of the filesystem type ("tmpfs", "overlay", ...).
+pub const MOUNT_FSTYPE_LEN: usize = 16;
+
+/// One completed mount, umount or move_mount call.
+///
+/// The enter tracepoint copies the arguments (they are user pointers that
+/// are only valid during the call) and the exit tracepoint adds the return
+/// value, so failed mounts are reported with their errno. At 272 bytes the
+/// event is too big for the 512-byte BPF stack to hold comfortably; build it
+/// in a per-CPU scratch map.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct MountEvent {
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// Thread ID
+    pub tid: u32,
+    /// `MOUNT_KIND_MOUNT`, `MOUNT_KIND_UMOUNT` or `MOUNT_KIND_MOVE`
+    pub kind: u32,
+    /// Mount namespace inode of the caller (matches `/proc/<pid>/ns/mnt`)
+    pub mntns_inum: u32,
+    /// Syscall return value: 0, or -errno
+    pub ret: i64,
+    /// `MS_*` flags for mount, `MNT_*` for umount, `MOVE_MOUNT_*` for move_mount
+    pub flags: u64,
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// Filesystem type (mount only; empty for bind mounts and propagation changes)
+    pub fstype: [u8; MOUNT_FSTYPE_LEN],
+    /// Source: device or name for mount, "from" path for move_mount
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub source: [u8; MOUNT_SOURCE_LEN],
+    /// Mount point: target for mount and umount, "to" path for move_mount
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub target: [u8; MOUNT_TARGET_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl MountEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            kind: MOUNT_KIND_MOUNT,
+            mntns_inum: 0,
+            ret: 0,
+            flags: 0,
+            timestamp_ns: 0,
+            comm: [0u8; COMM_LEN],
+            fstype: [0u8; MOUNT_FSTYPE_LEN],
+            source: [0u8; MOUNT_SOURCE_LEN],
+            target: [0u8; MOUNT_TARGET_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for MountEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Event Batches (Lesson 08, high event rates)
+// =============================================================================
+
+/// Events per `SyscallBatch`: 16 * 72 + 8 = 1160 bytes per perf record.
+pub const SYSCALL_BATCH_LEN: usize = 16;
+
+/// Length of the window over which each CPU measures its event rate.
+pub const BATCH_WINDOW_NS: u64 = 1_000_000;
+
+/// Events per window on one CPU above which that CPU switches to batches
+/// (16 per millisecond, i.e. 16k events/s per CPU).
+pub const BATCH_RATE_THRESHOLD: u32 = 16;
+
+/// Oldest a partly filled batch may get before the next event flushes it,
+/// so a burst that ends mid-batch is not held back indefinitely.
+pub const BATCH_MAX_AGE_NS: u64 = 10_000_000;
+
+/// Up to `N` syscall events sent as one perf buffer record.
+///
+/// Every `PerfEventArray::output()` call costs a helper call, a perf header
+/// and a wakeup check; above ~100k events/s that overhead dominates the
+/// tracer. A busy CPU collects events in a per-CPU `EventBatch` instead and
+/// outputs it once it is full. Only the first `count` entries are valid.
+///
+/// The batch is too big for the BPF stack: keep it in a per-CPU array map.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(serde::Serialize, serde::Deserialize, bytemuck::Zeroable)
+)]
+pub struct EventBatch<const N: usize> {
+    /// Number of valid events at the start of `events`
+    pub count: u32,
+    /// `EVENT_ABI` of the build that made the batch; set by `new()`. Also
+    /// keeps `events` 8-byte aligned without implicit padding
+    pub abi: u32,
+    /// The events, oldest first; entries at `count` and after are stale
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub events: [SyscallEvent; N],
+}
+
+// SAFETY: two u32 and then SyscallEvents, which are 8-byte aligned and a
+// multiple of 8 bytes long: no padding for any N. Pod cannot be derived for
+// const generic structs.
+#[cfg(feature = "user")]
+unsafe impl<const N: usize> bytemuck::Pod for EventBatch<N> {}
+
+/// The batch the syscall tracer sends through `SYSCALL_BATCHES`.
+pub type SyscallBatch = EventBatch<SYSCALL_BATCH_LEN>;
+
+impl<const N: usize> EventBatch<N> {
+    /// Create an empty batch (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            abi: EVENT_ABI,
+            events: [SyscallEvent::new(); N],
+        }
+    }
+
+    /// Append `event`. Returns false, leaving the batch unchanged, if the
+    /// batch is already full.
+    ///
+    /// The explicit index check is what lets the verifier accept the store.
+    #[inline(always)]
+    pub fn push(&mut self, event: SyscallEvent) -> bool {
+        let i = self.count as usize;
+        if i >= N {
+            return false;
+        }
+        self.events[i] = event;
+        self.count += 1;
+        true
+    }
+
+    /// Whether the next `push()` would fail.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.count as usize >= N
+    }
+
+    /// Timestamp of the oldest event, or None if the batch is empty.
+    #[inline(always)]
+    pub fn first_timestamp_ns(&self) -> Option<u64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.events[0].timestamp_ns)
+        }
+    }
+
+    /// Forget all events (the entries are overwritten by later pushes).
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.count = 0;
+    }
+
+    /// The valid events. A corrupt `count` larger than `N` is clamped.
+    pub fn events(&self) -> &[SyscallEvent] {
+        let count = if (self.count as usize) < N {
+            self.count as usize
+        } else {
+            N
+        };
+        &self.events[..count]
+    }
+}
+
+impl<const N: usize> Default for EventBatch<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Syscall Exits (Lesson 23)
+// =============================================================================
+
+/// What the entry program leaves in `PENDING_SYSCALLS` for the exit program,
+/// keyed by pid_tgid.
+///
+/// A thread is in at most one syscall at a time, so one entry per thread is
+/// enough. The map is an LRU map: a syscall whose exit never comes (exit(),
+/// a killed task) is evicted instead of filling it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct PendingSyscall {
+    /// System call number seen on entry
+    pub syscall_nr: u64,
+    /// `SyscallEvent::timestamp_ns` of the entry event
+    pub enter_ns: u64,
+}
+
+impl PendingSyscall {
+    /// Create a pending entry for syscall `syscall_nr` entered at `enter_ns`.
+    pub const fn new(syscall_nr: u64, enter_ns: u64) -> Self {
+        Self {
+            syscall_nr,
+            enter_ns,
+        }
+    }
+}
+
+/// Event generated when a traced system call returns.
+///
+/// Only sent for syscalls whose entry was sent, so every exit has an entry
+/// event with the same `tid` and `timestamp_ns == enter_ns`. The two can
+/// arrive from different CPUs' perf buffers, in either order; userspace
+/// pairs them.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct SyscallExitEvent {
+    /// Process ID (tgid in kernel terms)
+    pub pid: u32,
+    /// Thread ID (pid in kernel terms)
+    pub tid: u32,
+    /// System call number (from the pending entry)
+    pub syscall_nr: u64,
+    /// Return value: a result, or -errno for -4095..=-1
+    pub ret: i64,
+    /// Timestamp of the matching entry event
+    pub enter_ns: u64,
+    /// Timestamp of the exit, in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl SyscallExitEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            tid: 0,
+            syscall_nr: 0,
+            ret: 0,
+            enter_ns: 0,
+            timestamp_ns: 0,
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for SyscallExitEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Syscall Table (Lesson 26)
+// =============================================================================
+
+// SYSCALL_TABLE_ARCH, SYSCALL_TABLE_SOURCE and SYSCALLS: (number, name)
+// pairs for the target architecture, sorted by number. Generated by
+// build.rs from the kernel headers or syscalls/<arch>.tbl.
+include!(concat!(env!("OUT_DIR"), "/syscall_table.rs"));
+
+/// Name of syscall `nr` on the target architecture, from the build-time
+/// table. Userspace should prefer `syscalls::SyscallTable` in ebpf-tool,
+/// which also reads the runtime override file.
+pub fn syscall_name(nr: u64) -> Option<&'static str> {
+    SYSCALLS
+        .binary_search_by_key(&nr, |&(n, _)| n)
+        .ok()
+        .map(|i| SYSCALLS[i].1)
+}
+
+/// Number of syscall `name` on the target architecture, from the
+/// build-time table.
+pub fn syscall_nr(name: &str) -> Option<u64> {
+    SYSCALLS
+        .iter()
+        .find(|&&(_, n)| n == name)
+        .map(|&(nr, _)| nr)
+}
+
+// =============================================================================
+// Probe Events (Lesson 25, `trace --kprobe/--tracepoint/--uprobe`)
+// =============================================================================
+
+/// `ProbeEvent::kind` of an event from `multi_kprobe`.
+pub const PROBE_KIND_KPROBE: u32 = 0;
+
+/// `ProbeEvent::kind` of an event from `multi_tracepoint`.
+pub const PROBE_KIND_TRACEPOINT: u32 = 1;
+
+/// `ProbeEvent::kind` of an event from `multi_uprobe`.
+pub const PROBE_KIND_UPROBE: u32 = 2;
+
+/// Event generated when one of the extra probes of `trace` fires.
+///
+/// There is one program per kind, attached once per probe. The program does
+/// not know which attachment fired, so it sends `site` and userspace looks
+/// it up:
+///
+/// - kprobe: the probed function's address (`bpf_get_func_ip`)
+/// - tracepoint: the tracepoint's id (`common_type`, the first field of
+///   every tracepoint record, as in `events/<category>/<name>/id`)
+/// - uprobe: the instruction pointer in the process (`ctx.ip()`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct ProbeEvent {
+    /// One of the `PROBE_KIND_*` constants
+    pub kind: u32,
+    /// Process ID (tgid in kernel terms)
+    pub pid: u32,
+    /// Thread ID (pid in kernel terms)
+    pub tid: u32,
+    /// Keeps `site` 8-byte aligned without implicit padding; always 0
+    pub _reserved: u32,
+    /// What identifies the probe that fired (see above)
+    pub site: u64,
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns), the same clock as
+    /// `SyscallEvent::timestamp_ns`
+    pub timestamp_ns: u64,
+    /// Process name (null-terminated)
+    pub comm: [u8; COMM_LEN],
+    /// Parent process ID, as in `SyscallEvent`
+    pub ppid: u32,
+    /// PID namespace inode, as in `SyscallEvent`
+    pub pidns_inum: u32,
+    /// Mount namespace inode, as in `SyscallEvent`
+    pub mntns_inum: u32,
+    /// Real user ID, as in `SyscallEvent`
+    pub uid: u32,
+    /// Real group ID, as in `SyscallEvent`
+    pub gid: u32,
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// First string argument, for probes that ask for one (NUL-padded,
+    /// cut to `PROBE_ARG_LEN - 1` bytes); empty otherwise (Lesson 37)
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub arg: [u8; PROBE_ARG_LEN],
+}
+
+impl ProbeEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            kind: 0,
+            pid: 0,
+            tid: 0,
+            _reserved: 0,
+            site: 0,
+            timestamp_ns: 0,
+            comm: [0u8; COMM_LEN],
+            ppid: 0,
+            pidns_inum: 0,
+            mntns_inum: 0,
+            uid: 0,
+            gid: 0,
+            abi: EVENT_ABI,
+            arg: [0u8; PROBE_ARG_LEN],
+        }
+    }
+}
 
-#![no_std]
+impl Default for ProbeEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-/// Maximum length of process command name (TASK_COMM_LEN in Linux kernel).
-pub const COMM_LEN: usize = 16;
+// =============================================================================
+// Kernel Struct Offsets (Lesson 31)
+// =============================================================================
 
-/// Maximum entries in syscall counter maps.
-pub const MAX_MAP_ENTRIES: u32 = 10240;
+/// Symbol name of the `KernelOffsets` global in the eBPF object, which the
+/// loader overwrites before the programs are loaded.
+pub const KERNEL_OFFSETS_GLOBAL: &str = "KERNEL_OFFSETS";
+
+/// `KernelOffsets` value of a field the running kernel does not have (or
+/// that could not be looked up). The readers fail instead of using it.
+pub const OFFSET_UNKNOWN: u32 = u32::MAX;
+
+/// Byte offsets of the kernel struct fields the eBPF struct readers follow,
+/// looked up in the running kernel's BTF when the object is loaded.
+///
+/// C programs get this from CO-RE: clang records every field access as a
+/// relocation and libbpf patches the offsets for the running kernel.
+/// rustc cannot emit those relocations, so userspace does the lookup
+/// itself and writes the results into this global. A program compiled
+/// once then reads `task->real_parent->tgid` correctly on 5.4 and on 6.x,
+/// where the fields sit at different offsets.
+///
+/// `*_ns_inum` are offsets of `ns.inum` inside each namespace struct: every
+/// namespace embeds a `struct ns_common`, at a different place in each.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct KernelOffsets {
+    /// `task_struct.pid` (the thread id)
+    pub task_pid: u32,
+    /// `task_struct.tgid` (the process id)
+    pub task_tgid: u32,
+    /// `task_struct.real_parent`
+    pub task_real_parent: u32,
+    /// `task_struct.nsproxy`
+    pub task_nsproxy: u32,
+    /// `task_struct.mm` (NULL for kernel threads)
+    pub task_mm: u32,
+    /// `task_struct.thread_pid`
+    pub task_thread_pid: u32,
+    /// `nsproxy.uts_ns`
+    pub nsproxy_uts_ns: u32,
+    /// `nsproxy.ipc_ns`
+    pub nsproxy_ipc_ns: u32,
+    /// `nsproxy.mnt_ns`
+    pub nsproxy_mnt_ns: u32,
+    /// `nsproxy.net_ns`
+    pub nsproxy_net_ns: u32,
+    /// `nsproxy.cgroup_ns`
+    pub nsproxy_cgroup_ns: u32,
+    /// `uts_namespace.ns.inum`
+    pub uts_ns_inum: u32,
+    /// `ipc_namespace.ns.inum`
+    pub ipc_ns_inum: u32,
+    /// `mnt_namespace.ns.inum`
+    pub mnt_ns_inum: u32,
+    /// `net.ns.inum`
+    pub net_ns_inum: u32,
+    /// `cgroup_namespace.ns.inum`
+    pub cgroup_ns_inum: u32,
+    /// `pid.level`: depth of the task's PID namespace
+    pub pid_level: u32,
+    /// `pid.numbers`: one `struct upid` per level
+    pub pid_numbers: u32,
+    /// `sizeof(struct upid)`, to index `pid.numbers`
+    pub upid_size: u32,
+    /// `upid.ns`
+    pub upid_ns: u32,
+    /// `pid_namespace.ns.inum`
+    pub pid_ns_inum: u32,
+    /// `mm_struct.rss_stat`: one counter per `MM_*` type
+    pub mm_rss_stat: u32,
+    /// Bytes between two `rss_stat` counters: 8 (`atomic_long_t`) before
+    /// 6.2, `sizeof(struct percpu_counter)` since
+    pub rss_stat_stride: u32,
+    /// Offset of the count inside one `rss_stat` counter
+    pub rss_stat_count: u32,
+    /// `sock.__sk_common.skc_net.net`: the socket's network namespace
+    pub sock_net: u32,
+    /// `sock.__sk_common.skc_family` (`AF_INET`, `AF_INET6`)
+    pub sock_family: u32,
+    /// `sock.__sk_common.skc_rcv_saddr`: local IPv4 address
+    pub sock_rcv_saddr: u32,
+    /// `sock.__sk_common.skc_daddr`: peer IPv4 address of a connected socket
+    pub sock_daddr: u32,
+    /// `sock.__sk_common.skc_num`: local port, host byte order
+    pub sock_num: u32,
+    /// `sock.__sk_common.skc_dport`: peer port, network byte order
+    pub sock_dport: u32,
+    /// `msghdr.msg_name`: the address of sendto() and recvfrom()
+    pub msghdr_name: u32,
+    /// `sk_buff.dev`: the device a packet arrived on
+    pub skb_dev: u32,
+    /// `sk_buff.head`: start of the buffer the header offsets count from
+    pub skb_head: u32,
+    /// `sk_buff.network_header`: offset of the IP header from `head`
+    pub skb_network_header: u32,
+    /// `sk_buff.transport_header`: offset of the ICMP or UDP header from `head`
+    pub skb_transport_header: u32,
+    /// `net_device.nd_net.net`: the device's network namespace
+    pub netdev_net: u32,
+    /// `open_how.flags`: the `O_*` flags of open(), openat() and openat2()
+    pub open_how_flags: u32,
+    /// `open_how.mode`: the file mode with `O_CREAT` or `O_TMPFILE`
+    pub open_how_mode: u32,
+    /// `open_how.resolve`: the `RESOLVE_*` flags of openat2()
+    pub open_how_resolve: u32,
+    /// `task_struct.exit_code`: `status << 8 | signal`, set before
+    /// `sched_process_exit` fires
+    pub task_exit_code: u32,
+}
+
+impl KernelOffsets {
+    /// All fields unknown: the value the eBPF global starts with.
+    pub const fn unknown() -> Self {
+        Self {
+            task_pid: OFFSET_UNKNOWN,
+            task_tgid: OFFSET_UNKNOWN,
+            task_real_parent: OFFSET_UNKNOWN,
+            task_nsproxy: OFFSET_UNKNOWN,
+            task_mm: OFFSET_UNKNOWN,
+            task_thread_pid: OFFSET_UNKNOWN,
+            nsproxy_uts_ns: OFFSET_UNKNOWN,
+            nsproxy_ipc_ns: OFFSET_UNKNOWN,
+            nsproxy_mnt_ns: OFFSET_UNKNOWN,
+            nsproxy_net_ns: OFFSET_UNKNOWN,
+            nsproxy_cgroup_ns: OFFSET_UNKNOWN,
+            uts_ns_inum: OFFSET_UNKNOWN,
+            ipc_ns_inum: OFFSET_UNKNOWN,
+            mnt_ns_inum: OFFSET_UNKNOWN,
+            net_ns_inum: OFFSET_UNKNOWN,
+            cgroup_ns_inum: OFFSET_UNKNOWN,
+            pid_level: OFFSET_UNKNOWN,
+            pid_numbers: OFFSET_UNKNOWN,
+            upid_size: OFFSET_UNKNOWN,
+            upid_ns: OFFSET_UNKNOWN,
+            pid_ns_inum: OFFSET_UNKNOWN,
+            mm_rss_stat: OFFSET_UNKNOWN,
+            rss_stat_stride: OFFSET_UNKNOWN,
+            rss_stat_count: OFFSET_UNKNOWN,
+            sock_net: OFFSET_UNKNOWN,
+            sock_family: OFFSET_UNKNOWN,
+            sock_rcv_saddr: OFFSET_UNKNOWN,
+            sock_daddr: OFFSET_UNKNOWN,
+            sock_num: OFFSET_UNKNOWN,
+            sock_dport: OFFSET_UNKNOWN,
+            msghdr_name: OFFSET_UNKNOWN,
+            skb_dev: OFFSET_UNKNOWN,
+            skb_head: OFFSET_UNKNOWN,
+            skb_network_header: OFFSET_UNKNOWN,
+            skb_transport_header: OFFSET_UNKNOWN,
+            netdev_net: OFFSET_UNKNOWN,
+            open_how_flags: OFFSET_UNKNOWN,
+            open_how_mode: OFFSET_UNKNOWN,
+            open_how_resolve: OFFSET_UNKNOWN,
+            task_exit_code: OFFSET_UNKNOWN,
+        }
+    }
+}
+
+impl Default for KernelOffsets {
+    fn default() -> Self {
+        Self::unknown()
+    }
+}
+
+/// `mm_struct.rss_stat` index of file-backed pages (`MM_FILEPAGES`).
+pub const MM_FILEPAGES: u32 = 0;
+
+/// `mm_struct.rss_stat` index of anonymous pages (`MM_ANONPAGES`).
+pub const MM_ANONPAGES: u32 = 1;
+
+/// `mm_struct.rss_stat` index of shared memory pages (`MM_SHMEMPAGES`);
+/// index 2 is swap entries, which are not resident.
+pub const MM_SHMEMPAGES: u32 = 3;
+
+/// Namespace inodes of one task, as read by the struct readers. Each one
+/// matches the inode in `/proc/<pid>/ns/<kind>`; 0 means "could not read".
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct NsIds {
+    pub pid: u32,
+    pub mnt: u32,
+    pub net: u32,
+    pub uts: u32,
+    pub ipc: u32,
+    pub cgroup: u32,
+}
+
+impl NsIds {
+    /// All zero (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            pid: 0,
+            mnt: 0,
+            net: 0,
+            uts: 0,
+            ipc: 0,
+            cgroup: 0,
+        }
+    }
+}
+
+impl Default for NsIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 // =============================================================================
-// Syscall Event (Lessons 02-04, 08)
+// Network Events (Lesson 32)
 // =============================================================================
 
-/// Event generated when a system call is invoked.
+/// `NetEvent::proto`: ICMP (`IPPROTO_ICMP`).
+pub const NET_PROTO_ICMP: u8 = 1;
+
+/// `NetEvent::proto`: UDP (`IPPROTO_UDP`).
+pub const NET_PROTO_UDP: u8 = 17;
+
+/// `NetEvent::direction`: sent by a process in the namespace.
+pub const NET_DIR_SEND: u8 = 0;
+
+/// `NetEvent::direction`: received in the namespace.
+pub const NET_DIR_RECV: u8 = 1;
+
+/// Index in `NETSNOOP_TARGET` of the network namespace inode to report
+/// (0 = every namespace).
+pub const NETSNOOP_TARGET_NETNS: u32 = 0;
+
+/// One UDP datagram or ICMP message, tagged with the network namespace it
+/// was sent or received in.
 ///
-/// Used in kprobe-based syscall tracing. The eBPF program populates this
-/// struct and sends it to userspace via a perf event array.
+/// The namespace comes from the socket (UDP) or from the receiving device
+/// (ICMP), not from the current task: softirq processing runs in whatever
+/// task happened to be interrupted, which is usually in another namespace.
+/// Addresses are IPv4 in network byte order, so `Ipv4Addr::from(u32::from_be(..))`
+/// prints them.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
-pub struct SyscallEvent {
-    /// Process ID (tgid in kernel terms)
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct NetEvent {
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// Process ID (tgid); meaningless for received ICMP (softirq)
     pub pid: u32,
-    /// Thread ID (pid in kernel terms)
+    /// Thread ID
     pub tid: u32,
-    /// System call number (architecture-dependent)
-    pub syscall_nr: u64,
+    /// Network namespace inode (matches `/proc/<pid>/ns/net`)
+    pub netns_inum: u32,
+    /// Payload bytes sent or received, or -errno for a failed UDP call
+    pub len: i32,
+    /// `NET_PROTO_UDP` or `NET_PROTO_ICMP`
+    pub proto: u8,
+    /// `NET_DIR_SEND` or `NET_DIR_RECV`
+    pub direction: u8,
+    /// ICMP type (8 echo request, 0 echo reply, 3 unreachable, ...)
+    pub icmp_type: u8,
+    /// ICMP code
+    pub icmp_code: u8,
+    /// Source port, host byte order (UDP only)
+    pub sport: u16,
+    /// Destination port, host byte order (UDP only)
+    pub dport: u16,
+    /// Source IPv4 address, network byte order (0 if unknown)
+    pub saddr: u32,
+    /// Destination IPv4 address, network byte order (0 if unknown)
+    pub daddr: u32,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
+}
+
+impl NetEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            timestamp_ns: 0,
+            pid: 0,
+            tid: 0,
+            netns_inum: 0,
+            len: 0,
+            proto: 0,
+            direction: NET_DIR_SEND,
+            icmp_type: 0,
+            icmp_code: 0,
+            sport: 0,
+            dport: 0,
+            saddr: 0,
+            daddr: 0,
+            comm: [0u8; COMM_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
+        }
+    }
+}
+
+impl Default for NetEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Exec Events (Lesson 37)
+// =============================================================================
+
+/// One successful execve(), with the program path and its arguments.
+///
+/// Both are user memory that is only readable while the syscall runs, and
+/// of any length, so they are copied with bounded reads into fixed fields:
+/// `filename` holds at most `PATH_LEN - 1` bytes, `argv` the first
+/// `MAX_ARGS` entries of at most `ARG_LEN - 1` bytes each, one slot per
+/// entry (see [`argv_entry`]). `flags` says what was cut. At 832 bytes the
+/// event does not fit on the BPF stack; build it in a per-CPU scratch map,
+/// like `MountEvent`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct ExecEvent {
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// Thread ID
+    pub tid: u32,
+    /// Parent process ID, as in `SyscallEvent`
+    pub ppid: u32,
+    /// Number of arguments, including the ones beyond `MAX_ARGS`
+    pub argc: u32,
+    /// Cgroup ID (from bpf_get_current_cgroup_id)
+    pub cgroup_id: u64,
     /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
     pub timestamp_ns: u64,
-    /// Process command name (null-padded)
+    /// `STR_*` flags: what was truncated or could not be read
+    pub flags: u32,
+    /// Real user ID, as in `SyscallEvent`
+    pub uid: u32,
+    /// Process name before the exec (null-padded)
     pub comm: [u8; COMM_LEN],
+    /// Path passed to execve(), as given (may be relative)
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub filename: [u8; PATH_LEN],
+    /// argv, one `ARG_LEN` slot per entry
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub argv: [u8; MAX_ARGS * ARG_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
 }
 
-impl SyscallEvent {
+impl ExecEvent {
     /// Create a zeroed event (for initialization in eBPF programs).
     pub const fn new() -> Self {
         Self {
             pid: 0,
             tid: 0,
-            syscall_nr: 0,
+            ppid: 0,
+            argc: 0,
+            cgroup_id: 0,
             timestamp_ns: 0,
+            flags: 0,
+            uid: 0,
             comm: [0u8; COMM_LEN],
+            filename: [0u8; PATH_LEN],
+            argv: [0u8; MAX_ARGS * ARG_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
         }
     }
 }
 
-impl Default for SyscallEvent {
+impl Default for ExecEvent {
     fn default() -> Self {
         Self::new()
     }
 }
 
 // =============================================================================
-// Map Key (Lesson 03)
+// DNS Lookups (Lesson 41)
 // =============================================================================
 
-/// Key for syscall counting HashMap.
+/// Bytes kept of a looked-up name, with its NUL: a DNS name is at most 253
+/// characters, and a power of two keeps the copy maskable.
+pub const DNS_NAME_LEN: usize = 256;
+
+/// `DnsEvent::source`: `getaddrinfo(node, service, hints, res)`.
+pub const DNS_SRC_GETADDRINFO: u32 = 0;
+
+/// `DnsEvent::source`: `gethostbyname(name)`.
+pub const DNS_SRC_GETHOSTBYNAME: u32 = 1;
+
+/// One name lookup through libc, from call to return.
+///
+/// The name is read on entry, while the caller's pointer is known to be
+/// valid; the entry half waits in a map keyed by pid_tgid until the
+/// uretprobe fills in `ret` and `latency_ns`. At 320 bytes the event does
+/// not fit on the BPF stack; build it in a per-CPU scratch map.
+///
+/// Lookups that bypass libc (Go binaries, `dig`, systemd-resolved's own
+/// queries) never reach these probes: the packet side of `dns` sees them.
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct SyscallKey {
-    /// Process ID (0 for system-wide)
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct DnsEvent {
+    /// Timestamp of the return, in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// Time from call to return
+    pub latency_ns: u64,
+    /// Process ID (tgid)
     pub pid: u32,
-    pub _pad: u32,
-    /// System call number
-    pub syscall_nr: u64,
+    /// Thread ID
+    pub tid: u32,
+    /// getaddrinfo: 0 or an `EAI_*` code (negative in glibc).
+    /// gethostbyname: 0, or -1 when it returned NULL
+    pub ret: i32,
+    /// `DNS_SRC_GETADDRINFO` or `DNS_SRC_GETHOSTBYNAME`
+    pub source: u32,
+    /// `STR_*` flags for `name`
+    pub flags: u32,
+    /// Real user ID, as in `SyscallEvent`
+    pub uid: u32,
+    /// Process command name (null-padded)
+    pub comm: [u8; COMM_LEN],
+    /// Name asked for, as passed (may be an address literal or empty)
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub name: [u8; DNS_NAME_LEN],
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps the size a multiple of 8 without implicit padding; always 0
+    pub _reserved: u32,
 }
 
-impl SyscallKey {
-    pub const fn new(pid: u32, syscall_nr: u64) -> Self {
+impl DnsEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
         Self {
-            pid,
-            _pad: 0,
-            syscall_nr,
+            timestamp_ns: 0,
+            latency_ns: 0,
+            pid: 0,
+            tid: 0,
+            ret: 0,
+            source: DNS_SRC_GETADDRINFO,
+            flags: 0,
+            uid: 0,
+            comm: [0u8; COMM_LEN],
+            name: [0u8; DNS_NAME_LEN],
+            abi: EVENT_ABI,
+            _reserved: 0,
         }
     }
 }
 
-impl Default for SyscallKey {
+impl Default for DnsEvent {
     fn default() -> Self {
-        Self::new(0, 0)
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Process Events (Lesson 52, `trace --proc-events`)
+// =============================================================================
+
+/// `ProcEvent::kind` of `sched/sched_process_fork`: a new task.
+pub const PROC_FORK: u32 = 0;
+
+/// `ProcEvent::kind` of `sched/sched_process_exec`: a new program.
+pub const PROC_EXEC: u32 = 1;
+
+/// `ProcEvent::kind` of `sched/sched_process_exit`: a process is gone.
+pub const PROC_EXIT: u32 = 2;
+
+/// One fork, exec or exit, as seen by the `procevents` programs.
+///
+/// The three tracepoints share one struct and one perf array, so userspace
+/// prints them in the order they happened on each CPU. Which fields are set
+/// depends on `kind`:
+///
+/// | kind         | `pid`, `comm`         | also set                          |
+/// |--------------|-----------------------|-----------------------------------|
+/// | `PROC_FORK`  | the parent            | `child_pid`                       |
+/// | `PROC_EXEC`  | the task after exec   | `filename`, `flags`               |
+/// | `PROC_EXIT`  | the exiting process   | `exit_code`                       |
+///
+/// All ids are the host's. `pidns_inum` is the namespace the programs
+/// compared against `PROC_PIDNS`; 0 when it could not be read (no BTF).
+///
+/// The event is 320 bytes: build it in a scratch slot.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "user",
+    derive(
+        serde::Serialize,
+        serde::Deserialize,
+        bytemuck::Pod,
+        bytemuck::Zeroable
+    )
+)]
+pub struct ProcEvent {
+    /// Timestamp in nanoseconds (from bpf_ktime_get_ns)
+    pub timestamp_ns: u64,
+    /// One of the `PROC_*` constants
+    pub kind: u32,
+    /// Process ID (tgid)
+    pub pid: u32,
+    /// Thread ID
+    pub tid: u32,
+    /// Parent process ID (`real_parent->tgid`); 0 if unknown
+    pub ppid: u32,
+    /// `PROC_FORK`: the new task's ID. A thread shows up here too: the
+    /// tracepoint does not say which it is
+    pub child_pid: u32,
+    /// PID namespace inode of `pid`, as in `/proc/<pid>/ns/pid`
+    pub pidns_inum: u32,
+    /// `PROC_EXIT`: raw `task->exit_code` (status << 8 | signal)
+    pub exit_code: i32,
+    /// `STR_*` flags for `filename`
+    pub flags: u32,
+    /// `EVENT_ABI` of the build that made the event; set by `new()`
+    pub abi: u32,
+    /// Keeps `comm` 8-byte aligned without implicit padding; always 0
+    pub _reserved: u32,
+    /// Process command name (null-padded); after exec, the new program's
+    pub comm: [u8; COMM_LEN],
+    /// `PROC_EXEC`: the path the program was started from
+    #[cfg_attr(feature = "user", serde(with = "crate::serde_array"))]
+    pub filename: [u8; PATH_LEN],
+}
+
+impl ProcEvent {
+    /// Create a zeroed event (for initialization in eBPF programs).
+    pub const fn new() -> Self {
+        Self {
+            timestamp_ns: 0,
+            kind: PROC_FORK,
+            pid: 0,
+            tid: 0,
+            ppid: 0,
+            child_pid: 0,
+            pidns_inum: 0,
+            exit_code: 0,
+            flags: 0,
+            abi: EVENT_ABI,
+            _reserved: 0,
+            comm: [0u8; COMM_LEN],
+            filename: [0u8; PATH_LEN],
+        }
+    }
+}
+
+impl Default for ProcEvent {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -97,10 +2101,11 @@ impl Default for SyscallKey {
 
 // TODO (Lesson 05 - Uprobes): Add FunctionEvent struct
 // Hints:
-// - pid, tid, timestamp_ns (like SyscallEvent)
+// - pid, tid, cgroup_id, timestamp_ns (like SyscallEvent)
 // - ip: u64 (instruction pointer)
 // - is_return: u8 (0 = entry, 1 = return)
 // - comm: [u8; COMM_LEN]
+// - abi: u32, set to EVENT_ABI by new() like every event
 //
 // #[repr(C)]
 // #[derive(Debug, Clone, Copy)]
@@ -110,15 +2115,19 @@ impl Default for SyscallKey {
 
 // TODO (Lesson 06 - Tracepoints): Add TracepointEvent struct
 // Hints:
-// - Basic fields: pid, tid, timestamp_ns, comm
+// - Basic fields: pid, tid, cgroup_id, timestamp_ns, comm
 // - category: [u8; 32] (e.g., "sched", "syscalls")
 // - name: [u8; 64] (e.g., "sched_process_exec")
+// - filename: [u8; PATH_LEN] for sys_enter_openat, copied with the bounded
+//   readers in ebpf-tool-ebpf's strings.rs (Lesson 37)
+// - abi: u32, set to EVENT_ABI by new() like every event
 
 // TODO (Lesson 07 - Perf Sampling): Add PerfSampleEvent struct
 // Hints:
-// - pid, tid, timestamp_ns, comm
+// - pid, tid, cgroup_id, timestamp_ns, comm
 // - cpu: u32 (which CPU the sample was taken on)
 // - ip: u64 (instruction pointer at sample time)
+// - abi: u32, set to EVENT_ABI by new() like every event
 
 // =============================================================================
 // Tests - Learners implement these as they progress
@@ -135,12 +2144,12 @@ mod tests {
         //
         // Hints:
         // - Use core::mem::size_of::<SyscallEvent>()
-        // - Expected: 4 + 4 + 8 + 8 + 16 = 40 bytes (may have padding)
+        // - Expected: 4 + 4 + 8 + 8 + 8 + 16 + 6 * 4 = 72 bytes (may have padding)
         // - Use core::mem::align_of::<SyscallEvent>() to check alignment
         //
         // Why this matters: eBPF and userspace must agree on struct layout
 
-        todo!("Verify SyscallEvent size is between 40-48 bytes")
+        todo!("Verify SyscallEvent size is 72 bytes")
     }
 
     #[test]
@@ -168,6 +2177,247 @@ mod tests {
         todo!("Test SyscallKey construction")
     }
 
+    #[test]
+    #[ignore] // Enable when starting Lesson 09
+    fn test_lifecycle_event_layout() {
+        // TODO (Lesson 09): Verify LifecycleEvent layout
+        //
+        // Hints:
+        // - Expected: 4 + 4 + 4 + 4 + 8 + 8 + 16 + 4 + 4 = 56 bytes, no padding
+        // - The u64 fields must be 8-byte aligned (check with core::mem::offset_of!)
+        // - LifecycleEvent::new().kind should equal LIFECYCLE_EXIT
+
+        todo!("Verify LifecycleEvent size, alignment and defaults")
+    }
+
+    #[test]
+    #[ignore] // Enable when adding --event faults in Lesson 07
+    fn test_fault_key_new() {
+        // TODO (Lesson 07): Test FaultKey construction and layout
+        //
+        // Hints:
+        // - let key = FaultKey::new(1234, FAULT_MAJOR, 42);
+        // - Expected size: 4 + 4 + 8 = 16 bytes, no padding
+        // - FaultKey::default().user_stack_id should be -1
+
+        todo!("Test FaultKey construction")
+    }
+
+    #[test]
+    #[ignore] // Enable when starting Lesson 10
+    fn test_net_counters_layout() {
+        // TODO (Lesson 10): Verify NetCounters layout
+        //
+        // Hints:
+        // - Expected: 8 + 8 = 16 bytes
+        // - NetCounters::default() should equal NetCounters::new()
+        // - CGROUP_NET_INGRESS and CGROUP_NET_EGRESS must differ
+
+        todo!("Verify NetCounters size and defaults")
+    }
+
+    #[test]
+    #[ignore] // Enable when adding the comm cache in Lesson 08
+    fn test_comm_update_event_layout() {
+        // TODO (Lesson 08): Verify CommUpdateEvent layout
+        //
+        // Hints:
+        // - Expected: 4 + 4 + 4 + 16 + 4 = 32 bytes, alignment 4
+        // - CommUpdateEvent::new().comm should be all zeros
+        // - COMM_UPDATE_RENAME and COMM_UPDATE_EXEC must differ
+
+        todo!("Verify CommUpdateEvent size and defaults")
+    }
+
+    #[test]
+    #[ignore] // Enable when starting Lesson 11
+    fn test_lsm_types_layout() {
+        // TODO (Lesson 11): Verify InodeKey and LsmDenyEvent layout
+        //
+        // Hints:
+        // - InodeKey: 8 + 8 = 16 bytes, no padding (it is a map key, so
+        //   padding bytes would make equal keys hash differently)
+        // - LsmDenyEvent: 4 + 4 + 16 + 16 + 4 + 4 = 48 bytes, alignment 8
+        // - InodeKey::new(1, 2) should equal InodeKey { dev: 1, ino: 2 }
+
+        todo!("Verify InodeKey and LsmDenyEvent layout")
+    }
+
+    #[test]
+    #[ignore] // Enable when starting Lesson 12
+    fn test_task_record_layout() {
+        // TODO (Lesson 12): Verify TaskRecord layout
+        //
+        // Hints:
+        // - 6 * 4 + 8 + 16 + 4 + 4 = 56 bytes, alignment 8
+        // - The 24 bytes of u32 fields put cgroup_id on an 8-byte boundary,
+        //   so there is no hidden padding
+        // - Userspace splits the iterator output into 56-byte chunks, so a
+        //   size change here must be matched by a rebuild of both sides
+
+        todo!("Verify TaskRecord layout")
+    }
+
+    #[test]
+    #[ignore] // Enable when starting Lesson 14
+    fn test_sock_event_layout() {
+        // TODO (Lesson 14): Verify SockKey and SockEvent layout
+        //
+        // Hints:
+        // - SockKey: 8 bytes, alignment 4 (two u32, usable as a HashMap key)
+        // - SockEvent: 4 * 4 + 8 + 8 + 4 + 4 = 40 bytes, alignment 8
+        // - `ret` is signed: -11 (EAGAIN) from a non-blocking read must
+        //   survive the trip through the perf buffer
+
+        todo!("Verify SockKey and SockEvent layout")
+    }
+
+    #[test]
+    #[ignore] // Enable when starting Lesson 17
+    fn test_mount_event_layout() {
+        // TODO (Lesson 17): Verify MountEvent layout
+        //
+        // Hints:
+        // - 4 * 4 + 3 * 8 + COMM_LEN + MOUNT_FSTYPE_LEN + MOUNT_SOURCE_LEN
+        //   + MOUNT_TARGET_LEN + 4 + 4 = 272 bytes, alignment 8
+        // - MOUNT_KIND_* are distinct
+        // - `ret` is signed: -16 (EBUSY) from a busy umount must survive
+
+        todo!("Verify MountEvent layout")
+    }
+
+    #[test]
+    #[ignore] // Enable when adding batching in Lesson 08
+    fn test_event_batch() {
+        // TODO (Lesson 08): Verify EventBatch layout and bookkeeping
+        //
+        // Hints:
+        // - size_of::<SyscallBatch>() == 8 + SYSCALL_BATCH_LEN * 72 = 1160,
+        //   alignment 8: userspace checks perf records against this size
+        // - push() succeeds SYSCALL_BATCH_LEN times, then returns false and
+        //   leaves count at SYSCALL_BATCH_LEN
+        // - events() returns the pushed events in order; after clear() it is
+        //   empty and first_timestamp_ns() is None
+        // - A batch with count = 1000 (corrupt) still returns N events
+
+        todo!("Verify EventBatch")
+    }
+
+    #[test]
+    #[ignore] // Enable when starting Lesson 23
+    fn test_syscall_exit_layout() {
+        // TODO (Lesson 23): Verify PendingSyscall and SyscallExitEvent layout
+        //
+        // Hints:
+        // - PendingSyscall: 8 + 8 = 16 bytes
+        // - SyscallExitEvent: 4 + 4 + 4 * 8 + 4 + 4 = 48 bytes, alignment 8, no
+        //   padding (capture.rs records it byte for byte)
+        // - `ret` is signed: -2 (ENOENT) from a failed openat must survive
+
+        todo!("Verify PendingSyscall and SyscallExitEvent layout")
+    }
+
+    #[test]
+    fn test_syscall_table() {
+        // Sorted by number, or syscall_name's binary search misses entries
+        assert!(SYSCALLS.windows(2).all(|w| w[0].0 < w[1].0));
+        if SYSCALL_TABLE_ARCH == "x86_64" {
+            assert_eq!(syscall_name(0), Some("read"));
+            assert_eq!(syscall_name(257), Some("openat"));
+            assert_eq!(syscall_nr("exit_group"), Some(231));
+        }
+        if SYSCALL_TABLE_ARCH == "aarch64" {
+            assert_eq!(syscall_name(56), Some("openat"));
+            assert_eq!(syscall_nr("exit_group"), Some(94));
+        }
+        assert_eq!(syscall_name(100_000), None);
+    }
+
+    #[test]
+    #[ignore] // Enable after implementing the multi-probe programs in Lesson 25
+    fn test_probe_event_layout() {
+        // TODO (Lesson 25): Verify ProbeEvent layout
+        //
+        // Hints:
+        // - 4 * 4 + 8 + 8 + 16 + 6 * 4 = 72 bytes, alignment 8, no padding
+        //   (the same size as SyscallEvent)
+        // - The PROBE_KIND_* constants are distinct
+
+        todo!("Verify ProbeEvent layout")
+    }
+
+    #[test]
+    fn test_events_carry_abi() {
+        assert_eq!(EVENT_ABI >> 16, EVENT_MAGIC as u32);
+        assert_eq!(EVENT_ABI & 0xffff, EVENT_VERSION as u32);
+        // new() is where the eBPF programs get the field from
+        assert_eq!(SyscallEvent::new().abi, EVENT_ABI);
+        assert_eq!(SyscallBatch::new().abi, EVENT_ABI);
+        assert_eq!(SyscallBatch::new().events[0].abi, EVENT_ABI);
+        assert_eq!(LifecycleEvent::new().abi, EVENT_ABI);
+        assert_eq!(CommUpdateEvent::new().abi, EVENT_ABI);
+        assert_eq!(LsmDenyEvent::new().abi, EVENT_ABI);
+        assert_eq!(TaskRecord::new().abi, EVENT_ABI);
+        assert_eq!(SockEvent::new().abi, EVENT_ABI);
+        assert_eq!(MountEvent::new().abi, EVENT_ABI);
+        assert_eq!(SyscallExitEvent::new().abi, EVENT_ABI);
+        assert_eq!(ProbeEvent::new().abi, EVENT_ABI);
+        assert_eq!(NetEvent::new().abi, EVENT_ABI);
+        assert_eq!(ExecEvent::new().abi, EVENT_ABI);
+        assert_eq!(DnsEvent::new().abi, EVENT_ABI);
+    }
+
+    #[test]
+    fn test_string_fields() {
+        // The eBPF programs bound lengths with a mask
+        assert!(PATH_LEN.is_power_of_two() && ARG_LEN.is_power_of_two());
+        assert!(DNS_NAME_LEN.is_power_of_two());
+        assert_eq!(c_str(b"tmpfs\0\0\0"), b"tmpfs");
+        assert_eq!(c_str(b"\0"), b"");
+        assert_eq!(c_str(b"no-nul"), b"no-nul");
+
+        let mut event = ExecEvent::new();
+        event.argv[..3].copy_from_slice(b"ls\0");
+        event.argv[ARG_LEN..ARG_LEN + 3].copy_from_slice(b"-l\0");
+        assert_eq!(argv_entry(&event.argv, 0), Some(&b"ls"[..]));
+        assert_eq!(argv_entry(&event.argv, 1), Some(&b"-l"[..]));
+        assert_eq!(argv_entry(&event.argv, 2), Some(&b""[..]));
+        assert_eq!(argv_entry(&event.argv, MAX_ARGS), None);
+
+        // Both string-carrying events stay free of implicit padding
+        assert_eq!(core::mem::size_of::<ExecEvent>(), 832);
+        assert_eq!(core::mem::size_of::<ProbeEvent>(), 72 + PROBE_ARG_LEN);
+        assert_eq!(core::mem::size_of::<DnsEvent>(), 64 + DNS_NAME_LEN);
+        assert_eq!(core::mem::size_of::<OpenEvent>(), 72 + PATH_LEN);
+        assert_eq!(core::mem::size_of::<ProcEvent>(), 64 + PATH_LEN);
+    }
+
+    #[test]
+    fn test_kernel_offsets_start_unknown() {
+        // The eBPF global and a failed BTF lookup both look like this, so
+        // every reader can tell "not looked up" from offset 0
+        let offsets = KernelOffsets::unknown();
+        assert_eq!(offsets, KernelOffsets::default());
+        assert_eq!(offsets.task_real_parent, OFFSET_UNKNOWN);
+        assert_eq!(offsets.rss_stat_count, OFFSET_UNKNOWN);
+        // Only u32 fields: no padding for the loader to get wrong
+        assert_eq!(core::mem::size_of::<KernelOffsets>(), 40 * 4);
+        assert_eq!(core::mem::size_of::<NsIds>(), 6 * 4);
+    }
+
+    #[test]
+    #[ignore] // Enable after implementing the netsnoop programs in Lesson 32
+    fn test_net_event_layout() {
+        // TODO (Lesson 32): Verify NetEvent layout
+        //
+        // Hints:
+        // - 8 + 4 * 4 + 4 * 1 + 2 * 2 + 2 * 4 + 16 + 4 + 4 = 64 bytes, alignment 8,
+        //   no padding
+        // - NET_PROTO_* match libc::IPPROTO_ICMP and libc::IPPROTO_UDP
+
+        todo!("Verify NetEvent layout")
+    }
+
     #[test]
     #[ignore] // Enable after implementing FunctionEvent in Lesson 05
     fn test_function_event() {
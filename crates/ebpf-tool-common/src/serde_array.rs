@@ -0,0 +1,103 @@
+//! `#[serde(with = "crate::serde_array")]` for arrays serde cannot derive:
+//! longer than 32 elements, or of a const generic length.
+//!
+//! Arrays are written as tuples, like serde's own impls for short arrays, so
+//! the JSON of `[u8; 128]` is a list of 128 numbers.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use bytemuck::Zeroable;
+use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+pub fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for item in array {
+        tuple.serialize_element(item)?;
+    }
+    tuple.end()
+}
+
+pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Zeroable + Copy,
+{
+    struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+    where
+        T: Deserialize<'de> + Zeroable + Copy,
+    {
+        type Value = [T; N];
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an array of length {N}")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[T; N], A::Error> {
+            let mut array = [T::zeroed(); N];
+            for (i, slot) in array.iter_mut().enumerate() {
+                *slot = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<T>()?.is_some() {
+                return Err(A::Error::invalid_length(N + 1, &self));
+            }
+            Ok(array)
+        }
+    }
+
+    deserializer.deserialize_tuple(N, ArrayVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MountEvent, SyscallBatch, SyscallEvent, MOUNT_TARGET_LEN};
+
+    #[test]
+    fn test_long_arrays_roundtrip() {
+        let mut event = MountEvent::new();
+        event.target[..4].copy_from_slice(b"/mnt");
+        event.target[MOUNT_TARGET_LEN - 1] = b'x';
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MountEvent>(&json).unwrap().target,
+            event.target
+        );
+
+        let mut batch = SyscallBatch::new();
+        batch.push(SyscallEvent {
+            pid: 42,
+            ..SyscallEvent::new()
+        });
+        let json = serde_json::to_string(&batch).unwrap();
+        let back: SyscallBatch = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.count, 1);
+        assert_eq!(back.events()[0].pid, 42);
+    }
+
+    #[test]
+    fn test_wrong_length_is_an_error() {
+        let json = serde_json::to_string(&MountEvent::new()).unwrap();
+        let short = json.replacen("[0,", "[", 1);
+        assert!(serde_json::from_str::<MountEvent>(&short).is_err());
+    }
+
+    #[test]
+    fn test_pod_cast() {
+        let event = SyscallEvent {
+            pid: 7,
+            ..SyscallEvent::new()
+        };
+        let bytes = bytemuck::bytes_of(&event);
+        let back: SyscallEvent = bytemuck::pod_read_unaligned(bytes);
+        assert_eq!(back.pid, 7);
+    }
+}
@@ -0,0 +1,116 @@
+//! Batched Syscall Events for High Event Rates
+//!
+//! One `PerfEventArray::output()` per syscall is fine at a few thousand
+//! events per second. Above ~100k/s the per-call cost (helper call, perf
+//! header, wakeup check) dominates the tracer and the buffers start to drop
+//! events. Each CPU therefore measures its own rate and switches between two
+//! paths:
+//!
+//! ```text
+//!                    ┌─ rate <= BATCH_RATE_THRESHOLD ─► EVENTS (one SyscallEvent)
+//! emit_syscall() ────┤
+//!                    └─ rate >  BATCH_RATE_THRESHOLD ─► BATCH_SCRATCH[cpu].push()
+//!                                                        full or older than
+//!                                                        BATCH_MAX_AGE_NS
+//!                                                        ─► SYSCALL_BATCHES
+//! ```
+//!
+//! Events from one CPU stay in order: a partly filled batch is flushed
+//! before the CPU sends single events again. Whatever is left in
+//! `BATCH_SCRATCH` when tracing stops is read by userspace directly.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 08**: Combining Everything - "Batching at High Rates"
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/08-combining.md`
+//! - Tests: `crates/ebpf-tool/tests/tracer_test.rs`
+//! - Types: `ebpf_tool_common::EventBatch`, `SyscallBatch`, `BATCH_*`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    helpers::bpf_ktime_get_ns,
+    macros::map,
+    maps::{PerCpuArray, PerfEventArray},
+    EbpfContext,
+};
+use ebpf_tool_common::{
+    SyscallBatch, SyscallEvent, BATCH_MAX_AGE_NS, BATCH_RATE_THRESHOLD, BATCH_WINDOW_NS,
+};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Full (or aged-out) batches for userspace.
+#[map]
+static SYSCALL_BATCHES: PerfEventArray<SyscallBatch> = PerfEventArray::new(0);
+
+/// The batch each CPU is filling. 1160 bytes: too big for the stack.
+#[map]
+pub static BATCH_SCRATCH: PerCpuArray<SyscallBatch> = PerCpuArray::with_max_entries(1, 0);
+
+/// Per-CPU rate window: when the current window started, and how many
+/// events it has seen so far.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RateWindow {
+    pub start_ns: u64,
+    pub events: u64,
+}
+
+#[map]
+static BATCH_RATE: PerCpuArray<RateWindow> = PerCpuArray::with_max_entries(1, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Send `event` to userspace, one by one through `single` or in batches
+/// through `SYSCALL_BATCHES`, depending on this CPU's current event rate.
+///
+/// Programs that used to call `single.output(ctx, &event, 0)` call this
+/// instead; userspace reads both arrays.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn emit_syscall<C: EbpfContext>(
+    ctx: &C,
+    event: &SyscallEvent,
+    single: &PerfEventArray<SyscallEvent>,
+) {
+    // TODO: Implement in Lesson 08 ("Batching at High Rates")
+    //
+    // [ ] Callers apply sampling first (crate::sample::sample_keep, Lesson
+    //     40): to this function, a sampled-out event never happened
+    // [ ] rate = BATCH_RATE.get_ptr_mut(0); batch = BATCH_SCRATCH.get_ptr_mut(0)
+    //     (both always exist: index 0 of a per-CPU array). If either is
+    //     missing, fall back to single.output()
+    // [ ] now = event.timestamp_ns. If now - rate.start_ns > BATCH_WINDOW_NS,
+    //     start a new window (start_ns = now, events = 0); then events += 1
+    // [ ] Below BATCH_RATE_THRESHOLD: flush() a non-empty batch first, so
+    //     this CPU's events stay in order, then single.output(ctx, event, 0)
+    // [ ] Otherwise: if the batch is older than BATCH_MAX_AGE_NS
+    //     (first_timestamp_ns()), flush() it; push(*event); if is_full(),
+    //     flush()
+    let _ = (ctx, event, single);
+
+    todo!("Implement emit_syscall")
+}
+
+/// Output this CPU's batch if it holds any events, and empty it.
+#[allow(dead_code)]
+#[inline(always)]
+fn flush<C: EbpfContext>(ctx: &C, batch: &mut SyscallBatch) {
+    // TODO: Implement in Lesson 08 ("Batching at High Rates")
+    //
+    // [ ] Nothing to do when batch.count == 0
+    // [ ] SYSCALL_BATCHES.output(ctx, batch, 0), then batch.clear()
+    // [ ] The whole 1160-byte struct is copied even when it is half full;
+    //     userspace only looks at events()
+    let _ = (ctx, batch);
+
+    todo!("Implement flush")
+}
@@ -0,0 +1,119 @@
+//! eBPF Programs for Per-Cgroup Network Accounting (cgroup_skb)
+//!
+//! This module contains two `BPF_PROG_TYPE_CGROUP_SKB` programs. Unlike
+//! kprobes and tracepoints, these are not attached to a kernel function or
+//! event. They are attached to a *cgroup v2 directory* and run for every
+//! packet sent or received by a socket owned by a process in that cgroup
+//! (or any of its descendants).
+//!
+//! ```text
+//!           /sys/fs/cgroup/my-container
+//!                      │  attach (BPF_CGROUP_INET_INGRESS / _EGRESS)
+//!        ┌─────────────┴─────────────┐
+//!        ▼                           ▼
+//!  cgroup_skb_ingress          cgroup_skb_egress
+//!        │                           │
+//!        └──────► CGROUP_NET_STATS ◄─┘
+//!                 [0] ingress  { bytes, packets }
+//!                 [1] egress   { bytes, packets }
+//! ```
+//!
+//! The return value is a verdict: `1` lets the packet through, `0` drops it.
+//! This lesson only counts, so both programs always return `1`. The same
+//! hook is how container runtimes implement per-container egress policy.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 10**: cgroup_skb - per-container network byte/packet counters
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/10-cgroup-skb.md`
+//! - Tests: `crates/ebpf-tool/tests/cgroup_net_test.rs`
+//! - Attached programs: `bpftool cgroup tree /sys/fs/cgroup`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{cgroup_skb, map},
+    maps::PerCpuArray,
+    programs::SkBuffContext,
+};
+use ebpf_tool_common::{NetCounters, CGROUP_NET_EGRESS, CGROUP_NET_INGRESS};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Per-direction counters, indexed by `CGROUP_NET_INGRESS` / `CGROUP_NET_EGRESS`.
+///
+/// A `PerCpuArray` gives every CPU its own copy of each entry, so the
+/// programs can increment without atomic operations. Userspace reads all
+/// per-CPU values and sums them.
+#[map]
+static CGROUP_NET_STATS: PerCpuArray<NetCounters> = PerCpuArray::with_max_entries(2, 0);
+
+// =============================================================================
+// cgroup_skb/ingress
+// =============================================================================
+
+/// Count packets received by sockets in the attached cgroup.
+///
+/// # Example Userspace Attachment
+///
+/// ```rust,ignore
+/// let cgroup = std::fs::File::open("/sys/fs/cgroup/my-container")?;
+/// let program: &mut CgroupSkb = bpf.program_mut("cgroup_skb_ingress")?.try_into()?;
+/// program.load()?;
+/// program.attach(&cgroup, CgroupSkbAttachType::Ingress, CgroupAttachMode::Single)?;
+/// ```
+#[cgroup_skb]
+pub fn cgroup_skb_ingress(ctx: SkBuffContext) -> i32 {
+    // TODO: Implement in Lesson 10
+    // Lesson: docs/04-ebpf/10-cgroup-skb.md
+    // Tests: crates/ebpf-tool/tests/cgroup_net_test.rs
+    //
+    // Implementation steps:
+    // 1. Get the per-CPU slot:
+    //    CGROUP_NET_STATS.get_ptr_mut(CGROUP_NET_INGRESS)
+    // 2. If present: (*slot).bytes += ctx.len() as u64; (*slot).packets += 1
+    // 3. Return 1 (allow) - returning 0 would drop the packet
+    //
+    // Starter code:
+    //   count(&ctx, CGROUP_NET_INGRESS);
+    //   1
+    let _ = &ctx;
+
+    todo!("Implement cgroup_skb_ingress - see docs/04-ebpf/10-cgroup-skb.md")
+}
+
+// =============================================================================
+// cgroup_skb/egress
+// =============================================================================
+
+/// Count packets sent by sockets in the attached cgroup.
+///
+/// Attached with `CgroupSkbAttachType::Egress`. Otherwise identical to
+/// [`cgroup_skb_ingress`].
+#[cgroup_skb]
+pub fn cgroup_skb_egress(ctx: SkBuffContext) -> i32 {
+    // TODO: Implement in Lesson 10
+    // Lesson: docs/04-ebpf/10-cgroup-skb.md
+    //
+    // Same as ingress, but index CGROUP_NET_EGRESS.
+    let _ = &ctx;
+
+    todo!("Implement cgroup_skb_egress - see docs/04-ebpf/10-cgroup-skb.md")
+}
+
+/// Shared helper: add one packet of `ctx.len()` bytes to `CGROUP_NET_STATS[index]`.
+#[allow(dead_code)]
+#[inline(always)]
+fn count(ctx: &SkBuffContext, index: u32) {
+    // TODO: Implement in Lesson 10
+    // Hints:
+    // - if let Some(slot) = CGROUP_NET_STATS.get_ptr_mut(index) { unsafe { ... } }
+    // - No locking needed: each CPU only touches its own copy
+    let _ = (ctx, index);
+    todo!("Implement per-direction counter update")
+}
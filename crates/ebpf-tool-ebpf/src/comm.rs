@@ -0,0 +1,152 @@
+//! eBPF Programs for Process Name (comm) Changes
+//!
+//! The syscall tracer (Lesson 08) caches `pid -> comm` in userspace: it
+//! remembers the comm from the first event it sees for a PID. A process can
+//! change its name after that, and then the cache shows the old one:
+//!
+//! - `execve()` replaces the program; the comm becomes the new binary's name
+//! - `prctl(PR_SET_NAME)` and writes to `/proc/<pid>/task/<tid>/comm` rename
+//!   a thread in place (shells, JVMs and worker pools do this constantly)
+//!
+//! These two tracepoints report every change, so long-running traces show
+//! the names processes have now.
+//!
+//! ```text
+//! execve("/usr/bin/curl")              prctl(PR_SET_NAME, "worker-3")
+//!        │                                         │
+//!        ▼                                         ▼
+//! begin_new_exec()                        __set_task_comm()
+//!        │  └──► __set_task_comm() ──► tracepoint task/task_rename
+//!        ▼                                         │
+//! tracepoint sched/sched_process_exec              ▼
+//!        │                                   COMM_UPDATES
+//!        └────────────────────────────────────────►▲
+//! ```
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 08**: Combining Everything - keep the tracer's comm cache fresh
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/08-combining.md`
+//! - Tests: `crates/ebpf-tool/tests/tracer_test.rs`
+//! - Format files: `/sys/kernel/debug/tracing/events/{task/task_rename,sched/sched_process_exec}/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{map, tracepoint},
+    maps::PerfEventArray,
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{CommUpdateEvent, COMM_UPDATE_EXEC, COMM_UPDATE_RENAME};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Perf event array for comm changes.
+///
+/// Userspace reads it next to the syscall event array and updates its
+/// `pid -> comm` cache before it prints the next syscall.
+#[map]
+static COMM_UPDATES: PerfEventArray<CommUpdateEvent> = PerfEventArray::new(0);
+
+// =============================================================================
+// task/task_rename
+// =============================================================================
+
+/// Tracepoint for thread renames.
+///
+/// # Tracepoint Format (task_rename)
+///
+/// ```text
+/// field:pid_t pid;           offset:8;  size:4;  signed:1;
+/// field:char oldcomm[16];    offset:12; size:16; signed:0;
+/// field:char newcomm[16];    offset:28; size:16; signed:0;
+/// field:short oom_score_adj; offset:44; size:2;  signed:1;
+/// ```
+///
+/// Recent kernels dropped the `pid` field, so `newcomm` is at offset 24
+/// there. Read the format file on your machine before you hardcode an offset.
+///
+/// # Important: the name has not changed yet
+///
+/// The tracepoint fires just before the kernel copies the new name into
+/// `task->comm`, so `bpf_get_current_comm()` still returns the *old* name.
+/// Always read `newcomm` from the tracepoint.
+///
+/// # Example Userspace Attachment
+///
+/// ```rust,ignore
+/// let program: &mut TracePoint = bpf.program_mut("task_rename_tracepoint")?.try_into()?;
+/// program.load()?;
+/// program.attach("task", "task_rename")?;
+/// ```
+#[tracepoint]
+pub fn task_rename_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 08
+    // Lesson: docs/04-ebpf/08-combining.md
+    // Tests: crates/ebpf-tool/tests/tracer_test.rs
+    //
+    // Implementation steps:
+    // 1. let pid_tgid = bpf_get_current_pid_tgid();
+    //    pid = (pid_tgid >> 32) as u32, tid = pid_tgid as u32
+    //    (/proc/<pid>/task/<tid>/comm may only be written from the same
+    //    thread group, so the tgid is always the current one)
+    // 2. Fill a CommUpdateEvent with kind = COMM_UPDATE_RENAME
+    // 3. comm: bpf_probe_read_kernel_buf(ctx.as_ptr().add(NEWCOMM_OFFSET), &mut event.comm)
+    // 4. COMM_UPDATES.output(&ctx, &event, 0)
+    //
+    // Starter code:
+    //   match try_task_rename(&ctx) {
+    //       Ok(ret) => ret,
+    //       Err(_) => 0,
+    //   }
+    let _ = &ctx;
+
+    todo!("Implement task_rename_tracepoint - see docs/04-ebpf/08-combining.md")
+}
+
+// =============================================================================
+// sched/sched_process_exec
+// =============================================================================
+
+/// Tracepoint fired after a successful exec.
+///
+/// # Tracepoint Format (sched_process_exec)
+///
+/// ```text
+/// field:__data_loc char[] filename; offset:8;  size:4; signed:1;
+/// field:pid_t pid;                  offset:12; size:4; signed:1;
+/// field:pid_t old_pid;              offset:16; size:4; signed:1;
+/// ```
+///
+/// # Why also trace exec?
+///
+/// exec already passes through `__set_task_comm()`, so `task_rename` sees
+/// the new name. This event covers the cases `task_rename` does not:
+///
+/// - A non-leader thread that calls execve() takes over the thread group,
+///   so its tid changes from `old_pid` to `pid`
+/// - exec starts a new program. Userspace should drop everything it cached
+///   about the old one (comm, resolved symbols), not only the name
+///
+/// By the time this tracepoint runs, `task->comm` already holds the new
+/// name, so `bpf_get_current_comm()` is correct here.
+#[tracepoint]
+pub fn sched_process_exec_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 08
+    // Lesson: docs/04-ebpf/08-combining.md
+    // Tests: crates/ebpf-tool/tests/tracer_test.rs
+    //
+    // Implementation steps:
+    // 1. let pid: i32 = unsafe { ctx.read_at(12)? };
+    // 2. Fill a CommUpdateEvent with kind = COMM_UPDATE_EXEC, tid = pid
+    // 3. comm = bpf_get_current_comm()?
+    // 4. COMM_UPDATES.output(&ctx, &event, 0)
+    let _ = &ctx;
+
+    todo!("Implement sched_process_exec_tracepoint - see docs/04-ebpf/08-combining.md")
+}
@@ -0,0 +1,214 @@
+//! eBPF Programs for DNS Lookups (libc uprobes and a socket filter)
+//!
+//! A name lookup can be seen at two levels, and `ebpf-tool dns` uses both:
+//!
+//! ```text
+//! curl ─► getaddrinfo("example.com") ─► (nss, /etc/hosts, resolver) ─► return 0 / EAI_*
+//!           │ dns_getaddrinfo                                   │ dns_lookup_ret
+//!           └─► Slot::Event ─► DNS_INFLIGHT.insert(pid_tgid) ───┴─► DNS_EVENTS
+//!
+//! resolver ─► sendto(fd, query, 53) ─► ... ─► dev_queue_xmit ─► AF_PACKET socket
+//!                                                        dns_packet_filter: port 53?
+//!                                                        keep ─► userspace reads the frame
+//! ```
+//!
+//! - **libc uprobes** see the question a program asked, with its PID, its
+//!   comm and the answer libc gave, including names answered from
+//!   `/etc/hosts` that never reach the network. They miss programs that do
+//!   not resolve through libc (Go, `dig`, statically linked binaries)
+//! - **The socket filter** sees every DNS packet on the wire, whoever sent
+//!   it, with the server's response code. It runs in packet context and
+//!   has no process: userspace finds the owner of the client port
+//!
+//! A socket filter (`BPF_PROG_TYPE_SOCKET_FILTER`) is attached to one
+//! socket with `SO_ATTACH_BPF`, not to a hook in the kernel. For every
+//! packet that socket would receive, the return value is how many bytes to
+//! pass on: 0 drops the packet for this socket only, the original is not
+//! touched. On an `AF_PACKET` socket that is every frame of the network
+//! namespace, so the filter keeps the copies to userspace down to DNS.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 41**: DNS Tracer - uprobes and packet filters together
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/41-dns-tracer.md`
+//! - Tests: `crates/ebpf-tool/tests/dns_test.rs`
+//! - Signatures: `man 3 getaddrinfo`, `man 3 gethostbyname`; packet
+//!   offsets: `man 7 packet`, RFC 1035 section 4.1
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use crate::scratch::{self, Slot};
+use crate::strings::read_user_field;
+use aya_ebpf::{
+    macros::{map, socket_filter, uprobe, uretprobe},
+    maps::{HashMap, PerfEventArray},
+    programs::{ProbeContext, RetProbeContext, SkBuffContext},
+};
+use ebpf_tool_common::{
+    DnsEvent, DNS_NAME_LEN, DNS_SRC_GETADDRINFO, DNS_SRC_GETHOSTBYNAME, STR_NAME_TRUNCATED,
+};
+
+/// UDP port of DNS servers.
+const DNS_PORT: u16 = 53;
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Finished lookups for userspace, in per-CPU order.
+#[map]
+static DNS_EVENTS: PerfEventArray<DnsEvent> = PerfEventArray::new(0);
+
+/// Lookups between call and return, keyed by pid_tgid.
+///
+/// getaddrinfo() can block for seconds (a timeout per nameserver), and a
+/// thread only runs one lookup at a time.
+#[map]
+static DNS_INFLIGHT: HashMap<u64, DnsEvent> = HashMap::with_max_entries(1024, 0);
+
+// =============================================================================
+// libc uprobes
+// =============================================================================
+
+/// Record a `getaddrinfo()` call.
+///
+/// # Arguments (`int getaddrinfo(const char *node, const char *service, const struct addrinfo *hints, struct addrinfo **res)`)
+///
+/// - `ctx.arg::<*const u8>(0)`: the name; NULL when only a service is
+///   looked up ("localhost" for the port of "http")
+#[uprobe]
+pub fn dns_getaddrinfo(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 41
+    // Lesson: docs/04-ebpf/41-dns-tracer.md
+    // Tests: crates/ebpf-tool/tests/dns_test.rs
+    //
+    // Implementation steps:
+    // 1. start_lookup(&ctx, DNS_SRC_GETADDRINFO)
+    // 2. Return 0 whatever happened: a uprobe's return value is ignored
+    let _ = &ctx;
+
+    todo!("Implement dns_getaddrinfo - see docs/04-ebpf/41-dns-tracer.md")
+}
+
+/// Record a `gethostbyname()` call.
+///
+/// # Arguments (`struct hostent *gethostbyname(const char *name)`)
+///
+/// Obsolete, but still used by older programs and scripts; glibc's
+/// `gethostbyname2()` and the `_r` variants are separate symbols.
+#[uprobe]
+pub fn dns_gethostbyname(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 41
+    // Lesson: docs/04-ebpf/41-dns-tracer.md
+    //
+    // Same as dns_getaddrinfo: start_lookup(&ctx, DNS_SRC_GETHOSTBYNAME)
+    let _ = &ctx;
+
+    todo!("Implement dns_gethostbyname - see docs/04-ebpf/41-dns-tracer.md")
+}
+
+/// Finish a lookup and send it. Attached as the uretprobe of both
+/// functions: the inflight entry already says which one it was.
+#[uretprobe]
+pub fn dns_lookup_ret(ctx: RetProbeContext) -> u32 {
+    // TODO: Implement in Lesson 41
+    // Lesson: docs/04-ebpf/41-dns-tracer.md
+    //
+    // Implementation steps:
+    // 1. let pid_tgid = bpf_get_current_pid_tgid();
+    //    event = DNS_INFLIGHT.get_ptr_mut(&pid_tgid); return 0 if missing
+    //    (the tracer started during the call)
+    // 2. now = bpf_ktime_get_ns(); latency_ns = now - timestamp_ns (the
+    //    entry time), then timestamp_ns = now
+    // 3. ret: for DNS_SRC_GETADDRINFO ctx.ret::<i32>() as is; for
+    //    DNS_SRC_GETHOSTBYNAME a pointer: 0 if non-NULL, -1 if NULL
+    // 4. DNS_EVENTS.output(&ctx, &*event, 0), then DNS_INFLIGHT.remove(&pid_tgid)
+    let _ = &ctx;
+
+    todo!("Implement dns_lookup_ret - see docs/04-ebpf/41-dns-tracer.md")
+}
+
+// =============================================================================
+// Socket filter
+// =============================================================================
+
+/// Keep DNS packets (UDP, port 53 on either side) for the `AF_PACKET`
+/// socket this is attached to; drop everything else.
+///
+/// # Return Value
+///
+/// Bytes of the packet to pass to the socket: `ctx.len()` keeps it whole,
+/// 0 drops it (for this socket only).
+///
+/// # Example Userspace Attachment
+///
+/// ```rust,ignore
+/// let program: &mut SocketFilter = bpf.program_mut("dns_packet_filter")?.try_into()?;
+/// program.load()?;
+/// program.attach(&socket)?; // socket(AF_PACKET, SOCK_RAW, htons(ETH_P_ALL))
+/// ```
+#[socket_filter]
+pub fn dns_packet_filter(ctx: SkBuffContext) -> i64 {
+    // TODO: Implement in Lesson 41
+    // Lesson: docs/04-ebpf/41-dns-tracer.md
+    //
+    // Implementation steps:
+    // 1. On an AF_PACKET socket the data starts at the Ethernet header:
+    //    ethertype = u16::from_be(ctx.load::<u16>(12)?); one 802.1Q tag
+    //    (0x8100) moves everything by 4 bytes
+    // 2. IPv4 (0x0800): protocol at +9 must be 17 (UDP); the UDP header
+    //    starts at +((byte 0 & 0x0f) * 4). IPv6 (0x86dd): next header at +6
+    //    must be 17, UDP at +40 (extension headers: drop, DNS never uses them)
+    // 3. sport = UDP +0, dport = UDP +2 (big-endian): keep if either is
+    //    DNS_PORT
+    // 4. Every ctx.load() can fail (short packet): treat a failure as drop
+    // 5. Return ctx.len() as i64 to keep, 0 to drop
+    //
+    // Starter code:
+    //   match is_dns(&ctx) {
+    //       Ok(true) => ctx.len() as i64,
+    //       _ => 0,
+    //   }
+    let _ = &ctx;
+
+    todo!("Implement dns_packet_filter - see docs/04-ebpf/41-dns-tracer.md")
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Copy the name argument into a new `DnsEvent` and park it in
+/// `DNS_INFLIGHT` until the function returns.
+#[allow(dead_code)]
+#[inline(always)]
+fn start_lookup(ctx: &ProbeContext, source: u32) -> Result<(), i64> {
+    // TODO: Implement in Lesson 41
+    //
+    // [ ] event = scratch::get::<DnsEvent>(Slot::Event).ok_or(0)?; *event = DnsEvent::new()
+    //     (320 bytes, too big for the stack)
+    // [ ] source, timestamp_ns = bpf_ktime_get_ns() (entry time for now),
+    //     pid/tid from bpf_get_current_pid_tgid(), uid from
+    //     bpf_get_current_uid_gid() as u32, comm from bpf_get_current_comm()
+    // [ ] name: a NULL pointer leaves it empty; otherwise
+    //     flags |= read_user_field(ctx.arg(0), &mut event.name, STR_NAME_TRUNCATED)
+    // [ ] DNS_INFLIGHT.insert(&pid_tgid, &*event, 0)
+    let _ = (ctx, source);
+    todo!("Implement start_lookup")
+}
+
+/// Whether the frame in `ctx` is a UDP packet to or from port 53.
+#[allow(dead_code)]
+#[inline(always)]
+fn is_dns(ctx: &SkBuffContext) -> Result<bool, i64> {
+    // TODO: Implement in Lesson 41
+    //
+    // [ ] Steps 1-3 of dns_packet_filter, with `?` on every ctx.load()
+    // [ ] Offsets are constants or a masked IHL: the verifier only accepts
+    //     loads it can bound
+    let _ = (ctx, DNS_PORT);
+    todo!("Implement is_dns")
+}
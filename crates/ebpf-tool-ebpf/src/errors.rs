@@ -0,0 +1,94 @@
+//! eBPF Program for Syscall Errors (`ebpf-tool errors`)
+//!
+//! `trace --returns` shows each failing call, which is too much when a
+//! process retries the same denied open a thousand times a second. This
+//! program only counts: every syscall exit with a negative return value
+//! adds one to the key (pid, errno, syscall):
+//!
+//! ```text
+//! raw_syscalls/sys_exit ─► ret in -4095..=-1?  no ─► return
+//!                       ─► ERRORS_CONFIG[ERRORS_CONFIG_CGROUP] set and
+//!                          not the current cgroup?  ─► return
+//!                       ─► SYSCALL_ERRORS[ErrorKey { tgid, -ret, id }] += 1
+//! ```
+//!
+//! Unlike `syscall_exit_tracepoint`, it needs no entry program: `id` and
+//! `ret` are both in the exit tracepoint, and nothing is paired. Userspace
+//! reads the map every interval and reports the difference, like `sched`.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 50**: Syscall Errors - errno storms per process
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/50-syscall-errors.md`
+//! - Tests: `crates/ebpf-tool/tests/errors_test.rs`
+//! - Format file: `/sys/kernel/debug/tracing/events/raw_syscalls/sys_exit/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_cgroup_id, bpf_get_current_pid_tgid},
+    macros::{map, tracepoint},
+    maps::{Array, LruPerCpuHashMap},
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{ErrorKey, ERRORS_CONFIG_CGROUP, ERRORS_MAX_ENTRIES};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Failed exits per (pid, errno, syscall).
+///
+/// Per-CPU, so the hot path needs no atomic add: an errno storm is many
+/// CPUs hitting the same key. LRU, so processes that exited are evicted
+/// before the map stops taking new keys.
+#[map]
+static SYSCALL_ERRORS: LruPerCpuHashMap<ErrorKey, u64> =
+    LruPerCpuHashMap::with_max_entries(ERRORS_MAX_ENTRIES, 0);
+
+/// Options written by userspace before attaching (`ERRORS_CONFIG_CGROUP`).
+#[map]
+static ERRORS_CONFIG: Array<u64> = Array::with_max_entries(1, 0);
+
+// =============================================================================
+// raw_syscalls/sys_exit
+// =============================================================================
+
+/// Count a failed syscall.
+///
+/// # Tracepoint Format (raw_syscalls/sys_exit)
+///
+/// ```text
+/// field:long id;   offset:8;  size:8; signed:1;
+/// field:long ret;  offset:16; size:8; signed:1;
+/// ```
+///
+/// A separate program from `syscall_exit_tracepoint`: that one only runs
+/// for traced entries and sends events, this one runs for every exit and
+/// sends nothing. Both can be attached to the same tracepoint.
+#[tracepoint]
+pub fn syscall_errors_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 50
+    // Lesson: docs/04-ebpf/50-syscall-errors.md
+    // Tests: crates/ebpf-tool/tests/errors_test.rs
+    //
+    // Implementation steps:
+    // 1. let ret: i64 = ctx.read_at(16)?; return 0 unless ret is in
+    //    -4095..=-1. Larger negative values are addresses (mmap), not
+    //    errors; check this first, since most exits succeed
+    // 2. If ERRORS_CONFIG.get(ERRORS_CONFIG_CGROUP) is set and non-zero,
+    //    return 0 unless it equals bpf_get_current_cgroup_id()
+    // 3. let id: i64 = ctx.read_at(8)?; -1 is an exit without a syscall
+    //    (a ptrace-skipped call): skip it
+    // 4. key = ErrorKey { pid: (bpf_get_current_pid_tgid() >> 32) as u32,
+    //    errno: (-ret) as u32, syscall_nr: id as u64 }
+    // 5. SYSCALL_ERRORS.get_ptr_mut(&key): add 1, or insert 1 with
+    //    BPF_NOEXIST when the key is new. A failed insert (another CPU won
+    //    the race) only loses this one count: ignore it
+    let _ = &ctx;
+
+    todo!("Implement syscall_errors_tracepoint - see docs/04-ebpf/50-syscall-errors.md")
+}
@@ -0,0 +1,130 @@
+//! eBPF fentry/fexit Programs (BTF Trampolines)
+//!
+//! These are the fentry/fexit versions of the kprobe programs in
+//! [`crate::kprobe`]. They trace the same kernel functions through a different
+//! mechanism:
+//!
+//! ```text
+//! kprobe:   call do_sys_openat2 ──► INT3 ──► exception ──► save pt_regs ──► BPF
+//!
+//! fentry:   call do_sys_openat2 ──► patched `call` ──► BPF trampoline ──► BPF
+//!                                        (direct call, no exception)
+//! ```
+//!
+//! | | kprobe | fentry / fexit |
+//! |---|--------|----------------|
+//! | Mechanism | breakpoint + exception | direct call into a BPF trampoline |
+//! | Overhead | higher | close to a normal function call |
+//! | Arguments | raw registers (`pt_regs`) | typed, checked against BTF |
+//! | Return value | needs a separate kretprobe | fexit sees arguments **and** return value |
+//! | Requirements | any kernel | BTF (`/sys/kernel/btf/vmlinux`) + trampoline support (x86_64 5.5+, arm64 6.0+) |
+//!
+//! Userspace picks between this module and `kprobe.rs` with
+//! `ebpf-tool kprobe --mode fentry|kprobe`, and falls back to kprobe when
+//! fentry cannot be loaded.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 02**: Reading Kernel Data - the "fentry/fexit" section
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/02-reading-data.md`
+//! - Tests: `crates/ebpf-tool/tests/kprobe_test.rs`
+//! - Check a target has BTF: `bpftool btf dump file /sys/kernel/btf/vmlinux | grep "FUNC 'do_sys_openat2'"`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{fentry, fexit, map},
+    maps::PerfEventArray,
+    programs::{FEntryContext, FExitContext},
+};
+use ebpf_tool_common::SyscallEvent;
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Perf event array for fentry/fexit events.
+///
+/// Same event type as the kprobe `EVENTS` map, so userspace prints both
+/// modes with the same code. Map names must be unique in the object file,
+/// which is why this one has its own name.
+#[map]
+static FENTRY_EVENTS: PerfEventArray<SyscallEvent> = PerfEventArray::new(0);
+
+// =============================================================================
+// fentry: function entry
+// =============================================================================
+
+/// fentry program: runs when the traced kernel function is entered.
+///
+/// There is no target in the macro. Userspace chooses the function at load
+/// time, the same way `kprobe <function>` does:
+///
+/// ```rust,ignore
+/// let btf = Btf::from_sys_fs()?;
+/// let program: &mut FEntry = bpf.program_mut("syscall_fentry")?.try_into()?;
+/// program.load(&function, &btf)?;  // fails here if there is no BTF FUNC for it
+/// program.attach()?;
+/// ```
+///
+/// # Reading arguments
+///
+/// `ctx.arg::<T>(n)` reads argument `n` directly. Unlike kprobes, there are
+/// no `pt_regs` and no per-architecture register names. For
+/// `do_sys_openat2(int dfd, const char __user *filename, struct open_how *how)`,
+/// `ctx.arg::<i32>(0)` is `dfd`.
+#[fentry]
+pub fn syscall_fentry(ctx: FEntryContext) -> u32 {
+    // TODO: Implement in Lesson 02 (fentry/fexit section)
+    // Lesson: docs/04-ebpf/02-reading-data.md
+    // Tests: crates/ebpf-tool/tests/kprobe_test.rs
+    //
+    // Implementation steps:
+    // 1. Build a SyscallEvent exactly as in try_syscall_kprobe()
+    //    (pid/tid, comm, cgroup_id, timestamp_ns)
+    // 2. syscall_nr: leave 0; fentry on a kernel function has no syscall number
+    // 3. FENTRY_EVENTS.output(&ctx, &event, 0)
+    //
+    // Starter code:
+    //   match try_syscall_fentry(&ctx) {
+    //       Ok(ret) => ret,
+    //       Err(_) => 0,
+    //   }
+    let _ = &ctx;
+
+    todo!("Implement syscall_fentry - see docs/04-ebpf/02-reading-data.md")
+}
+
+// =============================================================================
+// fexit: function exit
+// =============================================================================
+
+/// fexit program: runs when the traced kernel function returns.
+///
+/// # Arguments and return value
+///
+/// fexit sees the original arguments *and* the return value. The return
+/// value comes right after the last argument, so for a function with N
+/// arguments it is `ctx.arg::<T>(N)`. For `do_sys_openat2` (3 arguments), the
+/// new file descriptor or `-errno` is `ctx.arg::<i64>(3)`.
+///
+/// A kprobe needs a separate kretprobe for this, and a kretprobe no longer
+/// has the arguments.
+#[fexit]
+pub fn syscall_fexit(ctx: FExitContext) -> u32 {
+    // TODO: Implement in Lesson 02 (fentry/fexit section)
+    // Lesson: docs/04-ebpf/02-reading-data.md
+    // Tests: crates/ebpf-tool/tests/kprobe_test.rs
+    //
+    // Implementation steps:
+    // 1. Same event as syscall_fentry
+    // 2. Optional: read the return value and skip failed calls (ret < 0)
+    //    to show only successful opens
+    // 3. FENTRY_EVENTS.output(&ctx, &event, 0)
+    let _ = &ctx;
+
+    todo!("Implement syscall_fexit - see docs/04-ebpf/02-reading-data.md")
+}
@@ -0,0 +1,183 @@
+//! eBPF Programs for Following a Process Tree (`trace --follow-children`)
+//!
+//! `trace --process bash` matches the name of each process. The commands a
+//! shell starts have their own names (`ls`, `make`, `cc1`), so a name alone
+//! never shows what a shell, a build or a service manager spawns. With
+//! `--follow-children`, the kernel keeps the set of processes to trace in
+//! `FOLLOW_PIDS`, and every fork of a member adds the child:
+//!
+//! ```text
+//! userspace: /proc scan ──► FOLLOW_PIDS { bash: MATCHED }
+//!
+//! bash ──fork──► 4300 ──exec──► make ──fork──► 4301 ──exec──► cc1
+//!        │                                │
+//!  follow_fork: parent followed    follow_fork: parent followed
+//!  ──► 4300: CHILD                 ──► 4301: CHILD
+//!
+//! exit of 4301 ──► follow_exit ──► removed
+//! syscall of 4301 ──► is_followed(4301)? ──► traced
+//! ```
+//!
+//! `follow_exec` adds processes that exec a program with the `--process`
+//! name later, so `bash` started after the trace is followed as well.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 44**: Following Children - process trees in a kernel map
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/44-follow-children.md`
+//! - Tests: `crates/ebpf-tool/tests/follow_test.rs`
+//! - Config: `ebpf_tool_common::FILTER_KEY_FOLLOW` in `FILTER_CONFIG`
+//! - Format files: `/sys/kernel/tracing/events/sched/{sched_process_fork,sched_process_exec,sched_process_exit}/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_comm, bpf_get_current_pid_tgid},
+    macros::{map, tracepoint},
+    maps::{Array, HashMap},
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{CommPrefix, FOLLOW_CHILD, FOLLOW_MATCHED, FOLLOW_MAX_ENTRIES};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Processes (by tgid) to trace, with why: `FOLLOW_MATCHED` or
+/// `FOLLOW_CHILD`.
+///
+/// Seeded by userspace from /proc, then kept current by the three programs
+/// below.
+#[map]
+static FOLLOW_PIDS: HashMap<u32, u8> = HashMap::with_max_entries(FOLLOW_MAX_ENTRIES, 0);
+
+/// The kernel part of the `--process` pattern, for `follow_exec`
+/// (`CommMatch::kernel_filter()`, Lesson 45).
+#[map]
+static FOLLOW_COMM: Array<CommPrefix> = Array::with_max_entries(1, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Whether the process `tgid` is in the followed tree.
+///
+/// Called by the syscall entry program (and `send_probe_event`) when
+/// `FILTER_CONFIG[FILTER_KEY_FOLLOW]` is 1, next to the cgroup check.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn is_followed(tgid: u32) -> bool {
+    // TODO: Implement in Lesson 44
+    //
+    // [ ] unsafe { FOLLOW_PIDS.get(&tgid) }.is_some()
+    // [ ] Only the tgid: threads share their process' entry, so a thread
+    //     started after the seed is traced without an insert
+    let _ = tgid;
+    todo!("Implement is_followed")
+}
+
+// =============================================================================
+// sched/sched_process_fork
+// =============================================================================
+
+/// Tracepoint fired in the parent for every new task.
+///
+/// # Tracepoint Format (sched_process_fork)
+///
+/// ```text
+/// field:char parent_comm[16]; offset:8;  size:16; signed:0;
+/// field:pid_t parent_pid;     offset:24; size:4;  signed:1;
+/// field:char child_comm[16];  offset:28; size:16; signed:0;
+/// field:pid_t child_pid;      offset:44; size:4;  signed:1;
+/// ```
+///
+/// Some recent kernels store the comms as `__data_loc char[]`, which moves
+/// the pids. Read the format file on your machine before you hardcode an
+/// offset.
+///
+/// # Processes and threads
+///
+/// `child_pid` is the new task's tid. For a new process (fork, vfork,
+/// clone without CLONE_THREAD) it is also its tgid; for a new thread it is
+/// not, and the thread is already covered by its process' entry.
+#[tracepoint]
+pub fn follow_fork(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 44
+    // Lesson: docs/04-ebpf/44-follow-children.md
+    // Tests: crates/ebpf-tool/tests/follow_test.rs
+    //
+    // Implementation steps:
+    // 1. parent = (bpf_get_current_pid_tgid() >> 32) as u32: the current
+    //    task is the parent, so this is its process, not just its thread
+    // 2. Not is_followed(parent): return 0. This is the common case and
+    //    must stay one lookup
+    // 3. child: i32 = ctx.read_at(44)?
+    // 4. FOLLOW_PIDS.insert(&(child as u32), &FOLLOW_CHILD, 0). A new
+    //    thread's tid gets an entry too, which follow_exit removes again;
+    //    telling threads apart would need the clone flags
+    // 5. A full map: the insert fails, and the child is not traced. Keep
+    //    going; userspace reports occupancy at the end
+    let _ = &ctx;
+
+    todo!("Implement follow_fork - see docs/04-ebpf/44-follow-children.md")
+}
+
+// =============================================================================
+// sched/sched_process_exec
+// =============================================================================
+
+/// Tracepoint fired after a successful exec: add processes that now run a
+/// program with the `--process` name.
+///
+/// A second program on the same tracepoint as the comm cache's
+/// `sched_process_exec_tracepoint`; both run on every exec.
+#[tracepoint]
+pub fn follow_exec(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 44
+    // Lesson: docs/04-ebpf/44-follow-children.md
+    //
+    // Implementation steps:
+    // 1. prefix = FOLLOW_COMM.get(0); nothing there (len 0): return 0
+    // 2. comm = bpf_get_current_comm()? (already the new name here, as in
+    //    comm.rs)
+    // 3. crate::maps::comm_has_prefix(&comm, prefix): insert the tgid with
+    //    FOLLOW_MATCHED. A name's prefix includes its NUL, so this is an
+    //    exact match for names and a prefix match for `NAME.*`
+    // 4. A process that was a CHILD keeps being followed after exec either
+    //    way: do not remove anything here
+    let _ = &ctx;
+
+    todo!("Implement follow_exec - see docs/04-ebpf/44-follow-children.md")
+}
+
+// =============================================================================
+// sched/sched_process_exit
+// =============================================================================
+
+/// Tracepoint fired for every exiting task: forget it, so a recycled PID
+/// is not traced by mistake.
+///
+/// # Tracepoint Format (sched_process_exit)
+///
+/// ```text
+/// field:char comm[16]; offset:8;  size:16; signed:0;
+/// field:pid_t pid;     offset:24; size:4;  signed:1;
+/// ```
+#[tracepoint]
+pub fn follow_exit(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 44
+    // Lesson: docs/04-ebpf/44-follow-children.md
+    //
+    // Implementation steps:
+    // 1. pid: i32 = ctx.read_at(24)? (the exiting task's tid)
+    // 2. FOLLOW_PIDS.remove(&(pid as u32)); ignore "not found", the
+    //    common case
+    // 3. Removing by tid: a thread only removes its own entry (from
+    //    follow_fork), and the process entry goes with the main thread
+    let _ = &ctx;
+
+    todo!("Implement follow_exit - see docs/04-ebpf/44-follow-children.md")
+}
@@ -39,7 +39,8 @@
 //! # Lessons in This Module
 //!
 //! - **Lesson 01**: Hello Kprobe - Basic kprobe that logs when triggered
-//! - **Lesson 02**: Reading Kernel Data - Extract syscall arguments and process info
+//! - **Lesson 02**: Reading Kernel Data - Extract process info, and the arguments
+//!   of `do_sys_openat2` (`openat2_kprobe`)
 //!
 //! # References
 //!
@@ -68,7 +69,10 @@ use aya_ebpf::{
     macros::kprobe,
     programs::ProbeContext,
     // TODO (Lesson 02): Add these imports for reading kernel data
-    // helpers::{bpf_get_current_comm, bpf_get_current_pid_tgid, bpf_ktime_get_ns},
+    // helpers::{
+    //     bpf_get_current_cgroup_id, bpf_get_current_comm, bpf_get_current_pid_tgid,
+    //     bpf_ktime_get_ns,
+    // },
 };
 
 // TODO (Lesson 01): Uncomment for logging support
@@ -79,7 +83,8 @@ use aya_ebpf::{
 //     macros::map,
 //     maps::PerfEventArray,
 // };
-// use ebpf_tool_common::SyscallEvent;
+// use ebpf_tool_common::{OpenEvent, SyscallEvent, STR_PATH_TRUNCATED};
+// use crate::scratch::{self, Slot};
 
 // =============================================================================
 // eBPF Maps (Lesson 02+)
@@ -93,6 +98,11 @@ use aya_ebpf::{
 // #[map]
 // static EVENTS: PerfEventArray<SyscallEvent> = PerfEventArray::new(0);
 
+// TODO (Lesson 02): Add perf event array for the arguments of do_sys_openat2
+//
+// #[map]
+// static OPEN_EVENTS: PerfEventArray<OpenEvent> = PerfEventArray::new(0);
+
 // =============================================================================
 // Lesson 01: Hello Kprobe - Basic Kernel Function Tracing
 // =============================================================================
@@ -228,6 +238,8 @@ fn try_hello_kprobe(_ctx: ProbeContext) -> Result<u32, i64> {
 /// - `bpf_get_current_pid_tgid()`: Returns (PID << 32 | TID)
 /// - `bpf_get_current_comm()`: Gets process command name (up to 16 chars)
 /// - `bpf_ktime_get_ns()`: High-resolution timestamp
+/// - `bpf_get_current_cgroup_id()`: cgroup v2 id (directory inode) of the task
+/// - `bpf_get_current_uid_gid()`: Returns (GID << 32 | UID), host ids
 /// - `ctx.arg::<T>(n)`: Read the nth function argument
 ///
 /// ## Implementation Hints
@@ -247,8 +259,10 @@ fn try_hello_kprobe(_ctx: ProbeContext) -> Result<u32, i64> {
 ///     pid,
 ///     tid,
 ///     syscall_nr: 0,  // Populated if probing syscall entry
+///     cgroup_id: bpf_get_current_cgroup_id(),
 ///     timestamp_ns: bpf_ktime_get_ns(),
 ///     comm,
+///     ..SyscallEvent::new()  // ppid and namespaces: Lesson 33
 /// };
 /// EVENTS.output(&ctx, &event, 0);
 /// ```
@@ -290,8 +304,7 @@ pub fn syscall_kprobe(ctx: ProbeContext) -> u32 {
 /// 1. Get PID/TID using `bpf_get_current_pid_tgid()`
 /// 2. Get process name using `bpf_get_current_comm()`
 /// 3. Get timestamp using `bpf_ktime_get_ns()`
-/// 4. Optionally read syscall arguments from context
-/// 5. Create a `SyscallEvent` and send via `EVENTS` perf array
+/// 4. Create a `SyscallEvent` and send via `EVENTS` perf array
 #[allow(dead_code)]
 fn try_syscall_kprobe(_ctx: ProbeContext) -> Result<u32, i64> {
     // TODO: Implement in Lesson 02
@@ -312,33 +325,125 @@ fn try_syscall_kprobe(_ctx: ProbeContext) -> Result<u32, i64> {
     // 3. Get timestamp:
     //    let timestamp_ns = unsafe { bpf_ktime_get_ns() };
     //
-    // 4. Read syscall number (optional, depends on probe target):
-    //    let syscall_nr = try_read_syscall_args(&ctx)?;
+    // 4. syscall_nr stays 0 here: a kprobe on a kernel function has no
+    //    syscall number. The arguments of do_sys_openat2 are read by
+    //    openat2_kprobe into an OpenEvent (below)
     //
     // 5. Build and send event:
-    //    let event = SyscallEvent {
+    //    let mut event = SyscallEvent {
     //        pid,
     //        tid,
-    //        syscall_nr,
+    //        syscall_nr: 0,
+    //        cgroup_id: unsafe { bpf_get_current_cgroup_id() },
     //        timestamp_ns,
     //        comm,
+    //        ..SyscallEvent::new()
     //    };
     //    EVENTS.output(&ctx, &event, 0);
     //
+    //    Lesson 33 (docs/04-ebpf/33-container-events.md): fill the parent
+    //    and the namespaces before sending, with the Lesson 31 readers:
+    //    let task = crate::kstruct::Task::current();
+    //    event.ppid = task.parent_tgid().unwrap_or(0);
+    //    let ns = task.ns_ids().unwrap_or(NsIds::new());
+    //    event.pidns_inum = ns.pid; event.mntns_inum = ns.mnt;
+    //    (0 when the kernel has no BTF; userspace treats 0 as unknown)
+    //
+    //    Lesson 34 (docs/04-ebpf/34-user-filter.md): the user, no BTF needed:
+    //    let uid_gid = bpf_get_current_uid_gid();
+    //    event.uid = uid_gid as u32; event.gid = (uid_gid >> 32) as u32;
+    //
+    //    Lesson 45 (docs/04-ebpf/45-process-patterns.md): right after step 2,
+    //    if !crate::maps::comm_allowed(&comm) { return Ok(0); }, so other
+    //    processes cost no event at all
+    //
+    //    Lesson 51 (docs/04-ebpf/51-tail-calls.md): when
+    //    FILTER_CONFIG[FILTER_KEY_TAIL] is 1, call
+    //    crate::tailcall::dispatch(&ctx, &event) instead of sending, and
+    //    return Ok(0): the category handler sends it, or nobody does
+    //
     // 6. Return success:
     //    Ok(0)
 
     todo!("Implement try_syscall_kprobe - read kernel data and send event")
 }
 
+/// Kprobe on `do_sys_openat2` that sends its arguments as an `OpenEvent`.
+///
+/// `syscall_kprobe` works on any function because it only reads the
+/// current task. This one knows the signature of the function it is
+/// attached to:
+///
+/// ```text
+/// long do_sys_openat2(int dfd, const char __user *filename, struct open_how *how)
+///                         │                     │                           │
+///                    ctx.arg(0)            ctx.arg(1)                  ctx.arg(2)
+///                    event.dfd     bpf_probe_read_user_str      kstruct::open_how()
+///                                  event.filename               event.open_flags, mode, resolve
+/// ```
+///
+/// Attached to any other function it sends garbage, so userspace only
+/// loads it for `do_sys_openat2` (and its `.isra` / `.constprop` clones).
+///
+/// ## TDD Workflow
+///
+/// 1. Enable `test_kprobe_reads_function_arguments` in
+///    `crates/ebpf-tool/tests/kprobe_test.rs` (remove `#[ignore]`)
+/// 2. Implement this function and `try_read_syscall_args` (GREEN)
+/// 3. Verify with `sudo -E cargo test -p ebpf-tool --test kprobe_test`
+#[kprobe]
+pub fn openat2_kprobe(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 02 ("Reading the Arguments")
+    // Lesson: docs/04-ebpf/02-reading-data.md
+    //
+    // Starter code:
+    //   match unsafe { try_openat2_kprobe(&ctx) } {
+    //       Ok(ret) => ret,
+    //       Err(_) => 0,
+    //   }
+
+    let _ = ctx;
+
+    todo!("Implement openat2_kprobe - see docs/04-ebpf/02-reading-data.md")
+}
+
+/// Helper function for openat2_kprobe.
+///
+/// An `OpenEvent` is 328 bytes: too large to build next to the helpers'
+/// own stack use, so it is built in the `Slot::Event` scratch slot.
+#[allow(dead_code)]
+unsafe fn try_openat2_kprobe(_ctx: &ProbeContext) -> Result<u32, i64> {
+    // TODO: Implement in Lesson 02
+    //
+    // Implementation steps:
+    // 1. let event = scratch::get::<OpenEvent>(Slot::Event).ok_or(-12i64)?;
+    //    *event = OpenEvent::new();  (the slot holds the last event of this
+    //    CPU; new() also sets abi and dfd = AT_FDCWD)
+    //
+    // 2. Same process info as try_syscall_kprobe:
+    //    let pid_tgid = bpf_get_current_pid_tgid();
+    //    event.pid = (pid_tgid >> 32) as u32;
+    //    event.tid = pid_tgid as u32;
+    //    event.comm = bpf_get_current_comm()?;
+    //    event.timestamp_ns = bpf_ktime_get_ns();
+    //
+    // 3. try_read_syscall_args(ctx, event)?;
+    //
+    // 4. OPEN_EVENTS.output(ctx, event, 0);
+    //    Ok(0)
+
+    todo!("Implement try_openat2_kprobe - read the open arguments and send them")
+}
+
 // =============================================================================
 // Helper Functions for Reading Kernel Data
 // =============================================================================
 
-/// Helper to safely read syscall arguments from kprobe context.
+/// Read the three arguments of `do_sys_openat2` into `event`.
 ///
-/// When probing syscall entry points, the first argument is typically
-/// the syscall number (on x86_64, in the `orig_rax` register).
+/// Only a missing argument register is an error. A filename that cannot be
+/// read (paged out) sets `STR_READ_FAILED`, an `open_how` that cannot be
+/// read sets `how_status`: the event is still worth sending.
 ///
 /// # Safety
 ///
@@ -347,36 +452,39 @@ fn try_syscall_kprobe(_ctx: ProbeContext) -> Result<u32, i64> {
 ///
 /// # Arguments
 ///
-/// * `ctx` - The probe context containing register state
-///
-/// # Returns
-///
-/// * `Ok(syscall_nr)` - The system call number
-/// * `Err(errno)` - Error code if reading fails
+/// * `ctx` - The probe context of `do_sys_openat2`
+/// * `event` - The event to fill (`dfd`, `filename`, `flags`, the `open_how` fields)
 ///
 /// # Example
 ///
 /// ```ignore
-/// let syscall_nr = unsafe { try_read_syscall_args(&ctx)? };
+/// unsafe { try_read_syscall_args(&ctx, event)? };
 /// ```
 #[allow(dead_code)]
-unsafe fn try_read_syscall_args(_ctx: &ProbeContext) -> Result<u64, i64> {
+unsafe fn try_read_syscall_args(
+    _ctx: &ProbeContext,
+    _event: &mut ebpf_tool_common::OpenEvent,
+) -> Result<(), i64> {
     // TODO: Implement in Lesson 02
     // Lesson: docs/04-ebpf/02-reading-data.md
     //
-    // Hints:
-    // - Use ctx.arg::<u64>(0) to read first argument
-    // - Different kernel functions have different argument layouts
-    // - For syscall entry points, argument 0 is often the syscall number
-    //
-    // Example:
-    //   let arg0: u64 = ctx.arg(0).ok_or(-1i64)?;
-    //   Ok(arg0)
+    // [ ] event.dfd = ctx.arg::<i32>(0).ok_or(-22i64)?;
+    // [ ] let filename: *const u8 = ctx.arg(1).ok_or(-22i64)?;
+    //     event.flags |= crate::strings::read_user_field(filename, &mut event.filename, STR_PATH_TRUNCATED);
+    //     (a user pointer: bpf_probe_read_user_str, bounded to PATH_LEN)
+    // [ ] let how: *const core::ffi::c_void = ctx.arg(2).ok_or(-22i64)?;
+    //     match crate::kstruct::open_how(how) {
+    //         Ok((flags, mode, resolve)) => { event.open_flags = flags; event.mode = mode; event.resolve = resolve; }
+    //         Err(e) => event.how_status = e as i32,  (-2 without BTF for open_how)
+    //     }
+    // [ ] Ok(())
     //
-    // Note: The exact method depends on which kernel function you're probing.
-    // When probing sys_enter, you may need to access pt_regs differently.
+    // Note: ctx.arg(n) reads the nth argument register of the function
+    // (rdi, rsi, rdx on x86_64; x0-x2 on arm64). It is only right at the
+    // entry of the function: a kretprobe sees whatever the registers hold
+    // on return.
 
-    todo!("Read syscall arguments from ProbeContext")
+    todo!("Read the do_sys_openat2 arguments from ProbeContext")
 }
 
 /// Helper to get the current CPU ID.
@@ -0,0 +1,349 @@
+//! Kernel Struct Readers (task_struct, nsproxy, mm_struct)
+//!
+//! Programs often want more than the tracepoint or probe gives them: the
+//! parent PID, the namespaces of the task, how much memory it uses. All of
+//! that is in `task_struct` and the structs it points to, but at offsets
+//! that change from one kernel build to the next:
+//!
+//! ```text
+//! task_struct ──real_parent──► task_struct.tgid                 parent_tgid()
+//!     │
+//!     ├──nsproxy──► nsproxy ──net_ns──► net.ns.inum             ns_ids()
+//!     │                     ──mnt_ns──► mnt_namespace.ns.inum
+//!     │                     ── ... uts, ipc, cgroup
+//!     ├──thread_pid──► pid.numbers[pid.level].ns
+//!     │                    ──► pid_namespace.ns.inum            ns_ids().pid
+//!     └──mm──► mm_struct.rss_stat[MM_*]                         rss_pages()
+//! ```
+//!
+//! The offsets are not compiled in. Userspace looks them up in the running
+//! kernel's BTF and writes them into the `KERNEL_OFFSETS` global before the
+//! object is loaded (`crates/ebpf-tool/src/btf.rs`). That is what CO-RE
+//! relocations do for C programs: one object, correct on every kernel.
+//!
+//! Every reader returns `Err` instead of guessing when an offset is
+//! `OFFSET_UNKNOWN` or a pointer is NULL (kernel threads have no `mm`,
+//! exiting tasks no `nsproxy`). Callers put 0 in the event; userspace shows
+//! it as "?".
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 02**: Reading Kernel Data - `struct open_how` of `do_sys_openat2`
+//!   (needs `offsets()` and `read_at()` first; Lesson 31 explains them)
+//! - **Lesson 31**: Kernel Structs - parent PIDs and namespace ids on any kernel
+//! - **Lesson 32**: Netsnoop - the network namespace of a socket or packet
+//! - **Lesson 52**: Process Events - exit codes, and the PID namespace filter
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/31-kernel-structs.md`
+//! - Tests: `crates/ebpf-tool/src/btf.rs` (the offset lookup; the readers
+//!   are exercised by the tasks and mounts tests)
+//! - Inspect the layout: `bpftool btf dump file /sys/kernel/btf/vmlinux format c`
+//!   or `pahole task_struct`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::helpers::{bpf_get_current_task, bpf_probe_read_kernel};
+use core::ffi::c_void;
+use ebpf_tool_common::{
+    KernelOffsets, NsIds, MM_ANONPAGES, MM_FILEPAGES, MM_SHMEMPAGES, OFFSET_UNKNOWN,
+};
+
+// =============================================================================
+// Globals
+// =============================================================================
+
+/// Field offsets for the running kernel, written by the loader.
+///
+/// `#[no_mangle]` keeps the symbol name that `EbpfLoader::set_global` looks
+/// for (`KERNEL_OFFSETS_GLOBAL`). The value starts as all-unknown, so an
+/// object loaded without the lookup fails cleanly instead of reading
+/// garbage.
+#[no_mangle]
+static KERNEL_OFFSETS: KernelOffsets = KernelOffsets::unknown();
+
+/// The offsets as patched by userspace.
+#[allow(dead_code)]
+#[inline(always)]
+fn offsets() -> KernelOffsets {
+    // TODO: Implement in Lesson 31
+    //
+    // [ ] unsafe { core::ptr::read_volatile(&KERNEL_OFFSETS) }
+    // [ ] Why volatile: the compiler sees a `static` that is never written
+    //     and folds every field to OFFSET_UNKNOWN at compile time. The
+    //     volatile read forces a load from .rodata, which is where the
+    //     loader put the real values
+    todo!("Implement offsets")
+}
+
+// =============================================================================
+// Readers
+// =============================================================================
+
+/// Read a `T` at `base + offset` in kernel memory.
+#[allow(dead_code)]
+#[inline(always)]
+fn read_at<T>(base: *const c_void, offset: u32) -> Result<T, i64> {
+    // TODO: Implement in Lesson 31
+    //
+    // [ ] Err(-2) (ENOENT) if offset == OFFSET_UNKNOWN: the running kernel
+    //     has no such field
+    // [ ] Err(-14) (EFAULT) if base is NULL
+    // [ ] unsafe { bpf_probe_read_kernel((base as *const u8).add(offset as usize) as *const T) }
+    let _ = (base, offset);
+    todo!("Implement read_at")
+}
+
+/// A `task_struct` pointer with readers for the fields the lessons use.
+#[derive(Clone, Copy)]
+pub struct Task {
+    ptr: *const c_void,
+}
+
+#[allow(dead_code)]
+impl Task {
+    /// The task the program runs for (`bpf_get_current_task()`).
+    #[inline(always)]
+    pub fn current() -> Self {
+        // TODO: Implement in Lesson 31
+        //
+        // [ ] Self { ptr: unsafe { bpf_get_current_task() } as *const c_void }
+        todo!("Implement Task::current")
+    }
+
+    /// A task from a program argument or a BPF iterator context.
+    ///
+    /// # Safety
+    /// `ptr` must be a `task_struct` pointer (or NULL).
+    #[inline(always)]
+    pub unsafe fn from_ptr(ptr: *const c_void) -> Self {
+        Self { ptr }
+    }
+
+    /// Process ID (`tgid`).
+    #[inline(always)]
+    pub fn tgid(&self) -> Result<u32, i64> {
+        // TODO: Implement in Lesson 31
+        //
+        // [ ] read_at::<i32>(self.ptr, offsets().task_tgid), as u32
+        // [ ] For the current task, prefer bpf_get_current_pid_tgid() >> 32:
+        //     no memory read at all. This one is for other tasks
+        todo!("Implement Task::tgid")
+    }
+
+    /// The parent: `real_parent`, not `parent`, which is the tracer while
+    /// a process is being ptraced.
+    #[inline(always)]
+    pub fn parent(&self) -> Result<Task, i64> {
+        // TODO: Implement in Lesson 31
+        //
+        // [ ] let ptr: *const c_void = read_at(self.ptr, offsets().task_real_parent)?
+        // [ ] Ok(Task { ptr })
+        todo!("Implement Task::parent")
+    }
+
+    /// Parent process ID: `real_parent->tgid`. After the parent exits it
+    /// is the subreaper or init of the task's PID namespace, as in getppid().
+    #[inline(always)]
+    pub fn parent_tgid(&self) -> Result<u32, i64> {
+        // TODO: Implement in Lesson 31
+        //
+        // [ ] self.parent()?.tgid()
+        // [ ] This is the host PID. Inside a container getppid() shows the
+        //     number from the container's PID namespace (upid.nr at its level)
+        todo!("Implement Task::parent_tgid")
+    }
+
+    /// Namespace inodes, matching `/proc/<pid>/ns/*`.
+    ///
+    /// Fields that cannot be read are 0; the call only fails when
+    /// `nsproxy` is NULL (the task is exiting).
+    #[inline(always)]
+    pub fn ns_ids(&self) -> Result<NsIds, i64> {
+        // TODO: Implement in Lesson 31
+        //
+        // [ ] let o = offsets();
+        // [ ] let nsproxy: *const c_void = read_at(self.ptr, o.task_nsproxy)?;
+        //     Err if NULL (read_at fails on it at the next step anyway)
+        // [ ] One helper for the five nsproxy namespaces:
+        //     ns_inum(nsproxy, o.nsproxy_net_ns, o.net_ns_inum) =
+        //       read_at::<*const c_void>(nsproxy, member)
+        //         .and_then(|ns| read_at::<u32>(ns, inum_offset))
+        //         .unwrap_or(0)
+        // [ ] pid: not in nsproxy (nsproxy.pid_ns_for_children is the
+        //     namespace of *future* children). Follow thread_pid instead:
+        //     level = read_at::<u32>(pid, o.pid_level);
+        //     upid = pid + o.pid_numbers + level * o.upid_size;
+        //     read_at(upid, o.upid_ns), then o.pid_ns_inum
+        // [ ] The verifier needs level bounded: `if level > 32 { return ... }`
+        //     (MAX_PID_NS_LEVEL is 32)
+        todo!("Implement Task::ns_ids")
+    }
+
+    /// Resident set size in pages: file + anonymous + shmem, as in
+    /// `/proc/<pid>/statm` (second field). `Err` for kernel threads.
+    #[inline(always)]
+    pub fn rss_pages(&self) -> Result<u64, i64> {
+        // TODO: Implement in Lesson 31
+        //
+        // [ ] let o = offsets();
+        // [ ] let mm: *const c_void = read_at(self.ptr, o.task_mm)?  (NULL for
+        //     kernel threads: read_at returns Err)
+        // [ ] For each of MM_FILEPAGES, MM_ANONPAGES, MM_SHMEMPAGES:
+        //     read_at::<i64>(mm, o.mm_rss_stat + i * o.rss_stat_stride + o.rss_stat_count)
+        // [ ] Clamp negative counts to 0: since 6.2 the count is a per-CPU
+        //     counter's folded total, which can lag and briefly go below 0
+        // [ ] Bytes: pages * 4096 on x86_64 and most aarch64 configs;
+        //     userspace knows the real page size (sysconf(_SC_PAGESIZE))
+        todo!("Implement Task::rss_pages")
+    }
+
+    /// Raw `exit_code`: `status << 8 | signal`, as waitpid() reports it.
+    /// Only meaningful once the task is exiting (`sched_process_exit`).
+    #[inline(always)]
+    pub fn exit_code(&self) -> Result<i32, i64> {
+        // TODO: Implement in Lesson 09 (or Lesson 52, whichever comes first)
+        //
+        // [ ] read_at::<i32>(self.ptr, offsets().task_exit_code)
+        // [ ] do_exit() stores the code before the tracepoint fires, so the
+        //     current task's value is final here
+        todo!("Implement Task::exit_code")
+    }
+}
+
+// =============================================================================
+// Socket and Packet Readers (Lesson 32)
+// =============================================================================
+
+/// `net->ns.inum`: the inode of a network namespace, as in `/proc/<pid>/ns/net`.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn net_inum(net: *const c_void) -> Result<u32, i64> {
+    // TODO: Implement in Lesson 32
+    //
+    // [ ] read_at::<u32>(net, offsets().net_ns_inum)
+    let _ = net;
+    todo!("Implement net_inum")
+}
+
+// =============================================================================
+// Open Arguments (Lesson 02)
+// =============================================================================
+
+/// `how->flags`, `how->mode` and `how->resolve` of `do_sys_openat2`.
+///
+/// `struct open_how` is UAPI and has not changed since 5.6, but the
+/// offsets still come from BTF like every other field here: a kernel
+/// without BTF for it gives `Err(-2)`, not three guessed values.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn open_how(how: *const c_void) -> Result<(u64, u64, u64), i64> {
+    // TODO: Implement in Lesson 02 ("Reading open_how")
+    //
+    // [ ] let o = offsets();
+    // [ ] let flags = read_at::<u64>(how, o.open_how_flags)?;
+    // [ ] Same for mode (o.open_how_mode) and resolve (o.open_how_resolve)
+    // [ ] how is a kernel pointer (the syscall already copied the struct
+    //     from userspace), so read_at's bpf_probe_read_kernel is right;
+    //     bpf_probe_read_user would fail with -EFAULT
+    let _ = how;
+    todo!("Implement open_how")
+}
+
+/// A `struct sock` pointer (the first argument of `udp_sendmsg` and friends).
+#[derive(Clone, Copy)]
+pub struct Sock {
+    ptr: *const c_void,
+}
+
+#[allow(dead_code)]
+impl Sock {
+    /// # Safety
+    /// `ptr` must be a `struct sock` pointer (or NULL).
+    #[inline(always)]
+    pub unsafe fn from_ptr(ptr: *const c_void) -> Self {
+        Self { ptr }
+    }
+
+    /// Network namespace inode of the socket: the namespace it was created
+    /// in, even if the process has moved to another one since (setns).
+    #[inline(always)]
+    pub fn netns(&self) -> Result<u32, i64> {
+        // TODO: Implement in Lesson 32
+        //
+        // [ ] let net: *const c_void = read_at(self.ptr, offsets().sock_net)?
+        // [ ] net_inum(net)
+        // [ ] skc_net is a `possible_net_t`, a struct around one pointer;
+        //     the BTF lookup already went through it (`skc_net.net`)
+        todo!("Implement Sock::netns")
+    }
+
+    /// (local address, local port, peer address, peer port). Addresses are
+    /// in network byte order, ports in host byte order; the peer is 0 for
+    /// an unconnected socket.
+    #[inline(always)]
+    pub fn ipv4_addrs(&self) -> Result<(u32, u16, u32, u16), i64> {
+        // TODO: Implement in Lesson 32
+        //
+        // [ ] let o = offsets();
+        // [ ] read_at::<u16>(self.ptr, o.sock_family)? must be AF_INET (2);
+        //     Err(-97) (EAFNOSUPPORT) for IPv6 sockets
+        // [ ] saddr = read_at::<u32>(.., o.sock_rcv_saddr), daddr from o.sock_daddr
+        // [ ] sport = read_at::<u16>(.., o.sock_num): already host order
+        // [ ] dport = u16::from_be(read_at::<u16>(.., o.sock_dport)?): skc_dport
+        //     is stored as on the wire
+        todo!("Implement Sock::ipv4_addrs")
+    }
+}
+
+/// A `struct sk_buff` pointer (the argument of `icmp_rcv`).
+#[derive(Clone, Copy)]
+pub struct SkBuff {
+    ptr: *const c_void,
+}
+
+#[allow(dead_code)]
+impl SkBuff {
+    /// # Safety
+    /// `ptr` must be a `struct sk_buff` pointer (or NULL).
+    #[inline(always)]
+    pub unsafe fn from_ptr(ptr: *const c_void) -> Self {
+        Self { ptr }
+    }
+
+    /// Network namespace inode of the device the packet arrived on.
+    #[inline(always)]
+    pub fn netns(&self) -> Result<u32, i64> {
+        // TODO: Implement in Lesson 32
+        //
+        // [ ] let dev: *const c_void = read_at(self.ptr, offsets().skb_dev)?
+        // [ ] let net: *const c_void = read_at(dev, offsets().netdev_net)?
+        // [ ] net_inum(net)
+        // [ ] Not skb->sk: a received packet has no socket yet
+        todo!("Implement SkBuff::netns")
+    }
+
+    /// Kernel address of the IP header (`head + network_header`).
+    #[inline(always)]
+    pub fn network_header(&self) -> Result<*const u8, i64> {
+        // TODO: Implement in Lesson 32
+        //
+        // [ ] let head: *const u8 = read_at(self.ptr, offsets().skb_head)?
+        // [ ] let off: u16 = read_at(self.ptr, offsets().skb_network_header)?
+        // [ ] Ok(head.add(off as usize)); read the header itself with
+        //     bpf_probe_read_kernel, it is kernel memory like the rest
+        todo!("Implement SkBuff::network_header")
+    }
+
+    /// Kernel address of the ICMP or UDP header (`head + transport_header`).
+    #[inline(always)]
+    pub fn transport_header(&self) -> Result<*const u8, i64> {
+        // TODO: Implement in Lesson 32
+        //
+        // [ ] Same as network_header() with offsets().skb_transport_header
+        // [ ] 0xffff means "not set" (the packet was not parsed that far):
+        //     Err(-2)
+        todo!("Implement SkBuff::transport_header")
+    }
+}
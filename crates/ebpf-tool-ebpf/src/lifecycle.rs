@@ -0,0 +1,153 @@
+//! eBPF Programs for Process Lifecycle Events (Exit and OOM Kill)
+//!
+//! This module contains two tracepoint programs that together answer the
+//! question "why did my process disappear?":
+//!
+//! - `sched/sched_process_exit` fires for *every* task that exits
+//! - `oom/mark_victim` fires when the OOM killer selects a victim
+//!
+//! When a cgroup exceeds `memory.max`, the kernel's memory controller invokes
+//! the OOM killer for that cgroup. You will see a `mark_victim` event for the
+//! chosen process, followed shortly by its `sched_process_exit` with a
+//! SIGKILL exit code.
+//!
+//! ```text
+//! memory.max exceeded
+//!        │
+//!        ▼
+//! mem_cgroup_out_of_memory()
+//!        │
+//!        ▼
+//! oom_kill_process() ──► mark_oom_victim() ──► tracepoint oom/mark_victim
+//!        │                                            │
+//!        ▼                                            ▼
+//!   SIGKILL delivered                          LIFECYCLE_EVENTS
+//!        │                                            ▲
+//!        ▼                                            │
+//!   do_exit() ──────────────────────► tracepoint sched/sched_process_exit
+//! ```
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 09**: Lifecycle Events - correlate OOM kills with cgroup limits
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/09-lifecycle-events.md`
+//! - Tests: `crates/ebpf-tool/tests/lifecycle_test.rs`
+//! - Format files: `/sys/kernel/debug/tracing/events/{sched/sched_process_exit,oom/mark_victim}/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{map, tracepoint},
+    maps::PerfEventArray,
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{LifecycleEvent, LIFECYCLE_EXIT, LIFECYCLE_OOM_KILL};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Perf event array shared by both lifecycle programs.
+///
+/// Userspace opens one buffer per CPU and receives exit and OOM events
+/// interleaved in the order they were emitted on each CPU.
+#[map]
+static LIFECYCLE_EVENTS: PerfEventArray<LifecycleEvent> = PerfEventArray::new(0);
+
+// =============================================================================
+// sched/sched_process_exit
+// =============================================================================
+
+/// Tracepoint for process exit events.
+///
+/// # Tracepoint Format (sched_process_exit)
+///
+/// ```text
+/// field:char comm[16];    offset:8;  size:16; signed:0;
+/// field:pid_t pid;        offset:24; size:4;  signed:1;
+/// field:int prio;         offset:28; size:4;  signed:1;
+/// ```
+///
+/// The exit code is *not* part of the tracepoint. It lives in
+/// `task_struct->exit_code`, which you can read from `bpf_get_current_task()`
+/// (the exiting task is still "current" when this tracepoint fires).
+///
+/// # Example Userspace Attachment
+///
+/// ```rust,ignore
+/// let program: &mut TracePoint = bpf.program_mut("process_exit_tracepoint")?.try_into()?;
+/// program.load()?;
+/// program.attach("sched", "sched_process_exit")?;
+/// ```
+#[tracepoint]
+pub fn process_exit_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 09
+    // Lesson: docs/04-ebpf/09-lifecycle-events.md
+    // Tests: crates/ebpf-tool/tests/lifecycle_test.rs
+    //
+    // Implementation steps:
+    // 1. Only report thread-group leaders (pid == tid) to avoid one event
+    //    per thread: let pid_tgid = bpf_get_current_pid_tgid();
+    // 2. Fill a LifecycleEvent with kind = LIFECYCLE_EXIT
+    // 3. cgroup_id = bpf_get_current_cgroup_id()
+    // 4. exit_code: Task::current().exit_code() from crate::kstruct, which
+    //    reads task_struct->exit_code at its offset from BTF (CO-RE)
+    // 5. LIFECYCLE_EVENTS.output(&ctx, &event, 0)
+    //
+    // Starter code:
+    //   match try_process_exit(&ctx) {
+    //       Ok(ret) => ret,
+    //       Err(_) => 0,
+    //   }
+    let _ = &ctx;
+
+    todo!("Implement process_exit_tracepoint - see docs/04-ebpf/09-lifecycle-events.md")
+}
+
+// =============================================================================
+// oom/mark_victim
+// =============================================================================
+
+/// Tracepoint fired when the OOM killer marks a victim.
+///
+/// # Tracepoint Format (oom/mark_victim)
+///
+/// ```text
+/// field:int pid;          offset:8;  size:4;  signed:1;
+/// ```
+///
+/// Newer kernels (6.x) append comm, total_vm, anon_rss, file_rss, shmem_rss,
+/// uid, pgtables and oom_score_adj. Only `pid` is guaranteed, so read that
+/// and nothing else unless you check the format file first.
+///
+/// # Important: "current" is NOT the victim
+///
+/// This tracepoint runs in the context of the task that *triggered* the OOM
+/// (the allocating task), which may differ from the victim. Use the `pid`
+/// field for the victim, but note that `bpf_get_current_cgroup_id()` returns
+/// the allocator's cgroup. For memory.max OOMs both are in the same cgroup.
+///
+/// # Fallback
+///
+/// If `oom/mark_victim` is unavailable, attach a kprobe to `oom_kill_process`
+/// instead (argument 0 is `struct oom_control *`).
+#[tracepoint]
+pub fn oom_mark_victim_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 09
+    // Lesson: docs/04-ebpf/09-lifecycle-events.md
+    // Tests: crates/ebpf-tool/tests/lifecycle_test.rs
+    //
+    // Implementation steps:
+    // 1. let victim_pid: i32 = unsafe { ctx.read_at(8)? };
+    // 2. Fill a LifecycleEvent with kind = LIFECYCLE_OOM_KILL
+    // 3. comm: the victim's comm is only in the 6.x format; leave it zeroed
+    //    and let userspace resolve it from /proc/<pid>/comm (the victim has
+    //    not exited yet when this fires)
+    // 4. LIFECYCLE_EVENTS.output(&ctx, &event, 0)
+    let _ = &ctx;
+
+    todo!("Implement oom_mark_victim_tracepoint - see docs/04-ebpf/09-lifecycle-events.md")
+}
@@ -0,0 +1,135 @@
+//! eBPF Programs for BPF LSM (Linux Security Module) Hooks
+//!
+//! Every program so far *observed* the kernel. An LSM program can also say
+//! no. The kernel calls LSM hooks before security-relevant operations
+//! (opening a file, executing a binary, creating a socket). With
+//! `CONFIG_BPF_LSM=y` and `bpf` in the `lsm=` boot parameter, a BPF program
+//! can attach to those hooks:
+//!
+//! ```text
+//! open("/etc/shadow")
+//!        │
+//!        ▼
+//! do_dentry_open()
+//!        │
+//!        ▼
+//! security_file_open(file) ──► capability ──► selinux/apparmor ──► bpf
+//!        │                                                          │
+//!        │                                              lsm_file_open(ctx)
+//!        │                                                          │
+//!        │                           DENY_INODES[(dev, ino)]? ──────┤
+//!        │                                                          │
+//!        ◄──────────── 0 (allow) or -EPERM (deny) ──────────────────┘
+//!        │
+//!        ▼
+//! open() returns fd or fails with EPERM
+//! ```
+//!
+//! A BPF LSM can only make the decision stricter. It runs after the other
+//! LSMs and cannot override a denial from them.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 11**: BPF LSM - deny opens of configured files
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/11-lsm.md`
+//! - Tests: `crates/ebpf-tool/tests/lsm_test.rs`
+//! - Hook signatures: `include/linux/lsm_hook_defs.h` in the kernel source
+//! - Active LSMs: `cat /sys/kernel/security/lsm`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{lsm, map},
+    maps::{HashMap, PerfEventArray},
+    programs::LsmContext,
+};
+use ebpf_tool_common::{InodeKey, LsmDenyEvent, MAX_MAP_ENTRIES};
+
+/// Return value that denies the operation (`-EPERM`).
+#[allow(dead_code)]
+const DENY: i32 = -1;
+
+/// Return value that allows the operation.
+#[allow(dead_code)]
+const ALLOW: i32 = 0;
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Files to deny, keyed by (device, inode). The value is unused (always 1).
+///
+/// Userspace fills this from `--deny-path` before it attaches the program,
+/// so no open slips through between attach and configuration.
+#[map]
+static DENY_INODES: HashMap<InodeKey, u8> = HashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+/// One event per denied open, so userspace can log who was blocked.
+#[map]
+static LSM_EVENTS: PerfEventArray<LsmDenyEvent> = PerfEventArray::new(0);
+
+// =============================================================================
+// lsm/file_open
+// =============================================================================
+
+/// LSM hook for every file open.
+///
+/// # Hook Signature
+///
+/// ```text
+/// LSM_HOOK(int, 0, file_open, struct file *file)
+/// ```
+///
+/// BPF LSM programs receive the hook arguments followed by the return value
+/// of the previous BPF LSM program on the same hook:
+///
+/// - `ctx.arg::<*const file>(0)`: the file being opened
+/// - `ctx.arg::<i32>(1)`: the previous return value. If it is non-zero,
+///   return it unchanged, so you never turn a denial back into an allow
+///
+/// # Reading the inode
+///
+/// `file->f_inode->i_ino` and `file->f_inode->i_sb->s_dev` are kernel
+/// struct fields. Read them with `bpf_probe_read_kernel` and offsets
+/// generated from BTF (for example with `aya-tool generate file inode
+/// super_block` into a `vmlinux.rs` module).
+///
+/// # Example Userspace Attachment
+///
+/// ```rust,ignore
+/// let btf = Btf::from_sys_fs()?;
+/// let program: &mut Lsm = bpf.program_mut("lsm_file_open")?.try_into()?;
+/// program.load("file_open", &btf)?;
+/// program.attach()?;
+/// ```
+#[lsm(hook = "file_open")]
+pub fn lsm_file_open(ctx: LsmContext) -> i32 {
+    // TODO: Implement in Lesson 11
+    // Lesson: docs/04-ebpf/11-lsm.md
+    // Tests: crates/ebpf-tool/tests/lsm_test.rs
+    //
+    // Implementation steps:
+    // 1. let prev: i32 = unsafe { ctx.arg(1) }; if prev != 0 { return prev; }
+    // 2. Read file->f_inode, then inode->i_ino and inode->i_sb->s_dev
+    // 3. let key = InodeKey::new(dev as u64, ino);
+    // 4. If DENY_INODES.get(&key) is None: return ALLOW
+    // 5. Fill an LsmDenyEvent (pid, uid from bpf_get_current_uid_gid(),
+    //    comm) and LSM_EVENTS.output(&ctx, &event, 0)
+    // 6. Return DENY
+    //
+    // Starter code:
+    //   match try_file_open(&ctx) {
+    //       Ok(ret) => ret,
+    //       Err(_) => ALLOW, // fail open: a verifier-safe bug must not lock you out
+    //   }
+    //
+    // Alternative hook: bprm_check_security(struct linux_binprm *bprm) runs
+    // on execve() and can deny *running* a binary. Read bprm->file and
+    // reuse the same DENY_INODES lookup.
+    let _ = &ctx;
+
+    todo!("Implement lsm_file_open - see docs/04-ebpf/11-lsm.md")
+}
@@ -83,10 +83,28 @@
 //!
 //! Each module contains eBPF programs for a specific probe type:
 //!
+//! - [`maps`]: Maps shared by several modules (counting map occupancy, the comm filter)
+//!   - Lesson: `docs/04-ebpf/03-maps.md`, `docs/04-ebpf/45-process-patterns.md`
+//!
+//! - [`kstruct`]: Readers for task_struct, nsproxy and mm_struct fields, with offsets from BTF
+//!   - Lesson: `docs/04-ebpf/31-kernel-structs.md`
+//!
+//! - [`scratch`]: Per-CPU slots to build events too large for the 512-byte stack
+//!   - Lesson: `docs/04-ebpf/37-bounded-strings.md`
+//!
+//! - [`strings`]: Bounded reads of user and kernel strings into a per-CPU scratch slot
+//!   - Lesson: `docs/04-ebpf/37-bounded-strings.md`
+//!
+//! - [`sample`]: Per-CPU countdown that sends one in N syscall events (`trace --sample`)
+//!   - Lesson: `docs/04-ebpf/40-sampling.md`
+//!
 //! - [`kprobe`]: Kernel function probes - attach to kernel function entry/exit
 //!   - Lesson: `docs/04-ebpf/01-first-kprobe.md`
 //!   - Lesson: `docs/04-ebpf/02-kprobe-args.md`
 //!
+//! - [`fentry`]: BTF trampoline probes - faster, typed alternative to kprobes
+//!   - Lesson: `docs/04-ebpf/02-reading-data.md`
+//!
 //! - [`uprobe`]: Userspace function probes - attach to userspace function entry/exit
 //!   - Lesson: `docs/04-ebpf/05-uprobe-basics.md`
 //!
@@ -96,6 +114,51 @@
 //! - [`perf`]: Perf event sampling - sample CPU, memory, and other hardware events
 //!   - Lesson: `docs/04-ebpf/07-perf-events.md`
 //!
+//! - [`lifecycle`]: Process exit and OOM-kill tracepoints
+//!   - Lesson: `docs/04-ebpf/09-lifecycle-events.md`
+//!
+//! - [`cgroup_skb`]: Cgroup-attached packet counters (per-container network)
+//!   - Lesson: `docs/04-ebpf/10-cgroup-skb.md`
+//!
+//! - [`comm`]: Rename and exec tracepoints that keep the tracer's comm cache fresh
+//!   - Lesson: `docs/04-ebpf/08-combining.md`
+//!
+//! - [`lsm`]: BPF LSM hook that can deny file opens
+//!   - Lesson: `docs/04-ebpf/11-lsm.md`
+//!
+//! - [`task_iter`]: BPF iterator that lists every task with its namespace and cgroup ids
+//!   - Lesson: `docs/04-ebpf/12-task-iterator.md`
+//!
+//! - [`reqlat`]: Syscall tracepoints on accepted connections, for request latency
+//!   - Lesson: `docs/04-ebpf/14-request-latency.md`
+//!
+//! - [`mounts`]: mount, umount2 and move_mount tracepoints, with the caller's mount namespace
+//!   - Lesson: `docs/04-ebpf/17-mount-events.md`
+//!
+//! - [`syscall_exit`]: raw_syscalls/sys_exit, paired with traced entries for return values
+//!   - Lesson: `docs/04-ebpf/23-syscall-exits.md`
+//!
+//! - [`multiprobe`]: Generic kprobe, tracepoint and uprobe programs for `trace --kprobe` and friends
+//!   - Lesson: `docs/04-ebpf/25-multi-probe.md`
+//!
+//! - [`netsnoop`]: UDP and ICMP kprobes with the network namespace of each message
+//!   - Lesson: `docs/04-ebpf/32-netsnoop.md`
+//!
+//! - [`dns`]: libc lookup uprobes and a socket filter for DNS packets
+//!   - Lesson: `docs/04-ebpf/41-dns-tracer.md`
+//!
+//! - [`follow`]: Fork, exec and exit tracepoints that keep the process tree of `trace --follow-children`
+//!   - Lesson: `docs/04-ebpf/44-follow-children.md`
+//!
+//! - [`errors`]: raw_syscalls/sys_exit counter of failed syscalls per process and errno
+//!   - Lesson: `docs/04-ebpf/50-syscall-errors.md`
+//!
+//! - [`tailcall`]: Per-category syscall handlers reached with `bpf_tail_call` (`trace --category`)
+//!   - Lesson: `docs/04-ebpf/51-tail-calls.md`
+//!
+//! - [`procevents`]: Fork, exec and exit tracepoints filtered by PID namespace (`trace --proc-events`)
+//!   - Lesson: `docs/04-ebpf/52-process-events.md`
+//!
 //! ## Getting Started
 //!
 //! To build and run eBPF programs:
@@ -123,6 +186,92 @@
 // Each module contains eBPF programs for a specific probe type. The programs
 // are annotated with Aya macros that define their type and attachment point.
 
+/// Maps used by programs in more than one module.
+///
+/// # Lessons
+/// - `docs/04-ebpf/03-maps.md` - "When the Map Fills Up"
+/// - `docs/04-ebpf/45-process-patterns.md` - A comm prefix in the kernel
+///
+/// # TODO
+/// Implement the following helpers:
+/// - `count_insert`: Count new keys in a counting map, for eviction reports
+/// - `comm_has_prefix` / `comm_allowed`: The kernel side of `trace --process`
+mod maps;
+
+/// Kernel struct readers (parent PID, namespace ids, RSS).
+///
+/// Follow `task_struct` pointers with offsets that userspace looked up in
+/// the running kernel's BTF, so one object works across kernel versions.
+///
+/// # Lessons
+/// - `docs/04-ebpf/31-kernel-structs.md` - Kernel structs without fixed offsets
+///
+/// # TODO
+/// Implement the following helpers:
+/// - `Task::parent_tgid`: `real_parent->tgid`
+/// - `Task::ns_ids`: Namespace inodes via nsproxy and thread_pid
+/// - `Task::rss_pages`: Resident pages from `mm->rss_stat`
+/// - `Sock::netns` / `SkBuff::netns`: Network namespace of a socket or packet
+mod kstruct;
+
+/// Per-CPU scratch space for large events.
+///
+/// One `PerCpuArray` of 1 KiB slots that every program builds its large
+/// values in (an event, a string, the event of a tail call), instead of a
+/// scratch map per event type.
+///
+/// # Lessons
+/// - `docs/04-ebpf/37-bounded-strings.md` - "Scratch Space for Large Events"
+///
+/// # TODO
+/// Implement the following helpers:
+/// - `get`: This CPU's slot as a `&mut T`, with a compile-time size check
+mod scratch;
+
+/// Bounded string reads (paths, argv, probe arguments).
+///
+/// Wrap `bpf_probe_read_{user,kernel}_str` with a per-CPU scratch slot,
+/// power-of-two bounds the verifier accepts, and truncation flags, so every
+/// program that copies a string does it the same way.
+///
+/// # Lessons
+/// - `docs/04-ebpf/37-bounded-strings.md` - Paths and argv without verifier errors
+///
+/// # TODO
+/// Implement the following helpers:
+/// - `read_user_str` / `read_kernel_str`: Copy a string into this CPU's scratch buffer
+/// - `copy_str` / `read_user_field`: Move it into a fixed event field, flagging cuts
+/// - `read_user_argv`: Copy the first MAX_ARGS argv entries into ExecEvent::argv
+mod strings;
+
+/// In-kernel sampling of syscall events (`trace --sample 1/N`).
+///
+/// A per-CPU countdown, reset to a random value with mean N, decides which
+/// of the events that passed the filters are sent.
+///
+/// # Lessons
+/// - `docs/04-ebpf/40-sampling.md` - Bounded overhead on hot syscalls
+///
+/// # TODO
+/// Implement the following helpers:
+/// - `sample_keep`: Whether to send this event
+/// - `next_countdown`: How many events to skip after a sent one
+mod sample;
+
+/// Batched syscall events (SYSCALL_BATCHES).
+///
+/// At high event rates a CPU collects syscall events in a per-CPU batch and
+/// outputs it once it is full, instead of one perf record per event.
+///
+/// # Lessons
+/// - `docs/04-ebpf/08-combining.md` - "Batching at High Rates"
+///
+/// # TODO
+/// Implement the following helpers:
+/// - `emit_syscall`: Send one event, alone or batched depending on the rate
+/// - `flush`: Output and empty this CPU's batch
+mod batch;
+
 /// Kernel function probes (kprobes and kretprobes).
 ///
 /// Kprobes allow you to dynamically attach to almost any kernel function and
@@ -136,10 +285,26 @@
 /// Implement the following probes:
 /// - `kprobe_execve`: Trace process execution (sys_execve)
 /// - `kretprobe_execve`: Capture execve return values
+/// - `openat2_kprobe`: The dfd, filename and `open_how` of `do_sys_openat2`
 ///
 /// See the lesson docs for step-by-step implementation guides.
 mod kprobe;
 
+/// BTF trampoline probes (fentry and fexit).
+///
+/// Same targets as the kprobes, but attached through a direct call instead
+/// of a breakpoint. Requires kernel BTF; userspace falls back to kprobes
+/// when it is missing.
+///
+/// # Lessons
+/// - `docs/04-ebpf/02-reading-data.md` - fentry/fexit section
+///
+/// # TODO
+/// Implement the following probes:
+/// - `syscall_fentry`: Same event as syscall_kprobe, via fentry
+/// - `syscall_fexit`: Same event on return, with access to the return value
+mod fentry;
+
 /// Userspace function probes (uprobes and uretprobes).
 ///
 /// Uprobes allow you to attach to functions in userspace binaries and shared
@@ -165,6 +330,7 @@ mod uprobe;
 ///
 /// # Lessons
 /// - `docs/04-ebpf/06-tracepoints.md` - Using kernel tracepoints
+/// - `docs/04-ebpf/42-cgroup-cpu.md` - CPU time per cgroup from sched_switch
 ///
 /// # TODO
 /// Implement the following probes:
@@ -186,10 +352,226 @@ mod tracepoint;
 /// Implement the following probes:
 /// - `perf_cpu_cycles`: Sample CPU cycles for profiling
 /// - `perf_cache_misses`: Monitor cache performance
+/// - `perf_fault_major` / `perf_fault_minor`: Count page faults per process and stack
 ///
 /// See the lesson docs for step-by-step implementation guides.
 mod perf;
 
+/// Process lifecycle tracepoints (exit and OOM kill).
+///
+/// Pairs `sched/sched_process_exit` with `oom/mark_victim` so learners can
+/// watch the OOM killer enforce a cgroup's `memory.max`.
+///
+/// # Lessons
+/// - `docs/04-ebpf/09-lifecycle-events.md` - OOM kills and process exits
+///
+/// # TODO
+/// Implement the following probes:
+/// - `process_exit_tracepoint`: Report exits with exit code and cgroup id
+/// - `oom_mark_victim_tracepoint`: Report OOM-killer victims
+mod lifecycle;
+
+/// Cgroup-attached socket buffer programs (cgroup_skb).
+///
+/// Attached to a cgroup v2 directory instead of a kernel function, these
+/// count every packet sent or received by processes in that cgroup.
+///
+/// # Lessons
+/// - `docs/04-ebpf/10-cgroup-skb.md` - Per-container network accounting
+///
+/// # TODO
+/// Implement the following programs:
+/// - `cgroup_skb_ingress`: Count received bytes/packets
+/// - `cgroup_skb_egress`: Count sent bytes/packets
+mod cgroup_skb;
+
+/// Process name change tracepoints (rename and exec).
+///
+/// Report comm changes so the syscall tracer's `pid -> comm` cache does not
+/// keep showing the name a process had when it was first seen.
+///
+/// # Lessons
+/// - `docs/04-ebpf/08-combining.md` - Keeping process names fresh
+///
+/// # TODO
+/// Implement the following probes:
+/// - `task_rename_tracepoint`: Report prctl(PR_SET_NAME) and /proc comm writes
+/// - `sched_process_exec_tracepoint`: Report the new name after exec
+mod comm;
+
+/// BPF LSM programs (security hooks).
+///
+/// Unlike the tracing programs, the return value of an LSM program is
+/// enforced: a negative errno denies the operation.
+///
+/// # Lessons
+/// - `docs/04-ebpf/11-lsm.md` - Deny opens of configured files
+///
+/// # TODO
+/// Implement the following programs:
+/// - `lsm_file_open`: Deny opens of inodes listed in DENY_INODES
+mod lsm;
+
+/// BPF iterator programs (bpf_iter).
+///
+/// Run on demand, once per element of a kernel data structure, when
+/// userspace reads the iterator's file descriptor.
+///
+/// # Lessons
+/// - `docs/04-ebpf/12-task-iterator.md` - Listing tasks from the kernel
+///
+/// # TODO
+/// Implement the following programs:
+/// - `dump_task`: Write one TaskRecord per task into the seq_file
+mod task_iter;
+
+/// Socket request latency tracepoints.
+///
+/// Report accept, read, write and close on connection fds of a target
+/// server; userspace pairs them into per-request latencies.
+///
+/// # Lessons
+/// - `docs/04-ebpf/14-request-latency.md` - Request latency per connection
+///
+/// # TODO
+/// Implement the following probes:
+/// - `reqlat_accept_exit`: Track fds returned by accept()/accept4()
+/// - `reqlat_rw_enter`: Remember the fd of a read/write on a tracked fd
+/// - `reqlat_read_exit` / `reqlat_write_exit`: Report completed reads and writes
+/// - `reqlat_close_enter`: Stop tracking and report the close
+mod reqlat;
+
+/// Mount syscall tracepoints.
+///
+/// Report every mount, umount2 and move_mount call with its arguments,
+/// its result and the mount namespace of the caller.
+///
+/// # Lessons
+/// - `docs/04-ebpf/17-mount-events.md` - Mount events per namespace
+///
+/// # TODO
+/// Implement the following probes:
+/// - `mount_enter` / `umount_enter` / `move_mount_enter`: Copy the arguments on entry
+/// - `mount_exit`: Add the return value and report the call
+mod mounts;
+
+/// Syscall exit tracepoint.
+///
+/// Report the return value of every syscall the tracer sent an entry event
+/// for, with the entry's timestamp so userspace can pair them.
+///
+/// # Lessons
+/// - `docs/04-ebpf/23-syscall-exits.md` - Return values and errno names
+///
+/// # TODO
+/// Implement the following:
+/// - `record_pending`: Called by the trace entry program for each sent event
+/// - `syscall_exit_tracepoint`: Report the exit of a pending syscall
+mod syscall_exit;
+
+/// Generic probe programs for `trace`.
+///
+/// One program per probe kind, attached once for every `--kprobe`,
+/// `--tracepoint` and `--uprobe`, all sending to one perf array.
+///
+/// # Lessons
+/// - `docs/04-ebpf/25-multi-probe.md` - Several probe kinds in one trace
+///
+/// # TODO
+/// Implement the following:
+/// - `send_probe_event`: Filter by cgroup and send a ProbeEvent
+/// - `multi_kprobe` / `multi_tracepoint` / `multi_uprobe`: Find the site and send
+mod multiprobe;
+
+/// UDP and ICMP kprobes tagged with the network namespace.
+///
+/// Report datagrams and ICMP messages with the namespace of their socket
+/// or receiving device, filtered to one namespace in the kernel.
+///
+/// # Lessons
+/// - `docs/04-ebpf/32-netsnoop.md` - Traffic per network namespace
+///
+/// # TODO
+/// Implement the following probes:
+/// - `netsnoop_udp_send`: Report sent datagrams
+/// - `netsnoop_udp_recv` / `netsnoop_udp_recv_ret`: Report received datagrams
+/// - `netsnoop_icmp_out` / `netsnoop_icmp_rcv`: Report ICMP messages
+mod netsnoop;
+
+/// DNS lookups: libc uprobes and a packet-level socket filter.
+///
+/// The uprobes report each getaddrinfo()/gethostbyname() call with its
+/// process and result; the socket filter passes only DNS packets to an
+/// `AF_PACKET` socket that userspace decodes.
+///
+/// # Lessons
+/// - `docs/04-ebpf/41-dns-tracer.md` - Combining uprobes with packet filters
+///
+/// # TODO
+/// Implement the following probes:
+/// - `dns_getaddrinfo` / `dns_gethostbyname`: Record the name on entry
+/// - `dns_lookup_ret`: Send the lookup with its result and latency
+/// - `dns_packet_filter`: Keep UDP port 53 packets
+mod dns;
+
+/// Process trees for `trace --process NAME --follow-children`.
+///
+/// `FOLLOW_PIDS` holds the processes to trace; every fork of a member adds
+/// the child, every exit removes the task.
+///
+/// # Lessons
+/// - `docs/04-ebpf/44-follow-children.md` - Following children in a kernel map
+///
+/// # TODO
+/// Implement the following programs:
+/// - `follow_fork`: Add the children of followed processes
+/// - `follow_exec`: Add processes that exec a program with the name
+/// - `follow_exit`: Forget exited tasks
+/// - `is_followed`: The check used by the syscall and probe programs
+mod follow;
+
+/// Failed syscalls per process, syscall and errno for `ebpf-tool errors`.
+///
+/// Counts every exit with `ret` in -4095..=-1 in a per-CPU LRU map;
+/// userspace reports the growth per interval.
+///
+/// # Lessons
+/// - `docs/04-ebpf/50-syscall-errors.md` - Finding errno storms
+///
+/// # TODO
+/// Implement the following program:
+/// - `syscall_errors_tracepoint`: Count the exit when it is an error
+mod errors;
+
+/// File, network and process handlers for the syscall tracer.
+///
+/// The entry program jumps to one of them through the `SYSCALL_HANDLERS`
+/// program array, so each category is verified as its own program.
+///
+/// # Lessons
+/// - `docs/04-ebpf/51-tail-calls.md` - Splitting a program with tail calls
+///
+/// # TODO
+/// Implement the following helpers and programs:
+/// - `dispatch`: Called by the entry program; tail-calls the category's handler
+/// - `syscall_file_handler` / `syscall_net_handler` / `syscall_proc_handler`:
+///   Send the event left in the `Slot::Tail` scratch slot
+mod tailcall;
+
+/// Fork, exec and exit of the processes in one PID namespace.
+///
+/// `PROC_PIDNS` holds the namespace to watch, a container's; the programs
+/// drop every other process before building an event.
+///
+/// # Lessons
+/// - `docs/04-ebpf/52-process-events.md` - Watching a container's processes
+///
+/// # TODO
+/// Implement the following programs:
+/// - `proc_fork` / `proc_exec` / `proc_exit`: Send a `ProcEvent` per step
+/// - `in_pidns`: The namespace check shared by the three
+mod procevents;
+
 // =============================================================================
 // Required no_std Infrastructure
 // =============================================================================
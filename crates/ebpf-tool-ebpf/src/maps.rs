@@ -0,0 +1,114 @@
+//! Maps Shared by Several Program Modules
+//!
+//! The counting maps (`SYSCALL_COUNTS`, `FAULT_COUNTS`) are LRU maps: when
+//! one is full, a new key evicts the least recently used key instead of
+//! failing. That keeps long traces counting, but the kernel does not report
+//! evictions anywhere. This module counts the inserts so userspace can:
+//!
+//! ```text
+//! evicted = MAP_INSERTS[slot] (summed over CPUs) - entries in the map
+//! ```
+//!
+//! Keys are never deleted by these programs, so every missing key was
+//! evicted. `ebpf-tool stats` prints the result under the table and warns as
+//! soon as it is non-zero.
+//!
+//! `FILTER_COMM` holds the literal start of a `trace --process` pattern.
+//! The syscall entry program and the probe programs check it with
+//! [`comm_allowed`] before they send anything; userspace applies the rest of
+//! the pattern.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 03**: eBPF Maps - "When the Map Fills Up"
+//! - **Lesson 45**: Process Patterns - a comm prefix in the kernel
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/03-maps.md`, `docs/04-ebpf/45-process-patterns.md`
+//! - Tests: `crates/ebpf-tool/tests/stats_test.rs`, `crates/ebpf-tool/tests/process_pattern_test.rs`
+//! - Slots: `ebpf_tool_common::INSERTS_SYSCALL_COUNTS`, `INSERTS_FAULT_COUNTS`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::map,
+    maps::{Array, PerCpuArray},
+};
+use ebpf_tool_common::{CommPrefix, COMM_LEN, INSERTS_SLOTS};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// New keys inserted into each counting map, one slot per map.
+///
+/// Per-CPU like the maps it describes, so counting an insert costs no
+/// atomic operation.
+#[map]
+pub static MAP_INSERTS: PerCpuArray<u64> = PerCpuArray::with_max_entries(INSERTS_SLOTS, 0);
+
+/// The kernel part of `trace --process`, written once by userspace
+/// (`CommMatch::kernel_filter()`). All zero, as after loading, means no
+/// process filter.
+#[map]
+pub static FILTER_COMM: Array<CommPrefix> = Array::with_max_entries(1, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Record that a new key was inserted into the counting map for `slot`.
+///
+/// Call it only when `insert()` created the key, not on every increment:
+/// the difference between inserts and live entries is what userspace
+/// reports as evictions.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn count_insert(slot: u32) {
+    // TODO: Implement in Lesson 03 ("When the Map Fills Up")
+    //
+    // [ ] MAP_INSERTS.get_ptr_mut(slot): increment this CPU's copy
+    // [ ] A missing slot (slot >= INSERTS_SLOTS) is a bug in the caller;
+    //     ignore it rather than failing the program
+    let _ = slot;
+
+    todo!("Implement count_insert")
+}
+
+/// Whether the first `prefix.len` bytes of `comm` are `prefix.bytes`.
+///
+/// Also used by `follow_exec` with `FOLLOW_COMM`.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn comm_has_prefix(comm: &[u8; COMM_LEN], prefix: &CommPrefix) -> bool {
+    // TODO: Implement in Lesson 45
+    // Lesson: docs/04-ebpf/45-process-patterns.md
+    //
+    // [ ] A loop over 0..COMM_LEN with a constant bound, so the verifier
+    //     can unroll it; stop comparing at i >= prefix.len
+    // [ ] No slicing with prefix.len: a bound the verifier cannot see is
+    //     rejected as an out-of-range access
+    let _ = (comm, prefix);
+
+    todo!("Implement comm_has_prefix")
+}
+
+/// Whether the current task passes the `--process` filter in `FILTER_COMM`.
+///
+/// Call it from the syscall entry program and `send_probe_event` next to
+/// the cgroup and uid checks. Not with `--follow-children`: userspace leaves
+/// `FILTER_COMM` empty then, since children have other names.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn comm_allowed(comm: &[u8; COMM_LEN]) -> bool {
+    // TODO: Implement in Lesson 45
+    //
+    // [ ] FILTER_COMM.get(0): missing or len 0 -> true (the common case,
+    //     keep it one lookup)
+    // [ ] Otherwise comm_has_prefix(comm, prefix). The comm is the one the
+    //     event carries anyway (bpf_get_current_comm()), no second read
+    let _ = comm;
+
+    todo!("Implement comm_allowed")
+}
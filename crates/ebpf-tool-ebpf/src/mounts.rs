@@ -0,0 +1,205 @@
+//! eBPF Programs for Mount Events (mount, umount2, move_mount)
+//!
+//! Every change to a mount table goes through one of three syscalls. These
+//! programs report each call together with the mount namespace it changed,
+//! read from the calling task in the kernel:
+//!
+//! ```text
+//! sys_enter_mount       ─┐
+//! sys_enter_umount      ─┼─► copy args into scratch Slot::Event, add mntns_inum
+//! sys_enter_move_mount  ─┘   ─► MOUNT_INFLIGHT.insert(pid_tgid, event)
+//!
+//! sys_exit_{mount,umount,move_mount}
+//!                        ─► MOUNT_INFLIGHT.get(pid_tgid), set ret
+//!                        ─► MOUNT_EVENTS, MOUNT_INFLIGHT.remove(pid_tgid)
+//! ```
+//!
+//! The string arguments are user pointers, valid only while the syscall
+//! runs, so they are copied on enter. Only the exit knows whether the call
+//! succeeded, so the event is sent from there.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 17**: Mount Events - who mounted what, in which namespace
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/17-mount-events.md`
+//! - Tests: `crates/ebpf-tool/tests/mounts_test.rs`
+//! - Format files: `/sys/kernel/debug/tracing/events/syscalls/{sys_enter_mount,sys_enter_umount,sys_enter_move_mount}/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{map, tracepoint},
+    maps::{HashMap, PerfEventArray},
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{MountEvent, MOUNT_KIND_MOUNT, MOUNT_KIND_MOVE, MOUNT_KIND_UMOUNT};
+
+use crate::scratch::{self, Slot};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Completed mount calls for userspace, in per-CPU order.
+#[map]
+static MOUNT_EVENTS: PerfEventArray<MountEvent> = PerfEventArray::new(0);
+
+/// Calls between enter and exit, keyed by pid_tgid.
+///
+/// A thread is in at most one syscall at a time, so a small map is enough;
+/// an entry whose exit never comes (the task was killed) is simply
+/// overwritten by the thread's next mount call.
+#[map]
+static MOUNT_INFLIGHT: HashMap<u64, MountEvent> = HashMap::with_max_entries(1024, 0);
+
+// =============================================================================
+// syscalls/sys_enter_mount
+// =============================================================================
+
+/// Copy the arguments of mount(2).
+///
+/// # Tracepoint Format (sys_enter_mount)
+///
+/// ```text
+/// field:char * dev_name;     offset:16; size:8; signed:0;
+/// field:char * dir_name;     offset:24; size:8; signed:0;
+/// field:char * type;         offset:32; size:8; signed:0;
+/// field:unsigned long flags; offset:40; size:8; signed:0;
+/// field:void * data;         offset:48; size:8; signed:0;
+/// ```
+///
+/// `dev_name` and `type` may be NULL (bind mounts, propagation changes).
+#[tracepoint]
+pub fn mount_enter(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 17
+    // Lesson: docs/04-ebpf/17-mount-events.md
+    // Tests: crates/ebpf-tool/tests/mounts_test.rs
+    //
+    // Implementation steps:
+    // 1. let event = scratch::get::<MountEvent>(Slot::Event)?; *event = MountEvent::new()
+    // 2. pid/tid from bpf_get_current_pid_tgid(), comm, timestamp_ns,
+    //    kind = MOUNT_KIND_MOUNT
+    // 3. mntns_inum = current_mntns() (helper below)
+    // 4. Read the three pointers and the flags with ctx.read_at(); copy the
+    //    strings with crate::strings::read_user_field() (Lesson 37) into
+    //    source, target and fstype. NULL pointers fail the read and leave
+    //    the field empty
+    // 5. MOUNT_INFLIGHT.insert(&pid_tgid, &*event, 0)
+    let _ = &ctx;
+
+    todo!("Implement mount_enter - see docs/04-ebpf/17-mount-events.md")
+}
+
+// =============================================================================
+// syscalls/sys_enter_umount (umount2)
+// =============================================================================
+
+/// Copy the arguments of umount2(2).
+///
+/// # Tracepoint Format (sys_enter_umount)
+///
+/// ```text
+/// field:char * name;         offset:16; size:8; signed:0;
+/// field:int flags;           offset:24; size:8; signed:0;
+/// ```
+///
+/// The tracepoint is named after the kernel's `SYSCALL_DEFINE2(umount, ...)`,
+/// which is the umount2 syscall on every current architecture.
+#[tracepoint]
+pub fn umount_enter(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 17
+    // Lesson: docs/04-ebpf/17-mount-events.md
+    //
+    // Implementation steps:
+    // 1. Same as mount_enter, with kind = MOUNT_KIND_UMOUNT
+    // 2. name -> target; flags (MNT_FORCE, MNT_DETACH, ...) -> flags
+    let _ = &ctx;
+
+    todo!("Implement umount_enter - see docs/04-ebpf/17-mount-events.md")
+}
+
+// =============================================================================
+// syscalls/sys_enter_move_mount
+// =============================================================================
+
+/// Copy the arguments of move_mount(2) (Linux 5.2+).
+///
+/// # Tracepoint Format (sys_enter_move_mount)
+///
+/// ```text
+/// field:int from_dfd;                  offset:16; size:8; signed:0;
+/// field:const char * from_pathname;    offset:24; size:8; signed:0;
+/// field:int to_dfd;                    offset:32; size:8; signed:0;
+/// field:const char * to_pathname;      offset:40; size:8; signed:0;
+/// field:unsigned int flags;            offset:48; size:8; signed:0;
+/// ```
+///
+/// util-linux 2.39+ mounts with fsopen/fsconfig/fsmount and then calls
+/// move_mount with an empty `from_pathname` and `MOVE_MOUNT_F_EMPTY_PATH`:
+/// the source is the fd from fsmount, not a path.
+#[tracepoint]
+pub fn move_mount_enter(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 17
+    // Lesson: docs/04-ebpf/17-mount-events.md
+    //
+    // Implementation steps:
+    // 1. Same as mount_enter, with kind = MOUNT_KIND_MOVE
+    // 2. from_pathname -> source, to_pathname -> target, flags -> flags
+    // 3. to_pathname is relative to to_dfd when it does not start with '/';
+    //    keep it as is, userspace prints it unchanged
+    let _ = &ctx;
+
+    todo!("Implement move_mount_enter - see docs/04-ebpf/17-mount-events.md")
+}
+
+// =============================================================================
+// syscalls/sys_exit_{mount,umount,move_mount}
+// =============================================================================
+
+/// Add the return value and send the event. Attached to all three exits.
+///
+/// # Tracepoint Format (sys_exit_mount)
+///
+/// ```text
+/// field:int __syscall_nr;  offset:8;  size:4; signed:1;
+/// field:long ret;          offset:16; size:8; signed:1;
+/// ```
+#[tracepoint]
+pub fn mount_exit(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 17
+    // Lesson: docs/04-ebpf/17-mount-events.md
+    //
+    // Implementation steps:
+    // 1. let pid_tgid = bpf_get_current_pid_tgid();
+    // 2. MOUNT_INFLIGHT.get_ptr_mut(&pid_tgid); return 0 if missing (the
+    //    tracer was attached while the call was already running)
+    // 3. (*event).ret = ctx.read_at::<i64>(16)?
+    // 4. MOUNT_EVENTS.output(&ctx, &*event, 0), then MOUNT_INFLIGHT.remove(&pid_tgid)
+    let _ = &ctx;
+
+    todo!("Implement mount_exit - see docs/04-ebpf/17-mount-events.md")
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Mount namespace inode of the current task.
+///
+/// Same chain as `mntns_inum` in `task_iter.rs`:
+/// `task->nsproxy->mnt_ns->ns.inum`, with the offsets from BTF.
+#[allow(dead_code)]
+#[inline(always)]
+fn current_mntns() -> u32 {
+    // TODO: Implement in Lesson 17
+    //
+    // [ ] Task::current().ns_ids().map(|ids| ids.mnt) (crate::kstruct,
+    //     Lesson 31), which reads the offsets userspace found in BTF
+    // [ ] nsproxy is never NULL here, since a task calling mount() is not
+    //     exiting
+    // [ ] Return 0 on any read error: userspace shows "?"
+    todo!("Implement current_mntns")
+}
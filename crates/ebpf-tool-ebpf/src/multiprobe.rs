@@ -0,0 +1,143 @@
+//! eBPF Programs for Extra Probes in `trace` (--kprobe, --tracepoint, --uprobe)
+//!
+//! Lessons 01, 05 and 06 attach one probe per run, each with its own
+//! program. `trace` can attach any number of them next to the syscall
+//! tracer; one generic program per kind, attached once per probe, sends the
+//! same `ProbeEvent`:
+//!
+//! ```text
+//! --kprobe vfs_read                ─► multi_kprobe     ─┐
+//! --kprobe vfs_write               ─► multi_kprobe     ─┤
+//! --tracepoint sched:sched_..exec  ─► multi_tracepoint ─┼─► PROBE_EVENTS (ProbeEvent)
+//! --uprobe /usr/bin/bash:readline  ─► multi_uprobe     ─┘
+//! ```
+//!
+//! A program cannot ask which of its attachments fired (attach cookies need
+//! a newer aya), so each event carries a `site` that identifies it: the
+//! function address for kprobes, the tracepoint id, or the instruction
+//! pointer for uprobes. Userspace maps sites back to the probes it attached.
+//!
+//! The programs apply the cgroup filter (`FILTER_KEY_CGROUP`) like the
+//! syscall tracer, so `trace --cgroup` covers the extra probes too.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 25**: Multi-probe sessions - several probe kinds in one trace
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/25-multi-probe.md`
+//! - Tests: `crates/ebpf-tool/tests/multiprobe_test.rs`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_comm, bpf_get_current_pid_tgid, bpf_ktime_get_ns},
+    macros::{kprobe, map, tracepoint, uprobe},
+    maps::PerfEventArray,
+    programs::{ProbeContext, TracePointContext},
+    EbpfContext,
+};
+use ebpf_tool_common::{ProbeEvent, PROBE_KIND_KPROBE, PROBE_KIND_TRACEPOINT, PROBE_KIND_UPROBE};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Events from all extra probes, in per-CPU order.
+///
+/// Separate from the syscall tracer's `EVENTS`: the record types differ, and
+/// userspace merges the two streams by timestamp anyway.
+#[map]
+static PROBE_EVENTS: PerfEventArray<ProbeEvent> = PerfEventArray::new(0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Fill and send one `ProbeEvent`; shared by the three programs.
+#[allow(dead_code)]
+#[inline(always)]
+fn send_probe_event<C: EbpfContext>(ctx: &C, kind: u32, site: u64) {
+    // TODO: Implement in Lesson 25
+    // Lesson: docs/04-ebpf/25-multi-probe.md
+    //
+    // Implementation steps:
+    // 1. Apply the cgroup filter the same way the trace entry program does
+    //    (FILTER_CONFIG[FILTER_KEY_CGROUP]), and the uid filter if
+    //    FILTER_KEY_UID is present (Lesson 34); return early on a mismatch.
+    //    Lesson 44: crate::follow::is_followed(tgid) when FILTER_KEY_FOLLOW
+    //    is 1. Lesson 45: crate::maps::comm_allowed(&comm) once the comm
+    //    of step 3 is read
+    // 2. let pid_tgid = bpf_get_current_pid_tgid();
+    // 3. Fill a ProbeEvent: kind, site, pid = (pid_tgid >> 32) as u32,
+    //    tid = pid_tgid as u32, timestamp_ns = bpf_ktime_get_ns(),
+    //    comm = bpf_get_current_comm().unwrap_or_default()
+    // 4. Lesson 33: ppid, pidns_inum and mntns_inum from
+    //    crate::kstruct::Task::current(), as in try_syscall_kprobe();
+    //    Lesson 34: uid and gid from bpf_get_current_uid_gid()
+    // 5. Lesson 37: for a probe with a string argument (a uprobe on
+    //    `open(const char *path, ...)`), crate::strings::read_user_field()
+    //    into event.arg; other probes leave it empty
+    // 6. PROBE_EVENTS.output(ctx, &event, 0)
+    let _ = (ctx.as_ptr(), kind, site);
+
+    todo!("Implement send_probe_event - see docs/04-ebpf/25-multi-probe.md")
+}
+
+// =============================================================================
+// Programs
+// =============================================================================
+
+/// Attached to every `--kprobe FUNCTION`.
+#[kprobe]
+pub fn multi_kprobe(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 25
+    //
+    // Implementation steps:
+    // 1. let site = unsafe { aya_ebpf::helpers::gen::bpf_get_func_ip(ctx.as_ptr()) };
+    //    (kernel 5.15+). It is the function's address, as in /proc/kallsyms
+    // 2. send_probe_event(&ctx, PROBE_KIND_KPROBE, site)
+    let _ = &ctx;
+
+    todo!("Implement multi_kprobe - see docs/04-ebpf/25-multi-probe.md")
+}
+
+/// Attached to every `--tracepoint CATEGORY:NAME`.
+///
+/// Every tracepoint record starts with the same header:
+///
+/// ```text
+/// field:unsigned short common_type;  offset:0; size:2; signed:0;
+/// ```
+///
+/// `common_type` is the tracepoint's id, the number in
+/// `/sys/kernel/tracing/events/<category>/<name>/id`.
+#[tracepoint]
+pub fn multi_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 25
+    //
+    // Implementation steps:
+    // 1. let id: u16 = unsafe { ctx.read_at(0) }.unwrap_or(0);
+    // 2. send_probe_event(&ctx, PROBE_KIND_TRACEPOINT, id as u64)
+    let _ = &ctx;
+
+    todo!("Implement multi_tracepoint - see docs/04-ebpf/25-multi-probe.md")
+}
+
+/// Attached to every `--uprobe BINARY:FUNCTION`.
+#[uprobe]
+pub fn multi_uprobe(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 25
+    //
+    // Implementation steps:
+    // 1. The instruction pointer is the probed function's address in this
+    //    process: unsafe { (*ctx.regs).rip } on x86_64, .pc on aarch64
+    // 2. send_probe_event(&ctx, PROBE_KIND_UPROBE, ip)
+    //
+    // The address differs between processes (ASLR); userspace turns it back
+    // into a function with /proc/<pid>/maps and the ELF symbols.
+    let _ = &ctx;
+
+    todo!("Implement multi_uprobe - see docs/04-ebpf/25-multi-probe.md")
+}
@@ -0,0 +1,231 @@
+//! eBPF Programs for UDP and ICMP Traffic per Network Namespace (netsnoop)
+//!
+//! Every datagram and ICMP message passes through a few kernel functions.
+//! These programs report each one with the network namespace it belongs to,
+//! so one tracer on the host can follow the traffic of a single netns-tool
+//! topology or container:
+//!
+//! ```text
+//! udp_sendmsg(sk, msg, len)     ─► Sock(sk).netns()   ─► NET_EVENTS (UDP, send)
+//! udp_recvmsg(sk, msg, ...)     ─► UDP_RECV_INFLIGHT.insert(pid_tgid, {sk, msg})
+//!   kretprobe (ret = bytes)      ─► Sock(sk).netns()   ─► NET_EVENTS (UDP, recv)
+//! icmp_out_count(net, type)     ─► net_inum(net)      ─► NET_EVENTS (ICMP, send)
+//! icmp_rcv(skb)                 ─► SkBuff(skb).netns() ─► NET_EVENTS (ICMP, recv)
+//! ```
+//!
+//! The namespace always comes from a kernel object (socket, `struct net`
+//! or receiving device), never from the current task. ICMP is received in
+//! softirq context, where "current" is whichever task was interrupted.
+//!
+//! `NETSNOOP_TARGET[NETSNOOP_TARGET_NETNS]` holds the namespace inode
+//! userspace asked for (`--netns`), or 0 for all. Filtering here, not in
+//! userspace, keeps the host's own traffic out of the perf buffer.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 32**: Netsnoop - UDP and ICMP traffic per network namespace
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/32-netsnoop.md`
+//! - Tests: `crates/ebpf-tool/tests/netsnoop_test.rs`
+//! - Function signatures: `net/ipv4/udp.c`, `net/ipv4/icmp.c` (`icmp_out_count`
+//!   is called for every ICMP message sent: replies, errors, ping and raw sockets)
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use crate::kstruct::{net_inum, SkBuff, Sock};
+use aya_ebpf::{
+    macros::{kprobe, kretprobe, map},
+    maps::{Array, HashMap, PerfEventArray},
+    programs::{ProbeContext, RetProbeContext},
+};
+use core::ffi::c_void;
+use ebpf_tool_common::{
+    NetEvent, MAX_MAP_ENTRIES, NETSNOOP_TARGET_NETNS, NET_DIR_RECV, NET_DIR_SEND, NET_PROTO_ICMP,
+    NET_PROTO_UDP,
+};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Datagrams and ICMP messages for userspace, in per-CPU order.
+#[map]
+static NET_EVENTS: PerfEventArray<NetEvent> = PerfEventArray::new(0);
+
+/// Namespace filter written by userspace before attaching (0 = all).
+#[map]
+static NETSNOOP_TARGET: Array<u32> = Array::with_max_entries(1, 0);
+
+/// Arguments of a `udp_recvmsg` call, kept for its kretprobe.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RecvArgs {
+    /// `struct sock *`
+    sk: u64,
+    /// `struct msghdr *`: `msg_name` holds the sender after the call
+    msg: u64,
+}
+
+/// `udp_recvmsg` calls between entry and return, keyed by pid_tgid.
+///
+/// A blocking recvfrom() stays in here until a datagram arrives, so the
+/// entry may be old when the kretprobe reads it.
+#[map]
+static UDP_RECV_INFLIGHT: HashMap<u64, RecvArgs> = HashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+// =============================================================================
+// UDP
+// =============================================================================
+
+/// Report a datagram sent by a process.
+///
+/// # Arguments (`int udp_sendmsg(struct sock *sk, struct msghdr *msg, size_t len)`)
+///
+/// - `ctx.arg::<*const c_void>(0)`: the socket
+/// - `ctx.arg::<*const c_void>(1)`: the message; `msg_name` is the
+///   destination of sendto(), NULL for send() on a connected socket
+/// - `ctx.arg::<u64>(2)`: payload length
+#[kprobe]
+pub fn netsnoop_udp_send(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 32
+    // Lesson: docs/04-ebpf/32-netsnoop.md
+    // Tests: crates/ebpf-tool/tests/netsnoop_test.rs
+    //
+    // Implementation steps:
+    // 1. let sock = unsafe { Sock::from_ptr(ctx.arg(0)?) };
+    // 2. let netns = sock.netns().unwrap_or(0); return if !wanted(netns)
+    // 3. NetEvent::new() with fill_task(), proto = NET_PROTO_UDP,
+    //    direction = NET_DIR_SEND, len = arg 2
+    // 4. sock.ipv4_addrs(): skip the event on Err (IPv6 is udpv6_sendmsg)
+    // 5. Unconnected socket (daddr == 0): read the sockaddr_in at
+    //    msg->msg_name (KernelOffsets::msghdr_name): sin_port at offset 2,
+    //    sin_addr at offset 4, like skc_dport/skc_daddr
+    // 6. NET_EVENTS.output(&ctx, &event, 0)
+    let _ = &ctx;
+
+    todo!("Implement netsnoop_udp_send - see docs/04-ebpf/32-netsnoop.md")
+}
+
+/// Remember the socket and message of a `udp_recvmsg` call.
+///
+/// # Arguments (`int udp_recvmsg(struct sock *sk, struct msghdr *msg, size_t len, int flags, int *addr_len)`)
+///
+/// The number of bytes is only known at return, and a kretprobe no longer
+/// has the arguments, so they are stashed here.
+#[kprobe]
+pub fn netsnoop_udp_recv(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 32
+    // Lesson: docs/04-ebpf/32-netsnoop.md
+    //
+    // Implementation steps:
+    // 1. sk = arg 0, msg = arg 1
+    // 2. Check the namespace here already (Sock::netns), so the map only
+    //    holds calls that will be reported
+    // 3. UDP_RECV_INFLIGHT.insert(&bpf_get_current_pid_tgid(), &RecvArgs { .. }, 0)
+    let _ = &ctx;
+
+    todo!("Implement netsnoop_udp_recv - see docs/04-ebpf/32-netsnoop.md")
+}
+
+/// Report a datagram received by a process.
+#[kretprobe]
+pub fn netsnoop_udp_recv_ret(ctx: RetProbeContext) -> u32 {
+    // TODO: Implement in Lesson 32
+    // Lesson: docs/04-ebpf/32-netsnoop.md
+    //
+    // Implementation steps:
+    // 1. let pid_tgid = bpf_get_current_pid_tgid();
+    //    args = UDP_RECV_INFLIGHT.get(&pid_tgid), return 0 if missing; remove it
+    // 2. ret = ctx.ret::<i32>(): bytes, or -errno (-EAGAIN for a
+    //    non-blocking socket with nothing queued: skip those)
+    // 3. direction = NET_DIR_RECV; the local side from Sock::ipv4_addrs()
+    //    becomes daddr/dport, the sender from msg->msg_name saddr/sport
+    // 4. NET_EVENTS.output(&ctx, &event, 0)
+    let _ = &ctx;
+
+    todo!("Implement netsnoop_udp_recv_ret - see docs/04-ebpf/32-netsnoop.md")
+}
+
+// =============================================================================
+// ICMP
+// =============================================================================
+
+/// Report an ICMP message sent in a namespace.
+///
+/// # Arguments (`void icmp_out_count(struct net *net, unsigned char type)`)
+///
+/// The kernel calls this for every outgoing ICMPv4 message to update the
+/// `Icmp: Out*` counters in `/proc/net/snmp`, whoever builds it: ping
+/// sockets, raw sockets, and the kernel's own replies and errors. It has
+/// no packet, so the event has the type but no addresses.
+#[kprobe]
+pub fn netsnoop_icmp_out(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 32
+    // Lesson: docs/04-ebpf/32-netsnoop.md
+    //
+    // Implementation steps:
+    // 1. netns = net_inum(ctx.arg(0)?); return if !wanted(netns)
+    // 2. icmp_type = ctx.arg::<u8>(1)
+    // 3. proto = NET_PROTO_ICMP, direction = NET_DIR_SEND
+    // 4. fill_task() only for echo requests (type 8), which ping sends from
+    //    process context. Replies and errors are built by the kernel in
+    //    softirq, where the current task is unrelated: leave pid 0
+    let _ = &ctx;
+
+    todo!("Implement netsnoop_icmp_out - see docs/04-ebpf/32-netsnoop.md")
+}
+
+/// Report an ICMP message received in a namespace.
+///
+/// # Arguments (`int icmp_rcv(struct sk_buff *skb)`)
+///
+/// Runs in softirq context for every ICMPv4 packet that reached the local
+/// stack, before it is answered or handed to ping and raw sockets.
+#[kprobe]
+pub fn netsnoop_icmp_rcv(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 32
+    // Lesson: docs/04-ebpf/32-netsnoop.md
+    //
+    // Implementation steps:
+    // 1. let skb = unsafe { SkBuff::from_ptr(ctx.arg(0)?) };
+    //    netns = skb.netns(); return if !wanted(netns)
+    // 2. IP header at skb.network_header(): saddr at offset 12, daddr at 16
+    // 3. ICMP header at skb.transport_header(): type at 0, code at 1
+    // 4. len = tot_len (IP offset 2, big-endian) minus the IP header length
+    //    ((byte 0 & 0x0f) * 4)
+    // 5. pid = 0 and comm empty: userspace prints "-" (softirq, no sender)
+    let _ = &ctx;
+
+    todo!("Implement netsnoop_icmp_rcv - see docs/04-ebpf/32-netsnoop.md")
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Whether events in namespace `netns` are wanted (`NETSNOOP_TARGET`).
+#[allow(dead_code)]
+#[inline(always)]
+fn wanted(netns: u32) -> bool {
+    // TODO: Implement in Lesson 32
+    //
+    // [ ] let target = NETSNOOP_TARGET.get(NETSNOOP_TARGET_NETNS).copied().unwrap_or(0)
+    // [ ] target == 0 || target == netns
+    // [ ] netns == 0 (unreadable) only passes without a filter
+    let _ = netns;
+    todo!("Implement wanted")
+}
+
+/// Set timestamp, pid, tid and comm from the current task.
+#[allow(dead_code)]
+#[inline(always)]
+fn fill_task(event: &mut NetEvent) {
+    // TODO: Implement in Lesson 32
+    //
+    // [ ] bpf_ktime_get_ns(), bpf_get_current_pid_tgid() (pid = >> 32,
+    //     tid = low 32 bits), bpf_get_current_comm()
+    let _ = event;
+    todo!("Implement fill_task")
+}
@@ -58,7 +58,8 @@
 //! - **Lesson 04**: [Perf Events](docs/04-ebpf/04-perf-events.md) - PerfEventArray for
 //!   streaming syscall events to userspace
 //! - **Lesson 07**: [Perf Sampling](docs/04-ebpf/07-perf-sampling.md) - CPU profiling
-//!   with stack traces for flame graphs
+//!   with stack traces for flame graphs; `--event faults` reuses the same
+//!   program type for PERF_COUNT_SW_PAGE_FAULTS_MAJ/MIN
 //!
 //! # Safety Considerations
 //!
@@ -72,13 +73,15 @@
 
 use aya_ebpf::{
     macros::{map, perf_event},
-    maps::PerfEventArray,
+    maps::{LruPerCpuHashMap, PerfEventArray},
     programs::PerfEventContext,
     EbpfContext,
 };
 #[allow(unused_imports)]
 use aya_log_ebpf::info;
-use ebpf_tool_common::SyscallEvent;
+use ebpf_tool_common::{
+    FaultKey, SyscallEvent, FAULT_MAJOR, FAULT_MINOR, INSERTS_FAULT_COUNTS, MAX_MAP_ENTRIES,
+};
 
 // =============================================================================
 // PerfEventArray Map (Lesson 04)
@@ -102,7 +105,7 @@ use ebpf_tool_common::SyscallEvent;
 /// # Usage from eBPF
 ///
 /// ```ignore
-/// let event = SyscallEvent { pid, tid, syscall_nr, timestamp_ns, comm };
+/// let event = SyscallEvent { pid, tid, syscall_nr, cgroup_id, timestamp_ns, comm, ..SyscallEvent::new() };
 /// EVENTS.output(&ctx, &event, 0);
 /// ```
 ///
@@ -278,8 +281,10 @@ pub fn perf_sample(ctx: PerfEventContext) -> u32 {
 ///         pid: ctx.pid(),
 ///         tid: ctx.tgid(),
 ///         syscall_nr: 59, // execve
+///         cgroup_id: unsafe { bpf_get_current_cgroup_id() },
 ///         timestamp_ns: unsafe { bpf_ktime_get_ns() },
 ///         comm: [0u8; 16],
+///         ..SyscallEvent::new()
 ///     };
 ///
 ///     if let Err(e) = send_event(&ctx, &event) {
@@ -445,6 +450,96 @@ fn send_event<C: EbpfContext>(ctx: &C, event: &SyscallEvent) -> Result<(), i64>
 //
 // Then update the EVENTS map type or add a separate PerfEventArray for samples.
 
+// =============================================================================
+// Page Fault Sampling (Lesson 07, --event faults)
+// =============================================================================
+
+/// Fault counters aggregated in the kernel, keyed by (pid, kind, user stack).
+///
+/// Page faults can arrive at hundreds of thousands per second during a
+/// memory storm, so instead of streaming one event per fault we count them
+/// here and let userspace read the totals when the run ends.
+///
+/// Per-CPU, because every CPU faults at the same time during a storm: each
+/// CPU increments its own copy of the counter without contention or lost
+/// updates, and userspace sums the copies (see `sum_per_cpu()`).
+///
+/// LRU, because a long storm with many distinct stacks would otherwise fill
+/// the map and silently stop counting new ones. Evicted keys are counted
+/// in `MAP_INSERTS` (see `crate::maps`).
+#[map]
+static FAULT_COUNTS: LruPerCpuHashMap<FaultKey, u64> =
+    LruPerCpuHashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+/// Perf event program for major page faults (PERF_COUNT_SW_PAGE_FAULTS_MAJ).
+///
+/// A major fault has to read the page from disk (or swap). Under a tight
+/// `memory.high` / `memory.max`, reclaim drops page cache and the same pages
+/// fault back in, which shows up as a burst of major faults.
+///
+/// # How Fault Sampling Differs From CPU Sampling
+///
+/// ```text
+/// CPU sampling:   perf_event_open(PERF_TYPE_SOFTWARE, PERF_COUNT_SW_CPU_CLOCK,
+///                                 sample_freq = 99, freq = 1)
+/// Fault sampling: perf_event_open(PERF_TYPE_SOFTWARE, PERF_COUNT_SW_PAGE_FAULTS_MAJ,
+///                                 sample_period = 1)          // every fault
+/// ```
+///
+/// The program runs in the context of the faulting task, so `ctx.tgid()` and
+/// a `BPF_F_USER_STACK` stack id attribute the fault to the code that touched
+/// the memory.
+#[perf_event]
+pub fn perf_fault_major(ctx: PerfEventContext) -> u32 {
+    // TODO: Implement in Lesson 07 (--event faults)
+    // Lesson: docs/04-ebpf/07-perf-sampling.md
+    // Tests: crates/ebpf-tool/tests/perf_test.rs
+    //
+    // Implementation checklist:
+    //
+    // [ ] Call count_fault(&ctx, FAULT_MAJOR) and return 0
+    //
+    // Suppress unused variable warning during scaffolding
+    let _ = &ctx;
+
+    todo!("Implement perf_fault_major - see docs/04-ebpf/07-perf-sampling.md")
+}
+
+/// Perf event program for minor page faults (PERF_COUNT_SW_PAGE_FAULTS_MIN).
+///
+/// A minor fault maps a page that is already in memory (first touch of an
+/// anonymous page, shared page cache). High minor fault rates usually mean a
+/// process is allocating and touching fresh memory.
+#[perf_event]
+pub fn perf_fault_minor(ctx: PerfEventContext) -> u32 {
+    // TODO: Implement in Lesson 07 (--event faults)
+    // Lesson: docs/04-ebpf/07-perf-sampling.md
+    //
+    // Same as perf_fault_major, with FAULT_MINOR.
+    let _ = &ctx;
+
+    todo!("Implement perf_fault_minor - see docs/04-ebpf/07-perf-sampling.md")
+}
+
+/// Shared helper: increment `FAULT_COUNTS[(pid, kind, user_stack_id)]`.
+#[allow(dead_code)]
+#[inline(always)]
+fn count_fault(ctx: &PerfEventContext, kind: u32) {
+    // TODO: Implement in Lesson 07 (--event faults)
+    //
+    // [ ] pid = ctx.tgid()
+    // [ ] user_stack_id = bpf_get_stackid(ctx.as_ptr(), &STACKS, BPF_F_USER_STACK)
+    //     (-1 if the STACKS map is not enabled yet or the call fails)
+    // [ ] key = FaultKey::new(pid, kind, user_stack_id)
+    // [ ] FAULT_COUNTS.get_ptr_mut(&key): increment, or insert 1
+    //     (the pointer is this CPU's copy and no other CPU writes it, so a
+    //     plain `*count += 1` loses no updates)
+    // [ ] After inserting a new key: crate::maps::count_insert(INSERTS_FAULT_COUNTS)
+    let _ = (ctx, kind);
+
+    todo!("Implement count_fault")
+}
+
 // =============================================================================
 // Module Tests
 // =============================================================================
@@ -0,0 +1,198 @@
+//! eBPF Programs for Process Events (`trace --proc-events`, `contain trace events`)
+//!
+//! Three scheduler tracepoints tell when processes come and go. Together
+//! they show a container's life from the host, one line per step:
+//!
+//! ```text
+//! sched_process_fork ─► proc_fork  ─┐
+//! sched_process_exec ─► proc_exec  ─┼─► in_pidns()? ─► PROC_EVENTS ─► userspace
+//! sched_process_exit ─► proc_exit  ─┘        │
+//!                                            └─ PROC_PIDNS[0]: the container's
+//!                                               PID namespace inode, 0 = all
+//! ```
+//!
+//! A container is a PID namespace, so the namespace is the filter: every
+//! program compares the current task's namespace inode with `PROC_PIDNS`
+//! before building an event. Processes of the host never leave the kernel,
+//! however busy the host is.
+//!
+//! The filter looks at the current task, which is the parent for a fork.
+//! The fork that creates a container's init happens in the runtime, on the
+//! host side: the first event of a new container is its init's exec.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 52**: Process Events - watching a container's processes
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/52-process-events.md`
+//! - Tests: `crates/ebpf-tool/tests/procevents_test.rs`, `crates/contain/tests/trace_test.rs`
+//! - Format files: `/sys/kernel/tracing/events/sched/{sched_process_fork,sched_process_exec,sched_process_exit}/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_comm, bpf_get_current_pid_tgid, bpf_ktime_get_ns},
+    macros::{map, tracepoint},
+    maps::{Array, PerfEventArray},
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{
+    ProcEvent, PROC_EXEC, PROC_EXIT, PROC_FORK, STR_PATH_TRUNCATED, STR_READ_FAILED,
+};
+
+use crate::kstruct::Task;
+use crate::scratch::{self, Slot};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Perf event array shared by the three programs.
+#[map]
+static PROC_EVENTS: PerfEventArray<ProcEvent> = PerfEventArray::new(0);
+
+/// The PID namespace inode to report, written by userspace before
+/// attaching; 0 (the initial value) reports every namespace.
+#[map]
+static PROC_PIDNS: Array<u32> = Array::with_max_entries(1, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Whether the current task is in the `PROC_PIDNS` namespace, and its
+/// namespace inode (0 if it could not be read).
+#[allow(dead_code)]
+#[inline(always)]
+fn in_pidns() -> (bool, u32) {
+    // TODO: Implement in Lesson 52
+    //
+    // [ ] let want = PROC_PIDNS.get(0).copied().unwrap_or(0);
+    // [ ] let have = Task::current().ns_ids().map(|ns| ns.pid).unwrap_or(0);
+    // [ ] (want == 0 || have == want, have): an unreadable namespace (no
+    //     BTF) matches only "all", never a container, so a filter never
+    //     shows host processes by mistake
+    // [ ] The pid namespace comes from task->thread_pid, not nsproxy: it
+    //     is still there during exit, after exit_task_namespaces()
+    todo!("Implement in_pidns")
+}
+
+/// Start an event in the `Slot::Event` scratch slot (320 bytes, too large
+/// for the stack) with the fields every kind has.
+#[allow(dead_code)]
+#[inline(always)]
+fn new_event(kind: u32, pidns_inum: u32) -> Option<&'static mut ProcEvent> {
+    // TODO: Implement in Lesson 52
+    //
+    // [ ] let event = scratch::get::<ProcEvent>(Slot::Event)?;
+    // [ ] *event = ProcEvent::new(): the slot holds the last event of this CPU
+    // [ ] kind, pidns_inum, timestamp_ns = bpf_ktime_get_ns()
+    // [ ] pid/tid from bpf_get_current_pid_tgid(), comm from
+    //     bpf_get_current_comm()
+    // [ ] ppid = Task::current().parent_tgid().unwrap_or(0)
+    let _ = (kind, pidns_inum);
+    todo!("Implement new_event")
+}
+
+// =============================================================================
+// sched/sched_process_fork
+// =============================================================================
+
+/// Tracepoint fired in the parent for every new task.
+///
+/// # Tracepoint Format (sched_process_fork)
+///
+/// ```text
+/// field:char parent_comm[16]; offset:8;  size:16; signed:0;
+/// field:pid_t parent_pid;     offset:24; size:4;  signed:1;
+/// field:char child_comm[16];  offset:28; size:16; signed:0;
+/// field:pid_t child_pid;      offset:44; size:4;  signed:1;
+/// ```
+///
+/// The same format as `follow_fork` (Lesson 44) reads, with the same
+/// caveat: check the format file before you hardcode offset 44.
+#[tracepoint]
+pub fn proc_fork(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 52
+    // Lesson: docs/04-ebpf/52-process-events.md
+    // Tests: crates/ebpf-tool/tests/procevents_test.rs
+    //
+    // Implementation steps:
+    // 1. let (wanted, pidns) = in_pidns(); not wanted: return 0
+    // 2. let Some(event) = new_event(PROC_FORK, pidns) else { return 0 };
+    // 3. event.child_pid = ctx.read_at::<i32>(44)? as u32: the child's
+    //    host tid, the same number for a new process
+    // 4. PROC_EVENTS.output(&ctx, event, 0)
+    let _ = &ctx;
+
+    todo!("Implement proc_fork - see docs/04-ebpf/52-process-events.md")
+}
+
+// =============================================================================
+// sched/sched_process_exec
+// =============================================================================
+
+/// Tracepoint fired after a successful exec, in the task running the new
+/// program.
+///
+/// # Tracepoint Format (sched_process_exec)
+///
+/// ```text
+/// field:__data_loc char[] filename; offset:8;  size:4; signed:1;
+/// field:pid_t pid;                  offset:12; size:4; signed:1;
+/// field:pid_t old_pid;              offset:16; size:4; signed:1;
+/// ```
+///
+/// A third program on this tracepoint, next to `exec_tracepoint` and
+/// `follow_exec`; all of them run on every exec.
+#[tracepoint]
+pub fn proc_exec(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 52
+    // Lesson: docs/04-ebpf/52-process-events.md
+    //
+    // Implementation steps:
+    // 1. in_pidns() and new_event(PROC_EXEC, pidns), as in proc_fork.
+    //    comm is already the new program's name
+    // 2. let loc: u32 = ctx.read_at(8)?; the low 16 bits are the offset of
+    //    the string from the start of the record
+    // 3. strings::read_kernel_str(ctx.as_ptr().add((loc & 0xffff) as usize))
+    //    and copy_str() into event.filename; set STR_PATH_TRUNCATED when it
+    //    was cut, STR_READ_FAILED when the read failed (Lesson 37)
+    // 4. PROC_EVENTS.output(&ctx, event, 0)
+    let _ = &ctx;
+
+    todo!("Implement proc_exec - see docs/04-ebpf/52-process-events.md")
+}
+
+// =============================================================================
+// sched/sched_process_exit
+// =============================================================================
+
+/// Tracepoint fired for every exiting task; reports processes, not threads.
+///
+/// # Tracepoint Format (sched_process_exit)
+///
+/// ```text
+/// field:char comm[16]; offset:8;  size:16; signed:0;
+/// field:pid_t pid;     offset:24; size:4;  signed:1;
+/// ```
+///
+/// The exit code is not in the record: it is `task->exit_code`, read with
+/// `Task::exit_code()` (the exiting task is still current).
+#[tracepoint]
+pub fn proc_exit(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 52
+    // Lesson: docs/04-ebpf/52-process-events.md
+    //
+    // Implementation steps:
+    // 1. let pid_tgid = bpf_get_current_pid_tgid(); pid != tid (a thread):
+    //    return 0. The main thread exits last, so this is the process
+    // 2. in_pidns() and new_event(PROC_EXIT, pidns)
+    // 3. event.exit_code = Task::current().exit_code().unwrap_or(0)
+    // 4. PROC_EVENTS.output(&ctx, event, 0)
+    let _ = &ctx;
+
+    todo!("Implement proc_exit - see docs/04-ebpf/52-process-events.md")
+}
@@ -0,0 +1,208 @@
+//! eBPF Programs for Socket Request Latency (accept → read → write → close)
+//!
+//! A request handled by a typical server shows up as a sequence of syscalls
+//! on one connection fd:
+//!
+//! ```text
+//! accept4() = 7 ─┐                                        connection opened
+//!                │  read(7)  = 312   ─┐
+//!                │                    │ request latency   request 1
+//!                │  write(7) = 4096  ─┘
+//!                │  read(7)  = 298   ─┐
+//!                │                    │                   request 2 (keep-alive)
+//!                │  write(7) = 2048  ─┘
+//!                │  read(7)  = 0                          peer closed
+//! close(7)      ─┘                                        connection closed
+//! ```
+//!
+//! The programs here only *report* those syscalls, and only for fds that
+//! came from accept(). Pairing them into requests happens in userspace
+//! (`ReqTracker` in `ebpf-tool`), where the per-connection state machine is
+//! easy to test and debug.
+//!
+//! ```text
+//! sys_exit_accept4 ─► SOCK_FDS.insert(pid, fd) ─► SOCK_EVENTS (SOCK_ACCEPT)
+//! sys_enter_read   ─► tracked fd? ─► INFLIGHT.insert(pid_tgid, fd)
+//! sys_exit_read    ─► INFLIGHT.remove(pid_tgid) ─► SOCK_EVENTS (SOCK_READ, ret)
+//! sys_enter_close  ─► SOCK_FDS.remove(pid, fd) ─► SOCK_EVENTS (SOCK_CLOSE)
+//! ```
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 14**: Request Latency - correlate syscalls per connection
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/14-request-latency.md`
+//! - Tests: `crates/ebpf-tool/tests/reqlat_test.rs`
+//! - Format files: `/sys/kernel/debug/tracing/events/syscalls/{sys_exit_accept4,sys_enter_read,sys_exit_read,sys_enter_close}/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{map, tracepoint},
+    maps::{Array, HashMap, PerfEventArray},
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{
+    SockEvent, SockKey, MAX_MAP_ENTRIES, REQLAT_TARGET_CGROUP, REQLAT_TARGET_PID, SOCK_ACCEPT,
+    SOCK_CLOSE, SOCK_READ, SOCK_WRITE,
+};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Connection events for userspace, in per-CPU order.
+#[map]
+static SOCK_EVENTS: PerfEventArray<SockEvent> = PerfEventArray::new(0);
+
+/// Connections being tracked: fds returned by accept() in a target process.
+///
+/// The value is unused; membership is what matters.
+#[map]
+static SOCK_FDS: HashMap<SockKey, u8> = HashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+/// fd of the read/write a thread is currently in, keyed by pid_tgid.
+///
+/// The exit tracepoints only have the return value, so the enter program
+/// stashes the fd here for its exit program to pick up.
+#[map]
+static INFLIGHT: HashMap<u64, u32> = HashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+/// Target filter written by userspace before attaching.
+///
+/// `REQLAT_TARGET_PID` holds the server PID and `REQLAT_TARGET_CGROUP` the
+/// cgroup id. Either can be 0, which means "any".
+#[map]
+static REQLAT_TARGET: Array<u64> = Array::with_max_entries(2, 0);
+
+// =============================================================================
+// syscalls/sys_exit_accept4 (and sys_exit_accept)
+// =============================================================================
+
+/// Start tracking a new connection.
+///
+/// # Tracepoint Format (sys_exit_accept4)
+///
+/// ```text
+/// field:int __syscall_nr;  offset:8;  size:4; signed:1;
+/// field:long ret;          offset:16; size:8; signed:1;
+/// ```
+///
+/// `ret` is the new connection fd. Attach the same program to
+/// `sys_exit_accept` for servers that still call accept().
+#[tracepoint]
+pub fn reqlat_accept_exit(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 14
+    // Lesson: docs/04-ebpf/14-request-latency.md
+    // Tests: crates/ebpf-tool/tests/reqlat_test.rs
+    //
+    // Implementation steps:
+    // 1. Return 0 unless the current task matches REQLAT_TARGET
+    //    (pid = bpf_get_current_pid_tgid() >> 32, bpf_get_current_cgroup_id())
+    // 2. let ret: i64 = unsafe { ctx.read_at(16)? }; return if ret < 0
+    // 3. SOCK_FDS.insert(&SockKey::new(pid, ret as u32), &1, 0)
+    // 4. SOCK_EVENTS.output(&ctx, &event, 0) with kind = SOCK_ACCEPT
+    let _ = &ctx;
+
+    todo!("Implement reqlat_accept_exit - see docs/04-ebpf/14-request-latency.md")
+}
+
+// =============================================================================
+// syscalls/sys_enter_{read,write,recvfrom,sendto}
+// =============================================================================
+
+/// Remember the fd of a read or write on a tracked connection.
+///
+/// # Tracepoint Format (sys_enter_read)
+///
+/// ```text
+/// field:unsigned int fd;   offset:16; size:8; signed:0;
+/// ```
+///
+/// The fd is the first argument of all four syscalls, so one program is
+/// attached to every enter tracepoint.
+#[tracepoint]
+pub fn reqlat_rw_enter(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 14
+    // Lesson: docs/04-ebpf/14-request-latency.md
+    //
+    // Implementation steps:
+    // 1. let fd: u64 = unsafe { ctx.read_at(16)? };
+    // 2. Return 0 unless SOCK_FDS contains SockKey::new(pid, fd as u32).
+    //    This single lookup is what keeps file and pipe I/O out of the
+    //    perf buffer
+    // 3. INFLIGHT.insert(&bpf_get_current_pid_tgid(), &(fd as u32), 0)
+    let _ = &ctx;
+
+    todo!("Implement reqlat_rw_enter - see docs/04-ebpf/14-request-latency.md")
+}
+
+// =============================================================================
+// syscalls/sys_exit_{read,recvfrom} and sys_exit_{write,sendto}
+// =============================================================================
+
+/// Report a completed read on a tracked connection.
+///
+/// Attached to `sys_exit_read` and `sys_exit_recvfrom`.
+#[tracepoint]
+pub fn reqlat_read_exit(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 14
+    // Lesson: docs/04-ebpf/14-request-latency.md
+    //
+    // Implementation steps:
+    // 1. let fd = INFLIGHT.get(&pid_tgid); return 0 if None (not tracked)
+    // 2. INFLIGHT.remove(&pid_tgid) - always, or the entry leaks
+    // 3. let ret: i64 = unsafe { ctx.read_at(16)? };
+    // 4. Output a SockEvent with kind = SOCK_READ and ret. Keep ret <= 0
+    //    too: 0 is "peer closed", -EAGAIN is a non-blocking read with no
+    //    data, and userspace needs to tell them apart
+    let _ = &ctx;
+
+    todo!("Implement reqlat_read_exit - see docs/04-ebpf/14-request-latency.md")
+}
+
+/// Report a completed write on a tracked connection.
+///
+/// Attached to `sys_exit_write` and `sys_exit_sendto`. Same steps as
+/// `reqlat_read_exit`, with kind = `SOCK_WRITE`.
+#[tracepoint]
+pub fn reqlat_write_exit(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 14
+    // Lesson: docs/04-ebpf/14-request-latency.md
+    //
+    // Hint: share the body with reqlat_read_exit through a helper
+    // fn try_rw_exit(ctx: &TracePointContext, kind: u32) -> Result<u32, i64>
+    let _ = &ctx;
+
+    todo!("Implement reqlat_write_exit - see docs/04-ebpf/14-request-latency.md")
+}
+
+// =============================================================================
+// syscalls/sys_enter_close
+// =============================================================================
+
+/// Stop tracking a connection.
+///
+/// # Tracepoint Format (sys_enter_close)
+///
+/// ```text
+/// field:unsigned int fd;   offset:16; size:8; signed:0;
+/// ```
+///
+/// Use the enter tracepoint, not exit: after close() returns, the fd number
+/// can already be reused by another accept() on a different thread.
+#[tracepoint]
+pub fn reqlat_close_enter(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 14
+    // Lesson: docs/04-ebpf/14-request-latency.md
+    //
+    // Implementation steps:
+    // 1. Read fd at offset 16 and build SockKey::new(pid, fd as u32)
+    // 2. If SOCK_FDS.remove(&key) succeeds, output kind = SOCK_CLOSE;
+    //    otherwise it was not a tracked connection: return 0
+    let _ = &ctx;
+
+    todo!("Implement reqlat_close_enter - see docs/04-ebpf/14-request-latency.md")
+}
@@ -0,0 +1,87 @@
+//! In-Kernel Sampling (`trace --sample 1/N`)
+//!
+//! `read()` and `write()` can run millions of times a second. Filters can
+//! only drop the events nobody asked for; on a hot syscall that is the
+//! events somebody did ask for. Sampling sends about one in N of them, so
+//! the perf buffer traffic (and the tracer's CPU time) is divided by N:
+//!
+//! ```text
+//! syscall ─► filters ─► SYSCALL_COUNTS += 1 ─► sample_keep(N)? ─► emit_syscall()
+//!              │             (exact)                │ no
+//!              └─ dropped                           └─ dropped, but counted
+//! ```
+//!
+//! The counting map is updated before sampling, so totals stay exact and
+//! only the per-event detail is thinned out.
+//!
+//! Each CPU keeps its own countdown in a per-CPU array: no atomics, no
+//! contention, and the verifier only sees a map value it owns. A fixed
+//! "every Nth" would alias with periodic workloads: a loop of read, write,
+//! read, write sampled 1/2 would only ever show one of the two. The
+//! countdown is therefore reset to a random value with mean N.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 40**: In-Kernel Sampling - bounded overhead on hot syscalls
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/40-sampling.md`
+//! - Tests: `crates/ebpf-tool/tests/sample_test.rs`
+//! - Config: `ebpf_tool_common::FILTER_KEY_SAMPLE` in `FILTER_CONFIG`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{helpers::bpf_get_prandom_u32, macros::map, maps::PerCpuArray};
+use ebpf_tool_common::{FILTER_KEY_SAMPLE, SAMPLE_MAX_RATE};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Events this CPU still skips before it sends the next one.
+///
+/// Starts at 0, so the first matching event on every CPU is sent.
+#[map]
+static SAMPLE_COUNTDOWN: PerCpuArray<u32> = PerCpuArray::with_max_entries(1, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Decide whether to send this event, with `rate` = N of `--sample 1/N`
+/// (read from `FILTER_CONFIG[FILTER_KEY_SAMPLE]` by the caller).
+///
+/// Call it after the filters and after counting in `SYSCALL_COUNTS`, right
+/// before `emit_syscall()`.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn sample_keep(rate: u32) -> bool {
+    // TODO: Implement in Lesson 40
+    //
+    // [ ] rate <= 1: return true without touching the map (the default
+    //     path stays as cheap as before)
+    // [ ] let Some(ptr) = SAMPLE_COUNTDOWN.get_ptr_mut(0) else { return true };
+    //     a missing slot must not silently drop everything
+    // [ ] Countdown > 0: decrement it and return false
+    // [ ] Countdown == 0: reset it to next_countdown(rate) and return true
+    let _ = rate;
+    todo!("Implement sample_keep")
+}
+
+/// Events to skip after a sent one: uniform in `0..=2(N-1)`, so one event
+/// in N is sent on average.
+#[allow(dead_code)]
+#[inline(always)]
+fn next_countdown(rate: u32) -> u32 {
+    // TODO: Implement in Lesson 40
+    //
+    // [ ] bpf_get_prandom_u32() % (2 * (rate - 1) + 1): mean rate - 1 skips,
+    //     plus the sent event, is one in rate
+    // [ ] rate is at most SAMPLE_MAX_RATE (checked in userspace), so
+    //     2 * rate cannot overflow
+    // [ ] Do not use `rate - 1` as the countdown: a fixed period aliases
+    //     with loops that alternate syscalls
+    let _ = rate;
+    todo!("Implement next_countdown")
+}
@@ -0,0 +1,115 @@
+//! Per-CPU Scratch Space for Large Events
+//!
+//! A BPF program has 512 bytes of stack for its whole call chain. An
+//! `ExecEvent` is 832 bytes, a `DnsEvent` 320, a `MountEvent` 272: built as
+//! locals they fail to load with "combined stack size" or "invalid write to
+//! stack R10" (Lesson 28). They are built in a per-CPU map value instead,
+//! and every program does it the same way, through this module:
+//!
+//! ```text
+//! scratch::get::<MountEvent>(Slot::Event) ─► SCRATCH[Slot::Event] on this CPU ─► &mut MountEvent
+//! strings::read_user_str()                ─► SCRATCH[Slot::Str]
+//! tailcall::dispatch()                    ─► SCRATCH[Slot::Tail] ─► (tail call) ─► handler
+//! ```
+//!
+//! - One map with one 1 KiB slot per use, not one map per event type: a
+//!   new event type needs no new map, and programs that never use a slot
+//!   pay nothing for it
+//! - A slot is a buffer to build in, not storage: it holds whatever the last
+//!   program on this CPU left there. Start from `T::new()` and set every
+//!   field you send (`copy_str()` NUL-terminates for that reason)
+//! - The slots exist so that one program can hold several buffers at once
+//!   (an event being built and the string being copied into it). Two
+//!   values in the same slot overwrite each other
+//! - State that has to survive between calls (`BATCH_SCRATCH`, the
+//!   `*_INFLIGHT` maps) is not scratch space and keeps its own map
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 37**: Bounded Strings - "Scratch Space for Large Events"
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/37-bounded-strings.md`
+//! - Users: `strings.rs`, `mounts.rs`, `dns.rs`, `tailcall.rs`, `procevents.rs`, `tracepoint.rs` (`exec_tracepoint`)
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+
+use aya_ebpf::{macros::map, maps::PerCpuArray};
+
+/// Bytes in one slot: room for the largest event built in a program
+/// (`ExecEvent`, 832 bytes), rounded up to a power of two.
+pub const SCRATCH_SIZE: usize = 1024;
+
+/// What a slot is used for. One program may use several slots at once,
+/// but never one slot for two values.
+#[allow(dead_code)]
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum Slot {
+    /// The event a program is building (`MountEvent`, `DnsEvent`, `ExecEvent`, ...)
+    Event = 0,
+    /// The raw string of `strings::read_user_str()` / `read_kernel_str()`
+    Str = 1,
+    /// The event handed to a tail-call handler (Lesson 51)
+    Tail = 2,
+}
+
+/// Number of `Slot` variants: the entries of `SCRATCH`.
+const SCRATCH_SLOTS: u32 = 3;
+
+/// One slot's bytes, 8-byte aligned like every event type.
+#[repr(C, align(8))]
+pub struct ScratchBuf([u8; SCRATCH_SIZE]);
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// The slots of every CPU: `SCRATCH_SLOTS` KiB per CPU in total.
+///
+/// A program runs to completion on its CPU, and kprobe, tracepoint and
+/// uprobe programs do not nest on one CPU, so one copy per CPU is enough.
+#[map]
+static SCRATCH: PerCpuArray<ScratchBuf> = PerCpuArray::with_max_entries(SCRATCH_SLOTS, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Compile-time check that `T` fits in a slot.
+///
+/// Evaluated when `get::<T>` is instantiated: an event type that grows past
+/// `SCRATCH_SIZE` fails the build of the eBPF object, not the verifier.
+struct Fits<T>(PhantomData<T>);
+
+impl<T> Fits<T> {
+    const OK: () = assert!(
+        size_of::<T>() <= SCRATCH_SIZE && align_of::<T>() <= align_of::<ScratchBuf>(),
+        "type does not fit in a scratch slot: raise SCRATCH_SIZE"
+    );
+}
+
+/// This CPU's `slot`, as a `T`.
+///
+/// The contents are what the last user of the slot left: reset them with
+/// `*event = T::new()`, which also sets `abi`. `None` only if the map is missing, which the
+/// loader prevents; callers return as they would on a failed read.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn get<T: Copy>(slot: Slot) -> Option<&'static mut T> {
+    // TODO: Implement in Lesson 37 ("Scratch Space for Large Events")
+    //
+    // [ ] let () = Fits::<T>::OK; first: the assertion only runs for the
+    //     types that are actually used
+    // [ ] let ptr = SCRATCH.get_ptr_mut(slot as u32)?;
+    // [ ] Some(unsafe { &mut *(ptr as *mut T) }): the map value is aligned
+    //     and large enough (checked above), and T is Copy, so no destructor
+    //     ever runs on the old bytes
+    let _ = slot;
+
+    todo!("Implement scratch::get")
+}
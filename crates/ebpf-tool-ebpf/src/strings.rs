@@ -0,0 +1,175 @@
+//! Bounded String Reads (paths, argv, probe arguments)
+//!
+//! Strings come from two places, and neither can be read like a Rust `&str`:
+//!
+//! - **User memory** (`openat`'s filename, `execve`'s argv, a uprobe's
+//!   `char *` argument): a pointer into the calling process, valid only
+//!   while the syscall or function runs, possibly paged out
+//! - **Kernel memory** (`file->f_path.dentry->d_name.name`, `bprm->filename`):
+//!   readable, but only through `bpf_probe_read_kernel_str`
+//!
+//! The `bpf_probe_read_{user,kernel}_str` helpers copy up to the first NUL
+//! into a buffer of a size known at load time, and always NUL-terminate. The
+//! helpers below wrap them with what every caller needs:
+//!
+//! ```text
+//! read_user_str(ptr) ──► Slot::Str (per-CPU, 512 bytes) ────► StrRead { len, truncated }
+//!                                                               │
+//!             copy_str(&read, &mut event.filename) ◄────────────┘ len & (N - 1)
+//! ```
+//!
+//! - The scratch buffer is the `Slot::Str` slot of `crate::scratch`, not
+//!   the 512-byte stack: one `PATH_LEN` buffer plus an event would not fit.
+//!   The event the string goes into is in `Slot::Event`, so both are held
+//!   at once
+//! - Lengths are masked with `& (N - 1)` (every size is a power of two)
+//!   before they index or size anything, so the verifier sees a bound
+//!   without following a comparison
+//! - A string longer than its field is cut and reported as truncated, so
+//!   events set a `STR_*` flag instead of silently losing bytes
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 37**: Bounded Strings - paths and argv without verifier errors
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/37-bounded-strings.md`
+//! - Tests: `crates/ebpf-tool-common/src/lib.rs` (`test_string_fields`; the
+//!   readers are exercised by the exec and open tracers that use them)
+//! - Fields: `ebpf_tool_common::{PATH_LEN, ARG_LEN, MAX_ARGS, PROBE_ARG_LEN}`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::helpers::{
+    bpf_probe_read_kernel_str_bytes, bpf_probe_read_user, bpf_probe_read_user_str_bytes,
+};
+use ebpf_tool_common::{
+    ARG_LEN, MAX_ARGS, PATH_LEN, STR_ARGS_DROPPED, STR_ARG_TRUNCATED, STR_READ_FAILED,
+};
+
+use crate::scratch::{self, Slot};
+
+/// Size of the per-CPU scratch buffer: the longest single string read.
+///
+/// Twice `PATH_LEN`, so a path one byte too long is still read in full
+/// and reported as truncated rather than looking like it fit.
+pub const SCRATCH_LEN: usize = 2 * PATH_LEN;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Result of a bounded read: the string sits at the start of the scratch
+/// buffer.
+#[derive(Clone, Copy)]
+pub struct StrRead {
+    /// Bytes before the NUL, at most `SCRATCH_LEN - 1`
+    pub len: usize,
+    /// The string did not end within the buffer
+    pub truncated: bool,
+}
+
+/// This CPU's string buffer.
+///
+/// Do not hold on to it across a helper that may run another program
+/// (`bpf_tail_call`): the next string read overwrites it.
+#[allow(dead_code)]
+#[inline(always)]
+fn str_buf() -> Option<&'static mut [u8; SCRATCH_LEN]> {
+    // TODO: Implement in Lesson 37
+    //
+    // [ ] scratch::get::<[u8; SCRATCH_LEN]>(Slot::Str)
+    // [ ] None only if the map is missing, which the loader prevents;
+    //     callers treat it like a failed read
+    todo!("Implement str_buf")
+}
+
+/// Copy a NUL-terminated string from user memory into the scratch buffer.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn read_user_str(src: *const u8) -> Result<StrRead, i64> {
+    // TODO: Implement in Lesson 37
+    //
+    // [ ] Err(-14) (EFAULT) for a NULL src without calling the helper:
+    //     mount's dev_name and type may be NULL
+    // [ ] let buf = str_buf().ok_or(-12)?;
+    // [ ] unsafe { bpf_probe_read_user_str_bytes(src, buf) } returns the
+    //     bytes before the NUL as a slice of buf
+    // [ ] truncated = len == SCRATCH_LEN - 1: the helper stops one byte
+    //     short to write the NUL, so a full buffer means "maybe longer"
+    // [ ] Mask before returning: len & (SCRATCH_LEN - 1)
+    let _ = src;
+    todo!("Implement read_user_str")
+}
+
+/// Copy a NUL-terminated string from kernel memory into the scratch buffer.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn read_kernel_str(src: *const u8) -> Result<StrRead, i64> {
+    // TODO: Implement in Lesson 37
+    //
+    // [ ] Same as read_user_str with bpf_probe_read_kernel_str_bytes
+    // [ ] Kernel strings for names (d_name, comm) are short; paths built
+    //     from dentries are not a single string and need bpf_d_path
+    //     (LSM and fentry programs only)
+    let _ = src;
+    todo!("Implement read_kernel_str")
+}
+
+/// Copy the string of the last read into a fixed field and NUL-pad it.
+///
+/// Returns true if the field cut it: the read was truncated, or longer
+/// than `N - 1`.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn copy_str<const N: usize>(read: &StrRead, dst: &mut [u8; N]) -> bool {
+    // TODO: Implement in Lesson 37
+    //
+    // [ ] N is PATH_LEN, ARG_LEN, PROBE_ARG_LEN, ...: all powers of two,
+    //     so let len = read.len.min(N - 1) & (N - 1) is a bound the
+    //     verifier accepts as the copy size
+    // [ ] Copy from str_buf() into dst[..len] with a bounded loop
+    //     (for i in 0..N { if i >= len { break } ... }) or
+    //     bpf_probe_read_kernel of len bytes from the map value
+    // [ ] dst[len] = 0: the field may hold the previous event's bytes when
+    //     it lives in a scratch slot
+    // [ ] Return read.truncated || read.len > N - 1
+    let _ = (read, dst);
+    todo!("Implement copy_str")
+}
+
+/// Read a string straight into a fixed field: [`read_user_str`], then
+/// [`copy_str`]. Returns the `STR_*` flags to set on the event.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn read_user_field<const N: usize>(src: *const u8, dst: &mut [u8; N], cut_flag: u32) -> u32 {
+    // TODO: Implement in Lesson 37
+    //
+    // [ ] Ok(read) => if copy_str(&read, dst) { cut_flag } else { 0 }
+    // [ ] Err(_) => dst[0] = 0 and STR_READ_FAILED: a paged-out string is
+    //     common (execve of a freshly mapped binary), not a program error
+    let _ = (src, dst, cut_flag);
+    todo!("Implement read_user_field")
+}
+
+/// Copy up to `MAX_ARGS` argv entries from user memory into a packed field
+/// with one `ARG_LEN` slot per entry. Returns `(argc, flags)`.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn read_user_argv(argv: *const *const u8, dst: &mut [u8; MAX_ARGS * ARG_LEN]) -> (u32, u32) {
+    // TODO: Implement in Lesson 37
+    //
+    // [ ] for i in 0..MAX_ARGS (a constant bound: the verifier unrolls or
+    //     checks it, a `while ptr != NULL` loop it rejects)
+    // [ ] let ptr: *const u8 = bpf_probe_read_user(argv.add(i))?; NULL ends argv
+    // [ ] The slot is a fixed sub-array: take it with
+    //     (&mut dst[i * ARG_LEN..][..ARG_LEN]).try_into() -> &mut [u8; ARG_LEN]
+    //     and use read_user_field(ptr, slot, STR_ARG_TRUNCATED)
+    // [ ] After MAX_ARGS entries, read one more pointer: non-NULL means
+    //     STR_ARGS_DROPPED. Count the rest for argc only up to a fixed bound
+    //     (say 64), or take argc from the caller (bprm->argc in
+    //     sched_process_exec)
+    let _ = (argv, dst);
+    todo!("Implement read_user_argv")
+}
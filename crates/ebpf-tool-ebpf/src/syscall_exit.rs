@@ -0,0 +1,127 @@
+//! eBPF Programs for Syscall Exits (return values for `trace --returns`)
+//!
+//! The tracer's entry event knows which syscall was called, not what it
+//! returned. The return value only exists at `raw_syscalls/sys_exit`, which
+//! has the syscall number and `ret` but nothing else. The two are joined in
+//! the kernel through a map keyed by thread:
+//!
+//! ```text
+//! sys_enter (trace entry program, event sent)
+//!     ─► record_pending(pid_tgid, nr, timestamp_ns)
+//!        ─► PENDING_SYSCALLS.insert(pid_tgid, PendingSyscall)
+//!
+//! raw_syscalls/sys_exit ─► PENDING_SYSCALLS.get(pid_tgid)?  no ─► return
+//!                       ─► SYSCALL_EXITS (SyscallExitEvent with ret, enter_ns)
+//!                       ─► PENDING_SYSCALLS.remove(pid_tgid)
+//! ```
+//!
+//! Only syscalls whose entry was sent are pending, so the exit needs no
+//! filter of its own: the entry program already applied the cgroup, pid and
+//! syscall filters. Userspace then pairs each exit with its entry event by
+//! `(tid, enter_ns)`. They can come from different CPUs' perf buffers when
+//! the thread migrated while it was blocked in the syscall.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 23**: Syscall Exits - return values and errno names
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/23-syscall-exits.md`
+//! - Tests: `crates/ebpf-tool/tests/returns_test.rs`
+//! - Format file: `/sys/kernel/debug/tracing/events/raw_syscalls/sys_exit/format`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_pid_tgid, bpf_ktime_get_ns},
+    macros::{map, tracepoint},
+    maps::{LruHashMap, PerfEventArray},
+    programs::TracePointContext,
+};
+use ebpf_tool_common::{PendingSyscall, SyscallExitEvent, MAX_MAP_ENTRIES};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// Exits of traced syscalls for userspace, in per-CPU order.
+#[map]
+static SYSCALL_EXITS: PerfEventArray<SyscallExitEvent> = PerfEventArray::new(0);
+
+/// Syscalls between enter and exit, keyed by pid_tgid.
+///
+/// LRU, unlike `MOUNT_INFLIGHT`: every traced thread can be pending at once,
+/// and exit()/exit_group() and killed tasks never reach sys_exit. Their
+/// entries are evicted by newer ones instead of making inserts fail.
+#[map]
+static PENDING_SYSCALLS: LruHashMap<u64, PendingSyscall> =
+    LruHashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Remember a syscall whose entry event was just sent.
+///
+/// Called by the trace entry program after `emit_syscall()`, and only when
+/// `FILTER_CONFIG[FILTER_KEY_EXITS]` is 1: without `--returns` nothing reads
+/// the exits, and the map update would be wasted work on every syscall.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn record_pending(pid_tgid: u64, syscall_nr: u64, enter_ns: u64) {
+    // TODO: Implement in Lesson 23
+    // Lesson: docs/04-ebpf/23-syscall-exits.md
+    //
+    // Implementation steps:
+    // 1. let pending = PendingSyscall::new(syscall_nr, enter_ns);
+    // 2. let _ = PENDING_SYSCALLS.insert(&pid_tgid, &pending, 0);
+    //    A failed insert only costs this one return value: ignore it
+    // 3. enter_ns must be the entry event's timestamp_ns, not a second
+    //    bpf_ktime_get_ns(): userspace matches on it
+    let _ = (pid_tgid, syscall_nr, enter_ns);
+
+    todo!("Implement record_pending - see docs/04-ebpf/23-syscall-exits.md")
+}
+
+// =============================================================================
+// raw_syscalls/sys_exit
+// =============================================================================
+
+/// Report the return value of a pending syscall.
+///
+/// # Tracepoint Format (raw_syscalls/sys_exit)
+///
+/// ```text
+/// field:long id;   offset:8;  size:8; signed:1;
+/// field:long ret;  offset:16; size:8; signed:1;
+/// ```
+///
+/// One tracepoint for every syscall, like `raw_syscalls/sys_enter`: a
+/// kretprobe per `__x64_sys_*` function would need one attachment per
+/// syscall and is architecture-specific.
+#[tracepoint]
+pub fn syscall_exit_tracepoint(ctx: TracePointContext) -> u32 {
+    // TODO: Implement in Lesson 23
+    // Lesson: docs/04-ebpf/23-syscall-exits.md
+    // Tests: crates/ebpf-tool/tests/returns_test.rs
+    //
+    // Implementation steps:
+    // 1. let pid_tgid = bpf_get_current_pid_tgid();
+    // 2. let Some(pending) = (unsafe { PENDING_SYSCALLS.get(&pid_tgid) }) else { return 0 };
+    //    Missing: not traced, or entered before the tracer attached
+    // 3. let id: i64 = ctx.read_at(8)?; if id as u64 != pending.syscall_nr,
+    //    the entry was lost (evicted, or its exit never came) and this is
+    //    a different call: remove the entry and return 0
+    // 4. Fill a SyscallExitEvent: pid/tid from pid_tgid, syscall_nr,
+    //    ret = ctx.read_at::<i64>(16)?, enter_ns = pending.enter_ns,
+    //    timestamp_ns = bpf_ktime_get_ns()
+    // 5. SYSCALL_EXITS.output(&ctx, &event, 0), then
+    //    PENDING_SYSCALLS.remove(&pid_tgid)
+    //
+    // execve() is special: on success, sys_exit runs in the new program,
+    // with the same pid_tgid, and ret = 0. The pairing still works.
+    let _ = &ctx;
+
+    todo!("Implement syscall_exit_tracepoint - see docs/04-ebpf/23-syscall-exits.md")
+}
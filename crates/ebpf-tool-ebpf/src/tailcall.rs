@@ -0,0 +1,181 @@
+//! Tail Calls: One Handler per Syscall Category
+//!
+//! Every feature of the syscall entry program adds instructions and
+//! branches to the one program, and the verifier explores every path
+//! through it. `bpf_tail_call()` splits it: the entry program does the
+//! work every event needs, then jumps to a separate program for the
+//! syscall's category, which the verifier checks on its own:
+//!
+//! ```text
+//! syscall_kprobe ─► filters, build SyscallEvent ─► FILTER_KEY_TAIL == 1?
+//!                                                   no ─► emit as before
+//!                                                   yes ─► dispatch()
+//!   dispatch(): SYSCALL_CATEGORIES[nr] ─► (none) drop
+//!               scratch Slot::Tail = event
+//!               SYSCALL_HANDLERS.tail_call(slot) ─► syscall_file_handler  (TAIL_FILE)
+//!                                                ─► syscall_net_handler   (TAIL_NET)
+//!                                                ─► syscall_proc_handler  (TAIL_PROC)
+//!               (returns: the slot is empty, the category is off) ─► drop
+//! ```
+//!
+//! A tail call never returns: on success the handler replaces the entry
+//! program, on the same stack frame budget and with only `ctx`. The event
+//! therefore travels through `Slot::Tail` of `crate::scratch`, a per-CPU
+//! slot that nothing else writes; nothing else runs on this CPU between
+//! the write and the handler.
+//!
+//! Userspace decides which categories exist: it fills `SYSCALL_CATEGORIES`
+//! from syscall names and only puts the enabled handlers into
+//! `SYSCALL_HANDLERS`.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 51**: Tail Calls - per-category handlers (`trace --category`)
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/51-tail-calls.md`
+//! - Tests: `crates/ebpf-tool/tests/tailcall_test.rs`
+//! - Types: `ebpf_tool_common::TAIL_*`, `FILTER_KEY_TAIL`; the event slot: `crate::scratch::Slot::Tail`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{kprobe, map},
+    maps::{HashMap, ProgramArray},
+    programs::ProbeContext,
+};
+use ebpf_tool_common::{
+    SyscallEvent, TAIL_CATEGORY_MAX_ENTRIES, TAIL_FILE, TAIL_NET, TAIL_PROC, TAIL_SLOTS,
+};
+
+use crate::scratch::{self, Slot};
+
+// =============================================================================
+// Maps
+// =============================================================================
+
+/// The category handlers, at `TAIL_FILE`, `TAIL_NET` and `TAIL_PROC`.
+///
+/// Filled by userspace with the file descriptors of the loaded handler
+/// programs. A slot left empty makes the tail call fail, which is how a
+/// category is turned off.
+#[map]
+static SYSCALL_HANDLERS: ProgramArray = ProgramArray::with_max_entries(TAIL_SLOTS, 0);
+
+/// Syscall number -> `SYSCALL_HANDLERS` slot, for the syscalls that have a
+/// category. Numbers differ between architectures, names do not: userspace
+/// builds this map from the names with its syscall table.
+#[map]
+static SYSCALL_CATEGORIES: HashMap<u64, u32> =
+    HashMap::with_max_entries(TAIL_CATEGORY_MAX_ENTRIES, 0);
+
+// =============================================================================
+// Dispatch
+// =============================================================================
+
+/// Hand `event` to the handler of its syscall's category.
+///
+/// Called by the entry program instead of sending the event, when
+/// `FILTER_CONFIG[FILTER_KEY_TAIL]` is 1. Returns only when the event is
+/// dropped: no category, category off, or no scratch slot.
+#[allow(dead_code)]
+#[inline(always)]
+pub fn dispatch(ctx: &ProbeContext, event: &SyscallEvent) {
+    // TODO: Implement in Lesson 51
+    // Lesson: docs/04-ebpf/51-tail-calls.md
+    //
+    // [ ] let Some(&slot) = (unsafe { SYSCALL_CATEGORIES.get(&event.syscall_nr) })
+    //     else { return }: a syscall without a category is not traced
+    // [ ] let Some(slot) = scratch::get::<SyscallEvent>(Slot::Tail) else { return };
+    //     *slot = *event. 72 bytes would fit on the stack, but a tail call
+    //     does not keep the caller's stack
+    // [ ] let _ = unsafe { SYSCALL_HANDLERS.tail_call(ctx, slot) };
+    //     On success this never returns. On failure (empty slot, or more
+    //     than 33 tail calls in a row) fall through and return
+    // [ ] Do not count or sample here: SYSCALL_COUNTS was updated before
+    //     the entry program called dispatch(), and sampling belongs to the
+    //     handler, next to the output
+    let _ = (ctx, event);
+
+    todo!("Implement dispatch - see docs/04-ebpf/51-tail-calls.md")
+}
+
+/// The event the entry program left for this handler.
+#[allow(dead_code)]
+#[inline(always)]
+fn tail_event() -> Option<SyscallEvent> {
+    // TODO: Implement in Lesson 51
+    //
+    // [ ] scratch::get::<SyscallEvent>(Slot::Tail).map(|e| *e)
+    // [ ] A handler runs right after dispatch() on the same CPU, so the
+    //     slot holds this syscall's event; there is nothing to clear
+    todo!("Implement tail_event")
+}
+
+/// What every handler ends with: sample, send, remember for `--returns`.
+#[allow(dead_code)]
+#[inline(always)]
+fn finish(ctx: &ProbeContext, event: &SyscallEvent) {
+    // TODO: Implement in Lesson 51
+    //
+    // [ ] The end of try_syscall_kprobe(), moved here unchanged:
+    //     crate::sample::sample_keep(FILTER_KEY_SAMPLE rate), then
+    //     crate::batch::emit_syscall(ctx, event, &EVENTS) (make EVENTS in
+    //     kprobe.rs pub(crate)), then crate::syscall_exit::record_pending()
+    //     when FILTER_KEY_EXITS is 1
+    // [ ] #[inline(always)]: every handler gets its own copy, which is the
+    //     point; each one is verified separately
+    let _ = (ctx, event);
+    todo!("Implement finish")
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// File syscalls: open, read, write, stat, unlink, rename, ...
+///
+/// Attached nowhere: loaded by userspace and stored in
+/// `SYSCALL_HANDLERS[TAIL_FILE]`. It must be a kprobe like the entry
+/// program; a program array only jumps between programs of one type.
+#[kprobe]
+pub fn syscall_file_handler(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 51
+    // Tests: crates/ebpf-tool/tests/tailcall_test.rs
+    //
+    // [ ] let Some(event) = tail_event() else { return 0 };
+    // [ ] Room for file-only work that would be too much for one program,
+    //     e.g. copying the path argument with crate::strings (Lesson 37):
+    //     only file syscalls pay for it
+    // [ ] finish(&ctx, &event); return 0
+    let _ = &ctx;
+
+    todo!("Implement syscall_file_handler - see docs/04-ebpf/51-tail-calls.md")
+}
+
+/// Network syscalls: socket, connect, accept, sendto, recvmsg, ...
+#[kprobe]
+pub fn syscall_net_handler(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 51
+    //
+    // [ ] Same shape as syscall_file_handler
+    // [ ] Room for network-only work, e.g. reading the sockaddr of
+    //     connect() and bind(), as netsnoop does for UDP (Lesson 32)
+    let _ = &ctx;
+
+    todo!("Implement syscall_net_handler - see docs/04-ebpf/51-tail-calls.md")
+}
+
+/// Process syscalls: clone, execve, exit, kill, setns, unshare, ...
+#[kprobe]
+pub fn syscall_proc_handler(ctx: ProbeContext) -> u32 {
+    // TODO: Implement in Lesson 51
+    //
+    // [ ] Same shape as syscall_file_handler
+    // [ ] Room for process-only work, e.g. the namespace ids from
+    //     crate::kstruct (Lesson 31), which change with setns and unshare
+    let _ = &ctx;
+
+    todo!("Implement syscall_proc_handler - see docs/04-ebpf/51-tail-calls.md")
+}
@@ -0,0 +1,129 @@
+//! eBPF Task Iterator (bpf_iter)
+//!
+//! Every other program in this crate runs when something *happens*. An
+//! iterator runs when userspace *reads* from it. The kernel walks a kernel
+//! data structure (here: every task) and calls the program once per element:
+//!
+//! ```text
+//! userspace                          kernel
+//! ─────────                          ──────
+//! attach()  ──► iterator link
+//! read(fd)  ──────────────────────►  for each task_struct:
+//!                                        dump_task(ctx)
+//!                                          │ bpf_seq_write(record)
+//!                                          ▼
+//!           ◄──────────────────────  seq_file buffer
+//! read(fd) == 0  (end of iteration)  dump_task(ctx) with ctx.task == NULL
+//! ```
+//!
+//! This is how `/proc` could have been written: one pass over the task list,
+//! with no `opendir("/proc")` and no race between listing PIDs and reading
+//! `/proc/<pid>/status`. The output here includes the namespace inodes and
+//! the cgroup id, so it can be matched against the `trace` output and
+//! against `ls -l /proc/<pid>/ns`.
+//!
+//! # Lessons in This Module
+//!
+//! - **Lesson 12**: BPF Iterators - list tasks with namespace and cgroup ids
+//!
+//! # References
+//!
+//! - Lesson: `docs/04-ebpf/12-task-iterator.md`
+//! - Tests: `crates/ebpf-tool/tests/tasks_test.rs`
+//! - Kernel docs: `Documentation/bpf/bpf_iterators.rst`
+//! - Kernel example: `tools/testing/selftests/bpf/progs/bpf_iter_task.c`
+
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::helpers::gen::bpf_seq_write;
+use core::ffi::c_void;
+use ebpf_tool_common::TaskRecord;
+
+// =============================================================================
+// Context Types
+// =============================================================================
+//
+// No maps in this module: the iterator writes straight into the seq_file
+// that userspace is reading.
+
+/// `struct bpf_iter_meta`: shared by every iterator type.
+#[repr(C)]
+pub struct BpfIterMeta {
+    /// The seq_file to write to (pass to `bpf_seq_write`)
+    pub seq: *mut c_void,
+    /// Unique per read session (per open of the iterator fd)
+    pub session_id: u64,
+    /// Number of records written so far in this session
+    pub seq_num: u64,
+}
+
+/// `struct bpf_iter__task`: the context of a `task` iterator program.
+///
+/// `task` is NULL on the final call, which marks the end of the iteration.
+#[repr(C)]
+pub struct BpfIterTask {
+    pub meta: *mut BpfIterMeta,
+    pub task: *mut c_void,
+}
+
+// =============================================================================
+// iter/task
+// =============================================================================
+
+/// Task iterator program: called once for every task (thread) on the system.
+///
+/// # Section name
+///
+/// The loader recognizes iterator programs by their ELF section,
+/// `iter/<target>`. The target here is `task`. Others include `task_file`,
+/// `tcp`, `bpf_map` and `cgroup`. Userspace loads it as an iterator:
+///
+/// ```rust,ignore
+/// let btf = Btf::from_sys_fs()?;
+/// let program: &mut Iter = bpf.program_mut("dump_task")?.try_into()?;
+/// program.load("task", &btf)?;
+/// let link_id = program.attach()?;
+/// let mut file = program.take_link(link_id)?.into_file()?;
+/// let mut buf = Vec::new();
+/// file.read_to_end(&mut buf)?; // runs the whole iteration
+/// ```
+///
+/// # Reading task fields
+///
+/// Iterator programs are BTF-typed, but from Rust you still read kernel
+/// struct fields with `bpf_probe_read_kernel`. `crate::kstruct::Task`
+/// (Lesson 31) wraps those reads with offsets from the running kernel's
+/// BTF: `unsafe { Task::from_ptr(task) }`, then `parent_tgid()` and
+/// `ns_ids()`:
+///
+/// | TaskRecord field | Kernel path |
+/// |------------------|-------------|
+/// | `pid` / `tid` | `task->tgid` / `task->pid` |
+/// | `ppid` | `task->real_parent->tgid` |
+/// | `pidns_inum` | `task->thread_pid->numbers[level].ns->ns.inum` |
+/// | `mntns_inum` | `task->nsproxy->mnt_ns->ns.inum` |
+/// | `netns_inum` | `task->nsproxy->net_ns->ns.inum` |
+/// | `cgroup_id` | `task->cgroups->dfl_cgrp->kn->id` |
+/// | `comm` | `task->comm` |
+#[no_mangle]
+#[link_section = "iter/task"]
+pub extern "C" fn dump_task(ctx: *mut BpfIterTask) -> i32 {
+    // TODO: Implement in Lesson 12
+    // Lesson: docs/04-ebpf/12-task-iterator.md
+    // Tests: crates/ebpf-tool/tests/tasks_test.rs
+    //
+    // Implementation steps:
+    // 1. let task = unsafe { (*ctx).task }; if task.is_null() { return 0; }
+    //    (the final call; there is nothing to write)
+    // 2. let seq = unsafe { (*(*ctx).meta).seq };
+    // 3. Fill a TaskRecord with the fields from the table above
+    // 4. A task that is exiting has nsproxy == NULL: leave the three ns
+    //    fields 0 instead of dereferencing it
+    // 5. unsafe { bpf_seq_write(seq, &record as *const _ as *const c_void,
+    //    size_of::<TaskRecord>() as u32) }
+    // 6. Return 0. The verifier only allows 0 or 1, and 1 makes read()
+    //    fail with EAGAIN, which is not what you want here
+    let _ = ctx;
+
+    todo!("Implement dump_task - see docs/04-ebpf/12-task-iterator.md")
+}
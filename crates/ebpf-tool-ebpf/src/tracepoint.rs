@@ -60,13 +60,21 @@
 // # Reference
 //
 // - Lesson: docs/04-ebpf/06-tracepoints.md
-// - Tests: crates/ebpf-tool/tests/tracepoint_test.rs
+// - Lesson: docs/04-ebpf/42-cgroup-cpu.md (sched_tracepoint, CPU time per cgroup)
+// - Tests: crates/ebpf-tool/tests/tracepoint_test.rs, crates/ebpf-tool/tests/sched_test.rs
 // - Kernel docs: Documentation/trace/tracepoints.rst
 //
 // =============================================================================
 
-use aya_ebpf::{macros::tracepoint, programs::TracePointContext};
+#![allow(unused_imports)] // Allow unused imports during scaffolding
+
+use aya_ebpf::{
+    macros::{map, tracepoint},
+    maps::{Array, LruPerCpuHashMap, PerCpuArray},
+    programs::TracePointContext,
+};
 use aya_log_ebpf::info;
+use ebpf_tool_common::{CgroupCpu, MAX_MAP_ENTRIES, SCHED_CONFIG_LOG};
 
 // =============================================================================
 // Syscall Tracepoints
@@ -149,6 +157,23 @@ pub fn sys_enter_tracepoint(ctx: TracePointContext) -> u32 {
 // Scheduler Tracepoints
 // =============================================================================
 
+/// On-CPU time and switches per cgroup id (`ebpf-tool sched`, Lesson 42).
+///
+/// LRU, because cgroups come and go: a container that exited long ago is
+/// evicted before a running one loses its counters.
+#[map]
+static CGROUP_CPU: LruPerCpuHashMap<u64, CgroupCpu> =
+    LruPerCpuHashMap::with_max_entries(MAX_MAP_ENTRIES, 0);
+
+/// When the task now running on this CPU was switched in (bpf_ktime_get_ns),
+/// 0 until the first switch seen on the CPU.
+#[map]
+static ONCPU_SINCE: PerCpuArray<u64> = PerCpuArray::with_max_entries(1, 0);
+
+/// Options written by userspace before attaching (`SCHED_CONFIG_LOG`).
+#[map]
+static SCHED_CONFIG: Array<u32> = Array::with_max_entries(1, 0);
+
 /// Tracepoint for scheduler events.
 ///
 /// # Tracepoint: sched/sched_switch
@@ -191,9 +216,11 @@ pub fn sched_tracepoint(ctx: TracePointContext) -> u32 {
     // Implementation hints:
     //
     // 1. Read prev_pid (offset 24) and next_pid (offset 56)
-    // 2. Log the context switch: "switch: pid {} -> pid {}"
-    // 3. Optional: Use a BPF map to track per-process CPU time
-    // 4. Optional: Calculate time between switches using bpf_ktime_get_ns()
+    // 2. Log the context switch: "switch: pid {} -> pid {}", only when
+    //    SCHED_CONFIG[SCHED_CONFIG_LOG] is 1: at thousands of switches per
+    //    second, the log is the expensive part
+    // 3. Lesson 42 (docs/04-ebpf/42-cgroup-cpu.md): call account_switch(&ctx)
+    //    on every switch, before the log; ignore its errors
     //
     // Advanced extensions:
     // - Build per-CPU statistics using a PerCpuArray map
@@ -208,6 +235,33 @@ pub fn sched_tracepoint(ctx: TracePointContext) -> u32 {
     todo!("Implement sched_tracepoint - see docs/04-ebpf/06-tracepoints.md")
 }
 
+/// Charge the time since the last switch on this CPU to the cgroup of the
+/// task being switched out.
+///
+/// `sched_switch` fires before the switch, so "current" is still `prev`:
+/// `bpf_get_current_cgroup_id()` is the cgroup that used the CPU.
+#[allow(dead_code)]
+#[inline(always)]
+fn account_switch(ctx: &TracePointContext) -> Result<(), i64> {
+    // TODO: Implement in Lesson 42
+    //
+    // [ ] now = bpf_ktime_get_ns(); since = ONCPU_SINCE.get_ptr_mut(0)
+    // [ ] Set *since = now at the end, whatever happened in between
+    // [ ] *since == 0 (first switch on this CPU since loading): nothing to
+    //     charge yet
+    // [ ] prev_pid (offset 24) == 0 is the idle task: idle time belongs to
+    //     no cgroup, skip it
+    // [ ] id = bpf_get_current_cgroup_id(); get_ptr_mut(&id), or insert
+    //     CgroupCpu::new() with BPF_NOEXIST and look it up again
+    // [ ] on_cpu_ns += now - *since; switches += 1
+    // [ ] prev_state (offset 32, long): 0 (TASK_RUNNING) or the
+    //     TASK_REPORT_MAX bit (preempted, 0x100 on current kernels; see
+    //     the format file's print fmt) means the task still wanted the
+    //     CPU: involuntary += 1
+    let _ = ctx;
+    todo!("Implement account_switch")
+}
+
 /// Tracepoint for process execution events.
 ///
 /// # Tracepoint: sched/sched_process_exec
@@ -234,6 +288,16 @@ pub fn exec_tracepoint(ctx: TracePointContext) -> u32 {
     //
     // Check the format file for exact offsets:
     //   cat /sys/kernel/debug/tracing/events/sched/sched_process_exec/format
+    //
+    // Lesson 37 (docs/04-ebpf/37-bounded-strings.md) sends an ExecEvent:
+    // - Build it in crate::scratch::get::<ExecEvent>(Slot::Event) (832
+    //   bytes), from ExecEvent::new()
+    // - filename: the tracepoint's __data_loc char[] filename field is an
+    //   offset into the record, a kernel string: strings::read_kernel_str()
+    // - argv: the user pointers are gone at this point (the new image is
+    //   mapped); read them on syscalls/sys_enter_execve with
+    //   strings::read_user_argv() into a map keyed by pid_tgid, and add
+    //   them here, as mounts.rs does with enter and exit
 
     todo!("Implement exec_tracepoint - see docs/04-ebpf/06-tracepoints.md")
 }
@@ -319,7 +383,9 @@ pub fn net_rx_tracepoint(ctx: TracePointContext) -> u32 {
 // # Common Pitfalls
 //
 // 1. **Pointer arguments**: Fields like `filename` contain pointers, not
-//    the actual string. Use bpf_probe_read_user_str() to read the string.
+//    the actual string. Copy it with crate::strings::read_user_field()
+//    into a PATH_LEN field (Lesson 37), which bounds the read for the
+//    verifier and flags paths that were cut.
 //
 // 2. **Architecture differences**: Some offsets may differ between 32-bit
 //    and 64-bit systems. Always check the format file on your target.
@@ -10,6 +10,14 @@
 //! 3. Places compiled .o files in OUT_DIR for inclusion via `include_bytes_aligned!`
 //! 4. Emits cargo directives so the build reruns when eBPF source changes
 //!
+//! # Embedded Objects
+//!
+//! Every binary of the eBPF crate is one object: the default `ebpf-tool-ebpf`
+//! (src/main.rs) plus each `[[bin]]` in its Cargo.toml. They are copied to
+//! `$OUT_DIR/objects/<name>`, and `$OUT_DIR/objects.rs` lists them for
+//! `src/loader.rs`. The default object is also copied to
+//! `$OUT_DIR/ebpf-tool-ebpf`, where the early lessons embed it directly.
+//!
 //! # Prerequisites
 //!
 //! - Rust nightly toolchain (for `-Z build-std`)
@@ -24,9 +32,13 @@
 
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// The eBPF crate's own binary (src/main.rs), which holds every program
+/// that has not been split into an object of its own.
+const DEFAULT_OBJECT: &str = "ebpf-tool-ebpf";
+
 fn main() {
     // TODO: This build script currently uses a manual cargo invocation approach.
     // Once aya-build stabilizes, learners should migrate to using:
@@ -54,6 +66,7 @@ fn main() {
         // Create a placeholder file so the main crate can still compile
         // This allows the `check` subcommand to work before eBPF programs exist
         create_placeholder(&out_dir);
+        println!("cargo:rustc-env=EBPF_OUT_DIR={}", out_dir);
         return;
     }
 
@@ -128,26 +141,41 @@ fn main() {
         Ok(status) if status.success() => {
             println!("cargo:warning=Successfully compiled eBPF programs");
 
-            // Copy the compiled eBPF object to a well-known location in OUT_DIR
-            // The main crate will use include_bytes_aligned! to embed it
+            // Copy the compiled eBPF objects to a well-known location in OUT_DIR
+            // The main crate embeds them through the table in objects.rs
             //
-            // TODO: Learners should update this path when adding new eBPF programs.
-            // Each program binary is named after the crate (ebpf-tool-ebpf).
-            let ebpf_binary = PathBuf::from(&out_dir)
+            // Each binary of the eBPF crate is one object, named after the
+            // binary: add a [[bin]] there and it is embedded here too.
+            let build_dir = PathBuf::from(&out_dir)
                 .join("ebpf-target")
                 .join(target)
-                .join(ebpf_profile)
-                .join("ebpf-tool-ebpf");
+                .join(ebpf_profile);
+            let names = object_names(&ebpf_crate_dir);
+            let objects_dir = PathBuf::from(&out_dir).join("objects");
+            fs::create_dir_all(&objects_dir).expect("Failed to create objects directory");
 
-            if ebpf_binary.exists() {
-                let dest = PathBuf::from(&out_dir).join("ebpf-tool-ebpf");
-                fs::copy(&ebpf_binary, &dest).expect("Failed to copy eBPF binary");
+            let mut embedded = Vec::new();
+            for name in &names {
+                let ebpf_binary = build_dir.join(name);
+                if ebpf_binary.exists() {
+                    fs::copy(&ebpf_binary, objects_dir.join(name))
+                        .expect("Failed to copy eBPF binary");
+                    embedded.push(name.clone());
+                } else {
+                    println!(
+                        "cargo:warning=eBPF binary not found at expected location: {}",
+                        ebpf_binary.display()
+                    );
+                }
+            }
+
+            if embedded.iter().any(|name| name == DEFAULT_OBJECT) {
+                let dest = PathBuf::from(&out_dir).join(DEFAULT_OBJECT);
+                fs::copy(objects_dir.join(DEFAULT_OBJECT), &dest)
+                    .expect("Failed to copy eBPF binary");
                 println!("cargo:warning=eBPF binary available at: {}", dest.display());
+                write_object_table(&out_dir, &embedded);
             } else {
-                println!(
-                    "cargo:warning=eBPF binary not found at expected location: {}",
-                    ebpf_binary.display()
-                );
                 create_placeholder(&out_dir);
             }
         }
@@ -178,6 +206,45 @@ fn main() {
     println!("cargo:rustc-env=EBPF_OUT_DIR={}", out_dir);
 }
 
+/// Names of the eBPF crate's binaries: the default one, then each `[[bin]]`.
+fn object_names(ebpf_crate_dir: &Path) -> Vec<String> {
+    let mut names = vec![DEFAULT_OBJECT.to_string()];
+    let manifest = fs::read_to_string(ebpf_crate_dir.join("Cargo.toml")).unwrap_or_default();
+    let mut in_bin = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_bin = line == "[[bin]]";
+            continue;
+        }
+        let Some(value) = line.strip_prefix("name") else {
+            continue;
+        };
+        let Some(value) = value.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let name = value.trim().trim_matches('"');
+        if in_bin && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Write `$OUT_DIR/objects.rs`: the `EMBEDDED` table of `src/loader.rs`.
+fn write_object_table(out_dir: &str, names: &[String]) {
+    let mut table = String::from("// Generated by build.rs\n");
+    table.push_str("pub static EMBEDDED: &[(&str, &[u8])] = &[\n");
+    for name in names {
+        table.push_str(&format!(
+            "    ({:?}, crate::include_bytes_aligned!(concat!(env!(\"EBPF_OUT_DIR\"), \"/objects/{}\"))),\n",
+            name, name
+        ));
+    }
+    table.push_str("];\n");
+    fs::write(PathBuf::from(out_dir).join("objects.rs"), table)
+        .expect("Failed to write objects.rs");
+}
+
 /// Create a placeholder file when eBPF compilation is not available.
 ///
 /// This allows the userspace CLI to compile even when:
@@ -187,11 +254,15 @@ fn main() {
 ///
 /// The CLI's `check` subcommand can detect this and warn the user.
 fn create_placeholder(out_dir: &str) {
-    let placeholder_path = PathBuf::from(out_dir).join("ebpf-tool-ebpf");
+    let placeholder_path = PathBuf::from(out_dir).join(DEFAULT_OBJECT);
 
     // Write a minimal placeholder that will cause a clear error if loaded
     // We use an empty file - Aya will fail gracefully when trying to load it
     fs::write(&placeholder_path, b"").expect("Failed to create placeholder");
+    let objects_dir = PathBuf::from(out_dir).join("objects");
+    fs::create_dir_all(&objects_dir).expect("Failed to create objects directory");
+    fs::write(objects_dir.join(DEFAULT_OBJECT), b"").expect("Failed to create placeholder");
+    write_object_table(out_dir, &[DEFAULT_OBJECT.to_string()]);
 
     println!(
         "cargo:warning=Created placeholder at: {}",
@@ -0,0 +1,276 @@
+//! `trace --aggregate`: collapse identical events into one line per window.
+//!
+//! A busy process makes the same syscall thousands of times a second, and
+//! one line per event hides everything else. With `--aggregate 1s`, `trace`
+//! groups the events of each window by (comm, syscall) and prints one
+//! summary per group when the window closes:
+//!
+//! ```text
+//! [12:34:56.000] bash(1234) read() x 542
+//! [12:34:56.000] nginx(811 +3) epoll_wait() x 97 (2 errors)
+//! ```
+//!
+//! Windows follow the event timestamps (`timestamp_ns`), not the wall clock
+//! of the reader. A window closes when the first event past its end
+//! arrives, or when `trace` calls [`Aggregator::flush_due`] from a timer
+//! so a quiet system still prints. Empty windows print nothing.
+//!
+//! The grouping runs in userspace, after `TraceFilter`. The kernel already
+//! counts by (pid, syscall) in `SYSCALL_COUNTS`, but that map has no comm
+//! and `--filter` is evaluated here.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+/// One group of a closed window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// Start of the window, in the events' clock (`timestamp_ns`)
+    pub start_ns: u64,
+    pub comm: String,
+    pub syscall: String,
+    /// Lowest PID in the group
+    pub pid: u32,
+    /// Other PIDs with the same comm, e.g. the workers of a server
+    pub other_pids: usize,
+    pub count: u64,
+    /// Events with a negative return value (only with `--returns`)
+    pub errors: u64,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({}", self.comm, self.pid)?;
+        if self.other_pids > 0 {
+            write!(f, " +{}", self.other_pids)?;
+        }
+        write!(f, ") {}() x {}", self.syscall, self.count)?;
+        match self.errors {
+            0 => Ok(()),
+            1 => write!(f, " (1 error)"),
+            n => write!(f, " ({n} errors)"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Group {
+    pids: BTreeSet<u32>,
+    count: u64,
+    errors: u64,
+    /// Arrival order, so equal counts print in a stable order
+    first: u64,
+}
+
+/// Groups events by (comm, syscall) in fixed windows.
+#[derive(Debug)]
+pub struct Aggregator {
+    window_ns: u64,
+    start_ns: Option<u64>,
+    groups: HashMap<(String, String), Group>,
+    seen: u64,
+}
+
+impl Aggregator {
+    /// `window` must not be zero; [`parse_window`] refuses it.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window_ns: (window.as_nanos() as u64).max(1),
+            start_ns: None,
+            groups: HashMap::new(),
+            seen: 0,
+        }
+    }
+
+    /// Add one event that passed the filter. Returns the summaries of the
+    /// previous window if this event is past its end.
+    ///
+    /// An event from before the current window (perf buffers of different
+    /// CPUs are read in turns) is counted in the current one.
+    pub fn event(
+        &mut self,
+        timestamp_ns: u64,
+        pid: u32,
+        comm: &str,
+        syscall: &str,
+        failed: bool,
+    ) -> Vec<Summary> {
+        let closed = self.flush_due(timestamp_ns);
+        if self.start_ns.is_none() {
+            self.start_ns = Some(if closed.is_empty() {
+                timestamp_ns
+            } else {
+                // Stay on the grid of the first window
+                let start = closed[0].start_ns;
+                start + (timestamp_ns - start) / self.window_ns * self.window_ns
+            });
+        }
+
+        let seen = self.seen;
+        let group = self
+            .groups
+            .entry((comm.to_string(), syscall.to_string()))
+            .or_insert_with(|| Group {
+                first: seen,
+                ..Group::default()
+            });
+        group.pids.insert(pid);
+        group.count += 1;
+        if failed {
+            group.errors += 1;
+        }
+        self.seen += 1;
+        closed
+    }
+
+    /// Close the current window if `now_ns` is past its end.
+    pub fn flush_due(&mut self, now_ns: u64) -> Vec<Summary> {
+        match self.start_ns {
+            Some(start) if now_ns >= start.saturating_add(self.window_ns) => self.finish(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Close the current window, whatever the time: at the end of the trace.
+    ///
+    /// Summaries are sorted by count, highest first.
+    pub fn finish(&mut self) -> Vec<Summary> {
+        let Some(start_ns) = self.start_ns.take() else {
+            return Vec::new();
+        };
+        let mut groups: Vec<_> = self.groups.drain().collect();
+        groups.sort_by(|(_, a), (_, b)| b.count.cmp(&a.count).then(a.first.cmp(&b.first)));
+        groups
+            .into_iter()
+            .map(|((comm, syscall), group)| Summary {
+                start_ns,
+                comm,
+                syscall,
+                pid: group.pids.first().copied().unwrap_or(0),
+                other_pids: group.pids.len().saturating_sub(1),
+                count: group.count,
+                errors: group.errors,
+            })
+            .collect()
+    }
+
+    /// Length of a window.
+    pub fn window(&self) -> Duration {
+        Duration::from_nanos(self.window_ns)
+    }
+}
+
+/// Parse `--aggregate WINDOW`: "1s", "500ms", "2m", or a bare number of
+/// seconds.
+pub fn parse_window(s: &str) -> Result<Duration, String> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let n: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid window {s:?}: expected e.g. 1s, 500ms or 2m"))?;
+    let window = match unit {
+        "" | "s" => Duration::from_secs(n),
+        "ms" => Duration::from_millis(n),
+        "m" => Duration::from_secs(n * 60),
+        _ => return Err(format!("invalid window {s:?}: unit must be ms, s or m")),
+    };
+    if window.is_zero() {
+        return Err("the window must be longer than 0".to_string());
+    }
+    Ok(window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_groups_by_comm_and_syscall() {
+        let mut agg = Aggregator::new(Duration::from_secs(1));
+        for i in 0..5 {
+            assert!(agg.event(i * 1000, 1234, "bash", "read", false).is_empty());
+        }
+        agg.event(2000, 1234, "bash", "write", false);
+        agg.event(3000, 811, "nginx", "read", true);
+
+        let out = agg.finish();
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[0].to_string(), "bash(1234) read() x 5");
+        assert_eq!(out[1].to_string(), "bash(1234) write() x 1");
+        assert_eq!(out[2].to_string(), "nginx(811) read() x 1 (1 error)");
+        assert!(out.iter().all(|s| s.start_ns == 0));
+        assert!(agg.finish().is_empty());
+    }
+
+    #[test]
+    fn test_pids_with_the_same_comm_share_a_line() {
+        let mut agg = Aggregator::new(Duration::from_secs(1));
+        for pid in [814, 811, 812, 811] {
+            agg.event(0, pid, "nginx", "epoll_wait", false);
+        }
+        let out = agg.finish();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].pid, 811);
+        assert_eq!(out[0].to_string(), "nginx(811 +2) epoll_wait() x 4");
+    }
+
+    #[test]
+    fn test_window_closes_on_later_event() {
+        let mut agg = Aggregator::new(Duration::from_secs(1));
+        agg.event(10 * SEC, 1, "a", "read", false);
+        agg.event(10 * SEC + SEC / 2, 1, "a", "read", false);
+
+        // 3.5 windows later: one summary for the first window, and the new
+        // window starts on the grid (13s), not at the event
+        let closed = agg.event(13 * SEC + SEC / 2, 1, "a", "read", false);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].count, 2);
+        assert_eq!(closed[0].start_ns, 10 * SEC);
+
+        let last = agg.finish();
+        assert_eq!(last[0].start_ns, 13 * SEC);
+        assert_eq!(last[0].count, 1);
+    }
+
+    #[test]
+    fn test_flush_due_and_late_events() {
+        let mut agg = Aggregator::new(Duration::from_millis(500));
+        assert!(agg.flush_due(u64::MAX).is_empty());
+
+        agg.event(SEC, 1, "a", "read", false);
+        // From another CPU's buffer, before the window: still counted
+        agg.event(SEC - 10, 1, "a", "read", false);
+        assert!(agg.flush_due(SEC + SEC / 4).is_empty());
+
+        let out = agg.flush_due(SEC + SEC / 2);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].count, 2);
+        assert!(agg.flush_due(10 * SEC).is_empty());
+    }
+
+    #[test]
+    fn test_busiest_first_then_arrival_order() {
+        let mut agg = Aggregator::new(Duration::from_secs(1));
+        agg.event(0, 1, "a", "close", false);
+        agg.event(0, 1, "a", "open", false);
+        agg.event(0, 1, "a", "read", false);
+        agg.event(0, 1, "a", "read", false);
+        let names: Vec<_> = agg.finish().into_iter().map(|s| s.syscall).collect();
+        assert_eq!(names, ["read", "close", "open"]);
+    }
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(parse_window("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_window("2"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_window("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_window("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_window("0s").is_err());
+        assert!(parse_window("1h").is_err());
+        assert!(parse_window("soon").is_err());
+        assert!(parse_window("").is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Dropped-event backfill: exact `trace` totals when the perf buffers overflow.
+//!
+//! The trace program sends each syscall to a perf buffer and also counts it in
+//! `SYSCALL_COUNTS`. When userspace falls behind, the kernel drops events but
+//! still counts them. `trace` snapshots the map once per
+//! [`SNAPSHOT_INTERVAL`]. For each interval, the totals come from one of two
+//! sources:
+//!
+//! ```text
+//! interval without drops  ─► the events received        (what was printed)
+//! interval with drops     ─► map delta since last tick  (what happened)
+//! ```
+//!
+//! Consecutive intervals with drops form one degraded [`Window`]. `trace`
+//! prints a notice when a window opens and when it closes. The run report
+//! lists the windows, so the numbers can be read with the right fidelity in
+//! mind.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// How often `trace` snapshots `SYSCALL_COUNTS`.
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Syscall counts by (pid, syscall number), summed over CPUs.
+pub type Counts = HashMap<(u32, u64), u64>;
+
+/// Build `Counts` from summed `SYSCALL_COUNTS` entries (`sum_per_cpu()`),
+/// keeping only the keys userspace would print.
+///
+/// `keep` must be the same function for every snapshot of a run, or the
+/// deltas compare different sets of keys.
+pub fn snapshot(
+    entries: &[(ebpf_tool_common::SyscallKey, u64)],
+    keep: impl Fn(u32, u64) -> bool,
+) -> Counts {
+    let mut counts = Counts::new();
+    for (key, count) in entries {
+        if keep(key.pid, key.syscall_nr) {
+            *counts.entry((key.pid, key.syscall_nr)).or_default() += count;
+        }
+    }
+    counts
+}
+
+/// Counts added between two snapshots.
+///
+/// A key whose count went down was evicted from the LRU map and inserted
+/// again: its whole current count is new. A key that disappeared was
+/// evicted; what it counted since `prev` is lost.
+pub fn delta(prev: &Counts, cur: &Counts) -> Counts {
+    let mut out = Counts::new();
+    for (key, &count) in cur {
+        let before = prev.get(key).copied().unwrap_or(0);
+        let added = if count >= before {
+            count - before
+        } else {
+            count
+        };
+        if added > 0 {
+            out.insert(*key, added);
+        }
+    }
+    out
+}
+
+/// A stretch of the run in which events were lost and the totals come from
+/// the map. Times are since the start of the trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Window {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Events the kernel reported as lost
+    pub lost: u64,
+    /// Syscalls counted in the map but never received as events
+    pub backfilled: u64,
+}
+
+/// What [`Backfill::tick`] has to tell the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notice {
+    /// Drops started: the window is open from `start_ms`.
+    Degraded { start_ms: u64, lost: u64 },
+    /// An interval without drops closed the window.
+    Recovered(Window),
+}
+
+impl fmt::Display for Notice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Notice::Degraded { start_ms, lost } => write!(
+                f,
+                "[DEGRADED] +{} {} events lost; totals from SYSCALL_COUNTS until drops stop",
+                seconds(*start_ms),
+                lost
+            ),
+            Notice::Recovered(w) => write!(
+                f,
+                "[RECOVERED] +{}..+{} {} events lost, {} backfilled from SYSCALL_COUNTS",
+                seconds(w.start_ms),
+                seconds(w.end_ms),
+                w.lost,
+                w.backfilled
+            ),
+        }
+    }
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{}.{:03}s", ms / 1000, ms % 1000)
+}
+
+/// Per-interval choice between received events and map deltas.
+#[derive(Debug, Default)]
+pub struct Backfill {
+    last: Counts,
+    interval_events: Counts,
+    interval_lost: u64,
+    interval_start_ms: u64,
+    totals: Counts,
+    open: Option<Window>,
+    windows: Vec<Window>,
+}
+
+impl Backfill {
+    /// Start from the snapshot taken right after attaching: a pinned map, or
+    /// other tracers sharing it, may already hold counts.
+    pub fn new(baseline: Counts) -> Self {
+        Self {
+            last: baseline,
+            ..Self::default()
+        }
+    }
+
+    /// Count one received event that passed the filter.
+    pub fn event(&mut self, pid: u32, syscall_nr: u64) {
+        *self.interval_events.entry((pid, syscall_nr)).or_default() += 1;
+    }
+
+    /// Record a lost-events count from a perf buffer.
+    pub fn lost(&mut self, count: u64) {
+        self.interval_lost += count;
+    }
+
+    /// Close the current interval with a new snapshot, taken at `now` since
+    /// the start of the trace.
+    pub fn tick(&mut self, snapshot: Counts, now: Duration) -> Option<Notice> {
+        let now_ms = now.as_millis() as u64;
+        let map_delta = delta(&self.last, &snapshot);
+        let events = std::mem::take(&mut self.interval_events);
+        let lost = std::mem::take(&mut self.interval_lost);
+        let start_ms = std::mem::replace(&mut self.interval_start_ms, now_ms);
+        self.last = snapshot;
+
+        if lost == 0 {
+            add(&mut self.totals, &events);
+            let window = self.open.take()?;
+            self.windows.push(window.clone());
+            return Some(Notice::Recovered(window));
+        }
+
+        let received: u64 = events.values().sum();
+        let counted: u64 = map_delta.values().sum();
+        add(&mut self.totals, &map_delta);
+        match self.open {
+            Some(ref mut w) => {
+                w.end_ms = now_ms;
+                w.lost += lost;
+                w.backfilled += counted.saturating_sub(received);
+                None
+            }
+            None => {
+                self.open = Some(Window {
+                    start_ms,
+                    end_ms: now_ms,
+                    lost,
+                    backfilled: counted.saturating_sub(received),
+                });
+                Some(Notice::Degraded { start_ms, lost })
+            }
+        }
+    }
+
+    /// Close the last interval and return the totals and all windows.
+    pub fn finish(mut self, snapshot: Counts, now: Duration) -> (Counts, Vec<Window>) {
+        self.tick(snapshot, now);
+        if let Some(window) = self.open.take() {
+            self.windows.push(window);
+        }
+        (self.totals, self.windows)
+    }
+
+    /// Totals of the intervals closed so far.
+    pub fn totals(&self) -> &Counts {
+        &self.totals
+    }
+}
+
+fn add(totals: &mut Counts, counts: &Counts) {
+    for (key, count) in counts {
+        *totals.entry(*key).or_default() += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ebpf_tool_common::SyscallKey;
+
+    fn counts(entries: &[((u32, u64), u64)]) -> Counts {
+        entries.iter().copied().collect()
+    }
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn test_delta_handles_eviction() {
+        let prev = counts(&[((1, 0), 10), ((2, 0), 5), ((3, 0), 7)]);
+        let cur = counts(&[((1, 0), 15), ((2, 0), 2), ((4, 1), 3)]);
+        // (2, 0) went down: evicted and re-inserted; (3, 0) was evicted
+        assert_eq!(
+            delta(&prev, &cur),
+            counts(&[((1, 0), 5), ((2, 0), 2), ((4, 1), 3)])
+        );
+        assert!(delta(&cur, &cur).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_filters_and_sums() {
+        let entries = [
+            (SyscallKey::new(1, 0), 4),
+            (SyscallKey::new(1, 257), 2),
+            (SyscallKey::new(2, 0), 9),
+        ];
+        let snap = snapshot(&entries, |pid, _| pid == 1);
+        assert_eq!(snap, counts(&[((1, 0), 4), ((1, 257), 2)]));
+    }
+
+    #[test]
+    fn test_uses_events_without_drops() {
+        let mut b = Backfill::new(counts(&[((1, 0), 100)]));
+        b.event(1, 0);
+        b.event(1, 0);
+        // The map is ahead: two more events are still in the buffer
+        assert_eq!(b.tick(counts(&[((1, 0), 104)]), secs(1)), None);
+        assert_eq!(b.totals(), &counts(&[((1, 0), 2)]));
+    }
+
+    #[test]
+    fn test_degraded_window() {
+        let mut b = Backfill::new(Counts::new());
+        b.event(1, 0);
+        assert_eq!(b.tick(counts(&[((1, 0), 1)]), secs(1)), None);
+
+        // Two intervals with drops: one window, totals from the map
+        b.event(1, 0);
+        b.lost(40);
+        assert_eq!(
+            b.tick(counts(&[((1, 0), 51)]), secs(2)),
+            Some(Notice::Degraded {
+                start_ms: 1000,
+                lost: 40
+            })
+        );
+        b.lost(10);
+        assert_eq!(b.tick(counts(&[((1, 0), 61)]), secs(3)), None);
+
+        b.event(1, 0);
+        let Some(Notice::Recovered(w)) = b.tick(counts(&[((1, 0), 62)]), secs(4)) else {
+            panic!("expected the window to close");
+        };
+        assert_eq!(
+            w,
+            Window {
+                start_ms: 1000,
+                end_ms: 3000,
+                lost: 50,
+                backfilled: 59
+            }
+        );
+
+        let (totals, windows) = b.finish(counts(&[((1, 0), 62)]), secs(5));
+        assert_eq!(totals, counts(&[((1, 0), 62)]));
+        assert_eq!(windows, vec![w]);
+    }
+
+    #[test]
+    fn test_finish_closes_open_window() {
+        let mut b = Backfill::new(Counts::new());
+        b.lost(5);
+        let (totals, windows) = b.finish(counts(&[((7, 1), 5)]), Duration::from_millis(2500));
+        assert_eq!(totals, counts(&[((7, 1), 5)]));
+        assert_eq!(windows.len(), 1);
+        assert_eq!((windows[0].start_ms, windows[0].end_ms), (0, 2500));
+    }
+
+    #[test]
+    fn test_notice_text() {
+        let degraded = Notice::Degraded {
+            start_ms: 3200,
+            lost: 812,
+        };
+        assert_eq!(
+            degraded.to_string(),
+            "[DEGRADED] +3.200s 812 events lost; totals from SYSCALL_COUNTS until drops stop"
+        );
+        let recovered = Notice::Recovered(Window {
+            start_ms: 3200,
+            end_ms: 6000,
+            lost: 812,
+            backfilled: 790,
+        });
+        assert_eq!(
+            recovered.to_string(),
+            "[RECOVERED] +3.200s..+6.000s 812 events lost, 790 backfilled from SYSCALL_COUNTS"
+        );
+    }
+}
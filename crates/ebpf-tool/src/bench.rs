@@ -0,0 +1,307 @@
+//! `ebpf-tool bench`: what tracing costs the traced program.
+//!
+//! A kprobe or tracepoint program runs in the context of the task that made
+//! the syscall, so its cost shows up as that task being slower. `bench`
+//! runs the same tight syscall loop without and then with the tracer
+//! attached and compares the two:
+//!
+//! ```text
+//! run() x runs          ─► baseline ns/syscall ─┐
+//! attach, run() x runs  ─► traced ns/syscall   ─┴► Report: overhead ns/event
+//!             └► process CPU - workload CPU ─────► tracer CPU%
+//! ```
+//!
+//! Each side takes the median of its runs: one run slowed down by an
+//! interrupt or a frequency change does not move the result. The overhead
+//! per event is `traced - baseline` per syscall, since every syscall of the
+//! workload fires the program once. The tracer's CPU% is the time the event
+//! consumer (reading and decoding the perf arrays) spent on a CPU during the
+//! traced runs, in percent of one CPU.
+
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Read;
+use std::time::Instant;
+
+/// The syscall loop `bench` measures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Workload {
+    /// Open and close /dev/null: two syscalls that touch the VFS
+    #[default]
+    Openat,
+    /// Read one byte from /dev/zero
+    Read,
+    /// getpid(): the cheapest syscall, so the tracer's share is largest
+    Getpid,
+}
+
+impl Workload {
+    pub fn name(self) -> &'static str {
+        match self {
+            Workload::Openat => "openat",
+            Workload::Read => "read",
+            Workload::Getpid => "getpid",
+        }
+    }
+
+    /// Syscalls made by one iteration of the loop.
+    pub fn syscalls_per_iteration(self) -> u64 {
+        match self {
+            Workload::Openat => 2,
+            Workload::Read | Workload::Getpid => 1,
+        }
+    }
+}
+
+/// One run of the loop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sample {
+    /// Wall-clock time of the whole loop
+    pub wall_ns: u64,
+    /// CPU time of the thread that ran it, user and kernel
+    pub cpu_ns: u64,
+    pub syscalls: u64,
+}
+
+impl Sample {
+    pub fn ns_per_syscall(&self) -> f64 {
+        match self.syscalls {
+            0 => 0.0,
+            n => self.wall_ns as f64 / n as f64,
+        }
+    }
+}
+
+/// Run `iterations` of `workload` on the calling thread.
+pub fn run(workload: Workload, iterations: u64) -> Result<Sample> {
+    let mut zero = match workload {
+        Workload::Read => Some(File::open("/dev/zero").context("failed to open /dev/zero")?),
+        _ => None,
+    };
+    let mut byte = [0u8; 1];
+    let cpu_start = thread_cpu_ns()?;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        match workload {
+            Workload::Openat => {
+                File::open("/dev/null").context("failed to open /dev/null")?;
+            }
+            Workload::Read => {
+                if let Some(zero) = zero.as_mut() {
+                    zero.read_exact(&mut byte)?;
+                }
+            }
+            Workload::Getpid => {
+                // Not libc::getpid(): a libc may cache it and skip the syscall
+                unsafe { libc::syscall(libc::SYS_getpid) };
+            }
+        }
+    }
+    let wall_ns = start.elapsed().as_nanos() as u64;
+    Ok(Sample {
+        wall_ns,
+        cpu_ns: thread_cpu_ns()?.saturating_sub(cpu_start),
+        syscalls: iterations * workload.syscalls_per_iteration(),
+    })
+}
+
+/// CPU time of the calling thread so far.
+pub fn thread_cpu_ns() -> Result<u64> {
+    rusage_ns(libc::RUSAGE_THREAD)
+}
+
+/// CPU time of the whole process so far, all threads.
+pub fn process_cpu_ns() -> Result<u64> {
+    rusage_ns(libc::RUSAGE_SELF)
+}
+
+fn rusage_ns(who: libc::c_int) -> Result<u64> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(who, &mut usage) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("getrusage failed");
+    }
+    let ns = |t: libc::timeval| t.tv_sec as u64 * 1_000_000_000 + t.tv_usec as u64 * 1_000;
+    Ok(ns(usage.ru_utime) + ns(usage.ru_stime))
+}
+
+/// The median of `values`; 0 for none.
+pub fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+    }
+}
+
+/// The result of a benchmark.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub workload: Workload,
+    pub runs: usize,
+    /// Syscalls in one run
+    pub syscalls: u64,
+    pub baseline_ns: f64,
+    pub traced_ns: f64,
+    /// Events the tracer delivered for the workload during the traced runs
+    pub events: u64,
+    /// CPU time of the event consumer during the traced runs
+    pub tracer_cpu_ns: u64,
+    /// Wall-clock time of all traced runs
+    pub traced_wall_ns: u64,
+}
+
+impl Report {
+    /// Compare the runs without (`baseline`) and with (`traced`) the tracer.
+    pub fn new(
+        workload: Workload,
+        baseline: &[Sample],
+        traced: &[Sample],
+        events: u64,
+        tracer_cpu_ns: u64,
+    ) -> Self {
+        let per_syscall =
+            |s: &[Sample]| median(&s.iter().map(Sample::ns_per_syscall).collect::<Vec<_>>());
+        Self {
+            workload,
+            runs: traced.len(),
+            syscalls: traced.first().map_or(0, |s| s.syscalls),
+            baseline_ns: per_syscall(baseline),
+            traced_ns: per_syscall(traced),
+            events,
+            tracer_cpu_ns,
+            traced_wall_ns: traced.iter().map(|s| s.wall_ns).sum(),
+        }
+    }
+
+    /// Extra time per traced syscall, which is one event each. Can come
+    /// out slightly negative when the tracer costs less than the noise.
+    pub fn overhead_ns(&self) -> f64 {
+        self.traced_ns - self.baseline_ns
+    }
+
+    /// How much slower the workload ran, in percent.
+    pub fn slowdown_percent(&self) -> f64 {
+        if self.baseline_ns <= 0.0 {
+            return 0.0;
+        }
+        self.overhead_ns() * 100.0 / self.baseline_ns
+    }
+
+    /// The consumer's CPU time over the traced runs, in percent of one CPU.
+    pub fn tracer_cpu_percent(&self) -> f64 {
+        match self.traced_wall_ns {
+            0 => 0.0,
+            ns => self.tracer_cpu_ns as f64 * 100.0 / ns as f64,
+        }
+    }
+
+    /// Share of the workload's syscalls that reached userspace as events.
+    /// Below 100 the perf arrays lost events: the consumer did less work
+    /// than it would for a real trace.
+    pub fn delivered_percent(&self) -> f64 {
+        match self.syscalls * self.runs as u64 {
+            0 => 0.0,
+            n => self.events as f64 * 100.0 / n as f64,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "workload: {} ({} syscalls x {} runs)",
+            self.workload.name(),
+            self.syscalls,
+            self.runs
+        );
+        let _ = writeln!(out, "baseline: {:>8.1} ns/syscall", self.baseline_ns);
+        let _ = writeln!(
+            out,
+            "traced:   {:>8.1} ns/syscall ({:+.1}%)",
+            self.traced_ns,
+            self.slowdown_percent()
+        );
+        let _ = writeln!(out, "overhead: {:>8.1} ns/event", self.overhead_ns());
+        let _ = writeln!(
+            out,
+            "tracer:   {:>8.1}% of one CPU, {} events ({:.1}% delivered)",
+            self.tracer_cpu_percent(),
+            self.events,
+            self.delivered_percent()
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(wall_ns: u64, syscalls: u64) -> Sample {
+        Sample {
+            wall_ns,
+            cpu_ns: wall_ns,
+            syscalls,
+        }
+    }
+
+    #[test]
+    fn test_run_counts_syscalls() {
+        for (workload, expected) in [
+            (Workload::Openat, 200),
+            (Workload::Read, 100),
+            (Workload::Getpid, 100),
+        ] {
+            let s = run(workload, 100).unwrap();
+            assert_eq!(s.syscalls, expected);
+            assert!(s.wall_ns > 0);
+        }
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+    }
+
+    #[test]
+    fn test_report() {
+        // 100 ns/syscall alone, 150 traced; one traced run hit by noise
+        let baseline = [
+            sample(100_000, 1000),
+            sample(101_000, 1000),
+            sample(99_000, 1000),
+        ];
+        let traced = [
+            sample(150_000, 1000),
+            sample(900_000, 1000),
+            sample(150_000, 1000),
+        ];
+        let report = Report::new(Workload::Getpid, &baseline, &traced, 3000, 60_000);
+        assert_eq!(report.baseline_ns, 100.0);
+        assert_eq!(report.traced_ns, 150.0);
+        assert_eq!(report.overhead_ns(), 50.0);
+        assert_eq!(report.slowdown_percent(), 50.0);
+        assert_eq!(report.tracer_cpu_percent(), 5.0);
+        assert_eq!(report.delivered_percent(), 100.0);
+    }
+
+    #[test]
+    fn test_render() {
+        let baseline = [sample(100_000, 1000)];
+        let traced = [sample(150_000, 1000)];
+        let report = Report::new(Workload::Getpid, &baseline, &traced, 900, 15_000);
+        assert_eq!(
+            report.render(),
+            "workload: getpid (1000 syscalls x 1 runs)\n\
+             baseline:    100.0 ns/syscall\n\
+             traced:      150.0 ns/syscall (+50.0%)\n\
+             overhead:     50.0 ns/event\n\
+             tracer:       10.0% of one CPU, 900 events (90.0% delivered)\n"
+        );
+    }
+}
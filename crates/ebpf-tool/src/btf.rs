@@ -0,0 +1,652 @@
+//! Kernel struct offsets from BTF, for the eBPF struct readers.
+//!
+//! `task->real_parent->tgid` is at a different offset on every kernel
+//! build: fields get added, configs remove others, and randstruct shuffles
+//! them. The kernel describes its own layout in BTF
+//! (`/sys/kernel/btf/vmlinux`), and C programs use it through CO-RE
+//! relocations that libbpf resolves at load time. rustc does not emit those
+//! relocations, so this module does the same lookup by hand:
+//!
+//! ```text
+//! /sys/kernel/btf/vmlinux ──► Btf::parse ──► kernel_offsets()
+//!                                                  │ KernelOffsets
+//!                                                  ▼
+//!              loader: EbpfLoader::set_global("KERNEL_OFFSETS", ...)
+//!                                                  │
+//!                                                  ▼
+//!                         kstruct.rs readers: base + offset, probe_read
+//! ```
+//!
+//! Only what the lookup needs is parsed: struct and union members, arrays,
+//! and the qualifiers and typedefs between them. A field the kernel lacks
+//! stays `OFFSET_UNKNOWN`, and the reader that needs it returns an error.
+
+use anyhow::{bail, Context, Result};
+use ebpf_tool_common::{KernelOffsets, OFFSET_UNKNOWN};
+use std::sync::OnceLock;
+
+/// Where the running kernel exposes its BTF (CONFIG_DEBUG_INFO_BTF).
+pub const VMLINUX_BTF: &str = "/sys/kernel/btf/vmlinux";
+
+const BTF_MAGIC: u16 = 0xeb9f;
+const HEADER_LEN: usize = 24;
+
+const KIND_INT: u32 = 1;
+const KIND_PTR: u32 = 2;
+const KIND_ARRAY: u32 = 3;
+const KIND_STRUCT: u32 = 4;
+const KIND_UNION: u32 = 5;
+const KIND_ENUM: u32 = 6;
+const KIND_TYPEDEF: u32 = 8;
+const KIND_VOLATILE: u32 = 9;
+const KIND_CONST: u32 = 10;
+const KIND_RESTRICT: u32 = 11;
+const KIND_FUNC_PROTO: u32 = 13;
+const KIND_VAR: u32 = 14;
+const KIND_DATASEC: u32 = 15;
+const KIND_DECL_TAG: u32 = 17;
+const KIND_TYPE_TAG: u32 = 18;
+const KIND_ENUM64: u32 = 19;
+
+#[derive(Debug, Clone)]
+struct Type {
+    name_off: u32,
+    kind: u32,
+    /// Size for structs, unions, ints and enums; the target type for
+    /// pointers, typedefs and qualifiers
+    size_or_type: u32,
+    members: Vec<Member>,
+    /// ARRAY: (element type, number of elements)
+    array: Option<(u32, u32)>,
+}
+
+#[derive(Debug, Clone)]
+struct Member {
+    name_off: u32,
+    type_id: u32,
+    bit_offset: u32,
+}
+
+/// A field found by [`Btf::field`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    /// Bytes from the start of the outer struct
+    pub offset: u32,
+    /// Type of the field, for [`Btf::size_of`]
+    pub type_id: u32,
+}
+
+/// The parsed type section of one BTF blob.
+#[derive(Debug)]
+pub struct Btf {
+    /// Indexed by type id; id 0 is `void`
+    types: Vec<Type>,
+    strings: Vec<u8>,
+}
+
+impl Btf {
+    /// Parse the running kernel's BTF.
+    pub fn from_sys_fs() -> Result<Self> {
+        let data = std::fs::read(VMLINUX_BTF).with_context(|| {
+            format!(
+                "cannot read {} (kernel built without CONFIG_DEBUG_INFO_BTF?)",
+                VMLINUX_BTF
+            )
+        })?;
+        Self::parse(&data)
+    }
+
+    /// Parse a raw BTF blob in host byte order.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let u16_at = |at: usize| {
+            data.get(at..at + 2)
+                .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+        };
+        match u16_at(0) {
+            Some(BTF_MAGIC) => {}
+            Some(m) if m == BTF_MAGIC.swap_bytes() => bail!("BTF is in the other byte order"),
+            _ => bail!("not BTF data (bad magic)"),
+        }
+        let mut header = Reader::new(data, 4);
+        let hdr_len = header.u32()? as usize;
+        let (type_off, type_len) = (header.u32()? as usize, header.u32()? as usize);
+        let (str_off, str_len) = (header.u32()? as usize, header.u32()? as usize);
+        if hdr_len < HEADER_LEN {
+            bail!(
+                "BTF header is {} bytes, expected at least {}",
+                hdr_len,
+                HEADER_LEN
+            );
+        }
+        let section = |off: usize, len: usize, what: &str| {
+            data.get(hdr_len + off..hdr_len + off + len)
+                .with_context(|| format!("BTF {} section is out of bounds", what))
+        };
+        let type_data = section(type_off, type_len, "type")?;
+        let strings = section(str_off, str_len, "string")?.to_vec();
+
+        let mut types = vec![Type {
+            name_off: 0,
+            kind: 0,
+            size_or_type: 0,
+            members: Vec::new(),
+            array: None,
+        }];
+        let mut r = Reader::new(type_data, 0);
+        while !r.done() {
+            types.push(
+                r.btf_type()
+                    .with_context(|| format!("BTF type {}", types.len()))?,
+            );
+        }
+        Ok(Self { types, strings })
+    }
+
+    fn name(&self, name_off: u32) -> &str {
+        let start = name_off as usize;
+        let rest = self.strings.get(start..).unwrap_or_default();
+        let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        std::str::from_utf8(&rest[..end]).unwrap_or("")
+    }
+
+    /// Id of the struct (or union) called `name`.
+    pub fn struct_id(&self, name: &str) -> Option<u32> {
+        self.types
+            .iter()
+            .position(|t| {
+                matches!(t.kind, KIND_STRUCT | KIND_UNION) && self.name(t.name_off) == name
+            })
+            .map(|id| id as u32)
+    }
+
+    /// Skip typedefs, qualifiers and type tags.
+    fn resolve(&self, mut id: u32) -> u32 {
+        for _ in 0..32 {
+            match self.types.get(id as usize) {
+                Some(t)
+                    if matches!(
+                        t.kind,
+                        KIND_TYPEDEF | KIND_VOLATILE | KIND_CONST | KIND_RESTRICT | KIND_TYPE_TAG
+                    ) =>
+                {
+                    id = t.size_or_type
+                }
+                _ => break,
+            }
+        }
+        id
+    }
+
+    /// Size in bytes of type `id`.
+    pub fn size_of(&self, id: u32) -> Option<u32> {
+        let t = self.types.get(self.resolve(id) as usize)?;
+        match t.kind {
+            KIND_INT | KIND_STRUCT | KIND_UNION | KIND_ENUM | KIND_ENUM64 => Some(t.size_or_type),
+            KIND_PTR => Some(8),
+            KIND_ARRAY => {
+                let (elem, n) = t.array?;
+                self.size_of(elem)?.checked_mul(n)
+            }
+            _ => None,
+        }
+    }
+
+    /// Element type of an array type.
+    pub fn array_elem(&self, id: u32) -> Option<u32> {
+        let t = self.types.get(self.resolve(id) as usize)?;
+        (t.kind == KIND_ARRAY).then_some(t.array?.0)
+    }
+
+    /// Offset of `path` in struct `name`, e.g. `("net", "ns.inum")`.
+    /// Members of anonymous structs and unions are found as if they were
+    /// direct members, as in C.
+    pub fn field(&self, name: &str, path: &str) -> Result<Field> {
+        let mut id = self
+            .struct_id(name)
+            .with_context(|| format!("no struct {} in BTF", name))?;
+        let mut offset = 0;
+        for part in path.split('.') {
+            let (bits, member_type) = self.find_member(id, part).with_context(|| {
+                format!(
+                    "no field {} in {} (looking up {}.{})",
+                    part,
+                    self.type_name(id),
+                    name,
+                    path
+                )
+            })?;
+            if bits % 8 != 0 {
+                bail!("{}.{} is a bitfield", name, path);
+            }
+            offset += bits / 8;
+            id = member_type;
+        }
+        Ok(Field {
+            offset,
+            type_id: id,
+        })
+    }
+
+    /// (bit offset, type) of member `name` of struct/union `id`.
+    fn find_member(&self, id: u32, name: &str) -> Option<(u32, u32)> {
+        let t = self.types.get(self.resolve(id) as usize)?;
+        if !matches!(t.kind, KIND_STRUCT | KIND_UNION) {
+            return None;
+        }
+        t.members.iter().find_map(|m| {
+            if m.name_off == 0 {
+                // Anonymous struct or union: search inside it
+                let (bits, ty) = self.find_member(m.type_id, name)?;
+                Some((m.bit_offset + bits, ty))
+            } else {
+                (self.name(m.name_off) == name).then_some((m.bit_offset, m.type_id))
+            }
+        })
+    }
+
+    fn type_name(&self, id: u32) -> String {
+        match self.types.get(self.resolve(id) as usize) {
+            Some(t) if t.name_off != 0 => self.name(t.name_off).to_string(),
+            _ => format!("type {}", id),
+        }
+    }
+}
+
+/// Look up every `KernelOffsets` field; missing ones stay `OFFSET_UNKNOWN`.
+pub fn kernel_offsets(btf: &Btf) -> KernelOffsets {
+    let offset = |name: &str, path: &str| {
+        btf.field(name, path)
+            .map(|f| f.offset)
+            .unwrap_or(OFFSET_UNKNOWN)
+    };
+    let mut o = KernelOffsets {
+        task_pid: offset("task_struct", "pid"),
+        task_tgid: offset("task_struct", "tgid"),
+        task_real_parent: offset("task_struct", "real_parent"),
+        task_nsproxy: offset("task_struct", "nsproxy"),
+        task_mm: offset("task_struct", "mm"),
+        task_thread_pid: offset("task_struct", "thread_pid"),
+        nsproxy_uts_ns: offset("nsproxy", "uts_ns"),
+        nsproxy_ipc_ns: offset("nsproxy", "ipc_ns"),
+        nsproxy_mnt_ns: offset("nsproxy", "mnt_ns"),
+        nsproxy_net_ns: offset("nsproxy", "net_ns"),
+        nsproxy_cgroup_ns: offset("nsproxy", "cgroup_ns"),
+        uts_ns_inum: offset("uts_namespace", "ns.inum"),
+        ipc_ns_inum: offset("ipc_namespace", "ns.inum"),
+        mnt_ns_inum: offset("mnt_namespace", "ns.inum"),
+        net_ns_inum: offset("net", "ns.inum"),
+        cgroup_ns_inum: offset("cgroup_namespace", "ns.inum"),
+        pid_level: offset("pid", "level"),
+        pid_numbers: offset("pid", "numbers"),
+        upid_size: btf
+            .struct_id("upid")
+            .and_then(|id| btf.size_of(id))
+            .unwrap_or(OFFSET_UNKNOWN),
+        upid_ns: offset("upid", "ns"),
+        pid_ns_inum: offset("pid_namespace", "ns.inum"),
+        sock_net: offset("sock", "__sk_common.skc_net.net"),
+        sock_family: offset("sock", "__sk_common.skc_family"),
+        sock_rcv_saddr: offset("sock", "__sk_common.skc_rcv_saddr"),
+        sock_daddr: offset("sock", "__sk_common.skc_daddr"),
+        sock_num: offset("sock", "__sk_common.skc_num"),
+        sock_dport: offset("sock", "__sk_common.skc_dport"),
+        msghdr_name: offset("msghdr", "msg_name"),
+        skb_dev: offset("sk_buff", "dev"),
+        skb_head: offset("sk_buff", "head"),
+        skb_network_header: offset("sk_buff", "network_header"),
+        skb_transport_header: offset("sk_buff", "transport_header"),
+        netdev_net: offset("net_device", "nd_net.net"),
+        open_how_flags: offset("open_how", "flags"),
+        open_how_mode: offset("open_how", "mode"),
+        open_how_resolve: offset("open_how", "resolve"),
+        task_exit_code: offset("task_struct", "exit_code"),
+        ..KernelOffsets::unknown()
+    };
+    if let Some((rss_stat, stride, count)) = rss_stat_layout(btf) {
+        o.mm_rss_stat = rss_stat;
+        o.rss_stat_stride = stride;
+        o.rss_stat_count = count;
+    }
+    o
+}
+
+/// (`mm_struct.rss_stat` offset, counter stride, count offset in a counter).
+///
+/// Before 6.2: `struct mm_rss_stat { atomic_long_t count[NR_MM_COUNTERS]; }`.
+/// Since 6.2: `struct percpu_counter rss_stat[NR_MM_COUNTERS]`, where the
+/// `count` field is the total as of the last per-CPU batch fold.
+fn rss_stat_layout(btf: &Btf) -> Option<(u32, u32, u32)> {
+    let rss_stat = btf.field("mm_struct", "rss_stat").ok()?;
+    if let Some(elem) = btf.array_elem(rss_stat.type_id) {
+        let count = btf.field("percpu_counter", "count").ok()?.offset;
+        return Some((rss_stat.offset, btf.size_of(elem)?, count));
+    }
+    let counters = btf.field("mm_rss_stat", "count").ok()?;
+    let elem = btf.array_elem(counters.type_id)?;
+    Some((rss_stat.offset + counters.offset, btf.size_of(elem)?, 0))
+}
+
+/// Offsets for the running kernel, looked up once per process. Without
+/// BTF every offset is unknown: programs still load, and the struct
+/// readers report their fields as missing.
+pub fn running_kernel_offsets() -> KernelOffsets {
+    static OFFSETS: OnceLock<KernelOffsets> = OnceLock::new();
+    *OFFSETS.get_or_init(|| match Btf::from_sys_fs() {
+        Ok(btf) => {
+            let offsets = kernel_offsets(&btf);
+            log::debug!("Kernel struct offsets from {}: {:?}", VMLINUX_BTF, offsets);
+            offsets
+        }
+        Err(e) => {
+            log::warn!("{:#}: parent PIDs and namespace ids will show as 0", e);
+            KernelOffsets::unknown()
+        }
+    })
+}
+
+/// `KernelOffsets` as an aya global (the common crate does not depend on aya).
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct GlobalOffsets(pub KernelOffsets);
+
+// SAFETY: KernelOffsets is repr(C) and only holds u32s, so any bit pattern
+// is valid and there is no padding.
+unsafe impl aya::Pod for GlobalOffsets {}
+
+/// Little cursor over native-endian BTF data.
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], at: usize) -> Self {
+        Self { data, at }
+    }
+
+    fn done(&self) -> bool {
+        self.at >= self.data.len()
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self
+            .data
+            .get(self.at..self.at + 4)
+            .context("BTF data ends in the middle of a type")?;
+        self.at += 4;
+        Ok(u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn skip(&mut self, words: u32) -> Result<()> {
+        for _ in 0..words {
+            self.u32()?;
+        }
+        Ok(())
+    }
+
+    /// One `struct btf_type` and the data that follows it.
+    fn btf_type(&mut self) -> Result<Type> {
+        let name_off = self.u32()?;
+        let info = self.u32()?;
+        let size_or_type = self.u32()?;
+        let kind = (info >> 24) & 0x1f;
+        let vlen = info & 0xffff;
+        let kind_flag = info >> 31 == 1;
+        let mut t = Type {
+            name_off,
+            kind,
+            size_or_type,
+            members: Vec::new(),
+            array: None,
+        };
+        match kind {
+            KIND_INT | KIND_VAR | KIND_DECL_TAG => self.skip(1)?,
+            KIND_ARRAY => {
+                let elem = self.u32()?;
+                let _index_type = self.u32()?;
+                t.array = Some((elem, self.u32()?));
+            }
+            KIND_STRUCT | KIND_UNION => {
+                for _ in 0..vlen {
+                    let name_off = self.u32()?;
+                    let type_id = self.u32()?;
+                    let offset = self.u32()?;
+                    // With kind_flag, the top 8 bits are the bitfield size
+                    let bit_offset = if kind_flag {
+                        offset & 0xff_ffff
+                    } else {
+                        offset
+                    };
+                    t.members.push(Member {
+                        name_off,
+                        type_id,
+                        bit_offset,
+                    });
+                }
+            }
+            KIND_ENUM | KIND_FUNC_PROTO => self.skip(2 * vlen)?,
+            KIND_DATASEC | KIND_ENUM64 => self.skip(3 * vlen)?,
+            0 | 20.. => bail!("unknown BTF kind {}", kind),
+            _ => {}
+        }
+        Ok(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds BTF blobs for the tests.
+    #[derive(Default)]
+    struct Builder {
+        types: Vec<u32>,
+        strings: Vec<u8>,
+        next_id: u32,
+    }
+
+    impl Builder {
+        fn new() -> Self {
+            Self {
+                strings: vec![0],
+                next_id: 1,
+                ..Default::default()
+            }
+        }
+
+        fn str(&mut self, s: &str) -> u32 {
+            if s.is_empty() {
+                return 0;
+            }
+            let off = self.strings.len() as u32;
+            self.strings.extend(s.as_bytes());
+            self.strings.push(0);
+            off
+        }
+
+        fn add(
+            &mut self,
+            name: &str,
+            kind: u32,
+            vlen: u32,
+            size_or_type: u32,
+            extra: &[u32],
+        ) -> u32 {
+            let name_off = self.str(name);
+            self.types
+                .extend([name_off, (kind << 24) | vlen, size_or_type]);
+            self.types.extend(extra);
+            self.next_id += 1;
+            self.next_id - 1
+        }
+
+        fn int(&mut self, name: &str, size: u32) -> u32 {
+            self.add(name, KIND_INT, 0, size, &[size * 8])
+        }
+
+        fn ptr(&mut self, to: u32) -> u32 {
+            self.add("", KIND_PTR, 0, to, &[])
+        }
+
+        fn array(&mut self, elem: u32, n: u32) -> u32 {
+            self.add("", KIND_ARRAY, 0, 0, &[elem, elem, n])
+        }
+
+        /// Members are (name, type, byte offset).
+        fn strukt(&mut self, name: &str, size: u32, members: &[(&str, u32, u32)]) -> u32 {
+            let mut extra = Vec::new();
+            for (member, ty, offset) in members {
+                extra.extend([self.str(member), *ty, offset * 8]);
+            }
+            self.add(name, KIND_STRUCT, members.len() as u32, size, &extra)
+        }
+
+        fn build(&self) -> Vec<u8> {
+            let type_len = self.types.len() as u32 * 4;
+            let mut out = Vec::new();
+            out.extend(BTF_MAGIC.to_ne_bytes());
+            out.extend([1, 0]);
+            let header = [
+                HEADER_LEN as u32,
+                0,
+                type_len,
+                type_len,
+                self.strings.len() as u32,
+            ];
+            header.iter().for_each(|w| out.extend(w.to_ne_bytes()));
+            self.types.iter().for_each(|w| out.extend(w.to_ne_bytes()));
+            out.extend(&self.strings);
+            out
+        }
+    }
+
+    /// A kernel with task_struct, nsproxy, net and open_how, since 6.2's rss_stat.
+    fn small_kernel() -> Btf {
+        let mut b = Builder::new();
+        let int = b.int("int", 4);
+        let uint = b.int("unsigned int", 4);
+        let ns_common = b.strukt("ns_common", 16, &[("stashed", int, 0), ("inum", uint, 8)]);
+        let net = b.strukt("net", 64, &[("passive", int, 0), ("ns", ns_common, 40)]);
+        let net_ptr = b.ptr(net);
+        let nsproxy = b.strukt("nsproxy", 48, &[("count", int, 0), ("net_ns", net_ptr, 32)]);
+        let nsproxy_ptr = b.ptr(nsproxy);
+        let pid_t = b.add("pid_t", KIND_TYPEDEF, 0, int, &[]);
+        // randstruct-style anonymous struct around the interesting fields
+        let inner = b.strukt(
+            "",
+            24,
+            &[
+                ("pid", pid_t, 0),
+                ("tgid", pid_t, 4),
+                ("exit_code", int, 8),
+                ("nsproxy", nsproxy_ptr, 16),
+            ],
+        );
+        let task = b.strukt("task_struct", 4096, &[("state", int, 0), ("", inner, 1000)]);
+        let _ = b.ptr(task);
+        let long = b.int("long", 8);
+        let counter = b.strukt(
+            "percpu_counter",
+            40,
+            &[("lock", int, 0), ("count", long, 8)],
+        );
+        let counters = b.array(counter, 4);
+        let _ = b.strukt("mm_struct", 1024, &[("rss_stat", counters, 720)]);
+        let pid_ns = b.strukt("pid_namespace", 128, &[("ns", ns_common, 96)]);
+        let pid_ns_ptr = b.ptr(pid_ns);
+        let const_ptr = b.add("", KIND_CONST, 0, pid_ns_ptr, &[]);
+        let _ = b.strukt("upid", 16, &[("nr", int, 0), ("ns", const_ptr, 8)]);
+        let u64_t = b.int("__u64", 8);
+        let _ = b.strukt(
+            "open_how",
+            24,
+            &[
+                ("flags", u64_t, 0),
+                ("mode", u64_t, 8),
+                ("resolve", u64_t, 16),
+            ],
+        );
+        Btf::parse(&b.build()).unwrap()
+    }
+
+    #[test]
+    fn test_field_follows_paths_and_anonymous_members() {
+        let btf = small_kernel();
+        assert_eq!(btf.field("net", "ns.inum").unwrap().offset, 48);
+        // pid is inside the anonymous struct at 1000
+        assert_eq!(btf.field("task_struct", "tgid").unwrap().offset, 1004);
+        assert_eq!(btf.field("task_struct", "nsproxy").unwrap().offset, 1016);
+
+        let err = btf.field("task_struct", "no_such_field").unwrap_err();
+        assert!(err.to_string().contains("no field no_such_field"), "{err}");
+        assert!(btf.field("no_such_struct", "x").is_err());
+
+        // The typedef is resolved for the size
+        let tgid = btf.field("task_struct", "tgid").unwrap();
+        assert_eq!(btf.size_of(tgid.type_id), Some(4));
+    }
+
+    #[test]
+    fn test_kernel_offsets_marks_missing_fields() {
+        let o = kernel_offsets(&small_kernel());
+        assert_eq!((o.task_tgid, o.task_exit_code), (1004, 1008));
+        assert_eq!(o.nsproxy_net_ns, 32);
+        assert_eq!(o.net_ns_inum, 48);
+        assert_eq!((o.upid_size, o.upid_ns), (16, 8));
+        assert_eq!(o.pid_ns_inum, 104);
+        assert_eq!(
+            (o.mm_rss_stat, o.rss_stat_stride, o.rss_stat_count),
+            (720, 40, 8)
+        );
+        assert_eq!(
+            (o.open_how_flags, o.open_how_mode, o.open_how_resolve),
+            (0, 8, 16)
+        );
+        // Not in this BTF
+        assert_eq!(o.task_real_parent, OFFSET_UNKNOWN);
+        assert_eq!(o.mnt_ns_inum, OFFSET_UNKNOWN);
+        assert_eq!(o.sock_net, OFFSET_UNKNOWN);
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert!(Btf::parse(b"").is_err());
+        assert!(Btf::parse(b"\x7fELF............................").is_err());
+        let mut truncated = Builder::new();
+        truncated.int("int", 4);
+        let mut data = truncated.build();
+        data[12..16].copy_from_slice(&100u32.to_ne_bytes()); // type_len past the end
+        assert!(Btf::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_running_kernel() {
+        // Only where the kernel has BTF (every major distribution since ~2020)
+        let Ok(btf) = Btf::from_sys_fs() else {
+            return;
+        };
+        let o = kernel_offsets(&btf);
+        for (name, value) in [
+            ("task_tgid", o.task_tgid),
+            ("task_real_parent", o.task_real_parent),
+            ("task_nsproxy", o.task_nsproxy),
+            ("task_exit_code", o.task_exit_code),
+            ("nsproxy_net_ns", o.nsproxy_net_ns),
+            ("net_ns_inum", o.net_ns_inum),
+            ("mnt_ns_inum", o.mnt_ns_inum),
+            ("mm_rss_stat", o.mm_rss_stat),
+            ("sock_net", o.sock_net),
+            ("sock_dport", o.sock_dport),
+            ("skb_dev", o.skb_dev),
+            ("skb_transport_header", o.skb_transport_header),
+            ("netdev_net", o.netdev_net),
+        ] {
+            assert_ne!(value, OFFSET_UNKNOWN, "{name}");
+        }
+        assert_eq!(o.task_tgid, o.task_pid + 4, "pid and tgid are adjacent");
+        // open_how is UAPI (5.6+): its layout never changes, only grows
+        if o.open_how_flags != OFFSET_UNKNOWN {
+            assert_eq!((o.open_how_mode, o.open_how_resolve), (8, 16));
+        }
+    }
+}
@@ -0,0 +1,548 @@
+//! Event captures: the `.etrc` files of `trace --record` and `replay`.
+//!
+//! A capture stores the raw perf buffer records exactly as the kernel
+//! delivered them, so a trace taken on a target machine can be rendered,
+//! filtered and summarized later on a laptop, without root or eBPF.
+//!
+//! ```text
+//! "ETRC"  u16 version  u16 reserved  u32 header_len   (little-endian)
+//! header: JSON, header_len bytes  (arch, kernel, struct layouts, ...)
+//! record: u16 kind  u16 cpu  u32 len  <len bytes>     (repeated to EOF)
+//! ```
+//!
+//! Records are the `#[repr(C)]` structs from `ebpf-tool-common`, byte for
+//! byte. The header describes their layout (size and every field offset),
+//! and the reader refuses a capture whose layout differs from this build's
+//! instead of decoding garbage. Record kinds this build does not know are
+//! passed through as-is, so an older `replay` can still read the syscalls
+//! from a newer recorder.
+
+use crate::events::{self, Event};
+use anyhow::{bail, ensure, Context, Result};
+use bytemuck::Pod;
+use ebpf_tool_common::{CommUpdateEvent, SyscallEvent, SyscallExitEvent};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::mem::{offset_of, size_of};
+use std::path::Path;
+
+/// First bytes of every capture.
+pub const MAGIC: &[u8; 4] = b"ETRC";
+
+/// Bumped when the framing or the header changes incompatibly. Struct
+/// layout changes do not need a bump: the header describes them.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// A `SyscallEvent` from the `EVENTS` perf array.
+pub const KIND_SYSCALL: u16 = 0;
+/// A `CommUpdateEvent` from the `COMM_UPDATES` perf array.
+pub const KIND_COMM_UPDATE: u16 = 1;
+/// A `SyscallExitEvent` from the `SYSCALL_EXITS` perf array (`--returns`).
+pub const KIND_SYSCALL_EXIT: u16 = 2;
+/// Events lost on one CPU; the payload is the count as a u64.
+pub const KIND_LOST: u16 = u16::MAX;
+
+/// Records larger than this are treated as corruption, not allocated.
+const MAX_RECORD_LEN: u32 = 1 << 20;
+/// Headers larger than this are treated as corruption, not allocated.
+const MAX_HEADER_LEN: u32 = 1 << 20;
+
+/// One field of a recorded struct.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldLayout {
+    pub name: String,
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// Layout of the struct stored in records of one kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordLayout {
+    pub kind: u16,
+    /// Struct name in `ebpf-tool-common`, e.g. "SyscallEvent"
+    pub name: String,
+    pub size: u32,
+    pub fields: Vec<FieldLayout>,
+}
+
+/// Everything needed to interpret the records on another machine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    /// Recording machine's architecture: syscall numbers depend on it
+    pub arch: String,
+    /// "little" or "big": records are in the recorder's byte order
+    pub endian: String,
+    /// Kernel release of the recording machine (/proc/sys/kernel/osrelease)
+    pub kernel: String,
+    pub hostname: String,
+    pub started_unix: u64,
+    /// CLOCK_REALTIME - CLOCK_MONOTONIC of the recording machine, to print
+    /// event timestamps as its wall clock. 0 in captures from older
+    /// recorders
+    #[serde(default)]
+    pub realtime_offset_ns: u64,
+    /// Command line of the recording `ebpf-tool`
+    pub command: Vec<String>,
+    pub layouts: Vec<RecordLayout>,
+}
+
+impl Header {
+    /// Header for a capture recorded now, on this machine.
+    pub fn current(command: impl IntoIterator<Item = String>) -> Self {
+        let read = |path: &str| {
+            std::fs::read_to_string(path)
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        Self {
+            arch: std::env::consts::ARCH.to_string(),
+            endian: host_endian().to_string(),
+            kernel: read("/proc/sys/kernel/osrelease"),
+            hostname: read("/proc/sys/kernel/hostname"),
+            started_unix: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            realtime_offset_ns: crate::clock::realtime_offset_ns(),
+            command: command.into_iter().collect(),
+            layouts: layouts(),
+        }
+    }
+
+    /// Layout recorded for `kind`, if the capture has one.
+    pub fn layout(&self, kind: u16) -> Option<&RecordLayout> {
+        self.layouts.iter().find(|l| l.kind == kind)
+    }
+}
+
+fn host_endian() -> &'static str {
+    if cfg!(target_endian = "little") {
+        "little"
+    } else {
+        "big"
+    }
+}
+
+macro_rules! field {
+    ($ty:ty, $field:ident) => {{
+        fn size<T>(_: fn(&$ty) -> &T) -> usize {
+            size_of::<T>()
+        }
+        FieldLayout {
+            name: stringify!($field).to_string(),
+            offset: offset_of!($ty, $field) as u32,
+            size: size(|s: &$ty| &s.$field) as u32,
+        }
+    }};
+}
+
+/// Layouts of the record structs in this build of `ebpf-tool-common`.
+///
+/// Add a field here whenever one is added to the struct, otherwise a
+/// capture with the old layout would still be accepted.
+pub fn layouts() -> Vec<RecordLayout> {
+    vec![
+        RecordLayout {
+            kind: KIND_SYSCALL,
+            name: "SyscallEvent".to_string(),
+            size: size_of::<SyscallEvent>() as u32,
+            fields: vec![
+                field!(SyscallEvent, pid),
+                field!(SyscallEvent, tid),
+                field!(SyscallEvent, syscall_nr),
+                field!(SyscallEvent, cgroup_id),
+                field!(SyscallEvent, timestamp_ns),
+                field!(SyscallEvent, comm),
+                field!(SyscallEvent, ppid),
+                field!(SyscallEvent, pidns_inum),
+                field!(SyscallEvent, mntns_inum),
+                field!(SyscallEvent, uid),
+                field!(SyscallEvent, gid),
+                field!(SyscallEvent, abi),
+            ],
+        },
+        RecordLayout {
+            kind: KIND_COMM_UPDATE,
+            name: "CommUpdateEvent".to_string(),
+            size: size_of::<CommUpdateEvent>() as u32,
+            fields: vec![
+                field!(CommUpdateEvent, pid),
+                field!(CommUpdateEvent, tid),
+                field!(CommUpdateEvent, kind),
+                field!(CommUpdateEvent, comm),
+                field!(CommUpdateEvent, abi),
+            ],
+        },
+        RecordLayout {
+            kind: KIND_SYSCALL_EXIT,
+            name: "SyscallExitEvent".to_string(),
+            size: size_of::<SyscallExitEvent>() as u32,
+            fields: vec![
+                field!(SyscallExitEvent, pid),
+                field!(SyscallExitEvent, tid),
+                field!(SyscallExitEvent, syscall_nr),
+                field!(SyscallExitEvent, ret),
+                field!(SyscallExitEvent, enter_ns),
+                field!(SyscallExitEvent, timestamp_ns),
+                field!(SyscallExitEvent, abi),
+            ],
+        },
+    ]
+}
+
+/// Fail if the capture cannot be decoded by this build.
+fn check_compatible(header: &Header) -> Result<()> {
+    ensure!(
+        header.endian == host_endian(),
+        "capture is {}-endian, this machine is {}-endian",
+        header.endian,
+        host_endian()
+    );
+    for current in layouts() {
+        let Some(recorded) = header.layout(current.kind) else {
+            continue;
+        };
+        if recorded.size != current.size {
+            bail!(
+                "capture has a different {} layout: {} bytes, this build has {}",
+                current.name,
+                recorded.size,
+                current.size
+            );
+        }
+        for field in &current.fields {
+            match recorded.fields.iter().find(|f| f.name == field.name) {
+                Some(f) if f == field => {}
+                Some(f) => bail!(
+                    "capture has a different {} layout: {} at offset {} ({} bytes), \
+                     this build has offset {} ({} bytes)",
+                    current.name,
+                    field.name,
+                    f.offset,
+                    f.size,
+                    field.offset,
+                    field.size
+                ),
+                None => bail!(
+                    "capture has a different {} layout: no field {}",
+                    current.name,
+                    field.name
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One record read back from a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    /// Raw struct bytes of `kind`; decode with [`syscall_event`],
+    /// [`comm_update`] or [`syscall_exit`]
+    Event {
+        kind: u16,
+        cpu: u16,
+        data: Vec<u8>,
+    },
+    Lost {
+        cpu: u16,
+        count: u64,
+    },
+}
+
+/// Writes a capture. Call [`Writer::finish`] to flush it.
+pub struct Writer<W: Write> {
+    out: W,
+}
+
+impl Writer<BufWriter<File>> {
+    /// Create (or truncate) `path` and write the header.
+    pub fn create(path: &Path, header: &Header) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        Self::new(BufWriter::new(file), header)
+    }
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(mut out: W, header: &Header) -> Result<Self> {
+        let json = serde_json::to_vec(header)?;
+        out.write_all(MAGIC)?;
+        out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        out.write_all(&0u16.to_le_bytes())?;
+        out.write_all(&(json.len() as u32).to_le_bytes())?;
+        out.write_all(&json)?;
+        Ok(Self { out })
+    }
+
+    /// Append one perf buffer record, as read from the buffer.
+    pub fn event(&mut self, kind: u16, cpu: u16, data: &[u8]) -> Result<()> {
+        ensure!(
+            data.len() as u32 <= MAX_RECORD_LEN,
+            "record too large: {} bytes",
+            data.len()
+        );
+        self.out.write_all(&kind.to_le_bytes())?;
+        self.out.write_all(&cpu.to_le_bytes())?;
+        self.out.write_all(&(data.len() as u32).to_le_bytes())?;
+        self.out.write_all(data)?;
+        Ok(())
+    }
+
+    /// Append a lost-events count for `cpu`.
+    pub fn lost(&mut self, cpu: u16, count: u64) -> Result<()> {
+        self.event(KIND_LOST, cpu, &count.to_le_bytes())
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Reads a capture written by [`Writer`].
+pub struct Reader<R: Read> {
+    input: R,
+    header: Header,
+}
+
+impl Reader<BufReader<File>> {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        Self::new(BufReader::new(file))
+            .with_context(|| format!("failed to read {}", path.display()))
+    }
+}
+
+impl<R: Read> Reader<R> {
+    /// Read and check the header; fails if this build cannot decode the records.
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut prefix = [0u8; 12];
+        input
+            .read_exact(&mut prefix)
+            .context("not an ebpf-tool capture (file too short)")?;
+        ensure!(
+            &prefix[0..4] == MAGIC,
+            "not an ebpf-tool capture (bad magic)"
+        );
+        let version = u16::from_le_bytes([prefix[4], prefix[5]]);
+        ensure!(
+            version == FORMAT_VERSION,
+            "capture format version {} is not supported (this build reads version {})",
+            version,
+            FORMAT_VERSION
+        );
+        let len = u32::from_le_bytes([prefix[8], prefix[9], prefix[10], prefix[11]]);
+        ensure!(
+            len <= MAX_HEADER_LEN,
+            "corrupt capture header ({} bytes)",
+            len
+        );
+        let mut json = vec![0u8; len as usize];
+        input
+            .read_exact(&mut json)
+            .context("truncated capture header")?;
+        let header: Header = serde_json::from_slice(&json).context("corrupt capture header")?;
+        check_compatible(&header)?;
+        Ok(Self { input, header })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Next record, or None at the end of the capture.
+    ///
+    /// A record cut off by the end of the file (the recording was killed)
+    /// is an error; everything before it is still valid.
+    pub fn next_record(&mut self) -> Result<Option<Record>> {
+        let mut head = [0u8; 8];
+        match self.input.read(&mut head[..1])? {
+            0 => return Ok(None),
+            _ => self.input.read_exact(&mut head[1..]).map_err(truncated)?,
+        }
+        let kind = u16::from_le_bytes([head[0], head[1]]);
+        let cpu = u16::from_le_bytes([head[2], head[3]]);
+        let len = u32::from_le_bytes([head[4], head[5], head[6], head[7]]);
+        ensure!(len <= MAX_RECORD_LEN, "corrupt record ({} bytes)", len);
+        let mut data = vec![0u8; len as usize];
+        self.input.read_exact(&mut data).map_err(truncated)?;
+
+        if kind == KIND_LOST {
+            let count: [u8; 8] = data
+                .as_slice()
+                .try_into()
+                .context("corrupt lost-events record")?;
+            return Ok(Some(Record::Lost {
+                cpu,
+                count: u64::from_le_bytes(count),
+            }));
+        }
+        Ok(Some(Record::Event { kind, cpu, data }))
+    }
+}
+
+fn truncated(e: std::io::Error) -> anyhow::Error {
+    if e.kind() == ErrorKind::UnexpectedEof {
+        anyhow::anyhow!(
+            "truncated record at the end of the capture (was the recording interrupted?)"
+        )
+    } else {
+        e.into()
+    }
+}
+
+/// Copy a record struct out of its raw bytes with [`events::parse`]: the
+/// length and the `abi` field are checked, extra bytes (perf padding) are
+/// ignored.
+fn read_struct<T: Event>(data: &[u8]) -> Option<T> {
+    events::parse(data).ok()
+}
+
+/// Decode a `KIND_SYSCALL` record.
+pub fn syscall_event(data: &[u8]) -> Option<SyscallEvent> {
+    read_struct(data)
+}
+
+/// Decode a `KIND_COMM_UPDATE` record.
+pub fn comm_update(data: &[u8]) -> Option<CommUpdateEvent> {
+    read_struct(data)
+}
+
+/// Decode a `KIND_SYSCALL_EXIT` record.
+pub fn syscall_exit(data: &[u8]) -> Option<SyscallExitEvent> {
+    read_struct(data)
+}
+
+/// Raw bytes of a record struct, for [`Writer::event`] in tests and tools
+/// that build captures without a perf buffer.
+pub fn struct_bytes<T: Pod>(value: &T) -> &[u8] {
+    bytemuck::bytes_of(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Header {
+        Header::current(["ebpf-tool".to_string(), "trace".to_string()])
+    }
+
+    fn event(pid: u32, nr: u64) -> SyscallEvent {
+        let mut e = SyscallEvent::new();
+        e.pid = pid;
+        e.tid = pid;
+        e.syscall_nr = nr;
+        e.timestamp_ns = 1_000 + nr;
+        e.comm[..4].copy_from_slice(b"bash");
+        e
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let h = header();
+        let mut w = Writer::new(Vec::new(), &h).unwrap();
+        w.event(KIND_SYSCALL, 0, struct_bytes(&event(42, 0)))
+            .unwrap();
+        w.lost(3, 17).unwrap();
+        w.event(KIND_SYSCALL, 1, struct_bytes(&event(43, 257)))
+            .unwrap();
+        let exit = SyscallExitEvent {
+            tid: 43,
+            ret: -2,
+            enter_ns: 1_257,
+            ..SyscallExitEvent::new()
+        };
+        w.event(KIND_SYSCALL_EXIT, 1, struct_bytes(&exit)).unwrap();
+        w.event(99, 1, b"from a newer recorder").unwrap();
+        let bytes = w.finish().unwrap();
+
+        let mut r = Reader::new(bytes.as_slice()).unwrap();
+        assert_eq!(r.header().arch, std::env::consts::ARCH);
+        assert_eq!(r.header().command, ["ebpf-tool", "trace"]);
+        assert_eq!(r.header().realtime_offset_ns, h.realtime_offset_ns);
+
+        let Some(Record::Event { kind, cpu, data }) = r.next_record().unwrap() else {
+            panic!("expected an event");
+        };
+        assert_eq!((kind, cpu), (KIND_SYSCALL, 0));
+        let e = syscall_event(&data).unwrap();
+        assert_eq!((e.pid, e.syscall_nr, &e.comm[..4]), (42, 0, &b"bash"[..]));
+
+        assert_eq!(
+            r.next_record().unwrap(),
+            Some(Record::Lost { cpu: 3, count: 17 })
+        );
+        let Some(Record::Event { data, .. }) = r.next_record().unwrap() else {
+            panic!("expected an event");
+        };
+        assert_eq!(syscall_event(&data).unwrap().syscall_nr, 257);
+        let Some(Record::Event { kind, data, .. }) = r.next_record().unwrap() else {
+            panic!("expected an exit");
+        };
+        assert_eq!(kind, KIND_SYSCALL_EXIT);
+        let x = syscall_exit(&data).unwrap();
+        assert_eq!((x.tid, x.ret, x.enter_ns), (43, -2, 1_257));
+        // Unknown kinds are passed through, not rejected
+        assert!(matches!(
+            r.next_record().unwrap(),
+            Some(Record::Event { kind: 99, .. })
+        ));
+        assert_eq!(r.next_record().unwrap(), None);
+    }
+
+    #[test]
+    fn test_header_without_clock_offset() {
+        // Captures from before `--timestamps` have no realtime_offset_ns
+        let mut json = serde_json::to_value(header()).unwrap();
+        json.as_object_mut().unwrap().remove("realtime_offset_ns");
+        let h: Header = serde_json::from_value(json).unwrap();
+        assert_eq!(h.realtime_offset_ns, 0);
+    }
+
+    #[test]
+    fn test_rejects_other_layout() {
+        let mut h = header();
+        h.layouts[0].fields[2].offset += 4;
+        let bytes = Writer::new(Vec::new(), &h).unwrap().finish().unwrap();
+        let err = Reader::new(bytes.as_slice()).err().unwrap().to_string();
+        assert!(err.contains("SyscallEvent layout: syscall_nr"), "{err}");
+
+        let mut h = header();
+        h.endian = "big".to_string();
+        let bytes = Writer::new(Vec::new(), &h).unwrap().finish().unwrap();
+        assert!(Reader::new(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        assert!(Reader::new(&b"ELF"[..]).is_err());
+
+        let mut bytes = Writer::new(Vec::new(), &header())
+            .unwrap()
+            .finish()
+            .unwrap();
+        bytes[4] = 9; // format version 9
+        let err = Reader::new(bytes.as_slice()).err().unwrap().to_string();
+        assert!(err.contains("version 9"), "{err}");
+    }
+
+    #[test]
+    fn test_truncated_record() {
+        let mut w = Writer::new(Vec::new(), &header()).unwrap();
+        w.event(KIND_SYSCALL, 0, struct_bytes(&event(1, 1)))
+            .unwrap();
+        w.event(KIND_SYSCALL, 0, struct_bytes(&event(2, 2)))
+            .unwrap();
+        let mut bytes = w.finish().unwrap();
+        bytes.truncate(bytes.len() - 5);
+
+        let mut r = Reader::new(bytes.as_slice()).unwrap();
+        assert!(r.next_record().unwrap().is_some());
+        let err = r.next_record().unwrap_err().to_string();
+        assert!(err.contains("truncated"), "{err}");
+    }
+}
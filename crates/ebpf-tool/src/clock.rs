@@ -0,0 +1,246 @@
+//! Event timestamps as the user reads them: wall clock, since boot, or
+//! since the start of the trace.
+//!
+//! Every event carries `bpf_ktime_get_ns()`, which is CLOCK_MONOTONIC: time
+//! since boot, not counting suspend. Turning it into wall-clock time needs
+//! the offset between the two clocks, measured once when tracing starts:
+//!
+//! ```text
+//! CLOCK_REALTIME = CLOCK_MONOTONIC + offset
+//! [12:34:56.789]   123456789012345   ─┘ realtime_offset_ns()
+//! ```
+//!
+//! The offset is stored in the capture header, so `replay` prints the wall
+//! clock of the recording machine, not the one it runs on. A live trace
+//! keeps a [`Timebase`] instead of a single offset, so it can recalibrate
+//! when NTP steps the clock during a long run (see src/timebase.rs).
+
+use std::fmt;
+
+use crate::timebase::{Sample, Timebase};
+
+/// How `trace` and `replay` print event timestamps (`--timestamps`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimestampMode {
+    /// Local time of day: [12:34:56.789]
+    #[default]
+    Wall,
+    /// Seconds since boot, as the kernel sees them: [5234.123456]
+    Mono,
+    /// Seconds since the trace started: [+1.234567]
+    Relative,
+}
+
+impl fmt::Display for TimestampMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimestampMode::Wall => "wall",
+            TimestampMode::Mono => "mono",
+            TimestampMode::Relative => "relative",
+        })
+    }
+}
+
+/// Formats `bpf_ktime_get_ns()` timestamps in one [`TimestampMode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    mode: TimestampMode,
+    /// How CLOCK_MONOTONIC maps to CLOCK_REALTIME
+    timebase: Timebase,
+    /// CLOCK_MONOTONIC value that `relative` counts from
+    start_ns: u64,
+    /// Local time zone, east of UTC
+    utc_offset_s: i64,
+}
+
+impl Clock {
+    /// A clock for timestamps of another boot, e.g. a capture's, in the
+    /// local time zone.
+    pub fn new(mode: TimestampMode, realtime_offset_ns: u64, start_ns: u64) -> Self {
+        Self::with_timebase(mode, Timebase::fixed(realtime_offset_ns), start_ns)
+    }
+
+    /// A clock for timestamps converted with `timebase`, e.g. the one saved
+    /// in a run report.
+    pub fn with_timebase(mode: TimestampMode, timebase: Timebase, start_ns: u64) -> Self {
+        Self {
+            mode,
+            utc_offset_s: local_utc_offset_s(timebase.unix_ns(start_ns)),
+            timebase,
+            start_ns,
+        }
+    }
+
+    /// A clock for a trace starting now, on this machine.
+    pub fn live(mode: TimestampMode) -> Self {
+        Self::with_timebase(mode, Timebase::calibrate(), monotonic_ns())
+    }
+
+    pub fn mode(&self) -> TimestampMode {
+        self.mode
+    }
+
+    /// CLOCK_REALTIME - CLOCK_MONOTONIC when the clock started.
+    pub fn realtime_offset_ns(&self) -> u64 {
+        self.timebase.realtime_offset_ns(self.start_ns)
+    }
+
+    pub fn timebase(&self) -> &Timebase {
+        &self.timebase
+    }
+
+    /// Take a new calibration sample if the last one is older than
+    /// `timebase::RECALIBRATE_INTERVAL_NS`. Returns how far the wall clock
+    /// moved since the previous sample, in nanoseconds.
+    pub fn recalibrate_if_due(&mut self) -> Option<i64> {
+        if self.timebase.due(monotonic_ns()) {
+            Some(self.timebase.recalibrate())
+        } else {
+            None
+        }
+    }
+
+    /// `timestamp_ns` as Unix time in nanoseconds.
+    pub fn unix_ns(&self, timestamp_ns: u64) -> u64 {
+        self.timebase.unix_ns(timestamp_ns)
+    }
+
+    /// `timestamp_ns` in this clock's mode, without brackets:
+    /// "12:34:56.789", "5234.123456" or "+1.234567".
+    pub fn format(&self, timestamp_ns: u64) -> String {
+        match self.mode {
+            TimestampMode::Wall => {
+                let local_ms =
+                    (self.unix_ns(timestamp_ns) / 1_000_000) as i64 + self.utc_offset_s * 1000;
+                let ms_of_day = local_ms.rem_euclid(86_400_000);
+                let s = ms_of_day / 1000;
+                format!(
+                    "{:02}:{:02}:{:02}.{:03}",
+                    s / 3600,
+                    s / 60 % 60,
+                    s % 60,
+                    ms_of_day % 1000
+                )
+            }
+            TimestampMode::Mono => seconds(timestamp_ns),
+            // Events read right after attaching can predate start_ns
+            TimestampMode::Relative => {
+                format!("+{}", seconds(timestamp_ns.saturating_sub(self.start_ns)))
+            }
+        }
+    }
+}
+
+fn seconds(ns: u64) -> String {
+    format!("{}.{:06}", ns / 1_000_000_000, ns % 1_000_000_000 / 1000)
+}
+
+pub(crate) fn clock_ns(clock: libc::clockid_t) -> u64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid timespec and both clocks always exist
+    unsafe { libc::clock_gettime(clock, &mut ts) };
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+/// CLOCK_MONOTONIC now: the clock of `bpf_ktime_get_ns()`.
+pub fn monotonic_ns() -> u64 {
+    clock_ns(libc::CLOCK_MONOTONIC)
+}
+
+/// CLOCK_REALTIME - CLOCK_MONOTONIC now, in nanoseconds.
+pub fn realtime_offset_ns() -> u64 {
+    Sample::take().realtime_offset_ns()
+}
+
+/// Local time zone offset at `unix_ns`, in seconds east of UTC.
+fn local_utc_offset_s(unix_ns: u64) -> i64 {
+    let t = (unix_ns / 1_000_000_000) as libc::time_t;
+    // SAFETY: an all-zero tm is valid, and localtime_r only writes to it
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    // SAFETY: both pointers are valid for the duration of the call
+    if unsafe { libc::localtime_r(&t, &mut tm) }.is_null() {
+        return 0;
+    }
+    tm.tm_gmtoff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(mode: TimestampMode) -> Clock {
+        Clock {
+            mode,
+            // 2026-10-14 12:00:00 UTC, 1000s after boot
+            timebase: Timebase::fixed(1_791_979_200_000_000_000 - 1_000_000_000_000),
+            start_ns: 1_000_000_000_000,
+            utc_offset_s: 0,
+        }
+    }
+
+    #[test]
+    fn test_wall() {
+        let c = clock(TimestampMode::Wall);
+        assert_eq!(c.format(1_000_000_000_000), "12:00:00.000");
+        assert_eq!(
+            c.format(1_000_000_000_000 + 3_723_456_789_000),
+            "13:02:03.456"
+        );
+        let east = Clock {
+            utc_offset_s: 2 * 3600,
+            ..c.clone()
+        };
+        assert_eq!(east.format(1_000_000_000_000), "14:00:00.000");
+        let west = Clock {
+            utc_offset_s: -13 * 3600,
+            ..c
+        };
+        assert_eq!(west.format(1_000_000_000_000), "23:00:00.000");
+    }
+
+    #[test]
+    fn test_mono_and_relative() {
+        let ts = 1_000_000_000_000 + 1_234_567_890;
+        assert_eq!(clock(TimestampMode::Mono).format(ts), "1001.234567");
+        assert_eq!(clock(TimestampMode::Relative).format(ts), "+1.234567");
+        assert_eq!(clock(TimestampMode::Relative).format(5), "+0.000000");
+    }
+
+    #[test]
+    fn test_wall_follows_recalibration() {
+        let s = 1_000_000_000;
+        let mut timebase = Timebase::default();
+        for (monotonic_ns, realtime_ns) in [
+            (1000 * s, 1_791_979_200 * s),
+            // NTP stepped the clock back by 5s before the second sample
+            (1060 * s, 1_791_979_255 * s),
+        ] {
+            timebase.push(Sample {
+                monotonic_ns,
+                boottime_ns: monotonic_ns,
+                realtime_ns,
+                width_ns: 0,
+            });
+        }
+        let c = Clock {
+            utc_offset_s: 0,
+            ..Clock::with_timebase(TimestampMode::Wall, timebase, 1000 * s)
+        };
+        assert_eq!(c.format(1030 * s), "12:00:30.000");
+        assert_eq!(c.format(1070 * s), "12:01:05.000");
+        assert_eq!(c.realtime_offset_ns(), 1_791_978_200 * s);
+    }
+
+    #[test]
+    fn test_offset_matches_clocks() {
+        let offset = realtime_offset_ns();
+        let real = clock_ns(libc::CLOCK_REALTIME);
+        let estimate = monotonic_ns() + offset;
+        assert!(real.abs_diff(estimate) < 50_000_000, "{real} vs {estimate}");
+        let c = Clock::live(TimestampMode::Relative);
+        assert!(c.format(monotonic_ns()).starts_with("+0."));
+    }
+}
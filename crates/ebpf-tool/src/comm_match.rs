@@ -0,0 +1,178 @@
+//! `trace --process PATTERN`: names, prefixes and regular expressions.
+//!
+//! The kernel keeps at most 15 bytes of a process name, so `systemd-resolved`
+//! runs as `systemd-resolve`, and one service often runs under several
+//! names (`postgres`, `postgres_exporter` cut to `postgres_export`). A
+//! pattern matches the name as the kernel has it:
+//!
+//! | Pattern | Kind | Matches |
+//! |---------|------|---------|
+//! | `nginx` | [`CommMatch::Exact`] | `nginx` only |
+//! | `postgres.*` | [`CommMatch::Prefix`] | `postgres`, `postgres_export`, ... |
+//! | `php-fpm[0-9]+` | [`CommMatch::Regex`] | the whole name against the regex |
+//!
+//! The kernel checks the literal start of the pattern
+//! ([`CommMatch::kernel_filter`], stored in `FILTER_COMM`) before an event is
+//! sent, and userspace applies the full pattern to what gets through:
+//!
+//! ```text
+//! "php-fpm[0-9]+" ─► FILTER_COMM { "php-fpm", len 7 } ─► kernel drops "nginx"
+//!                 └► Regex ^(?:php-fpm[0-9]+)$       ─► userspace drops "php-fpm-cli"
+//! ```
+
+use anyhow::{bail, Context, Result};
+use ebpf_tool_common::{CommPrefix, COMM_LEN};
+use regex::Regex;
+
+/// Characters with a meaning in a regex; a pattern without them is a name.
+const META: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '[', ']', '{', '}', '^', '$', '|',
+];
+
+/// A parsed `--process` pattern.
+#[derive(Debug, Clone)]
+pub enum CommMatch {
+    /// A plain name, cut to what the kernel keeps
+    Exact(String),
+    /// `NAME.*`: every name starting with NAME
+    Prefix(String),
+    /// Any other pattern, matched against the whole name
+    Regex { regex: Regex, prefix: String },
+}
+
+impl CommMatch {
+    /// Parse `pattern`; fails on an empty pattern or a bad regex.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        if pattern.is_empty() {
+            bail!("empty --process pattern");
+        }
+        if !pattern.contains(META) {
+            return Ok(Self::Exact(truncate(pattern).to_string()));
+        }
+        if let Some(prefix) = pattern.strip_suffix(".*") {
+            if !prefix.is_empty() && !prefix.contains(META) {
+                return Ok(Self::Prefix(truncate(prefix).to_string()));
+            }
+        }
+        let regex = Regex::new(&format!("^(?:{})$", pattern))
+            .with_context(|| format!("invalid --process pattern '{}'", pattern))?;
+        Ok(Self::Regex {
+            regex,
+            prefix: truncate(&literal_prefix(pattern)).to_string(),
+        })
+    }
+
+    /// Whether the process name `comm` (as the kernel has it) matches.
+    pub fn matches(&self, comm: &str) -> bool {
+        match self {
+            Self::Exact(name) => comm == name,
+            Self::Prefix(prefix) => comm.starts_with(prefix.as_str()),
+            Self::Regex { regex, .. } => regex.is_match(comm),
+        }
+    }
+
+    pub fn is_regex(&self) -> bool {
+        matches!(self, Self::Regex { .. })
+    }
+
+    /// What `FILTER_COMM` should hold: a name with its NUL, a prefix
+    /// without, and `len` 0 when a regex has no literal start.
+    pub fn kernel_filter(&self) -> CommPrefix {
+        let (text, nul) = match self {
+            Self::Exact(name) => (name.as_str(), true),
+            Self::Prefix(prefix) => (prefix.as_str(), false),
+            Self::Regex { prefix, .. } => (prefix.as_str(), false),
+        };
+        let mut filter = CommPrefix::new();
+        filter.bytes[..text.len()].copy_from_slice(text.as_bytes());
+        filter.len = (text.len() + usize::from(nul)) as u32;
+        filter
+    }
+}
+
+/// The part of `name` the kernel keeps (`COMM_LEN - 1` bytes), cut at a
+/// character boundary.
+pub fn truncate(name: &str) -> &str {
+    let mut end = name.len().min(COMM_LEN - 1);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    &name[..end]
+}
+
+/// The literal text every match of `pattern` starts with; empty when there
+/// is none (an alternation, a leading group or class, a flag).
+fn literal_prefix(pattern: &str) -> String {
+    if pattern.contains('|') {
+        return String::new();
+    }
+    let mut prefix = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if META.contains(&c) {
+            break;
+        }
+        // `ab?` or `ab*` or `ab{0,2}`: the b is optional
+        if matches!(chars.peek(), Some('?' | '*' | '{')) {
+            break;
+        }
+        prefix.push(c);
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kernel(pattern: &str) -> (String, u32) {
+        let filter = CommMatch::parse(pattern).unwrap().kernel_filter();
+        let text = String::from_utf8_lossy(&filter.bytes[..filter.len as usize]);
+        (text.into_owned(), filter.len)
+    }
+
+    #[test]
+    fn test_parse_kinds() {
+        let exact = CommMatch::parse("nginx").unwrap();
+        assert!(matches!(exact, CommMatch::Exact(ref n) if n == "nginx"));
+        let prefix = CommMatch::parse("postgres.*").unwrap();
+        assert!(matches!(prefix, CommMatch::Prefix(ref p) if p == "postgres"));
+        assert!(CommMatch::parse("php-fpm[0-9]+").unwrap().is_regex());
+        assert!(CommMatch::parse(".*").unwrap().is_regex());
+        assert!(CommMatch::parse("").is_err());
+        let err = CommMatch::parse("bad[").unwrap_err().to_string();
+        assert!(err.contains("invalid --process pattern 'bad['"));
+    }
+
+    #[test]
+    fn test_matches_truncated_names() {
+        let exact = CommMatch::parse("systemd-resolved").unwrap();
+        assert!(exact.matches("systemd-resolve"));
+        assert!(!exact.matches("systemd"));
+
+        let prefix = CommMatch::parse("postgres.*").unwrap();
+        assert!(prefix.matches("postgres"));
+        assert!(prefix.matches("postgres_export"));
+        assert!(!prefix.matches("pg_ctl"));
+
+        let regex = CommMatch::parse("php-fpm[0-9]+").unwrap();
+        assert!(regex.matches("php-fpm8"));
+        assert!(!regex.matches("php-fpm-cli"));
+        assert!(!regex.matches("xphp-fpm8"));
+    }
+
+    #[test]
+    fn test_kernel_filter() {
+        // A name includes its NUL: `sh` must not let `sshd` through
+        assert_eq!(kernel("sh"), ("sh\0".to_string(), 3));
+        assert_eq!(
+            kernel("systemd-resolved"),
+            ("systemd-resolve\0".to_string(), 16)
+        );
+        assert_eq!(kernel("postgres.*"), ("postgres".to_string(), 8));
+        assert_eq!(kernel("php-fpm[0-9]+"), ("php-fpm".to_string(), 7));
+        assert_eq!(kernel("colou?r"), ("colo".to_string(), 4));
+        assert_eq!(kernel("(?i)nginx"), (String::new(), 0));
+        assert_eq!(kernel("nginx|httpd"), (String::new(), 0));
+    }
+}
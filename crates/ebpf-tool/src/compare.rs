@@ -0,0 +1,623 @@
+//! `ebpf-tool compare`: before/after comparison of two run reports.
+//!
+//! A performance change is only a result once it is measured twice: before
+//! and after, with the same command. `compare` lines up the two reports
+//! row by row and flags what moved by more than a threshold:
+//!
+//! ```text
+//! SECTION  NAME            BEFORE     AFTER   CHANGE
+//! latency  request p99     12.4ms    18.1ms   +46.0%  REGRESSION
+//! top      openat         120.0/s   240.0/s  +100.0%  REGRESSION
+//! stacks   ...;parse;lex     12.0%     30.0%  +150.0%  REGRESSION
+//! top      read            90.0/s    91.0/s    +1.1%
+//! ```
+//!
+//! Every section is "higher is worse": more calls per second, slower
+//! percentiles, a larger share of the CPU samples. Counts are compared as
+//! rates, so runs of different lengths still compare. Stacks are compared
+//! by their share of all stack samples, because the sample count follows
+//! the run's length and frequency.
+
+use anyhow::{bail, Result};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+
+use crate::report::{self, RunReport, TopEntry};
+
+/// When a change counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    /// Relative change, in percent, above which a row is flagged
+    pub percent: f64,
+    /// Rows with fewer samples than this on both sides are noise
+    pub min_count: u64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            percent: 10.0,
+            min_count: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    Latency,
+    Top,
+    Stacks,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Section::Latency => "latency",
+            Section::Top => "top",
+            Section::Stacks => "stacks",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    PerSecond,
+    Nanos,
+    Share,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    Regression,
+    Improvement,
+    /// Only in the second run
+    New,
+    /// Only in the first run
+    Gone,
+    Unchanged,
+    /// Too few samples on both sides to say anything
+    Noise,
+}
+
+/// One compared value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub section: Section,
+    pub label: String,
+    pub unit: Unit,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+    pub verdict: Verdict,
+}
+
+impl Row {
+    /// Relative change in percent, when both sides have a value.
+    pub fn change(&self) -> Option<f64> {
+        match (self.before, self.after) {
+            (Some(b), Some(a)) if b > 0.0 => Some((a - b) / b * 100.0),
+            (Some(_), Some(a)) => (a == 0.0).then_some(0.0),
+            _ => None,
+        }
+    }
+}
+
+/// The result of comparing two reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub before: String,
+    pub after: String,
+    /// Regressions first, biggest change first; Noise last
+    pub rows: Vec<Row>,
+    /// Reasons to distrust the numbers (drops, different arguments, ...)
+    pub warnings: Vec<String>,
+    pub thresholds: Thresholds,
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| r.verdict == Verdict::Regression)
+            .count()
+    }
+
+    /// The table, the warnings and a one-line verdict. Noise rows only
+    /// with `all`.
+    pub fn render(&self, all: bool) -> String {
+        let mut out = format!("before: {}\nafter:  {}\n", self.before, self.after);
+        for warning in &self.warnings {
+            out.push_str(&format!("warning: {warning}\n"));
+        }
+
+        if self.rows.is_empty() {
+            out.push_str("\nNothing to compare: neither report has top, latency or stack rows\n");
+            return out;
+        }
+        let shown: Vec<&Row> = self
+            .rows
+            .iter()
+            .filter(|r| all || r.verdict != Verdict::Noise)
+            .collect();
+        let shown_count = shown.len();
+        // All noise: no table, but the summary still says what is hidden
+        if !shown.is_empty() {
+            out.push_str(&format!(
+                "\n{:<8} {:<32} {:>10} {:>10} {:>9}\n",
+                "SECTION", "NAME", "BEFORE", "AFTER", "CHANGE"
+            ));
+        }
+        for row in shown {
+            let change = match row.change() {
+                Some(c) => format!("{c:+.1}%"),
+                None => "-".to_string(),
+            };
+            let flag = match row.verdict {
+                Verdict::Regression => "  REGRESSION",
+                Verdict::Improvement => "  improved",
+                Verdict::New => "  new",
+                Verdict::Gone => "  gone",
+                Verdict::Noise => "  (noise)",
+                Verdict::Unchanged => "",
+            };
+            out.push_str(&format!(
+                "{:<8} {:<32} {:>10} {:>10} {:>9}{}\n",
+                row.section.to_string(),
+                shorten(&row.label, 32),
+                format_value(row.before, row.unit),
+                format_value(row.after, row.unit),
+                change,
+                flag
+            ));
+        }
+
+        let hidden = self.rows.len() - shown_count;
+        out.push_str(&format!(
+            "\n{} regression(s) above {}%",
+            self.regressions(),
+            self.thresholds.percent
+        ));
+        if hidden > 0 {
+            out.push_str(&format!(
+                " ({hidden} row(s) below {} samples hidden; --all shows them)",
+                self.thresholds.min_count
+            ));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Load a report given as a file path, or as a run id, id prefix or "last"
+/// from `runs_dir()`.
+pub fn load(spec: &str) -> Result<RunReport> {
+    let path = Path::new(spec);
+    if path.is_file() {
+        return RunReport::load(path);
+    }
+    report::find(&report::runs_dir()?, spec)
+}
+
+/// Compare `after` against `before`.
+pub fn compare(
+    before: &RunReport,
+    after: &RunReport,
+    thresholds: Thresholds,
+) -> Result<Comparison> {
+    if before.command != after.command {
+        bail!(
+            "cannot compare a {} run with a {} run",
+            before.command,
+            after.command
+        );
+    }
+
+    let mut warnings = Vec::new();
+    for r in [before, after] {
+        if !r.success {
+            warnings.push(format!("{} failed: its numbers cover a partial run", r.id));
+        }
+        if r.drops > 0 {
+            warnings.push(format!(
+                "{} lost {} events: its counts are lower bounds",
+                r.id, r.drops
+            ));
+        }
+        if r.duration_ms == 0 && !r.top.is_empty() {
+            warnings.push(format!(
+                "{} has no duration: its counts are compared as totals",
+                r.id
+            ));
+        }
+    }
+    if before.sample != after.sample {
+        let rate = |r: &RunReport| {
+            r.sample
+                .map_or("every event".to_string(), |n| format!("1/{n}"))
+        };
+        warnings.push(format!(
+            "the runs were sampled differently ({} vs {}): counts near the threshold may be noise",
+            rate(before),
+            rate(after)
+        ));
+    }
+    if before.args.get(1..) != after.args.get(1..) {
+        warnings.push(format!(
+            "the runs used different arguments: [{}] vs [{}]",
+            before.args.get(1..).unwrap_or_default().join(" "),
+            after.args.get(1..).unwrap_or_default().join(" ")
+        ));
+    }
+
+    let mut rows = Vec::new();
+
+    // Latency: each percentile of each label is a row
+    for label in labels(&before.latency, &after.latency, |l| &l.label) {
+        let b = before.latency.iter().find(|l| l.label == label);
+        let a = after.latency.iter().find(|l| l.label == label);
+        let samples = (b.map_or(0, |l| l.count), a.map_or(0, |l| l.count));
+        for (name, get) in PERCENTILES {
+            rows.push(row(
+                Section::Latency,
+                format!("{label} {name}"),
+                Unit::Nanos,
+                b.map(|l| get(l) as f64),
+                a.map(|l| get(l) as f64),
+                samples,
+                &thresholds,
+            ));
+        }
+    }
+
+    // Top rows: rates
+    for label in labels(&before.top, &after.top, |t| &t.label) {
+        let b = find_count(&before.top, &label);
+        let a = find_count(&after.top, &label);
+        rows.push(row(
+            Section::Top,
+            label,
+            Unit::PerSecond,
+            b.map(|c| rate(c, before.duration_ms)),
+            a.map(|c| rate(c, after.duration_ms)),
+            (b.unwrap_or(0), a.unwrap_or(0)),
+            &thresholds,
+        ));
+    }
+
+    // Stacks: share of all stack samples
+    let totals = (total(&before.stacks), total(&after.stacks));
+    for label in labels(&before.stacks, &after.stacks, |t| &t.label) {
+        let b = find_count(&before.stacks, &label);
+        let a = find_count(&after.stacks, &label);
+        rows.push(row(
+            Section::Stacks,
+            label,
+            Unit::Share,
+            b.map(|c| share(c, totals.0)),
+            a.map(|c| share(c, totals.1)),
+            (b.unwrap_or(0), a.unwrap_or(0)),
+            &thresholds,
+        ));
+    }
+
+    rows.sort_by(|x, y| {
+        let bigger = |r: &Row| -r.change().unwrap_or(0.0).abs();
+        (x.verdict, x.section)
+            .cmp(&(y.verdict, y.section))
+            .then(bigger(x).total_cmp(&bigger(y)))
+    });
+
+    Ok(Comparison {
+        before: describe(before),
+        after: describe(after),
+        rows,
+        warnings,
+        thresholds,
+    })
+}
+
+type Percentile = (&'static str, fn(&report::LatencyRecord) -> u64);
+
+const PERCENTILES: [Percentile; 4] = [
+    ("p50", |l| l.p50_ns),
+    ("p90", |l| l.p90_ns),
+    ("p99", |l| l.p99_ns),
+    ("max", |l| l.max_ns),
+];
+
+fn row(
+    section: Section,
+    label: String,
+    unit: Unit,
+    before: Option<f64>,
+    after: Option<f64>,
+    samples: (u64, u64),
+    thresholds: &Thresholds,
+) -> Row {
+    let verdict = if samples.0 < thresholds.min_count && samples.1 < thresholds.min_count {
+        Verdict::Noise
+    } else {
+        match (before, after) {
+            (None, _) => Verdict::New,
+            (_, None) => Verdict::Gone,
+            _ => Verdict::Unchanged,
+        }
+    };
+    let mut row = Row {
+        section,
+        label,
+        unit,
+        before,
+        after,
+        verdict,
+    };
+    if row.verdict == Verdict::Unchanged {
+        match row.change() {
+            Some(c) if c > thresholds.percent => row.verdict = Verdict::Regression,
+            Some(c) if c < -thresholds.percent => row.verdict = Verdict::Improvement,
+            _ => {}
+        }
+    }
+    row
+}
+
+/// Labels of both lists, each once, in sorted order.
+fn labels<T>(a: &[T], b: &[T], label: impl Fn(&T) -> &String) -> BTreeSet<String> {
+    a.iter().chain(b).map(|t| label(t).clone()).collect()
+}
+
+fn find_count(entries: &[TopEntry], label: &str) -> Option<u64> {
+    entries.iter().find(|t| t.label == label).map(|t| t.count)
+}
+
+fn total(entries: &[TopEntry]) -> u64 {
+    entries.iter().map(|t| t.count).sum()
+}
+
+/// Count per second, or the count itself for a run without a duration.
+fn rate(count: u64, duration_ms: u64) -> f64 {
+    if duration_ms == 0 {
+        count as f64
+    } else {
+        count as f64 * 1000.0 / duration_ms as f64
+    }
+}
+
+fn share(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+fn describe(r: &RunReport) -> String {
+    format!(
+        "{} ({:.1}s, {} events)",
+        r.id,
+        r.duration_ms as f64 / 1000.0,
+        r.events
+    )
+}
+
+fn format_value(value: Option<f64>, unit: Unit) -> String {
+    let Some(v) = value else {
+        return "-".to_string();
+    };
+    match unit {
+        Unit::PerSecond => format!("{v:.1}/s"),
+        Unit::Share => format!("{v:.1}%"),
+        Unit::Nanos => format_ns(v as u64),
+    }
+}
+
+/// "850ns", "412us", "1.8ms", "2.50s": the units of the reqlat output.
+fn format_ns(ns: u64) -> String {
+    match ns {
+        0..=999 => format!("{ns}ns"),
+        1_000..=999_999 => format!("{}us", ns / 1_000),
+        1_000_000..=999_999_999 => format!("{:.1}ms", ns as f64 / 1e6),
+        _ => format!("{:.2}s", ns as f64 / 1e9),
+    }
+}
+
+/// Keep the end of a long label: for a folded stack, the leaf frames are
+/// the interesting part.
+fn shorten(label: &str, width: usize) -> String {
+    let chars: Vec<char> = label.chars().collect();
+    if chars.len() <= width {
+        return label.to_string();
+    }
+    let tail: String = chars[chars.len() - (width - 3)..].iter().collect();
+    format!("...{tail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::LatencyRecord;
+
+    fn run(id: &str, duration_ms: u64, top: &[(&str, u64)]) -> RunReport {
+        let mut report = RunReport::default();
+        report.id = id.to_string();
+        report.command = "trace".to_string();
+        report.args = vec!["ebpf-tool".into(), "trace".into()];
+        report.duration_ms = duration_ms;
+        report.top = top
+            .iter()
+            .map(|&(label, count)| TopEntry {
+                label: label.into(),
+                count,
+            })
+            .collect();
+        report.success = true;
+        report
+    }
+
+    fn find<'a>(c: &'a Comparison, label: &str) -> &'a Row {
+        c.rows.iter().find(|r| r.label == label).unwrap()
+    }
+
+    #[test]
+    fn test_counts_compare_as_rates() {
+        // Twice the calls in twice the time: unchanged
+        let before = run("a", 1000, &[("read", 100), ("openat", 100)]);
+        let after = run("b", 2000, &[("read", 200), ("openat", 400)]);
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+
+        assert_eq!(find(&c, "read").verdict, Verdict::Unchanged);
+        assert_eq!(find(&c, "openat").verdict, Verdict::Regression);
+        assert_eq!(find(&c, "openat").change(), Some(100.0));
+        assert_eq!(c.regressions(), 1);
+        assert!(c.warnings.is_empty());
+        // Regressions sort first
+        assert_eq!(c.rows[0].label, "openat");
+    }
+
+    #[test]
+    fn test_threshold_and_noise() {
+        let before = run("a", 1000, &[("read", 100), ("close", 2), ("write", 100)]);
+        let after = run("b", 1000, &[("read", 109), ("close", 8), ("write", 80)]);
+        let strict = Thresholds {
+            percent: 5.0,
+            min_count: 10,
+        };
+
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+        assert_eq!(find(&c, "read").verdict, Verdict::Unchanged);
+        assert_eq!(find(&c, "write").verdict, Verdict::Improvement);
+        // +300%, but from 2 to 8 calls
+        assert_eq!(find(&c, "close").verdict, Verdict::Noise);
+
+        let c = compare(&before, &after, strict).unwrap();
+        assert_eq!(find(&c, "read").verdict, Verdict::Regression);
+    }
+
+    #[test]
+    fn test_render_all_noise() {
+        let before = run("a", 1000, &[("close", 2), ("dup", 3)]);
+        let after = run("b", 1000, &[("close", 8), ("dup", 1)]);
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+        assert!(c.rows.iter().all(|r| r.verdict == Verdict::Noise));
+
+        let text = c.render(false);
+        assert!(!text.contains("Nothing to compare"), "{text}");
+        assert!(!text.contains("SECTION"), "{text}");
+        assert!(text.contains("0 regression(s)"), "{text}");
+        assert!(text.contains("(2 row(s) below 10 samples hidden; --all shows them)"));
+        assert!(c.render(true).contains("(noise)"));
+
+        let empty = compare(
+            &run("a", 1000, &[]),
+            &run("b", 1000, &[]),
+            Thresholds::default(),
+        );
+        assert!(empty.unwrap().render(false).contains("Nothing to compare"));
+    }
+
+    #[test]
+    fn test_new_and_gone_rows() {
+        let before = run("a", 1000, &[("read", 100)]);
+        let after = run("b", 1000, &[("futex", 500)]);
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+
+        assert_eq!(find(&c, "futex").verdict, Verdict::New);
+        assert_eq!(find(&c, "read").verdict, Verdict::Gone);
+        assert_eq!(c.regressions(), 0);
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let latency = |p99_ns| LatencyRecord {
+            label: "request".into(),
+            count: 1000,
+            p50_ns: 400_000,
+            p90_ns: 1_800_000,
+            p99_ns,
+            max_ns: 31_000_000,
+        };
+        let mut before = run("a", 1000, &[]);
+        let mut after = run("b", 1000, &[]);
+        before.latency.push(latency(12_400_000));
+        after.latency.push(latency(18_100_000));
+
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+        assert_eq!(find(&c, "request p99").verdict, Verdict::Regression);
+        assert_eq!(find(&c, "request p50").verdict, Verdict::Unchanged);
+        assert_eq!(c.rows.len(), 4);
+        assert!(c.render(false).contains("12.4ms"));
+    }
+
+    #[test]
+    fn test_stacks_compare_as_shares() {
+        let stacks = |hot, rest| {
+            vec![
+                TopEntry {
+                    label: "main;parse;lex".into(),
+                    count: hot,
+                },
+                TopEntry {
+                    label: "main;io".into(),
+                    count: rest,
+                },
+            ]
+        };
+        let mut before = run("a", 1000, &[]);
+        let mut after = run("b", 1000, &[]);
+        before.stacks = stacks(100, 900); // 10%
+        after.stacks = stacks(600, 1400); // 30%, at twice the sample count
+
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+        let hot = find(&c, "main;parse;lex");
+        assert_eq!(hot.verdict, Verdict::Regression);
+        assert_eq!((hot.before, hot.after), (Some(10.0), Some(30.0)));
+        assert_eq!(find(&c, "main;io").verdict, Verdict::Improvement);
+    }
+
+    #[test]
+    fn test_warnings_and_mismatched_commands() {
+        let before = run("a", 1000, &[("read", 100)]);
+        let mut after = run("b", 1000, &[("read", 100)]);
+        after.drops = 42;
+        after.args.push("-p".into());
+        after.args.push("cat".into());
+
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+        assert_eq!(c.warnings.len(), 2);
+        assert!(c.warnings[0].contains("lost 42 events"));
+        assert!(c.warnings[1].contains("[trace] vs [trace -p cat]"));
+
+        after.sample = Some(100);
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+        assert!(c.warnings[1].contains("sampled differently (every event vs 1/100)"));
+
+        after.command = "stats".into();
+        assert!(compare(&before, &after, Thresholds::default()).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let before = run("a", 1000, &[("read", 100), ("close", 1)]);
+        let after = run("b", 1000, &[("read", 250), ("close", 1)]);
+        let c = compare(&before, &after, Thresholds::default()).unwrap();
+
+        let text = c.render(false);
+        assert!(text.contains("before: a (1.0s, 0 events)"));
+        assert!(text.contains("100.0/s"));
+        assert!(text.contains("+150.0%  REGRESSION"));
+        assert!(!text.contains("close"));
+        assert!(text.contains("1 regression(s) above 10%"));
+        assert!(text.contains("1 row(s) below 10 samples hidden"));
+        assert!(c.render(true).contains("close"));
+    }
+
+    #[test]
+    fn test_format_helpers() {
+        assert_eq!(format_ns(850), "850ns");
+        assert_eq!(format_ns(412_000), "412us");
+        assert_eq!(format_ns(1_800_000), "1.8ms");
+        assert_eq!(format_ns(2_500_000_000), "2.50s");
+        assert_eq!(shorten("short", 10), "short");
+        assert_eq!(shorten("main;parse;lex;next", 10), "...ex;next");
+    }
+}
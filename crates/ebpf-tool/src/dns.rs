@@ -0,0 +1,502 @@
+//! `ebpf-tool dns`: decoding what the DNS programs capture.
+//!
+//! The libc uprobes send a finished `DnsEvent`; all that is left here is
+//! a name for its return code ([`eai_name`]). The socket filter passes
+//! whole Ethernet frames, and the DNS message inside is decoded here, not
+//! in the kernel. Names are variable length, can be compressed, and a BPF
+//! program that followed compression pointers would need a loop the
+//! verifier can bound. Userspace has none of those problems, and the
+//! parser can be tested without root:
+//!
+//! ```text
+//! frame ─► parse_frame()  Ethernet ─► IPv4/IPv6 ─► UDP ─► Packet { addrs, ports, payload }
+//!                                                          │
+//!          parse_message(payload) ─► Message { id, qr, rcode, question, answers }
+//!                                                          │
+//!          Pending::query() / response() ─► latency per (client port, id)
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+/// UDP port of DNS servers.
+pub const DNS_PORT: u16 = 53;
+
+/// Queries without a response are dropped from [`Pending`] after this.
+/// The glibc resolver gives up on a server after 5 s (`timeout:5`).
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest name in presentation form (RFC 1035 section 2.3.4).
+const MAX_NAME_LEN: usize = 253;
+
+/// The UDP part of a captured frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet<'a> {
+    pub src: IpAddr,
+    pub dst: IpAddr,
+    pub sport: u16,
+    pub dport: u16,
+    pub payload: &'a [u8],
+}
+
+impl Packet<'_> {
+    /// The client's side of the exchange: the port that is not 53. Queries
+    /// and their responses have the same key.
+    pub fn client(&self) -> (IpAddr, u16) {
+        if self.dport == DNS_PORT {
+            (self.src, self.sport)
+        } else {
+            (self.dst, self.dport)
+        }
+    }
+}
+
+/// Decode an Ethernet frame (as an `AF_PACKET` socket delivers it) down to
+/// its UDP payload. None for anything that is not UDP over IPv4 or IPv6.
+pub fn parse_frame(frame: &[u8]) -> Option<Packet<'_>> {
+    let mut ethertype = be16(frame, 12)?;
+    let mut offset = 14;
+    if ethertype == 0x8100 {
+        // One 802.1Q VLAN tag
+        ethertype = be16(frame, 16)?;
+        offset = 18;
+    }
+    let ip = frame.get(offset..)?;
+
+    let (src, dst, udp) = match ethertype {
+        0x0800 => {
+            let ihl = (*ip.first()? & 0x0f) as usize * 4;
+            if ihl < 20 || *ip.get(9)? != 17 {
+                return None;
+            }
+            let src = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(12..16)?).ok()?);
+            let dst = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(16..20)?).ok()?);
+            (IpAddr::V4(src), IpAddr::V4(dst), ip.get(ihl..)?)
+        }
+        0x86dd => {
+            if *ip.get(6)? != 17 {
+                return None;
+            }
+            let src = Ipv6Addr::from(<[u8; 16]>::try_from(ip.get(8..24)?).ok()?);
+            let dst = Ipv6Addr::from(<[u8; 16]>::try_from(ip.get(24..40)?).ok()?);
+            (IpAddr::V6(src), IpAddr::V6(dst), ip.get(40..)?)
+        }
+        _ => return None,
+    };
+
+    // The UDP length bounds the payload: short frames are padded to 60
+    // bytes on the wire
+    let len = (be16(udp, 4)? as usize).clamp(8, udp.len());
+    Some(Packet {
+        src,
+        dst,
+        sport: be16(udp, 0)?,
+        dport: be16(udp, 2)?,
+        payload: &udp[8..len],
+    })
+}
+
+/// Why a payload could not be decoded as a DNS message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsError {
+    /// Shorter than the 12-byte header, or a name or question cut off
+    Truncated,
+    /// A compression pointer that points forward or loops
+    BadPointer,
+    /// A label longer than 63 bytes, or a name longer than 253
+    BadName,
+    /// No question: nothing to show
+    NoQuestion,
+}
+
+impl fmt::Display for DnsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            DnsError::Truncated => "message cut off",
+            DnsError::BadPointer => "bad compression pointer",
+            DnsError::BadName => "bad name",
+            DnsError::NoQuestion => "no question",
+        };
+        f.write_str(text)
+    }
+}
+
+impl std::error::Error for DnsError {}
+
+/// The parts of a DNS message `dns` prints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub id: u16,
+    /// QR bit: a response, not a query
+    pub response: bool,
+    /// Response code (low 4 bits of the flags); 0 in queries
+    pub rcode: u8,
+    /// First question's name, without the trailing dot ("" for the root)
+    pub name: String,
+    /// First question's type (1 A, 28 AAAA, ...)
+    pub qtype: u16,
+    /// Number of answer records
+    pub answers: u16,
+}
+
+/// Decode the header and the first question of a DNS message (RFC 1035
+/// section 4.1). Answer records are only counted.
+pub fn parse_message(payload: &[u8]) -> Result<Message, DnsError> {
+    let header = payload.get(..12).ok_or(DnsError::Truncated)?;
+    let flags = u16::from_be_bytes([header[2], header[3]]);
+    if u16::from_be_bytes([header[4], header[5]]) == 0 {
+        return Err(DnsError::NoQuestion);
+    }
+    let (name, end) = read_name(payload, 12)?;
+    let qtype = be16(payload, end).ok_or(DnsError::Truncated)?;
+    // QCLASS follows; it is IN (1) in practice and not shown
+    be16(payload, end + 2).ok_or(DnsError::Truncated)?;
+
+    Ok(Message {
+        id: u16::from_be_bytes([header[0], header[1]]),
+        response: flags & 0x8000 != 0,
+        rcode: (flags & 0x000f) as u8,
+        name,
+        qtype,
+        answers: u16::from_be_bytes([header[6], header[7]]),
+    })
+}
+
+/// Read the name at `offset`, following compression pointers. Returns the
+/// name and the offset just past it in the original position.
+fn read_name(msg: &[u8], offset: usize) -> Result<(String, usize), DnsError> {
+    let mut name = String::new();
+    let mut pos = offset;
+    let mut end = None;
+    // Every pointer must point backwards: at most one jump per earlier
+    // position, so the loop ends even on hostile input
+    let mut limit = offset;
+
+    loop {
+        let len = *msg.get(pos).ok_or(DnsError::Truncated)? as usize;
+        match len {
+            0 => break,
+            l if l & 0xc0 == 0xc0 => {
+                let low = *msg.get(pos + 1).ok_or(DnsError::Truncated)? as usize;
+                let target = ((l & 0x3f) << 8) | low;
+                if target >= limit {
+                    return Err(DnsError::BadPointer);
+                }
+                end.get_or_insert(pos + 2);
+                limit = target;
+                pos = target;
+            }
+            l if l > 63 => return Err(DnsError::BadName),
+            l => {
+                let label = msg.get(pos + 1..pos + 1 + l).ok_or(DnsError::Truncated)?;
+                if !name.is_empty() {
+                    name.push('.');
+                }
+                // Labels are bytes; show anything unusual escaped
+                name.extend(
+                    label
+                        .iter()
+                        .flat_map(|&b| std::ascii::escape_default(b))
+                        .map(char::from),
+                );
+                if name.len() > MAX_NAME_LEN {
+                    return Err(DnsError::BadName);
+                }
+                pos += 1 + l;
+            }
+        }
+    }
+    Ok((name, end.unwrap_or(pos + 1)))
+}
+
+fn be16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([b[0], b[1]]))
+}
+
+/// Name of a response code, e.g. "NXDOMAIN".
+pub fn rcode_name(rcode: u8) -> String {
+    match rcode {
+        0 => "NOERROR".to_string(),
+        1 => "FORMERR".to_string(),
+        2 => "SERVFAIL".to_string(),
+        3 => "NXDOMAIN".to_string(),
+        4 => "NOTIMP".to_string(),
+        5 => "REFUSED".to_string(),
+        n => format!("RCODE{n}"),
+    }
+}
+
+/// Name of a query type, e.g. "AAAA".
+pub fn qtype_name(qtype: u16) -> String {
+    match qtype {
+        1 => "A".to_string(),
+        2 => "NS".to_string(),
+        5 => "CNAME".to_string(),
+        6 => "SOA".to_string(),
+        12 => "PTR".to_string(),
+        15 => "MX".to_string(),
+        16 => "TXT".to_string(),
+        28 => "AAAA".to_string(),
+        33 => "SRV".to_string(),
+        64 => "SVCB".to_string(),
+        65 => "HTTPS".to_string(),
+        255 => "ANY".to_string(),
+        n => format!("TYPE{n}"),
+    }
+}
+
+/// Name of a `getaddrinfo()` return value, e.g. "EAI_NONAME". The values
+/// are glibc's (`<netdb.h>`); musl uses the same numbers.
+pub fn eai_name(ret: i32) -> String {
+    match ret {
+        0 => "0".to_string(),
+        -1 => "EAI_BADFLAGS".to_string(),
+        -2 => "EAI_NONAME".to_string(),
+        -3 => "EAI_AGAIN".to_string(),
+        -4 => "EAI_FAIL".to_string(),
+        -5 => "EAI_NODATA".to_string(),
+        -6 => "EAI_FAMILY".to_string(),
+        -7 => "EAI_SOCKTYPE".to_string(),
+        -8 => "EAI_SERVICE".to_string(),
+        -9 => "EAI_ADDRFAMILY".to_string(),
+        -10 => "EAI_MEMORY".to_string(),
+        -11 => "EAI_SYSTEM".to_string(),
+        n => format!("EAI_{n}"),
+    }
+}
+
+/// Key of a query: the client's address and port, and the message id. A
+/// resolver uses a fresh port and id per query, so this is unique among
+/// the queries in flight.
+pub type QueryKey = (IpAddr, u16, u16);
+
+#[derive(Debug)]
+struct Query {
+    sent: Duration,
+    name: String,
+}
+
+/// Queries waiting for a response, to time the response.
+///
+/// Times are whatever clock the caller uses for packets, as a `Duration`
+/// since some start.
+#[derive(Debug, Default)]
+pub struct Pending {
+    queries: HashMap<QueryKey, Query>,
+}
+
+impl Pending {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember a query sent at `at`. A retransmission keeps the first time.
+    pub fn query(&mut self, key: QueryKey, name: &str, at: Duration) {
+        self.queries.entry(key).or_insert_with(|| Query {
+            sent: at,
+            name: name.to_string(),
+        });
+    }
+
+    /// A response arrived at `at`: the time since its query, or None if the
+    /// query was not seen (sent before the capture started) or the name
+    /// differs (an id collision, or a spoofed response).
+    pub fn response(&mut self, key: QueryKey, name: &str, at: Duration) -> Option<Duration> {
+        let query = self.queries.remove(&key)?;
+        if !query.name.eq_ignore_ascii_case(name) {
+            return None;
+        }
+        Some(at.saturating_sub(query.sent))
+    }
+
+    /// Drop queries older than [`QUERY_TIMEOUT`] at `now`, and return their
+    /// names: lookups that got no answer.
+    pub fn expire(&mut self, now: Duration) -> Vec<String> {
+        let mut expired = Vec::new();
+        self.queries.retain(|_, q| {
+            let keep = now.saturating_sub(q.sent) < QUERY_TIMEOUT;
+            if !keep {
+                expired.push(std::mem::take(&mut q.name));
+            }
+            keep
+        });
+        expired.sort();
+        expired
+    }
+
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Query for example.com A, id 0x1a2b
+    const QUERY: &[u8] = &[
+        0x1a, 0x2b, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0, // header: RD, 1 question
+        7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // name
+        0, 1, 0, 1, // A, IN
+    ];
+
+    fn response(rcode: u8, answers: u16) -> Vec<u8> {
+        let mut msg = QUERY.to_vec();
+        msg[2] = 0x81;
+        msg[3] = 0x80 | rcode;
+        msg[6..8].copy_from_slice(&answers.to_be_bytes());
+        for _ in 0..answers {
+            // Answer name: a pointer to the question's name at offset 12
+            msg.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+        }
+        msg
+    }
+
+    fn frame_v4(sport: u16, dport: u16, payload: &[u8]) -> Vec<u8> {
+        let mut f = vec![0u8; 12];
+        f.extend_from_slice(&[0x08, 0x00]);
+        let total = (20 + 8 + payload.len()) as u16;
+        f.extend_from_slice(&[0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17, 0, 0]);
+        f[16..18].copy_from_slice(&total.to_be_bytes());
+        f.extend_from_slice(&[10, 0, 2, 15, 10, 0, 2, 3]);
+        f.extend_from_slice(&sport.to_be_bytes());
+        f.extend_from_slice(&dport.to_be_bytes());
+        f.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        f.extend_from_slice(&[0, 0]);
+        f.extend_from_slice(payload);
+        f
+    }
+
+    #[test]
+    fn test_parse_query_and_response() {
+        let q = parse_message(QUERY).unwrap();
+        assert_eq!(q.id, 0x1a2b);
+        assert!(!q.response);
+        assert_eq!(q.name, "example.com");
+        assert_eq!(qtype_name(q.qtype), "A");
+
+        let r = parse_message(&response(3, 0)).unwrap();
+        assert!(r.response);
+        assert_eq!(rcode_name(r.rcode), "NXDOMAIN");
+        assert_eq!(r.answers, 0);
+
+        let r = parse_message(&response(0, 2)).unwrap();
+        assert_eq!((r.rcode, r.answers), (0, 2));
+        assert_eq!(r.name, "example.com");
+    }
+
+    #[test]
+    fn test_compression_pointers() {
+        // A question whose name is "www" + a pointer to "example.com" in an
+        // earlier question
+        let mut msg = QUERY.to_vec();
+        msg[5] = 2;
+        msg.extend_from_slice(&[3, b'w', b'w', b'w', 0xc0, 12, 0, 28, 0, 1]);
+        assert_eq!(read_name(&msg, 29), Ok(("www.example.com".to_string(), 35)));
+
+        // Pointing at itself, or forward, would loop
+        let mut looped = QUERY[..12].to_vec();
+        looped.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1]);
+        assert_eq!(parse_message(&looped), Err(DnsError::BadPointer));
+    }
+
+    #[test]
+    fn test_malformed_messages() {
+        assert_eq!(parse_message(&QUERY[..8]), Err(DnsError::Truncated));
+        assert_eq!(parse_message(&QUERY[..20]), Err(DnsError::Truncated));
+        let mut none = QUERY.to_vec();
+        none[5] = 0;
+        assert_eq!(parse_message(&none), Err(DnsError::NoQuestion));
+        let mut long = QUERY[..12].to_vec();
+        long.push(64);
+        long.extend_from_slice(&[b'a'; 64]);
+        assert_eq!(parse_message(&long), Err(DnsError::BadName));
+
+        let mut root = QUERY[..12].to_vec();
+        root.extend_from_slice(&[0, 0, 2, 0, 1]);
+        assert_eq!(parse_message(&root).unwrap().name, "");
+    }
+
+    #[test]
+    fn test_parse_frame() {
+        let mut frame = frame_v4(51234, 53, QUERY);
+        // Ethernet padding after the datagram is not payload
+        frame.extend_from_slice(&[0; 6]);
+        let p = parse_frame(&frame).unwrap();
+        assert_eq!(p.src, IpAddr::V4(Ipv4Addr::new(10, 0, 2, 15)));
+        assert_eq!((p.sport, p.dport), (51234, 53));
+        assert_eq!(p.payload, QUERY);
+        assert_eq!(p.client(), (p.src, 51234));
+
+        // The response has the same client key
+        let r = frame_v4(53, 51234, QUERY);
+        let r = parse_frame(&r).unwrap();
+        assert_eq!(r.client(), (IpAddr::V4(Ipv4Addr::new(10, 0, 2, 3)), 51234));
+
+        // TCP, ARP and runts are not packets
+        let mut tcp = frame_v4(1, 53, QUERY);
+        tcp[23] = 6;
+        assert_eq!(parse_frame(&tcp), None);
+        assert_eq!(parse_frame(&[0u8; 12]), None);
+        let mut arp = frame.clone();
+        arp[12..14].copy_from_slice(&[0x08, 0x06]);
+        assert_eq!(parse_frame(&arp), None);
+    }
+
+    #[test]
+    fn test_parse_frame_ipv6_and_vlan() {
+        let mut f = vec![0u8; 12];
+        f.extend_from_slice(&[0x81, 0x00, 0, 5, 0x86, 0xdd]);
+        let mut ip = vec![0x60, 0, 0, 0, 0, 0, 17, 64];
+        ip.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        ip.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        f.extend_from_slice(&ip);
+        f.extend_from_slice(&[0xc8, 0x00, 0, 53, 0, 8 + QUERY.len() as u8, 0, 0]);
+        f.extend_from_slice(QUERY);
+
+        let p = parse_frame(&f).unwrap();
+        assert_eq!(p.dst, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!((p.sport, p.dport), (51200, 53));
+        assert_eq!(parse_message(p.payload).unwrap().name, "example.com");
+    }
+
+    #[test]
+    fn test_pending_times_responses() {
+        let client = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let ms = Duration::from_millis;
+        let mut pending = Pending::new();
+        pending.query((client, 40000, 1), "example.com", ms(100));
+        // A retransmission keeps the first send time
+        pending.query((client, 40000, 1), "example.com", ms(150));
+        pending.query((client, 40001, 2), "slow.example", ms(100));
+
+        assert_eq!(
+            pending.response((client, 40000, 1), "EXAMPLE.com", ms(112)),
+            Some(ms(12))
+        );
+        assert_eq!(
+            pending.response((client, 40000, 1), "example.com", ms(113)),
+            None
+        );
+        assert_eq!(pending.len(), 1);
+
+        assert!(pending.expire(ms(4000)).is_empty());
+        assert_eq!(pending.expire(ms(5100)), ["slow.example"]);
+        assert_eq!(pending.len(), 0);
+    }
+
+    #[test]
+    fn test_code_names() {
+        assert_eq!(eai_name(0), "0");
+        assert_eq!(eai_name(-2), "EAI_NONAME");
+        assert_eq!(eai_name(-3), "EAI_AGAIN");
+        assert_eq!(eai_name(-42), "EAI_-42");
+        assert_eq!(rcode_name(2), "SERVFAIL");
+        assert_eq!(rcode_name(9), "RCODE9");
+        assert_eq!(qtype_name(28), "AAAA");
+        assert_eq!(qtype_name(99), "TYPE99");
+    }
+}
@@ -0,0 +1,574 @@
+//! Environment doctor for `ebpf-tool check`.
+//!
+//! Each probe looks at one thing the lessons depend on and returns a
+//! [`Check`]: a status, what was found, and how to fix it. A `Fail` means
+//! some lessons cannot work at all; a `Warn` only affects a few of them.
+//!
+//! ```text
+//! Kernel version:    6.8.0 [OK]
+//! BTF:               /sys/kernel/btf/vmlinux [OK]
+//! Capabilities:      CAP_BPF CAP_PERFMON CAP_SYS_ADMIN [OK]
+//! ...
+//! bpffs:             not mounted [WARN]
+//!   -> mount -t bpf bpf /sys/fs/bpf (needed by --pin and --detach)
+//! ```
+//!
+//! The probes read procfs, sysfs and the mount table only: `check` never
+//! loads a program, so it is safe to run anywhere.
+
+use crate::loader::{self, Loader, Origin};
+use crate::syscalls::SyscallTable;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+const BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+const TRACEFS_PATHS: [&str; 2] = ["/sys/kernel/tracing", "/sys/kernel/debug/tracing"];
+
+// Capability bits, from include/uapi/linux/capability.h
+pub(crate) const CAP_SYS_ADMIN: u32 = 21;
+pub(crate) const CAP_PERFMON: u32 = 38;
+pub(crate) const CAP_BPF: u32 = 39;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "[OK]",
+            Status::Warn => "[WARN]",
+            Status::Fail => "[FAIL]",
+        }
+    }
+}
+
+/// Result of one probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    /// What was found (e.g. "6.8.0", "not mounted")
+    pub detail: String,
+    /// How to fix it; None when the status is Ok
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Run every probe, in the order they are printed. The eBPF object probe
+/// checks the object `loader` would load (so `--bpf-object` is checked too).
+pub fn run_all(loader: &Loader) -> Vec<Check> {
+    let kernel = kernel_version().ok();
+    let caps = read_file("/proc/self/status")
+        .ok()
+        .and_then(|s| parse_cap_eff(&s));
+    let mounts = read_file("/proc/self/mounts").unwrap_or_default();
+
+    vec![
+        check_kernel(kernel),
+        check_btf(btf_available()),
+        check_capabilities(caps, kernel),
+        check_unprivileged_bpf(
+            read_file("/proc/sys/kernel/unprivileged_bpf_disabled").ok(),
+            caps,
+        ),
+        check_memlock(memlock_limit(), kernel),
+        check_jit(read_file("/proc/sys/net/core/bpf_jit_enable").ok()),
+        check_bpffs_mount(&mounts),
+        check_tracefs(
+            TRACEFS_PATHS
+                .into_iter()
+                .find(|p| Path::new(p).join("events").is_dir()),
+        ),
+        check_bpf_lsm(read_file("/sys/kernel/security/lsm").ok()),
+        check_loader_object(loader),
+        check_syscall_table(SyscallTable::load()),
+    ]
+}
+
+/// Print the checks as aligned text lines, with hints below the problems.
+pub fn render_text(checks: &[Check]) -> String {
+    let mut out = String::new();
+    for check in checks {
+        let name = format!("{}:", check.name);
+        out.push_str(&format!(
+            "{:<18} {} {}\n",
+            name,
+            check.detail,
+            check.status.label()
+        ));
+        if let Some(hint) = &check.hint {
+            out.push_str(&format!("  -> {}\n", hint));
+        }
+    }
+
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    out.push_str(&format!(
+        "\n{} checks: {} ok, {} warnings, {} failed\n",
+        checks.len(),
+        count(Status::Ok),
+        count(Status::Warn),
+        count(Status::Fail)
+    ));
+    out
+}
+
+/// The checks as a JSON document: `{"ok": bool, "checks": [...]}`.
+pub fn render_json(checks: &[Check]) -> Result<String> {
+    let doc = serde_json::json!({
+        "ok": failures(checks) == 0,
+        "checks": checks,
+    });
+    Ok(serde_json::to_string_pretty(&doc)?)
+}
+
+/// Number of hard failures (the exit code of `check` is non-zero if any).
+pub fn failures(checks: &[Check]) -> usize {
+    checks.iter().filter(|c| c.status == Status::Fail).count()
+}
+
+// =============================================================================
+// System queries
+// =============================================================================
+
+/// Get the kernel version as a tuple (major, minor, patch).
+pub fn kernel_version() -> Result<(u32, u32, u32)> {
+    let info = nix::sys::utsname::uname()?;
+    let release = info.release().to_string_lossy().into_owned();
+    parse_kernel_release(&release).with_context(|| format!("unexpected kernel release {release}"))
+}
+
+/// Check if BTF (BPF Type Format) is available on the system.
+///
+/// BTF enables CO-RE (Compile Once, Run Everywhere), and fentry, LSM and
+/// iterator programs cannot be loaded without it.
+pub fn btf_available() -> bool {
+    Path::new(BTF_PATH).exists()
+}
+
+/// Check if the running kernel has the BPF LSM enabled.
+///
+/// `CONFIG_BPF_LSM=y` is not enough: "bpf" must also appear in the active
+/// LSM list, which is set with the `lsm=` boot parameter.
+#[allow(dead_code)]
+pub fn bpf_lsm_enabled() -> Result<bool> {
+    match read_file("/sys/kernel/security/lsm") {
+        Ok(list) => Ok(lsm_list_has_bpf(&list)),
+        Err(_) => Ok(false),
+    }
+}
+
+pub(crate) fn read_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))
+}
+
+/// Soft RLIMIT_MEMLOCK in bytes, None for unlimited.
+pub(crate) fn memlock_limit() -> Option<u64> {
+    let mut rlim = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit only writes to the struct we pass.
+    if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut rlim) } != 0 {
+        return Some(0);
+    }
+    (rlim.rlim_cur != libc::RLIM_INFINITY).then_some(rlim.rlim_cur)
+}
+
+// =============================================================================
+// Parsers
+// =============================================================================
+
+/// Parse "6.8.0-45-generic" or "5.15.0" into (major, minor, patch).
+fn parse_kernel_release(release: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = release.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// The effective capability set from /proc/<pid>/status.
+pub(crate) fn parse_cap_eff(status: &str) -> Option<u64> {
+    let hex = status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))?
+        .trim();
+    u64::from_str_radix(hex, 16).ok()
+}
+
+pub(crate) fn has_cap(caps: u64, cap: u32) -> bool {
+    caps & (1 << cap) != 0
+}
+
+fn lsm_list_has_bpf(list: &str) -> bool {
+    list.trim().split(',').any(|lsm| lsm == "bpf")
+}
+
+/// Mount point of the first filesystem of type `fstype` in a mounts table.
+fn find_mount<'a>(mounts: &'a str, fstype: &str) -> Option<&'a str> {
+    mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let _source = fields.next()?;
+        let target = fields.next()?;
+        (fields.next()? == fstype).then_some(target)
+    })
+}
+
+pub(crate) fn at_least(kernel: Option<(u32, u32, u32)>, major: u32, minor: u32) -> bool {
+    kernel.is_some_and(|(ma, mi, _)| (ma, mi) >= (major, minor))
+}
+
+// =============================================================================
+// Probes
+// =============================================================================
+
+fn check_kernel(kernel: Option<(u32, u32, u32)>) -> Check {
+    const NAME: &str = "Kernel version";
+    match kernel {
+        None => Check::fail(NAME, "unknown", "could not parse `uname -r`"),
+        Some((ma, mi, pa)) if at_least(kernel, 5, 8) => Check::ok(NAME, format!("{ma}.{mi}.{pa}")),
+        Some((ma, mi, pa)) => Check::warn(
+            NAME,
+            format!("{ma}.{mi}.{pa}"),
+            "5.8+ recommended: older kernels have no CAP_BPF and lack some program types",
+        ),
+    }
+}
+
+fn check_btf(available: bool) -> Check {
+    const NAME: &str = "BTF";
+    if available {
+        Check::ok(NAME, BTF_PATH)
+    } else {
+        Check::fail(
+            NAME,
+            format!("{BTF_PATH} not found"),
+            "use a kernel built with CONFIG_DEBUG_INFO_BTF=y (most distribution kernels since 2021)",
+        )
+    }
+}
+
+fn check_capabilities(caps: Option<u64>, kernel: Option<(u32, u32, u32)>) -> Check {
+    const NAME: &str = "Capabilities";
+    let Some(caps) = caps else {
+        return Check::fail(
+            NAME,
+            "unknown",
+            "could not read CapEff from /proc/self/status",
+        );
+    };
+
+    let names: Vec<&str> = [
+        (CAP_BPF, "CAP_BPF"),
+        (CAP_PERFMON, "CAP_PERFMON"),
+        (CAP_SYS_ADMIN, "CAP_SYS_ADMIN"),
+    ]
+    .into_iter()
+    .filter(|(cap, _)| has_cap(caps, *cap))
+    .map(|(_, name)| name)
+    .collect();
+    let detail = if names.is_empty() {
+        "none of CAP_BPF, CAP_PERFMON, CAP_SYS_ADMIN".to_string()
+    } else {
+        names.join(" ")
+    };
+
+    let split_caps = at_least(kernel, 5, 8) && has_cap(caps, CAP_BPF) && has_cap(caps, CAP_PERFMON);
+    if has_cap(caps, CAP_SYS_ADMIN) {
+        Check::ok(NAME, detail)
+    } else if split_caps {
+        Check::warn(
+            NAME,
+            detail,
+            "enough for tracing; `prog list` and `map list` also need CAP_SYS_ADMIN",
+        )
+    } else {
+        Check::fail(
+            NAME,
+            detail,
+            "run with sudo, or grant CAP_BPF and CAP_PERFMON (setcap cap_bpf,cap_perfmon+ep)",
+        )
+    }
+}
+
+fn check_unprivileged_bpf(value: Option<String>, caps: Option<u64>) -> Check {
+    const NAME: &str = "Unprivileged BPF";
+    let privileged = caps.is_some_and(|c| has_cap(c, CAP_SYS_ADMIN) || has_cap(c, CAP_BPF));
+    let value = value.map(|v| v.trim().to_string());
+    match value.as_deref() {
+        Some("0") => Check::ok(NAME, "allowed (kernel.unprivileged_bpf_disabled=0)"),
+        Some(v) if privileged => Check::ok(
+            NAME,
+            format!("disabled (kernel.unprivileged_bpf_disabled={v}), not needed with CAP_BPF"),
+        ),
+        Some(v) => Check::warn(
+            NAME,
+            format!("disabled (kernel.unprivileged_bpf_disabled={v})"),
+            "every bpf() call needs privileges here: run with sudo",
+        ),
+        None => Check::warn(
+            NAME,
+            "unknown",
+            "kernel.unprivileged_bpf_disabled is missing: is CONFIG_BPF_SYSCALL set?",
+        ),
+    }
+}
+
+fn check_memlock(limit: Option<u64>, kernel: Option<(u32, u32, u32)>) -> Check {
+    const NAME: &str = "Memlock limit";
+    let detail = match limit {
+        None => "unlimited".to_string(),
+        Some(bytes) => format!("{} KiB", bytes / 1024),
+    };
+    if at_least(kernel, 5, 11) {
+        // Since 5.11 BPF memory is charged to the memory cgroup instead.
+        Check::ok(NAME, format!("{detail} (not used for BPF since 5.11)"))
+    } else if limit.is_none_or(|bytes| bytes >= 64 << 20) {
+        Check::ok(NAME, detail)
+    } else {
+        Check::warn(
+            NAME,
+            detail,
+            "maps may fail with EPERM before 5.11: `ulimit -l unlimited`, or setrlimit(RLIMIT_MEMLOCK) before loading",
+        )
+    }
+}
+
+fn check_jit(value: Option<String>) -> Check {
+    const NAME: &str = "JIT";
+    match value.as_deref().map(str::trim) {
+        Some("1") => Check::ok(NAME, "enabled"),
+        Some("2") => Check::warn(
+            NAME,
+            "enabled with debug output",
+            "sysctl net.core.bpf_jit_enable=1 (2 logs every JIT image to the kernel log)",
+        ),
+        Some(_) => Check::warn(
+            NAME,
+            "disabled (interpreter)",
+            "sysctl net.core.bpf_jit_enable=1 for native speed",
+        ),
+        None => Check::warn(
+            NAME,
+            "unknown",
+            "net.core.bpf_jit_enable is missing: was the kernel built with CONFIG_BPF_JIT?",
+        ),
+    }
+}
+
+fn check_syscall_table(table: Result<SyscallTable>) -> Check {
+    const NAME: &str = "Syscall table";
+    match table {
+        Ok(t) if t.is_empty() => Check::warn(
+            NAME,
+            format!("none for {}", std::env::consts::ARCH),
+            format!(
+                "syscalls show as syscall_<nr>; list NUMBER NAME lines in {}",
+                crate::syscalls::DEFAULT_OVERRIDE
+            ),
+        ),
+        Ok(t) => Check::ok(NAME, format!("{} syscalls ({})", t.len(), t.source())),
+        Err(e) => Check::warn(
+            NAME,
+            format!("{:#}", e),
+            format!(
+                "fix the file, or unset {} to use the built-in table",
+                crate::syscalls::OVERRIDE_ENV
+            ),
+        ),
+    }
+}
+
+fn check_bpffs_mount(mounts: &str) -> Check {
+    const NAME: &str = "bpffs";
+    match find_mount(mounts, "bpf") {
+        Some(target) => Check::ok(NAME, format!("mounted at {target}")),
+        None => Check::warn(
+            NAME,
+            "not mounted",
+            "mount -t bpf bpf /sys/fs/bpf (needed by --pin and --detach)",
+        ),
+    }
+}
+
+fn check_tracefs(events_dir: Option<&str>) -> Check {
+    const NAME: &str = "tracefs";
+    match events_dir {
+        Some(path) => Check::ok(NAME, path),
+        None => Check::fail(
+            NAME,
+            "no events/ under /sys/kernel/tracing or /sys/kernel/debug/tracing",
+            "mount -t tracefs tracefs /sys/kernel/tracing (and debugfs on /sys/kernel/debug)",
+        ),
+    }
+}
+
+fn check_bpf_lsm(list: Option<String>) -> Check {
+    const NAME: &str = "BPF LSM";
+    match list {
+        Some(list) if lsm_list_has_bpf(&list) => {
+            Check::ok(NAME, format!("enabled ({})", list.trim()))
+        }
+        Some(_) => Check::warn(
+            NAME,
+            "not in lsm=",
+            "add \",bpf\" to lsm= on the kernel command line (only the `lsm` subcommand needs it)",
+        ),
+        None => Check::warn(
+            NAME,
+            "unknown",
+            "mount -t securityfs securityfs /sys/kernel/security",
+        ),
+    }
+}
+
+fn check_loader_object(loader: &Loader) -> Check {
+    match loader.unchecked_object(loader::DEFAULT_OBJECT) {
+        Ok(object) => {
+            let mut check = check_ebpf_object(object.bytes());
+            if let Origin::File(path) = object.origin() {
+                check.detail = format!("{} ({})", check.detail, path.display());
+            }
+            check
+        }
+        Err(e) => Check::fail(
+            "eBPF object",
+            format!("{:#}", e),
+            "check the --bpf-object path",
+        ),
+    }
+}
+
+fn check_ebpf_object(object: &[u8]) -> Check {
+    const NAME: &str = "eBPF object";
+    const HINT: &str =
+        "install nightly, rust-src and bpf-linker, then rebuild (see the build.rs warnings)";
+    if object.is_empty() {
+        return Check::fail(NAME, "placeholder (eBPF programs were not built)", HINT);
+    }
+
+    if !loader::is_bpf_elf(object) {
+        return Check::fail(NAME, "not a BPF ELF object", HINT);
+    }
+    Check::ok(NAME, format!("{} KiB", object.len().div_ceil(1024)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kernel_release() {
+        assert_eq!(parse_kernel_release("6.8.0-45-generic"), Some((6, 8, 0)));
+        assert_eq!(parse_kernel_release("5.15.167"), Some((5, 15, 167)));
+        assert_eq!(parse_kernel_release("6.10"), Some((6, 10, 0)));
+        assert_eq!(parse_kernel_release("garbage"), None);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let status = "Name:\tcat\nCapEff:\t000001fffeffffff\n";
+        let root = parse_cap_eff(status);
+        assert_eq!(root, Some(0x1fffeffffff));
+        assert_eq!(check_capabilities(root, Some((6, 8, 0))).status, Status::Ok);
+
+        let split = Some((1 << CAP_BPF) | (1 << CAP_PERFMON));
+        assert_eq!(
+            check_capabilities(split, Some((6, 8, 0))).status,
+            Status::Warn
+        );
+        // CAP_BPF does not exist before 5.8
+        assert_eq!(
+            check_capabilities(split, Some((5, 4, 0))).status,
+            Status::Fail
+        );
+        assert_eq!(check_capabilities(Some(0), None).status, Status::Fail);
+    }
+
+    #[test]
+    fn test_mounts_and_lsm() {
+        let mounts = "proc /proc proc rw 0 0\nbpf /sys/fs/bpf bpf rw,nosuid 0 0\n";
+        assert_eq!(find_mount(mounts, "bpf"), Some("/sys/fs/bpf"));
+        assert_eq!(find_mount(mounts, "tracefs"), None);
+
+        assert!(lsm_list_has_bpf("lockdown,capability,landlock,bpf"));
+        assert!(!lsm_list_has_bpf("lockdown,capability,bpfilter"));
+    }
+
+    #[test]
+    fn test_ebpf_object() {
+        assert_eq!(check_ebpf_object(b"").status, Status::Fail);
+        assert_eq!(check_ebpf_object(b"not an elf").status, Status::Fail);
+
+        let mut elf = vec![0u8; 64];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[18..20].copy_from_slice(&247u16.to_le_bytes()); // EM_BPF
+        assert_eq!(check_ebpf_object(&elf).status, Status::Ok);
+    }
+
+    #[test]
+    fn test_syscall_table_check() {
+        let builtin = check_syscall_table(Ok(SyscallTable::builtin()));
+        if ebpf_tool_common::SYSCALLS.is_empty() {
+            assert_eq!(builtin.status, Status::Warn);
+        } else {
+            assert_eq!(builtin.status, Status::Ok);
+            assert!(builtin
+                .detail
+                .ends_with(&format!("({})", ebpf_tool_common::SYSCALL_TABLE_SOURCE)));
+        }
+        let broken = check_syscall_table(Err(anyhow::anyhow!("line 3: bad")));
+        assert_eq!(broken.status, Status::Warn);
+        assert!(broken.hint.unwrap().contains("EBPF_TOOL_SYSCALLS"));
+    }
+
+    #[test]
+    fn test_failures_and_json() {
+        let checks = vec![
+            check_jit(Some("1\n".into())),
+            check_bpffs_mount(""),
+            check_tracefs(None),
+        ];
+        assert_eq!(failures(&checks), 1);
+
+        let json: serde_json::Value = serde_json::from_str(&render_json(&checks).unwrap()).unwrap();
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["checks"][1]["status"], "warn");
+        assert!(json["checks"][0].get("hint").is_none());
+    }
+}
@@ -0,0 +1,270 @@
+//! `ebpf-tool errors`: failed syscalls per process, from `SYSCALL_ERRORS`.
+//!
+//! `syscall_errors_tracepoint` counts every syscall exit that returned an
+//! error, keyed by (pid, errno, syscall). The counts only grow, so
+//! `errors` reads the map every interval and reports the difference:
+//!
+//! ```text
+//! SYSCALL_ERRORS (per-CPU) ─► sum CPUs ─► delta(before, after) ─► rows(): --errno,
+//!                                                                     busiest first
+//!                                                                 ─► render()
+//! ```
+//!
+//! A confined container that fails usually fails loudly: the same denied
+//! open or connect retried in a loop. One row with a high `/s` is the
+//! storm, and its errno says which wall it hits: EPERM and EACCES are a
+//! seccomp profile, a capability or an LSM; ENOENT a path that is not in
+//! the container's mount namespace; EAGAIN a full queue or a limit such as
+//! `pids.max`.
+
+use ebpf_tool_common::ErrorKey;
+use nix::errno::Errno;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Largest errno the kernel returns: `ret` in -4095..=-1 is an error.
+pub const MAX_ERRNO: i32 = 4095;
+
+/// Parse `--errno`: a name (`ENOENT`, `enoent`) or a number (`2`). clap
+/// `value_parser`.
+pub fn parse_errno(arg: &str) -> Result<i32, String> {
+    let arg = arg.trim();
+    if let Ok(n) = arg.parse::<i32>() {
+        if (1..=MAX_ERRNO).contains(&n) {
+            return Ok(n);
+        }
+        return Err(format!("errno {} out of range 1..={}", n, MAX_ERRNO));
+    }
+    let upper = arg.to_ascii_uppercase();
+    (1..=MAX_ERRNO)
+        .find(|&n| {
+            let errno = Errno::from_raw(n);
+            errno != Errno::UnknownErrno && format!("{:?}", errno) == upper
+        })
+        .ok_or_else(|| {
+            format!(
+                "unknown errno '{}' (expected a name like ENOENT or a number)",
+                arg
+            )
+        })
+}
+
+/// `ENOENT` for 2, `errno 528` for a number without a name.
+pub fn errno_name(errno: u32) -> String {
+    match Errno::from_raw(errno as i32) {
+        Errno::UnknownErrno => format!("errno {}", errno),
+        e => format!("{:?}", e),
+    }
+}
+
+/// The counts of one interval: `after - before` for each key.
+///
+/// A key not in `before` is new and counts from 0. A key whose count went
+/// down was evicted from the LRU map and inserted again, so it also counts
+/// from 0. Keys that did not grow are left out.
+pub fn delta(
+    before: &HashMap<ErrorKey, u64>,
+    after: &HashMap<ErrorKey, u64>,
+) -> HashMap<ErrorKey, u64> {
+    after
+        .iter()
+        .map(|(key, &now)| {
+            let then = before.get(key).copied().filter(|&then| then <= now);
+            (*key, now - then.unwrap_or(0))
+        })
+        .filter(|&(_, d)| d > 0)
+        .collect()
+}
+
+/// One line of the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub pid: u32,
+    pub syscall_nr: u64,
+    pub errno: u32,
+    pub count: u64,
+}
+
+/// The rows of `counts` with an errno in `errnos` (all when empty),
+/// the most frequent first.
+pub fn rows(counts: &HashMap<ErrorKey, u64>, errnos: &[i32]) -> Vec<Row> {
+    let mut rows: Vec<Row> = counts
+        .iter()
+        .filter(|(key, _)| errnos.is_empty() || errnos.contains(&(key.errno as i32)))
+        .map(|(key, &count)| Row {
+            pid: key.pid,
+            syscall_nr: key.syscall_nr,
+            errno: key.errno,
+            count,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.pid.cmp(&b.pid))
+            .then_with(|| a.syscall_nr.cmp(&b.syscall_nr))
+            .then_with(|| a.errno.cmp(&b.errno))
+    });
+    rows
+}
+
+/// The table for one interval: the `top` most frequent rows, then the
+/// total rate and the three most common errnos over all rows.
+pub fn render(
+    rows: &[Row],
+    elapsed: Duration,
+    top: usize,
+    syscall_name: impl Fn(u64) -> String,
+    mut comm: impl FnMut(u32) -> String,
+) -> String {
+    let secs = elapsed.as_secs_f64().max(f64::MIN_POSITIVE);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:>7}  {:<15}  {:<16}  {:<12}  {:>8}  {:>8}",
+        "PID", "COMM", "SYSCALL", "ERRNO", "COUNT", "/s"
+    );
+    for row in rows.iter().take(top) {
+        let _ = writeln!(
+            out,
+            "{:>7}  {:<15}  {:<16}  {:<12}  {:>8}  {:>8.1}",
+            row.pid,
+            comm(row.pid),
+            syscall_name(row.syscall_nr),
+            errno_name(row.errno),
+            row.count,
+            row.count as f64 / secs
+        );
+    }
+    if rows.len() > top {
+        let _ = writeln!(out, "... {} more", rows.len() - top);
+    }
+
+    let total: u64 = rows.iter().map(|r| r.count).sum();
+    let mut by_errno: HashMap<u32, u64> = HashMap::new();
+    for row in rows {
+        *by_errno.entry(row.errno).or_default() += row.count;
+    }
+    let mut by_errno: Vec<(u32, u64)> = by_errno.into_iter().collect();
+    by_errno.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let _ = write!(out, "total: {} errors, {:.1}/s", total, total as f64 / secs);
+    for (errno, count) in by_errno.iter().take(3) {
+        let _ = write!(
+            out,
+            ", {} {:.0}%",
+            errno_name(*errno),
+            *count as f64 * 100.0 / total as f64
+        );
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(pid: u32, syscall_nr: u64, errno: u32) -> ErrorKey {
+        ErrorKey {
+            pid,
+            errno,
+            syscall_nr,
+        }
+    }
+
+    #[test]
+    fn test_parse_errno() {
+        assert_eq!(parse_errno("ENOENT"), Ok(2));
+        assert_eq!(parse_errno("eperm"), Ok(1));
+        assert_eq!(parse_errno(" EAGAIN "), Ok(11));
+        assert_eq!(parse_errno("13"), Ok(13));
+        assert!(parse_errno("0").unwrap_err().contains("out of range"));
+        assert!(parse_errno("ENOPE")
+            .unwrap_err()
+            .contains("unknown errno 'ENOPE'"));
+    }
+
+    #[test]
+    fn test_errno_name() {
+        assert_eq!(errno_name(2), "ENOENT");
+        assert_eq!(errno_name(11), "EAGAIN");
+        assert_eq!(errno_name(528), "errno 528");
+    }
+
+    #[test]
+    fn test_delta() {
+        let before = HashMap::from([
+            (key(10, 257, 2), 100),
+            (key(10, 257, 13), 5),
+            (key(11, 0, 11), 900),
+        ]);
+        let after = HashMap::from([
+            (key(10, 257, 2), 350), // grew
+            (key(10, 257, 13), 5),  // quiet this interval
+            (key(11, 0, 11), 20),   // evicted and inserted again
+            (key(12, 42, 1), 7),    // new
+        ]);
+        let d = delta(&before, &after);
+        assert_eq!(d.len(), 3);
+        assert_eq!(d[&key(10, 257, 2)], 250);
+        assert_eq!(d[&key(11, 0, 11)], 20);
+        assert_eq!(d[&key(12, 42, 1)], 7);
+    }
+
+    #[test]
+    fn test_rows_filter_and_sort() {
+        let counts = HashMap::from([
+            (key(10, 257, 2), 50),
+            (key(11, 42, 1), 300),
+            (key(12, 0, 11), 50),
+        ]);
+        let all = rows(&counts, &[]);
+        let pids: Vec<_> = all.iter().map(|r| r.pid).collect();
+        assert_eq!(pids, [11, 10, 12]);
+
+        let eagain = rows(&counts, &[11]);
+        assert_eq!(
+            eagain,
+            [Row {
+                pid: 12,
+                syscall_nr: 0,
+                errno: 11,
+                count: 50
+            }]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let counts = HashMap::from([
+            (key(811, 257, 2), 600),
+            (key(811, 42, 1), 300),
+            (key(920, 0, 11), 100),
+        ]);
+        let name = |nr| match nr {
+            0 => "read".to_string(),
+            42 => "connect".to_string(),
+            _ => "openat".to_string(),
+        };
+        let text = render(
+            &rows(&counts, &[]),
+            Duration::from_secs(2),
+            2,
+            name,
+            |pid| format!("app-{}", pid),
+        );
+        let lines: Vec<_> = text.lines().collect();
+        assert!(lines[0].trim_start().starts_with("PID"));
+        assert_eq!(
+            lines[1],
+            "    811  app-811          openat            ENOENT             600     300.0"
+        );
+        assert!(lines[2].contains("connect") && lines[2].contains("EPERM"));
+        assert_eq!(lines[3], "... 1 more");
+        assert_eq!(
+            lines[4],
+            "total: 1000 errors, 500.0/s, ENOENT 60%, EPERM 30%, EAGAIN 10%"
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Typed decoding of the events the eBPF programs send to userspace.
+//!
+//! Perf buffer records, task iterator output and capture records are plain
+//! bytes. [`parse`] turns them into the `ebpf-tool-common` struct they hold,
+//! after two checks:
+//!
+//! ```text
+//! bytes.len() >= size_of::<T>()       else Short   (wrong map, truncated)
+//! event.abi == EVENT_ABI              else BadMagic (not an event at all)
+//!                                          Version  (eBPF and userspace built
+//!                                                    from different versions)
+//! ```
+//!
+//! The copy is `bytemuck::pod_read_unaligned`: no `unsafe`, and perf
+//! buffers are only 4-byte aligned.
+//!
+//! Every event type is listed below with the size, alignment and `abi`
+//! offset this module was written against. The `const` assertions fail the
+//! build when `ebpf-tool-common` changes a struct without this table, which
+//! is also the reminder to bump `EVENT_VERSION`.
+
+use bytemuck::Pod;
+use ebpf_tool_common::{
+    CommUpdateEvent, DnsEvent, ExecEvent, LifecycleEvent, LsmDenyEvent, MountEvent, NetEvent,
+    OpenEvent, ProbeEvent, ProcEvent, SockEvent, SyscallBatch, SyscallEvent, SyscallExitEvent,
+    TaskRecord, EVENT_ABI, EVENT_MAGIC, EVENT_VERSION,
+};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{align_of, offset_of, size_of};
+
+/// A struct the eBPF programs send to userspace.
+pub trait Event: Pod {
+    /// Struct name in `ebpf-tool-common`, for error messages
+    const NAME: &'static str;
+
+    /// The `abi` field: [`EVENT_ABI`] of the build that made the event.
+    fn abi(&self) -> u32;
+}
+
+macro_rules! event {
+    ($ty:ty, size: $size:expr, align: $align:expr, abi: $abi:expr) => {
+        const _: () = assert!(
+            size_of::<$ty>() == $size,
+            concat!(
+                stringify!($ty),
+                " changed size: update events.rs and bump EVENT_VERSION"
+            )
+        );
+        const _: () = assert!(
+            align_of::<$ty>() == $align,
+            concat!(
+                stringify!($ty),
+                " changed alignment: update events.rs and bump EVENT_VERSION"
+            )
+        );
+        const _: () = assert!(
+            offset_of!($ty, abi) == $abi,
+            concat!(
+                stringify!($ty),
+                " moved its abi field: update events.rs and bump EVENT_VERSION"
+            )
+        );
+
+        impl Event for $ty {
+            const NAME: &'static str = stringify!($ty);
+
+            fn abi(&self) -> u32 {
+                self.abi
+            }
+        }
+    };
+}
+
+event!(SyscallEvent, size: 72, align: 8, abi: 68);
+event!(SyscallBatch, size: 1160, align: 8, abi: 4);
+event!(LifecycleEvent, size: 56, align: 8, abi: 48);
+event!(CommUpdateEvent, size: 32, align: 4, abi: 28);
+event!(LsmDenyEvent, size: 48, align: 8, abi: 40);
+event!(TaskRecord, size: 56, align: 8, abi: 48);
+event!(SockEvent, size: 40, align: 8, abi: 32);
+event!(MountEvent, size: 272, align: 8, abi: 264);
+event!(SyscallExitEvent, size: 48, align: 8, abi: 40);
+event!(ProbeEvent, size: 136, align: 8, abi: 68);
+event!(NetEvent, size: 64, align: 8, abi: 56);
+event!(ExecEvent, size: 832, align: 8, abi: 824);
+event!(DnsEvent, size: 320, align: 8, abi: 312);
+event!(OpenEvent, size: 328, align: 8, abi: 52);
+event!(ProcEvent, size: 320, align: 8, abi: 40);
+
+/// Why bytes could not be decoded as an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// Fewer bytes than the struct
+    Short {
+        event: &'static str,
+        len: usize,
+        size: usize,
+    },
+    /// Back-to-back records whose total is not a multiple of the struct size
+    Length {
+        event: &'static str,
+        len: usize,
+        size: usize,
+    },
+    /// No [`EVENT_MAGIC`]: the bytes are not an event of `ebpf-tool-common`
+    BadMagic { event: &'static str, abi: u32 },
+    /// An event of another [`EVENT_VERSION`]
+    Version { event: &'static str, version: u16 },
+}
+
+impl ParseError {
+    /// Whether every following event will fail too: the eBPF programs and
+    /// this binary were built from different versions.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, ParseError::Version { .. } | ParseError::Length { .. })
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Short { event, len, size } => {
+                write!(f, "{event}: record of {len} bytes, expected {size}")
+            }
+            ParseError::Length { event, len, size } => write!(
+                f,
+                "{event}: {len} bytes is not a multiple of {size}; \
+                 the eBPF programs were built with another layout"
+            ),
+            ParseError::BadMagic { event, abi } => write!(
+                f,
+                "{event}: no event magic (abi field {abi:#010x}); \
+                 is the record from another map?"
+            ),
+            ParseError::Version { event, version } => write!(
+                f,
+                "{event}: eBPF programs built with event version {version}, \
+                 this ebpf-tool reads version {EVENT_VERSION}; rebuild both \
+                 with cargo build -p ebpf-tool"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Decode one `T` from the start of `bytes`.
+///
+/// Extra bytes are ignored: perf pads its records to 8 bytes.
+pub fn parse<T: Event>(bytes: &[u8]) -> Result<T, ParseError> {
+    let size = size_of::<T>();
+    let Some(bytes) = bytes.get(..size) else {
+        return Err(ParseError::Short {
+            event: T::NAME,
+            len: bytes.len(),
+            size,
+        });
+    };
+    let event: T = bytemuck::pod_read_unaligned(bytes);
+    check(&event)?;
+    Ok(event)
+}
+
+fn check<T: Event>(event: &T) -> Result<(), ParseError> {
+    let abi = event.abi();
+    if abi == EVENT_ABI {
+        Ok(())
+    } else if (abi >> 16) as u16 == EVENT_MAGIC {
+        Err(ParseError::Version {
+            event: T::NAME,
+            version: abi as u16,
+        })
+    } else {
+        Err(ParseError::BadMagic {
+            event: T::NAME,
+            abi,
+        })
+    }
+}
+
+/// Decodes a stream of one event type and keeps count of bad records.
+///
+/// One corrupt record should not end a trace, but a version mismatch makes
+/// every record fail and should: [`EventParser::parse`] skips the first
+/// kind and returns the second.
+#[derive(Debug)]
+pub struct EventParser<T> {
+    parsed: u64,
+    rejected: u64,
+    _event: PhantomData<T>,
+}
+
+impl<T: Event> Default for EventParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Event> EventParser<T> {
+    pub fn new() -> Self {
+        Self {
+            parsed: 0,
+            rejected: 0,
+            _event: PhantomData,
+        }
+    }
+
+    /// Decode one record. `Ok(None)`: the record was skipped, counted in
+    /// [`rejected`](Self::rejected) and, if it was the first, logged.
+    pub fn parse(&mut self, bytes: &[u8]) -> Result<Option<T>, ParseError> {
+        match parse(bytes) {
+            Ok(event) => {
+                self.parsed += 1;
+                Ok(Some(event))
+            }
+            Err(e) if e.is_fatal() => Err(e),
+            Err(e) => {
+                if self.rejected == 0 {
+                    log::warn!("skipping bad record: {e}");
+                }
+                self.rejected += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Decode records written back to back, as by the task iterator. The
+    /// whole buffer fails if any record does.
+    pub fn parse_all(&mut self, bytes: &[u8]) -> Result<Vec<T>, ParseError> {
+        let size = size_of::<T>();
+        if !bytes.len().is_multiple_of(size) {
+            return Err(ParseError::Length {
+                event: T::NAME,
+                len: bytes.len(),
+                size,
+            });
+        }
+        let events = bytes
+            .chunks_exact(size)
+            .map(parse)
+            .collect::<Result<Vec<T>, _>>()?;
+        self.parsed += events.len() as u64;
+        Ok(events)
+    }
+
+    /// Records decoded so far.
+    pub fn parsed(&self) -> u64 {
+        self.parsed
+    }
+
+    /// Records skipped so far.
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<T: Pod>(value: &T) -> Vec<u8> {
+        bytemuck::bytes_of(value).to_vec()
+    }
+
+    #[test]
+    fn test_parse_roundtrip_ignores_padding() {
+        let event = SyscallEvent {
+            pid: 42,
+            syscall_nr: 257,
+            ..SyscallEvent::new()
+        };
+        let mut data = bytes(&event);
+        data.extend_from_slice(&[0xff; 4]);
+        let back: SyscallEvent = parse(&data).unwrap();
+        assert_eq!((back.pid, back.syscall_nr), (42, 257));
+        // At an odd offset, as a perf buffer record may be
+        let shifted = [&[0u8; 4][..], &data].concat();
+        let back: SyscallEvent = parse(&shifted[4..]).unwrap();
+        assert_eq!(back.pid, 42);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let data = bytes(&SyscallExitEvent::new());
+        assert_eq!(
+            parse::<SyscallExitEvent>(&data[..40]).unwrap_err(),
+            ParseError::Short {
+                event: "SyscallExitEvent",
+                len: 40,
+                size: 48
+            }
+        );
+
+        // Zeroed memory, or a struct without the field
+        let zeroed = bytes(&<SyscallExitEvent as bytemuck::Zeroable>::zeroed());
+        assert_eq!(
+            parse::<SyscallExitEvent>(&zeroed).unwrap_err(),
+            ParseError::BadMagic {
+                event: "SyscallExitEvent",
+                abi: 0
+            }
+        );
+
+        let other = SyscallExitEvent {
+            abi: ((EVENT_MAGIC as u32) << 16) | (EVENT_VERSION as u32 + 1),
+            ..SyscallExitEvent::new()
+        };
+        let err = parse::<SyscallExitEvent>(&bytes(&other)).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::Version {
+                event: "SyscallExitEvent",
+                version: EVENT_VERSION + 1
+            }
+        );
+        assert!(err.is_fatal());
+        assert!(err.to_string().contains("rebuild"), "{err}");
+    }
+
+    #[test]
+    fn test_parser_skips_bad_records_but_not_versions() {
+        let mut parser = EventParser::<CommUpdateEvent>::new();
+        let good = bytes(&CommUpdateEvent::new());
+        assert!(parser.parse(&good).unwrap().is_some());
+        assert!(parser.parse(&good[..8]).unwrap().is_none());
+        assert!(parser.parse(&[0; 32]).unwrap().is_none());
+        assert_eq!((parser.parsed(), parser.rejected()), (1, 2));
+
+        let other = CommUpdateEvent {
+            abi: EVENT_ABI + 1,
+            ..CommUpdateEvent::new()
+        };
+        assert!(parser.parse(&bytes(&other)).is_err());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let mut parser = EventParser::<TaskRecord>::new();
+        let mut data = Vec::new();
+        for pid in [1, 2, 3] {
+            data.extend(bytes(&TaskRecord {
+                pid,
+                ..TaskRecord::new()
+            }));
+        }
+        let records = parser.parse_all(&data).unwrap();
+        assert_eq!(records.iter().map(|r| r.pid).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(parser.parsed(), 3);
+
+        // A TaskRecord of the old 48-byte layout
+        assert!(matches!(
+            parser.parse_all(&data[..48 * 3]),
+            Err(ParseError::Length { size: 56, .. })
+        ));
+    }
+
+    #[test]
+    fn test_batch_events_carry_abi() {
+        let mut batch = SyscallBatch::new();
+        batch.push(SyscallEvent {
+            pid: 7,
+            ..SyscallEvent::new()
+        });
+        let back: SyscallBatch = parse(&bytes(&batch)).unwrap();
+        assert_eq!(back.events()[0].pid, 7);
+        assert!(back.events().iter().all(|e| check(e).is_ok()));
+    }
+}
@@ -0,0 +1,675 @@
+//! Filter expressions: `trace --filter` and `replay --filter`.
+//!
+//! `--process` and `--syscall` select one value each. An expression can
+//! combine several fields:
+//!
+//! ```text
+//! comm == "nginx" && (syscall == "openat" || syscall == "connect") && pid != 1
+//! ```
+//!
+//! | Field     | Values  | Operators              |
+//! |-----------|---------|------------------------|
+//! | `comm`    | string  | `==` `!=`              |
+//! | `syscall` | string  | `==` `!=`              |
+//! | `pid`     | number  | `==` `!=` `<` `<=` `>` `>=` |
+//! | `tid`     | number  | same as pid            |
+//! | `cgroup`  | number (cgroup id) | same as pid |
+//! | `ppid`    | number  | same as pid            |
+//! | `pidns`   | number (inode) | same as pid     |
+//! | `mntns`   | number (inode) | same as pid     |
+//! | `uid`     | number  | same as pid            |
+//! | `gid`     | number  | same as pid            |
+//!
+//! Strings are quoted with `"` or `'`; a bare word (`syscall == openat`)
+//! works too. `&&` binds tighter than `||`, `!` negates, parentheses group.
+//!
+//! Expressions are evaluated in userspace, on every event that reaches the
+//! perf buffer. [`Expr::pushdown`] works out which PIDs and syscalls can
+//! match at all; `trace --push-down` writes those sets into the kernel's
+//! filter maps, so the rest is dropped before it costs a perf buffer slot.
+//! The userspace check still runs, so pushing down never changes the output.
+
+use anyhow::{bail, Result};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// An event field a filter can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Comm,
+    Syscall,
+    Pid,
+    Tid,
+    Cgroup,
+    Ppid,
+    Pidns,
+    Mntns,
+    Uid,
+    Gid,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "comm" => Field::Comm,
+            "syscall" => Field::Syscall,
+            "pid" => Field::Pid,
+            "tid" => Field::Tid,
+            "cgroup" => Field::Cgroup,
+            "ppid" => Field::Ppid,
+            "pidns" => Field::Pidns,
+            "mntns" => Field::Mntns,
+            "uid" => Field::Uid,
+            "gid" => Field::Gid,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Comm => "comm",
+            Field::Syscall => "syscall",
+            Field::Pid => "pid",
+            Field::Tid => "tid",
+            Field::Cgroup => "cgroup",
+            Field::Ppid => "ppid",
+            Field::Pidns => "pidns",
+            Field::Mntns => "mntns",
+            Field::Uid => "uid",
+            Field::Gid => "gid",
+        }
+    }
+
+    fn is_string(self) -> bool {
+        matches!(self, Field::Comm | Field::Syscall)
+    }
+}
+
+/// A comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+        }
+    }
+
+    /// The operator that gives the opposite result: `!(a < b)` is `a >= b`.
+    fn negate(self) -> Self {
+        match self {
+            Op::Eq => Op::Ne,
+            Op::Ne => Op::Eq,
+            Op::Lt => Op::Ge,
+            Op::Le => Op::Gt,
+            Op::Gt => Op::Le,
+            Op::Ge => Op::Lt,
+        }
+    }
+
+    fn apply<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+        }
+    }
+}
+
+/// The right-hand side of a comparison. The parser checks that it has the
+/// field's type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Str(String),
+    Num(u64),
+}
+
+/// A parsed filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Cmp { field: Field, op: Op, value: Value },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+/// The fields of one event, as the tracer knows them.
+#[derive(Debug, Clone, Copy)]
+pub struct Fields<'a> {
+    pub pid: u32,
+    pub tid: u32,
+    /// Current process name (CommCache), not necessarily event.comm
+    pub comm: &'a str,
+    /// Syscall name, e.g. "openat"
+    pub syscall: &'a str,
+    pub cgroup_id: u64,
+    pub ppid: u32,
+    /// PID and mount namespace inodes; 0 when the kernel has no BTF
+    pub pidns_inum: u32,
+    pub mntns_inum: u32,
+    /// Real user and group ID, as seen from the host
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// What the kernel may drop without changing the result: events whose pid
+/// (or syscall) is outside the set can never match. `None` means any value
+/// may match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pushdown {
+    pub pids: Option<BTreeSet<u32>>,
+    pub syscalls: Option<BTreeSet<String>>,
+}
+
+impl Expr {
+    /// Whether an event with these fields passes the filter.
+    pub fn eval(&self, f: &Fields) -> bool {
+        match self {
+            Expr::Cmp { field, op, value } => match (field, value) {
+                (Field::Comm, Value::Str(s)) => op.apply(f.comm, s.as_str()),
+                (Field::Syscall, Value::Str(s)) => op.apply(f.syscall, s.as_str()),
+                (Field::Pid, Value::Num(n)) => op.apply(u64::from(f.pid), *n),
+                (Field::Tid, Value::Num(n)) => op.apply(u64::from(f.tid), *n),
+                (Field::Cgroup, Value::Num(n)) => op.apply(f.cgroup_id, *n),
+                (Field::Ppid, Value::Num(n)) => op.apply(u64::from(f.ppid), *n),
+                (Field::Pidns, Value::Num(n)) => op.apply(u64::from(f.pidns_inum), *n),
+                (Field::Mntns, Value::Num(n)) => op.apply(u64::from(f.mntns_inum), *n),
+                (Field::Uid, Value::Num(n)) => op.apply(u64::from(f.uid), *n),
+                (Field::Gid, Value::Num(n)) => op.apply(u64::from(f.gid), *n),
+                // parse() never builds these
+                _ => false,
+            },
+            Expr::And(a, b) => a.eval(f) && b.eval(f),
+            Expr::Or(a, b) => a.eval(f) || b.eval(f),
+            Expr::Not(e) => !e.eval(f),
+        }
+    }
+
+    /// The PID and syscall sets outside of which nothing can match.
+    ///
+    /// Only `pid == N` and `syscall == "name"` comparisons narrow the sets;
+    /// `!` is pushed inward first, so `!(pid != 5)` counts as `pid == 5`.
+    /// Everything else leaves a field unconstrained, which is always safe.
+    pub fn pushdown(&self) -> Pushdown {
+        self.bound(false)
+    }
+
+    fn bound(&self, negated: bool) -> Pushdown {
+        match self {
+            Expr::Cmp { field, op, value } => {
+                let op = if negated { op.negate() } else { *op };
+                let mut p = Pushdown::default();
+                match (field, op, value) {
+                    (Field::Pid, Op::Eq, Value::Num(n)) => {
+                        // A pid that does not fit in u32 never matches
+                        p.pids = Some(u32::try_from(*n).into_iter().collect());
+                    }
+                    (Field::Syscall, Op::Eq, Value::Str(s)) => {
+                        p.syscalls = Some(BTreeSet::from([s.clone()]));
+                    }
+                    _ => {}
+                }
+                p
+            }
+            // !(a && b) is !a || !b, and !(a || b) is !a && !b
+            Expr::And(a, b) if !negated => a.bound(false).intersect(b.bound(false)),
+            Expr::And(a, b) => a.bound(true).union(b.bound(true)),
+            Expr::Or(a, b) if !negated => a.bound(false).union(b.bound(false)),
+            Expr::Or(a, b) => a.bound(true).intersect(b.bound(true)),
+            Expr::Not(e) => e.bound(!negated),
+        }
+    }
+}
+
+impl Pushdown {
+    /// Both must hold: a value must be in both sets.
+    fn intersect(self, other: Self) -> Self {
+        fn both<T: Ord>(a: Option<BTreeSet<T>>, b: Option<BTreeSet<T>>) -> Option<BTreeSet<T>> {
+            match (a, b) {
+                (Some(mut a), Some(b)) => {
+                    a.retain(|x| b.contains(x));
+                    Some(a)
+                }
+                (a, None) => a,
+                (None, b) => b,
+            }
+        }
+        Self {
+            pids: both(self.pids, other.pids),
+            syscalls: both(self.syscalls, other.syscalls),
+        }
+    }
+
+    /// Either may hold: unconstrained unless both sides constrain the field.
+    fn union(self, other: Self) -> Self {
+        fn either<T: Ord>(a: Option<BTreeSet<T>>, b: Option<BTreeSet<T>>) -> Option<BTreeSet<T>> {
+            let mut a = a?;
+            a.extend(b?);
+            Some(a)
+        }
+        Self {
+            pids: either(self.pids, other.pids),
+            syscalls: either(self.syscalls, other.syscalls),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Fully parenthesized, so the log shows how the expression was read.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Cmp {
+                field,
+                op,
+                value: Value::Str(s),
+            } => write!(f, "{} {} {:?}", field.name(), op.symbol(), s),
+            Expr::Cmp {
+                field,
+                op,
+                value: Value::Num(n),
+            } => write!(f, "{} {} {}", field.name(), op.symbol(), n),
+            Expr::And(a, b) => write!(f, "({} && {})", a, b),
+            Expr::Or(a, b) => write!(f, "({} || {})", a, b),
+            Expr::Not(e) => write!(f, "!{}", e),
+        }
+    }
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(Op),
+    AndAnd,
+    OrOr,
+    Bang,
+    LParen,
+    RParen,
+}
+
+/// Parse a `--filter` expression. Errors point at the offending column:
+///
+/// ```text
+/// invalid filter at column 8: pid takes a number, found "abc"
+///   pid == abc
+///          ^
+/// ```
+pub fn parse(src: &str) -> Result<Expr> {
+    let tokens = tokenize(src)?;
+    let mut parser = Parser {
+        src,
+        tokens,
+        next: 0,
+    };
+    let expr = parser.or()?;
+    if let Some((pos, token)) = parser.tokens.get(parser.next) {
+        return Err(error(src, *pos, &format!("unexpected {}", describe(token))));
+    }
+    Ok(expr)
+}
+
+fn error(src: &str, pos: usize, message: &str) -> anyhow::Error {
+    let column = src[..pos].chars().count();
+    anyhow::anyhow!(
+        "invalid filter at column {}: {}\n  {}\n  {}^",
+        column + 1,
+        message,
+        src,
+        " ".repeat(column)
+    )
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(w) => format!("{:?}", w),
+        Token::Str(s) => format!("string {:?}", s),
+        Token::Op(op) => format!("'{}'", op.symbol()),
+        Token::AndAnd => "'&&'".to_string(),
+        Token::OrOr => "'||'".to_string(),
+        Token::Bang => "'!'".to_string(),
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+    }
+}
+
+/// Tokens with their byte offset in `src`.
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some(&(pos, c)) = chars.peek() {
+        let two = src[pos..].get(..2).unwrap_or("");
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '"' | '\'' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, ch)) if ch == c => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, ch)) => s.push(ch),
+                            None => return Err(error(src, pos, "unterminated string")),
+                        },
+                        Some((_, ch)) => s.push(ch),
+                        None => return Err(error(src, pos, "unterminated string")),
+                    }
+                }
+                tokens.push((pos, Token::Str(s)));
+                continue;
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&(_, ch)) = chars.peek() {
+                    if !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' || ch == '.') {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push((pos, Token::Word(word)));
+                continue;
+            }
+            _ => {
+                let (token, len) = match two {
+                    "&&" => (Token::AndAnd, 2),
+                    "||" => (Token::OrOr, 2),
+                    "==" => (Token::Op(Op::Eq), 2),
+                    "!=" => (Token::Op(Op::Ne), 2),
+                    "<=" => (Token::Op(Op::Le), 2),
+                    ">=" => (Token::Op(Op::Ge), 2),
+                    _ => match c {
+                        '<' => (Token::Op(Op::Lt), 1),
+                        '>' => (Token::Op(Op::Gt), 1),
+                        '!' => (Token::Bang, 1),
+                        '=' => return Err(error(src, pos, "use '==' to compare")),
+                        '&' => return Err(error(src, pos, "use '&&' for and")),
+                        '|' => return Err(error(src, pos, "use '||' for or")),
+                        _ => return Err(error(src, pos, &format!("unexpected character {:?}", c))),
+                    },
+                };
+                for _ in 0..len {
+                    chars.next();
+                }
+                tokens.push((pos, token));
+                continue;
+            }
+        };
+        chars.next();
+        tokens.push((pos, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    /// Byte offset of the next token, or the end of the input.
+    fn pos(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.src.len(), |(pos, _)| *pos)
+    }
+
+    fn take(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).map(|(_, t)| t.clone());
+        self.next += 1;
+        token
+    }
+
+    fn fail<T>(&self, message: &str) -> Result<T> {
+        Err(error(self.src, self.pos(), message))
+    }
+
+    /// Describe the next token for "expected X, found Y" messages.
+    fn found(&self) -> String {
+        self.peek().map_or("the end".to_string(), describe)
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::OrOr) {
+            self.take();
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::AndAnd) {
+            self.take();
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some(Token::Bang) => {
+                self.take();
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::LParen) => {
+                let open = self.pos();
+                self.take();
+                let expr = self.or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    if self.peek().is_none() {
+                        return Err(error(self.src, open, "unclosed '('"));
+                    }
+                    return self.fail(&format!("expected ')', found {}", self.found()));
+                }
+                self.take();
+                Ok(expr)
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let field = match self.peek() {
+            Some(Token::Word(w)) => match Field::from_name(w) {
+                Some(field) => field,
+                None => {
+                    return self.fail(&format!(
+                        "unknown field {:?} (expected comm, syscall, pid, tid, cgroup, ppid, pidns, mntns, uid or gid)",
+                        w
+                    ))
+                }
+            },
+            _ => return self.fail(&format!("expected a field name, found {}", self.found())),
+        };
+        self.take();
+
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            _ => {
+                return self.fail(&format!(
+                    "expected a comparison after {}, found {}",
+                    field.name(),
+                    self.found()
+                ))
+            }
+        };
+        if field.is_string() && !matches!(op, Op::Eq | Op::Ne) {
+            return self.fail(&format!(
+                "{} can only be compared with '==' or '!='",
+                field.name()
+            ));
+        }
+        self.take();
+
+        let value = match (field.is_string(), self.peek()) {
+            (true, Some(Token::Str(s) | Token::Word(s))) => Value::Str(s.clone()),
+            (false, Some(Token::Word(w))) => match w.parse() {
+                Ok(n) => Value::Num(n),
+                Err(_) => {
+                    return self.fail(&format!("{} takes a number, found {:?}", field.name(), w))
+                }
+            },
+            (false, Some(Token::Str(s))) => {
+                return self.fail(&format!("{} takes a number, found {:?}", field.name(), s))
+            }
+            _ => {
+                return self.fail(&format!(
+                    "expected a value for {}, found {}",
+                    field.name(),
+                    self.found()
+                ))
+            }
+        };
+        self.take();
+        Ok(Expr::Cmp { field, op, value })
+    }
+}
+
+/// Parse `--filter`, if given. Used by both `trace` and `replay`.
+pub fn parse_arg(arg: Option<&str>) -> Result<Option<Expr>> {
+    match arg {
+        Some(src) if src.trim().is_empty() => bail!("--filter is empty"),
+        Some(src) => parse(src).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields<'a>(comm: &'a str, syscall: &'a str, pid: u32) -> Fields<'a> {
+        Fields {
+            pid,
+            tid: pid,
+            comm,
+            syscall,
+            cgroup_id: 7,
+            ppid: 1,
+            pidns_inum: 4026531836,
+            mntns_inum: 4026531841,
+            uid: 1000,
+            gid: 100,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_eval() {
+        let e = parse(r#"comm=="nginx" && (syscall=="openat" || syscall=="connect") && pid!=1"#)
+            .unwrap();
+        assert!(e.eval(&fields("nginx", "openat", 42)));
+        assert!(e.eval(&fields("nginx", "connect", 42)));
+        assert!(!e.eval(&fields("nginx", "read", 42)));
+        assert!(!e.eval(&fields("nginx", "openat", 1)));
+        assert!(!e.eval(&fields("bash", "openat", 42)));
+
+        let e = parse("pid >= 100 && pid < 200 && cgroup == 7 && !(tid == 150)").unwrap();
+        assert!(e.eval(&fields("x", "read", 100)));
+        assert!(!e.eval(&fields("x", "read", 150)));
+        assert!(!e.eval(&fields("x", "read", 200)));
+
+        let e = parse("ppid == 1 && pidns != 4026531836").unwrap();
+        assert!(!e.eval(&fields("x", "read", 100)));
+        assert!(parse("mntns == 4026531841")
+            .unwrap()
+            .eval(&fields("x", "read", 100)));
+        assert!(parse("uid >= 1000 && gid == 100")
+            .unwrap()
+            .eval(&fields("x", "read", 100)));
+        assert!(!parse("uid == 0").unwrap().eval(&fields("x", "read", 100)));
+
+        // Bare words and single quotes are strings
+        assert_eq!(
+            parse("syscall == openat").unwrap(),
+            parse("syscall == 'openat'").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_precedence_and_display() {
+        let e = parse("pid == 1 || pid == 2 && comm == 'a'").unwrap();
+        assert_eq!(e.to_string(), r#"(pid == 1 || (pid == 2 && comm == "a"))"#);
+        assert_eq!(parse(&e.to_string()).unwrap(), e);
+        assert_eq!(parse("!!pid==1").unwrap().to_string(), "!!pid == 1");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |src: &str| parse(src).unwrap_err().to_string();
+
+        assert_eq!(
+            err("pid == abc"),
+            "invalid filter at column 8: pid takes a number, found \"abc\"\n  pid == abc\n         ^"
+        );
+        assert!(err("user == 0").contains("unknown field \"user\""));
+        assert!(err("comm < 'a'").contains("only be compared with '==' or '!='"));
+        assert!(err("pid = 1").contains("use '=='"));
+        assert!(err("pid == 1 & pid == 2").contains("use '&&'"));
+        assert!(err("(pid == 1").contains("column 1: unclosed '('"));
+        assert!(err("pid == 1)").contains("unexpected ')'"));
+        assert!(err("comm == 'nginx").contains("unterminated string"));
+        assert!(err("pid ==").contains("found the end"));
+        assert!(err("").contains("expected a field name"));
+        assert!(parse_arg(Some("  ")).is_err());
+    }
+
+    #[test]
+    fn test_pushdown() {
+        let sets = |src: &str| {
+            let p = parse(src).unwrap().pushdown();
+            (
+                p.pids.map(|s| s.into_iter().collect::<Vec<_>>()),
+                p.syscalls.map(|s| s.into_iter().collect::<Vec<_>>()),
+            )
+        };
+        let names = |v: &[&str]| Some(v.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+
+        assert_eq!(
+            sets(r#"comm=="nginx" && (syscall=="openat" || syscall=="connect") && pid!=1"#),
+            (None, names(&["connect", "openat"]))
+        );
+        assert_eq!(sets("pid == 5 || pid == 6"), (Some(vec![5, 6]), None));
+        assert_eq!(sets("pid == 5 && pid == 6"), (Some(vec![]), None));
+        // Only one side constrains pid: any pid may match
+        assert_eq!(sets("pid == 5 || syscall == read"), (None, None));
+        // ! is pushed inward
+        assert_eq!(sets("!(pid != 5)"), (Some(vec![5]), None));
+        assert_eq!(
+            sets("!(pid != 5 || syscall != read)"),
+            (Some(vec![5]), names(&["read"]))
+        );
+        assert_eq!(sets("!(pid == 5)"), (None, None));
+        assert_eq!(sets("pid == 99999999999"), (Some(vec![]), None));
+    }
+}
@@ -0,0 +1,186 @@
+//! `trace --process NAME --follow-children`: the processes named NAME and
+//! everything they start.
+//!
+//! The kernel keeps the set in `FOLLOW_PIDS` once tracing runs (see
+//! `crates/ebpf-tool-ebpf/src/follow.rs`), but processes that already exist
+//! have forked before the programs were attached. This module seeds the
+//! map from /proc:
+//!
+//! ```text
+//! /proc/*/stat ─► parse_stat() ─► Proc { pid, ppid, comm } ─► seed(procs, &bash)
+//!                                                             ─► [(bash, MATCHED),
+//!                                                                 (its children, CHILD), ...]
+//! ```
+//!
+//! Attach the fork and exit programs before the scan: a process forked
+//! during the scan is then added by the kernel, the scan or both, but never
+//! missed.
+
+use crate::comm_match::CommMatch;
+use anyhow::{Context, Result};
+use ebpf_tool_common::{FOLLOW_CHILD, FOLLOW_MATCHED};
+use std::collections::{HashMap, HashSet};
+
+/// One process from /proc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proc {
+    pub pid: u32,
+    pub ppid: u32,
+    pub comm: String,
+}
+
+/// Parse a `/proc/<pid>/stat` line.
+///
+/// The name is in parentheses and may itself contain spaces and `)`, so it
+/// ends at the last `)`. None for anything else.
+pub fn parse_stat(line: &str) -> Option<Proc> {
+    let open = line.find('(')?;
+    let close = line.rfind(')')?;
+    if close < open {
+        return None;
+    }
+    let pid = line[..open].trim().parse().ok()?;
+    let comm = line[open + 1..close].to_string();
+    // After the name: state, then ppid
+    let mut rest = line[close + 1..].split_whitespace();
+    let _state = rest.next()?;
+    let ppid = rest.next()?.parse().ok()?;
+    Some(Proc { pid, ppid, comm })
+}
+
+/// All processes in /proc. Processes that exit during the scan are skipped.
+pub fn read_proc() -> Result<Vec<Proc>> {
+    let mut procs = Vec::new();
+    for entry in std::fs::read_dir("/proc").context("Failed to read /proc")? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        if !name.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        if let Ok(line) = std::fs::read_to_string(entry.path().join("stat")) {
+            procs.extend(parse_stat(&line));
+        }
+    }
+    Ok(procs)
+}
+
+/// The `FOLLOW_PIDS` entries for `pattern`: every process whose name
+/// matches (`FOLLOW_MATCHED`) and all their descendants (`FOLLOW_CHILD`),
+/// sorted by pid.
+pub fn seed(procs: &[Proc], pattern: &CommMatch) -> Vec<(u32, u8)> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for p in procs {
+        children.entry(p.ppid).or_default().push(p.pid);
+    }
+
+    // Matches first, so a match below another match keeps FOLLOW_MATCHED
+    let mut stack: Vec<u32> = procs
+        .iter()
+        .filter(|p| pattern.matches(&p.comm))
+        .map(|p| p.pid)
+        .collect();
+    let mut seen: HashSet<u32> = stack.iter().copied().collect();
+    let mut entries: Vec<_> = stack.iter().map(|&pid| (pid, FOLLOW_MATCHED)).collect();
+    while let Some(pid) = stack.pop() {
+        for &child in children.get(&pid).into_iter().flatten() {
+            if seen.insert(child) {
+                entries.push((child, FOLLOW_CHILD));
+                stack.push(child);
+            }
+        }
+    }
+    entries.sort_unstable();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(text: &str) -> CommMatch {
+        CommMatch::parse(text).unwrap()
+    }
+
+    fn proc(pid: u32, ppid: u32, comm: &str) -> Proc {
+        Proc {
+            pid,
+            ppid,
+            comm: comm.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_stat_handles_odd_names() {
+        assert_eq!(
+            parse_stat("4242 (bash) S 4200 4242 4200 34816 0"),
+            Some(proc(4242, 4200, "bash"))
+        );
+        assert_eq!(
+            parse_stat("17 (my (odd) name) R 1 17 17 0 -1"),
+            Some(proc(17, 1, "my (odd) name"))
+        );
+        assert_eq!(parse_stat("garbage"), None);
+        assert_eq!(parse_stat("12 (x)"), None);
+    }
+
+    #[test]
+    fn test_seed_adds_descendants() {
+        let procs = [
+            proc(1, 0, "systemd"),
+            proc(100, 1, "bash"),
+            proc(101, 100, "make"),
+            proc(102, 101, "cc1"),
+            proc(200, 1, "sshd"),
+            proc(201, 200, "bash"),
+            proc(300, 1, "cron"),
+        ];
+        assert_eq!(
+            seed(&procs, &pattern("bash")),
+            [
+                (100, FOLLOW_MATCHED),
+                (101, FOLLOW_CHILD),
+                (102, FOLLOW_CHILD),
+                (201, FOLLOW_MATCHED),
+            ]
+        );
+        assert!(seed(&procs, &pattern("nginx")).is_empty());
+    }
+
+    #[test]
+    fn test_seed_keeps_nested_matches() {
+        // bash started from bash: both are MATCHED, and nothing twice
+        let procs = [
+            proc(10, 1, "bash"),
+            proc(11, 10, "bash"),
+            proc(12, 11, "ls"),
+        ];
+        assert_eq!(
+            seed(&procs, &pattern("bash")),
+            [
+                (10, FOLLOW_MATCHED),
+                (11, FOLLOW_MATCHED),
+                (12, FOLLOW_CHILD)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_seed_with_prefix_pattern() {
+        let procs = [
+            proc(5, 1, "systemd-resolve"),
+            proc(6, 1, "postgres"),
+            proc(7, 6, "postgres_export"),
+            proc(8, 7, "sh"),
+        ];
+        assert_eq!(
+            seed(&procs, &pattern("systemd-resolved")),
+            [(5, FOLLOW_MATCHED)]
+        );
+        assert_eq!(
+            seed(&procs, &pattern("postgres.*")),
+            [(6, FOLLOW_MATCHED), (7, FOLLOW_MATCHED), (8, FOLLOW_CHILD)]
+        );
+    }
+}
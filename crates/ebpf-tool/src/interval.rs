@@ -0,0 +1,298 @@
+//! `--interval N`: a summary every N seconds of a long `trace` or `stats`.
+//!
+//! A `trace -d 0` session prints one line per event and its summary only at
+//! Ctrl+C, which may be hours away. With `--interval 5` it also prints what
+//! happened in each 5 s stretch:
+//!
+//! ```text
+//! --- interval 3 (+10.0s..+15.0s): 48213 events, 9642.6/s, 0 lost ---
+//! SYSCALL          COUNT      /SEC
+//! read             20113    4022.6
+//! write            18004    3600.8
+//! ```
+//!
+//! Both commands already have cumulative counts: `trace` the totals of its
+//! [`Backfill`](crate::backfill::Backfill) (exact even with drops), `stats`
+//! the `SYSCALL_COUNTS` snapshots. [`Intervals`] keeps the counts of the
+//! last cut and hands out the difference:
+//!
+//! ```text
+//! totals (cumulative) ─► Intervals::tick(totals, now) ─► due? ─► Interval {
+//!                                                                  counts: totals - last cut,
+//!                                                                  lost since last cut }
+//! ```
+//!
+//! Cuts happen on the caller's timer (the backfill's 1 s tick, or the
+//! `stats` refresh), so an interval can end up to one tick late. The
+//! header shows the real bounds, and the rates use them.
+//!
+//! `stats --diff N` is the one-shot form: two snapshots N seconds apart,
+//! and only what changed in between ([`Diff`]). On a busy machine, or with
+//! the pinned map of a session that has been counting for days, that is the
+//! part that says what the system is doing now.
+
+use crate::backfill::{self, Counts};
+use ebpf_tool_common::SyscallKey;
+use std::time::Duration;
+
+/// The counts of one interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval {
+    /// 1 for the first interval of the run
+    pub index: u64,
+    /// Since the start of the run
+    pub start: Duration,
+    pub end: Duration,
+    /// Syscalls by (pid, syscall number) added in the interval
+    pub counts: Counts,
+    /// Events lost from the perf buffers in the interval (always 0 for
+    /// `stats`, which reads no events)
+    pub lost: u64,
+}
+
+impl Interval {
+    /// All syscalls of the interval.
+    pub fn events(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Syscalls per second over the interval.
+    pub fn per_sec(&self) -> f64 {
+        match self.elapsed().as_secs_f64() {
+            s if s > 0.0 => self.events() as f64 / s,
+            _ => 0.0,
+        }
+    }
+
+    /// The counts as `SYSCALL_COUNTS` entries, sorted by key, for the
+    /// grouping and table functions of `stats`.
+    pub fn entries(&self) -> Vec<(SyscallKey, u64)> {
+        let mut entries: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(&(pid, nr), &count)| (SyscallKey::new(pid, nr), count))
+            .collect();
+        entries.sort_by_key(|(key, _)| (key.pid, key.syscall_nr));
+        entries
+    }
+
+    /// The line printed above the interval's table.
+    pub fn header(&self) -> String {
+        format!(
+            "--- interval {} (+{:.1}s..+{:.1}s): {} events, {:.1}/s, {} lost ---",
+            self.index,
+            self.start.as_secs_f64(),
+            self.end.as_secs_f64(),
+            self.events(),
+            self.per_sec(),
+            self.lost
+        )
+    }
+}
+
+/// Cuts cumulative counts into [`Interval`]s of about `every`.
+#[derive(Debug)]
+pub struct Intervals {
+    every: Duration,
+    index: u64,
+    start: Duration,
+    last: Counts,
+    lost: u64,
+}
+
+impl Intervals {
+    /// Start at time 0 of the run, from `baseline`: what the counts already
+    /// were (a pinned map, or an empty `Counts` for the backfill's totals).
+    pub fn new(every: Duration, baseline: Counts) -> Self {
+        Self {
+            every,
+            index: 0,
+            start: Duration::ZERO,
+            last: baseline,
+            lost: 0,
+        }
+    }
+
+    pub fn every(&self) -> Duration {
+        self.every
+    }
+
+    /// Record a lost-events count from a perf buffer.
+    pub fn lost(&mut self, count: u64) {
+        self.lost += count;
+    }
+
+    /// The interval ending at `now`, if it is at least `every` long.
+    pub fn tick(&mut self, totals: &Counts, now: Duration) -> Option<Interval> {
+        if now.saturating_sub(self.start) < self.every {
+            return None;
+        }
+        Some(self.cut(totals, now))
+    }
+
+    /// The last, usually shorter, interval when the run ends; None if
+    /// nothing happened since the previous cut.
+    pub fn finish(mut self, totals: &Counts, now: Duration) -> Option<Interval> {
+        if now <= self.start {
+            return None;
+        }
+        let interval = self.cut(totals, now);
+        (interval.events() > 0 || interval.lost > 0).then_some(interval)
+    }
+
+    fn cut(&mut self, totals: &Counts, now: Duration) -> Interval {
+        self.index += 1;
+        let interval = Interval {
+            index: self.index,
+            start: self.start,
+            end: now,
+            counts: backfill::delta(&self.last, totals),
+            lost: std::mem::take(&mut self.lost),
+        };
+        self.start = now;
+        self.last = totals.clone();
+        interval
+    }
+}
+
+/// `stats --diff N`: the counts added between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    /// The one interval between the snapshots
+    pub interval: Interval,
+    /// Keys of the first snapshot evicted before the second (gone, or
+    /// counting again from 0): what they counted in between is missing
+    pub evicted: usize,
+}
+
+impl Diff {
+    /// Compare `before` and `after`, taken `elapsed` apart.
+    pub fn new(before: &Counts, after: &Counts, elapsed: Duration) -> Self {
+        let evicted = before
+            .iter()
+            .filter(|(key, &count)| after.get(key).is_none_or(|&now| now < count))
+            .count();
+        Self {
+            interval: Interval {
+                index: 1,
+                start: Duration::ZERO,
+                end: elapsed,
+                counts: backfill::delta(before, after),
+                lost: 0,
+            },
+            evicted,
+        }
+    }
+
+    /// The line printed above the table.
+    pub fn header(&self) -> String {
+        format!(
+            "--- diff over {:.1}s: {} syscalls, {:.1}/s, {} keys evicted ---",
+            self.interval.elapsed().as_secs_f64(),
+            self.interval.events(),
+            self.interval.per_sec(),
+            self.evicted
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn test_tick_cuts_every_interval() {
+        let mut intervals = Intervals::new(secs(5), Counts::from([((1, 0), 100)]));
+        let mut totals = Counts::from([((1, 0), 130), ((2, 1), 10)]);
+        assert_eq!(intervals.tick(&totals, secs(4)), None);
+
+        intervals.lost(7);
+        let first = intervals.tick(&totals, secs(5)).unwrap();
+        assert_eq!(first.index, 1);
+        assert_eq!((first.start, first.end), (secs(0), secs(5)));
+        assert_eq!(first.events(), 40);
+        assert_eq!(first.lost, 7);
+        assert_eq!(first.per_sec(), 8.0);
+
+        // A late tick: the interval is longer, and its rate says so
+        *totals.get_mut(&(1, 0)).unwrap() += 60;
+        let second = intervals.tick(&totals, secs(11)).unwrap();
+        assert_eq!(
+            (second.index, second.start, second.end),
+            (2, secs(5), secs(11))
+        );
+        assert_eq!(second.counts, Counts::from([((1, 0), 60)]));
+        assert_eq!(second.lost, 0);
+        assert_eq!(second.per_sec(), 10.0);
+    }
+
+    #[test]
+    fn test_finish_reports_partial_interval() {
+        let mut intervals = Intervals::new(secs(5), Counts::new());
+        let totals = Counts::from([((1, 0), 3)]);
+        intervals.tick(&totals, secs(5)).unwrap();
+
+        let last = intervals
+            .finish(&Counts::from([((1, 0), 5)]), secs(7))
+            .unwrap();
+        assert_eq!((last.index, last.start, last.end), (2, secs(5), secs(7)));
+        assert_eq!(last.events(), 2);
+
+        // Nothing since the last cut: no empty table at exit
+        let mut quiet = Intervals::new(secs(5), Counts::new());
+        quiet.tick(&totals, secs(5)).unwrap();
+        assert_eq!(quiet.finish(&totals, secs(6)), None);
+    }
+
+    #[test]
+    fn test_entries_and_header() {
+        let interval = Interval {
+            index: 3,
+            start: secs(10),
+            end: secs(15),
+            counts: Counts::from([((20, 1), 5), ((10, 0), 20)]),
+            lost: 2,
+        };
+        let keys: Vec<_> = interval
+            .entries()
+            .iter()
+            .map(|(k, n)| (k.pid, k.syscall_nr, *n))
+            .collect();
+        assert_eq!(keys, [(10, 0, 20), (20, 1, 5)]);
+        assert_eq!(
+            interval.header(),
+            "--- interval 3 (+10.0s..+15.0s): 25 events, 5.0/s, 2 lost ---"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = Counts::from([((1, 0), 1000), ((2, 1), 50), ((3, 0), 7)]);
+        // pid 1 kept counting, pid 2 was evicted and inserted again, pid 3
+        // was evicted, pid 4 is new
+        let after = Counts::from([((1, 0), 1040), ((2, 1), 5), ((4, 0), 15)]);
+        let diff = Diff::new(&before, &after, secs(2));
+        assert_eq!(
+            diff.interval.counts,
+            Counts::from([((1, 0), 40), ((2, 1), 5), ((4, 0), 15)])
+        );
+        assert_eq!(diff.evicted, 2);
+        assert_eq!(
+            diff.header(),
+            "--- diff over 2.0s: 60 syscalls, 30.0/s, 2 keys evicted ---"
+        );
+
+        // Nothing changed: an empty table, not an error
+        let idle = Diff::new(&after, &after, secs(2));
+        assert_eq!(idle.interval.events(), 0);
+        assert_eq!(idle.evicted, 0);
+    }
+}
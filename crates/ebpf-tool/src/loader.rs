@@ -0,0 +1,272 @@
+//! Which eBPF object a subcommand loads, and where its bytes come from.
+//!
+//! build.rs embeds every binary of the eBPF crate as one object (see
+//! `$OUT_DIR/objects.rs`). A subcommand asks for the object of its program
+//! group by name, and gets the embedded object of that name, or the
+//! default one, which holds every program not split out yet:
+//!
+//! ```text
+//! loader.load("lsm")     -> embedded "lsm" if the eBPF crate has that [[bin]]
+//!                        -> otherwise embedded "ebpf-tool-ebpf" (src/main.rs)
+//! ```
+//!
+//! So splitting a group into its own object (smaller, loads faster, fails
+//! the verifier alone) needs a `[[bin]]` in `crates/ebpf-tool-ebpf` and
+//! nothing here.
+//!
+//! `--bpf-object PATH` replaces the embedded objects without rebuilding
+//! userspace, for iterating on the eBPF crate:
+//!
+//! ```text
+//! --bpf-object prog.o       every subcommand loads prog.o
+//! --bpf-object DIR          DIR/<name> if it exists, else DIR/ebpf-tool-ebpf
+//!                           (e.g. crates/ebpf-tool-ebpf/target/bpfel-unknown-none/release)
+//! ```
+
+use crate::btf;
+use anyhow::{bail, Context, Result};
+use ebpf_tool_common::KERNEL_OFFSETS_GLOBAL;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+include!(concat!(env!("EBPF_OUT_DIR"), "/objects.rs"));
+
+/// The eBPF crate's own binary: every program that has no object of its own.
+pub const DEFAULT_OBJECT: &str = "ebpf-tool-ebpf";
+
+/// ELF machine type of BPF objects.
+const EM_BPF: u16 = 247;
+
+/// Where an object came from, for log lines and errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Embedded at build time under this name
+    Embedded(&'static str),
+    /// Read from `--bpf-object`
+    File(PathBuf),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Embedded(name) => write!(f, "embedded object {}", name),
+            Origin::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The bytes of one eBPF object, 8-byte aligned as the ELF parser needs
+/// (the same reason `include_bytes_aligned!` exists).
+#[derive(Debug)]
+pub struct Object {
+    origin: Origin,
+    bytes: Bytes,
+}
+
+#[derive(Debug)]
+enum Bytes {
+    Static(&'static [u8]),
+    /// A file read into u64s, so the start is aligned
+    Owned(Vec<u64>, usize),
+}
+
+impl Object {
+    pub fn origin(&self) -> &Origin {
+        &self.origin
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        match &self.bytes {
+            Bytes::Static(bytes) => bytes,
+            Bytes::Owned(words, len) => {
+                // SAFETY: `words` holds at least `len` initialized bytes
+                unsafe { std::slice::from_raw_parts(words.as_ptr().cast::<u8>(), *len) }
+            }
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("cannot read eBPF object {}", path.display()))?;
+        let words = data
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_ne_bytes(word)
+            })
+            .collect();
+        Ok(Self {
+            origin: Origin::File(path.to_path_buf()),
+            bytes: Bytes::Owned(words, data.len()),
+        })
+    }
+}
+
+/// Picks and loads objects for the subcommands (`--bpf-object`).
+#[derive(Debug, Clone, Default)]
+pub struct Loader {
+    override_path: Option<PathBuf>,
+}
+
+impl Loader {
+    /// Load from `override_path` if set, else from the embedded objects.
+    pub fn new(override_path: Option<PathBuf>) -> Self {
+        Self { override_path }
+    }
+
+    /// The object `name`, checked to be a BPF ELF object.
+    pub fn object(&self, name: &str) -> Result<Object> {
+        let object = self.unchecked_object(name)?;
+        check_object(&object)?;
+        Ok(object)
+    }
+
+    /// The object `name` as found, for `check` to diagnose.
+    pub fn unchecked_object(&self, name: &str) -> Result<Object> {
+        Ok(match &self.override_path {
+            Some(path) if path.is_dir() => {
+                let named = path.join(name);
+                Object::read(&if named.is_file() {
+                    named
+                } else {
+                    path.join(DEFAULT_OBJECT)
+                })?
+            }
+            Some(path) => Object::read(path)?,
+            None => {
+                let (name, bytes) = find_embedded(name)
+                    .or_else(|| find_embedded(DEFAULT_OBJECT))
+                    .context("no eBPF objects were embedded at build time")?;
+                Object {
+                    origin: Origin::Embedded(name),
+                    bytes: Bytes::Static(bytes),
+                }
+            }
+        })
+    }
+
+    /// Run the pre-flight checks, then parse `name` and create its maps,
+    /// with the running kernel's struct offsets in `KERNEL_OFFSETS` (see
+    /// `btf.rs`). The programs are loaded into the kernel one by one
+    /// afterwards, with `program.load()` (see `verifier::explain`).
+    pub fn load(&self, name: &str) -> Result<aya::Ebpf> {
+        crate::preflight::before_load()?;
+        let object = self.object(name)?;
+        log::debug!(
+            "Loading {} ({} bytes)",
+            object.origin(),
+            object.bytes().len()
+        );
+        let offsets = btf::GlobalOffsets(btf::running_kernel_offsets());
+        aya::EbpfLoader::new()
+            // Not every object reads kernel structs
+            .set_global(KERNEL_OFFSETS_GLOBAL, &offsets, false)
+            .load(object.bytes())
+            .with_context(|| format!("failed to load {}", object.origin()))
+    }
+}
+
+/// The embedded object `name`, if build.rs embedded one.
+pub fn embedded(name: &str) -> Option<&'static [u8]> {
+    find_embedded(name).map(|(_, bytes)| bytes)
+}
+
+fn find_embedded(name: &str) -> Option<(&'static str, &'static [u8])> {
+    EMBEDDED.iter().find(|(n, _)| *n == name).copied()
+}
+
+/// Names of all embedded objects, default first.
+pub fn embedded_names() -> impl Iterator<Item = &'static str> {
+    EMBEDDED.iter().map(|(name, _)| *name)
+}
+
+/// True if `bytes` is an ELF object for the BPF machine.
+pub fn is_bpf_elf(bytes: &[u8]) -> bool {
+    let machine = bytes.get(18..20).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    bytes.starts_with(b"\x7fELF") && machine == Some(EM_BPF)
+}
+
+fn check_object(object: &Object) -> Result<()> {
+    let bytes = object.bytes();
+    if bytes.is_empty() {
+        bail!(
+            "{} is empty: the eBPF programs were not built (see the build.rs warnings \
+             and `ebpf-tool check`), or pass --bpf-object",
+            object.origin()
+        );
+    }
+    if !is_bpf_elf(bytes) {
+        bail!(
+            "{} is not a BPF ELF object (build the eBPF crate for bpfel-unknown-none)",
+            object.origin()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The start of an ELF header for EM_BPF, followed by padding.
+    fn bpf_elf(extra: usize) -> Vec<u8> {
+        let mut bytes = b"\x7fELF\x02\x01\x01".to_vec();
+        bytes.resize(18, 0);
+        bytes.extend(EM_BPF.to_le_bytes());
+        bytes.resize(20 + extra, 0);
+        bytes
+    }
+
+    #[test]
+    fn test_is_bpf_elf() {
+        assert!(is_bpf_elf(&bpf_elf(0)));
+        let mut x86 = bpf_elf(0);
+        x86[18] = 62;
+        assert!(!is_bpf_elf(&x86));
+        assert!(!is_bpf_elf(b""));
+        assert!(!is_bpf_elf(b"#!/bin/sh\n"));
+    }
+
+    #[test]
+    fn test_embedded_table_has_default() {
+        assert_eq!(embedded_names().next(), Some(DEFAULT_OBJECT));
+        assert!(embedded(DEFAULT_OBJECT).is_some());
+        assert_eq!(embedded("no-such-object"), None);
+    }
+
+    #[test]
+    fn test_file_override_is_aligned_and_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prog.o");
+        std::fs::write(&path, bpf_elf(13)).unwrap();
+
+        let object = Loader::new(Some(path.clone())).object("lsm").unwrap();
+        assert_eq!(object.origin(), &Origin::File(path.clone()));
+        assert_eq!(object.bytes(), bpf_elf(13).as_slice());
+        assert_eq!(object.bytes().as_ptr() as usize % 8, 0);
+
+        std::fs::write(&path, b"not an object").unwrap();
+        let err = Loader::new(Some(path)).object("lsm").unwrap_err();
+        assert!(err.to_string().contains("not a BPF ELF object"), "{err}");
+    }
+
+    #[test]
+    fn test_directory_override_prefers_named_object() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(DEFAULT_OBJECT), bpf_elf(0)).unwrap();
+        std::fs::write(dir.path().join("lsm"), bpf_elf(8)).unwrap();
+        let loader = Loader::new(Some(dir.path().to_path_buf()));
+
+        let lsm = loader.object("lsm").unwrap();
+        assert_eq!(lsm.origin(), &Origin::File(dir.path().join("lsm")));
+        let kprobe = loader.object("kprobe").unwrap();
+        assert_eq!(
+            kprobe.origin(),
+            &Origin::File(dir.path().join(DEFAULT_OBJECT))
+        );
+
+        let missing = Loader::new(Some(dir.path().join("nope.o")));
+        assert!(missing.object("lsm").is_err());
+    }
+}
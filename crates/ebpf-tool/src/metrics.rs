@@ -0,0 +1,361 @@
+//! Prometheus metrics for `ebpf-tool export`.
+//!
+//! `export` keeps the syscall counting programs attached and answers
+//! `GET /metrics` with the counts in the Prometheus text format:
+//!
+//! ```text
+//! # HELP ebpf_tool_syscalls_total Syscalls counted in the kernel, by syscall name.
+//! # TYPE ebpf_tool_syscalls_total counter
+//! ebpf_tool_syscalls_total{syscall="read"} 61233
+//! ebpf_tool_comm_syscalls_total{comm="nginx"} 51100
+//! ebpf_tool_map_evictions_total{map="SYSCALL_COUNTS"} 0
+//! ```
+//!
+//! This module has no eBPF in it: the caller reads the maps and hands the
+//! per-key counts to [`Counters`], which turns them into totals that only
+//! ever grow (as Prometheus expects of a counter) even when the LRU map
+//! evicts keys. [`serve`] is a deliberately small HTTP/1.1 server: one
+//! connection at a time is plenty for a scraper every 15 seconds.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Requests larger than this are rejected instead of buffered.
+const MAX_REQUEST_LEN: usize = 8192;
+
+/// How long a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Monotonic per-label totals built from per-key counts.
+///
+/// The counting maps hold one entry per (pid, syscall). When the LRU map is
+/// full it evicts entries, and their counts vanish: summing the live entries
+/// would make a counter go down, which Prometheus reads as a restart. So
+/// the last value of a key that disappears is kept in its label's
+/// `retired` total, and only the labels, not the keys, are remembered.
+#[derive(Debug, Clone)]
+pub struct Counters<K> {
+    /// Last count and label of every live key
+    live: HashMap<K, (String, u64)>,
+    /// Counts of keys that were evicted (or whose PID went away)
+    retired: HashMap<String, u64>,
+}
+
+impl<K> Default for Counters<K> {
+    fn default() -> Self {
+        Self {
+            live: HashMap::new(),
+            retired: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> Counters<K> {
+    /// Apply one read of the map: every key currently in it with its count.
+    ///
+    /// `label` names a key's series; it is asked once, when the key is first
+    /// seen, so a key keeps its label even if e.g. its process is renamed.
+    pub fn update(&mut self, current: &[(K, u64)], mut label: impl FnMut(&K) -> String) {
+        let mut live = HashMap::with_capacity(current.len());
+        for (key, count) in current {
+            let entry = match self.live.remove(key) {
+                // A smaller count: the key was evicted and inserted again
+                // between two reads. Its old count is not lost.
+                Some((l, last)) if *count < last => {
+                    *self.retired.entry(l.clone()).or_default() += last;
+                    (l, *count)
+                }
+                Some((l, _)) => (l, *count),
+                None => (label(key), *count),
+            };
+            live.insert(key.clone(), entry);
+        }
+        // What is left was evicted since the last read
+        for (_, (l, last)) in self.live.drain() {
+            *self.retired.entry(l).or_default() += last;
+        }
+        self.live = live;
+    }
+
+    /// Total per label, sorted by label for stable output.
+    pub fn totals(&self) -> Vec<(String, u64)> {
+        let mut totals = self.retired.clone();
+        for (l, count) in self.live.values() {
+            *totals.entry(l.clone()).or_default() += count;
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort();
+        totals
+    }
+}
+
+/// Occupancy of one counting map, for the map gauges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapMetrics {
+    pub name: String,
+    pub entries: u64,
+    pub max_entries: u32,
+    pub evictions: u64,
+}
+
+/// Everything one scrape reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    /// (syscall name, total)
+    pub syscalls: Vec<(String, u64)>,
+    /// (comm, total)
+    pub comms: Vec<(String, u64)>,
+    pub maps: Vec<MapMetrics>,
+}
+
+impl Snapshot {
+    /// The snapshot in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        family(
+            &mut out,
+            "ebpf_tool_syscalls_total",
+            "counter",
+            "Syscalls counted in the kernel, by syscall name.",
+            self.syscalls
+                .iter()
+                .map(|(s, n)| (("syscall", s.as_str()), *n)),
+        );
+        family(
+            &mut out,
+            "ebpf_tool_comm_syscalls_total",
+            "counter",
+            "Syscalls counted in the kernel, by process name.",
+            self.comms.iter().map(|(c, n)| (("comm", c.as_str()), *n)),
+        );
+        family(
+            &mut out,
+            "ebpf_tool_map_entries",
+            "gauge",
+            "Keys currently in the counting map.",
+            self.maps
+                .iter()
+                .map(|m| (("map", m.name.as_str()), m.entries)),
+        );
+        family(
+            &mut out,
+            "ebpf_tool_map_max_entries",
+            "gauge",
+            "Capacity of the counting map.",
+            self.maps
+                .iter()
+                .map(|m| (("map", m.name.as_str()), m.max_entries as u64)),
+        );
+        family(
+            &mut out,
+            "ebpf_tool_map_evictions_total",
+            "counter",
+            "Keys the kernel evicted from a full counting map.",
+            self.maps
+                .iter()
+                .map(|m| (("map", m.name.as_str()), m.evictions)),
+        );
+        out
+    }
+}
+
+/// Append one metric family: HELP, TYPE, then one sample per label value.
+fn family<'a>(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: impl Iterator<Item = ((&'a str, &'a str), u64)>,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for ((label, value), n) in samples {
+        let _ = writeln!(out, "{}{{{}=\"{}\"}} {}", name, label, escape(value), n);
+    }
+}
+
+/// Escape a label value: backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The full HTTP response to `request` (everything up to the blank line).
+///
+/// `GET /metrics` calls `scrape` for the body; a scrape error is a 500 with
+/// the error as the body, so it shows up on Prometheus' targets page.
+pub fn respond(request: &str, scrape: impl FnOnce() -> Result<String>) -> Vec<u8> {
+    let mut words = request.lines().next().unwrap_or("").split_whitespace();
+    let (method, path) = (words.next().unwrap_or(""), words.next().unwrap_or(""));
+    // Ignore a query string: some scrapers add one
+    let path = path.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => match scrape() {
+            Ok(body) => ("200 OK", CONTENT_TYPE, body),
+            Err(e) => (
+                "500 Internal Server Error",
+                "text/plain",
+                format!("{:#}\n", e),
+            ),
+        },
+        ("GET", "/") => (
+            "200 OK",
+            "text/plain",
+            "ebpf-tool export: metrics at /metrics\n".to_string(),
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "only GET is supported\n".to_string(),
+        ),
+    };
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+/// Serve `/metrics` on `addr` until the future is dropped (e.g. by a
+/// `tokio::select!` on Ctrl+C). Connections are handled one at a time, and
+/// `scrape` runs once for each request for `/metrics`.
+pub async fn serve(addr: SocketAddr, mut scrape: impl FnMut() -> Result<String>) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to listen on {}: {}", addr, e))?;
+    log::debug!("Listening on {}", listener.local_addr()?);
+    loop {
+        let (mut stream, peer) = listener.accept().await?;
+        let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+            Ok(Ok(request)) => request,
+            Ok(Err(e)) => {
+                log::debug!("{}: {}", peer, e);
+                continue;
+            }
+            Err(_) => {
+                log::debug!("{}: request timed out", peer);
+                continue;
+            }
+        };
+        let response = respond(&request, &mut scrape);
+        if let Err(e) = stream.write_all(&response).await {
+            log::debug!("{}: {}", peer, e);
+        }
+    }
+}
+
+/// Read up to the end of the request headers. The body, if any, is ignored.
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Result<String> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        anyhow::ensure!(buf.len() < MAX_REQUEST_LEN, "request too large");
+        let n = stream.read(&mut chunk).await?;
+        anyhow::ensure!(n > 0, "connection closed before the end of the request");
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_survive_evictions() {
+        let mut c = Counters::default();
+        let label = |k: &u32| if *k < 10 { "read" } else { "write" }.to_string();
+
+        c.update(&[(1, 5), (2, 3), (11, 7)], label);
+        assert_eq!(c.totals(), [("read".into(), 8), ("write".into(), 7)]);
+
+        // Key 2 evicted, key 1 counting on
+        c.update(&[(1, 6), (11, 7)], label);
+        assert_eq!(c.totals(), [("read".into(), 9), ("write".into(), 7)]);
+
+        // Key 11 evicted and inserted again: its old 7 is kept
+        c.update(&[(1, 6), (11, 2)], label);
+        assert_eq!(c.totals(), [("read".into(), 9), ("write".into(), 9)]);
+    }
+
+    #[test]
+    fn test_label_is_fixed_at_first_sight() {
+        let mut c = Counters::default();
+        c.update(&[(42u32, 1)], |_| "bash".to_string());
+        c.update(&[(42u32, 4)], |_| "renamed".to_string());
+        assert_eq!(c.totals(), [("bash".into(), 4)]);
+    }
+
+    #[test]
+    fn test_render() {
+        let snapshot = Snapshot {
+            syscalls: vec![("read".into(), 10)],
+            comms: vec![("my \"app\"\\".into(), 3)],
+            maps: vec![MapMetrics {
+                name: "SYSCALL_COUNTS".into(),
+                entries: 7,
+                max_entries: 10240,
+                evictions: 0,
+            }],
+        };
+        let text = snapshot.render();
+        assert!(text.contains("# TYPE ebpf_tool_syscalls_total counter\n"));
+        assert!(text.contains("ebpf_tool_syscalls_total{syscall=\"read\"} 10\n"));
+        assert!(text.contains("ebpf_tool_comm_syscalls_total{comm=\"my \\\"app\\\"\\\\\"} 3\n"));
+        assert!(text.contains("ebpf_tool_map_max_entries{map=\"SYSCALL_COUNTS\"} 10240\n"));
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            assert!(line.starts_with("ebpf_tool_"), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_respond() {
+        let ok = String::from_utf8(respond("GET /metrics HTTP/1.1\r\n\r\n", || {
+            Ok("x 1\n".into())
+        }))
+        .unwrap();
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"), "{ok}");
+        assert!(ok.contains(CONTENT_TYPE));
+        assert!(ok.ends_with("\r\n\r\nx 1\n"));
+
+        let failed = String::from_utf8(respond("GET /metrics?x=1 HTTP/1.1\r\n", || {
+            anyhow::bail!("map read failed")
+        }))
+        .unwrap();
+        assert!(failed.starts_with("HTTP/1.1 500"), "{failed}");
+        assert!(failed.ends_with("map read failed\n"));
+
+        let status = |req: &str| {
+            let r = String::from_utf8(respond(req, || unreachable!())).unwrap();
+            r.split("\r\n").next().unwrap().to_string()
+        };
+        assert_eq!(status("GET /other HTTP/1.1\r\n"), "HTTP/1.1 404 Not Found");
+        assert_eq!(
+            status("POST /metrics HTTP/1.1\r\n"),
+            "HTTP/1.1 405 Method Not Allowed"
+        );
+        assert_eq!(status(""), "HTTP/1.1 405 Method Not Allowed");
+    }
+}
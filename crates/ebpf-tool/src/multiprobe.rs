@@ -0,0 +1,360 @@
+//! Extra probes for `trace`: probe specs and a timestamp-ordered merge.
+//!
+//! `trace --kprobe vfs_read --tracepoint sched:sched_process_exec --uprobe
+//! /usr/bin/bash:readline` reads several perf arrays (`EVENTS`,
+//! `SYSCALL_BATCHES`, `PROBE_EVENTS`), each with one buffer per CPU. Every
+//! buffer is in time order on its own, but the buffers are read by separate
+//! tasks, so lines printed as they arrive would jump back and forth in time.
+//! [`Merger`] puts them back in order:
+//!
+//! ```text
+//! source 0 (EVENTS, cpu 0)        ─┐
+//! source 1 (EVENTS, cpu 1)        ─┼─► Merger ─► ready(): every event no source
+//! source 2 (PROBE_EVENTS, cpu 0)  ─┘              can still undercut, oldest first
+//! ```
+//!
+//! An event is released once every source has sent something newer, or once
+//! it is older than the newest event by the hold time, so an idle CPU does
+//! not hold back the output. Like `pairing`, time is the events' own clock.
+
+use anyhow::{bail, Context, Result};
+use ebpf_tool_common::{PROBE_KIND_KPROBE, PROBE_KIND_TRACEPOINT, PROBE_KIND_UPROBE};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+
+/// How long an event waits for older events from other sources.
+///
+/// Perf buffers are read within milliseconds of the wakeup; 50 ms keeps the
+/// output close to live and covers a busy reader task.
+pub const DEFAULT_MERGE_HOLD_NS: u64 = 50_000_000;
+
+/// One `--kprobe`, `--tracepoint` or `--uprobe` argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeSpec {
+    Kprobe { function: String },
+    Tracepoint { category: String, name: String },
+    Uprobe { binary: PathBuf, function: String },
+}
+
+impl ProbeSpec {
+    /// Parse `--kprobe FUNCTION`, e.g. `vfs_read` or `tcp_v4_connect`.
+    pub fn kprobe(arg: &str) -> Result<Self> {
+        check_symbol("kprobe function", arg)?;
+        Ok(ProbeSpec::Kprobe {
+            function: arg.to_string(),
+        })
+    }
+
+    /// Parse `--tracepoint CATEGORY:NAME`; `CATEGORY/NAME`, as under
+    /// `/sys/kernel/tracing/events`, works too.
+    pub fn tracepoint(arg: &str) -> Result<Self> {
+        let (category, name) = arg
+            .split_once(':')
+            .or_else(|| arg.split_once('/'))
+            .with_context(|| format!("expected CATEGORY:NAME, got {:?}", arg))?;
+        check_symbol("tracepoint category", category)?;
+        check_symbol("tracepoint name", name)?;
+        Ok(ProbeSpec::Tracepoint {
+            category: category.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    /// Parse `--uprobe BINARY:FUNCTION`, e.g. `/usr/bin/bash:readline`.
+    /// Library names (`libc:malloc`) are resolved by aya when attaching.
+    pub fn uprobe(arg: &str) -> Result<Self> {
+        let (binary, function) = arg
+            .rsplit_once(':')
+            .with_context(|| format!("expected BINARY:FUNCTION, got {:?}", arg))?;
+        if binary.is_empty() {
+            bail!("uprobe {:?} has no binary", arg);
+        }
+        check_symbol("uprobe function", function)?;
+        Ok(ProbeSpec::Uprobe {
+            binary: PathBuf::from(binary),
+            function: function.to_string(),
+        })
+    }
+
+    /// The `ProbeEvent::kind` this probe's events carry.
+    pub fn kind(&self) -> u32 {
+        match self {
+            ProbeSpec::Kprobe { .. } => PROBE_KIND_KPROBE,
+            ProbeSpec::Tracepoint { .. } => PROBE_KIND_TRACEPOINT,
+            ProbeSpec::Uprobe { .. } => PROBE_KIND_UPROBE,
+        }
+    }
+
+    /// The eBPF program that is attached for this probe.
+    pub fn program_name(&self) -> &'static str {
+        match self {
+            ProbeSpec::Kprobe { .. } => "multi_kprobe",
+            ProbeSpec::Tracepoint { .. } => "multi_tracepoint",
+            ProbeSpec::Uprobe { .. } => "multi_uprobe",
+        }
+    }
+}
+
+/// As printed in event lines and accepted back by the flags:
+/// `kprobe:vfs_read`, `tracepoint:sched:sched_process_exec`,
+/// `uprobe:/usr/bin/bash:readline`.
+impl fmt::Display for ProbeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeSpec::Kprobe { function } => write!(f, "kprobe:{}", function),
+            ProbeSpec::Tracepoint { category, name } => {
+                write!(f, "tracepoint:{}:{}", category, name)
+            }
+            ProbeSpec::Uprobe { binary, function } => {
+                write!(f, "uprobe:{}:{}", binary.display(), function)
+            }
+        }
+    }
+}
+
+/// Parse all probe flags of one `trace` run, rejecting duplicates: the
+/// second attachment would report every event twice.
+pub fn parse_probes(
+    kprobes: &[String],
+    tracepoints: &[String],
+    uprobes: &[String],
+) -> Result<Vec<ProbeSpec>> {
+    let mut specs: Vec<ProbeSpec> = Vec::new();
+    let parsed = kprobes
+        .iter()
+        .map(|a| ProbeSpec::kprobe(a))
+        .chain(tracepoints.iter().map(|a| ProbeSpec::tracepoint(a)))
+        .chain(uprobes.iter().map(|a| ProbeSpec::uprobe(a)));
+    for spec in parsed {
+        let spec = spec?;
+        if specs.contains(&spec) {
+            bail!("{} is given twice", spec);
+        }
+        specs.push(spec);
+    }
+    Ok(specs)
+}
+
+/// Kernel and ELF symbols, and tracepoint names: letters, digits, `_` and
+/// `.` (`foo.isra.0`, `foo.cold`).
+fn check_symbol(what: &str, s: &str) -> Result<()> {
+    if s.is_empty() {
+        bail!("{} is empty", what);
+    }
+    if let Some(c) = s
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '.'))
+    {
+        bail!("{} {:?} contains {:?}", what, s, c);
+    }
+    Ok(())
+}
+
+struct Pending<T> {
+    timestamp_ns: u64,
+    seq: u64,
+    item: T,
+}
+
+impl<T> PartialEq for Pending<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Pending<T> {}
+
+impl<T> PartialOrd for Pending<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Pending<T> {
+    /// By timestamp; equal timestamps keep their arrival order.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.timestamp_ns, self.seq).cmp(&(other.timestamp_ns, other.seq))
+    }
+}
+
+/// Merges events from several time-ordered sources into one ordered stream.
+pub struct Merger<T> {
+    hold_ns: u64,
+    heap: BinaryHeap<Reverse<Pending<T>>>,
+    /// Newest timestamp per source
+    newest: HashMap<usize, u64>,
+    now_ns: u64,
+    released_ns: u64,
+    seq: u64,
+    late: u64,
+}
+
+impl<T> Merger<T> {
+    /// `sources`: every source that can send events, so none is released
+    /// before all have been heard from, e.g. `perf arrays * CPUs`.
+    pub fn new(sources: usize, hold_ns: u64) -> Self {
+        Self {
+            hold_ns,
+            heap: BinaryHeap::new(),
+            newest: (0..sources).map(|s| (s, 0)).collect(),
+            now_ns: 0,
+            released_ns: 0,
+            seq: 0,
+            late: 0,
+        }
+    }
+
+    /// Add an event from `source`. Events of one source must come in
+    /// timestamp order, as they do from one perf buffer.
+    pub fn push(&mut self, source: usize, timestamp_ns: u64, item: T) {
+        let newest = self.newest.entry(source).or_insert(0);
+        *newest = (*newest).max(timestamp_ns);
+        self.now_ns = self.now_ns.max(timestamp_ns);
+        if timestamp_ns < self.released_ns {
+            // Too late to be put in order: released next, and counted
+            self.late += 1;
+        }
+        self.heap.push(Reverse(Pending {
+            timestamp_ns,
+            seq: self.seq,
+            item,
+        }));
+        self.seq += 1;
+    }
+
+    /// Events that no source can still undercut, oldest first. Call it
+    /// after each batch of events read from the perf buffers.
+    pub fn ready(&mut self) -> Vec<T> {
+        let watermark = self.newest.values().copied().min().unwrap_or(0);
+        let cutoff = watermark.max(self.now_ns.saturating_sub(self.hold_ns));
+        let mut out = Vec::new();
+        while let Some(Reverse(next)) = self.heap.peek() {
+            if next.timestamp_ns > cutoff && next.timestamp_ns >= self.released_ns {
+                break;
+            }
+            let Some(Reverse(next)) = self.heap.pop() else {
+                break;
+            };
+            self.released_ns = self.released_ns.max(next.timestamp_ns);
+            out.push(next.item);
+        }
+        out
+    }
+
+    /// Everything still held, oldest first, at the end of the trace.
+    pub fn finish(&mut self) -> Vec<T> {
+        let mut out = Vec::with_capacity(self.heap.len());
+        while let Some(Reverse(next)) = self.heap.pop() {
+            self.released_ns = self.released_ns.max(next.timestamp_ns);
+            out.push(next.item);
+        }
+        out
+    }
+
+    /// Events that arrived after a newer one was already released: a reader
+    /// task fell behind by more than the hold time.
+    pub fn late(&self) -> u64 {
+        self.late
+    }
+
+    /// Number of events held.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_parse_probe_specs() {
+        assert_eq!(
+            ProbeSpec::kprobe("vfs_read").unwrap().to_string(),
+            "kprobe:vfs_read"
+        );
+        let tp = ProbeSpec::tracepoint("sched/sched_process_exec").unwrap();
+        assert_eq!(tp.to_string(), "tracepoint:sched:sched_process_exec");
+        assert_eq!(tp.program_name(), "multi_tracepoint");
+        let up = ProbeSpec::uprobe("/usr/bin/bash:readline").unwrap();
+        assert_eq!(
+            up,
+            ProbeSpec::Uprobe {
+                binary: PathBuf::from("/usr/bin/bash"),
+                function: "readline".to_string()
+            }
+        );
+        assert_eq!(up.kind(), PROBE_KIND_UPROBE);
+        assert!(ProbeSpec::kprobe("do_sys_openat2.isra.0").is_ok());
+    }
+
+    #[test]
+    fn test_reject_bad_specs() {
+        assert!(ProbeSpec::kprobe("").is_err());
+        assert!(ProbeSpec::kprobe("vfs read").is_err());
+        assert!(ProbeSpec::tracepoint("sched_process_exec").is_err());
+        assert!(ProbeSpec::tracepoint("sched:").is_err());
+        assert!(ProbeSpec::uprobe("readline").is_err());
+        assert!(ProbeSpec::uprobe(":readline").is_err());
+
+        let dup = parse_probes(&["vfs_read".into(), "vfs_read".into()], &[], &[]);
+        assert!(dup.unwrap_err().to_string().contains("given twice"));
+        let all = parse_probes(
+            &["vfs_read".into()],
+            &["sched:sched_process_exec".into()],
+            &["/usr/bin/bash:readline".into()],
+        )
+        .unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
+    #[test]
+    fn test_merges_sources_in_order() {
+        let mut m = Merger::new(2, DEFAULT_MERGE_HOLD_NS);
+        m.push(0, 10, "a10");
+        m.push(0, 30, "a30");
+        // Source 1 has not sent anything: nothing is safe yet
+        assert!(m.ready().is_empty());
+
+        m.push(1, 20, "b20");
+        assert_eq!(m.ready(), vec!["a10", "b20"]);
+        m.push(1, 40, "b40");
+        assert_eq!(m.ready(), vec!["a30"]);
+        assert_eq!(m.finish(), vec!["b40"]);
+        assert_eq!(m.late(), 0);
+    }
+
+    #[test]
+    fn test_idle_source_released_by_hold() {
+        let mut m = Merger::new(2, 10 * MS);
+        m.push(0, MS, 1);
+        m.push(0, 5 * MS, 2);
+        assert!(m.ready().is_empty());
+        // Source 1 stays idle; time moves on in source 0
+        m.push(0, 14 * MS, 3);
+        assert_eq!(m.ready(), vec![1]);
+        assert_eq!(m.len(), 2);
+    }
+
+    #[test]
+    fn test_late_event_counted() {
+        let mut m = Merger::new(1, DEFAULT_MERGE_HOLD_NS);
+        m.push(0, 100, "a");
+        assert_eq!(m.ready(), vec!["a"]);
+        // A second source that was not announced, far behind
+        m.push(7, 50, "late");
+        m.push(0, 200, "b");
+        assert_eq!(m.ready(), vec!["late"]);
+        assert_eq!(m.late(), 1);
+        assert_eq!(m.finish(), vec!["b"]);
+        assert!(m.is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+//! `kprobe do_sys_openat2`: the open arguments of an `OpenEvent` as text.
+//!
+//! `openat2_kprobe` copies the three arguments of every open in the kernel;
+//! this module prints them the way strace would:
+//!
+//! ```text
+//! OpenEvent { dfd: -100, filename: "/etc/hostname", open_flags: 0o2100000, .. }
+//!   ─► render() ─► dfd=AT_FDCWD path="/etc/hostname" flags=O_RDONLY|O_CLOEXEC
+//! ```
+//!
+//! The flag values are the running architecture's (`O_DIRECTORY` is
+//! `0o200000` on x86_64 and `0o40000` on arm64), so they come from libc,
+//! except the ones libc does not have or defines differently from the
+//! kernel. `mode` is only printed with `O_CREAT` or `O_TMPFILE`, `resolve`
+//! only when set: the kernel ignores them otherwise.
+
+use ebpf_tool_common::{c_str, OpenEvent, AT_FDCWD, STR_PATH_TRUNCATED, STR_READ_FAILED};
+
+/// The kernel's `O_LARGEFILE`, which it adds to every open on 64-bit
+/// (`force_o_largefile()`). libc defines it as 0 on 64-bit targets, since
+/// userspace never needs to pass it.
+#[cfg(target_arch = "x86_64")]
+const O_LARGEFILE: u64 = 0o100000;
+#[cfg(target_arch = "aarch64")]
+const O_LARGEFILE: u64 = 0o400000;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const O_LARGEFILE: u64 = libc::O_LARGEFILE as u64;
+
+/// `open_how.resolve` flags (`linux/openat2.h`), the same on every
+/// architecture.
+const RESOLVE_FLAGS: &[(u64, &str)] = &[
+    (0x01, "RESOLVE_NO_XDEV"),
+    (0x02, "RESOLVE_NO_MAGICLINKS"),
+    (0x04, "RESOLVE_NO_SYMLINKS"),
+    (0x08, "RESOLVE_BENEATH"),
+    (0x10, "RESOLVE_IN_ROOT"),
+    (0x20, "RESOLVE_CACHED"),
+];
+
+/// Flags made of several bits come first, so their bits are taken before
+/// the single-bit flags inside them (`O_TMPFILE` contains `O_DIRECTORY`,
+/// `O_SYNC` contains `O_DSYNC`).
+fn open_flag_names() -> [(u64, &'static str); 16] {
+    [
+        (libc::O_TMPFILE as u64, "O_TMPFILE"),
+        (libc::O_SYNC as u64, "O_SYNC"),
+        (libc::O_CREAT as u64, "O_CREAT"),
+        (libc::O_EXCL as u64, "O_EXCL"),
+        (libc::O_NOCTTY as u64, "O_NOCTTY"),
+        (libc::O_TRUNC as u64, "O_TRUNC"),
+        (libc::O_APPEND as u64, "O_APPEND"),
+        (libc::O_NONBLOCK as u64, "O_NONBLOCK"),
+        (libc::O_DSYNC as u64, "O_DSYNC"),
+        (libc::O_ASYNC as u64, "O_ASYNC"),
+        (libc::O_DIRECT as u64, "O_DIRECT"),
+        (libc::O_DIRECTORY as u64, "O_DIRECTORY"),
+        (libc::O_NOFOLLOW as u64, "O_NOFOLLOW"),
+        (libc::O_NOATIME as u64, "O_NOATIME"),
+        (libc::O_CLOEXEC as u64, "O_CLOEXEC"),
+        (libc::O_PATH as u64, "O_PATH"),
+    ]
+}
+
+/// `AT_FDCWD` or the fd number.
+pub fn dfd(dfd: i32) -> String {
+    if dfd == AT_FDCWD {
+        "AT_FDCWD".to_string()
+    } else {
+        dfd.to_string()
+    }
+}
+
+/// `O_RDONLY|O_CLOEXEC` for `open_how.flags`.
+///
+/// The access mode always comes first, as in strace. `O_LARGEFILE` is
+/// left out: the kernel sets it on every 64-bit open, so it says nothing.
+/// Unknown bits are shown in octal, like the flags themselves in C.
+pub fn open_flags(flags: u64) -> String {
+    let mut names = vec![match flags & libc::O_ACCMODE as u64 {
+        0 => "O_RDONLY".to_string(),
+        1 => "O_WRONLY".to_string(),
+        2 => "O_RDWR".to_string(),
+        _ => "O_ACCMODE".to_string(),
+    }];
+    let mut rest = flags & !(libc::O_ACCMODE as u64) & !O_LARGEFILE;
+    for (bits, name) in open_flag_names() {
+        if bits != 0 && rest & bits == bits {
+            names.push(name.to_string());
+            rest &= !bits;
+        }
+    }
+    if rest != 0 {
+        names.push(format!("0o{:o}", rest));
+    }
+    names.join("|")
+}
+
+/// `RESOLVE_BENEATH|RESOLVE_NO_SYMLINKS` for `open_how.resolve`, `0` for none.
+pub fn resolve(resolve: u64) -> String {
+    if resolve == 0 {
+        return "0".to_string();
+    }
+    let mut names: Vec<String> = RESOLVE_FLAGS
+        .iter()
+        .filter(|(bit, _)| resolve & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let known = RESOLVE_FLAGS.iter().fold(0, |all, (bit, _)| all | bit);
+    if resolve & !known != 0 {
+        names.push(format!("{:#x}", resolve & !known));
+    }
+    names.join("|")
+}
+
+/// One event as `dfd=AT_FDCWD path="/etc/hostname" flags=O_RDONLY|O_CLOEXEC`.
+///
+/// A path the kernel could not read is `path=?`, a cut one ends in `...`
+/// after the quote; flags the program could not read (no `open_how` in
+/// BTF) are `flags=?`.
+pub fn render(event: &OpenEvent) -> String {
+    let mut line = format!("dfd={} ", dfd(event.dfd));
+    if event.flags & STR_READ_FAILED != 0 {
+        line.push_str("path=?");
+    } else {
+        let path = String::from_utf8_lossy(c_str(&event.filename));
+        line.push_str(&format!("path={:?}", path));
+        if event.flags & STR_PATH_TRUNCATED != 0 {
+            line.push_str("...");
+        }
+    }
+    if event.how_status != 0 {
+        line.push_str(" flags=?");
+        return line;
+    }
+    line.push_str(&format!(" flags={}", open_flags(event.open_flags)));
+    let tmpfile = libc::O_TMPFILE as u64;
+    if event.open_flags & libc::O_CREAT as u64 != 0 || event.open_flags & tmpfile == tmpfile {
+        line.push_str(&format!(" mode={:04o}", event.mode));
+    }
+    if event.resolve != 0 {
+        line.push_str(&format!(" resolve={}", resolve(event.resolve)));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(path: &str, open_flags: u64) -> OpenEvent {
+        let mut event = OpenEvent {
+            open_flags,
+            ..OpenEvent::new()
+        };
+        event.filename[..path.len()].copy_from_slice(path.as_bytes());
+        event
+    }
+
+    #[test]
+    fn test_open_flags() {
+        let cloexec = libc::O_CLOEXEC as u64;
+        assert_eq!(open_flags(0), "O_RDONLY");
+        assert_eq!(open_flags(cloexec | O_LARGEFILE), "O_RDONLY|O_CLOEXEC");
+        assert_eq!(
+            open_flags((libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC) as u64),
+            "O_WRONLY|O_CREAT|O_TRUNC"
+        );
+        // O_TMPFILE includes the O_DIRECTORY bit, O_SYNC the O_DSYNC bit
+        assert_eq!(
+            open_flags((libc::O_RDWR | libc::O_TMPFILE) as u64),
+            "O_RDWR|O_TMPFILE"
+        );
+        assert_eq!(open_flags(libc::O_SYNC as u64 | 1), "O_WRONLY|O_SYNC");
+        assert_eq!(open_flags(1 << 40), "O_RDONLY|0o20000000000000");
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve(0), "0");
+        assert_eq!(resolve(0x08 | 0x04), "RESOLVE_NO_SYMLINKS|RESOLVE_BENEATH");
+        assert_eq!(resolve(0x10 | 0x100), "RESOLVE_IN_ROOT|0x100");
+    }
+
+    #[test]
+    fn test_render() {
+        let e = event("/etc/hostname", libc::O_CLOEXEC as u64 | O_LARGEFILE);
+        assert_eq!(
+            render(&e),
+            "dfd=AT_FDCWD path=\"/etc/hostname\" flags=O_RDONLY|O_CLOEXEC"
+        );
+
+        // mode only with O_CREAT, resolve only when set
+        let mut e = event("out.txt", (libc::O_WRONLY | libc::O_CREAT) as u64);
+        e.dfd = 3;
+        e.mode = 0o644;
+        e.resolve = 0x08;
+        assert_eq!(
+            render(&e),
+            "dfd=3 path=\"out.txt\" flags=O_WRONLY|O_CREAT mode=0644 resolve=RESOLVE_BENEATH"
+        );
+    }
+
+    #[test]
+    fn test_render_marks_what_could_not_be_read() {
+        let mut e = event("/very/long", 0);
+        e.flags = STR_PATH_TRUNCATED;
+        e.how_status = -2;
+        assert_eq!(render(&e), "dfd=AT_FDCWD path=\"/very/long\"... flags=?");
+
+        let e = OpenEvent {
+            flags: STR_READ_FAILED,
+            ..OpenEvent::new()
+        };
+        assert_eq!(render(&e), "dfd=AT_FDCWD path=? flags=O_RDONLY");
+    }
+}
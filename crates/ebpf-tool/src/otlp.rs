@@ -0,0 +1,469 @@
+//! OpenTelemetry export for `ebpf-tool trace --otlp-endpoint`.
+//!
+//! Only built with the `otlp` cargo feature:
+//!
+//! ```text
+//! cargo build -p ebpf-tool --features otlp
+//! sudo ebpf-tool trace --otlp-endpoint http://localhost:4318 -d 0
+//! ```
+//!
+//! Most syscalls are noise in a trace viewer, so only the ones that mark a
+//! step in a process's life become spans: exec, file opens and connects
+//! ([`high_level`]). Each is a zero-length span at the time of the syscall,
+//! and all spans of one process share a trace id, so Jaeger or Tempo shows
+//! one trace per process with its execs, opens and connects in order:
+//!
+//! ```text
+//! trace 5f1c...000006d2  (bash, pid 1746)
+//!   process.exec     syscall.name=execve   process.executable.name=bash
+//!   file.open        syscall.name=openat
+//!   network.connect  syscall.name=connect
+//! ```
+//!
+//! Spans are sent in the OTLP/HTTP JSON encoding (`POST /v1/traces`), which
+//! every OpenTelemetry Collector accepts on port 4318. Like `metrics`, the
+//! HTTP part is written by hand: one POST per batch needs no HTTP client,
+//! gRPC stack or protobuf code generator. Plain `http://` only; run a
+//! collector next to the tracer if the backend needs TLS.
+
+use anyhow::{Context, Result};
+use ebpf_tool_common::SyscallEvent;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Port of the OTLP/HTTP receiver of an OpenTelemetry Collector.
+pub const DEFAULT_PORT: u16 = 4318;
+
+/// Path of the OTLP/HTTP trace receiver.
+pub const TRACES_PATH: &str = "/v1/traces";
+
+/// Spans per POST. The collector's default limit is far above this.
+pub const MAX_BATCH: usize = 512;
+
+/// Longest a span waits in a batch before it is sent.
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Connect, send and response timeout for one POST.
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where to send spans: `--otlp-endpoint http://HOST[:PORT][/PATH]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl Endpoint {
+    /// Parse an endpoint URL. The port defaults to 4318 and the path to
+    /// `/v1/traces`, so `http://collector` is enough.
+    pub fn parse(url: &str) -> Result<Self> {
+        let rest = match url.split_once("://") {
+            Some(("http", rest)) => rest,
+            Some(("https", _)) => anyhow::bail!(
+                "{}: https is not supported; send to a local collector over http and let it forward with TLS",
+                url
+            ),
+            _ => anyhow::bail!("{}: expected an http:// URL", url),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            // An IPv6 address without a port, like [::1]
+            Some((_, p)) if p.ends_with(']') => (authority, DEFAULT_PORT),
+            Some((h, p)) => (
+                h,
+                p.parse()
+                    .with_context(|| format!("{}: invalid port {:?}", url, p))?,
+            ),
+            None => (authority, DEFAULT_PORT),
+        };
+        anyhow::ensure!(!host.is_empty(), "{}: missing host", url);
+        let path = match path {
+            "" | "/" => TRACES_PATH,
+            p => p,
+        };
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// `host:port`, for the Host header and for connecting.
+    pub fn authority(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "http://{}{}", self.authority(), self.path)
+    }
+}
+
+/// The span name for a syscall worth exporting, or `None` for the rest.
+pub fn high_level(syscall: &str) -> Option<&'static str> {
+    match syscall {
+        "execve" | "execveat" => Some("process.exec"),
+        "open" | "openat" | "openat2" | "creat" => Some("file.open"),
+        "connect" => Some("network.connect"),
+        _ => None,
+    }
+}
+
+/// An attribute value. OTLP has more types; these are the ones we send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrValue {
+    Str(String),
+    Int(i64),
+}
+
+/// One exported span, in the units OTLP wants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub name: &'static str,
+    /// Unix time in nanoseconds; start and end are the same instant
+    pub time_unix_nano: u64,
+    pub attributes: Vec<(&'static str, AttrValue)>,
+}
+
+/// Turns syscall events into spans: ids, timestamps and attributes.
+#[derive(Debug)]
+pub struct Mapper {
+    seed: u64,
+    next_span: u64,
+    /// CLOCK_REALTIME - CLOCK_MONOTONIC, to turn bpf_ktime_get_ns() values
+    /// into Unix time
+    realtime_offset_ns: u64,
+}
+
+impl Mapper {
+    /// A mapper with fixed ids and clock offset, for tests.
+    pub fn new(seed: u64, realtime_offset_ns: u64) -> Self {
+        Self {
+            seed,
+            next_span: 0,
+            realtime_offset_ns,
+        }
+    }
+
+    /// A mapper for this tracing session: a random seed, so two sessions
+    /// never share trace ids, and the clock offset of this boot.
+    pub fn for_session() -> Result<Self> {
+        let mut seed = [0u8; 8];
+        std::fs::File::open("/dev/urandom")
+            .and_then(|mut f| f.read_exact(&mut seed))
+            .context("failed to read /dev/urandom")?;
+        Ok(Self::new(
+            u64::from_ne_bytes(seed),
+            crate::clock::realtime_offset_ns(),
+        ))
+    }
+
+    /// The span for `event`, if its syscall is one [`high_level`] keeps.
+    pub fn span(&mut self, event: &SyscallEvent, syscall: &str, comm: &str) -> Option<Span> {
+        let name = high_level(syscall)?;
+        self.next_span += 1;
+
+        let mut trace_id = [0u8; 16];
+        trace_id[..8].copy_from_slice(&self.seed.to_be_bytes());
+        trace_id[8..].copy_from_slice(&u64::from(event.pid).to_be_bytes());
+        // Never all zeros, which OTLP treats as invalid
+        let span_id = (self.seed ^ self.next_span).max(1).to_be_bytes();
+
+        Some(Span {
+            trace_id,
+            span_id,
+            name,
+            time_unix_nano: event.timestamp_ns + self.realtime_offset_ns,
+            attributes: vec![
+                ("syscall.name", AttrValue::Str(syscall.to_string())),
+                ("process.pid", AttrValue::Int(event.pid.into())),
+                ("thread.id", AttrValue::Int(event.tid.into())),
+                ("process.executable.name", AttrValue::Str(comm.to_string())),
+                (
+                    "ebpf_tool.cgroup_id",
+                    AttrValue::Int(event.cgroup_id as i64),
+                ),
+            ],
+        })
+    }
+}
+
+/// Collects spans until a batch is full or old enough to send.
+#[derive(Debug)]
+pub struct Batcher {
+    spans: Vec<Span>,
+    oldest: Option<Instant>,
+}
+
+impl Default for Batcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Batcher {
+    pub fn new() -> Self {
+        Self {
+            spans: Vec::new(),
+            oldest: None,
+        }
+    }
+
+    /// Add a span; returns the batch to send once it holds [`MAX_BATCH`].
+    pub fn push(&mut self, span: Span, now: Instant) -> Option<Vec<Span>> {
+        self.oldest.get_or_insert(now);
+        self.spans.push(span);
+        (self.spans.len() >= MAX_BATCH).then(|| self.take())
+    }
+
+    /// The batch to send if its oldest span waited [`FLUSH_INTERVAL`].
+    /// Call it on a timer, so a quiet process's spans still go out.
+    pub fn due(&mut self, now: Instant) -> Option<Vec<Span>> {
+        let oldest = self.oldest?;
+        (now.duration_since(oldest) >= FLUSH_INTERVAL).then(|| self.take())
+    }
+
+    /// Everything collected so far, e.g. when tracing stops.
+    pub fn take(&mut self) -> Vec<Span> {
+        self.oldest = None;
+        std::mem::take(&mut self.spans)
+    }
+}
+
+/// An `ExportTraceServiceRequest` in the OTLP JSON encoding.
+///
+/// Ids are hex (not base64 as in the generic protobuf JSON mapping) and
+/// 64-bit integers are strings, as the OTLP specification requires.
+pub fn encode(spans: &[Span], host_name: &str) -> String {
+    let spans: Vec<Value> = spans
+        .iter()
+        .map(|s| {
+            json!({
+                "traceId": hex(&s.trace_id),
+                "spanId": hex(&s.span_id),
+                "name": s.name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": s.time_unix_nano.to_string(),
+                "endTimeUnixNano": s.time_unix_nano.to_string(),
+                "attributes": s.attributes.iter().map(|(k, v)| attribute(k, v)).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    attribute("service.name", &AttrValue::Str("ebpf-tool".into())),
+                    attribute("host.name", &AttrValue::Str(host_name.into())),
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "ebpf-tool", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+    .to_string()
+}
+
+fn attribute(key: &str, value: &AttrValue) -> Value {
+    let value = match value {
+        AttrValue::Str(s) => json!({ "stringValue": s }),
+        AttrValue::Int(i) => json!({ "intValue": i.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The complete HTTP request that sends `body` to `endpoint`.
+pub fn request(endpoint: &Endpoint, body: &str) -> Vec<u8> {
+    format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        endpoint.path,
+        endpoint.authority(),
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+/// Check the collector's response: any 2xx is success. Otherwise the
+/// error carries the status line and the start of the body, which is
+/// where collectors explain what they rejected.
+pub fn check_response(response: &str) -> Result<()> {
+    let status_line = response.lines().next().unwrap_or("");
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .with_context(|| format!("invalid HTTP response: {:?}", status_line))?;
+    if (200..300).contains(&status) {
+        return Ok(());
+    }
+    let body = response
+        .split_once("\r\n\r\n")
+        .map_or("", |(_, b)| b)
+        .trim();
+    let body: String = body.chars().take(200).collect();
+    anyhow::bail!("collector answered {}: {}", status_line, body)
+}
+
+/// POST one batch to the collector.
+pub async fn post(endpoint: &Endpoint, spans: &[Span], host_name: &str) -> Result<()> {
+    let send = async {
+        let mut stream = TcpStream::connect(endpoint.authority()).await?;
+        stream
+            .write_all(&request(endpoint, &encode(spans, host_name)))
+            .await?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+        check_response(&String::from_utf8_lossy(&response))
+    };
+    tokio::time::timeout(POST_TIMEOUT, send)
+        .await
+        .map_err(|_| anyhow::anyhow!("timed out"))?
+        .with_context(|| format!("failed to send {} spans to {}", spans.len(), endpoint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(pid: u32, timestamp_ns: u64) -> SyscallEvent {
+        SyscallEvent {
+            pid,
+            tid: pid + 1,
+            cgroup_id: 42,
+            timestamp_ns,
+            ..SyscallEvent::new()
+        }
+    }
+
+    #[test]
+    fn test_endpoint_parse() {
+        let e = Endpoint::parse("http://localhost").unwrap();
+        assert_eq!(
+            e,
+            Endpoint {
+                host: "localhost".into(),
+                port: 4318,
+                path: "/v1/traces".into(),
+            }
+        );
+        assert_eq!(e.to_string(), "http://localhost:4318/v1/traces");
+
+        let e = Endpoint::parse("http://10.0.0.5:14318/otlp/v1/traces").unwrap();
+        assert_eq!((e.port, e.path.as_str()), (14318, "/otlp/v1/traces"));
+        assert_eq!(Endpoint::parse("http://[::1]").unwrap().host, "[::1]");
+        assert_eq!(Endpoint::parse("http://[::1]:9/").unwrap().port, 9);
+
+        for bad in [
+            "localhost:4318",
+            "https://x",
+            "http://:4318",
+            "http://x:port",
+        ] {
+            assert!(Endpoint::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_mapper_keeps_high_level_events() {
+        let mut m = Mapper::new(0x1122_3344_5566_7788, 1_000);
+        assert!(m.span(&event(7, 5), "read", "cat").is_none());
+
+        let exec = m.span(&event(7, 5), "execve", "cat").unwrap();
+        let open = m.span(&event(7, 9), "openat", "cat").unwrap();
+        let other = m.span(&event(8, 9), "connect", "curl").unwrap();
+
+        assert_eq!(exec.name, "process.exec");
+        assert_eq!(open.name, "file.open");
+        assert_eq!(other.name, "network.connect");
+        assert_eq!(exec.time_unix_nano, 1_005);
+        // One trace per process, one span id per event
+        assert_eq!(exec.trace_id, open.trace_id);
+        assert_ne!(exec.trace_id, other.trace_id);
+        assert_eq!(&exec.trace_id[..8], &0x1122_3344_5566_7788u64.to_be_bytes());
+        assert_ne!(exec.span_id, open.span_id);
+        assert!(exec
+            .attributes
+            .contains(&("process.executable.name", AttrValue::Str("cat".into()))));
+        assert!(exec
+            .attributes
+            .contains(&("process.pid", AttrValue::Int(7))));
+    }
+
+    #[test]
+    fn test_batcher() {
+        let mut m = Mapper::new(1, 0);
+        let span = m.span(&event(1, 1), "execve", "sh").unwrap();
+        let start = Instant::now();
+        let mut b = Batcher::new();
+
+        assert!(b.due(start).is_none());
+        assert!(b.push(span.clone(), start).is_none());
+        assert!(b.due(start + FLUSH_INTERVAL / 2).is_none());
+        assert_eq!(b.due(start + FLUSH_INTERVAL).unwrap().len(), 1);
+        assert!(b.due(start + FLUSH_INTERVAL * 2).is_none());
+
+        for i in 1..MAX_BATCH {
+            assert!(b.push(span.clone(), start).is_none(), "{i}");
+        }
+        assert_eq!(b.push(span, start).unwrap().len(), MAX_BATCH);
+        assert!(b.take().is_empty());
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut m = Mapper::new(0xff, 0);
+        let span = m.span(&event(2, 1_700_000_000_000_000_000), "connect", "curl");
+        let body: Value = serde_json::from_str(&encode(&[span.unwrap()], "lab")).unwrap();
+
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0],
+            json!({ "key": "service.name", "value": { "stringValue": "ebpf-tool" } })
+        );
+        let span = &resource["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["traceId"], "00000000000000ff0000000000000002");
+        assert_eq!(span["spanId"], "00000000000000fe");
+        assert_eq!(span["name"], "network.connect");
+        assert_eq!(span["startTimeUnixNano"], "1700000000000000000");
+        assert!(span["attributes"]
+            .as_array()
+            .unwrap()
+            .contains(&json!({ "key": "process.pid", "value": { "intValue": "2" } })));
+    }
+
+    #[test]
+    fn test_request_and_response() {
+        let e = Endpoint::parse("http://collector:4318").unwrap();
+        let req = String::from_utf8(request(&e, "{}")).unwrap();
+        assert!(req.starts_with("POST /v1/traces HTTP/1.1\r\nHost: collector:4318\r\n"));
+        assert!(req.contains("Content-Length: 2\r\n"));
+        assert!(req.ends_with("\r\n\r\n{}"));
+
+        assert!(check_response("HTTP/1.1 200 OK\r\n\r\n{}").is_ok());
+        assert!(check_response("HTTP/1.1 202 Accepted\r\n\r\n").is_ok());
+        let err = check_response("HTTP/1.1 400 Bad Request\r\n\r\ninvalid traceId\n").unwrap_err();
+        assert!(
+            err.to_string().contains("400 Bad Request: invalid traceId"),
+            "{err}"
+        );
+        assert!(check_response("garbage").is_err());
+    }
+}
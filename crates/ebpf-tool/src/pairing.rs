@@ -0,0 +1,328 @@
+//! Pairing syscall entries with their exits for `trace --returns` and `replay`.
+//!
+//! With `--returns` the kernel sends two events per syscall: the entry
+//! (`SyscallEvent`, from `EVENTS` or a batch) and the exit
+//! (`SyscallExitEvent`, from `SYSCALL_EXITS`). The exit carries the entry's
+//! timestamp, so `(tid, timestamp)` identifies the pair exactly. What this
+//! module handles is everything around that:
+//!
+//! ```text
+//! entry ─► exit waiting for it? ─► Complete
+//!          else hold it ──────────── exit arrives ─► Complete
+//!                      └─ hold_ns passes ─► Unfinished ── exit arrives ─► Resumed
+//! exit ──► entry held? ─► Complete      entry reported Unfinished? ─► Resumed
+//!          else keep it (its entry is still in another CPU's buffer)
+//! ```
+//!
+//! Time is the events' own clock (the largest timestamp seen so far), never
+//! the wall clock, so a replayed capture pairs exactly like the live trace.
+
+use ebpf_tool_common::{SyscallEvent, SyscallExitEvent};
+use std::collections::HashMap;
+
+/// How long an entry waits for its exit before it is printed without one.
+///
+/// Long enough for every exit that is merely in another CPU's buffer, short
+/// enough that a `read()` blocked on a terminal does not hold back the
+/// output. Its exit is printed later as `Resumed`.
+pub const DEFAULT_HOLD_NS: u64 = 1_000_000_000;
+
+/// Most entries kept after being reported `Unfinished`; the oldest are
+/// forgotten first, and their late exits count as unmatched.
+const MAX_UNFINISHED: usize = 4096;
+
+/// Syscall numbers that never return on success, per architecture. Their
+/// entries are reported `Unfinished` right away, like strace's `= ?`.
+pub fn no_return_syscalls(arch: &str) -> &'static [u64] {
+    match arch {
+        "x86_64" => &[60, 231], // exit, exit_group
+        "aarch64" => &[93, 94], // exit, exit_group
+        "riscv64" => &[93, 94], // exit, exit_group
+        _ => &[],
+    }
+}
+
+/// The result of pairing, in the order it should be printed.
+#[derive(Debug, Clone, Copy)]
+pub enum Paired {
+    /// An entry together with its exit.
+    Complete {
+        entry: SyscallEvent,
+        exit: SyscallExitEvent,
+    },
+    /// An entry whose exit did not come within the hold time, or never will.
+    Unfinished(SyscallEvent),
+    /// The exit of an entry that was already reported `Unfinished`.
+    Resumed {
+        entry: SyscallEvent,
+        exit: SyscallExitEvent,
+    },
+}
+
+impl Paired {
+    /// The entry event of this result.
+    pub fn entry(&self) -> &SyscallEvent {
+        match self {
+            Paired::Complete { entry, .. }
+            | Paired::Unfinished(entry)
+            | Paired::Resumed { entry, .. } => entry,
+        }
+    }
+
+    /// The exit event, if the syscall has returned.
+    pub fn exit(&self) -> Option<&SyscallExitEvent> {
+        match self {
+            Paired::Complete { exit, .. } | Paired::Resumed { exit, .. } => Some(exit),
+            Paired::Unfinished(_) => None,
+        }
+    }
+}
+
+type Key = (u32, u64);
+
+/// Joins entry and exit events that may arrive in any order.
+#[derive(Debug)]
+pub struct Pairer {
+    hold_ns: u64,
+    no_return: Vec<u64>,
+    /// Entries waiting for their exit
+    entries: HashMap<Key, SyscallEvent>,
+    /// Entries already reported `Unfinished`, for a late `Resumed`
+    unfinished: HashMap<Key, SyscallEvent>,
+    /// Exits that arrived before their entry
+    early_exits: HashMap<Key, SyscallExitEvent>,
+    now_ns: u64,
+    unmatched_exits: u64,
+}
+
+impl Pairer {
+    /// `no_return`: syscall numbers reported `Unfinished` without waiting,
+    /// usually [`no_return_syscalls`] of the traced machine.
+    pub fn new(hold_ns: u64, no_return: &[u64]) -> Self {
+        Self {
+            hold_ns,
+            no_return: no_return.to_vec(),
+            entries: HashMap::new(),
+            unfinished: HashMap::new(),
+            early_exits: HashMap::new(),
+            now_ns: 0,
+            unmatched_exits: 0,
+        }
+    }
+
+    /// Add an entry event. Returns a result if it can be printed already.
+    pub fn entry(&mut self, event: SyscallEvent) -> Option<Paired> {
+        self.now_ns = self.now_ns.max(event.timestamp_ns);
+        let key = (event.tid, event.timestamp_ns);
+        if let Some(exit) = self.early_exits.remove(&key) {
+            return Some(Paired::Complete { entry: event, exit });
+        }
+        if self.no_return.contains(&event.syscall_nr) {
+            return Some(Paired::Unfinished(event));
+        }
+        self.entries.insert(key, event);
+        None
+    }
+
+    /// Add an exit event. Returns a result if its entry was seen.
+    pub fn exit(&mut self, exit: SyscallExitEvent) -> Option<Paired> {
+        self.now_ns = self.now_ns.max(exit.timestamp_ns);
+        let key = (exit.tid, exit.enter_ns);
+        if let Some(entry) = self.entries.remove(&key) {
+            return Some(Paired::Complete { entry, exit });
+        }
+        if let Some(entry) = self.unfinished.remove(&key) {
+            return Some(Paired::Resumed { entry, exit });
+        }
+        self.early_exits.insert(key, exit);
+        None
+    }
+
+    /// Entries that waited longer than the hold time, oldest first. Call it
+    /// after each batch of events read from the perf buffers.
+    pub fn expire(&mut self) -> Vec<Paired> {
+        let Some(cutoff) = self.now_ns.checked_sub(self.hold_ns) else {
+            return Vec::new();
+        };
+        let before = self.early_exits.len();
+        self.early_exits
+            .retain(|_, exit| exit.timestamp_ns > cutoff);
+        self.unmatched_exits += (before - self.early_exits.len()) as u64;
+
+        let expired: Vec<Key> = self
+            .entries
+            .iter()
+            .filter(|(_, e)| e.timestamp_ns <= cutoff)
+            .map(|(key, _)| *key)
+            .collect();
+        let mut out: Vec<SyscallEvent> = expired
+            .into_iter()
+            .filter_map(|key| self.entries.remove(&key))
+            .collect();
+        out.sort_by_key(|e| e.timestamp_ns);
+        for entry in &out {
+            self.unfinished
+                .insert((entry.tid, entry.timestamp_ns), *entry);
+        }
+        self.trim_unfinished();
+        out.into_iter().map(Paired::Unfinished).collect()
+    }
+
+    /// Everything still waiting, at the end of the trace: entries as
+    /// `Unfinished`, oldest first; early exits count as unmatched.
+    pub fn finish(&mut self) -> Vec<Paired> {
+        self.unmatched_exits += self.early_exits.len() as u64;
+        self.early_exits.clear();
+        self.unfinished.clear();
+        let mut out: Vec<SyscallEvent> = self.entries.drain().map(|(_, e)| e).collect();
+        out.sort_by_key(|e| e.timestamp_ns);
+        out.into_iter().map(Paired::Unfinished).collect()
+    }
+
+    /// Exits whose entry never arrived: it was lost (perf buffer full) or
+    /// filtered out in userspace before pairing.
+    pub fn unmatched_exits(&self) -> u64 {
+        self.unmatched_exits
+    }
+
+    fn trim_unfinished(&mut self) {
+        while self.unfinished.len() > MAX_UNFINISHED {
+            let oldest = self
+                .unfinished
+                .iter()
+                .min_by_key(|(_, e)| e.timestamp_ns)
+                .map(|(key, _)| *key);
+            match oldest {
+                Some(key) => self.unfinished.remove(&key),
+                None => break,
+            };
+        }
+    }
+}
+
+/// A return value the way strace prints it: `3`, `0x7f3a2c000000`, or
+/// `-1 ENOENT (No such file or directory)`.
+pub fn format_return(ret: i64) -> String {
+    if (-4095..0).contains(&ret) {
+        let errno = nix::errno::Errno::from_raw(-ret as i32);
+        if errno == nix::errno::Errno::UnknownErrno {
+            return format!("-1 errno {}", -ret);
+        }
+        return format!("-1 {:?} ({})", errno, errno.desc());
+    }
+    if ret > u32::MAX as i64 {
+        // Addresses (mmap, brk) read better in hex
+        return format!("{:#x}", ret);
+    }
+    ret.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    fn entry(tid: u32, nr: u64, ts: u64) -> SyscallEvent {
+        SyscallEvent {
+            pid: tid,
+            tid,
+            syscall_nr: nr,
+            timestamp_ns: ts,
+            ..SyscallEvent::new()
+        }
+    }
+
+    fn exit(tid: u32, nr: u64, enter_ns: u64, ts: u64, ret: i64) -> SyscallExitEvent {
+        SyscallExitEvent {
+            pid: tid,
+            tid,
+            syscall_nr: nr,
+            ret,
+            enter_ns,
+            timestamp_ns: ts,
+            ..SyscallExitEvent::new()
+        }
+    }
+
+    #[test]
+    fn test_pairs_in_either_order() {
+        let mut p = Pairer::new(DEFAULT_HOLD_NS, &[]);
+        assert!(p.entry(entry(10, 257, 100)).is_none());
+        let Some(Paired::Complete { entry: e, exit: x }) = p.exit(exit(10, 257, 100, 150, 3))
+        else {
+            panic!("expected a complete pair");
+        };
+        assert_eq!((e.syscall_nr, x.ret), (257, 3));
+
+        // Exit first: the entry is still in another CPU's buffer
+        assert!(p.exit(exit(11, 0, 200, 260, 12)).is_none());
+        assert!(matches!(
+            p.entry(entry(11, 0, 200)),
+            Some(Paired::Complete { .. })
+        ));
+        assert!(p.finish().is_empty());
+        assert_eq!(p.unmatched_exits(), 0);
+    }
+
+    #[test]
+    fn test_same_thread_keyed_by_entry_time() {
+        let mut p = Pairer::new(DEFAULT_HOLD_NS, &[]);
+        p.entry(entry(10, 0, 100));
+        p.entry(entry(10, 1, 300)); // a lost exit left the first one pending
+        let paired = p.exit(exit(10, 1, 300, 310, 5)).unwrap();
+        assert_eq!(paired.entry().syscall_nr, 1);
+        let rest = p.finish();
+        assert_eq!(rest.len(), 1);
+        assert!(matches!(rest[0], Paired::Unfinished(e) if e.syscall_nr == 0));
+    }
+
+    #[test]
+    fn test_unfinished_then_resumed() {
+        let mut p = Pairer::new(10 * MS, &[]);
+        p.entry(entry(10, 0, MS)); // read() blocked on a terminal
+        p.entry(entry(11, 1, 2 * MS));
+        assert!(p.expire().is_empty());
+
+        p.entry(entry(12, 1, 12 * MS)); // time moves on
+        let expired = p.expire();
+        assert_eq!(expired.len(), 2);
+        assert_eq!(expired[0].entry().tid, 10, "oldest first");
+        assert!(expired[1].exit().is_none());
+
+        let late = p.exit(exit(10, 0, MS, 20 * MS, 1)).unwrap();
+        assert!(matches!(late, Paired::Resumed { .. }));
+        assert_eq!(late.exit().unwrap().ret, 1);
+    }
+
+    #[test]
+    fn test_no_return_and_unmatched() {
+        let mut p = Pairer::new(10 * MS, no_return_syscalls("x86_64"));
+        assert!(matches!(
+            p.entry(entry(10, 231, MS)),
+            Some(Paired::Unfinished(_))
+        ));
+
+        // An exit whose entry was lost expires after the hold time
+        p.exit(exit(11, 0, MS, 2 * MS, 0));
+        p.entry(entry(12, 1, 30 * MS));
+        p.expire();
+        assert_eq!(p.unmatched_exits(), 1);
+
+        p.exit(exit(13, 0, 31 * MS, 32 * MS, 0));
+        p.finish();
+        assert_eq!(p.unmatched_exits(), 2);
+    }
+
+    #[test]
+    fn test_format_return() {
+        assert_eq!(format_return(3), "3");
+        assert_eq!(format_return(0), "0");
+        assert_eq!(format_return(-2), "-1 ENOENT (No such file or directory)");
+        assert!(format_return(-11).starts_with("-1 EAGAIN ("));
+        assert_eq!(format_return(-4000), "-1 errno 4000");
+        assert_eq!(format_return(0x7f3a_2c00_0000), "0x7f3a2c000000");
+        // Below -4095 it is a value, not an error (e.g. lseek on /dev/mem)
+        assert_eq!(format_return(-5000), "-5000");
+    }
+}
@@ -0,0 +1,198 @@
+//! Pre-load step: raise RLIMIT_MEMLOCK and check capabilities before the
+//! first bpf() call.
+//!
+//! Without it, a missing privilege surfaces as whatever bpf() returned
+//! first, usually a bare EPERM from creating the first map:
+//!
+//! ```text
+//! Error: failed to create map `EVENTS`: Operation not permitted (os error 1)
+//! ```
+//!
+//! That EPERM has two unrelated causes. Before 5.11 the kernel charges map
+//! and program memory to RLIMIT_MEMLOCK, and the default of 64 KiB (8 MiB
+//! on newer distributions) is soon exhausted: raising the limit fixes it.
+//! On every kernel, the process also needs CAP_BPF and CAP_PERFMON (5.8+)
+//! or CAP_SYS_ADMIN: that one the tool cannot fix, but it can say so:
+//!
+//! ```text
+//! Error: loading eBPF programs needs CAP_BPF and CAP_PERFMON (this process has none of them)
+//!
+//! hint: run with sudo, or grant CAP_BPF via setcap once:
+//!   sudo setcap cap_bpf,cap_perfmon+ep /usr/local/bin/ebpf-tool
+//! ```
+//!
+//! `ebpf-tool check` reports the same two things without loading anything.
+
+use crate::doctor::{self, CAP_BPF, CAP_PERFMON, CAP_SYS_ADMIN};
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+
+/// Memlock limit that is always enough for the lessons' maps.
+const MEMLOCK_ENOUGH: u64 = 64 << 20;
+
+type Kernel = Option<(u32, u32, u32)>;
+
+/// What [`raise_memlock`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Memlock {
+    /// 5.11+ charges BPF memory to the memory cgroup: nothing to do
+    NotUsed,
+    /// Already unlimited or at least [`MEMLOCK_ENOUGH`]
+    Enough,
+    /// Raised to this many bytes (None: unlimited)
+    Raised(Option<u64>),
+    /// Could not be raised: loading may fail with EPERM
+    Low(u64),
+}
+
+/// Run both steps before `Ebpf::load()`. Capability problems are errors;
+/// a memlock limit that cannot be raised is only a warning, since small
+/// programs may still fit.
+pub fn before_load() -> Result<Memlock> {
+    let kernel = doctor::kernel_version().ok();
+    let caps = doctor::read_file("/proc/self/status")
+        .ok()
+        .and_then(|s| doctor::parse_cap_eff(&s));
+    check_privileges(caps, kernel)?;
+
+    let memlock = raise_memlock(kernel);
+    match memlock {
+        Memlock::Raised(limit) => log::debug!(
+            "Raised RLIMIT_MEMLOCK to {}",
+            limit.map_or("unlimited".to_string(), |b| format!("{} KiB", b / 1024))
+        ),
+        Memlock::Low(bytes) => log::warn!(
+            "RLIMIT_MEMLOCK is {} KiB and cannot be raised: maps may fail with EPERM \
+             (try `ulimit -l unlimited`)",
+            bytes / 1024
+        ),
+        Memlock::NotUsed | Memlock::Enough => {}
+    }
+    Ok(memlock)
+}
+
+/// Fail with an actionable message unless `caps` can load and attach
+/// tracing programs on `kernel`.
+pub fn check_privileges(caps: Option<u64>, kernel: Kernel) -> Result<()> {
+    // Unknown caps (no /proc?): let bpf() decide rather than refuse
+    let Some(caps) = caps else {
+        return Ok(());
+    };
+    if doctor::has_cap(caps, CAP_SYS_ADMIN) {
+        return Ok(());
+    }
+    let split_caps = doctor::at_least(kernel, 5, 8);
+    if split_caps && doctor::has_cap(caps, CAP_BPF) && doctor::has_cap(caps, CAP_PERFMON) {
+        return Ok(());
+    }
+
+    if !split_caps {
+        bail!(
+            "loading eBPF programs needs CAP_SYS_ADMIN on this kernel \
+             (CAP_BPF only exists since 5.8)\n\nhint: run with sudo"
+        );
+    }
+    let missing: Vec<&str> = [(CAP_BPF, "CAP_BPF"), (CAP_PERFMON, "CAP_PERFMON")]
+        .into_iter()
+        .filter(|(cap, _)| !doctor::has_cap(caps, *cap))
+        .map(|(_, name)| name)
+        .collect();
+    let has = if missing.len() == 2 {
+        "this process has none of them".to_string()
+    } else {
+        format!("this process lacks {}", missing[0])
+    };
+    bail!(
+        "loading eBPF programs needs CAP_BPF and CAP_PERFMON ({})\n\n\
+         hint: run with sudo, or grant CAP_BPF via setcap once:\n  \
+         sudo setcap cap_bpf,cap_perfmon+ep {}",
+        has,
+        binary_path().display()
+    )
+}
+
+fn binary_path() -> PathBuf {
+    std::env::current_exe().unwrap_or_else(|_| PathBuf::from("ebpf-tool"))
+}
+
+/// Raise the soft and hard RLIMIT_MEMLOCK where the kernel still uses it.
+pub fn raise_memlock(kernel: Kernel) -> Memlock {
+    if doctor::at_least(kernel, 5, 11) {
+        return Memlock::NotUsed;
+    }
+    let current = doctor::memlock_limit();
+    if current.is_none_or(|bytes| bytes >= MEMLOCK_ENOUGH) {
+        return Memlock::Enough;
+    }
+    // Unlimited needs CAP_SYS_RESOURCE to exceed the hard limit; without it
+    // (CAP_BPF via setcap) the soft limit can still go up to the hard one.
+    if set_memlock(libc::RLIM_INFINITY, libc::RLIM_INFINITY) {
+        return Memlock::Raised(None);
+    }
+    match hard_memlock() {
+        Some(hard) if hard > current.unwrap_or(0) && set_memlock(hard, hard) => {
+            Memlock::Raised((hard != libc::RLIM_INFINITY).then_some(hard))
+        }
+        _ => Memlock::Low(current.unwrap_or(0)),
+    }
+}
+
+fn set_memlock(soft: libc::rlim_t, hard: libc::rlim_t) -> bool {
+    let rlim = libc::rlimit {
+        rlim_cur: soft,
+        rlim_max: hard,
+    };
+    // SAFETY: setrlimit only reads the struct we pass.
+    unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &rlim) == 0 }
+}
+
+fn hard_memlock() -> Option<libc::rlim_t> {
+    let mut rlim = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit only writes to the struct we pass.
+    (unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut rlim) } == 0).then_some(rlim.rlim_max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEW: Kernel = Some((6, 8, 0));
+
+    #[test]
+    fn test_privileged_caps_pass() {
+        assert!(check_privileges(Some(1 << CAP_SYS_ADMIN), Some((5, 4, 0))).is_ok());
+        assert!(check_privileges(Some((1 << CAP_BPF) | (1 << CAP_PERFMON)), NEW).is_ok());
+        assert!(check_privileges(None, NEW).is_ok());
+    }
+
+    #[test]
+    fn test_missing_caps_explain_the_fix() {
+        let err = check_privileges(Some(0), NEW).unwrap_err().to_string();
+        assert!(err.contains("none of them"), "{err}");
+        assert!(err.contains("setcap cap_bpf,cap_perfmon+ep"), "{err}");
+
+        let err = check_privileges(Some(1 << CAP_BPF), NEW)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("lacks CAP_PERFMON"), "{err}");
+    }
+
+    #[test]
+    fn test_old_kernel_needs_sys_admin() {
+        let split = Some((1 << CAP_BPF) | (1 << CAP_PERFMON));
+        let err = check_privileges(split, Some((5, 4, 0)))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("CAP_SYS_ADMIN"), "{err}");
+        assert!(!err.contains("setcap"), "{err}");
+    }
+
+    #[test]
+    fn test_memlock_not_used_since_5_11() {
+        assert_eq!(raise_memlock(Some((5, 11, 0))), Memlock::NotUsed);
+        assert_eq!(raise_memlock(NEW), Memlock::NotUsed);
+    }
+}
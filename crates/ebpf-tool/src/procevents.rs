@@ -0,0 +1,133 @@
+//! `trace --proc-events`: the forks, execs and exits of a `ProcEvent` as text.
+//!
+//! The `procevents` programs send one event per step of a process's life;
+//! this module prints each as one line, after the timestamp:
+//!
+//! ```text
+//! ProcEvent { kind: PROC_FORK, pid: 4242, child_pid: 4243, .. } ─► sh(4242 ppid=4200) fork child=4243
+//! ProcEvent { kind: PROC_EXEC, pid: 4243, filename: "/bin/ls" } ─► ls(4243 ppid=4242) exec /bin/ls
+//! ProcEvent { kind: PROC_EXIT, pid: 4243, exit_code: 0x0100 }   ─► ls(4243 ppid=4242) exit 1
+//! ```
+//!
+//! The ids are the host's, like everything else `trace` prints: inside the
+//! container the same processes have other numbers.
+
+use ebpf_tool_common::{
+    c_str, ProcEvent, PROC_EXEC, PROC_EXIT, PROC_FORK, STR_PATH_TRUNCATED, STR_READ_FAILED,
+};
+use nix::sys::signal::Signal;
+
+/// `fork`, `exec` or `exit`.
+pub fn kind_name(kind: u32) -> &'static str {
+    match kind {
+        PROC_FORK => "fork",
+        PROC_EXEC => "exec",
+        PROC_EXIT => "exit",
+        _ => "?",
+    }
+}
+
+/// How a process ended, from the raw `exit_code` (`status << 8 | signal`):
+/// `0`, `1`, `SIGKILL`, `SIGSEGV (core dumped)`.
+///
+/// The same decoding as the shell's `$?` and `WIFSIGNALED()`, without the
+/// `128 +`: a name says more than 137.
+pub fn exit_status(code: i32) -> String {
+    let signal = code & 0x7f;
+    if signal == 0 {
+        return ((code >> 8) & 0xff).to_string();
+    }
+    let name = match Signal::try_from(signal) {
+        Ok(s) => s.as_str().to_string(),
+        Err(_) => format!("signal {}", signal),
+    };
+    if code & 0x80 != 0 {
+        format!("{} (core dumped)", name)
+    } else {
+        name
+    }
+}
+
+/// One event as `comm(pid ppid=N) kind detail`, without the timestamp.
+///
+/// `ppid=` is left out when the parent could not be read (0). An exec path
+/// the kernel could not read is `?`, a cut one ends in `...`.
+pub fn render(event: &ProcEvent) -> String {
+    let comm = String::from_utf8_lossy(c_str(&event.comm));
+    let mut line = if event.ppid != 0 {
+        format!("{}({} ppid={}) ", comm, event.pid, event.ppid)
+    } else {
+        format!("{}({}) ", comm, event.pid)
+    };
+    line.push_str(kind_name(event.kind));
+    match event.kind {
+        PROC_FORK => line.push_str(&format!(" child={}", event.child_pid)),
+        PROC_EXEC if event.flags & STR_READ_FAILED != 0 => line.push_str(" ?"),
+        PROC_EXEC => {
+            line.push(' ');
+            line.push_str(&String::from_utf8_lossy(c_str(&event.filename)));
+            if event.flags & STR_PATH_TRUNCATED != 0 {
+                line.push_str("...");
+            }
+        }
+        PROC_EXIT => line.push_str(&format!(" {}", exit_status(event.exit_code))),
+        _ => {}
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: u32, comm: &str, pid: u32, ppid: u32) -> ProcEvent {
+        let mut event = ProcEvent {
+            kind,
+            pid,
+            tid: pid,
+            ppid,
+            ..ProcEvent::new()
+        };
+        event.comm[..comm.len()].copy_from_slice(comm.as_bytes());
+        event
+    }
+
+    #[test]
+    fn test_exit_status() {
+        assert_eq!(exit_status(0), "0");
+        assert_eq!(exit_status(1 << 8), "1");
+        assert_eq!(exit_status(127 << 8), "127");
+        assert_eq!(exit_status(9), "SIGKILL");
+        assert_eq!(exit_status(0x80 | 11), "SIGSEGV (core dumped)");
+        assert_eq!(exit_status(0x7e), "signal 126");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut fork = event(PROC_FORK, "sh", 4242, 4200);
+        fork.child_pid = 4243;
+        assert_eq!(render(&fork), "sh(4242 ppid=4200) fork child=4243");
+
+        let mut exec = event(PROC_EXEC, "ls", 4243, 4242);
+        exec.filename[..7].copy_from_slice(b"/bin/ls");
+        assert_eq!(render(&exec), "ls(4243 ppid=4242) exec /bin/ls");
+
+        let mut exit = event(PROC_EXIT, "ls", 4243, 4242);
+        exit.exit_code = 2 << 8;
+        assert_eq!(render(&exit), "ls(4243 ppid=4242) exit 2");
+    }
+
+    #[test]
+    fn test_render_marks_what_could_not_be_read() {
+        // No BTF: no parent
+        let exit = event(PROC_EXIT, "sleep", 4250, 0);
+        assert_eq!(render(&exit), "sleep(4250) exit 0");
+
+        let mut exec = event(PROC_EXEC, "app", 4251, 1);
+        exec.flags = STR_READ_FAILED;
+        assert_eq!(render(&exec), "app(4251 ppid=1) exec ?");
+        exec.flags = STR_PATH_TRUNCATED;
+        exec.filename[..4].copy_from_slice(b"/opt");
+        assert_eq!(render(&exec), "app(4251 ppid=1) exec /opt...");
+    }
+}
@@ -0,0 +1,385 @@
+//! Tracing profiles: named presets of `trace` flags, for `ebpf-tool run`.
+//!
+//! ```toml
+//! # ~/.config/ebpf-tool/profiles.toml
+//! [nginx-files]
+//! description = "What nginx opens"
+//! syscalls = ["openat", "openat2"]        # the probe set
+//! filter = 'comm == "nginx" && pid != 1'
+//! push_down = true
+//! duration = 60
+//! output = "text"                          # or "tui"
+//! record = "/tmp/nginx.etrc"
+//! ```
+//!
+//! A profile expands to an ordinary `ebpf-tool trace` command line
+//! ([`Profile::trace_args`]), so it accepts exactly what `trace` accepts
+//! and clap checks it the same way. `syscalls` and `filter` are combined
+//! into one `--filter` expression; with `push_down`, the kernel drops the
+//! syscalls outside the set (see `filter::Expr::pushdown`).
+//!
+//! The built-in profiles in `profiles/builtin.toml` are compiled in. A
+//! profile in the user's file replaces the built-in one with the same name.
+
+use crate::filter;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Profiles compiled into the binary, for the lessons.
+const BUILTIN: &str = include_str!("../profiles/builtin.toml");
+
+/// How `trace` shows the events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    /// One line per event
+    #[default]
+    Text,
+    /// The live dashboard (`trace --tui`)
+    Tui,
+}
+
+/// One named profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// One line for `run --list`
+    pub description: Option<String>,
+    /// Syscalls to trace; empty means all of them
+    #[serde(default)]
+    pub syscalls: Vec<String>,
+    /// `--process`
+    pub process: Option<String>,
+    /// `--cgroup`
+    pub cgroup: Option<PathBuf>,
+    /// `--filter`, combined with `syscalls`
+    pub filter: Option<String>,
+    /// `--push-down`
+    #[serde(default)]
+    pub push_down: bool,
+    /// `--duration`; `trace`'s default if unset
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub output: Output,
+    /// `--record`
+    pub record: Option<PathBuf>,
+}
+
+impl Profile {
+    /// The `--filter` expression: the syscall set and `filter`, both
+    /// required to match.
+    pub fn filter_expr(&self) -> Option<String> {
+        let set = match self.syscalls.as_slice() {
+            [] => None,
+            [one] => Some(format!("syscall == \"{one}\"")),
+            many => Some(
+                many.iter()
+                    .map(|s| format!("syscall == \"{s}\""))
+                    .collect::<Vec<_>>()
+                    .join(" || "),
+            ),
+        };
+        match (set, &self.filter) {
+            (None, None) => None,
+            (Some(set), None) => Some(set),
+            (None, Some(f)) => Some(f.clone()),
+            (Some(set), Some(f)) if self.syscalls.len() == 1 => Some(format!("{set} && ({f})")),
+            (Some(set), Some(f)) => Some(format!("({set}) && ({f})")),
+        }
+    }
+
+    /// The command line this profile stands for, including the program
+    /// name, ready for `Cli::try_parse_from`.
+    pub fn trace_args(&self, duration: Option<u64>) -> Vec<String> {
+        let mut args = vec!["ebpf-tool".to_string(), "trace".to_string()];
+        if let Some(ref p) = self.process {
+            args.extend(["--process".to_string(), p.clone()]);
+        }
+        if let Some(ref c) = self.cgroup {
+            args.extend(["--cgroup".to_string(), c.display().to_string()]);
+        }
+        if let Some(f) = self.filter_expr() {
+            args.extend(["--filter".to_string(), f]);
+        }
+        if self.push_down {
+            args.push("--push-down".to_string());
+        }
+        if self.output == Output::Tui {
+            args.push("--tui".to_string());
+        }
+        if let Some(ref r) = self.record {
+            args.extend(["--record".to_string(), r.display().to_string()]);
+        }
+        if let Some(d) = duration.or(self.duration) {
+            args.extend(["--duration".to_string(), d.to_string()]);
+        }
+        args
+    }
+
+    /// Catch mistakes when the file is loaded, not after a trace started.
+    fn validate(&self) -> Result<()> {
+        for s in &self.syscalls {
+            if s.is_empty() || !s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
+                bail!("invalid syscall name \"{s}\"");
+            }
+        }
+        if let Some(ref f) = self.filter {
+            filter::parse(f)?;
+        }
+        if self.push_down && self.filter_expr().is_none() {
+            bail!("push_down needs syscalls or a filter to push down");
+        }
+        Ok(())
+    }
+}
+
+/// Where a profile was defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Builtin,
+    File(PathBuf),
+}
+
+/// All profiles that `run` can use, by name.
+#[derive(Debug, Clone, Default)]
+pub struct Profiles {
+    profiles: BTreeMap<String, (Profile, Source)>,
+}
+
+impl Profiles {
+    /// The built-in profiles only.
+    pub fn builtin() -> Profiles {
+        let mut profiles = Profiles::default();
+        profiles
+            .add(BUILTIN, Source::Builtin)
+            .expect("profiles/builtin.toml is valid");
+        profiles
+    }
+
+    /// The built-in profiles, overridden by the ones in `path`.
+    ///
+    /// `required` is false for the default path: no file there just means
+    /// there are no user profiles.
+    pub fn load(path: &Path, required: bool) -> Result<Profiles> {
+        let mut profiles = Profiles::builtin();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => return Ok(profiles),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+        profiles
+            .add(&text, Source::File(path.to_path_buf()))
+            .with_context(|| format!("invalid profiles file {}", path.display()))?;
+        Ok(profiles)
+    }
+
+    fn add(&mut self, text: &str, source: Source) -> Result<()> {
+        let parsed: BTreeMap<String, Profile> = toml::from_str(text)?;
+        for (name, profile) in parsed {
+            profile
+                .validate()
+                .with_context(|| format!("profile \"{name}\""))?;
+            self.profiles.insert(name, (profile, source.clone()));
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Profile> {
+        match self.profiles.get(name) {
+            Some((profile, _)) => Ok(profile),
+            None => bail!(
+                "no profile named \"{name}\" (available: {}); see `ebpf-tool run --list`",
+                self.profiles
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// Name, profile and source of every profile, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Profile, &Source)> {
+        self.profiles
+            .iter()
+            .map(|(name, (profile, source))| (name.as_str(), profile, source))
+    }
+}
+
+/// `profiles.toml` in the user's config directory.
+///
+/// Under sudo, this is the invoking user's file: `run` needs root, and the
+/// profiles belong to whoever wrote them, not to root.
+pub fn default_path() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(dir).join("ebpf-tool/profiles.toml"));
+    }
+    let sudo_home = std::env::var("SUDO_USER")
+        .ok()
+        .and_then(|user| nix::unistd::User::from_name(&user).ok().flatten())
+        .map(|user| user.dir);
+    let home = match sudo_home {
+        Some(home) => home,
+        None => PathBuf::from(
+            std::env::var_os("HOME").context("neither XDG_CONFIG_HOME nor HOME is set")?,
+        ),
+    };
+    Ok(home.join(".config/ebpf-tool/profiles.toml"))
+}
+
+/// Quote `args` for a shell, for `run --dry-run`.
+pub fn shell_words(args: &[String]) -> String {
+    args.iter()
+        .map(|a| {
+            let plain = !a.is_empty()
+                && a.bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-_./=:,".contains(&b));
+            if plain {
+                a.clone()
+            } else {
+                format!("'{}'", a.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Profiles> {
+        let mut p = Profiles::default();
+        p.add(text, Source::Builtin)?;
+        Ok(p)
+    }
+
+    #[test]
+    fn test_builtin_profiles_are_valid() {
+        let p = Profiles::builtin();
+        for (name, profile, _) in p.iter() {
+            assert!(profile.description.is_some(), "{name} has no description");
+            let args = profile.trace_args(None);
+            assert_eq!(args[1], "trace");
+        }
+        assert!(p.get("file-access").is_ok());
+    }
+
+    #[test]
+    fn test_trace_args() {
+        let p = parse(
+            r#"
+            [web]
+            syscalls = ["openat", "connect"]
+            filter = 'comm == "nginx"'
+            cgroup = "/sys/fs/cgroup/web"
+            push_down = true
+            duration = 60
+            output = "tui"
+            record = "/tmp/web.etrc"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            p.get("web").unwrap().trace_args(None),
+            [
+                "ebpf-tool",
+                "trace",
+                "--cgroup",
+                "/sys/fs/cgroup/web",
+                "--filter",
+                r#"(syscall == "openat" || syscall == "connect") && (comm == "nginx")"#,
+                "--push-down",
+                "--tui",
+                "--record",
+                "/tmp/web.etrc",
+                "--duration",
+                "60",
+            ]
+        );
+        // -d on the command line wins over the profile
+        let args = p.get("web").unwrap().trace_args(Some(5));
+        assert_eq!(args[args.len() - 2..], ["--duration", "5"]);
+    }
+
+    #[test]
+    fn test_filter_expr() {
+        let one = Profile {
+            syscalls: vec!["openat".into()],
+            filter: Some("pid != 1".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            one.filter_expr().as_deref(),
+            Some(r#"syscall == "openat" && (pid != 1)"#)
+        );
+        assert!(filter::parse(&one.filter_expr().unwrap()).is_ok());
+        assert_eq!(Profile::default().filter_expr(), None);
+        assert_eq!(Profile::default().trace_args(None), ["ebpf-tool", "trace"]);
+    }
+
+    #[test]
+    fn test_invalid_profiles() {
+        let err = parse("[a]\nsyscall = [\"openat\"]\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown field `syscall`"),
+            "{err:#}"
+        );
+
+        let err = parse("[a]\noutput = \"json\"\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown variant `json`"),
+            "{err:#}"
+        );
+
+        let err = parse("[a]\nfilter = \"pid == abc\"\n").unwrap_err();
+        assert!(
+            format!("{err:#}").starts_with("profile \"a\": invalid filter"),
+            "{err:#}"
+        );
+
+        let err = parse("[a]\nsyscalls = [\"open\\\"at\"]\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("invalid syscall name"),
+            "{err:#}"
+        );
+
+        let err = parse("[a]\npush_down = true\n").unwrap_err();
+        assert!(format!("{err:#}").contains("push_down needs"), "{err:#}");
+    }
+
+    #[test]
+    fn test_user_file_overrides_builtin() {
+        let dir = std::env::temp_dir().join(format!("ebpf-tool-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("profiles.toml");
+        std::fs::write(&path, "[exec]\ndescription = \"mine\"\nduration = 3\n").unwrap();
+
+        let p = Profiles::load(&path, true).unwrap();
+        assert_eq!(p.get("exec").unwrap().duration, Some(3));
+        assert!(p.get("file-access").is_ok());
+        let (_, _, source) = p.iter().find(|(name, _, _)| *name == "exec").unwrap();
+        assert_eq!(source, &Source::File(path.clone()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(Profiles::load(&path, false).is_ok());
+        assert!(Profiles::load(&path, true).is_err());
+
+        let err = p.get("nope").unwrap_err().to_string();
+        assert!(err.contains("available: container-setup, exec,"), "{err}");
+    }
+
+    #[test]
+    fn test_shell_words() {
+        let args: Vec<String> = ["trace", "--filter", r#"comm == "it's""#, "-d", "5"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            shell_words(&args),
+            r#"trace --filter 'comm == "it'\''s"' -d 5"#
+        );
+    }
+}
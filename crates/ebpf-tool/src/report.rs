@@ -0,0 +1,321 @@
+//! Run reports: a JSON summary of every tracing run.
+//!
+//! Each tracing subcommand fills a [`RunReport`] while it runs (programs
+//! attached, event counts, top results). `run_cli` saves it when the command
+//! returns, successful or not, and `ebpf-tool runs list|show` reads them
+//! back. One file per run:
+//!
+//! ```text
+//! ~/.local/state/ebpf-tool/runs/
+//!   1791981296-4242-trace.json
+//!   1791981410-4301-reqlat.json
+//! ```
+//!
+//! Reports are plain JSON so a lesson can be graded with `jq` as well as
+//! with `runs show`, and two experiments compared with `compare`.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::timebase::Timebase;
+
+/// A program attached during the run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramRecord {
+    /// Program name in the eBPF object (e.g. "syscall_kprobe")
+    pub name: String,
+    /// Where it was attached (e.g. "kprobe:do_sys_openat2")
+    pub attach: String,
+}
+
+/// One row of the run's "top" results (top syscalls, top processes, ...).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopEntry {
+    pub label: String,
+    pub count: u64,
+}
+
+/// Latency percentiles of one kind of operation (e.g. "request",
+/// "openat"), in nanoseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyRecord {
+    pub label: String,
+    /// Number of samples the percentiles are computed from
+    pub count: u64,
+    pub p50_ns: u64,
+    pub p90_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+/// Summary of one run of a tracing subcommand.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    /// `<unix secs>-<pid>-<command>`: unique, and sorts by start time
+    pub id: String,
+    pub command: String,
+    /// Full command line, including the binary name
+    pub args: Vec<String>,
+    pub started_unix: u64,
+    pub duration_ms: u64,
+    pub programs: Vec<ProgramRecord>,
+    /// Events received in userspace
+    pub events: u64,
+    /// Events lost (perf buffer overruns reported by the kernel)
+    pub drops: u64,
+    /// Parts of the run whose totals were backfilled from the counting map
+    /// because events were lost (`trace` only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<crate::backfill::Window>,
+    /// Clock samples of the run: turns any event timestamp of the run into
+    /// wall-clock time afterwards (see src/timebase.rs)
+    #[serde(default, skip_serializing_if = "Timebase::is_empty")]
+    pub timebase: Timebase,
+    pub errors: Vec<String>,
+    pub top: Vec<TopEntry>,
+    /// Latency percentiles (`reqlat`, `trace --returns`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub latency: Vec<LatencyRecord>,
+    /// Hottest stacks in folded form ("main;parse;lex"), with their sample
+    /// counts (`perf`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stacks: Vec<TopEntry>,
+    /// N of `trace --sample 1/N`: counts taken from events were scaled
+    /// by N and are estimates (see src/sample.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<u32>,
+    pub success: bool,
+    #[serde(skip)]
+    started_at: Option<Instant>,
+}
+
+impl RunReport {
+    /// Start a report for `command`, timed from now, with one clock sample.
+    /// Commands that recalibrate while they run replace `timebase` with
+    /// their clock's.
+    pub fn begin(command: &str, args: impl IntoIterator<Item = String>) -> Self {
+        let started_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            id: format!("{}-{}-{}", started_unix, std::process::id(), command),
+            command: command.to_string(),
+            args: args.into_iter().collect(),
+            started_unix,
+            timebase: Timebase::calibrate(),
+            started_at: Some(Instant::now()),
+            ..Self::default()
+        }
+    }
+
+    /// Record an attached program.
+    #[allow(dead_code)]
+    pub fn program(&mut self, name: &str, attach: &str) -> &mut Self {
+        self.programs.push(ProgramRecord {
+            name: name.to_string(),
+            attach: attach.to_string(),
+        });
+        self
+    }
+
+    /// Stop the clock and record how the command ended.
+    pub fn finish(&mut self, result: &Result<()>) {
+        if let Some(started_at) = self.started_at {
+            self.duration_ms = started_at.elapsed().as_millis() as u64;
+        }
+        self.success = result.is_ok();
+        if let Err(e) = result {
+            self.errors.push(format!("{e:#}"));
+        }
+    }
+
+    /// Write the report to `<dir>/<id>.json` and return the path.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{}.json", self.id));
+        let tmp = dir.join(format!(".{}.json.tmp", self.id));
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// Read a report written by `save()`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("invalid report {}", path.display()))
+    }
+
+    /// One line for `runs list`.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{:<28} {:<10} {:>8.1}s {:>10} {:>6}  {}",
+            self.id,
+            self.command,
+            self.duration_ms as f64 / 1000.0,
+            self.events,
+            self.drops,
+            if self.success { "ok" } else { "error" }
+        )
+    }
+}
+
+/// Directory holding the run reports.
+///
+/// `$XDG_STATE_HOME/ebpf-tool/runs`, or `~/.local/state/ebpf-tool/runs`.
+pub fn runs_dir() -> Result<PathBuf> {
+    let state = match std::env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let home =
+                std::env::var_os("HOME").context("neither XDG_STATE_HOME nor HOME is set")?;
+            PathBuf::from(home).join(".local/state")
+        }
+    };
+    Ok(state.join("ebpf-tool/runs"))
+}
+
+/// All reports in `dir`, oldest first. Files that do not parse are skipped.
+pub fn list(dir: &Path) -> Result<Vec<RunReport>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+
+    let mut reports = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            match RunReport::load(&path) {
+                Ok(report) => reports.push(report),
+                Err(e) => log::warn!("Skipping {}: {:#}", path.display(), e),
+            }
+        }
+    }
+    reports.sort_by(|a, b| (a.started_unix, &a.id).cmp(&(b.started_unix, &b.id)));
+    Ok(reports)
+}
+
+/// Find one report by exact id, unique id prefix, or "last".
+pub fn find(dir: &Path, spec: &str) -> Result<RunReport> {
+    let reports = list(dir)?;
+    if spec == "last" {
+        return reports.into_iter().last().context("no runs recorded yet");
+    }
+    if let Some(report) = reports.iter().find(|r| r.id == spec) {
+        return Ok(report.clone());
+    }
+
+    let mut matches: Vec<RunReport> = reports
+        .into_iter()
+        .filter(|r| r.id.starts_with(spec))
+        .collect();
+    match matches.len() {
+        0 => bail!("no run matching '{}' in {}", spec, dir.display()),
+        1 => Ok(matches.remove(0)),
+        n => bail!("'{}' matches {} runs; use a longer prefix", spec, n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(id: &str, started_unix: u64) -> RunReport {
+        RunReport {
+            id: id.to_string(),
+            command: "trace".to_string(),
+            started_unix,
+            ..RunReport::default()
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut original = RunReport::begin("trace", vec!["ebpf-tool".into(), "trace".into()]);
+        original.program("syscall_kprobe", "kprobe:do_sys_openat2");
+        original.events = 42;
+        original.degraded.push(crate::backfill::Window {
+            start_ms: 1000,
+            end_ms: 3000,
+            lost: 12,
+            backfilled: 12,
+        });
+        original.top.push(TopEntry {
+            label: "openat".into(),
+            count: 40,
+        });
+        original.latency.push(LatencyRecord {
+            label: "request".into(),
+            count: 40,
+            p50_ns: 412_000,
+            p90_ns: 1_800_000,
+            p99_ns: 12_400_000,
+            max_ns: 31_000_000,
+        });
+        original.finish(&Ok(()));
+
+        let path = original.save(dir.path()).unwrap();
+        let loaded = RunReport::load(&path).unwrap();
+
+        assert_eq!(
+            path.file_name().unwrap(),
+            format!("{}.json", original.id).as_str()
+        );
+        assert_eq!(loaded.programs, original.programs);
+        assert_eq!(loaded.top, original.top);
+        assert_eq!(loaded.latency, original.latency);
+        assert!(loaded.stacks.is_empty());
+        assert_eq!(loaded.events, 42);
+        assert_eq!(loaded.degraded, original.degraded);
+        assert_eq!(loaded.timebase, original.timebase);
+        assert_eq!(loaded.timebase.samples().len(), 1);
+        assert!(loaded.success);
+    }
+
+    #[test]
+    fn test_finish_records_error() {
+        let mut report = RunReport::begin("reqlat", Vec::new());
+        report.finish(&Err(anyhow::anyhow!("attach failed")));
+
+        assert!(!report.success);
+        assert_eq!(report.errors, vec!["attach failed".to_string()]);
+        assert!(report.id.ends_with("-reqlat"));
+    }
+
+    #[test]
+    fn test_list_sorts_and_skips_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        report("200-1-trace", 200).save(dir.path()).unwrap();
+        report("100-1-trace", 100).save(dir.path()).unwrap();
+        std::fs::write(dir.path().join("broken.json"), "{").unwrap();
+
+        let ids: Vec<String> = list(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+
+        assert_eq!(ids, vec!["100-1-trace", "200-1-trace"]);
+        assert!(list(&dir.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_by_id_prefix_and_last() {
+        let dir = tempfile::tempdir().unwrap();
+        report("100-1-trace", 100).save(dir.path()).unwrap();
+        report("100-2-trace", 100).save(dir.path()).unwrap();
+        report("300-1-stats", 300).save(dir.path()).unwrap();
+
+        assert_eq!(find(dir.path(), "last").unwrap().id, "300-1-stats");
+        assert_eq!(find(dir.path(), "100-2").unwrap().id, "100-2-trace");
+        assert!(find(dir.path(), "100").is_err()); // ambiguous
+        assert!(find(dir.path(), "999").is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! `trace --sample 1/N`: the userspace side of in-kernel sampling.
+//!
+//! The eBPF program sends about one in N of the events that pass the
+//! filters (`crates/ebpf-tool-ebpf/src/sample.rs`). Userspace stays honest
+//! about it in two ways:
+//!
+//! - Counts derived from events are estimates, `count * N`, and are
+//!   printed with a `~` (`read() x ~54200`)
+//! - Totals that must be exact come from `SYSCALL_COUNTS`, which the
+//!   kernel updates before it samples
+//!
+//! A sampled count of k events estimates `k * N` with a relative standard
+//! error of about `1 / sqrt(k)`: 100 sampled events are good to ~10%,
+//! whatever N is.
+
+use std::fmt;
+
+use ebpf_tool_common::SAMPLE_MAX_RATE;
+
+/// The N of `--sample 1/N`. `SampleRate(1)` is "every event".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleRate(pub u32);
+
+impl SampleRate {
+    pub const ALL: SampleRate = SampleRate(1);
+
+    pub fn is_sampled(self) -> bool {
+        self.0 > 1
+    }
+
+    /// Number of events `sampled` received events stand for.
+    pub fn estimate(self, sampled: u64) -> u64 {
+        sampled.saturating_mul(self.0 as u64)
+    }
+
+    /// `estimate()` as text: exact counts as is, estimates with a `~`.
+    pub fn format_count(self, sampled: u64) -> String {
+        if self.is_sampled() {
+            format!("~{}", self.estimate(sampled))
+        } else {
+            sampled.to_string()
+        }
+    }
+}
+
+impl fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "1/{}", self.0)
+    }
+}
+
+/// Parse `--sample 1/N`, N from 1 to `SAMPLE_MAX_RATE`.
+pub fn parse_sample(s: &str) -> Result<SampleRate, String> {
+    let rate = s
+        .strip_prefix("1/")
+        .ok_or_else(|| format!("invalid sample rate {s:?}: expected 1/N, e.g. 1/100"))?;
+    let n: u32 = rate
+        .parse()
+        .map_err(|_| format!("invalid sample rate {s:?}: N must be a number"))?;
+    if n == 0 || n > SAMPLE_MAX_RATE {
+        return Err(format!(
+            "invalid sample rate {s:?}: N must be between 1 and {SAMPLE_MAX_RATE}"
+        ));
+    }
+    Ok(SampleRate(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sample() {
+        assert_eq!(parse_sample("1/100"), Ok(SampleRate(100)));
+        assert_eq!(parse_sample("1/1"), Ok(SampleRate::ALL));
+        assert!(parse_sample("100").is_err());
+        assert!(parse_sample("2/100").is_err());
+        assert!(parse_sample("1/0").is_err());
+        assert!(parse_sample("1/-5").is_err());
+        assert!(parse_sample("1/2000000").is_err());
+        assert_eq!(SampleRate(100).to_string(), "1/100");
+    }
+
+    #[test]
+    fn test_estimates() {
+        assert!(!SampleRate::ALL.is_sampled());
+        assert_eq!(SampleRate::ALL.format_count(542), "542");
+        assert_eq!(SampleRate(100).estimate(542), 54200);
+        assert_eq!(SampleRate(100).format_count(542), "~54200");
+        assert_eq!(SampleRate(1000).estimate(u64::MAX), u64::MAX);
+    }
+}
@@ -0,0 +1,288 @@
+//! `ebpf-tool sched`: CPU time per cgroup, from `CGROUP_CPU`.
+//!
+//! `sched_tracepoint` charges every slice of CPU time to the cgroup of the
+//! task that used it. The counters only grow, so `sched` reads the map
+//! every interval and reports the difference:
+//!
+//! ```text
+//! CGROUP_CPU (per-CPU, by id) ─► sum CPUs ─► delta(before, after) ─► rows(): id -> path,
+//!                                                                        roll up to --depth
+//!                                                                    ─► render()
+//! ```
+//!
+//! `CPU%` is the same as in `top`: 100 is one CPU busy for the whole
+//! interval, so a container can show 250 on a machine with 4 CPUs.
+//! `SHARE` is the part of all busy time (idle not included) that went to
+//! the cgroup: the "who gets the CPU" column. Time is charged when a task
+//! is switched out, so a task that has been running without a switch all
+//! interval is only counted once it stops.
+
+use ebpf_tool_common::CgroupCpu;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Label for cgroup ids with no directory: removed since, or not under the
+/// cgroup v2 mount.
+pub const GONE: &str = "(gone)";
+
+/// The counters of one interval: `after - before` for each cgroup id.
+///
+/// An id not in `before` is new and counts from 0. An id whose counters
+/// went down was evicted from the LRU map and inserted again, so it also
+/// counts from 0. Ids missing from `after` are gone and have nothing to
+/// report.
+pub fn delta(
+    before: &HashMap<u64, CgroupCpu>,
+    after: &HashMap<u64, CgroupCpu>,
+) -> HashMap<u64, CgroupCpu> {
+    after
+        .iter()
+        .map(|(&id, now)| {
+            let then = before
+                .get(&id)
+                .filter(|then| then.on_cpu_ns <= now.on_cpu_ns && then.switches <= now.switches)
+                .copied()
+                .unwrap_or_default();
+            let d = CgroupCpu {
+                on_cpu_ns: now.on_cpu_ns - then.on_cpu_ns,
+                switches: now.switches - then.switches,
+                involuntary: now.involuntary.saturating_sub(then.involuntary),
+            };
+            (id, d)
+        })
+        .filter(|(_, d)| d.switches > 0)
+        .collect()
+}
+
+/// The ancestor of `path` at `depth` components: `ancestor("/a/b/c", 1)`
+/// is `/a`, depth 0 is the root. Paths already at or above `depth` are
+/// returned as is.
+pub fn ancestor(path: &str, depth: usize) -> &str {
+    if depth == 0 {
+        return "/";
+    }
+    match path.match_indices('/').nth(depth) {
+        Some((end, _)) => &path[..end],
+        None => path,
+    }
+}
+
+/// One line of the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Cgroup path relative to the cgroup root, or [`GONE`]
+    pub cgroup: String,
+    pub usage: CgroupCpu,
+}
+
+impl Row {
+    /// On-CPU time over `elapsed`, in percent of one CPU.
+    pub fn cpu_percent(&self, elapsed: Duration) -> f64 {
+        match elapsed.as_nanos() {
+            0 => 0.0,
+            ns => self.usage.on_cpu_ns as f64 * 100.0 / ns as f64,
+        }
+    }
+
+    /// Part of the switches where the task was preempted, in percent.
+    pub fn involuntary_percent(&self) -> f64 {
+        match self.usage.switches {
+            0 => 0.0,
+            n => self.usage.involuntary as f64 * 100.0 / n as f64,
+        }
+    }
+}
+
+/// Name the cgroups of `usage` with `path_of` (None: [`GONE`]), roll them
+/// up to `depth` if given, and sort them by CPU time, busiest first.
+pub fn rows(
+    usage: &HashMap<u64, CgroupCpu>,
+    path_of: impl Fn(u64) -> Option<String>,
+    depth: Option<usize>,
+) -> Vec<Row> {
+    let mut grouped: HashMap<String, CgroupCpu> = HashMap::new();
+    for (&id, u) in usage {
+        let path = path_of(id).unwrap_or_else(|| GONE.to_string());
+        let key = match depth {
+            Some(d) if path != GONE => ancestor(&path, d).to_string(),
+            _ => path,
+        };
+        let total = grouped.entry(key).or_default();
+        total.on_cpu_ns += u.on_cpu_ns;
+        total.switches += u.switches;
+        total.involuntary += u.involuntary;
+    }
+    let mut rows: Vec<Row> = grouped
+        .into_iter()
+        .map(|(cgroup, usage)| Row { cgroup, usage })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.usage
+            .on_cpu_ns
+            .cmp(&a.usage.on_cpu_ns)
+            .then_with(|| a.cgroup.cmp(&b.cgroup))
+    });
+    rows
+}
+
+/// The table for one interval: the `top` busiest rows, then a total over
+/// all of them.
+pub fn render(rows: &[Row], elapsed: Duration, cpus: usize, top: usize) -> String {
+    let busy: u64 = rows.iter().map(|r| r.usage.on_cpu_ns).sum();
+    let secs = elapsed.as_secs_f64().max(f64::MIN_POSITIVE);
+    let width = rows
+        .iter()
+        .take(top)
+        .map(|r| r.cgroup.len())
+        .max()
+        .unwrap_or(0)
+        .max("CGROUP".len());
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<width$}  {:>6}  {:>6}  {:>10}  {:>6}",
+        "CGROUP", "CPU%", "SHARE", "SWITCHES/s", "INVOL%"
+    );
+    for row in rows.iter().take(top) {
+        let share = match busy {
+            0 => 0.0,
+            b => row.usage.on_cpu_ns as f64 * 100.0 / b as f64,
+        };
+        let _ = writeln!(
+            out,
+            "{:<width$}  {:>6.1}  {:>5.1}%  {:>10.0}  {:>5.1}%",
+            row.cgroup,
+            row.cpu_percent(elapsed),
+            share,
+            row.usage.switches as f64 / secs,
+            row.involuntary_percent()
+        );
+    }
+    if rows.len() > top {
+        let _ = writeln!(out, "... {} more", rows.len() - top);
+    }
+    let total = Row {
+        cgroup: String::new(),
+        usage: CgroupCpu {
+            on_cpu_ns: busy,
+            switches: rows.iter().map(|r| r.usage.switches).sum(),
+            involuntary: rows.iter().map(|r| r.usage.involuntary).sum(),
+        },
+    };
+    let _ = writeln!(
+        out,
+        "total: {:.1}% of {} CPUs busy, {:.0} switches/s",
+        total.cpu_percent(elapsed) / cpus.max(1) as f64,
+        cpus,
+        total.usage.switches as f64 / secs
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    fn cpu(on_cpu_ms: u64, switches: u64, involuntary: u64) -> CgroupCpu {
+        CgroupCpu {
+            on_cpu_ns: on_cpu_ms * MS,
+            switches,
+            involuntary,
+        }
+    }
+
+    #[test]
+    fn test_delta() {
+        let before = HashMap::from([
+            (10, cpu(100, 10, 1)),
+            (11, cpu(50, 5, 0)),
+            (12, cpu(900, 90, 9)),
+        ]);
+        let after = HashMap::from([
+            (10, cpu(350, 30, 6)), // grew
+            (11, cpu(50, 5, 0)),   // idle this interval
+            (12, cpu(20, 2, 1)),   // evicted and inserted again
+            (13, cpu(5, 1, 0)),    // new
+        ]);
+        let d = delta(&before, &after);
+        assert_eq!(d.len(), 3);
+        assert_eq!(d[&10], cpu(250, 20, 5));
+        assert_eq!(d[&12], cpu(20, 2, 1));
+        assert_eq!(d[&13], cpu(5, 1, 0));
+    }
+
+    #[test]
+    fn test_ancestor() {
+        let path = "/system.slice/docker-3f2a.scope/init";
+        assert_eq!(ancestor(path, 0), "/");
+        assert_eq!(ancestor(path, 1), "/system.slice");
+        assert_eq!(ancestor(path, 2), "/system.slice/docker-3f2a.scope");
+        assert_eq!(ancestor(path, 5), path);
+        assert_eq!(ancestor("/", 1), "/");
+    }
+
+    #[test]
+    fn test_rows_group_and_sort() {
+        let usage = HashMap::from([
+            (1, cpu(100, 10, 0)),
+            (2, cpu(300, 30, 3)),
+            (3, cpu(200, 20, 2)),
+            (4, cpu(1, 1, 0)),
+        ]);
+        let path_of = |id| match id {
+            1 => Some("/user.slice".to_string()),
+            2 => Some("/lab/web".to_string()),
+            3 => Some("/lab/db".to_string()),
+            _ => None,
+        };
+
+        let flat = rows(&usage, path_of, None);
+        let names: Vec<_> = flat.iter().map(|r| r.cgroup.as_str()).collect();
+        assert_eq!(names, ["/lab/web", "/lab/db", "/user.slice", GONE]);
+
+        let rolled = rows(&usage, path_of, Some(1));
+        assert_eq!(rolled[0].cgroup, "/lab");
+        assert_eq!(rolled[0].usage, cpu(500, 50, 5));
+        assert_eq!(rolled.len(), 3);
+        assert_eq!(rolled[2].cgroup, GONE);
+    }
+
+    #[test]
+    fn test_percentages() {
+        let row = Row {
+            cgroup: "/lab".into(),
+            usage: cpu(1500, 40, 10),
+        };
+        // 1.5 s of CPU in 1 s: one and a half CPUs
+        assert_eq!(row.cpu_percent(Duration::from_secs(1)), 150.0);
+        assert_eq!(row.cpu_percent(Duration::ZERO), 0.0);
+        assert_eq!(row.involuntary_percent(), 25.0);
+    }
+
+    #[test]
+    fn test_render() {
+        let rows = vec![
+            Row {
+                cgroup: "/lab/web".into(),
+                usage: cpu(750, 300, 30),
+            },
+            Row {
+                cgroup: "/user.slice".into(),
+                usage: cpu(250, 100, 0),
+            },
+        ];
+        let text = render(&rows, Duration::from_secs(1), 4, 1);
+        let lines: Vec<_> = text.lines().collect();
+        assert!(lines[0].starts_with("CGROUP"));
+        assert!(lines[1].starts_with("/lab/web"));
+        assert!(lines[1].contains("75.0"));
+        assert!(lines[1].contains("75.0%"));
+        assert!(lines[1].contains("10.0%"));
+        assert_eq!(lines[2], "... 1 more");
+        assert_eq!(lines[3], "total: 25.0% of 4 CPUs busy, 400 switches/s");
+    }
+}
@@ -0,0 +1,351 @@
+//! Where `trace` and `replay` get their events from: [`EventSource`].
+//!
+//! The printing path (decode, comm cache, filters, rendering) does not care
+//! whether a record came from a perf array a second ago or from a capture
+//! recorded last week. It reads [`Record`]s from an `EventSource`:
+//!
+//! ```text
+//! perf arrays ─► reader tasks ─► channel() ─► ChannelSource ─┐
+//! .etrc file ─────────────────────────────► capture::Reader ─┼► Decoder ─► print_events()
+//! a unit test ──────────────────────────────────► MockSource ┘
+//! ```
+//!
+//! [`MockSource`] builds records from synthetic events, so the filters and
+//! the formatting can be tested without root, eBPF or a capture file.
+//! [`Decoder`] turns records into typed events with the same checks as a
+//! live trace: a bad record is skipped and counted, a version mismatch ends
+//! the pass.
+
+use crate::capture::{self, Record, KIND_COMM_UPDATE, KIND_SYSCALL, KIND_SYSCALL_EXIT};
+use crate::events::EventParser;
+use anyhow::Result;
+use bytemuck::Pod;
+use ebpf_tool_common::{CommUpdateEvent, SyscallEvent, SyscallExitEvent, COMM_LEN};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::sync::mpsc;
+
+/// A stream of raw event records.
+pub trait EventSource {
+    /// Next record, or None when the source has nothing more.
+    fn next_record(&mut self) -> Result<Option<Record>>;
+}
+
+impl<R: Read> EventSource for capture::Reader<R> {
+    fn next_record(&mut self) -> Result<Option<Record>> {
+        capture::Reader::next_record(self)
+    }
+}
+
+/// The receiving end of [`channel`]: records sent by the perf array readers
+/// of a live trace.
+#[derive(Debug)]
+pub struct ChannelSource {
+    rx: mpsc::Receiver<Record>,
+}
+
+/// A channel for a live trace. The reader tasks send every record (and
+/// `Record::Lost` for lost counts) to the sender; the source ends when all
+/// senders are dropped.
+pub fn channel() -> (mpsc::Sender<Record>, ChannelSource) {
+    let (tx, rx) = mpsc::channel();
+    (tx, ChannelSource { rx })
+}
+
+impl EventSource for ChannelSource {
+    /// Blocks until a record arrives: call it from a blocking thread
+    /// (`tokio::task::spawn_blocking`), not from a tokio task.
+    fn next_record(&mut self) -> Result<Option<Record>> {
+        Ok(self.rx.recv().ok())
+    }
+}
+
+/// Records made up in a test, returned in the order they were added.
+///
+/// ```text
+/// MockSource::new()
+///     .syscall(mock_syscall(100, "bash", nr, 1_000))
+///     .comm_update(mock_rename(100, "worker"))
+///     .lost(0, 3)
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockSource {
+    records: VecDeque<Record>,
+}
+
+impl MockSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A `KIND_SYSCALL` record from CPU 0.
+    pub fn syscall(self, event: SyscallEvent) -> Self {
+        self.event(KIND_SYSCALL, &event)
+    }
+
+    /// A `KIND_SYSCALL_EXIT` record from CPU 0.
+    pub fn exit(self, event: SyscallExitEvent) -> Self {
+        self.event(KIND_SYSCALL_EXIT, &event)
+    }
+
+    pub fn comm_update(self, event: CommUpdateEvent) -> Self {
+        self.event(KIND_COMM_UPDATE, &event)
+    }
+
+    /// `count` events lost on `cpu`.
+    pub fn lost(self, cpu: u16, count: u64) -> Self {
+        self.record(Record::Lost { cpu, count })
+    }
+
+    /// Any record, e.g. a truncated one or an unknown kind.
+    pub fn record(mut self, record: Record) -> Self {
+        self.records.push_back(record);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn event<T: Pod>(self, kind: u16, event: &T) -> Self {
+        self.record(Record::Event {
+            kind,
+            cpu: 0,
+            data: capture::struct_bytes(event).to_vec(),
+        })
+    }
+}
+
+impl EventSource for MockSource {
+    fn next_record(&mut self) -> Result<Option<Record>> {
+        Ok(self.records.pop_front())
+    }
+}
+
+/// A syscall entry of the main thread of `pid`, with `EVENT_ABI` set.
+pub fn mock_syscall(pid: u32, comm: &str, syscall_nr: u64, timestamp_ns: u64) -> SyscallEvent {
+    let mut event = SyscallEvent::new();
+    event.pid = pid;
+    event.tid = pid;
+    event.syscall_nr = syscall_nr;
+    event.timestamp_ns = timestamp_ns;
+    event.comm = comm_bytes(comm);
+    event
+}
+
+/// The exit of `entry`, `duration_ns` later, returning `ret`.
+pub fn mock_exit(entry: &SyscallEvent, ret: i64, duration_ns: u64) -> SyscallExitEvent {
+    let mut event = SyscallExitEvent::new();
+    event.pid = entry.pid;
+    event.tid = entry.tid;
+    event.syscall_nr = entry.syscall_nr;
+    event.ret = ret;
+    event.enter_ns = entry.timestamp_ns;
+    event.timestamp_ns = entry.timestamp_ns + duration_ns;
+    event
+}
+
+/// The main thread of `pid` renamed itself (`prctl(PR_SET_NAME)`).
+pub fn mock_rename(pid: u32, comm: &str) -> CommUpdateEvent {
+    let mut event = CommUpdateEvent::new();
+    event.pid = pid;
+    event.tid = pid;
+    event.comm = comm_bytes(comm);
+    event
+}
+
+/// `comm` as the kernel stores it: at most 15 bytes and a NUL.
+fn comm_bytes(comm: &str) -> [u8; COMM_LEN] {
+    let mut bytes = [0u8; COMM_LEN];
+    let comm = crate::comm_match::truncate(comm);
+    bytes[..comm.len()].copy_from_slice(comm.as_bytes());
+    bytes
+}
+
+/// A record decoded into the event it holds.
+#[derive(Debug, Clone, Copy)]
+pub enum Decoded {
+    Syscall { cpu: u16, event: SyscallEvent },
+    Exit { cpu: u16, event: SyscallExitEvent },
+    CommUpdate(CommUpdateEvent),
+    Lost { cpu: u16, count: u64 },
+}
+
+/// What a pass over a source saw, for the summary after it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// Syscall entries decoded
+    pub events: u64,
+    /// Lines written
+    pub printed: u64,
+    /// Events the kernel reported lost
+    pub lost: u64,
+    /// Records that failed to decode and were skipped
+    pub rejected: u64,
+    /// Records of kinds this build does not know (a newer recorder)
+    pub unknown: u64,
+}
+
+/// Decodes the records of an [`EventSource`], one [`EventParser`] per kind.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    syscalls: EventParser<SyscallEvent>,
+    exits: EventParser<SyscallExitEvent>,
+    comm_updates: EventParser<CommUpdateEvent>,
+    lost: u64,
+    unknown: u64,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The next decodable event of `source`, or None at its end. Bad
+    /// records and unknown kinds are skipped and counted; a version
+    /// mismatch is an error.
+    pub fn next_event(&mut self, source: &mut dyn EventSource) -> Result<Option<Decoded>> {
+        while let Some(record) = source.next_record()? {
+            let (kind, cpu, data) = match record {
+                Record::Lost { cpu, count } => {
+                    self.lost += count;
+                    return Ok(Some(Decoded::Lost { cpu, count }));
+                }
+                Record::Event { kind, cpu, data } => (kind, cpu, data),
+            };
+            let decoded = match kind {
+                KIND_SYSCALL => self
+                    .syscalls
+                    .parse(&data)?
+                    .map(|event| Decoded::Syscall { cpu, event }),
+                KIND_SYSCALL_EXIT => self
+                    .exits
+                    .parse(&data)?
+                    .map(|event| Decoded::Exit { cpu, event }),
+                KIND_COMM_UPDATE => self.comm_updates.parse(&data)?.map(Decoded::CommUpdate),
+                _ => {
+                    self.unknown += 1;
+                    None
+                }
+            };
+            if decoded.is_some() {
+                return Ok(decoded);
+            }
+        }
+        Ok(None)
+    }
+
+    /// The counts so far; `printed` is the caller's.
+    pub fn counts(&self) -> Counts {
+        Counts {
+            events: self.syscalls.parsed(),
+            printed: 0,
+            lost: self.lost,
+            rejected: self.syscalls.rejected()
+                + self.exits.rejected()
+                + self.comm_updates.rejected(),
+            unknown: self.unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(source: &mut dyn EventSource) -> (Vec<Decoded>, Counts) {
+        let mut decoder = Decoder::new();
+        let mut out = Vec::new();
+        while let Some(d) = decoder.next_event(source).unwrap() {
+            out.push(d);
+        }
+        (out, decoder.counts())
+    }
+
+    #[test]
+    fn test_mock_source_in_order() {
+        let entry = mock_syscall(100, "bash", 257, 1_000);
+        let mut source = MockSource::new()
+            .syscall(entry)
+            .comm_update(mock_rename(100, "worker"))
+            .exit(mock_exit(&entry, 3, 500))
+            .lost(2, 7);
+        assert_eq!(source.len(), 4);
+
+        let (decoded, counts) = drain(&mut source);
+        assert!(matches!(decoded[0], Decoded::Syscall { event, .. }
+            if event.pid == 100 && &event.comm[..5] == b"bash\0"));
+        assert!(matches!(decoded[1], Decoded::CommUpdate(u) if &u.comm[..7] == b"worker\0"));
+        assert!(matches!(decoded[2], Decoded::Exit { event, .. }
+            if event.ret == 3 && event.enter_ns == 1_000 && event.timestamp_ns == 1_500));
+        assert!(matches!(decoded[3], Decoded::Lost { cpu: 2, count: 7 }));
+        assert_eq!(counts.events, 1);
+        assert_eq!(counts.lost, 7);
+        assert!(source.is_empty());
+    }
+
+    #[test]
+    fn test_decoder_skips_bad_and_unknown_records() {
+        let mut source = MockSource::new()
+            .record(Record::Event {
+                kind: KIND_SYSCALL,
+                cpu: 0,
+                data: vec![0; 8],
+            })
+            .record(Record::Event {
+                kind: 99,
+                cpu: 0,
+                data: vec![1, 2, 3],
+            })
+            .syscall(mock_syscall(7, "sshd", 0, 1));
+        let (decoded, counts) = drain(&mut source);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(counts.rejected, 1);
+        assert_eq!(counts.unknown, 1);
+    }
+
+    #[test]
+    fn test_decoder_fails_on_other_version() {
+        let mut event = mock_syscall(1, "init", 0, 1);
+        event.abi = (ebpf_tool_common::EVENT_MAGIC as u32) << 16 | 0xffff;
+        let mut source = MockSource::new().syscall(event);
+        let err = Decoder::new().next_event(&mut source).unwrap_err();
+        assert!(err.to_string().contains("event version"));
+    }
+
+    #[test]
+    fn test_capture_reader_is_a_source() {
+        let header = capture::Header::current(["ebpf-tool".to_string()]);
+        let mut writer = capture::Writer::new(Vec::new(), &header).unwrap();
+        let event = mock_syscall(42, "curl", 1, 5);
+        writer
+            .event(KIND_SYSCALL, 3, capture::struct_bytes(&event))
+            .unwrap();
+        writer.lost(1, 2).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut reader = capture::Reader::new(bytes.as_slice()).unwrap();
+        let (decoded, counts) = drain(&mut reader);
+        assert!(matches!(decoded[0], Decoded::Syscall { cpu: 3, event } if event.pid == 42));
+        assert_eq!(counts.lost, 2);
+    }
+
+    #[test]
+    fn test_channel_source_ends_with_senders() {
+        let (tx, mut source) = channel();
+        tx.send(Record::Lost { cpu: 0, count: 1 }).unwrap();
+        drop(tx);
+        assert!(source.next_record().unwrap().is_some());
+        assert!(source.next_record().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_mock_comm_is_cut_like_the_kernel() {
+        let event = mock_syscall(1, "a-very-long-worker-name", 0, 0);
+        assert_eq!(&event.comm, b"a-very-long-wor\0");
+    }
+}
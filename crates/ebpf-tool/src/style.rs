@@ -0,0 +1,385 @@
+//! Human output of `trace` and `replay`: colors and aligned columns.
+//!
+//! The default line is compact, like strace:
+//!
+//! ```text
+//! [12:34:56.789] bash(1234) openat = -1 ENOENT (No such file or directory)
+//! ```
+//!
+//! `--fields` switches to aligned columns with only the fields asked for,
+//! in the order given, under a header line:
+//!
+//! ```text
+//! $ ebpf-tool trace --fields time,comm,syscall,ret --returns
+//! TIME            COMM            SYSCALL        RET
+//! 12:34:56.789    bash            openat         -1 ENOENT (No such file or directory)
+//! ```
+//!
+//! Every column but the last has a fixed width, so the columns line up
+//! without buffering. On a terminal the last column is cut to the
+//! terminal's width; in a pipe or a CI log lines are never cut.
+//!
+//! `--color auto` (the default) colors only terminals, and honors
+//! `NO_COLOR` and `TERM=dumb`. Cells are padded before they are colored,
+//! so escape codes never shift a column.
+
+use std::fmt;
+use std::io::IsTerminal;
+
+/// `--color`: when to use ANSI colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorMode {
+    /// Only on a terminal, unless NO_COLOR is set or TERM is dumb
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Whether to color, given what the output and the environment are.
+    pub fn resolve(self, is_terminal: bool, no_color: bool, dumb_term: bool) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => is_terminal && !no_color && !dumb_term,
+        }
+    }
+
+    /// [`ColorMode::resolve`] for stdout and the current environment.
+    pub fn for_stdout(self) -> bool {
+        self.resolve(
+            std::io::stdout().is_terminal(),
+            std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            std::env::var("TERM").is_ok_and(|t| t == "dumb"),
+        )
+    }
+}
+
+/// A column of `--fields`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Time,
+    Pid,
+    Comm,
+    Syscall,
+    Args,
+    Ret,
+}
+
+impl Field {
+    pub const ALL: [Field; 6] = [
+        Field::Time,
+        Field::Pid,
+        Field::Comm,
+        Field::Syscall,
+        Field::Args,
+        Field::Ret,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Time => "time",
+            Field::Pid => "pid",
+            Field::Comm => "comm",
+            Field::Syscall => "syscall",
+            Field::Args => "args",
+            Field::Ret => "ret",
+        }
+    }
+
+    /// Width of the column when it is not the last one.
+    fn width(self) -> usize {
+        match self {
+            // [12:34:56.789] and [5234.123456] without the brackets
+            Field::Time => 15,
+            // "4194304/4194305": pid_max is at most 2^22
+            Field::Pid => 15,
+            Field::Comm => 15,
+            Field::Syscall => 14,
+            Field::Args => 40,
+            Field::Ret => 12,
+        }
+    }
+
+    /// Numbers line up on the right.
+    fn right_aligned(self) -> bool {
+        self == Field::Pid
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The `--fields` columns, in the order given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldList(pub Vec<Field>);
+
+/// Parse `--fields`, e.g. `time,pid,comm,syscall`: clap `value_parser`.
+pub fn parse_fields(arg: &str) -> Result<FieldList, String> {
+    let mut fields = Vec::new();
+    for name in arg.split(',').map(str::trim) {
+        let field = Field::ALL
+            .into_iter()
+            .find(|f| f.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = Field::ALL.iter().map(|f| f.name()).collect();
+                format!("unknown field '{}' (expected {})", name, names.join(", "))
+            })?;
+        if fields.contains(&field) {
+            return Err(format!("field '{}' given twice", name));
+        }
+        fields.push(field);
+    }
+    Ok(FieldList(fields))
+}
+
+/// The text of each field of one event, before layout. Cells a line does
+/// not have (no return value yet, no arguments) stay empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cells {
+    pub time: String,
+    /// "1234", or "1234/1240" for a thread other than the main one
+    pub pid: String,
+    pub comm: String,
+    pub syscall: String,
+    pub args: String,
+    pub ret: String,
+    /// The return value is an error: colored red
+    pub failed: bool,
+}
+
+impl Cells {
+    fn get(&self, field: Field) -> &str {
+        match field {
+            Field::Time => &self.time,
+            Field::Pid => &self.pid,
+            Field::Comm => &self.comm,
+            Field::Syscall => &self.syscall,
+            Field::Args => &self.args,
+            Field::Ret => &self.ret,
+        }
+    }
+}
+
+/// ANSI SGR codes for the parts of a line.
+const DIM: &str = "2";
+const BOLD: &str = "1";
+const RED: &str = "31";
+const GREEN: &str = "32";
+const CYAN: &str = "36";
+
+/// `text` in SGR `code` when `color` is on.
+pub fn paint(text: &str, code: &str, color: bool) -> String {
+    if color && !text.is_empty() {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// A process name in the compact line.
+pub fn paint_comm(comm: &str, color: bool) -> String {
+    paint(comm, CYAN, color)
+}
+
+/// A syscall or probe name in the compact line.
+pub fn paint_syscall(name: &str, color: bool) -> String {
+    paint(name, BOLD, color)
+}
+
+/// A return value, red when it is an error.
+pub fn paint_ret(ret: &str, failed: bool, color: bool) -> String {
+    paint(ret, if failed { RED } else { GREEN }, color)
+}
+
+/// Lays out [`Cells`] in the `--fields` columns.
+#[derive(Debug, Clone)]
+pub struct Columns {
+    fields: Vec<Field>,
+    color: bool,
+    /// Terminal width; None in a pipe (never cut)
+    width: Option<usize>,
+}
+
+impl Columns {
+    pub fn new(fields: Vec<Field>, color: bool, width: Option<usize>) -> Self {
+        Self {
+            fields,
+            color,
+            width,
+        }
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// The header line: the field names in capitals.
+    pub fn header(&self) -> String {
+        let cells: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| f.name().to_uppercase())
+            .collect();
+        let line = self.layout(&cells);
+        paint(&line, BOLD, self.color)
+    }
+
+    /// One event.
+    pub fn row(&self, cells: &Cells) -> String {
+        let texts: Vec<String> = self
+            .fields
+            .iter()
+            .map(|&f| cells.get(f).to_string())
+            .collect();
+        let padded = self.pad(&texts);
+        let painted: Vec<String> = self
+            .fields
+            .iter()
+            .zip(padded)
+            .map(|(&field, text)| match field {
+                Field::Time => paint(&text, DIM, self.color),
+                Field::Comm => paint(&text, CYAN, self.color),
+                Field::Syscall => paint(&text, BOLD, self.color),
+                Field::Ret if !cells.ret.is_empty() => paint_ret(&text, cells.failed, self.color),
+                _ => text,
+            })
+            .collect();
+        painted.join(" ").trim_end().to_string()
+    }
+
+    fn layout(&self, texts: &[String]) -> String {
+        self.pad(texts).join(" ").trim_end().to_string()
+    }
+
+    /// Pad or cut every cell to its column; cut the last one to what is
+    /// left of the terminal.
+    fn pad(&self, texts: &[String]) -> Vec<String> {
+        let mut used = 0;
+        let last = self.fields.len().saturating_sub(1);
+        let mut out = Vec::with_capacity(texts.len());
+        for (i, (&field, text)) in self.fields.iter().zip(texts).enumerate() {
+            let cell = if i < last {
+                let w = field.width();
+                let text = cut(text, w);
+                if field.right_aligned() {
+                    format!("{:>w$}", text)
+                } else {
+                    format!("{:<w$}", text)
+                }
+            } else {
+                match self.width {
+                    Some(width) => cut(text, width.saturating_sub(used)),
+                    None => text.clone(),
+                }
+            };
+            used += cell.chars().count() + 1;
+            out.push(cell);
+        }
+        out
+    }
+}
+
+/// `text` in at most `width` characters, ending in `~` when cut.
+fn cut(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push('~');
+    cut
+}
+
+/// The terminal's width in columns when stdout is one.
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    ratatui::crossterm::terminal::size()
+        .ok()
+        .map(|(cols, _)| cols as usize)
+        .filter(|&cols| cols > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells() -> Cells {
+        Cells {
+            time: "12:34:56.789".to_string(),
+            pid: "1234".to_string(),
+            comm: "bash".to_string(),
+            syscall: "openat".to_string(),
+            args: "\"/etc/passwd\", O_RDONLY".to_string(),
+            ret: "-1 ENOENT (No such file or directory)".to_string(),
+            failed: true,
+        }
+    }
+
+    #[test]
+    fn test_color_mode_resolve() {
+        assert!(ColorMode::Always.resolve(false, true, true));
+        assert!(!ColorMode::Never.resolve(true, false, false));
+        assert!(ColorMode::Auto.resolve(true, false, false));
+        assert!(!ColorMode::Auto.resolve(false, false, false));
+        assert!(!ColorMode::Auto.resolve(true, true, false));
+        assert!(!ColorMode::Auto.resolve(true, false, true));
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            parse_fields("comm, syscall,ret").unwrap().0,
+            [Field::Comm, Field::Syscall, Field::Ret]
+        );
+        assert!(parse_fields("comm,cpu")
+            .unwrap_err()
+            .contains("unknown field 'cpu' (expected time, pid, comm, syscall, args, ret)"));
+        assert!(parse_fields("pid,pid").unwrap_err().contains("given twice"));
+        assert!(parse_fields("").is_err());
+    }
+
+    #[test]
+    fn test_columns_align() {
+        let columns = Columns::new(vec![Field::Pid, Field::Comm, Field::Syscall], false, None);
+        assert_eq!(columns.header(), "            PID COMM            SYSCALL");
+        assert_eq!(
+            columns.row(&cells()),
+            "           1234 bash            openat"
+        );
+        // A long comm is cut, not shifted
+        let long = Cells {
+            syscall: "read".to_string(),
+            comm: "a-very-long-comm-name".to_string(),
+            ..cells()
+        };
+        assert_eq!(columns.row(&long), "           1234 a-very-long-co~ read");
+    }
+
+    #[test]
+    fn test_last_column_fits_terminal() {
+        let columns = Columns::new(vec![Field::Comm, Field::Ret], false, Some(30));
+        assert_eq!(columns.row(&cells()), "bash            -1 ENOENT (No~");
+        let piped = Columns::new(vec![Field::Comm, Field::Ret], false, None);
+        assert!(piped.row(&cells()).ends_with("(No such file or directory)"));
+    }
+
+    #[test]
+    fn test_colors_do_not_shift_columns() {
+        let columns = Columns::new(vec![Field::Comm, Field::Ret], true, None);
+        assert_eq!(
+            columns.row(&cells()),
+            "\x1b[36mbash           \x1b[0m \x1b[31m-1 ENOENT (No such file or directory)\x1b[0m"
+        );
+        assert_eq!(paint("", RED, true), "");
+        assert_eq!(paint_ret("3", false, false), "3");
+    }
+}
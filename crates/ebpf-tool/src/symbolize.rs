@@ -0,0 +1,614 @@
+//! Stack symbolization: instruction addresses to `function+offset` frames.
+//!
+//! `bpf_get_stackid()` stores raw instruction pointers in a `StackTraceMap`.
+//! This module turns them into frames a person can read:
+//!
+//! ```text
+//! 0xffffffff81c0a1f4  ->  do_sys_openat2+0x74 [kernel]
+//! 0x000055d4a2c0b1e2  ->  main+0x22 [/usr/bin/stress]
+//! ```
+//!
+//! - Kernel addresses are looked up in `/proc/kallsyms` (module symbols
+//!   included). It shows all-zero addresses unless the reader has
+//!   `CAP_SYSLOG` or `kernel.kptr_restrict` is 0.
+//! - User addresses are first mapped to a file with `/proc/<pid>/maps`, then
+//!   to a virtual address in that file through its `PT_LOAD` segments, and
+//!   looked up in its `.symtab` (or `.dynsym` when stripped). Files are read
+//!   through `/proc/<pid>/root`, so processes in containers resolve against
+//!   their own binaries.
+//!
+//! A [`Symbolizer`] caches everything it reads: kallsyms once, each ELF file
+//! once per (device, inode), each process's maps once, and each resolved
+//! frame. Call [`Symbolizer::forget`] when a process exits, since its PID
+//! can be reused. Names are returned as stored in the symbol table, so C++
+//! and Rust names are mangled (pipe through `c++filt` or `rustfilt`).
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const KALLSYMS_PATH: &str = "/proc/kallsyms";
+
+// ELF constants, from include/uapi/linux/elf.h
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const PT_LOAD: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+const STT_FUNC: u8 = 2;
+const SHN_UNDEF: u16 = 0;
+const SYM_SIZE: usize = 24;
+
+/// One resolved stack frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub addr: u64,
+    /// Function containing `addr`, if any symbol covers it
+    pub symbol: Option<String>,
+    /// Distance from the start of `symbol`
+    pub offset: u64,
+    /// "kernel", a module name, or the mapped file ("[vdso]" and the like too)
+    pub module: Option<String>,
+}
+
+impl Frame {
+    fn unresolved(addr: u64, module: Option<String>) -> Self {
+        Frame {
+            addr,
+            symbol: None,
+            offset: 0,
+            module,
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.symbol {
+            Some(name) => write!(f, "{}+{:#x}", name, self.offset)?,
+            None => write!(f, "{:#x}", self.addr)?,
+        }
+        if let Some(module) = &self.module {
+            write!(f, " [{}]", module)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Symbol {
+    addr: u64,
+    /// 0 when unknown (kallsyms): the symbol then ends at the next one
+    size: u64,
+    name: String,
+    module: Option<String>,
+}
+
+/// Symbols sorted by address, for "which function contains this address".
+#[derive(Debug, Default, Clone)]
+struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    fn new(mut symbols: Vec<Symbol>) -> Self {
+        symbols.sort_by_key(|s| s.addr);
+        // Aliases share an address; keep the first (symtab order puts the
+        // real name before weak aliases often enough for a learning tool)
+        symbols.dedup_by_key(|s| s.addr);
+        SymbolTable { symbols }
+    }
+
+    fn lookup(&self, addr: u64) -> Option<&Symbol> {
+        let index = self.symbols.partition_point(|s| s.addr <= addr);
+        let symbol = self.symbols.get(index.checked_sub(1)?)?;
+        // addr >= symbol.addr here; a subtraction cannot overflow on a huge size
+        if symbol.size > 0 && addr - symbol.addr >= symbol.size {
+            return None;
+        }
+        Some(symbol)
+    }
+
+    fn frame(&self, addr: u64, lookup_addr: u64, module: Option<String>) -> Frame {
+        match self.lookup(lookup_addr) {
+            Some(symbol) => Frame {
+                addr,
+                symbol: Some(symbol.name.clone()),
+                offset: lookup_addr - symbol.addr,
+                module: symbol.module.clone().or(module),
+            },
+            None => Frame::unresolved(addr, module),
+        }
+    }
+}
+
+/// Kernel text symbols from `/proc/kallsyms`.
+#[derive(Debug, Default, Clone)]
+pub struct KernelSymbols {
+    table: SymbolTable,
+}
+
+impl KernelSymbols {
+    pub fn load() -> Result<Self> {
+        let text = std::fs::read_to_string(KALLSYMS_PATH)
+            .with_context(|| format!("failed to read {}", KALLSYMS_PATH))?;
+        Self::parse(&text)
+    }
+
+    /// Parse kallsyms lines: `ffffffff81c0a180 T do_sys_openat2` or
+    /// `ffffffffc0a01000 t ext4_fill_super\t[ext4]`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut symbols = Vec::new();
+        let mut any_address = false;
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(addr), Some(kind), Some(name)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            // Text symbols only: t/T, and weak w/W, which are functions too
+            if !matches!(kind, "t" | "T" | "w" | "W") {
+                continue;
+            }
+            let Ok(addr) = u64::from_str_radix(addr, 16) else {
+                continue;
+            };
+            any_address |= addr != 0;
+            let module = fields
+                .next()
+                .map(|m| m.trim_matches(|c| c == '[' || c == ']').to_string())
+                .unwrap_or_else(|| "kernel".to_string());
+            symbols.push(Symbol {
+                addr,
+                size: 0,
+                name: name.to_string(),
+                module: Some(module),
+            });
+        }
+        if !symbols.is_empty() && !any_address {
+            bail!(
+                "{} shows no addresses: run as root or set kernel.kptr_restrict=0",
+                KALLSYMS_PATH
+            );
+        }
+        Ok(KernelSymbols {
+            table: SymbolTable::new(symbols),
+        })
+    }
+
+    pub fn resolve(&self, addr: u64) -> Frame {
+        self.table.frame(addr, addr, None)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LoadSegment {
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+}
+
+/// Function symbols and load segments of one ELF file.
+#[derive(Debug, Default, Clone)]
+pub struct ElfSymbols {
+    table: SymbolTable,
+    segments: Vec<LoadSegment>,
+}
+
+impl ElfSymbols {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data =
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&data).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Read the `PT_LOAD` segments and the `STT_FUNC` symbols of a 64-bit
+    /// little-endian ELF file (x86_64 and aarch64).
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if !data.starts_with(b"\x7fELF") {
+            bail!("not an ELF file");
+        }
+        if data.get(4) != Some(&ELFCLASS64) || data.get(5) != Some(&ELFDATA2LSB) {
+            bail!("only 64-bit little-endian ELF files are supported");
+        }
+        let phoff = read_u64(data, 32)? as usize;
+        let shoff = read_u64(data, 40)? as usize;
+        let phentsize = read_u16(data, 54)? as usize;
+        let phnum = read_u16(data, 56)? as usize;
+        let shentsize = read_u16(data, 58)? as usize;
+        let shnum = read_u16(data, 60)? as usize;
+
+        // Every offset below comes from the file: a corrupt or hostile one must
+        // fail as out of bounds, not overflow
+        let mut segments = Vec::new();
+        for i in 0..phnum {
+            let ph = i
+                .checked_mul(phentsize)
+                .and_then(|at| at.checked_add(phoff))
+                .filter(|&at| at < data.len())
+                .context("program header out of bounds")?;
+            if read_u32(data, ph)? == PT_LOAD {
+                segments.push(LoadSegment {
+                    offset: read_u64(data, ph + 8)?,
+                    vaddr: read_u64(data, ph + 16)?,
+                    filesz: read_u64(data, ph + 32)?,
+                });
+            }
+        }
+
+        let section = |i: usize| {
+            i.checked_mul(shentsize)
+                .and_then(|at| at.checked_add(shoff))
+                .filter(|&at| at < data.len())
+                .context("section header out of bounds")
+        };
+        let mut symtab = Vec::new();
+        let mut dynsym = Vec::new();
+        for i in 0..shnum {
+            let sh = section(i)?;
+            let kind = read_u32(data, sh + 4)?;
+            if kind != SHT_SYMTAB && kind != SHT_DYNSYM {
+                continue;
+            }
+            let offset = read_u64(data, sh + 24)? as usize;
+            let size = read_u64(data, sh + 32)? as usize;
+            let strtab = section(read_u32(data, sh + 40)? as usize)?;
+            let strtab_offset = read_u64(data, strtab + 24)? as usize;
+            let strtab_size = read_u64(data, strtab + 32)? as usize;
+            let strings = strtab_offset
+                .checked_add(strtab_size)
+                .and_then(|end| data.get(strtab_offset..end))
+                .context("string table out of bounds")?;
+            let end = offset
+                .checked_add(size)
+                .filter(|&end| end <= data.len())
+                .context("symbol table out of bounds")?;
+
+            let out = if kind == SHT_SYMTAB {
+                &mut symtab
+            } else {
+                &mut dynsym
+            };
+            for sym in (offset..end).step_by(SYM_SIZE) {
+                let info = *data.get(sym + 4).context("symbol out of bounds")?;
+                let value = read_u64(data, sym + 8)?;
+                if info & 0xf != STT_FUNC || read_u16(data, sym + 6)? == SHN_UNDEF || value == 0 {
+                    continue;
+                }
+                let name = read_str(strings, read_u32(data, sym)? as usize);
+                if name.is_empty() {
+                    continue;
+                }
+                out.push(Symbol {
+                    addr: value,
+                    size: read_u64(data, sym + 16)?,
+                    name,
+                    module: None,
+                });
+            }
+        }
+
+        // .symtab is a superset of .dynsym; stripped files only have .dynsym
+        let symbols = if symtab.is_empty() { dynsym } else { symtab };
+        Ok(ElfSymbols {
+            table: SymbolTable::new(symbols),
+            segments,
+        })
+    }
+
+    /// The address `file_offset` is loaded at, as the symbol table sees it.
+    fn vaddr(&self, file_offset: u64) -> Option<u64> {
+        self.segments
+            .iter()
+            .find(|s| file_offset >= s.offset && file_offset - s.offset < s.filesz)
+            .and_then(|s| (file_offset - s.offset).checked_add(s.vaddr))
+    }
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16> {
+    let bytes = at
+        .checked_add(2)
+        .and_then(|end| data.get(at..end))
+        .context("ELF header out of bounds")?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32> {
+    let bytes = at
+        .checked_add(4)
+        .and_then(|end| data.get(at..end))
+        .context("ELF header out of bounds")?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+fn read_u64(data: &[u8], at: usize) -> Result<u64> {
+    let bytes = at
+        .checked_add(8)
+        .and_then(|end| data.get(at..end))
+        .context("ELF header out of bounds")?;
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+fn read_str(strings: &[u8], at: usize) -> String {
+    let tail = strings.get(at..).unwrap_or_default();
+    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+    String::from_utf8_lossy(&tail[..end]).into_owned()
+}
+
+/// One executable mapping from `/proc/<pid>/maps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub start: u64,
+    pub end: u64,
+    /// File offset of `start`
+    pub offset: u64,
+    /// "08:01"-style device and inode: together they identify the file
+    /// across mount namespaces, where paths do not
+    pub dev: String,
+    pub inode: u64,
+    /// File path, "[vdso]"-style name, or empty for anonymous memory (JIT)
+    pub path: String,
+}
+
+/// Parse the executable mappings of a `/proc/<pid>/maps` file.
+pub fn parse_maps(text: &str) -> Vec<Mapping> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(6, ' ');
+            let (range, perms, offset, dev, inode) = (
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+            );
+            if !perms.contains('x') {
+                return None;
+            }
+            let (start, end) = range.split_once('-')?;
+            let path = fields.next().unwrap_or("").trim();
+            Some(Mapping {
+                start: u64::from_str_radix(start, 16).ok()?,
+                end: u64::from_str_radix(end, 16).ok()?,
+                offset: u64::from_str_radix(offset, 16).ok()?,
+                dev: dev.to_string(),
+                inode: inode.parse().ok()?,
+                path: path.trim_end_matches(" (deleted)").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Resolves kernel and user addresses, caching what it reads.
+#[derive(Debug, Default)]
+pub struct Symbolizer {
+    /// Loaded on first use; empty if kallsyms could not be read
+    kernel: Option<KernelSymbols>,
+    maps: HashMap<u32, Vec<Mapping>>,
+    /// Keyed by (dev, inode); None when the file could not be read or parsed
+    elfs: HashMap<(String, u64), Option<ElfSymbols>>,
+    /// Keyed by (pid, addr); pid 0 holds kernel frames
+    frames: HashMap<(u32, u64), Frame>,
+}
+
+impl Symbolizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn kernel_frame(&mut self, addr: u64) -> Frame {
+        if let Some(frame) = self.frames.get(&(0, addr)) {
+            return frame.clone();
+        }
+        let kernel = self.kernel.get_or_insert_with(|| {
+            KernelSymbols::load().unwrap_or_else(|e| {
+                log::warn!("kernel frames will not be resolved: {:#}", e);
+                KernelSymbols::default()
+            })
+        });
+        let frame = kernel.resolve(addr);
+        self.frames.insert((0, addr), frame.clone());
+        frame
+    }
+
+    pub fn user_frame(&mut self, pid: u32, addr: u64) -> Frame {
+        if let Some(frame) = self.frames.get(&(pid, addr)) {
+            return frame.clone();
+        }
+        let frame = self.resolve_user(pid, addr);
+        self.frames.insert((pid, addr), frame.clone());
+        frame
+    }
+
+    /// Resolve a whole stack, innermost frame first, as `StackTraceMap` stores it.
+    pub fn stack(&mut self, pid: Option<u32>, addrs: &[u64]) -> Vec<Frame> {
+        addrs
+            .iter()
+            .map(|&addr| match pid {
+                Some(pid) => self.user_frame(pid, addr),
+                None => self.kernel_frame(addr),
+            })
+            .collect()
+    }
+
+    /// Drop what is cached for `pid` (call it when the process exits).
+    pub fn forget(&mut self, pid: u32) {
+        self.maps.remove(&pid);
+        self.frames.retain(|&(p, _), _| p != pid);
+    }
+
+    fn resolve_user(&mut self, pid: u32, addr: u64) -> Frame {
+        let maps = self.maps.entry(pid).or_insert_with(|| {
+            std::fs::read_to_string(format!("/proc/{}/maps", pid))
+                .map(|text| parse_maps(&text))
+                .unwrap_or_default()
+        });
+        let Some(mapping) = maps.iter().find(|m| addr >= m.start && addr < m.end) else {
+            return Frame::unresolved(addr, None);
+        };
+        let module = (!mapping.path.is_empty()).then(|| mapping.path.clone());
+        if !mapping.path.starts_with('/') {
+            return Frame::unresolved(addr, module);
+        }
+
+        let file_offset = addr - mapping.start + mapping.offset;
+        let elf = self
+            .elfs
+            .entry((mapping.dev.clone(), mapping.inode))
+            .or_insert_with(|| {
+                // The process's root, so containers resolve their own files
+                let path = PathBuf::from(format!("/proc/{}/root{}", pid, mapping.path));
+                ElfSymbols::load(&path)
+                    .map_err(|e| log::debug!("{:#}", e))
+                    .ok()
+            });
+        match elf
+            .as_ref()
+            .and_then(|elf| Some((elf, elf.vaddr(file_offset)?)))
+        {
+            Some((elf, vaddr)) => elf.table.frame(addr, vaddr, module),
+            None => Frame::unresolved(addr, module),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kallsyms() {
+        let text = "\
+ffffffff81000000 T _stext
+ffffffff81c0a180 T do_sys_openat2
+ffffffff81c0a400 t do_sys_open
+ffffffff82a00000 D some_data
+ffffffffc0a01000 t ext4_fill_super\t[ext4]
+";
+        let kernel = KernelSymbols::parse(text).unwrap();
+        let frame = kernel.resolve(0xffffffff81c0a1f4);
+        assert_eq!(frame.symbol.as_deref(), Some("do_sys_openat2"));
+        assert_eq!(frame.offset, 0x74);
+        assert_eq!(frame.to_string(), "do_sys_openat2+0x74 [kernel]");
+        assert_eq!(
+            kernel.resolve(0xffffffffc0a01010).to_string(),
+            "ext4_fill_super+0x10 [ext4]"
+        );
+        assert_eq!(kernel.resolve(0x1000).to_string(), "0x1000");
+
+        let restricted = "0000000000000000 T _stext\n0000000000000000 T do_sys_openat2\n";
+        let err = KernelSymbols::parse(restricted).unwrap_err();
+        assert!(err.to_string().contains("kptr_restrict"));
+    }
+
+    #[test]
+    fn test_parse_maps() {
+        let text = "\
+55d4a2c00000-55d4a2c02000 r--p 00000000 08:01 1234                       /usr/bin/stress
+55d4a2c02000-55d4a2c0c000 r-xp 00002000 08:01 1234                       /usr/bin/stress
+7f1e2a228000-7f1e2a3bd000 r-xp 00028000 08:01 5678                       /usr/lib/libc.so.6 (deleted)
+7ffc3b7f2000-7ffc3b7f4000 r-xp 00000000 00:00 0                          [vdso]
+7f1e2a000000-7f1e2a001000 rwxp 00000000 00:00 0
+";
+        let maps = parse_maps(text);
+        assert_eq!(maps.len(), 4);
+        assert_eq!(maps[0].offset, 0x2000);
+        assert_eq!(maps[0].path, "/usr/bin/stress");
+        assert_eq!((maps[0].dev.as_str(), maps[0].inode), ("08:01", 1234));
+        assert_eq!(maps[1].path, "/usr/lib/libc.so.6");
+        assert_eq!(maps[2].path, "[vdso]");
+        assert_eq!(maps[3].path, "");
+    }
+
+    #[test]
+    fn test_symbol_sizes() {
+        let table = SymbolTable::new(vec![
+            Symbol {
+                addr: 0x2000,
+                size: 0x10,
+                name: "b".into(),
+                module: None,
+            },
+            Symbol {
+                addr: 0x1000,
+                size: 0x100,
+                name: "a".into(),
+                module: None,
+            },
+        ]);
+        assert_eq!(table.lookup(0x10ff).map(|s| s.name.as_str()), Some("a"));
+        // Past the end of "a" and before "b": a gap, not "a"
+        assert_eq!(table.lookup(0x1100), None);
+        assert_eq!(table.lookup(0x0fff), None);
+        assert_eq!(table.lookup(0x2008).map(|s| s.name.as_str()), Some("b"));
+
+        assert!(ElfSymbols::parse(b"not an elf").is_err());
+    }
+
+    /// An ELF header with two section headers: .symtab at 0 and a string
+    /// table at 1, each with the given (sh_offset, sh_size).
+    fn elf_with_sections(symtab: (u64, u64), strtab: (u64, u64)) -> Vec<u8> {
+        let mut data = vec![0u8; 64 + 2 * 64];
+        data[..6].copy_from_slice(&[0x7f, b'E', b'L', b'F', ELFCLASS64, ELFDATA2LSB]);
+        data[40..48].copy_from_slice(&64u64.to_le_bytes()); // e_shoff
+        data[58..60].copy_from_slice(&64u16.to_le_bytes()); // e_shentsize
+        data[60..62].copy_from_slice(&2u16.to_le_bytes()); // e_shnum
+        for (i, (offset, size)) in [symtab, strtab].into_iter().enumerate() {
+            let sh = 64 + i * 64;
+            data[sh + 24..sh + 32].copy_from_slice(&offset.to_le_bytes());
+            data[sh + 32..sh + 40].copy_from_slice(&size.to_le_bytes());
+        }
+        data[64 + 4..64 + 8].copy_from_slice(&SHT_SYMTAB.to_le_bytes());
+        data[64 + 40..64 + 44].copy_from_slice(&1u32.to_le_bytes()); // sh_link
+        data
+    }
+
+    #[test]
+    fn test_parse_huge_section() {
+        assert!(ElfSymbols::parse(&elf_with_sections((0, 0), (0, 0))).is_ok());
+
+        let err = ElfSymbols::parse(&elf_with_sections((u64::MAX, u64::MAX), (0, 0)));
+        assert!(format!("{:#}", err.unwrap_err()).contains("out of bounds"));
+        let err = ElfSymbols::parse(&elf_with_sections((0, 0), (u64::MAX, u64::MAX)));
+        assert!(format!("{:#}", err.unwrap_err()).contains("out of bounds"));
+        // In range for usize, past the end of the file
+        let err = ElfSymbols::parse(&elf_with_sections((64, 1 << 40), (0, 0)));
+        assert!(format!("{:#}", err.unwrap_err()).contains("out of bounds"));
+
+        // e_shoff near the top of the address space
+        let mut data = elf_with_sections((0, 0), (0, 0));
+        data[40..48].copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+        assert!(ElfSymbols::parse(&data).is_err());
+    }
+
+    #[inline(never)]
+    fn symbolize_marker() -> u64 {
+        symbolize_marker as *const () as usize as u64
+    }
+
+    #[test]
+    fn test_resolve_own_function() {
+        // The test binary is an unstripped PIE: this goes through maps,
+        // the load segments and .symtab, without root
+        let addr = symbolize_marker() + 1;
+        let mut symbolizer = Symbolizer::new();
+        let frame = symbolizer.user_frame(std::process::id(), addr);
+
+        assert!(
+            frame
+                .symbol
+                .as_deref()
+                .unwrap_or("")
+                .contains("symbolize_marker"),
+            "{frame}"
+        );
+        assert_eq!(frame.offset, 1);
+        let exe = std::fs::read_link("/proc/self/exe").unwrap();
+        assert_eq!(frame.module.as_deref(), exe.to_str());
+        // Cached: the same answer, even once the maps are gone
+        symbolizer.maps.clear();
+        assert_eq!(symbolizer.user_frame(std::process::id(), addr), frame);
+    }
+}
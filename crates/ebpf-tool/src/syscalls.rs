@@ -0,0 +1,217 @@
+//! Syscall names and numbers for output, filters and map dumps.
+//!
+//! The numbers are generated at build time from the kernel headers (see
+//! `crates/ebpf-tool-common/build.rs`). A kernel newer than those headers
+//! has syscalls the table does not know, and they show as `syscall_<nr>`.
+//! An override file fixes that without a rebuild:
+//!
+//! ```text
+//! $EBPF_TOOL_SYSCALLS, or /etc/ebpf-tool/syscalls.tbl if it exists
+//!
+//! # NUMBER NAME                 (the format of syscalls/<arch>.tbl)
+//! 467 open_tree_attr
+//! # NUMBER ABI NAME [ENTRY]     (the kernel's arch/x86/entry/syscalls/syscall_64.tbl)
+//! 468 common file_getattr sys_file_getattr
+//! ```
+//!
+//! Lines of the override file replace or add entries of the built-in table.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the override file.
+pub const OVERRIDE_ENV: &str = "EBPF_TOOL_SYSCALLS";
+
+/// Override file read when it exists and `EBPF_TOOL_SYSCALLS` is unset.
+pub const DEFAULT_OVERRIDE: &str = "/etc/ebpf-tool/syscalls.tbl";
+
+/// Syscall table of the target architecture.
+#[derive(Debug, Clone)]
+pub struct SyscallTable {
+    by_nr: BTreeMap<u64, String>,
+    /// Where the entries came from, for `check`
+    source: String,
+    overridden: usize,
+}
+
+impl SyscallTable {
+    /// The table generated at build time.
+    pub fn builtin() -> Self {
+        Self {
+            by_nr: ebpf_tool_common::SYSCALLS
+                .iter()
+                .map(|&(nr, name)| (nr, name.to_string()))
+                .collect(),
+            source: ebpf_tool_common::SYSCALL_TABLE_SOURCE.to_string(),
+            overridden: 0,
+        }
+    }
+
+    /// The built-in table with the override file applied, if there is one.
+    /// `EBPF_TOOL_SYSCALLS` naming a missing file is an error; a missing
+    /// default file is not.
+    pub fn load() -> Result<Self> {
+        match override_path() {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("cannot read syscall table {}", path.display()))?;
+                Self::builtin().with_overrides(&text, &path)
+            }
+            None => Ok(Self::builtin()),
+        }
+    }
+
+    /// Apply the lines of an override file read from `origin`.
+    pub fn with_overrides(mut self, text: &str, origin: &Path) -> Result<Self> {
+        let entries = parse_overrides(text)
+            .with_context(|| format!("invalid syscall table {}", origin.display()))?;
+        self.overridden = entries.len();
+        self.by_nr.extend(entries);
+        self.source = format!("{} + {}", self.source, origin.display());
+        Ok(self)
+    }
+
+    /// Name of syscall `nr`, if known.
+    pub fn name(&self, nr: u64) -> Option<&str> {
+        self.by_nr.get(&nr).map(String::as_str)
+    }
+
+    /// Name of syscall `nr`, or `syscall_<nr>` if unknown.
+    pub fn display(&self, nr: u64) -> String {
+        match self.name(nr) {
+            Some(name) => name.to_string(),
+            None => format!("syscall_{}", nr),
+        }
+    }
+
+    /// Number of syscall `name`. `syscall_<nr>`, as printed by
+    /// [`display`](Self::display), works too.
+    pub fn number(&self, name: &str) -> Option<u64> {
+        if let Some(nr) = name.strip_prefix("syscall_").and_then(|n| n.parse().ok()) {
+            return Some(nr);
+        }
+        self.by_nr
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(&nr, _)| nr)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_nr.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_nr.is_empty()
+    }
+
+    /// "/usr/include/.../unistd_64.h + syscalls/x86_64.tbl", plus the
+    /// override file if one was applied.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Number of entries the override file set.
+    pub fn overridden(&self) -> usize {
+        self.overridden
+    }
+}
+
+/// The override file to read, if any.
+pub fn override_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(OVERRIDE_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let default = Path::new(DEFAULT_OVERRIDE);
+    default.is_file().then(|| default.to_path_buf())
+}
+
+/// Parse an override file: `NUMBER NAME` or `NUMBER ABI NAME [ENTRY]` per
+/// line, `#` comments. Kernel table lines for the x32 ABI are skipped: they
+/// are not 64-bit syscall numbers.
+pub fn parse_overrides(text: &str) -> Result<Vec<(u64, String)>> {
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Ok(nr) = words[0].parse::<u64>() else {
+            bail!("line {}: {:?} is not a syscall number", i + 1, words[0]);
+        };
+        let name = match words.len() {
+            2 => words[1],
+            3 | 4 if words[1] == "x32" => continue,
+            3 | 4 => words[2],
+            _ => bail!(
+                "line {}: expected NUMBER NAME or NUMBER ABI NAME [ENTRY]",
+                i + 1
+            ),
+        };
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("line {}: {:?} is not a syscall name", i + 1, name);
+        }
+        entries.push((nr, name.to_string()));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: &[(u64, &str)]) -> SyscallTable {
+        SyscallTable {
+            by_nr: entries
+                .iter()
+                .map(|&(nr, name)| (nr, name.to_string()))
+                .collect(),
+            source: "test".to_string(),
+            overridden: 0,
+        }
+    }
+
+    #[test]
+    fn test_builtin_matches_common() {
+        let t = SyscallTable::builtin();
+        assert_eq!(t.len(), ebpf_tool_common::SYSCALLS.len());
+        if let Some(&(nr, name)) = ebpf_tool_common::SYSCALLS.first() {
+            assert_eq!(t.name(nr), Some(name));
+            assert_eq!(t.number(name), Some(nr));
+        }
+    }
+
+    #[test]
+    fn test_names_and_fallback() {
+        let t = table(&[(0, "read"), (257, "openat")]);
+        assert_eq!(t.display(257), "openat");
+        assert_eq!(t.display(999), "syscall_999");
+        assert_eq!(t.number("openat"), Some(257));
+        assert_eq!(t.number("syscall_999"), Some(999));
+        assert_eq!(t.number("nope"), None);
+    }
+
+    #[test]
+    fn test_overrides_replace_and_add() {
+        let text = "# newer kernel\n467 open_tree_attr\n0 common read_renamed sys_read\n\
+                    512 x32 rt_sigaction compat_sys_rt_sigaction\n";
+        let t = table(&[(0, "read"), (257, "openat")])
+            .with_overrides(text, Path::new("/etc/ebpf-tool/syscalls.tbl"))
+            .unwrap();
+        assert_eq!(t.display(467), "open_tree_attr");
+        assert_eq!(t.display(0), "read_renamed");
+        assert_eq!(t.name(512), None, "x32 lines are skipped");
+        assert_eq!(t.overridden(), 2);
+        assert_eq!(t.source(), "test + /etc/ebpf-tool/syscalls.tbl");
+    }
+
+    #[test]
+    fn test_bad_override_lines() {
+        let err = parse_overrides("0 read\nread 0\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+        assert!(parse_overrides("1").is_err());
+        assert!(parse_overrides("1 wr-ite").is_err());
+        assert!(parse_overrides("\n# only comments\n").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+//! `trace --category`: which syscalls go to which tail-call handler.
+//!
+//! The entry program jumps to a handler program per category through the
+//! `SYSCALL_HANDLERS` program array (see `crates/ebpf-tool-ebpf/src/tailcall.rs`).
+//! Userspace sets both maps up before attaching:
+//!
+//! ```text
+//! --category file,net ─► entries(number): ("openat" -> 257) ─► SYSCALL_CATEGORIES[257] = TAIL_FILE
+//!                      ─► for each category: load program_name() ─► SYSCALL_HANDLERS[slot()] = fd
+//!                      ─► FILTER_CONFIG[FILTER_KEY_TAIL] = 1
+//! ```
+//!
+//! Every category's syscalls are in `SYSCALL_CATEGORIES`, enabled or not:
+//! a disabled category is an empty slot, so the tail call fails and the
+//! event is dropped. Syscalls in no category (`futex`, `mmap`, `ioctl`, ...)
+//! are never traced with `--category`.
+//!
+//! The lists are by name, not number, so they hold on every architecture;
+//! a name the running architecture does not have (`open` on arm64) is
+//! skipped. `read`, `write` and `close` take any fd, sockets included:
+//! they count as file syscalls.
+
+use ebpf_tool_common::{TAIL_FILE, TAIL_NET, TAIL_PROC};
+
+/// A syscall category with its own handler program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum Category {
+    /// Files and file descriptors: open, read, write, stat, rename, ...
+    File,
+    /// Sockets: socket, connect, accept, sendto, recvmsg, ...
+    Net,
+    /// Processes: clone, execve, exit, kill, setns, unshare, ...
+    Proc,
+}
+
+const FILE_SYSCALLS: &[&str] = &[
+    "open",
+    "openat",
+    "openat2",
+    "creat",
+    "close",
+    "close_range",
+    "read",
+    "write",
+    "pread64",
+    "pwrite64",
+    "readv",
+    "writev",
+    "preadv",
+    "pwritev",
+    "preadv2",
+    "pwritev2",
+    "lseek",
+    "stat",
+    "fstat",
+    "lstat",
+    "newfstatat",
+    "statx",
+    "access",
+    "faccessat",
+    "faccessat2",
+    "readlink",
+    "readlinkat",
+    "getdents64",
+    "mkdir",
+    "mkdirat",
+    "rmdir",
+    "unlink",
+    "unlinkat",
+    "rename",
+    "renameat",
+    "renameat2",
+    "link",
+    "linkat",
+    "symlink",
+    "symlinkat",
+    "chmod",
+    "fchmod",
+    "fchmodat",
+    "chown",
+    "fchown",
+    "fchownat",
+    "lchown",
+    "truncate",
+    "ftruncate",
+    "fsync",
+    "fdatasync",
+    "dup",
+    "dup2",
+    "dup3",
+    "fcntl",
+    "flock",
+    "sendfile",
+    "copy_file_range",
+    "splice",
+];
+
+const NET_SYSCALLS: &[&str] = &[
+    "socket",
+    "socketpair",
+    "bind",
+    "listen",
+    "accept",
+    "accept4",
+    "connect",
+    "getsockname",
+    "getpeername",
+    "sendto",
+    "recvfrom",
+    "sendmsg",
+    "recvmsg",
+    "sendmmsg",
+    "recvmmsg",
+    "shutdown",
+    "setsockopt",
+    "getsockopt",
+];
+
+const PROC_SYSCALLS: &[&str] = &[
+    "fork",
+    "vfork",
+    "clone",
+    "clone3",
+    "execve",
+    "execveat",
+    "exit",
+    "exit_group",
+    "wait4",
+    "waitid",
+    "kill",
+    "tkill",
+    "tgkill",
+    "pidfd_open",
+    "pidfd_send_signal",
+    "setsid",
+    "setpgid",
+    "setuid",
+    "setgid",
+    "setreuid",
+    "setregid",
+    "setresuid",
+    "setresgid",
+    "prctl",
+    "unshare",
+    "setns",
+    "ptrace",
+];
+
+impl Category {
+    pub const ALL: [Category; 3] = [Category::File, Category::Net, Category::Proc];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::File => "file",
+            Category::Net => "net",
+            Category::Proc => "proc",
+        }
+    }
+
+    /// Index of the handler in `SYSCALL_HANDLERS`.
+    pub fn slot(self) -> u32 {
+        match self {
+            Category::File => TAIL_FILE,
+            Category::Net => TAIL_NET,
+            Category::Proc => TAIL_PROC,
+        }
+    }
+
+    /// The handler program in the eBPF object.
+    pub fn program_name(self) -> &'static str {
+        match self {
+            Category::File => "syscall_file_handler",
+            Category::Net => "syscall_net_handler",
+            Category::Proc => "syscall_proc_handler",
+        }
+    }
+
+    /// The syscalls of this category, by name.
+    pub fn syscalls(self) -> &'static [&'static str] {
+        match self {
+            Category::File => FILE_SYSCALLS,
+            Category::Net => NET_SYSCALLS,
+            Category::Proc => PROC_SYSCALLS,
+        }
+    }
+
+    /// The category of syscall `name`, if it has one.
+    pub fn of(name: &str) -> Option<Category> {
+        Category::ALL
+            .into_iter()
+            .find(|c| c.syscalls().contains(&name))
+    }
+}
+
+/// The `SYSCALL_CATEGORIES` entries, `(syscall number, slot)`, for every
+/// syscall of every category that `number` knows (`SyscallTable::number`).
+pub fn entries(number: impl Fn(&str) -> Option<u64>) -> Vec<(u64, u32)> {
+    let mut entries: Vec<(u64, u32)> = Category::ALL
+        .into_iter()
+        .flat_map(|c| {
+            c.syscalls()
+                .iter()
+                .filter_map(|name| number(name))
+                .map(move |nr| (nr, c.slot()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    entries.sort_unstable();
+    entries
+}
+
+/// The categories of `--category` without repeats, in slot order.
+pub fn enabled(categories: &[Category]) -> Vec<Category> {
+    Category::ALL
+        .into_iter()
+        .filter(|c| categories.contains(c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_category_of() {
+        assert_eq!(Category::of("openat"), Some(Category::File));
+        assert_eq!(Category::of("connect"), Some(Category::Net));
+        assert_eq!(Category::of("execve"), Some(Category::Proc));
+        assert_eq!(Category::of("futex"), None);
+    }
+
+    #[test]
+    fn test_categories_do_not_overlap() {
+        let mut seen = HashSet::new();
+        for c in Category::ALL {
+            for name in c.syscalls() {
+                assert!(seen.insert(*name), "{} is in two categories", name);
+            }
+        }
+        let slots: HashSet<_> = Category::ALL.iter().map(|c| c.slot()).collect();
+        assert_eq!(slots.len(), Category::ALL.len());
+        assert!(slots.iter().all(|&s| s < ebpf_tool_common::TAIL_SLOTS));
+    }
+
+    #[test]
+    fn test_entries_skip_unknown_names() {
+        // A table with only three syscalls, as if `open` and the rest did
+        // not exist on this architecture
+        let number = |name: &str| match name {
+            "openat" => Some(257),
+            "connect" => Some(42),
+            "execve" => Some(59),
+            _ => None,
+        };
+        assert_eq!(
+            entries(number),
+            [(42, TAIL_NET), (59, TAIL_PROC), (257, TAIL_FILE)]
+        );
+    }
+
+    #[test]
+    fn test_enabled() {
+        assert_eq!(
+            enabled(&[Category::Proc, Category::File, Category::Proc]),
+            [Category::File, Category::Proc]
+        );
+        assert!(enabled(&[]).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Clock calibration: how CLOCK_MONOTONIC, CLOCK_BOOTTIME and
+//! CLOCK_REALTIME relate while a run is in progress.
+//!
+//! Kernel timestamps come from two clocks, and neither is wall-clock time:
+//! `bpf_ktime_get_ns()` is MONOTONIC, `bpf_ktime_get_boot_ns()` is BOOTTIME,
+//! which also counts the time the machine was suspended. A [`Sample`] reads
+//! all three clocks at (nearly) the same instant:
+//!
+//! ```text
+//! REALTIME = MONOTONIC + realtime offset   (moves when NTP steps the clock)
+//! BOOTTIME = MONOTONIC + suspended time    (grows after every resume)
+//! ```
+//!
+//! One sample at startup is enough for a short trace. A long one takes a
+//! new sample every [`RECALIBRATE_INTERVAL_NS`], so a clock step or a
+//! suspend in the middle of the run only affects the events after it. Each
+//! timestamp is converted with the last sample taken before it.
+//!
+//! The samples are saved in the run report (`"timebase"` in `runs show`),
+//! so the wall-clock time of any timestamp of the run can be rebuilt later.
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::clock_ns;
+
+/// How often a long-running trace takes a new [`Sample`].
+pub const RECALIBRATE_INTERVAL_NS: u64 = 60 * 1_000_000_000;
+
+/// The three clocks, read together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sample {
+    /// CLOCK_MONOTONIC, midway between the reads before and after the others
+    pub monotonic_ns: u64,
+    /// CLOCK_BOOTTIME
+    pub boottime_ns: u64,
+    /// CLOCK_REALTIME, nanoseconds since the Unix epoch
+    pub realtime_ns: u64,
+    /// Time between the two monotonic reads: how far off the sample can be
+    pub width_ns: u64,
+}
+
+impl Sample {
+    /// Read the clocks now.
+    ///
+    /// Reads BOOTTIME and REALTIME between two monotonic reads and keeps
+    /// the tightest of a few tries, so a preemption between the reads does
+    /// not skew the sample.
+    pub fn take() -> Self {
+        let mut best = Sample {
+            width_ns: u64::MAX,
+            ..Sample::default()
+        };
+        for _ in 0..5 {
+            let before = clock_ns(libc::CLOCK_MONOTONIC);
+            let boottime_ns = clock_ns(libc::CLOCK_BOOTTIME);
+            let realtime_ns = clock_ns(libc::CLOCK_REALTIME);
+            let after = clock_ns(libc::CLOCK_MONOTONIC);
+            let width_ns = after - before;
+            if width_ns < best.width_ns {
+                best = Sample {
+                    monotonic_ns: before + width_ns / 2,
+                    boottime_ns,
+                    realtime_ns,
+                    width_ns,
+                };
+            }
+        }
+        best
+    }
+
+    /// CLOCK_REALTIME - CLOCK_MONOTONIC
+    pub fn realtime_offset_ns(&self) -> u64 {
+        self.realtime_ns.saturating_sub(self.monotonic_ns)
+    }
+
+    /// CLOCK_BOOTTIME - CLOCK_MONOTONIC: time spent suspended since boot
+    pub fn suspended_ns(&self) -> u64 {
+        self.boottime_ns.saturating_sub(self.monotonic_ns)
+    }
+}
+
+/// The samples of one run, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timebase {
+    samples: Vec<Sample>,
+}
+
+impl Timebase {
+    /// A timebase with one sample taken now.
+    pub fn calibrate() -> Self {
+        Self {
+            samples: vec![Sample::take()],
+        }
+    }
+
+    /// A timebase that knows only the realtime offset, e.g. from an older
+    /// capture header. BOOTTIME is taken to equal MONOTONIC.
+    pub fn fixed(realtime_offset_ns: u64) -> Self {
+        Self {
+            samples: vec![Sample {
+                realtime_ns: realtime_offset_ns,
+                ..Sample::default()
+            }],
+        }
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Add `sample`. Samples older than the last one are ignored, so the
+    /// list stays sorted.
+    pub fn push(&mut self, sample: Sample) {
+        if self
+            .samples
+            .last()
+            .is_none_or(|last| sample.monotonic_ns > last.monotonic_ns)
+        {
+            self.samples.push(sample);
+        }
+    }
+
+    /// Whether the last sample is older than [`RECALIBRATE_INTERVAL_NS`]
+    /// at CLOCK_MONOTONIC `now_ns`.
+    pub fn due(&self, now_ns: u64) -> bool {
+        self.samples
+            .last()
+            .is_none_or(|last| now_ns >= last.monotonic_ns + RECALIBRATE_INTERVAL_NS)
+    }
+
+    /// Take a new sample. Returns how far the wall clock moved relative to
+    /// CLOCK_MONOTONIC since the previous one (an NTP step or slew), in
+    /// nanoseconds; 0 for the first sample.
+    pub fn recalibrate(&mut self) -> i64 {
+        let previous = self.samples.last().map(Sample::realtime_offset_ns);
+        let sample = Sample::take();
+        self.push(sample);
+        previous.map_or(0, |p| sample.realtime_offset_ns() as i64 - p as i64)
+    }
+
+    /// The sample that applies to CLOCK_MONOTONIC `monotonic_ns`: the last
+    /// one taken before it, or the first for earlier timestamps.
+    fn at(&self, monotonic_ns: u64) -> Option<&Sample> {
+        let i = self
+            .samples
+            .partition_point(|s| s.monotonic_ns <= monotonic_ns);
+        self.samples.get(i.saturating_sub(1))
+    }
+
+    /// CLOCK_REALTIME - CLOCK_MONOTONIC at `monotonic_ns`, 0 without samples.
+    pub fn realtime_offset_ns(&self, monotonic_ns: u64) -> u64 {
+        self.at(monotonic_ns).map_or(0, Sample::realtime_offset_ns)
+    }
+
+    /// A `bpf_ktime_get_ns()` timestamp as Unix time in nanoseconds.
+    pub fn unix_ns(&self, monotonic_ns: u64) -> u64 {
+        monotonic_ns + self.realtime_offset_ns(monotonic_ns)
+    }
+
+    /// A `bpf_ktime_get_boot_ns()` timestamp on CLOCK_MONOTONIC, so both
+    /// kinds of timestamps can be sorted and formatted together.
+    pub fn boot_to_monotonic(&self, boottime_ns: u64) -> u64 {
+        let i = self
+            .samples
+            .partition_point(|s| s.boottime_ns <= boottime_ns);
+        let suspended = self
+            .samples
+            .get(i.saturating_sub(1))
+            .map_or(0, Sample::suspended_ns);
+        boottime_ns.saturating_sub(suspended)
+    }
+
+    /// A `bpf_ktime_get_boot_ns()` timestamp as Unix time in nanoseconds.
+    pub fn boot_to_unix_ns(&self, boottime_ns: u64) -> u64 {
+        self.unix_ns(self.boot_to_monotonic(boottime_ns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S: u64 = 1_000_000_000;
+    // 2026-10-14 12:00:00 UTC
+    const NOON: u64 = 1_791_979_200 * S;
+
+    fn sample(monotonic_s: u64, suspended_s: u64, realtime_ns: u64) -> Sample {
+        Sample {
+            monotonic_ns: monotonic_s * S,
+            boottime_ns: (monotonic_s + suspended_s) * S,
+            realtime_ns,
+            width_ns: 100,
+        }
+    }
+
+    fn timebase() -> Timebase {
+        let mut tb = Timebase::default();
+        // Trace starts 1000s after boot
+        tb.push(sample(1000, 0, NOON));
+        // 60s later NTP steps the clock forward by 2s
+        tb.push(sample(1060, 0, NOON + 62 * S));
+        // The machine sleeps for an hour; 20s of trace later, a new sample
+        tb.push(sample(1080, 3600, NOON + 3600 * S + 82 * S));
+        tb
+    }
+
+    #[test]
+    fn test_take_matches_clocks() {
+        let s = Sample::take();
+        assert!(s.width_ns < 50_000_000, "{s:?}");
+        assert!(s.boottime_ns + s.width_ns >= s.monotonic_ns);
+        let real = clock_ns(libc::CLOCK_REALTIME);
+        let estimate = clock_ns(libc::CLOCK_MONOTONIC) + s.realtime_offset_ns();
+        assert!(real.abs_diff(estimate) < 50_000_000, "{real} vs {estimate}");
+    }
+
+    #[test]
+    fn test_unix_ns_uses_last_sample_before() {
+        let tb = timebase();
+        assert_eq!(tb.unix_ns(1010 * S), NOON + 10 * S);
+        // Before the first sample: the first sample's offset
+        assert_eq!(tb.unix_ns(990 * S), NOON - 10 * S);
+        // After the step, the new offset
+        assert_eq!(tb.unix_ns(1070 * S), NOON + 72 * S);
+        // After the suspend, the wall clock includes the hour
+        assert_eq!(tb.unix_ns(1090 * S), NOON + 3600 * S + 92 * S);
+        assert_eq!(Timebase::default().unix_ns(5), 5);
+    }
+
+    #[test]
+    fn test_boot_to_monotonic() {
+        let tb = timebase();
+        assert_eq!(tb.boot_to_monotonic(1010 * S), 1010 * S);
+        assert_eq!(tb.boot_to_monotonic((3600 + 1090) * S), 1090 * S);
+        assert_eq!(
+            tb.boot_to_unix_ns((3600 + 1090) * S),
+            NOON + 3600 * S + 92 * S
+        );
+        assert_eq!(Timebase::fixed(NOON).boot_to_unix_ns(10 * S), NOON + 10 * S);
+    }
+
+    #[test]
+    fn test_push_keeps_order_and_due() {
+        let mut tb = timebase();
+        tb.push(sample(1070, 0, NOON));
+        assert_eq!(tb.samples().len(), 3);
+        assert!(!tb.due(1080 * S + RECALIBRATE_INTERVAL_NS - 1));
+        assert!(tb.due(1080 * S + RECALIBRATE_INTERVAL_NS));
+        assert!(Timebase::default().due(0));
+    }
+
+    #[test]
+    fn test_recalibrate() {
+        let mut tb = Timebase::default();
+        assert_eq!(tb.recalibrate(), 0);
+        let drift = tb.recalibrate();
+        assert_eq!(tb.samples().len(), 2);
+        assert!(drift.abs() < 50_000_000, "{drift}");
+    }
+
+    #[test]
+    fn test_serializes_as_list() {
+        let tb = Timebase::fixed(NOON);
+        let json = serde_json::to_string(&tb).unwrap();
+        assert!(json.starts_with("[{\"monotonic_ns\":0,"), "{json}");
+        assert_eq!(serde_json::from_str::<Timebase>(&json).unwrap(), tb);
+    }
+}
@@ -0,0 +1,436 @@
+//! Live dashboard for `ebpf-tool trace --tui`.
+//!
+//! A busy system makes thousands of syscalls per second, far too many to
+//! read as scrolling lines. The dashboard keeps counting instead and redraws
+//! the totals a few times per second:
+//!
+//! ```text
+//! ebpf-tool trace   events: 184203  lost: 0  rate: 9120/s  filter: -
+//! ┌ events/s ──────────────────────────────────────────────────────┐
+//! │ ▂▃▅▇█▇▅▃▂▂▃▅▆▇█                                                │
+//! └────────────────────────────────────────────────────────────────┘
+//! ┌ syscalls ──────────────────┐┌ processes ───────────────────────┐
+//! │SYSCALL              COUNT  ││PID     COMM              COUNT   │
+//! │read                 61233  ││1742    postgres          80311   │
+//! │epoll_wait           40102  ││2210    nginx             51100   │
+//! └────────────────────────────┘└──────────────────────────────────┘
+//! q: quit  /: filter by comm  c: clear filter
+//! ```
+//!
+//! The tracer sends [`Update`]s over a channel; [`run`] owns the terminal
+//! and returns the final [`Dashboard`] so the caller can fill its run
+//! report. Everything except [`run`] works without a terminal, which is
+//! what the tests below use.
+
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Paragraph, Row, Sparkline, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// Seconds of history kept for the events/s sparkline.
+pub const HISTORY_SECS: usize = 120;
+
+/// How long to wait for a key press before redrawing.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(200);
+
+/// One syscall event, with the names already resolved by the tracer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub pid: u32,
+    /// Current process name (from `CommCache`, not the raw event)
+    pub comm: String,
+    pub syscall: String,
+}
+
+/// What the tracer sends to the dashboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Update {
+    Sample(Sample),
+    /// Events the kernel dropped because the perf buffer was full
+    Lost(u64),
+}
+
+/// Result of a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Continue,
+    Quit,
+}
+
+/// Counters and UI state behind the dashboard.
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    /// Event counts by (pid, comm, syscall); the tables are views of this
+    counts: HashMap<(u32, String, String), u64>,
+    total: u64,
+    lost: u64,
+    /// Matching events since the last tick
+    this_second: u64,
+    /// Matching events per second, oldest first
+    history: VecDeque<u64>,
+    filter: String,
+    editing: bool,
+}
+
+impl Dashboard {
+    /// Count one update.
+    pub fn record(&mut self, update: &Update) {
+        match update {
+            Update::Sample(sample) => {
+                *self
+                    .counts
+                    .entry((sample.pid, sample.comm.clone(), sample.syscall.clone()))
+                    .or_insert(0) += 1;
+                self.total += 1;
+                if self.matches(&sample.comm) {
+                    self.this_second += 1;
+                }
+            }
+            Update::Lost(n) => self.lost += n,
+        }
+    }
+
+    /// Close the current one-second bucket of the sparkline.
+    ///
+    /// The sparkline follows the filter from the moment it is set; the
+    /// buckets before that keep counting every process.
+    pub fn tick(&mut self) {
+        self.history.push_back(self.this_second);
+        if self.history.len() > HISTORY_SECS {
+            self.history.pop_front();
+        }
+        self.this_second = 0;
+    }
+
+    /// Events received, including those hidden by the filter.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Events lost in the perf buffer.
+    pub fn lost(&self) -> u64 {
+        self.lost
+    }
+
+    /// Matching events in the last complete second.
+    pub fn rate(&self) -> u64 {
+        self.history.back().copied().unwrap_or(0)
+    }
+
+    /// Current comm filter ("" = every process).
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// True if `comm` passes the filter (substring, ignoring case).
+    fn matches(&self, comm: &str) -> bool {
+        self.filter.is_empty() || comm.to_lowercase().contains(&self.filter.to_lowercase())
+    }
+
+    /// Syscalls of matching processes, most frequent first.
+    pub fn top_syscalls(&self, n: usize) -> Vec<(String, u64)> {
+        let mut by_syscall: HashMap<&str, u64> = HashMap::new();
+        for ((_, comm, syscall), count) in &self.counts {
+            if self.matches(comm) {
+                *by_syscall.entry(syscall).or_insert(0) += count;
+            }
+        }
+        let mut rows: Vec<(String, u64)> = by_syscall
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        rows.truncate(n);
+        rows
+    }
+
+    /// Matching processes, most syscalls first.
+    pub fn top_processes(&self, n: usize) -> Vec<(u32, String, u64)> {
+        let mut by_process: HashMap<(u32, &str), u64> = HashMap::new();
+        for ((pid, comm, _), count) in &self.counts {
+            if self.matches(comm) {
+                *by_process.entry((*pid, comm)).or_insert(0) += count;
+            }
+        }
+        let mut rows: Vec<(u32, String, u64)> = by_process
+            .into_iter()
+            .map(|((pid, comm), count)| (pid, comm.to_string(), count))
+            .collect();
+        rows.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        rows.truncate(n);
+        rows
+    }
+
+    /// Apply a key press.
+    ///
+    /// `/` starts editing the filter; Enter keeps it and Esc clears it.
+    /// Outside the editor, `q`, Esc and Ctrl+C quit and `c` clears the filter.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Continue;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        if self.editing {
+            match key.code {
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.editing = false;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(c) => self.filter.push(c),
+                _ => {}
+            }
+            return Action::Continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Char('c') => self.filter.clear(),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    /// Draw the dashboard into `frame`.
+    pub fn render(&self, frame: &mut Frame) {
+        let [header, spark, tables, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(5),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let filter = if self.filter.is_empty() {
+            "-"
+        } else {
+            self.filter.as_str()
+        };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "ebpf-tool trace   events: {}  lost: {}  rate: {}/s  filter: {}",
+                self.total,
+                self.lost,
+                self.rate(),
+                filter
+            ))
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+            header,
+        );
+
+        // Show the newest seconds that fit inside the border
+        let width = spark.width.saturating_sub(2) as usize;
+        let skip = self.history.len().saturating_sub(width);
+        frame.render_widget(
+            Sparkline::default()
+                .block(Block::bordered().title(" events/s "))
+                .data(self.history.iter().skip(skip)),
+            spark,
+        );
+
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(tables);
+        let rows = tables.height.saturating_sub(3) as usize;
+        let header_style = Style::new().add_modifier(Modifier::BOLD);
+
+        let syscalls = self
+            .top_syscalls(rows)
+            .into_iter()
+            .map(|(name, count)| Row::new(vec![name, count.to_string()]));
+        frame.render_widget(
+            Table::new(syscalls, [Constraint::Fill(1), Constraint::Length(10)])
+                .header(Row::new(vec!["SYSCALL", "COUNT"]).style(header_style))
+                .block(Block::bordered().title(" syscalls ")),
+            left,
+        );
+
+        let processes = self
+            .top_processes(rows)
+            .into_iter()
+            .map(|(pid, comm, count)| Row::new(vec![pid.to_string(), comm, count.to_string()]));
+        frame.render_widget(
+            Table::new(
+                processes,
+                [
+                    Constraint::Length(8),
+                    Constraint::Fill(1),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(Row::new(vec!["PID", "COMM", "COUNT"]).style(header_style))
+            .block(Block::bordered().title(" processes ")),
+            right,
+        );
+
+        let help = if self.editing {
+            format!("filter: {}_   Enter: apply  Esc: clear", self.filter)
+        } else {
+            "q: quit  /: filter by comm  c: clear filter".to_string()
+        };
+        frame.render_widget(Paragraph::new(help), footer);
+    }
+}
+
+/// Take over the terminal and show `updates` until the user quits or the
+/// sender is dropped. The terminal is restored on every path out.
+pub fn run(updates: Receiver<Update>) -> Result<Dashboard> {
+    let mut terminal = ratatui::try_init().context("failed to set up the terminal")?;
+    let result = event_loop(&mut terminal, updates);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, updates: Receiver<Update>) -> Result<Dashboard> {
+    let mut dashboard = Dashboard::default();
+    let mut next_tick = Instant::now() + Duration::from_secs(1);
+    loop {
+        loop {
+            match updates.try_recv() {
+                Ok(update) => dashboard.record(&update),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(dashboard),
+            }
+        }
+        while Instant::now() >= next_tick {
+            dashboard.tick();
+            next_tick += Duration::from_secs(1);
+        }
+
+        terminal.draw(|frame| dashboard.render(frame))?;
+
+        if event::poll(REDRAW_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                if dashboard.handle_key(key) == Action::Quit {
+                    return Ok(dashboard);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn sample(pid: u32, comm: &str, syscall: &str) -> Update {
+        Update::Sample(Sample {
+            pid,
+            comm: comm.to_string(),
+            syscall: syscall.to_string(),
+        })
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn dashboard() -> Dashboard {
+        let mut d = Dashboard::default();
+        for _ in 0..3 {
+            d.record(&sample(10, "nginx", "read"));
+        }
+        d.record(&sample(10, "nginx", "write"));
+        d.record(&sample(20, "bash", "read"));
+        d.record(&sample(20, "bash", "openat"));
+        d.record(&Update::Lost(7));
+        d
+    }
+
+    #[test]
+    fn test_top_tables_follow_filter() {
+        let mut d = dashboard();
+        assert_eq!(d.total(), 6);
+        assert_eq!(d.lost(), 7);
+        assert_eq!(
+            d.top_syscalls(2),
+            vec![("read".to_string(), 4), ("openat".to_string(), 1)]
+        );
+        assert_eq!(
+            d.top_processes(10),
+            vec![(10, "nginx".to_string(), 4), (20, "bash".to_string(), 2)]
+        );
+
+        for c in "/BA".chars() {
+            d.handle_key(key(KeyCode::Char(c)));
+        }
+        d.handle_key(key(KeyCode::Enter));
+        assert_eq!(d.filter(), "BA");
+        assert_eq!(d.top_processes(10), vec![(20, "bash".to_string(), 2)]);
+        assert_eq!(
+            d.total(),
+            6,
+            "the filter hides events, it does not drop them"
+        );
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut d = Dashboard::default();
+        for i in 0..HISTORY_SECS + 5 {
+            d.record(&sample(1, "init", "read"));
+            if i % 2 == 0 {
+                d.record(&sample(1, "init", "read"));
+            }
+            d.tick();
+        }
+        assert_eq!(d.history.len(), HISTORY_SECS);
+        // The last bucket is i = HISTORY_SECS + 4, an even second
+        assert_eq!(d.rate(), 2);
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut d = Dashboard::default();
+        assert_eq!(d.handle_key(key(KeyCode::Char('/'))), Action::Continue);
+        // While editing, q is part of the filter
+        assert_eq!(d.handle_key(key(KeyCode::Char('q'))), Action::Continue);
+        assert_eq!(d.filter(), "q");
+        d.handle_key(key(KeyCode::Backspace));
+        d.handle_key(key(KeyCode::Char('x')));
+        d.handle_key(key(KeyCode::Esc));
+        assert_eq!(d.filter(), "", "Esc in the editor clears the filter");
+        assert_eq!(
+            d.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+        assert_eq!(d.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 16)).unwrap();
+        let d = dashboard();
+        terminal.draw(|frame| d.render(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        for text in [
+            "events: 6",
+            "lost: 7",
+            "SYSCALL",
+            "read",
+            "nginx",
+            "q: quit",
+        ] {
+            assert!(screen.contains(text), "{text:?} not on screen");
+        }
+    }
+}
@@ -0,0 +1,585 @@
+//! Readable verifier errors: what failed, where in the Rust source, and why.
+//!
+//! When the kernel rejects a program, aya returns the verifier log as one
+//! long string inside `ProgramError::LoadError`. The interesting part is at
+//! the end: the last instruction the verifier looked at and the reason it
+//! gave up. Programs built with BTF line info (bpf-linker emits it for
+//! debug builds) also get the source line in the log, as `; <code> @ <file>:<line>`
+//! comments before the instructions:
+//!
+//! ```text
+//! ; *value += 1; @ maps.rs:53                  ─► source
+//! 7: (79) r1 = *(u64 *)(r0 +0)                 ─► failing instruction
+//! R0 invalid mem access 'map_value_or_null'    ─► message ─► hint
+//! ```
+//!
+//! [`explain`] turns a load error into a [`Report`] with those parts, a hint
+//! for the common causes, and the path of the full log.
+
+use aya::programs::ProgramError;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Instructions shown before the failing one.
+const CONTEXT_INSNS: usize = 3;
+
+/// Stack size of one BPF program (MAX_BPF_STACK).
+const MAX_STACK: i64 = 512;
+
+/// The first HINTS entries, stack and loops, are also matched against the
+/// whole log when there is no message: their errors can come from the CFG
+/// check, before any instruction is printed.
+const WHOLE_LOG_HINTS: usize = 2;
+
+const STACK_HINT: &str = "the program uses more than 512 bytes of stack: move large structs \
+     and buffers into a per-CPU array map (PerCpuArray) and work on the map \
+     value instead";
+
+/// Common causes, by what the message (or, for the first WHOLE_LOG_HINTS,
+/// the whole log) says. The stack hint also matches any stack access below
+/// -512 ([`stack_overflow`]).
+const HINTS: &[(&[&str], &str)] = &[
+    (&["combined stack size", "stack limit"], STACK_HINT),
+    (
+        &["back-edge", "infinite loop", "loop detected"],
+        "the verifier cannot prove that a loop ends: bound it with a \
+         constant (`for i in 0..MAX`) and check the bound before every \
+         access, or use bpf_loop()",
+    ),
+    (
+        &["program is too large", "too complex", "1000001 insn"],
+        "the verifier gave up after a million instructions: every branch \
+         in a loop multiplies the paths it checks. Lower the loop bound, \
+         or split the work with a tail call",
+    ),
+    (
+        &[
+            "map_value_or_null",
+            "_or_null",
+            "invalid mem access 'scalar'",
+        ],
+        "a map lookup can return null: check the result before using it \
+         (`if let Some(v) = MAP.get_ptr_mut(&key)`), never unwrap_unchecked \
+         it",
+    ),
+    (
+        &[
+            "outside of the allowed memory range",
+            "invalid access to map value",
+            "invalid access to packet",
+            "unbounded memory access",
+            "min value is negative",
+            "invalid variable-offset",
+        ],
+        "an index or offset is not bounds-checked on this path: compare it \
+         with the buffer length right before the access (the verifier \
+         forgets checks done in another function or before a copy)",
+    ),
+    (
+        &["invalid indirect read from stack", "!read_ok"],
+        "memory or a register is read before it was written: zero-initialize \
+         structs passed to helpers (`core::mem::zeroed()` or `Default`)",
+    ),
+    (
+        &[
+            "unknown func",
+            "cannot call",
+            "not allowed",
+            "helper call is not",
+        ],
+        "this helper is not available to this program type or on this \
+         kernel: check the helper's minimum kernel version and the program \
+         types that may call it (`bpftool feature probe kernel`)",
+    ),
+    (
+        &["R1 type=", "expected=ctx", "arg#0 expected"],
+        "a helper got the wrong kind of pointer: pass the program's `ctx`, \
+         a stack or map pointer as the helper expects, not an integer",
+    ),
+];
+
+/// One instruction line of the log: `7: (79) r1 = *(u64 *)(r0 +0)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insn {
+    pub index: u32,
+    /// The instruction without the register state after `;`
+    pub text: String,
+}
+
+/// A source comment of the log: `; *value += 1; @ maps.rs:53`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLine {
+    pub code: String,
+    /// File and line; only kernels since 6.x print it, older ones only
+    /// the code
+    pub location: Option<(String, u32)>,
+}
+
+/// A load error, taken apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub program: String,
+    /// errno of BPF_PROG_LOAD, if it was a syscall error
+    pub errno: Option<i32>,
+    /// The failing instruction last, with up to CONTEXT_INSNS before it
+    pub insns: Vec<Insn>,
+    /// Source of the failing instruction, from BTF line info
+    pub source: Option<SourceLine>,
+    /// Why the verifier gave up
+    pub message: Vec<String>,
+    /// "processed 7 insns (limit 1000000) ..."
+    pub stats: Option<String>,
+    pub hints: Vec<&'static str>,
+    /// Where the full log was saved, if it was
+    pub log_file: Option<PathBuf>,
+    /// Whether the log had any source comments at all
+    pub has_line_info: bool,
+}
+
+impl Report {
+    /// Take apart the verifier log of `program`.
+    pub fn parse(program: &str, log: &str, errno: Option<i32>) -> Self {
+        let mut insns: Vec<Insn> = Vec::new();
+        let mut source = None;
+        let mut last_source = None;
+        let mut message = Vec::new();
+        let mut stats = None;
+        let mut has_line_info = false;
+
+        for line in log.lines() {
+            let line = line.trim_end();
+            if let Some(code) = line.strip_prefix("; ") {
+                last_source = Some(parse_source(code));
+                has_line_info = true;
+            } else if let Some(insn) = parse_insn(line) {
+                // A new instruction: what came after the previous one was
+                // not the final error
+                message.clear();
+                source = last_source.clone();
+                insns.push(insn);
+            } else if line.starts_with("processed ") {
+                stats = Some(line.to_string());
+            } else if !is_noise(line) {
+                message.push(line.to_string());
+            }
+        }
+
+        let keep = insns.len().saturating_sub(CONTEXT_INSNS + 1);
+        let insns = insns.split_off(keep);
+        let hints = hints(&message, log);
+        Self {
+            program: program.to_string(),
+            errno,
+            insns,
+            source,
+            message,
+            stats,
+            hints,
+            log_file: None,
+            has_line_info,
+        }
+    }
+
+    /// The instruction the verifier rejected, if the log shows one.
+    pub fn failing(&self) -> Option<&Insn> {
+        self.insns.last()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load program `{}`", self.program)?;
+        let errno = self.errno.map(|e| nix::errno::Errno::from_raw(e).desc());
+        if self.message.is_empty() && self.insns.is_empty() {
+            write!(f, ": {}", errno.unwrap_or("no verifier log"))?;
+            if self.errno == Some(libc::EPERM) {
+                write!(
+                    f,
+                    "\n\nhint: the kernel refused before verifying anything: run as \
+                     root or with CAP_BPF and CAP_PERFMON (see `ebpf-tool check`)"
+                )?;
+            }
+            return Ok(());
+        }
+        write!(f, ": the verifier rejected it")?;
+        if let Some(errno) = errno {
+            write!(f, " ({})", errno)?;
+        }
+        writeln!(f)?;
+
+        if let Some(ref src) = self.source {
+            match src.location {
+                Some((ref file, line)) => write!(f, "\n  {}:{}: {}", file, line, src.code)?,
+                None => write!(f, "\n  source: {}", src.code)?,
+            }
+        }
+        let last = self.insns.len().saturating_sub(1);
+        for (i, insn) in self.insns.iter().enumerate() {
+            let mark = if i == last { ">" } else { " " };
+            write!(f, "\n  {} {:>5}: {}", mark, insn.index, insn.text)?;
+        }
+        for line in &self.message {
+            write!(f, "\n  {}", line)?;
+        }
+        writeln!(f)?;
+
+        for hint in &self.hints {
+            write!(f, "\nhint: {}", hint)?;
+        }
+        if self.source.is_none() && !self.has_line_info {
+            write!(
+                f,
+                "\nhint: the program has no BTF line info, so the log cannot show the \
+                 Rust source: build the eBPF crate with debug info (bpf-linker --btf)"
+            )?;
+        }
+        if let Some(ref stats) = self.stats {
+            write!(f, "\n{}", stats)?;
+        }
+        match self.log_file {
+            Some(ref path) => write!(f, "\nfull verifier log: {}", path.display()),
+            None => write!(f, "\nfull verifier log: run with --verbose"),
+        }
+    }
+}
+
+impl std::error::Error for Report {}
+
+/// Turn the error of `program.load()` into something readable. Errors other
+/// than a rejected load are passed through with the program name.
+///
+/// ```ignore
+/// program.load().map_err(|e| verifier::explain("trace_syscall_enter", e))?;
+/// ```
+pub fn explain(program: &str, err: ProgramError) -> anyhow::Error {
+    match err {
+        ProgramError::LoadError {
+            io_error,
+            verifier_log,
+        } => {
+            let log = verifier_log.to_string();
+            log::debug!("verifier log of {}:\n{}", program, log);
+            let mut report = Report::parse(program, &log, io_error.raw_os_error());
+            if !log.is_empty() {
+                report.log_file = save_log(program, &log);
+            }
+            anyhow::Error::new(report)
+        }
+        other => anyhow::Error::new(other).context(format!("failed to load program `{}`", program)),
+    }
+}
+
+/// Save the full log to the temp directory; logs can be megabytes long.
+///
+/// The tool runs as root and /tmp is shared: the file is always a new one
+/// (never an existing file or a symlink someone planted), readable only by
+/// its owner, and named after this process so concurrent runs keep their own.
+fn save_log(program: &str, log: &str) -> Option<PathBuf> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let pid = std::process::id();
+    for n in 0..100 {
+        let name = match n {
+            0 => format!("ebpf-tool-verifier-{}-{}.log", program, pid),
+            n => format!("ebpf-tool-verifier-{}-{}-{}.log", program, pid, n),
+        };
+        let path = std::env::temp_dir().join(name);
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path);
+        match file {
+            Ok(mut file) => return file.write_all(log.as_bytes()).ok().map(|()| path),
+            // A log of an earlier load of this program, in this process
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                log::debug!(
+                    "failed to save the verifier log to {}: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        }
+    }
+    None
+}
+
+fn parse_insn(line: &str) -> Option<Insn> {
+    let (index, rest) = line.split_once(": (")?;
+    let index = index.parse().ok()?;
+    // "(79) r1 = *(u64 *)(r0 +0)        ; R1_w=..."
+    let (_, text) = rest.split_once(") ")?;
+    let text = match text.find("  ;") {
+        Some(i) => &text[..i],
+        None => text,
+    };
+    Some(Insn {
+        index,
+        text: text.trim().to_string(),
+    })
+}
+
+fn parse_source(code: &str) -> SourceLine {
+    if let Some((code, at)) = code.rsplit_once(" @ ") {
+        if let Some((file, line)) = at.rsplit_once(':') {
+            if let Ok(line) = line.parse() {
+                return SourceLine {
+                    code: code.trim().to_string(),
+                    location: Some((file.to_string(), line)),
+                };
+            }
+        }
+    }
+    SourceLine {
+        code: code.trim().to_string(),
+        location: None,
+    }
+}
+
+/// Log lines that say nothing about the error: register states between
+/// instructions, function markers and statistics.
+fn is_noise(line: &str) -> bool {
+    let state = line
+        .split_once(':')
+        .is_some_and(|(head, _)| head.parse::<u32>().is_ok() || head.starts_with("from "));
+    line.is_empty()
+        || state
+        || line.starts_with("func#")
+        || line.starts_with("verification time")
+        || line.starts_with("stack depth")
+        || line.starts_with("mark_precise")
+        || line.starts_with("last_idx")
+        || line.starts_with("regs=")
+        || line.starts_with("Validating ")
+        || line.starts_with("Func#")
+}
+
+/// Whether `line` is an out-of-range stack access:
+/// `invalid write to stack R10 off=-520 size=8`, also with "read from" or
+/// "indirect access to", through any register.
+fn stack_overflow(line: &str) -> bool {
+    let Some(rest) = line.find("invalid ").map(|i| &line[i..]) else {
+        return false;
+    };
+    let Some((_, reg)) = rest.split_once(" stack R") else {
+        return false;
+    };
+    let Some((regno, rest)) = reg.split_once(' ') else {
+        return false;
+    };
+    let offset = rest
+        .strip_prefix("off=")
+        .and_then(|off| off.split(' ').next())
+        .and_then(|off| off.parse::<i64>().ok());
+    regno.parse::<u8>().is_ok() && offset.is_some_and(|off| off < -MAX_STACK)
+}
+
+fn hints(message: &[String], log: &str) -> Vec<&'static str> {
+    let text = message.join("\n");
+    HINTS
+        .iter()
+        .enumerate()
+        .filter(|&(i, &(patterns, hint))| {
+            let whole_log = message.is_empty() && i < WHOLE_LOG_HINTS;
+            patterns
+                .iter()
+                .any(|p| text.contains(p) || (whole_log && log.contains(p)))
+                || (hint == STACK_HINT && message.iter().any(|line| stack_overflow(line)))
+        })
+        .map(|(_, &(_, hint))| hint)
+        .take(2)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NULL_CHECK: &str = "\
+func#0 @0
+0: R1=ctx() R10=fp0
+; let key = 0u32; @ maps.rs:50
+0: (b7) r1 = 0                        ; R1_w=0
+1: (63) *(u32 *)(r10 -4) = r1         ; R1_w=0 R10=fp0 fp-8=0000????
+2: (bf) r2 = r10                      ; R2_w=fp0 R10=fp0
+3: (07) r2 += -4                      ; R2_w=fp-4
+; let value = unsafe { COUNTS.get_ptr_mut(&key) }; @ maps.rs:52
+4: (18) r1 = 0xffff888103c6e000       ; R1_w=map_ptr(map=COUNTS,ks=4,vs=8)
+6: (85) call bpf_map_lookup_elem#1    ; R0_w=map_value_or_null(id=1,map=COUNTS,ks=4,vs=8)
+; unsafe { *value += 1 }; @ maps.rs:53
+7: (79) r1 = *(u64 *)(r0 +0)
+R0 invalid mem access 'map_value_or_null'
+verification time 45 usec
+stack depth 4
+processed 7 insns (limit 1000000) max_states_per_insn 0 total_states 0 peak_states 0 mark_read 0
+";
+
+    #[test]
+    fn test_parse_failing_insn_and_source() {
+        let r = Report::parse("count_syscalls", NULL_CHECK, Some(libc::EACCES));
+        assert_eq!(
+            r.failing(),
+            Some(&Insn {
+                index: 7,
+                text: "r1 = *(u64 *)(r0 +0)".to_string()
+            })
+        );
+        assert_eq!(r.insns.len(), CONTEXT_INSNS + 1);
+        assert_eq!(
+            r.source,
+            Some(SourceLine {
+                code: "unsafe { *value += 1 };".to_string(),
+                location: Some(("maps.rs".to_string(), 53)),
+            })
+        );
+        assert_eq!(r.message, ["R0 invalid mem access 'map_value_or_null'"]);
+        assert!(r.stats.as_deref().unwrap().starts_with("processed 7 insns"));
+        assert_eq!(r.hints.len(), 1);
+        assert!(r.hints[0].contains("can return null"));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut r = Report::parse("count_syscalls", NULL_CHECK, Some(libc::EACCES));
+        r.log_file = Some(PathBuf::from("/tmp/v.log"));
+        let text = r.to_string();
+        assert!(text.starts_with(
+            "failed to load program `count_syscalls`: the verifier rejected it (Permission denied)"
+        ));
+        assert!(
+            text.contains("\n  maps.rs:53: unsafe { *value += 1 };"),
+            "{text}"
+        );
+        assert!(text.contains("\n  >     7: r1 = *(u64 *)(r0 +0)"), "{text}");
+        assert!(
+            text.contains("\n        6: call bpf_map_lookup_elem#1"),
+            "{text}"
+        );
+        assert!(text.contains("\nhint: a map lookup can return null"));
+        assert!(text.ends_with("full verifier log: /tmp/v.log"));
+        assert!(!text.contains("no BTF line info"));
+    }
+
+    #[test]
+    fn test_old_kernel_without_locations() {
+        let log = "\
+; let b = buf[i];
+12: (71) r3 = *(u8 *)(r2 +0)
+invalid access to map value, value_size=64 off=64 size=1
+R2 max value is outside of the allowed memory range
+processed 20 insns (limit 1000000)
+";
+        let r = Report::parse("read_buf", log, Some(libc::EACCES));
+        assert_eq!(r.source.as_ref().unwrap().location, None);
+        assert_eq!(r.source.as_ref().unwrap().code, "let b = buf[i];");
+        assert_eq!(r.message.len(), 2);
+        assert!(r.hints[0].contains("bounds-checked"));
+        assert!(r.to_string().contains("\n  source: let b = buf[i];"));
+    }
+
+    #[test]
+    fn test_errors_without_insn() {
+        // CFG check: before any instruction is printed
+        let r = Report::parse("spin", "back-edge from insn 15 to 10\n", Some(libc::EINVAL));
+        assert_eq!(r.failing(), None);
+        assert!(r.hints[0].contains("loop"));
+        let text = r.to_string();
+        assert!(text.contains("back-edge from insn 15 to 10"));
+        assert!(text.contains("no BTF line info"));
+
+        let stack = Report::parse(
+            "big",
+            "0: (7b) *(u64 *)(r10 -520) = r1\ninvalid write to stack R10 off=-520 size=8\n",
+            Some(libc::EACCES),
+        );
+        assert!(stack.hints[0].contains("512 bytes of stack"));
+    }
+
+    #[test]
+    fn test_stack_overflow_offsets() {
+        for (message, overflow) in [
+            ("invalid write to stack R10 off=-520 size=8", true),
+            ("invalid write to stack R10 off=-600 size=8", true),
+            ("invalid write to stack R10 off=-1024 size=8", true),
+            ("invalid read from stack R10 off=-600 size=8", true),
+            ("invalid indirect access to stack R1 off=-600 size=16", true),
+            ("invalid write to stack R10 off=-512 size=8", false),
+            ("invalid read from stack R10 off=-8 size=8", false),
+            ("invalid write to stack R10 off=0 size=8", false),
+            ("R0 invalid mem access 'scalar'", false),
+        ] {
+            assert_eq!(stack_overflow(message), overflow, "{message}");
+        }
+
+        let read = Report::parse(
+            "big",
+            "0: (79) r1 = *(u64 *)(r10 -600)\ninvalid read from stack R10 off=-600 size=8\n",
+            Some(libc::EACCES),
+        );
+        assert!(read.hints[0].contains("512 bytes of stack"));
+        let write = Report::parse(
+            "big",
+            "0: (7b) *(u64 *)(r10 -600) = r1\ninvalid write to stack R10 off=-600 size=8\n",
+            Some(libc::EACCES),
+        );
+        assert!(write.hints[0].contains("512 bytes of stack"));
+        let in_range = Report::parse(
+            "small",
+            "0: (79) r1 = *(u64 *)(r10 -8)\ninvalid read from stack R10 off=-8 size=8\n",
+            Some(libc::EACCES),
+        );
+        assert!(in_range.hints.iter().all(|h| !h.contains("512 bytes")));
+    }
+
+    #[test]
+    fn test_whole_log_fallback() {
+        // No message: only the stack and loop hints look at the whole log,
+        // the null check in a register state is not an error
+        let log = "0: (b7) r0 = 0\nback-edge from insn 1 to 0\n1: R0=map_value_or_null()\n";
+        let found = hints(&[], log);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("loop"));
+    }
+
+    #[test]
+    fn test_save_log_creates_new_files() {
+        let first = save_log("save_log_test", "log one").unwrap();
+        let second = save_log("save_log_test", "log two").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "log one");
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), "log two");
+        let mode = std::os::unix::fs::PermissionsExt::mode(
+            &std::fs::metadata(&first).unwrap().permissions(),
+        );
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn test_refused_before_verifying() {
+        let r = Report::parse("kprobe_fn", "", Some(libc::EPERM));
+        let text = r.to_string();
+        assert!(text.starts_with("failed to load program `kprobe_fn`: Operation not permitted"));
+        assert!(text.contains("CAP_BPF"));
+    }
+
+    #[test]
+    fn test_insn_lines() {
+        assert_eq!(
+            parse_insn("6: (85) call bpf_map_lookup_elem#1    ; R0_w=map_value_or_null(id=1)"),
+            Some(Insn {
+                index: 6,
+                text: "call bpf_map_lookup_elem#1".to_string()
+            })
+        );
+        assert_eq!(parse_insn("0: R1=ctx() R10=fp0"), None);
+        assert_eq!(parse_insn("R0 invalid mem access 'scalar'"), None);
+        assert!(is_noise("from 5 to 7: R0=scalar() R10=fp0"));
+        assert!(is_noise("7: R0=scalar()"));
+        assert!(!is_noise("R0 invalid mem access 'scalar'"));
+    }
+}
@@ -0,0 +1,120 @@
+// Tests for `bench` (syscall loop without and with the tracer)
+// Lesson: docs/04-ebpf/47-bench.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs (GREEN)
+//
+// The loop, the medians and the report (src/bench.rs) are already covered
+// by unit tests: cargo test -p ebpf-tool --lib bench
+//
+// NOTE: Root tests require CAP_BPF and CAP_PERFMON (or root).
+// Run with: sudo -E cargo test -p ebpf-tool --test bench_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_bench_help() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["bench", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--workload"))
+        .stdout(predicate::str::contains("openat"))
+        .stdout(predicate::str::contains("getpid"));
+}
+
+#[test]
+fn test_bench_rejects_unknown_workload() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["bench", "--workload", "fork"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'fork'"));
+}
+
+#[test]
+fn test_bench_rejects_zero_counts() {
+    // A run without syscalls has no time per syscall to compare
+    for flag in ["--iterations", "--runs"] {
+        Command::cargo_bin("ebpf-tool")
+            .unwrap()
+            .args(["bench", flag, "0"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid value '0'"));
+    }
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_bench_reports_overhead() {
+    // TODO: Verify the report of a short benchmark
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Run `ebpf-tool bench --workload getpid -n 20000 -r 3`
+    // - stdout has "baseline:", "traced:", "overhead:" and "tracer:" lines
+    // - The traced time per syscall is larger than the baseline one: parse
+    //   the two numbers before "ns/syscall"
+
+    if !is_root() {
+        eprintln!("Skipping test_bench_reports_overhead: requires root");
+        return;
+    }
+
+    todo!("Implement test for the bench report")
+}
+
+#[test]
+fn test_bench_counts_only_the_workload() {
+    // TODO: Verify that other processes do not add events
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Start `yes > /dev/null` in the background, then run
+    //   `ebpf-tool bench --workload read -n 20000 -r 1`
+    // - The events count is at most 20000 plus a few: the PID filter keeps
+    //   `yes` out. Kill `yes` afterwards
+
+    if !is_root() {
+        eprintln!("Skipping test_bench_counts_only_the_workload: requires root");
+        return;
+    }
+
+    todo!("Implement test for the PID filter of bench")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_bench_detaches() {
+    // TODO: Verify that nothing stays attached after the benchmark
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Count the programs in `ebpf-tool prog list` before and after
+    //   `ebpf-tool bench -n 1000 -r 1`: the same number
+
+    if !is_root() {
+        eprintln!("Skipping test_bench_detaches: requires root");
+        return;
+    }
+
+    todo!("Implement test for detaching after bench")
+}
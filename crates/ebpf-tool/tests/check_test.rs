@@ -1,14 +1,13 @@
-// Tests for the `check` subcommand (eBPF environment validation)
+// Tests for the `check` subcommand (eBPF environment doctor)
 // Lesson: docs/04-ebpf/00-ebpf-setup.md
 //
-// TDD Workflow:
-// 1. Write the test(s) below FIRST (RED - they will fail)
-// 2. Implement the code in src/main.rs to make tests pass (GREEN)
-// 3. Refactor if needed
+// `check` is already implemented (src/doctor.rs), so these tests pass from
+// the start. They do not assume a fully working environment: a machine
+// without tracefs or without a built eBPF object makes `check` fail, and
+// the tests only verify that it fails consistently and explains why.
 //
-// NOTE: Most tests require root privileges to validate eBPF capabilities.
-// Tests that require root will skip gracefully when run as non-root user.
-// Run with: sudo -E cargo test -p ebpf-tool
+// Run with: cargo test -p ebpf-tool --test check_test
+// As root the permission test also runs: sudo -E cargo test -p ebpf-tool --test check_test
 
 use assert_cmd::Command;
 use predicates::prelude::*;
@@ -21,149 +20,81 @@ fn is_root() -> bool {
 
 #[test]
 fn test_check_help() {
-    // TODO: Test that --help shows check subcommand info
-    //
-    // The check subcommand should have helpful documentation explaining
-    // that it validates the eBPF environment including BTF, kernel version,
-    // and permissions.
-    //
-    // Hints:
-    // - Use Command::cargo_bin("ebpf-tool")
-    // - Add args: ["check", "--help"]
-    // - Assert success
-    // - Check stdout contains "Validate" (from the subcommand description)
-    //
-    // Implementation:
-    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
-    // cmd.args(["check", "--help"])
-    //    .assert()
-    //    .success()
-    //    .stdout(predicate::str::contains("Validate"));
-
-    todo!("Implement test for check --help")
+    let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    cmd.args(["check", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Validate"))
+        .stdout(predicate::str::contains("--format"));
 }
 
 #[test]
-fn test_check_runs_as_root() {
-    // TODO: Test that check subcommand runs successfully with root privileges
-    //
-    // When run as root, the check command should complete successfully
-    // and output diagnostic information about the eBPF environment.
-    //
-    // Hints:
-    // - Use is_root() helper to skip if not root
-    // - Use Command::cargo_bin("ebpf-tool")
-    // - Add arg: "check"
-    // - Assert success (exit code 0)
-    //
-    // Implementation:
-    // if !is_root() {
-    //     eprintln!("Skipping test_check_runs_as_root: requires root privileges");
-    //     return;
-    // }
-    //
-    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
-    // cmd.arg("check")
-    //    .assert()
-    //    .success();
+fn test_check_lists_every_check() {
+    let output = Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .arg("check")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
 
-    todo!("Implement test for check running as root")
+    for name in [
+        "Kernel version:",
+        "BTF:",
+        "Capabilities:",
+        "Unprivileged BPF:",
+        "Memlock limit:",
+        "JIT:",
+        "bpffs:",
+        "tracefs:",
+        "BPF LSM:",
+        "eBPF object:",
+        "Syscall table:",
+    ] {
+        assert!(stdout.contains(name), "missing {name:?} in:\n{stdout}");
+    }
+    assert!(
+        predicate::str::is_match(r"\d+ checks: \d+ ok, \d+ warnings, \d+ failed")
+            .unwrap()
+            .eval(&stdout)
+    );
 }
 
 #[test]
-fn test_check_shows_kernel_version() {
-    // TODO: Test that check output includes kernel version information
-    //
-    // The check command should display the current kernel version
-    // and indicate whether it meets the minimum requirements for eBPF.
-    // A good eBPF experience requires kernel 5.8+ (for CAP_BPF, ring buffers, etc.)
-    //
-    // Hints:
-    // - Use is_root() helper to skip if not root
-    // - Use Command::cargo_bin("ebpf-tool")
-    // - Add arg: "check"
-    // - Assert stdout contains "Kernel" or "kernel"
-    // - Optionally check for version pattern like "5." or "6."
-    //
-    // Implementation:
-    // if !is_root() {
-    //     eprintln!("Skipping test_check_shows_kernel_version: requires root privileges");
-    //     return;
-    // }
-    //
-    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
-    // cmd.arg("check")
-    //    .assert()
-    //    .success()
-    //    .stdout(predicate::str::is_match("[Kk]ernel").unwrap());
-
-    todo!("Implement test for kernel version in check output")
-}
+fn test_check_json_matches_exit_code() {
+    let output = Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["check", "--format", "json"])
+        .output()
+        .unwrap();
+    let doc: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be one JSON document");
 
-#[test]
-fn test_check_shows_btf_status() {
-    // TODO: Test that check output includes BTF availability status
-    //
-    // BTF (BPF Type Format) is essential for CO-RE (Compile Once, Run Everywhere).
-    // The check command should verify that /sys/kernel/btf/vmlinux exists
-    // and report its status.
-    //
-    // Hints:
-    // - Use is_root() helper to skip if not root
-    // - Use Command::cargo_bin("ebpf-tool")
-    // - Add arg: "check"
-    // - Assert stdout contains "BTF" (case-sensitive, it's an acronym)
-    // - The output might show path "/sys/kernel/btf/vmlinux" or just status
-    //
-    // Implementation:
-    // if !is_root() {
-    //     eprintln!("Skipping test_check_shows_btf_status: requires root privileges");
-    //     return;
-    // }
-    //
-    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
-    // cmd.arg("check")
-    //    .assert()
-    //    .success()
-    //    .stdout(predicate::str::contains("BTF"));
+    let checks = doc["checks"].as_array().unwrap();
+    assert_eq!(checks.len(), 11);
+    let failed = checks.iter().filter(|c| c["status"] == "fail").count();
+    for check in checks {
+        let status = check["status"].as_str().unwrap();
+        assert!(["ok", "warn", "fail"].contains(&status), "{check}");
+        // Every problem comes with a remediation hint
+        assert_eq!(check.get("hint").is_some(), status != "ok", "{check}");
+    }
 
-    todo!("Implement test for BTF status in check output")
+    // Hard failures, and only hard failures, make the exit code non-zero
+    assert_eq!(doc["ok"], failed == 0);
+    assert_eq!(output.status.success(), failed == 0);
 }
 
 #[test]
 fn test_check_shows_permissions() {
-    // TODO: Test that check output includes permission/capability information
-    //
-    // Loading eBPF programs requires specific capabilities:
-    // - CAP_BPF (kernel 5.8+) for loading BPF programs
-    // - CAP_PERFMON for perf events and tracing
-    // - CAP_SYS_ADMIN (legacy, pre-5.8 kernels)
-    //
-    // The check command should report whether the current user has
-    // sufficient permissions to load and run eBPF programs.
-    //
-    // Hints:
-    // - Use is_root() helper to skip if not root
-    // - Use Command::cargo_bin("ebpf-tool")
-    // - Add arg: "check"
-    // - Assert stdout contains permission-related text
-    // - Look for "CAP_BPF", "CAP_SYS_ADMIN", "permission", or "root"
-    //
-    // Implementation:
-    // if !is_root() {
-    //     eprintln!("Skipping test_check_shows_permissions: requires root privileges");
-    //     return;
-    // }
-    //
-    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
-    // cmd.arg("check")
-    //    .assert()
-    //    .success()
-    //    .stdout(
-    //        predicate::str::contains("CAP_BPF")
-    //            .or(predicate::str::contains("CAP_SYS_ADMIN"))
-    //            .or(predicate::str::contains("ermission"))
-    //    );
+    if !is_root() {
+        eprintln!("Skipping test_check_shows_permissions: requires root privileges");
+        return;
+    }
 
-    todo!("Implement test for permissions in check output")
+    // Root has every capability, whatever else is wrong with the machine
+    let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    cmd.arg("check").assert().stdout(
+        predicate::str::is_match(r"Capabilities: +CAP_BPF CAP_PERFMON CAP_SYS_ADMIN \[OK\]")
+            .unwrap(),
+    );
 }
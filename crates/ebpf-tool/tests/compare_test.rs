@@ -0,0 +1,137 @@
+// Tests for run comparison (`compare <before> <after>`)
+// Lesson: docs/04-ebpf/39-compare-runs.md
+//
+// `compare` is already implemented, so these tests pass from the start.
+// They write reports by hand and point XDG_STATE_HOME at a temp dir, so
+// the reports of your own runs are never read or modified.
+//
+// Run with: cargo test -p ebpf-tool --test compare_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Write a report with the given top rows and p99 latency, the way
+/// `ebpf-tool` would, and return its path.
+fn write_report(
+    state_home: &Path,
+    id: &str,
+    command: &str,
+    top: &[(&str, u64)],
+    p99_ns: u64,
+) -> PathBuf {
+    let dir = state_home.join("ebpf-tool/runs");
+    std::fs::create_dir_all(&dir).unwrap();
+    let started_unix: u64 = id.split('-').next().unwrap().parse().unwrap();
+    let top: Vec<_> = top
+        .iter()
+        .map(|(label, count)| serde_json::json!({ "label": label, "count": count }))
+        .collect();
+    let report = serde_json::json!({
+        "id": id,
+        "command": command,
+        "args": ["ebpf-tool", command],
+        "started_unix": started_unix,
+        "duration_ms": 10000,
+        "programs": [],
+        "events": 5000,
+        "drops": 0,
+        "errors": [],
+        "top": top,
+        "latency": [{
+            "label": "request", "count": 1000,
+            "p50_ns": 400000, "p90_ns": 1800000, "p99_ns": p99_ns, "max_ns": 31000000
+        }],
+        "success": true
+    });
+    let path = dir.join(format!("{id}.json"));
+    std::fs::write(&path, report.to_string()).unwrap();
+    path
+}
+
+fn ebpf_tool(state_home: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    cmd.env("XDG_STATE_HOME", state_home);
+    cmd
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_compare_files_flags_regressions() {
+    let state = tempfile::tempdir().unwrap();
+    let before = write_report(
+        state.path(),
+        "1000-1-reqlat",
+        "reqlat",
+        &[("read", 1000)],
+        12_400_000,
+    );
+    let after = write_report(
+        state.path(),
+        "2000-1-reqlat",
+        "reqlat",
+        &[("read", 1020)],
+        18_100_000,
+    );
+
+    ebpf_tool(state.path())
+        .arg("compare")
+        .arg(&before)
+        .arg(&after)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("request p99"))
+        .stdout(predicate::str::contains("REGRESSION"))
+        .stdout(predicate::str::contains("1 regression(s) above 10%"));
+}
+
+#[test]
+fn test_compare_by_run_id_and_last() {
+    let state = tempfile::tempdir().unwrap();
+    write_report(state.path(), "1000-1-trace", "trace", &[("openat", 100)], 0);
+    write_report(state.path(), "2000-1-trace", "trace", &[("openat", 100)], 0);
+
+    ebpf_tool(state.path())
+        .args(["compare", "1000", "last"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("before: 1000-1-trace"))
+        .stdout(predicate::str::contains("after:  2000-1-trace"))
+        .stdout(predicate::str::contains("0 regression(s)"));
+}
+
+#[test]
+fn test_compare_threshold_and_check() {
+    let state = tempfile::tempdir().unwrap();
+    write_report(state.path(), "1000-1-trace", "trace", &[("read", 1000)], 0);
+    write_report(state.path(), "2000-1-trace", "trace", &[("read", 1080)], 0);
+
+    // +8%: below the default threshold, above --threshold 5
+    ebpf_tool(state.path())
+        .args(["compare", "1000", "2000", "--check"])
+        .assert()
+        .success();
+    ebpf_tool(state.path())
+        .args(["compare", "1000", "2000", "--check", "--threshold", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 regression(s) above 5%"));
+}
+
+#[test]
+fn test_compare_rejects_different_commands() {
+    let state = tempfile::tempdir().unwrap();
+    write_report(state.path(), "1000-1-trace", "trace", &[("read", 10)], 0);
+    write_report(state.path(), "2000-1-stats", "stats", &[("read", 10)], 0);
+
+    ebpf_tool(state.path())
+        .args(["compare", "1000", "2000"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "cannot compare a trace run with a stats run",
+        ));
+}
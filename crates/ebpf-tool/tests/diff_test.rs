@@ -0,0 +1,122 @@
+// Tests for `stats --diff` (the counts between two snapshots)
+// Lesson: docs/04-ebpf/49-stats-diff.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement the --diff hints of Command::Stats in src/lib.rs (GREEN)
+//
+// Comparing snapshots (interval::Diff) is already covered by unit tests:
+// cargo test -p ebpf-tool --lib interval
+//
+// NOTE: Root tests require CAP_BPF (or CAP_SYS_ADMIN).
+// Run with: sudo -E cargo test -p ebpf-tool --test diff_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_diff_in_help() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["stats", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--diff <SECS>"));
+}
+
+#[test]
+fn test_diff_rejects_zero() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["stats", "--diff", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value '0'"));
+}
+
+#[test]
+fn test_diff_conflicts() {
+    // One diff is one table: no refreshes, no series, no CPU columns
+    for other in [&["--watch"][..], &["--interval", "5"], &["--per-cpu"]] {
+        Command::cargo_bin("ebpf-tool")
+            .unwrap()
+            .args(["stats", "--diff", "2"])
+            .args(other)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_stats_diff_prints_one_table() {
+    // TODO: Verify that --diff prints a header and one table of changes
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Run `ebpf-tool stats --diff 1` while `dd if=/dev/zero of=/dev/null
+    //   bs=1 count=100000` runs
+    // - stdout has one "--- diff over 1." header and rows with "read" and
+    //   "write" at the top
+    // - The run ends by itself, after about 1 second plus loading
+
+    if !is_root() {
+        eprintln!("Skipping test_stats_diff_prints_one_table: requires root");
+        return;
+    }
+
+    todo!("Implement test for stats --diff")
+}
+
+#[test]
+fn test_stats_diff_on_pinned_map() {
+    // TODO: Verify that --diff shows only new counts of a pinned map
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - `ebpf-tool trace --pin --detach` in a temp pin dir, generate some
+    //   syscalls, wait 2 s
+    // - `ebpf-tool stats --pin <dir> --diff 1` with nothing running: the
+    //   COUNT column is far smaller than in `ebpf-tool stats --pin <dir>`
+    // - `ebpf-tool stop` at the end, even if an assertion failed
+
+    if !is_root() {
+        eprintln!("Skipping test_stats_diff_on_pinned_map: requires root");
+        return;
+    }
+
+    todo!("Implement test for stats --diff --pin")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_stats_diff_reports_evictions() {
+    // TODO: Verify that evictions between the snapshots are counted
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - `ebpf-tool stats --max-entries 16 --diff 2 --by pid` while a shell
+    //   loop starts many short processes
+    // - The header does not say "0 keys evicted"
+
+    if !is_root() {
+        eprintln!("Skipping test_stats_diff_reports_evictions: requires root");
+        return;
+    }
+
+    todo!("Implement test for evictions in a diff")
+}
@@ -0,0 +1,113 @@
+// Tests for `trace --process NAME --follow-children` (process tree filtering)
+// Lesson: docs/04-ebpf/44-follow-children.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement follow_fork, follow_exec, follow_exit and is_followed in
+//    crates/ebpf-tool-ebpf/src/follow.rs, and the "Process trees" hints of
+//    Command::Trace in src/lib.rs (GREEN)
+//
+// Seeding from /proc (src/follow.rs) is already covered by unit tests:
+// cargo test -p ebpf-tool --lib follow
+//
+// NOTE: Root tests require CAP_BPF and CAP_PERFMON (or root).
+// Run with: sudo -E cargo test -p ebpf-tool --test follow_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_follow_children_in_help() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["trace", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--follow-children"));
+}
+
+#[test]
+fn test_follow_children_requires_process() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["trace", "--follow-children"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--process <PATTERN>"));
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_follow_children_traces_descendants() {
+    // TODO: Verify that children of a matched process are traced, whatever
+    // their name
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Start `sh -c 'sleep 1; cat /etc/hostname; ls /'` and, right away,
+    //   `ebpf-tool trace -p sh --follow-children -d 3`
+    // - stdout has lines for "cat(" and "ls(", with pids that are not the
+    //   shell's
+    // - Without --follow-children, the same run has no "cat(" lines
+
+    if !is_root() {
+        eprintln!("Skipping test_follow_children_traces_descendants: requires root");
+        return;
+    }
+
+    todo!("Implement test for --follow-children")
+}
+
+#[test]
+fn test_follow_children_seeds_running_tree() {
+    // TODO: Verify that a tree started before the trace is followed
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Spawn `bash -c 'sleep 30 & while :; do cat /dev/null; sleep 0.2; done'`
+    //   and wait 0.5 s, so the tree exists before tracing starts
+    // - `ebpf-tool trace -p bash --follow-children -d 2` shows "cat(" lines:
+    //   the /proc seed found bash, the fork program added each cat
+    // - Kill the bash process group afterwards
+
+    if !is_root() {
+        eprintln!("Skipping test_follow_children_seeds_running_tree: requires root");
+        return;
+    }
+
+    todo!("Implement test for seeding from /proc")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_follow_children_forgets_exited() {
+    // TODO: Verify that exited processes leave FOLLOW_PIDS
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - RUST_LOG=info `ebpf-tool trace -p sh --follow-children -d 4` while
+    //   `sh -c 'for i in $(seq 200); do /bin/true; done'` runs its 200
+    //   children and exits
+    // - The log line at the end reports the FOLLOW_PIDS entries left: far
+    //   fewer than 200, the children are gone
+
+    if !is_root() {
+        eprintln!("Skipping test_follow_children_forgets_exited: requires root");
+        return;
+    }
+
+    todo!("Implement test for exit cleanup")
+}
@@ -0,0 +1,104 @@
+// Tests for the `lsm` subcommand
+// Lesson: docs/04-ebpf/11-lsm.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs and ebpf-tool-ebpf/src/lsm.rs (GREEN)
+//
+// The `lsm` subcommand attaches a BPF LSM program to the file_open hook and
+// denies opens of the files given with --deny-path.
+//
+// Usage: ebpf-tool lsm --deny-path <path> [--deny-path <path>...] [-d duration]
+//
+// NOTE: Root tests also need a kernel booted with "bpf" in lsm=
+// (check with `cat /sys/kernel/security/lsm`). They skip otherwise.
+// Run with: sudo -E cargo test -p ebpf-tool --test lsm_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+/// Helper to check if the BPF LSM is active on this kernel.
+fn bpf_lsm_enabled() -> bool {
+    std::fs::read_to_string("/sys/kernel/security/lsm")
+        .map(|lsms| lsms.trim().split(',').any(|lsm| lsm == "bpf"))
+        .unwrap_or(false)
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_lsm_help() {
+    // TODO: Verify that `ebpf-tool lsm --help` shows usage information
+    //
+    // Hints:
+    // - Use Command::cargo_bin("ebpf-tool")
+    // - Add args: ["lsm", "--help"]
+    // - Check stdout contains "--deny-path" and "--duration"
+
+    todo!("Implement test for lsm help text")
+}
+
+#[test]
+fn test_lsm_requires_deny_path() {
+    // TODO: Verify that `ebpf-tool lsm` without --deny-path is rejected
+    //
+    // Hints:
+    // - Run `ebpf-tool lsm` with no other arguments
+    // - Assert failure (clap exits with code 2)
+    // - stderr should mention "--deny-path"
+
+    todo!("Implement test for missing --deny-path")
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_lsm_denies_open() {
+    // TODO: Verify that opening a denied file fails with EPERM
+    //
+    // Hints:
+    // - Skip if !is_root() || !bpf_lsm_enabled()
+    // - Create a temp file, e.g. /tmp/ebpf-lsm-test, with some content
+    // - Spawn `ebpf-tool lsm --deny-path /tmp/ebpf-lsm-test -d 3`
+    // - Sleep ~1s for the program to attach
+    // - `cat /tmp/ebpf-lsm-test` should fail and stderr should contain
+    //   "Operation not permitted" (even as root: LSMs apply to everyone)
+    // - The ebpf-tool stdout should contain "[DENY]" and "comm=cat"
+
+    if !is_root() || !bpf_lsm_enabled() {
+        eprintln!("Skipping test_lsm_denies_open: requires root and bpf in lsm=");
+        return;
+    }
+
+    todo!("Implement test that a denied open fails")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_lsm_allows_other_files() {
+    // TODO: Verify that files not on the deny list still open normally
+    //
+    // Hints:
+    // - Same setup as test_lsm_denies_open
+    // - `cat /etc/hostname` should succeed while the program is attached
+    // - After ebpf-tool exits, the denied file should open again
+    //   (the program detaches when its file descriptor is closed)
+
+    if !is_root() || !bpf_lsm_enabled() {
+        eprintln!("Skipping test_lsm_allows_other_files: requires root and bpf in lsm=");
+        return;
+    }
+
+    todo!("Implement test that other files are unaffected")
+}
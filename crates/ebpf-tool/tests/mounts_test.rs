@@ -0,0 +1,107 @@
+// Tests for the `mounts` subcommand (mount events per mount namespace)
+// Lesson: docs/04-ebpf/17-mount-events.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs and crates/ebpf-tool-ebpf/src/mounts.rs (GREEN)
+//
+// The root tests make their mounts inside `unshare -m`, so nothing leaks
+// into the host's mount table even if a test fails halfway.
+//
+// NOTE: Root tests require CAP_BPF (or CAP_SYS_ADMIN).
+// Run with: sudo -E cargo test -p ebpf-tool --test mounts_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_mounts_help() {
+    // TODO: Verify that the mounts subcommand documents its options
+    //
+    // Hints:
+    // - Run `ebpf-tool mounts --help`
+    // - Assert success; stdout should mention "--follow", "--mntns",
+    //   "--failed" and "--duration"
+
+    todo!("Implement test for mounts --help")
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_mounts_in_new_namespace() {
+    // TODO: Verify that a mount in a new mount namespace is reported with
+    // that namespace, not the host's
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Spawn `ebpf-tool mounts --follow` and wait ~500ms for it to attach
+    // - Run `unshare -m sh -c 'mount -t tmpfs none /mnt; readlink /proc/self/ns/mnt'`
+    //   and parse the inode from its output ("mnt:[4026532205]")
+    // - Kill the tracer and read its stdout
+    // - Assert a "[MOUNT ]" line with "/mnt", "type=tmpfs" and
+    //   "mntns=<inode>", and that the inode differs from
+    //   readlink /proc/1/ns/mnt
+    // - newer util-linux mounts with move_mount: accept "[MOVE  ]" for /mnt too
+
+    if !is_root() {
+        eprintln!("Skipping test_mounts_in_new_namespace: requires root");
+        return;
+    }
+
+    todo!("Implement test for mounts in a new namespace")
+}
+
+#[test]
+fn test_mounts_failed_call() {
+    // TODO: Verify that --failed shows calls that returned an error
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Spawn `ebpf-tool mounts --follow --failed`
+    // - Run `unshare -m sh -c 'mount -t tmpfs none /does-not-exist'`
+    //   (expect it to fail)
+    // - Assert a line with "/does-not-exist" and "ret=-2 (ENOENT)"
+    // - Without --failed, the same call should not be printed
+
+    if !is_root() {
+        eprintln!("Skipping test_mounts_failed_call: requires root");
+        return;
+    }
+
+    todo!("Implement test for failed mount calls")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_mounts_umount_flags() {
+    // TODO: Verify that umount2 flags are decoded and --mntns filters
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Start `unshare -m sh -c 'mount -t tmpfs none /mnt; sleep 2; umount -l /mnt'`
+    //   and read its mount namespace from /proc/<pid>/ns/mnt
+    // - Spawn `ebpf-tool mounts --follow --mntns <inode>`
+    // - Assert an "[UMOUNT]" line with "/mnt" and "flags=detach"
+    // - Meanwhile mount and unmount a tmpfs in a second `unshare -m`, and
+    //   assert none of its lines show up
+
+    if !is_root() {
+        eprintln!("Skipping test_mounts_umount_flags: requires root");
+        return;
+    }
+
+    todo!("Implement test for umount flags and the mntns filter")
+}
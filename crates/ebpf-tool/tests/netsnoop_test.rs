@@ -0,0 +1,121 @@
+// Tests for the `netsnoop` subcommand (UDP and ICMP per network namespace)
+// Lesson: docs/04-ebpf/32-netsnoop.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs and crates/ebpf-tool-ebpf/src/netsnoop.rs (GREEN)
+//
+// The root tests build their own two-namespace topology with `ip netns`
+// and `ip link add ... type veth`, under names starting with "ebpf-test-",
+// and delete it at the end.
+//
+// NOTE: Root tests require CAP_BPF (or CAP_SYS_ADMIN) and CAP_NET_ADMIN.
+// Run with: sudo -E cargo test -p ebpf-tool --test netsnoop_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_netsnoop_help() {
+    // TODO: Verify that the netsnoop subcommand documents its options
+    //
+    // Hints:
+    // - Run `ebpf-tool netsnoop --help`
+    // - Assert success; stdout should mention "--netns", "--proto",
+    //   "udp", "icmp" and "--duration"
+
+    todo!("Implement test for netsnoop --help")
+}
+
+#[test]
+fn test_netsnoop_unknown_netns() {
+    // TODO: Verify that an unknown namespace name fails before loading
+    //
+    // Hints:
+    // - Run `ebpf-tool netsnoop --netns ebpf-test-does-not-exist`
+    // - Assert failure; stderr should contain "no network namespace"
+    // - Works without root: resolve_netns() runs before any BPF call
+
+    todo!("Implement test for an unknown --netns")
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_netsnoop_udp_between_namespaces() {
+    // TODO: Verify that a datagram between two namespaces is reported once
+    // per side, each with its own namespace
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - ip netns add ebpf-test-a / ebpf-test-b, a veth pair between them,
+    //   10.99.0.1/24 and 10.99.0.2/24, links up
+    // - Spawn `ebpf-tool netsnoop --proto udp -d 3` and wait ~500ms
+    // - `ip netns exec ebpf-test-b timeout 2 nc -u -l 9000` in the
+    //   background, then `ip netns exec ebpf-test-a sh -c 'echo hi | nc -u -w1 10.99.0.2 9000'`
+    // - Assert a "[UDP  >]" line with "(ebpf-test-a)" and "10.99.0.2:9000",
+    //   and a "[UDP  <]" line with "(ebpf-test-b)"; both with "len=3"
+    // - Delete both namespaces (the veth goes with them)
+
+    if !is_root() {
+        eprintln!("Skipping test_netsnoop_udp_between_namespaces: requires root");
+        return;
+    }
+
+    todo!("Implement test for UDP between two namespaces")
+}
+
+#[test]
+fn test_netsnoop_netns_filter() {
+    // TODO: Verify that --netns hides traffic of other namespaces
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Same topology as above
+    // - Spawn `ebpf-tool netsnoop --netns ebpf-test-a -d 3`
+    // - `ip netns exec ebpf-test-a ping -c1 10.99.0.2`, and some UDP on the
+    //   host (e.g. a DNS lookup, or `echo x | nc -u -w1 127.0.0.1 9`)
+    // - Every event line contains "(ebpf-test-a)"; none contains "(host)"
+    // - The ping shows up as "[ICMP >]" echo-request and "[ICMP <]" echo-reply
+
+    if !is_root() {
+        eprintln!("Skipping test_netsnoop_netns_filter: requires root");
+        return;
+    }
+
+    todo!("Implement test for the --netns filter")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_netsnoop_icmp_unreachable() {
+    // TODO: Verify that kernel-generated ICMP errors are attributed to the
+    // namespace that sent them
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Same topology; nothing listens on UDP 9999 in ebpf-test-b
+    // - Spawn `ebpf-tool netsnoop --proto icmp -d 3`
+    // - `ip netns exec ebpf-test-a sh -c 'echo x | nc -u -w1 10.99.0.2 9999'`
+    // - Assert "[ICMP >]" ... "(ebpf-test-b)" ... "unreachable" with
+    //   "pid=-" (sent from softirq), and "[ICMP <]" ... "(ebpf-test-a)"
+
+    if !is_root() {
+        eprintln!("Skipping test_netsnoop_icmp_unreachable: requires root");
+        return;
+    }
+
+    todo!("Implement test for ICMP port unreachable")
+}
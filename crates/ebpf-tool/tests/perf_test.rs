@@ -3,13 +3,13 @@
 //
 // TDD Workflow:
 // 1. Write tests below FIRST (RED)
-// 2. Implement code in src/main.rs and ebpf-tool-ebpf/src/perf.rs (GREEN)
+// 2. Implement code in src/lib.rs and ebpf-tool-ebpf/src/perf.rs (GREEN)
 //
 // NOTE: Most tests require root privileges for eBPF operations.
 // Run with: sudo -E cargo test -p ebpf-tool
 //
 // The `perf` subcommand provides CPU performance sampling using eBPF perf events.
-// Usage: ebpf-tool perf [-f frequency] [-d duration]
+// Usage: ebpf-tool perf [-f frequency] [-e cpu|faults] [-d duration]
 
 use assert_cmd::Command;
 use predicates::prelude::*;
@@ -206,3 +206,96 @@ fn test_perf_samples_all_cpus() {
 
     todo!("Implement test for multi-CPU sampling")
 }
+
+#[test]
+fn test_perf_event_faults_help() {
+    // TODO: Verify that `ebpf-tool perf --help` documents the faults mode
+    //
+    // This test does NOT require root - it only checks help text.
+    //
+    // Hints:
+    // - Help should list --event with the possible values "cpu" and "faults"
+    // - The default should stay cpu so existing usage is unchanged
+    //
+    // Implementation:
+    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    // cmd.args(["perf", "--help"])
+    //    .assert()
+    //    .success()
+    //    .stdout(predicate::str::contains("--event"))
+    //    .stdout(predicate::str::contains("faults"));
+
+    todo!("Implement test for --event faults help text")
+}
+
+#[test]
+fn test_perf_faults_attributes_to_process() {
+    // TODO: Verify that page faults are attributed to the faulting process
+    //
+    // REQUIRES ROOT: attaching to software perf events needs CAP_PERFMON
+    //
+    // Hints:
+    // - Skip test if not running as root
+    // - Spawn a child that touches fresh memory, e.g.
+    //   `dd if=/dev/zero of=/dev/null bs=64M count=20` (each new buffer page
+    //   is a minor fault)
+    // - Run `ebpf-tool perf -e faults -d 2` while the child runs
+    // - Assert the output has MAJOR/MINOR columns and a row for the child's PID
+    //
+    // Implementation:
+    // if !is_root() {
+    //     eprintln!("Skipping test_perf_faults_attributes_to_process: requires root");
+    //     return;
+    // }
+    // let mut child = std::process::Command::new("sh")
+    //     .args(["-c", "while true; do dd if=/dev/zero of=/dev/null bs=64M count=20 2>/dev/null; done"])
+    //     .spawn()
+    //     .unwrap();
+    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    // let assert = cmd.args(["perf", "-e", "faults", "-d", "2"]).assert().success();
+    // child.kill().unwrap();
+    // assert.stdout(predicate::str::contains("MINOR"))
+    //       .stdout(predicate::str::contains("dd"));
+
+    todo!("Implement test for page fault attribution")
+}
+
+#[test]
+fn test_perf_faults_fills_run_report() {
+    // TODO: Verify that a fault run records per-process totals in its report
+    //
+    // REQUIRES ROOT: attaching to software perf events needs CAP_PERFMON
+    //
+    // Hints:
+    // - Skip test if not running as root
+    // - Point XDG_STATE_HOME at a temp dir, so your own reports are untouched
+    // - Run `ebpf-tool perf -e faults -d 2` while a `dd` child faults, as in
+    //   test_perf_faults_attributes_to_process
+    // - `ebpf-tool runs show last` prints the report as JSON: its "top" has
+    //   a "minor dd" row (fault_top_entries() labels rows by comm)
+    //
+    // Implementation:
+    // if !is_root() {
+    //     eprintln!("Skipping test_perf_faults_fills_run_report: requires root");
+    //     return;
+    // }
+    // let state = tempfile::tempdir().unwrap();
+    // let mut child = std::process::Command::new("sh")
+    //     .args(["-c", "while true; do dd if=/dev/zero of=/dev/null bs=64M count=20 2>/dev/null; done"])
+    //     .spawn()
+    //     .unwrap();
+    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    // cmd.env("XDG_STATE_HOME", state.path())
+    //     .args(["perf", "-e", "faults", "-d", "2"])
+    //     .assert()
+    //     .success();
+    // child.kill().unwrap();
+    // let mut cmd = Command::cargo_bin("ebpf-tool").unwrap();
+    // cmd.env("XDG_STATE_HOME", state.path())
+    //     .args(["runs", "show", "last"])
+    //     .assert()
+    //     .success()
+    //     .stdout(predicate::str::contains("\"label\": \"minor dd\""));
+
+    todo!("Implement test for fault rows in the run report")
+}
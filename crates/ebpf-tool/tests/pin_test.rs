@@ -0,0 +1,105 @@
+// Tests for map pinning (`trace --pin --detach`, `stats --pin`, `unpin`)
+// Lesson: docs/04-ebpf/03-maps.md ("Pinning Maps")
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs (GREEN)
+//
+// Pinning keeps a map (and, through a pinned link, its program) alive in
+// the kernel after ebpf-tool exits. A detached `trace` session keeps
+// counting, and later `stats --pin` runs read the same counters.
+//
+// NOTE: Root tests need bpffs mounted at /sys/fs/bpf. Each test uses its
+// own pin directory so tests can run in parallel.
+// Run with: sudo -E cargo test -p ebpf-tool --test pin_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_trace_help_shows_pin_and_detach() {
+    // TODO: Verify that `trace --help` documents the pinning flags
+    //
+    // Hints:
+    // - Run `ebpf-tool trace --help`
+    // - Assert success; stdout should contain "--pin" and "--detach"
+    // - `unpin --help` should show the default dir /sys/fs/bpf/ebpf-tool
+
+    todo!("Implement test for the pinning flags in --help")
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_detached_trace_keeps_counting() {
+    // TODO: Verify that counters survive the trace process and keep growing
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Pin dir: /sys/fs/bpf/ebpf-tool-test-detach
+    // - `ebpf-tool trace --pin <dir> --detach` must exit quickly with success
+    // - <dir>/maps/SYSCALL_COUNTS must exist
+    // - Run `stats --pin <dir>` twice with some syscalls in between
+    //   (e.g., `cat /etc/hostname` a few times); the second total must be
+    //   larger than the first
+    // - Always run `unpin <dir>` at the end, even if an assertion failed
+
+    if !is_root() {
+        eprintln!("Skipping test_detached_trace_keeps_counting: requires root");
+        return;
+    }
+
+    todo!("Implement test for a detached, pinned trace session")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_unpin_detaches_programs() {
+    // TODO: Verify that unpin removes the pins and detaches the programs
+    //
+    // Hints:
+    // - Start a detached session in /sys/fs/bpf/ebpf-tool-test-unpin
+    // - Note the program ids from `bpftool prog list` (name syscall_kprobe)
+    // - Run `ebpf-tool unpin <dir>` and assert success
+    // - The directory must be gone, and the program ids must no longer be
+    //   listed (the kernel frees them once the last link is closed)
+    // - A second `unpin <dir>` must still succeed ("nothing pinned")
+
+    if !is_root() {
+        eprintln!("Skipping test_unpin_detaches_programs: requires root");
+        return;
+    }
+
+    todo!("Implement test for unpin")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_pin_rejects_non_bpffs_dir() {
+    // TODO: Verify a clear error when the pin directory is not on bpffs
+    //
+    // Hints:
+    // - Run `ebpf-tool trace --pin /tmp/not-bpffs --detach`
+    // - Assert failure; stderr should mention "bpffs" or "mount -t bpf"
+    // - `ebpf-tool unpin /tmp` must also refuse, so it can never delete
+    //   files outside bpffs
+
+    if !is_root() {
+        eprintln!("Skipping test_pin_rejects_non_bpffs_dir: requires root");
+        return;
+    }
+
+    todo!("Implement test for non-bpffs pin directories")
+}
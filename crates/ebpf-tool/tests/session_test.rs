@@ -0,0 +1,105 @@
+// Tests for detached tracing sessions (`trace --detach`, `status`, `stop`)
+// Lesson: docs/04-ebpf/13-detached-sessions.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs (GREEN)
+//
+// A detached session outlives the ebpf-tool process: its links and maps are
+// pinned to bpffs and its settings are recorded in /run/ebpf-tool/<name>.
+//
+// NOTE: Root tests use their own --pin directory so they do not stop a
+// session you started by hand. Always `stop` at the end of a test, even
+// when an assertion fails, or the probes keep running until reboot.
+// Run with: sudo -E cargo test -p ebpf-tool --test session_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_status_and_stop_help() {
+    // TODO: Verify that `status --help` and `stop --help` document --pin
+    //
+    // Hints:
+    // - Run `ebpf-tool status --help` and `ebpf-tool stop --help`
+    // - Assert success; stdout should contain "--pin" and the default
+    //   directory /sys/fs/bpf/ebpf-tool
+
+    todo!("Implement test for status/stop --help")
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_detach_status_stop() {
+    // TODO: Verify the whole session lifecycle
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - PIN=/sys/fs/bpf/ebpf-tool-test-session
+    // - `trace --detach --pin $PIN -p cat` exits 0 within a second or two
+    // - `status --pin $PIN` shows "running", "process=cat" and "[attached]"
+    // - `stop --pin $PIN` succeeds; afterwards $PIN and
+    //   /run/ebpf-tool/ebpf-tool-test-session are gone
+    // - `status --pin $PIN` now prints "No detached session"
+
+    if !is_root() {
+        eprintln!("Skipping test_detach_status_stop: requires root");
+        return;
+    }
+
+    todo!("Implement test for detach, status and stop")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_second_detach_is_rejected() {
+    // TODO: Verify that a running session is not started twice
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Start a detached session in /sys/fs/bpf/ebpf-tool-test-twice
+    // - A second `trace --detach --pin <same dir>` must fail, and stderr
+    //   should mention "already running"
+    // - `stop` once, and `stop` again must fail with "no detached session"
+
+    if !is_root() {
+        eprintln!("Skipping test_second_detach_is_rejected: requires root");
+        return;
+    }
+
+    todo!("Implement test for duplicate sessions")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_status_reports_stale_session() {
+    // TODO: Verify that status notices pins removed behind its back
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Start a detached session in /sys/fs/bpf/ebpf-tool-test-stale
+    // - Remove only the link pins by hand: rm <dir>/links/*
+    //   (the programs detach, the map pin and the session file remain)
+    // - `status --pin <dir>` should say "stale" instead of "running"
+    // - `stop --pin <dir>` must still succeed and remove the session file
+
+    if !is_root() {
+        eprintln!("Skipping test_status_reports_stale_session: requires root");
+        return;
+    }
+
+    todo!("Implement test for stale sessions")
+}
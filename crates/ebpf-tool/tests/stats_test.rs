@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write tests below FIRST (RED)
-// 2. Implement code in src/main.rs (GREEN)
+// 2. Implement code in src/lib.rs (GREEN)
 //
 // The `stats` subcommand displays eBPF map statistics from a HashMap that
 // tracks syscall counts. It reads the SYSCALL_COUNTS map and presents the
@@ -201,3 +201,153 @@ fn test_stats_after_workload() {
 
     todo!("Implement test that verifies counts increase after syscall activity")
 }
+
+// =============================================================================
+// Per-process breakdown (Lesson 03 "Per-Process Breakdown")
+// =============================================================================
+
+#[test]
+fn test_stats_by_rejects_unknown_group() {
+    // TODO: Verify that --by only accepts pid, syscall and comm
+    //
+    // This test does NOT require root: clap rejects the value before
+    // anything is loaded.
+    //
+    // Hints:
+    // - Run `ebpf-tool stats --by cpu`
+    // - Assert failure, and stderr lists the possible values
+    //   (predicate::str::contains("pid").and(contains("comm")))
+
+    todo!("Implement test for --by validation")
+}
+
+#[test]
+fn test_stats_by_pid_shows_own_process() {
+    // TODO: Verify that --by pid attributes syscalls to the right process
+    //
+    // This test REQUIRES root to load eBPF programs and access maps.
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Start a busy child first: `sh -c 'while true; do cat /etc/hostname; done'`
+    // - Run `ebpf-tool stats --by pid -n 20`
+    // - The header contains "PID" and "/SEC", and one row starts with the
+    //   child's PID (child.id())
+    // - Kill the child at the end, even if the assertion fails
+
+    if !is_root() {
+        eprintln!("Skipping test_stats_by_pid_shows_own_process: requires root");
+        return;
+    }
+
+    todo!("Implement test for --by pid")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_stats_top_limits_rows() {
+    // TODO: Verify that -n limits the number of rows
+    //
+    // Hints:
+    // - Run `ebpf-tool stats --by syscall -n 3`
+    // - Count the lines after the header: at most 3
+
+    todo!("Implement test for --top")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_stats_watch_refreshes() {
+    // TODO: Verify that --watch keeps printing tables until interrupted
+    //
+    // Hints:
+    // - Spawn `ebpf-tool stats --watch` with std::process::Command and
+    //   stdout piped; read for ~3 seconds
+    // - Expect at least two table headers in the output
+    // - Stop it with SIGINT (nix::sys::signal::kill) and assert it exits 0
+
+    todo!("Implement test for --watch")
+}
+
+// =============================================================================
+// Per-CPU counters (Lesson 03 "Per-CPU Counters")
+// =============================================================================
+
+#[test]
+fn test_stats_per_cpu_total_matches_sum() {
+    // TODO: Verify that --per-cpu shows one column per CPU that adds up to COUNT
+    //
+    // This test REQUIRES root to load eBPF programs and access maps.
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Run `ebpf-tool stats --per-cpu -n 5`
+    // - The header contains "COUNT" and at least one "CPU<n>" column
+    // - For every row: COUNT == sum of the CPU columns (the /SEC column is
+    //   not a CPU column; split_whitespace() and skip label, COUNT, /SEC)
+
+    if !is_root() {
+        eprintln!("Skipping test_stats_per_cpu_total_matches_sum: requires root");
+        return;
+    }
+
+    todo!("Implement test for --per-cpu")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_stats_per_cpu_spreads_across_cpus() {
+    // TODO: Verify that a workload running on two CPUs shows up on both
+    //
+    // Hints:
+    // - Needs at least 2 CPUs (std::thread::available_parallelism())
+    // - Start two busy children pinned with `taskset -c 0` and `taskset -c 1`
+    //   (e.g. `taskset -c 0 sh -c 'while true; do cat /etc/hostname; done'`)
+    // - Run `ebpf-tool stats --per-cpu --by comm -n 20`
+    // - The "cat" row has non-zero counts in both the CPU0 and CPU1 columns
+    // - Kill the children at the end
+
+    todo!("Implement test for per-CPU distribution")
+}
+
+// =============================================================================
+// Map occupancy (Lesson 03 "When the Map Fills Up")
+// =============================================================================
+
+#[test]
+fn test_stats_shows_map_occupancy() {
+    // TODO: Verify that the footer reports how full SYSCALL_COUNTS is
+    //
+    // This test REQUIRES root to load eBPF programs and access maps.
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Run `ebpf-tool stats -n 5`
+    // - The last line starts with "SYSCALL_COUNTS:" and contains
+    //   "/10240 entries" (MAX_MAP_ENTRIES) and "0 evicted"
+    // - Nothing on stderr starts with "warning:"
+
+    if !is_root() {
+        eprintln!("Skipping test_stats_shows_map_occupancy: requires root");
+        return;
+    }
+
+    todo!("Implement test for the occupancy footer")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_stats_small_map_warns_on_eviction() {
+    // TODO: Verify that a map that is too small warns instead of silently
+    // dropping counts
+    //
+    // Hints:
+    // - Run `ebpf-tool stats --by pid --max-entries 16`
+    // - While it collects, start ~50 short-lived processes
+    //   (e.g. `for i in $(seq 50); do /bin/true; done` with sh -c)
+    // - The footer shows "16/16 entries" and a non-zero "evicted" count
+    // - stderr contains "warning: SYSCALL_COUNTS is full" exactly once
+    // - `--max-entries` with `--pin` is rejected by clap (exit code 2)
+
+    todo!("Implement test for eviction warnings")
+}
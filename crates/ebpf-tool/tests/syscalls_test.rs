@@ -0,0 +1,51 @@
+// Tests for the syscall table (build-time generation and the override file)
+// Lesson: docs/04-ebpf/26-syscall-tables.md
+//
+// The table itself is generated by crates/ebpf-tool-common/build.rs and is
+// covered by unit tests: cargo test -p ebpf-tool-common test_syscall_table
+// and cargo test -p ebpf-tool --lib syscalls
+//
+// These tests only run `check`, so they need no privileges.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::io::Write;
+
+/// Run `ebpf-tool check` with `contents` as the override file.
+fn check_with_override(contents: &str) -> assert_cmd::assert::Assert {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(contents.as_bytes()).unwrap();
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .arg("check")
+        .env("EBPF_TOOL_SYSCALLS", file.path())
+        .assert()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_check_reports_table_source() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .arg("check")
+        .env_remove("EBPF_TOOL_SYSCALLS")
+        .assert()
+        .stdout(predicate::str::is_match(r"Syscall table:\s+\d+ syscalls \(").unwrap());
+}
+
+#[test]
+fn test_override_file_is_applied() {
+    check_with_override("# a newer kernel\n467 open_tree_attr\n")
+        .stdout(predicate::str::contains("syscalls ("))
+        .stdout(predicate::str::contains(" + /"));
+}
+
+#[test]
+fn test_broken_override_is_a_warning() {
+    check_with_override("467 open_tree_attr\nnot-a-number read\n")
+        .stdout(predicate::str::contains("line 2"))
+        .stdout(predicate::str::contains("unset EBPF_TOOL_SYSCALLS"));
+}
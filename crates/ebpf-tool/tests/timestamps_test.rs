@@ -0,0 +1,112 @@
+// Tests for `--timestamps wall|mono|relative` on trace and replay
+// Lesson: docs/04-ebpf/27-timestamps.md
+//
+// TDD Workflow:
+// 1. Write tests below FIRST (RED)
+// 2. Implement code in src/lib.rs (GREEN)
+//
+// The conversion itself (src/clock.rs) is already covered by unit tests:
+// cargo test -p ebpf-tool --lib clock
+//
+// NOTE: Root tests require CAP_BPF (or CAP_SYS_ADMIN).
+// Run with: sudo -E cargo test -p ebpf-tool --test timestamps_test
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to check if running as root.
+/// Tests that require root will skip if this returns false.
+fn is_root() -> bool {
+    nix::unistd::Uid::effective().is_root()
+}
+
+// =============================================================================
+// Non-root tests (can run without privileges)
+// =============================================================================
+
+#[test]
+fn test_timestamps_flag_documented() {
+    for cmd in ["trace", "replay"] {
+        Command::cargo_bin("ebpf-tool")
+            .unwrap()
+            .args([cmd, "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("--timestamps"))
+            .stdout(predicate::str::contains("wall"))
+            .stdout(predicate::str::contains("relative"));
+    }
+}
+
+#[test]
+fn test_timestamps_rejects_unknown_mode() {
+    Command::cargo_bin("ebpf-tool")
+        .unwrap()
+        .args(["trace", "--timestamps", "utc"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'utc'"));
+}
+
+// =============================================================================
+// Root-required tests
+// =============================================================================
+
+#[test]
+fn test_trace_wall_timestamps() {
+    // TODO: Verify that the default timestamps are the local time of day
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - Note the time (`date +%H:%M`), then run `ebpf-tool trace -s openat -d 2`
+    //   and `cat /etc/hostname` while it runs
+    // - Every event line starts with "[HH:MM:SS.mmm]" and its HH:MM is the
+    //   noted time or the minute after it, never the time since boot
+
+    if !is_root() {
+        eprintln!("Skipping test_trace_wall_timestamps: requires root");
+        return;
+    }
+
+    todo!("Implement test for wall-clock timestamps")
+}
+
+#[test]
+fn test_trace_mono_and_relative_timestamps() {
+    // TODO: Verify the other two modes
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - --timestamps mono: "[<seconds>.<6 digits>]", close to the first
+    //   number in /proc/uptime (both count from boot)
+    // - --timestamps relative: "[+0.<6 digits>]" for the first events, and
+    //   never more than the --duration
+
+    if !is_root() {
+        eprintln!("Skipping test_trace_mono_and_relative_timestamps: requires root");
+        return;
+    }
+
+    todo!("Implement test for mono and relative timestamps")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_replay_uses_recording_clock() {
+    // TODO: Verify that replay prints the wall clock of the recording
+    //
+    // Hints:
+    // - Skip if !is_root()
+    // - `ebpf-tool trace --record FILE -d 2`, then `ebpf-tool replay FILE`
+    //   twice, a minute apart: the timestamps are the same both times
+    // - `replay --info FILE` shows the header's realtime_offset_ns
+    // - With realtime_offset_ns removed from the header (an old capture),
+    //   replay warns and prints relative timestamps
+
+    if !is_root() {
+        eprintln!("Skipping test_replay_uses_recording_clock: requires root");
+        return;
+    }
+
+    todo!("Implement test for replay timestamps")
+}
@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write tests below FIRST (RED)
-// 2. Implement code in src/main.rs and ebpf-tool-ebpf/src/tracepoint.rs (GREEN)
+// 2. Implement code in src/lib.rs and ebpf-tool-ebpf/src/tracepoint.rs (GREEN)
 //
 // Tracepoints are stable kernel instrumentation points defined in the kernel source.
 // Unlike kprobes, tracepoints have a stable ABI and are less likely to break across
@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write tests below FIRST (RED)
-// 2. Implement code in src/main.rs and ebpf-tool-ebpf/src/uprobe.rs (GREEN)
+// 2. Implement code in src/lib.rs and ebpf-tool-ebpf/src/uprobe.rs (GREEN)
 //
 // Uprobes allow tracing userspace functions in binaries and shared libraries.
 // Unlike kprobes (kernel functions), uprobes attach to functions in ELF binaries.
@@ -0,0 +1,196 @@
+// isolation - every lesson tool in one binary
+//
+// For classrooms: install one artifact instead of five. Each tool is a
+// subcommand, and everything after the tool name is handed to the tool
+// unchanged:
+//
+//   isolation ns pid                 = ns-tool pid
+//   isolation netns create lab       = netns-tool create lab
+//   isolation cgroup memory-max ...  = cgroup-tool memory-max ...
+//   isolation oci init ./bundle      = oci-tool init ./bundle
+//   isolation ebpf check             = ebpf-tool check
+//
+// Global flags go before the tool name. Each tool has its own spelling for
+// them (see TOOLS), and a tool without the mode ignores the flag with a
+// warning:
+//
+//   isolation --json ns pid          = ns-tool --verify pid
+//   isolation --verbose ebpf trace   = ebpf-tool --verbose trace
+//
+// It is also a multi-call binary: run through a symlink named after a tool,
+// it is that tool, so the commands in the lessons work as written:
+//
+//   ln -s isolation ~/.local/bin/ns-tool
+//   ns-tool pid                      = isolation ns pid
+//
+// The tools are linked in through their library entry points,
+// `<tool>::run(<tool>::Cli)`, which is all their own main.rs calls too.
+
+use anyhow::{bail, Result};
+use clap::{Args, Parser, Subcommand};
+use std::ffi::OsString;
+use std::path::Path;
+
+#[derive(Parser)]
+#[command(name = "isolation")]
+#[command(version)]
+#[command(about = "Linux isolation lesson tools in one binary")]
+#[command(
+    after_help = "Run `isolation <TOOL> --help` for the tool's own commands.\n\
+    A symlink named after a tool (e.g. ns-tool -> isolation) runs that tool directly."
+)]
+struct Cli {
+    /// Machine-readable output, where the tool has it (ns: --verify)
+    #[arg(long)]
+    json: bool,
+
+    /// More detail on stderr, where the tool has it (ebpf: --verbose)
+    #[arg(short, long)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    tool: Tool,
+}
+
+#[derive(Subcommand)]
+enum Tool {
+    /// Namespaces (ns-tool)
+    #[command(alias = "ns-tool", disable_help_flag = true)]
+    Ns(ToolArgs),
+
+    /// Network namespaces, veth pairs, bridges and NAT (netns-tool)
+    #[command(alias = "netns-tool", disable_help_flag = true)]
+    Netns(ToolArgs),
+
+    /// Cgroup v2 limits and accounting (cgroup-tool)
+    #[command(alias = "cgroup-tool", disable_help_flag = true)]
+    Cgroup(ToolArgs),
+
+    /// OCI bundles for runc (oci-tool)
+    #[command(alias = "oci-tool", disable_help_flag = true)]
+    Oci(ToolArgs),
+
+    /// eBPF tracing (ebpf-tool)
+    #[command(alias = "ebpf-tool", disable_help_flag = true)]
+    Ebpf(ToolArgs),
+}
+
+#[derive(Args)]
+struct ToolArgs {
+    /// Arguments for the tool, including --help
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<OsString>,
+}
+
+/// A tool and how it spells the global flags.
+struct ToolInfo {
+    /// Binary name, also the name a multi-call symlink must have
+    bin: &'static str,
+    /// Subcommand name under `isolation`
+    sub: &'static str,
+    /// Flag that `--json` becomes, or None if the tool has no JSON mode
+    json: Option<&'static str>,
+    /// Flag that `--verbose` becomes, or None if the tool has no verbose mode
+    verbose: Option<&'static str>,
+}
+
+/// Every embedded tool. The translated flags are all `global = true` in the
+/// tool's Cli, so they are valid right after the binary name.
+const TOOLS: &[ToolInfo] = &[
+    ToolInfo {
+        bin: "ns-tool",
+        sub: "ns",
+        json: Some("--verify"),
+        verbose: None,
+    },
+    ToolInfo {
+        bin: "netns-tool",
+        sub: "netns",
+        json: None,
+        verbose: None,
+    },
+    ToolInfo {
+        bin: "cgroup-tool",
+        sub: "cgroup",
+        json: None,
+        verbose: None,
+    },
+    ToolInfo {
+        bin: "oci-tool",
+        sub: "oci",
+        json: None,
+        verbose: None,
+    },
+    ToolInfo {
+        bin: "ebpf-tool",
+        sub: "ebpf",
+        json: None,
+        verbose: Some("--verbose"),
+    },
+];
+
+impl Tool {
+    fn into_parts(self) -> (&'static ToolInfo, Vec<OsString>) {
+        let (sub, args) = match self {
+            Tool::Ns(a) => ("ns", a.args),
+            Tool::Netns(a) => ("netns", a.args),
+            Tool::Cgroup(a) => ("cgroup", a.args),
+            Tool::Oci(a) => ("oci", a.args),
+            Tool::Ebpf(a) => ("ebpf", a.args),
+        };
+        let info = TOOLS
+            .iter()
+            .find(|t| t.sub == sub)
+            .expect("every Tool is in TOOLS");
+        (info, args)
+    }
+}
+
+/// The argv the tool parses: a program name for its usage lines, the
+/// translated global flags, then `args`.
+fn tool_argv(tool: &ToolInfo, json: bool, verbose: bool, args: Vec<OsString>) -> Vec<OsString> {
+    let mut argv = vec![OsString::from(format!("isolation {}", tool.sub))];
+    for (set, flag, name) in [
+        (json, tool.json, "--json"),
+        (verbose, tool.verbose, "--verbose"),
+    ] {
+        match (set, flag) {
+            (true, Some(flag)) => argv.push(flag.into()),
+            (true, None) => eprintln!("isolation: {} has no effect on {}", name, tool.bin),
+            (false, _) => {}
+        }
+    }
+    argv.extend(args);
+    argv
+}
+
+/// Parse `argv` with the tool's own Cli and run it. Parse errors and
+/// --help exit here, with the tool's own message.
+fn run_tool(bin: &str, argv: Vec<OsString>) -> Result<()> {
+    match bin {
+        "ns-tool" => ns_tool::run(ns_tool::Cli::parse_from(argv)),
+        "netns-tool" => netns_tool::run(netns_tool::Cli::parse_from(argv)),
+        "cgroup-tool" => cgroup_tool::run(cgroup_tool::Cli::parse_from(argv)),
+        "oci-tool" => oci_tool::run(oci_tool::Cli::parse_from(argv)),
+        "ebpf-tool" => ebpf_tool::run(ebpf_tool::Cli::parse_from(argv)),
+        _ => bail!("unknown tool: {}", bin),
+    }
+}
+
+fn main() -> Result<()> {
+    let argv: Vec<OsString> = std::env::args_os().collect();
+
+    // Multi-call: a symlink named after a tool runs that tool as is
+    let called_as = argv
+        .first()
+        .and_then(|arg0| Path::new(arg0).file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    if let Some(tool) = TOOLS.iter().find(|t| t.bin == called_as) {
+        return run_tool(tool.bin, argv);
+    }
+
+    let cli = Cli::parse_from(argv);
+    let (tool, args) = cli.tool.into_parts();
+    run_tool(tool.bin, tool_argv(tool, cli.json, cli.verbose, args))
+}
@@ -0,0 +1,85 @@
+// Tests for the `isolation` meta-CLI
+//
+// The tools themselves are tested in their own crates. These tests only
+// check the dispatch: the right tool runs, with the right argv, both as
+// `isolation <tool>` and through a symlink named after the tool.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+
+#[test]
+fn test_help_lists_every_tool() {
+    let output = cargo_bin_cmd!("isolation").arg("--help").output().unwrap();
+    assert!(output.status.success());
+    let help = String::from_utf8_lossy(&output.stdout);
+    for tool in [
+        "ns",
+        "netns",
+        "cgroup",
+        "oci",
+        "ebpf",
+        "--json",
+        "--verbose",
+    ] {
+        assert!(help.contains(tool), "{tool:?} missing from:\n{help}");
+    }
+}
+
+#[test]
+fn test_tool_help_is_forwarded() {
+    // --help after the tool name is the tool's help, not the subcommand's
+    cargo_bin_cmd!("isolation")
+        .args(["ns", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Namespace learning tool"))
+        .stdout(predicate::str::contains("Usage: isolation ns"));
+
+    // The full binary names work as aliases
+    cargo_bin_cmd!("isolation")
+        .args(["cgroup-tool", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cgroup v2 tool"));
+}
+
+#[test]
+fn test_runs_tool_subcommand() {
+    // `ns-tool proc` only reads /proc/self/ns, so it works without root
+    cargo_bin_cmd!("isolation")
+        .args(["ns", "proc"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pid -> pid:["));
+}
+
+#[test]
+fn test_unsupported_global_flag_warns() {
+    cargo_bin_cmd!("isolation")
+        .args(["--json", "oci", "--help"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("--json has no effect on oci-tool"));
+
+    // Global flags belong to isolation only before the tool name; after it,
+    // they are the tool's arguments
+    cargo_bin_cmd!("isolation")
+        .args(["oci", "--json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--json"));
+}
+
+#[test]
+fn test_multi_call_symlink() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("netns-tool");
+    std::os::unix::fs::symlink(assert_cmd::cargo::cargo_bin!("isolation"), &link).unwrap();
+
+    assert_cmd::Command::new(&link)
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Network namespace tool"))
+        .stdout(predicate::str::contains("Usage: netns-tool"));
+}
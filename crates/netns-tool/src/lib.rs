@@ -0,0 +1,827 @@
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)] // Used by `save` and `restore` once implemented
+mod manifest;
+#[allow(dead_code)] // Used by `capture` once implemented
+mod pcap;
+#[allow(dead_code)] // Used by `tunnel` once implemented
+mod tunnel;
+
+#[derive(Parser)]
+#[command(name = "netns-tool")]
+#[command(about = "Network namespace tool (Rust-first rewrite)")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Create {
+        name: String,
+    },
+    Delete {
+        name: String,
+    },
+    Veth {
+        host: String,
+        /// Namespace name (under /run/netns); or use --pid / --container
+        #[arg(required_unless_present_any = ["pid", "container"])]
+        #[arg(conflicts_with_all = ["pid", "container"])]
+        ns: Option<String>,
+        #[command(flatten)]
+        target: NsTarget,
+    },
+    Bridge {
+        name: String,
+    },
+    Nat {
+        bridge: String,
+        outbound: String,
+    },
+    /// Apply a minimal nftables firewall inside a namespace
+    Firewall {
+        /// Namespace name (under /run/netns); or use --pid / --container
+        #[arg(required_unless_present_any = ["pid", "container"])]
+        #[arg(conflicts_with_all = ["pid", "container"])]
+        ns: Option<String>,
+        #[command(flatten)]
+        target: NsTarget,
+        /// Allowed outbound TCP ports (comma-separated, e.g. 80,443)
+        #[arg(long, value_delimiter = ',')]
+        allow_out: Vec<u16>,
+        /// Inbound policy: "all" drops every new inbound connection
+        #[arg(long)]
+        deny_in: Option<String>,
+        #[command(subcommand)]
+        action: Option<FirewallAction>,
+    },
+    /// Check a namespace's veth pair for MTU and offload pitfalls
+    Tune {
+        /// Namespace name (under /run/netns); or use --pid / --container
+        #[arg(required_unless_present_any = ["pid", "container"])]
+        #[arg(conflicts_with_all = ["pid", "container"])]
+        ns: Option<String>,
+        #[command(flatten)]
+        target: NsTarget,
+        /// Apply the suggested changes instead of only reporting them
+        #[arg(long)]
+        fix: bool,
+        /// Also turn off TSO/GSO/GRO on both ends, so iperf3 measures per-packet cost
+        #[arg(long)]
+        benchmark: bool,
+        /// Path MTU to size the pair for (default: MTU of the default route's device)
+        #[arg(long)]
+        path_mtu: Option<u32>,
+    },
+    /// Record the namespaces, veths, bridges and NAT rules netns-tool set up
+    Save {
+        /// Manifest to write
+        #[arg(long, default_value = manifest::DEFAULT_PATH)]
+        manifest: PathBuf,
+    },
+    /// Re-create everything in a saved manifest (e.g., after a reboot)
+    Restore {
+        /// Manifest to read
+        #[arg(long, default_value = manifest::DEFAULT_PATH)]
+        manifest: PathBuf,
+        /// Print what would be created instead of creating it
+        #[arg(long)]
+        dry_run: bool,
+        /// Print a systemd unit that runs this restore at boot, and exit
+        #[arg(long, conflicts_with = "dry_run")]
+        systemd_unit: bool,
+    },
+    /// Create an overlay tunnel to another host, optionally moved into a namespace
+    Tunnel {
+        #[command(subcommand)]
+        kind: TunnelCommand,
+    },
+    /// Capture packets inside a namespace as pcapng (e.g. `--stdout | wireshark -k -i -`)
+    Capture {
+        /// Namespace name (under /run/netns); or use --pid / --container
+        #[arg(required_unless_present_any = ["pid", "container"])]
+        #[arg(conflicts_with_all = ["pid", "container"])]
+        ns: Option<String>,
+        #[command(flatten)]
+        target: NsTarget,
+        /// Interface inside the namespace (default: all of them)
+        #[arg(short, long)]
+        interface: Option<String>,
+        /// Write pcapng to stdout, for a pipe into wireshark or tcpdump
+        #[arg(long, required_unless_present = "write", conflicts_with = "write")]
+        stdout: bool,
+        /// Write pcapng to this file
+        #[arg(short, long)]
+        write: Option<PathBuf>,
+        /// Stop after this many packets (0 = until Ctrl+C or the reader exits)
+        #[arg(short, long, default_value_t = 0)]
+        count: u64,
+        /// Bytes of each packet to keep
+        #[arg(long, default_value_t = pcap::DEFAULT_SNAPLEN)]
+        snaplen: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum TunnelCommand {
+    /// Ethernet over UDP (port 4789): the device can join a bridge
+    Vxlan(TunnelArgs),
+    /// IP over GRE: a point-to-point link with an address on each end
+    Gre(TunnelArgs),
+}
+
+#[derive(Args)]
+struct TunnelArgs {
+    /// VXLAN VNI or GRE key; both ends must use the same one
+    #[arg(long)]
+    id: u32,
+    /// Underlay address of this host
+    #[arg(long)]
+    local: IpAddr,
+    /// Underlay address of the other host
+    #[arg(long)]
+    remote: IpAddr,
+    /// Move the tunnel device into this namespace (under /run/netns)
+    #[arg(long)]
+    ns: Option<String>,
+    /// Interface name (default: vxlan<id> or gre<id>)
+    #[arg(long)]
+    name: Option<String>,
+    /// Address inside the tunnel, e.g. 10.200.0.1/24
+    #[arg(long)]
+    address: Option<String>,
+    /// VXLAN destination UDP port
+    #[arg(long, default_value_t = tunnel::VXLAN_PORT)]
+    port: u16,
+    /// Print the equivalent ip commands instead of creating anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// A network namespace that was not created by `netns-tool create`.
+///
+/// Named namespaces live in /run/netns. Namespaces created by `contain`,
+/// runc or Docker usually have no name there, but every one of them is
+/// reachable through a process inside it: /proc/<pid>/ns/net.
+#[derive(Args)]
+struct NsTarget {
+    /// Use the network namespace of this process (/proc/<pid>/ns/net)
+    #[arg(long, conflicts_with = "container")]
+    pid: Option<i32>,
+    /// Use the network namespace of a contain container (/sys/fs/cgroup/contain/<id>)
+    #[arg(long)]
+    container: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum FirewallAction {
+    /// Print the ruleset currently loaded in the namespace
+    Show,
+    /// Remove the netns-tool table from the namespace
+    Flush,
+}
+
+/// Run netns-tool with parsed arguments: `main.rs` and the `isolation` CLI both
+/// end up here.
+pub fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        // TODO: Implement network namespace creation
+        // Lesson: docs/01-namespaces/05-network-namespace.md (part 1)
+        // Tests: tests/create_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/create_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Create /run/netns directory if needed
+        // - Use nix::sched::unshare(CloneFlags::CLONE_NEWNET)
+        // - Bind-mount /proc/self/ns/net to /run/netns/{name}
+        // - This makes the namespace persistent
+        Command::Create { name } => {
+            todo!("Implement network namespace creation - write tests first! (name: {name})")
+        }
+
+        // TODO: Implement network namespace deletion
+        // Lesson: docs/01-namespaces/05-network-namespace.md (part 2)
+        // Tests: tests/delete_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/delete_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Unmount /run/netns/{name}
+        // - Remove the file
+        // - Handle errors gracefully if namespace doesn't exist
+        Command::Delete { name } => {
+            todo!("Implement network namespace deletion - write tests first! (name: {name})")
+        }
+
+        // TODO: Implement veth pair creation
+        // Lesson: docs/01-namespaces/05-network-namespace.md (part 3)
+        // Tests: tests/veth_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/veth_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Create veth pair using rtnetlink crate or ip command
+        // - Move one end to target namespace
+        // - Assign IP addresses and bring interfaces UP
+        // - For rtnetlink: see examples in rtnetlink crate docs
+        // - Resolve the namespace with resolve_netns(ns, &target) so --pid and
+        //   --container work too (lesson 13). `ip link set <if> netns` accepts a
+        //   name or a PID; for a /proc path, move the link from inside a setns()
+        //   child instead
+        Command::Veth { host, ns, target } => {
+            todo!(
+                "Implement veth pair creation - write tests first! (host: {host}, ns: {ns:?}, pid: {:?}, container: {:?})",
+                target.pid,
+                target.container
+            )
+        }
+
+        // TODO: Implement bridge creation
+        // Lesson: docs/01-namespaces/05-network-namespace.md (part 4)
+        // Tests: tests/bridge_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/bridge_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Use `ip link add {name} type bridge`
+        // - Bring bridge UP
+        // - Optionally assign IP address to bridge
+        Command::Bridge { name } => {
+            todo!("Implement bridge creation - write tests first! (name: {name})")
+        }
+
+        // TODO: Implement NAT setup for internet access
+        // Lesson: docs/01-namespaces/05-network-namespace.md (part 5)
+        // Tests: tests/nat_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/nat_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Enable IP forwarding: echo 1 > /proc/sys/net/ipv4/ip_forward
+        // - Add iptables MASQUERADE rule
+        // - Add forward accept rules for the bridge
+        Command::Nat { bridge, outbound } => {
+            todo!(
+                "Implement NAT setup - write tests first! (bridge: {bridge}, outbound: {outbound})"
+            )
+        }
+
+        // TODO: Implement per-namespace firewall
+        // Lesson: docs/01-namespaces/12-netns-firewall.md
+        // Tests: tests/firewall_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/firewall_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Every namespace has its own netfilter tables, so run nft *inside*
+        //   the namespace: `ip netns exec {ns} nft -f -` (or setns() first)
+        // - Build the ruleset text with render_ruleset() below and pipe it to
+        //   nft's stdin (std::process::Command + Stdio::piped())
+        // - Show: `nft list table inet netns_tool`
+        // - Flush: `nft delete table inet netns_tool` (ignore "No such file")
+        // - `ip netns exec` only takes names. For --pid / --container, use
+        //   `nsenter --net=<path> nft ...` with the path from resolve_netns()
+        Command::Firewall {
+            ns,
+            target,
+            allow_out,
+            deny_in,
+            action,
+        } => {
+            let _ = (&target.pid, &target.container);
+            match action {
+                None => todo!(
+                    "Implement firewall apply - write tests first! (ns: {ns:?}, allow_out: {allow_out:?}, deny_in: {deny_in:?})"
+                ),
+                Some(FirewallAction::Show) => {
+                    todo!("Implement firewall show - write tests first! (ns: {ns:?})")
+                }
+                Some(FirewallAction::Flush) => {
+                    todo!("Implement firewall flush - write tests first! (ns: {ns:?})")
+                }
+            }
+        }
+
+        // TODO: Implement veth MTU/offload checks
+        // Lesson: docs/01-namespaces/14-veth-tuning.md
+        // Tests: tests/tune_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/tune_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - resolve_netns(ns, &target), then find_veth_pair() to get the
+        //   namespace end and its host peer
+        // - read_veth_end() for both ends: MTU over rtnetlink (IFLA_MTU, or
+        //   `ip -j link show`), offloads over ethtool netlink
+        // - path_mtu.unwrap_or(default_route_mtu()?)
+        // - NAT is in use if /proc/sys/net/ipv4/ip_forward is 1 (what
+        //   `netns-tool nat` turns on); it decides the checksum finding
+        // - check_veth() decides everything; print one line per Finding,
+        //   then "N problem(s) found; run with --fix to apply" (exit 0:
+        //   findings are advice, not errors)
+        // - --fix: apply_fix() for each finding, and print what changed.
+        //   A refused fix (an offload the driver marks as fixed) is printed
+        //   and the others are still applied. Read both ends again
+        //   afterwards, so a fix that did not stick is reported, not hidden
+        //
+        // Expected output format:
+        //   veth pair: veth-web (host, mtu 1500) <-> eth0 (netns web, mtu 9000)
+        //   path MTU:  1420 (default route via wg0)
+        //   [MTU]      eth0: 9000 differs from its peer (1500): larger frames are dropped silently
+        //   [MTU]      veth-web, eth0: 1500 is above the path MTU 1420
+        //   [OFFLOAD]  eth0: tx-checksum-ip-generic is on and NAT is in use
+        //   3 problem(s) found; run with --fix to apply
+        Command::Tune {
+            ns,
+            target,
+            fix,
+            benchmark,
+            path_mtu,
+        } => {
+            let _ = (&target.pid, &target.container);
+            todo!(
+                "Implement tune - write tests first! (ns: {ns:?}, fix: {fix}, benchmark: {benchmark}, path_mtu: {path_mtu:?})"
+            )
+        }
+
+        // TODO: Implement saving the managed state
+        // Lesson: docs/01-namespaces/15-save-restore.md
+        // Tests: tests/restore_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/restore_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - scan_state() below finds what exists right now; the manifest
+        //   module (src/manifest.rs) already parses, renders and writes it
+        // - Manifest::save() creates /var/lib/netns-tool and replaces the
+        //   file atomically
+        // - Print one line per entry and the path, so a save of nothing
+        //   ("0 entries") is visible instead of silently clearing the file
+        //
+        // Expected output format:
+        //   netns web
+        //   bridge br0
+        //   veth veth-web web
+        //   nat br0 eth0
+        //   saved 4 entries to /var/lib/netns-tool/manifest
+        Command::Save { manifest } => {
+            todo!(
+                "Implement save - write tests first! (manifest: {})",
+                manifest.display()
+            )
+        }
+
+        // TODO: Implement restoring from a manifest
+        // Lesson: docs/01-namespaces/15-save-restore.md
+        // Tests: tests/restore_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/restore_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - --systemd-unit: print manifest::systemd_unit() with
+        //   std::env::current_exe()? and the manifest path, then return;
+        //   this needs no root and does not read the manifest
+        // - Manifest::load(), then entries() in order: namespaces, bridges,
+        //   veths, NAT (the order the kernel needs them in)
+        // - Restore must be safe to run twice (systemd may run it on a host
+        //   where nothing was lost): skip an entry when entry_exists()
+        // - Otherwise re-run this same tool: Cli::try_parse_from() with
+        //   "netns-tool" and entry.args(), then run(); keep going after a
+        //   failure and bail at the end with how many entries failed
+        // - --dry-run: print the commands (`netns-tool veth veth-web web`)
+        //   and whether each one exists already
+        //
+        // Expected output format:
+        //   exists   netns web
+        //   created  bridge br0
+        //   created  veth veth-web web
+        //   created  nat br0 eth0
+        //   restored 3 of 4 entries (1 already present)
+        Command::Restore {
+            manifest,
+            dry_run,
+            systemd_unit,
+        } => {
+            todo!(
+                "Implement restore - write tests first! (manifest: {}, dry_run: {dry_run}, systemd_unit: {systemd_unit})",
+                manifest.display()
+            )
+        }
+
+        // TODO: Implement overlay tunnels
+        // Lesson: docs/01-namespaces/16-overlay-tunnels.md
+        // Tests: tests/tunnel_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/tunnel_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Build a tunnel::TunnelSpec from the args (name defaults to
+        //   tunnel::default_name(), address through tunnel::parse_cidr())
+        //   and validate() it before touching the kernel
+        // - underlay_device(local) finds the device that owns --local and
+        //   its MTU; the tunnel MTU is spec.mtu(underlay MTU)
+        // - --dry-run: print `ip <spec.ip_link_args()>` and the
+        //   `ip link set`/`ip addr add` commands that would follow, then return
+        // - Otherwise create_tunnel() below, with /run/netns/{ns} for --ns
+        //
+        // Expected output format:
+        //   vxlan42: id 42, 192.168.1.10 -> 192.168.1.20 udp/4789 via eth0
+        //   vxlan42: mtu 1450 (eth0 1500 - 50 bytes of headers)
+        //   vxlan42: moved to netns web, address 10.200.0.1/24, up
+        Command::Tunnel { kind } => {
+            let (kind, args) = match kind {
+                TunnelCommand::Vxlan(args) => (tunnel::TunnelKind::Vxlan, args),
+                TunnelCommand::Gre(args) => (tunnel::TunnelKind::Gre, args),
+            };
+            todo!(
+                "Implement tunnel - write tests first! ({kind} id: {}, local: {}, remote: {}, ns: {:?}, name: {:?}, address: {:?}, port: {}, dry_run: {})",
+                args.id,
+                args.local,
+                args.remote,
+                args.ns,
+                args.name,
+                args.address,
+                args.port,
+                args.dry_run
+            )
+        }
+
+        // TODO: Implement packet capture
+        // Lesson: docs/01-namespaces/18-packet-capture.md
+        // Tests: tests/capture_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/capture_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - --stdout: bail if std::io::IsTerminal::is_terminal(&std::io::stdout())
+        //   ("refusing to write pcapng to a terminal; pipe it into
+        //   `wireshark -k -i -` or `tcpdump -nr -`"), before opening anything
+        // - resolve_netns(ns, &target), then open_packet_socket() below
+        // - pcap::Writer::new() on stdout().lock() or a File, with snaplen
+        // - For each packet: recvfrom() into a snaplen buffer with
+        //   MSG_TRUNC (the return value is then the length on the wire), and
+        //   a sockaddr_ll for sll_ifindex, sll_hatype and sll_pkttype
+        // - Skip sll_pkttype PACKET_OUTGOING on lo: loopback packets are seen
+        //   twice, once leaving and once arriving
+        // - pcap::linktype(sll_hatype); skip (and count) unknown link types
+        // - writer.interface(ifindex, name, linktype) once per ifindex, with
+        //   the name from interface_name() below; then writer.packet() with
+        //   the CLOCK_REALTIME time of the packet, and flush()
+        // - Progress on stderr: pcap::Progress::record() per packet, and when
+        //   due(), eprint "\r<progress>" if stderr is a terminal, else one
+        //   line each time. Always a final line "captured <progress>"
+        // - stdout closed (wireshark quit, `| head`): EPIPE from the writer
+        //   ends the capture normally, not as an error
+        //
+        // Expected stderr:
+        //   capturing on all interfaces in netns web (snaplen 262144)
+        //   captured 42 packets, 6.1 KiB
+        Command::Capture {
+            ns,
+            target,
+            interface,
+            stdout,
+            write,
+            count,
+            snaplen,
+        } => {
+            let _ = (&target.pid, &target.container);
+            todo!(
+                "Implement capture - write tests first! (ns: {ns:?}, interface: {interface:?}, stdout: {stdout}, write: {write:?}, count: {count}, snaplen: {snaplen})"
+            )
+        }
+    }
+
+    Ok(())
+}
+
+/// Render the nftables ruleset applied by `firewall`.
+///
+/// All rules live in a dedicated `inet netns_tool` table so `flush` can
+/// remove them without touching anything else in the namespace.
+#[allow(dead_code)]
+fn render_ruleset(allow_out: &[u16], deny_in: bool) -> String {
+    // TODO: Implement in lesson 12 (namespace firewall)
+    // Hints:
+    // - Start with "table inet netns_tool {" and two base chains:
+    //   `chain input  { type filter hook input priority 0; policy accept; }`
+    //   `chain output { type filter hook output priority 0; policy accept; }`
+    // - Always accept `ct state established,related` and `iif lo` / `oif lo`
+    // - deny_in: set the input policy to drop
+    // - allow_out non-empty: `tcp dport { 80, 443 } accept`, allow udp dport
+    //   53 for DNS, and set the output policy to drop
+    let _ = (allow_out, deny_in);
+    todo!("Implement nftables ruleset rendering")
+}
+
+/// Turn a namespace name, `--pid` or `--container` into a path that can be
+/// opened and passed to setns(2).
+///
+/// Exactly one of `name`, `target.pid` and `target.container` is set; clap
+/// enforces that before we get here.
+#[allow(dead_code)]
+fn resolve_netns(name: Option<&str>, target: &NsTarget) -> Result<PathBuf> {
+    // TODO: Implement in lesson 13 (targeting any network namespace)
+    // Hints:
+    // - name: /run/netns/{name}; bail with "create it first" if missing
+    // - pid: /proc/{pid}/ns/net; bail with "no such process" if missing
+    // - container: container_pid() below, then the same as pid
+    // - Callers should open the path right away and keep the File: the open
+    //   fd pins the namespace, while a PID can exit (and be reused) at any time
+    let _ = (name, target);
+    todo!("Implement network namespace resolution")
+}
+
+/// Find a process inside a contain container: the first PID listed in
+/// /sys/fs/cgroup/contain/<id>/cgroup.procs.
+#[allow(dead_code)]
+fn container_pid(id: &str) -> Result<i32> {
+    // TODO: Implement in lesson 13 (targeting any network namespace)
+    // Hints:
+    // - Reject ids containing '/' so "--container ../.." cannot escape the
+    //   contain cgroup root
+    // - All processes in a container share its network namespace, so any
+    //   PID works; an empty cgroup.procs means the container is not running
+    // - runc containers: /run/runc/<id>/state.json has "init_process_pid"
+    let _ = id;
+    todo!("Implement contain container PID lookup")
+}
+
+/// MTU and offload state of one end of a veth pair.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct VethEnd {
+    /// Interface name (e.g., "veth-web" on the host, "eth0" in the namespace)
+    name: String,
+    mtu: u32,
+    /// Active offloads by ethtool feature name (e.g., "tx-tcp-segmentation")
+    features: BTreeMap<String, bool>,
+}
+
+/// A change that `tune --fix` can make.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TuneFix {
+    /// Set the interface MTU
+    Mtu(u32),
+    /// Turn an ethtool feature on or off
+    Feature(&'static str, bool),
+}
+
+/// One problem found by `tune`, on one interface.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    link: String,
+    /// Why this is a problem, one sentence
+    problem: String,
+    fix: TuneFix,
+}
+
+/// Offloads switched off by `tune --benchmark`.
+#[allow(dead_code)]
+const BENCHMARK_OFFLOADS: &[&str] = &["tx-tcp-segmentation", "tx-generic-segmentation", "rx-gro"];
+
+/// Decide what is wrong with a veth pair. Pure, so it can be tested
+/// without root.
+#[allow(dead_code)]
+fn check_veth(
+    host: &VethEnd,
+    peer: &VethEnd,
+    path_mtu: u32,
+    nat: bool,
+    benchmark: bool,
+) -> Vec<Finding> {
+    // TODO: Implement in lesson 14 (veth tuning)
+    // Hints:
+    // - MTU mismatch: the two ends disagree. Fix the larger one down to the
+    //   smaller: a veth drops frames above the receiver's MTU without
+    //   sending anything back
+    // - MTU above the path: either end above path_mtu. Fix both to path_mtu:
+    //   behind NAT, the ICMP "fragmentation needed" that PMTU discovery
+    //   relies on is often filtered, and TCP connections stall after the
+    //   handshake
+    // - Checksum offload with NAT: "tx-checksum-ip-generic" on for the
+    //   namespace end while nat is true. Fix: Feature("tx-checksum-ip-generic",
+    //   false) on the namespace end
+    // - benchmark: every BENCHMARK_OFFLOADS feature that is on, on either
+    //   end -> Feature(name, false)
+    // - A feature missing from `features` is not a finding: the driver
+    //   does not have it
+    let _ = (host, peer, path_mtu, nat, benchmark);
+    todo!("Implement veth checks")
+}
+
+/// Find the veth end inside the namespace at `netns` and its peer on the
+/// host: (host end, namespace end).
+#[allow(dead_code)]
+fn find_veth_pair(netns: &Path) -> Result<(String, String)> {
+    // TODO: Implement in lesson 14 (veth tuning)
+    // Hints:
+    // - Inside the namespace (setns() in a child, or `nsenter --net=<path>
+    //   ip -j -d link show`): the interface whose linkinfo kind is "veth"
+    // - Its IFLA_LINK (the "link_index" in `ip -j` output) is the ifindex
+    //   of the peer in the host namespace
+    // - On the host, the interface with that ifindex is the host end
+    // - Bail with "no veth in <netns>; create one with `netns-tool veth`"
+    //   if there is none, and list them if there are several
+    let _ = netns;
+    todo!("Implement veth pair lookup")
+}
+
+/// Read the MTU and offloads of `name`, in `netns` (None = host namespace).
+#[allow(dead_code)]
+fn read_veth_end(name: &str, netns: Option<&Path>) -> Result<VethEnd> {
+    // TODO: Implement in lesson 14 (veth tuning)
+    // Hints:
+    // - ethtool netlink is a generic netlink family: resolve "ethtool" with
+    //   CTRL_CMD_GETFAMILY, then send ETHTOOL_MSG_FEATURES_GET with
+    //   ETHTOOL_A_FEATURES_HEADER -> ETHTOOL_A_HEADER_DEV_NAME = name
+    // - The reply's ETHTOOL_A_FEATURES_ACTIVE is a bitset with names
+    //   (ETHTOOL_A_BITSET_BITS -> BIT -> NAME / VALUE): exactly what
+    //   `ethtool -k` prints, under the kernel's names
+    // - Without a netlink crate, `ethtool -k <name>` prints the same data,
+    //   one "feature: on|off [fixed]" line each; run it under
+    //   `nsenter --net=<path>` for the namespace end
+    // - MTU: /sys/class/net/<name>/mtu is only valid in the namespace
+    //   that mounted /sys; inside another namespace use IFLA_MTU
+    let _ = (name, netns);
+    todo!("Implement veth state read")
+}
+
+/// Apply one fix to `link` in `netns` (None = host namespace).
+#[allow(dead_code)]
+fn apply_fix(link: &str, netns: Option<&Path>, fix: &TuneFix) -> Result<()> {
+    // TODO: Implement in lesson 14 (veth tuning)
+    // Hints:
+    // - Mtu: RTM_NEWLINK with IFLA_MTU (`ip link set <link> mtu <n>`)
+    // - Feature: ETHTOOL_MSG_FEATURES_SET with ETHTOOL_A_FEATURES_WANTED
+    //   holding just that one bit (`ethtool -K <link> <feature> off`)
+    // - The kernel may refuse a feature change (EOPNOTSUPP, or a
+    //   "fixed" feature): return the error with the feature name in it
+    let _ = (link, netns, fix);
+    todo!("Implement fix application")
+}
+
+/// MTU of the device the host's default route goes through, and its name.
+#[allow(dead_code)]
+fn default_route_mtu() -> Result<(u32, String)> {
+    // TODO: Implement in lesson 14 (veth tuning)
+    // Hints:
+    // - /proc/net/route: the line with Destination 00000000 names the device
+    // - /sys/class/net/<dev>/mtu
+    // - No default route: bail and suggest --path-mtu
+    todo!("Implement default route MTU lookup")
+}
+
+/// Find what netns-tool manages on this host right now.
+#[allow(dead_code)]
+fn scan_state() -> Result<manifest::Manifest> {
+    // TODO: Implement in lesson 15 (save and restore)
+    // Hints:
+    // - Namespaces: the entries of /run/netns (a missing directory means none)
+    // - Bridges: `ip -j link show type bridge`; skip docker0 and other
+    //   bridges netns-tool did not create, e.g. by keeping the ones a saved
+    //   veth or NAT entry refers to, or all of them if you prefer
+    // - Veths: `ip -j -d link show type veth` on the host; the peer's
+    //   "link_netnsid" maps to a name in `ip -j netns list`. A veth whose
+    //   peer is in an unnamed namespace (--pid / --container) cannot be
+    //   restored: skip it with a warning
+    // - NAT: `iptables -t nat -S POSTROUTING` lines with
+    //   `-s <bridge subnet> -o <outbound> -j MASQUERADE`, as `nat` adds them
+    todo!("Implement managed state scan")
+}
+
+/// Whether `entry` is already present, so `restore` can skip it.
+#[allow(dead_code)]
+fn entry_exists(entry: &manifest::Entry) -> Result<bool> {
+    // TODO: Implement in lesson 15 (save and restore)
+    // Hints:
+    // - Netns: /run/netns/{name} exists *and* is a mount point (after a
+    //   reboot a stale empty file can be left behind; compare st_dev with
+    //   /run/netns, or look for it in /proc/self/mountinfo)
+    // - Bridge and Veth: /sys/class/net/{name} exists on the host
+    // - Nat: `iptables -t nat -C POSTROUTING ...` exits 0 if the rule exists
+    let _ = entry;
+    todo!("Implement restore existence check")
+}
+
+/// The device that owns `local`, and its MTU: the underlay of a tunnel.
+#[allow(dead_code)]
+fn underlay_device(local: IpAddr) -> Result<(String, u32)> {
+    // TODO: Implement in lesson 16 (overlay tunnels)
+    // Hints:
+    // - RTM_GETADDR dump (`ip -j addr show`): the IFA_LOCAL/IFA_ADDRESS equal
+    //   to `local` names the interface index
+    // - Bail if no device has it: "192.168.1.10 is not an address of this
+    //   host"; the kernel would accept it and the tunnel would never work
+    // - MTU from IFLA_MTU of that link (or /sys/class/net/<dev>/mtu: we are
+    //   still in the host namespace here)
+    let _ = local;
+    todo!("Implement underlay device lookup")
+}
+
+/// Create the tunnel device on the host, set its MTU, then move it into
+/// `netns` (None = stay on the host), give it its address and bring it up.
+#[allow(dead_code)]
+fn create_tunnel(
+    spec: &tunnel::TunnelSpec,
+    dev: &str,
+    mtu: u32,
+    netns: Option<&Path>,
+) -> Result<()> {
+    // TODO: Implement in lesson 16 (overlay tunnels)
+    // Hints:
+    // - RTM_NEWLINK with IFLA_IFNAME and IFLA_LINKINFO -> IFLA_INFO_KIND
+    //   = spec.link_kind() and IFLA_INFO_DATA:
+    //   vxlan: IFLA_VXLAN_ID, IFLA_VXLAN_LOCAL/LOCAL6, IFLA_VXLAN_GROUP/GROUP6
+    //   (the remote, despite the name), IFLA_VXLAN_PORT in network byte
+    //   order, IFLA_VXLAN_LINK = ifindex of dev
+    //   gre: IFLA_GRE_LOCAL, IFLA_GRE_REMOTE, IFLA_GRE_IKEY and IFLA_GRE_OKEY
+    //   = id, and GRE_KEY in IFLA_GRE_IFLAGS/OFLAGS
+    // - `ip <spec.ip_link_args(Some(dev))>` does the same if you shell out
+    // - Create it in the host namespace, where `local` is: the device's
+    //   socket stays there after the move. Then IFLA_NET_NS_FD (`ip link
+    //   set <name> netns <ns>`), and the address and `up` from inside the
+    //   namespace (setns() in a child, or `ip -n <ns>`)
+    // - EEXIST: say the name is taken and suggest --name; delete the
+    //   half-made device if a later step fails
+    let _ = (spec, dev, mtu, netns);
+    todo!("Implement tunnel creation")
+}
+
+/// Open an AF_PACKET socket inside `netns` that receives every packet on
+/// `interface`, or on all interfaces of the namespace.
+///
+/// The socket belongs to the namespace it was created in, so it keeps
+/// capturing there after the calling thread returns to the host namespace.
+#[allow(dead_code)]
+fn open_packet_socket(netns: &Path, interface: Option<&str>) -> Result<std::os::fd::OwnedFd> {
+    // TODO: Implement in lesson 18 (packet capture)
+    // Hints:
+    // - Run the namespace part on a scratch thread (std::thread::scope):
+    //   setns(File::open(netns)?, CLONE_NEWNET) moves only that thread,
+    //   and the socket stays usable after it ends
+    // - socket(AF_PACKET, SOCK_RAW | SOCK_CLOEXEC, (ETH_P_ALL as u16).to_be())
+    // - interface: if_nametoindex() inside the namespace (the host may have
+    //   an eth0 too), then bind() a sockaddr_ll with sll_family AF_PACKET,
+    //   sll_protocol ETH_P_ALL (big-endian) and that sll_ifindex. Bail with
+    //   "no interface <name> in netns <ns>" if the lookup fails
+    // - SO_TIMESTAMPNS gives each packet its kernel receive time (a
+    //   SCM_TIMESTAMPNS cmsg with recvmsg); without it, stamp on receipt
+    let _ = (netns, interface);
+    todo!("Implement AF_PACKET socket setup")
+}
+
+/// Name of interface `ifindex` in the namespace the socket lives in, for
+/// the pcapng interface block (e.g. "eth0").
+#[allow(dead_code)]
+fn interface_name(socket: std::os::fd::BorrowedFd<'_>, ifindex: i32) -> Result<String> {
+    // TODO: Implement in lesson 18 (packet capture)
+    // Hints:
+    // - ioctl(socket, SIOCGIFNAME, &mut ifreq { ifr_ifindex: ifindex }):
+    //   the ioctl runs in the socket's namespace, whatever thread calls it.
+    //   if_indextoname() would look in the caller's namespace instead
+    // - Fall back to "if<ifindex>" if the interface disappeared meanwhile
+    let _ = (socket, ifindex);
+    todo!("Implement interface name lookup")
+}
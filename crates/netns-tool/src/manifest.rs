@@ -0,0 +1,315 @@
+//! The manifest of `save` and `restore`: what netns-tool set up, so it can
+//! be set up again after a reboot.
+//!
+//! Named namespaces are bind mounts under /run/netns, and /run is a tmpfs;
+//! links and iptables rules live only in the kernel. After a reboot all of
+//! it is gone. The manifest is the list of netns-tool commands that rebuild
+//! it, one per line:
+//!
+//! ```text
+//! # netns-tool manifest v1
+//! netns web
+//! bridge br0
+//! veth veth-web web
+//! nat br0 eth0
+//! ```
+//!
+//! Each line is the arguments of the subcommand with the same meaning
+//! (`netns` is `create`), so restoring is running them again in order.
+//! Only named namespaces can be saved: a `--pid` or `--container`
+//! namespace dies with its process and has nothing to restore.
+
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Where `save` writes and `restore` reads by default. Not under /run: that
+/// is exactly what a reboot clears.
+pub const DEFAULT_PATH: &str = "/var/lib/netns-tool/manifest";
+
+/// First line of every manifest.
+const HEADER: &str = "# netns-tool manifest v1";
+
+/// Longest interface name the kernel accepts (IFNAMSIZ minus the NUL).
+const MAX_IFNAME_LEN: usize = 15;
+
+/// One object netns-tool manages.
+///
+/// The variant order is the restore order: a veth needs its namespace, and
+/// NAT needs its bridge.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Entry {
+    /// A named namespace: /run/netns/{name}
+    Netns { name: String },
+    /// A bridge on the host
+    Bridge { name: String },
+    /// A veth pair: `host` stays on the host, its peer is in `ns`
+    Veth { host: String, ns: String },
+    /// MASQUERADE for `bridge` out of `outbound`
+    Nat { bridge: String, outbound: String },
+}
+
+impl Entry {
+    /// The netns-tool arguments that create this entry, e.g.
+    /// `["veth", "veth-web", "web"]`.
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            Entry::Netns { name } => vec!["create".into(), name.clone()],
+            Entry::Bridge { name } => vec!["bridge".into(), name.clone()],
+            Entry::Veth { host, ns } => vec!["veth".into(), host.clone(), ns.clone()],
+            Entry::Nat { bridge, outbound } => {
+                vec!["nat".into(), bridge.clone(), outbound.clone()]
+            }
+        }
+    }
+
+    fn parse(line: &str) -> Result<Entry> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let entry = match words.as_slice() {
+            ["netns", name] => Entry::Netns {
+                name: netns_name(name)?,
+            },
+            ["bridge", name] => Entry::Bridge {
+                name: ifname(name)?,
+            },
+            ["veth", host, ns] => Entry::Veth {
+                host: ifname(host)?,
+                ns: netns_name(ns)?,
+            },
+            ["nat", bridge, outbound] => Entry::Nat {
+                bridge: ifname(bridge)?,
+                outbound: ifname(outbound)?,
+            },
+            [kind @ ("netns" | "bridge" | "veth" | "nat"), ..] => {
+                bail!("wrong number of fields for \"{kind}\"")
+            }
+            [kind, ..] => bail!("unknown entry \"{kind}\""),
+            [] => unreachable!("blank lines are skipped"),
+        };
+        Ok(entry)
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Netns { name } => write!(f, "netns {name}"),
+            Entry::Bridge { name } => write!(f, "bridge {name}"),
+            Entry::Veth { host, ns } => write!(f, "veth {host} {ns}"),
+            Entry::Nat { bridge, outbound } => write!(f, "nat {bridge} {outbound}"),
+        }
+    }
+}
+
+fn netns_name(name: &str) -> Result<String> {
+    if name.contains('/') || name == "." || name == ".." {
+        bail!("invalid namespace name \"{name}\"");
+    }
+    Ok(name.to_string())
+}
+
+fn ifname(name: &str) -> Result<String> {
+    if name.len() > MAX_IFNAME_LEN || name.contains('/') {
+        bail!("invalid interface name \"{name}\" (at most {MAX_IFNAME_LEN} bytes, no '/')");
+    }
+    Ok(name.to_string())
+}
+
+/// Everything `restore` re-creates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: Vec<Entry>,
+}
+
+impl Manifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry; adding one twice keeps one.
+    pub fn add(&mut self, entry: Entry) {
+        if let Err(i) = self.entries.binary_search(&entry) {
+            self.entries.insert(i, entry);
+        }
+    }
+
+    /// Entries in restore order: namespaces, bridges, veths, NAT.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Parse a manifest. Errors name the line.
+    pub fn parse(text: &str) -> Result<Manifest> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, first)) if first.trim() == HEADER => {}
+            _ => bail!("not a netns-tool manifest (the first line must be \"{HEADER}\")"),
+        }
+        let mut manifest = Manifest::new();
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = Entry::parse(line).with_context(|| format!("manifest line {}", i + 1))?;
+            manifest.add(entry);
+        }
+        Ok(manifest)
+    }
+
+    /// Render the manifest, in restore order.
+    pub fn render(&self) -> String {
+        let mut out = format!("{HEADER}\n");
+        for entry in &self.entries {
+            out.push_str(&format!("{entry}\n"));
+        }
+        out
+    }
+
+    pub fn load(path: &Path) -> Result<Manifest> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read manifest {}", path.display()))?;
+        Manifest::parse(&text).with_context(|| format!("invalid manifest {}", path.display()))
+    }
+
+    /// Write the manifest, creating its directory. The file is replaced
+    /// with a rename, so a crash during `save` leaves the old manifest.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.render())
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// A systemd unit that runs `restore` once at boot.
+///
+/// It runs after the network is up, so `nat` finds its outbound device.
+/// `ConditionPathExists` makes it a no-op until the first `save`.
+pub fn systemd_unit(exe: &Path, manifest: &Path) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Restore netns-tool namespaces, veths, bridges and NAT\n\
+         Wants=network-online.target\n\
+         After=network-online.target\n\
+         ConditionPathExists={manifest}\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         RemainAfterExit=yes\n\
+         ExecStart={exe} restore --manifest {manifest}\n\
+         \n\
+         [Install]\n\
+         WantedBy=multi-user.target\n",
+        exe = exe.display(),
+        manifest = manifest.display(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Manifest {
+        let mut m = Manifest::new();
+        m.add(Entry::Nat {
+            bridge: "br0".into(),
+            outbound: "eth0".into(),
+        });
+        m.add(Entry::Veth {
+            host: "veth-web".into(),
+            ns: "web".into(),
+        });
+        m.add(Entry::Netns { name: "web".into() });
+        m.add(Entry::Bridge { name: "br0".into() });
+        m.add(Entry::Netns { name: "web".into() });
+        m
+    }
+
+    #[test]
+    fn test_render_in_restore_order() {
+        assert_eq!(
+            sample().render(),
+            "# netns-tool manifest v1\n\
+             netns web\n\
+             bridge br0\n\
+             veth veth-web web\n\
+             nat br0 eth0\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let text = sample().render();
+        let parsed = Manifest::parse(&text).unwrap();
+        assert_eq!(parsed.render(), text);
+
+        let args: Vec<Vec<String>> = parsed.entries().iter().map(|e| e.args()).collect();
+        assert_eq!(args[0], ["create", "web"]);
+        assert_eq!(args[2], ["veth", "veth-web", "web"]);
+    }
+
+    #[test]
+    fn test_parse_skips_comments_and_blank_lines() {
+        let m =
+            Manifest::parse("# netns-tool manifest v1\n\n# lab setup\n  netns web  \nbridge br0\n")
+                .unwrap();
+        assert_eq!(m.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Manifest::parse("netns web\n").unwrap_err();
+        assert!(format!("{err:#}").contains("not a netns-tool manifest"));
+
+        let err = Manifest::parse("# netns-tool manifest v1\nnetns web\nveth a\n").unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "manifest line 3: wrong number of fields for \"veth\""
+        );
+
+        let err = Manifest::parse("# netns-tool manifest v1\nroute default\n").unwrap_err();
+        assert!(format!("{err:#}").contains("unknown entry \"route\""));
+
+        let err = Manifest::parse("# netns-tool manifest v1\nnetns ../etc\n").unwrap_err();
+        assert!(format!("{err:#}").contains("invalid namespace name"));
+
+        let err =
+            Manifest::parse("# netns-tool manifest v1\nbridge a-very-long-bridge\n").unwrap_err();
+        assert!(format!("{err:#}").contains("at most 15 bytes"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("netns-tool-manifest-{}", std::process::id()));
+        let path = dir.join("state").join("manifest");
+        sample().save(&path).unwrap();
+        assert_eq!(Manifest::load(&path).unwrap(), sample());
+        assert!(!path.with_extension("tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let unit = systemd_unit(
+            Path::new("/usr/local/bin/netns-tool"),
+            Path::new(DEFAULT_PATH),
+        );
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/netns-tool restore --manifest /var/lib/netns-tool/manifest\n"
+        ));
+        assert!(unit.contains("Type=oneshot\n"));
+        assert!(unit.contains("ConditionPathExists=/var/lib/netns-tool/manifest\n"));
+        assert!(unit.ends_with("WantedBy=multi-user.target\n"));
+    }
+}
@@ -0,0 +1,319 @@
+//! pcapng output for `capture`: the part that needs no socket, so it can be
+//! tested without root.
+//!
+//! `capture` reads packets from an AF_PACKET socket inside the namespace
+//! and writes them as pcapng, which Wireshark and tcpdump read from a pipe
+//! as well as from a file:
+//!
+//! ```text
+//! sudo netns-tool capture web --stdout | wireshark -k -i -
+//! sudo netns-tool capture web --stdout | tcpdump -nr -
+//! ```
+//!
+//! A pcapng stream is a list of blocks. The writer emits a Section Header
+//! Block first, then an Interface Description Block the first time it sees
+//! each interface, then one Enhanced Packet Block per packet:
+//!
+//! ```text
+//! SHB (netns-tool) │ IDB 0 (eth0, Ethernet) │ EPB 0 │ EPB 0 │ IDB 1 (gre7, raw IP) │ EPB 1 │ ...
+//! ```
+//!
+//! Unlike classic pcap, every interface has its own link type, so a capture
+//! of all interfaces can mix Ethernet devices with layer-3 tunnels. Blocks
+//! are written in host byte order; the byte-order magic in the SHB tells
+//! readers which one that is.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Bytes of each packet kept by default: tcpdump's default, enough for any
+/// frame including GSO super-packets.
+pub const DEFAULT_SNAPLEN: u32 = 262_144;
+
+/// How often `capture` prints its progress line on stderr.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// LINKTYPE_ETHERNET: frames start with an Ethernet header.
+pub const LINKTYPE_ETHERNET: u16 = 1;
+/// LINKTYPE_RAW: packets start with the IP header (GRE, WireGuard, tun).
+pub const LINKTYPE_RAW: u16 = 101;
+
+const BLOCK_SHB: u32 = 0x0A0D_0D0A;
+const BLOCK_IDB: u32 = 0x0000_0001;
+const BLOCK_EPB: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const OPT_END: u16 = 0;
+const OPT_SHB_USERAPPL: u16 = 4;
+const OPT_IF_NAME: u16 = 2;
+const OPT_IF_TSRESOL: u16 = 9;
+
+/// Link type for an AF_PACKET `sll_hatype` (ARPHRD_* from
+/// `/usr/include/linux/if_arp.h`), or None for devices this tool does not
+/// decode.
+///
+/// Loopback frames carry a (zeroed) Ethernet header, like veths and bridges.
+pub fn linktype(arphrd: u16) -> Option<u16> {
+    match arphrd {
+        1 | 772 => Some(LINKTYPE_ETHERNET), // ARPHRD_ETHER, ARPHRD_LOOPBACK
+        // ARPHRD_IPGRE, ARPHRD_IP6GRE, ARPHRD_NONE (tun, wireguard)
+        778 | 823 | 0xFFFE => Some(LINKTYPE_RAW),
+        _ => None,
+    }
+}
+
+/// Writes a pcapng stream.
+///
+/// Interfaces are announced on first use, keyed by ifindex, so a capture of
+/// every interface only lists the ones that carried packets.
+pub struct Writer<W: Write> {
+    out: W,
+    snaplen: u32,
+    /// ifindex -> pcapng interface id
+    interfaces: HashMap<i32, u32>,
+}
+
+impl<W: Write> Writer<W> {
+    /// Start a stream: writes the Section Header Block.
+    pub fn new(mut out: W, snaplen: u32) -> io::Result<Self> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_ne_bytes());
+        body.extend_from_slice(&1u16.to_ne_bytes()); // major version
+        body.extend_from_slice(&0u16.to_ne_bytes()); // minor version
+        body.extend_from_slice(&(-1i64).to_ne_bytes()); // section length: unknown
+        option(&mut body, OPT_SHB_USERAPPL, b"netns-tool");
+        option_end(&mut body);
+        write_block(&mut out, BLOCK_SHB, &body)?;
+        Ok(Self {
+            out,
+            snaplen,
+            interfaces: HashMap::new(),
+        })
+    }
+
+    /// The pcapng id of interface `ifindex`, writing its Interface
+    /// Description Block if this is the first packet from it.
+    pub fn interface(&mut self, ifindex: i32, name: &str, linktype: u16) -> io::Result<u32> {
+        if let Some(&id) = self.interfaces.get(&ifindex) {
+            return Ok(id);
+        }
+        let mut body = Vec::new();
+        body.extend_from_slice(&linktype.to_ne_bytes());
+        body.extend_from_slice(&0u16.to_ne_bytes()); // reserved
+        body.extend_from_slice(&self.snaplen.to_ne_bytes());
+        option(&mut body, OPT_IF_NAME, name.as_bytes());
+        // Timestamps in nanoseconds (10^-9) instead of the default microseconds
+        option(&mut body, OPT_IF_TSRESOL, &[9]);
+        option_end(&mut body);
+        write_block(&mut self.out, BLOCK_IDB, &body)?;
+        let id = self.interfaces.len() as u32;
+        self.interfaces.insert(ifindex, id);
+        Ok(id)
+    }
+
+    /// Write one packet. `data` is cut to the snap length; `orig_len` is
+    /// the length on the wire, which may be larger than `data` already.
+    pub fn packet(
+        &mut self,
+        interface: u32,
+        unix_ns: u64,
+        orig_len: u32,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let data = &data[..data.len().min(self.snaplen as usize)];
+        let mut body = Vec::with_capacity(20 + data.len() + 3);
+        body.extend_from_slice(&interface.to_ne_bytes());
+        body.extend_from_slice(&((unix_ns >> 32) as u32).to_ne_bytes());
+        body.extend_from_slice(&(unix_ns as u32).to_ne_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_ne_bytes());
+        body.extend_from_slice(&orig_len.max(data.len() as u32).to_ne_bytes());
+        body.extend_from_slice(data);
+        pad(&mut body);
+        write_block(&mut self.out, BLOCK_EPB, &body)
+    }
+
+    /// Flush after every batch of packets: a reader on the other end of a
+    /// pipe only sees what was flushed.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+fn write_block<W: Write>(out: &mut W, kind: u32, body: &[u8]) -> io::Result<()> {
+    // Type, total length, body, total length again (so readers can walk back)
+    let len = (12 + body.len()) as u32;
+    let mut block = Vec::with_capacity(len as usize);
+    block.extend_from_slice(&kind.to_ne_bytes());
+    block.extend_from_slice(&len.to_ne_bytes());
+    block.extend_from_slice(body);
+    block.extend_from_slice(&len.to_ne_bytes());
+    out.write_all(&block)
+}
+
+fn option(body: &mut Vec<u8>, code: u16, value: &[u8]) {
+    body.extend_from_slice(&code.to_ne_bytes());
+    body.extend_from_slice(&(value.len() as u16).to_ne_bytes());
+    body.extend_from_slice(value);
+    pad(body);
+}
+
+fn option_end(body: &mut Vec<u8>) {
+    body.extend_from_slice(&OPT_END.to_ne_bytes());
+    body.extend_from_slice(&0u16.to_ne_bytes());
+}
+
+/// Pad to a multiple of 4 bytes, as every pcapng field must be.
+fn pad(body: &mut Vec<u8>) {
+    body.resize(body.len().next_multiple_of(4), 0);
+}
+
+/// Packet and byte counts for the progress line on stderr.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    pub packets: u64,
+    /// Bytes on the wire, not the (snaplen-cut) bytes written
+    pub bytes: u64,
+    last_report: Option<Instant>,
+}
+
+impl Progress {
+    pub fn record(&mut self, orig_len: u32) {
+        self.packets += 1;
+        self.bytes += u64::from(orig_len);
+    }
+
+    /// Whether the progress line is due at `now`: at most once per
+    /// [`PROGRESS_INTERVAL`], and not before the first packet.
+    pub fn due(&mut self, now: Instant) -> bool {
+        if self.packets == 0 {
+            return false;
+        }
+        match self.last_report {
+            Some(last) if now.duration_since(last) < PROGRESS_INTERVAL => false,
+            _ => {
+                self.last_report = Some(now);
+                true
+            }
+        }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} packets, {}", self.packets, human_bytes(self.bytes))
+    }
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(b: &[u8], at: usize) -> u32 {
+        u32::from_ne_bytes(b[at..at + 4].try_into().unwrap())
+    }
+
+    /// (type, body) of every block, checking both length fields.
+    fn blocks(mut b: &[u8]) -> Vec<(u32, Vec<u8>)> {
+        let mut out = Vec::new();
+        while !b.is_empty() {
+            let len = u32_at(b, 4) as usize;
+            assert_eq!(len % 4, 0, "block length {len} not padded");
+            assert_eq!(u32_at(b, len - 4) as usize, len, "trailing length");
+            out.push((u32_at(b, 0), b[8..len - 4].to_vec()));
+            b = &b[len..];
+        }
+        out
+    }
+
+    #[test]
+    fn test_stream_layout() {
+        let mut w = Writer::new(Vec::new(), DEFAULT_SNAPLEN).unwrap();
+        let eth0 = w.interface(2, "eth0", LINKTYPE_ETHERNET).unwrap();
+        w.packet(eth0, 1_700_000_000_123_456_789, 60, &[0xaa; 60])
+            .unwrap();
+        let gre = w.interface(5, "gre7", LINKTYPE_RAW).unwrap();
+        w.packet(gre, 1_700_000_001_000_000_000, 21, &[0x45; 21])
+            .unwrap();
+        // Known interface: no second IDB
+        assert_eq!(w.interface(2, "eth0", LINKTYPE_ETHERNET).unwrap(), eth0);
+
+        let bytes = w.into_inner();
+        let blocks = blocks(&bytes);
+        let kinds: Vec<u32> = blocks.iter().map(|(k, _)| *k).collect();
+        assert_eq!(
+            kinds,
+            [BLOCK_SHB, BLOCK_IDB, BLOCK_EPB, BLOCK_IDB, BLOCK_EPB]
+        );
+        assert_eq!(&bytes[..4], &[0x0A, 0x0D, 0x0D, 0x0A]);
+        assert_eq!(u32_at(&blocks[0].1, 0), BYTE_ORDER_MAGIC);
+
+        let idb = &blocks[3].1;
+        assert_eq!(u16::from_ne_bytes([idb[0], idb[1]]), LINKTYPE_RAW);
+        // if_name option right after linktype, reserved and snaplen
+        assert_eq!(&idb[12..16], b"gre7");
+
+        let epb = &blocks[2].1;
+        assert_eq!(u32_at(epb, 0), 0);
+        let ts = (u64::from(u32_at(epb, 4)) << 32) | u64::from(u32_at(epb, 8));
+        assert_eq!(ts, 1_700_000_000_123_456_789);
+        assert_eq!((u32_at(epb, 12), u32_at(epb, 16)), (60, 60));
+        let epb = &blocks[4].1;
+        assert_eq!(u32_at(epb, 0), 1);
+        // 21 bytes of data padded to 24
+        assert_eq!(epb.len(), 20 + 24);
+    }
+
+    #[test]
+    fn test_snaplen_cuts_packets() {
+        let mut w = Writer::new(Vec::new(), 64).unwrap();
+        let id = w.interface(1, "lo", LINKTYPE_ETHERNET).unwrap();
+        w.packet(id, 0, 1500, &[0; 1500]).unwrap();
+        let blocks = blocks(&w.into_inner());
+        let epb = &blocks[2].1;
+        assert_eq!((u32_at(epb, 12), u32_at(epb, 16)), (64, 1500));
+    }
+
+    #[test]
+    fn test_linktype() {
+        assert_eq!(linktype(1), Some(LINKTYPE_ETHERNET));
+        assert_eq!(linktype(772), Some(LINKTYPE_ETHERNET));
+        assert_eq!(linktype(778), Some(LINKTYPE_RAW));
+        assert_eq!(linktype(0xFFFE), Some(LINKTYPE_RAW));
+        assert_eq!(linktype(32), None); // ARPHRD_INFINIBAND
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut p = Progress::default();
+        let start = Instant::now();
+        assert!(!p.due(start), "nothing to report yet");
+        p.record(1500);
+        p.record(98);
+        assert!(p.due(start));
+        assert!(!p.due(start + Duration::from_millis(500)));
+        assert!(p.due(start + PROGRESS_INTERVAL));
+        assert_eq!(p.to_string(), "2 packets, 1.6 KiB");
+        assert_eq!(human_bytes(512), "512 B");
+        assert_eq!(human_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
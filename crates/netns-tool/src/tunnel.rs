@@ -0,0 +1,276 @@
+//! Overlay tunnels for `tunnel vxlan` and `tunnel gre`: the part that needs
+//! no kernel, so it can be tested without root.
+//!
+//! A veth pair connects namespaces on one host. To connect namespaces on
+//! two hosts, wrap their packets in packets between the hosts:
+//!
+//! ```text
+//!  host A (192.168.1.10)                         host B (192.168.1.20)
+//! ┌──────────────────────┐                      ┌──────────────────────┐
+//! │ netns web            │                      │ netns db             │
+//! │   vxlan42 10.200.0.1 │                      │   vxlan42 10.200.0.2 │
+//! └─────────┬────────────┘                      └────────────┬─────────┘
+//!           └─ UDP 4789 ── eth0 ════ network ════ eth0 ── UDP 4789 ─┘
+//! ```
+//!
+//! The tunnel device is created on the host, where the local address is,
+//! and then moved into the namespace. It keeps its socket in the namespace
+//! it was created in, so the namespace gets a private link across the
+//! hosts without any route to the physical network. This is how Flannel,
+//! Calico (VXLAN mode) and Docker overlay networks work.
+//!
+//! VXLAN carries Ethernet frames (the device can join a bridge); GRE here
+//! is the layer-3 kind, a point-to-point link for IP packets only. Both
+//! need the same id on both ends: the VNI for VXLAN, the key for GRE.
+
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::net::IpAddr;
+
+/// Standard VXLAN UDP port (IANA). Linux defaults to 8472 for historical
+/// reasons, so always pass it explicitly.
+pub const VXLAN_PORT: u16 = 4789;
+
+/// Largest VXLAN Network Identifier: the field is 24 bits.
+pub const MAX_VNI: u32 = (1 << 24) - 1;
+
+/// Longest interface name the kernel accepts (IFNAMSIZ minus the NUL).
+const MAX_IFNAME_LEN: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunnelKind {
+    Vxlan,
+    Gre,
+}
+
+impl fmt::Display for TunnelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TunnelKind::Vxlan => "vxlan",
+            TunnelKind::Gre => "gre",
+        })
+    }
+}
+
+/// Everything needed to create one end of a tunnel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TunnelSpec {
+    pub kind: TunnelKind,
+    /// Interface name, e.g. "vxlan42"
+    pub name: String,
+    /// VXLAN VNI or GRE key
+    pub id: u32,
+    /// Underlay address of this host
+    pub local: IpAddr,
+    /// Underlay address of the other end
+    pub remote: IpAddr,
+    /// VXLAN destination port (unused by GRE)
+    pub port: u16,
+    /// Address inside the tunnel, e.g. 10.200.0.1/24
+    pub address: Option<(IpAddr, u8)>,
+}
+
+impl TunnelSpec {
+    /// Check what the kernel would reject with a less helpful EINVAL.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.len() > MAX_IFNAME_LEN {
+            bail!(
+                "interface name {:?} must be 1 to {} characters",
+                self.name,
+                MAX_IFNAME_LEN
+            );
+        }
+        if self.kind == TunnelKind::Vxlan && self.id > MAX_VNI {
+            bail!(
+                "VXLAN id {} is above the 24-bit maximum {}",
+                self.id,
+                MAX_VNI
+            );
+        }
+        if self.local.is_ipv4() != self.remote.is_ipv4() {
+            bail!(
+                "--local {} and --remote {} must both be IPv4 or both IPv6",
+                self.local,
+                self.remote
+            );
+        }
+        if self.local == self.remote {
+            bail!("--local and --remote are both {}", self.local);
+        }
+        if self.remote.is_multicast() || self.remote.is_unspecified() {
+            bail!("--remote {} must be a unicast address", self.remote);
+        }
+        Ok(())
+    }
+
+    /// Kernel link kind: `ip link add ... type <kind>`.
+    pub fn link_kind(&self) -> &'static str {
+        match (self.kind, self.local.is_ipv6()) {
+            (TunnelKind::Vxlan, _) => "vxlan",
+            (TunnelKind::Gre, false) => "gre",
+            (TunnelKind::Gre, true) => "ip6gre",
+        }
+    }
+
+    /// Bytes each packet grows by in the underlay.
+    pub fn overhead(&self) -> u32 {
+        let outer_ip = if self.local.is_ipv6() { 40 } else { 20 };
+        match self.kind {
+            // UDP 8 + VXLAN 8 + the inner Ethernet header 14
+            TunnelKind::Vxlan => outer_ip + 8 + 8 + 14,
+            // GRE 4 + key 4
+            TunnelKind::Gre => outer_ip + 4 + 4,
+        }
+    }
+
+    /// MTU of the tunnel device over an underlay with `underlay_mtu`, so
+    /// the wrapped packets are not fragmented (or dropped, with DF set).
+    pub fn mtu(&self, underlay_mtu: u32) -> u32 {
+        underlay_mtu.saturating_sub(self.overhead())
+    }
+
+    /// The equivalent `ip link add` command, for `--dry-run` and errors.
+    pub fn ip_link_args(&self, dev: Option<&str>) -> Vec<String> {
+        let mut args: Vec<String> = ["link", "add", &self.name, "type", self.link_kind()]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        match self.kind {
+            TunnelKind::Vxlan => args.extend(["id".into(), self.id.to_string()]),
+            TunnelKind::Gre => args.extend(["key".into(), self.id.to_string()]),
+        }
+        args.extend([
+            "local".into(),
+            self.local.to_string(),
+            "remote".into(),
+            self.remote.to_string(),
+        ]);
+        if self.kind == TunnelKind::Vxlan {
+            args.extend(["dstport".into(), self.port.to_string()]);
+            if let Some(dev) = dev {
+                args.extend(["dev".into(), dev.to_string()]);
+            }
+        }
+        args
+    }
+}
+
+/// Default interface name: "vxlan42", "gre42".
+pub fn default_name(kind: TunnelKind, id: u32) -> String {
+    format!("{}{}", kind, id)
+}
+
+/// Parse "10.200.0.1/24" or "fd00::1/64".
+pub fn parse_cidr(text: &str) -> Result<(IpAddr, u8)> {
+    let (addr, prefix) = text
+        .split_once('/')
+        .with_context(|| format!("{:?} needs a prefix length, e.g. 10.200.0.1/24", text))?;
+    let addr: IpAddr = addr
+        .parse()
+        .with_context(|| format!("{:?} is not an IP address", addr))?;
+    let prefix: u8 = prefix
+        .parse()
+        .with_context(|| format!("{:?} is not a prefix length", prefix))?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    if prefix > max {
+        bail!("prefix length {} is above {} for {}", prefix, max, addr);
+    }
+    Ok((addr, prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vxlan() -> TunnelSpec {
+        TunnelSpec {
+            kind: TunnelKind::Vxlan,
+            name: default_name(TunnelKind::Vxlan, 42),
+            id: 42,
+            local: "192.168.1.10".parse().unwrap(),
+            remote: "192.168.1.20".parse().unwrap(),
+            port: VXLAN_PORT,
+            address: Some(parse_cidr("10.200.0.1/24").unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_ip_link_args() {
+        assert_eq!(
+            vxlan().ip_link_args(Some("eth0")).join(" "),
+            "link add vxlan42 type vxlan id 42 local 192.168.1.10 remote 192.168.1.20 dstport 4789 dev eth0"
+        );
+        let gre = TunnelSpec {
+            kind: TunnelKind::Gre,
+            name: default_name(TunnelKind::Gre, 7),
+            id: 7,
+            local: "fd00::1".parse().unwrap(),
+            remote: "fd00::2".parse().unwrap(),
+            ..vxlan()
+        };
+        assert_eq!(
+            gre.ip_link_args(Some("eth0")).join(" "),
+            "link add gre7 type ip6gre key 7 local fd00::1 remote fd00::2"
+        );
+    }
+
+    #[test]
+    fn test_mtu_leaves_room_for_headers() {
+        assert_eq!(vxlan().mtu(1500), 1450);
+        let gre = TunnelSpec {
+            kind: TunnelKind::Gre,
+            ..vxlan()
+        };
+        assert_eq!(gre.mtu(1500), 1472);
+        assert_eq!(gre.mtu(10), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(vxlan().validate().is_ok());
+        let cases = [
+            TunnelSpec {
+                id: MAX_VNI + 1,
+                ..vxlan()
+            },
+            TunnelSpec {
+                remote: "fd00::2".parse().unwrap(),
+                ..vxlan()
+            },
+            TunnelSpec {
+                remote: "192.168.1.10".parse().unwrap(),
+                ..vxlan()
+            },
+            TunnelSpec {
+                remote: "239.1.1.1".parse().unwrap(),
+                ..vxlan()
+            },
+            TunnelSpec {
+                name: "vxlan-much-too-long".into(),
+                ..vxlan()
+            },
+        ];
+        for spec in cases {
+            assert!(spec.validate().is_err(), "{:?}", spec);
+        }
+        // GRE keys are 32 bits
+        let gre = TunnelSpec {
+            kind: TunnelKind::Gre,
+            id: u32::MAX,
+            ..vxlan()
+        };
+        assert!(gre.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_cidr() {
+        assert_eq!(
+            parse_cidr("10.200.0.1/24").unwrap(),
+            ("10.200.0.1".parse().unwrap(), 24)
+        );
+        assert_eq!(parse_cidr("fd00::1/64").unwrap().1, 64);
+        assert!(parse_cidr("10.200.0.1").is_err());
+        assert!(parse_cidr("10.200.0.1/33").is_err());
+        assert!(parse_cidr("web/24").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+// Tests for the `firewall` subcommand (nftables policy inside a namespace)
+// Lesson: docs/01-namespaces/12-netns-firewall.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
+// 3. Refactor if needed
+//
+// NOTE: These tests require root privileges and the `nft` binary.
+// Run with: sudo -E cargo test -p netns-tool --test firewall_test
+
+#[test]
+fn test_firewall_applies_ruleset() {
+    // TODO: Write a test that verifies the ruleset is loaded in the namespace
+    //
+    // Hints:
+    // - Create a namespace first: `netns-tool create fw-test`
+    // - Run `netns-tool firewall fw-test --allow-out 80,443 --deny-in all`
+    // - Run `ip netns exec fw-test nft list table inet netns_tool`
+    // - Verify output contains "dport { 80, 443 }" and "policy drop"
+    //
+    // Test approach:
+    // 1. Create namespace
+    // 2. Apply firewall
+    // 3. Inspect ruleset from inside the namespace
+    // 4. Clean up: `netns-tool delete fw-test`
+
+    todo!("Implement test for applying a firewall ruleset")
+}
+
+#[test]
+fn test_firewall_rules_are_namespace_local() {
+    // TODO: Write a test that verifies the host ruleset is untouched
+    //
+    // Hints:
+    // - Apply the firewall to a namespace as above
+    // - Run `nft list tables` on the host
+    // - The host must NOT have an "inet netns_tool" table
+    //
+    // Why this matters: each network namespace has its own netfilter state
+
+    todo!("Implement test that firewall rules stay inside the namespace")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_firewall_show_and_flush() {
+    // TODO: Write a test for `firewall <ns> show` and `firewall <ns> flush`
+    //
+    // Hints:
+    // - After applying, `netns-tool firewall fw-test show` prints the table
+    // - `netns-tool firewall fw-test flush` removes it
+    // - A second flush should still succeed (idempotent)
+    // - `show` after flush should report that no ruleset is loaded
+
+    todo!("Implement test for firewall show and flush")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_firewall_blocks_disallowed_egress() {
+    // TODO: Write an integration test for egress policy
+    //
+    // Hints:
+    // - Set up namespace + veth + bridge + NAT (see nat_test.rs)
+    // - Apply `--allow-out 443`
+    // - TCP to port 443 should connect; TCP to port 80 should time out
+    // - Use `ip netns exec fw-test curl -m 3 ...` or a TcpStream with timeout
+
+    todo!("Implement integration test for egress filtering")
+}
@@ -0,0 +1,74 @@
+// Tests for --pid / --container namespace targets (veth, firewall)
+// Lesson: docs/01-namespaces/13-netns-targets.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement resolve_netns() and container_pid() in src/lib.rs (GREEN)
+// 3. Refactor if needed
+//
+// NOTE: These tests require root privileges.
+// Run with: sudo -E cargo test -p netns-tool --test target_test
+
+#[test]
+fn test_veth_into_pid_namespace() {
+    // TODO: Write a test that moves a veth end into an unnamed namespace
+    //
+    // Hints:
+    // - Start a process in a new network namespace that has no /run/netns
+    //   entry: `unshare --net sleep 30` (std::process::Command::spawn)
+    // - unshare execs sleep, so Child::id() is the PID to target. Wait until
+    //   /proc/<pid>/ns/net differs from /proc/self/ns/net before using it
+    // - Run `netns-tool veth tgt-host --pid <pid>`
+    // - Verify with `nsenter --net=/proc/<pid>/ns/net ip link show`
+    //
+    // Test approach:
+    // 1. Spawn the namespace holder
+    // 2. Create the veth pair with --pid
+    // 3. Verify the peer is inside the namespace, not on the host
+    // 4. Clean up: kill the holder (its namespace and the peer go with it)
+
+    todo!("Implement test for veth with a --pid target")
+}
+
+#[test]
+fn test_target_flags_are_exclusive() {
+    // TODO: Write a test that verifies a name, --pid and --container cannot
+    // be combined, and that one of them is required
+    //
+    // Hints:
+    // - `netns-tool veth h0 myns --pid 1` should fail (clap usage error)
+    // - `netns-tool veth h0 --pid 1 --container web` should fail
+    // - `netns-tool veth h0` should fail and mention "--pid"
+    // - These fail during argument parsing, so they do not need root
+
+    todo!("Implement test for mutually exclusive namespace targets")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_firewall_into_container() {
+    // TODO: Write a test that applies a firewall through --container
+    //
+    // Hints:
+    // - Create /sys/fs/cgroup/contain/tgt-test and move a namespace holder
+    //   (as in test_veth_into_pid_namespace) into it via cgroup.procs
+    // - Run `netns-tool firewall --container tgt-test --allow-out 443`
+    // - Verify with `nsenter --net=/proc/<pid>/ns/net nft list table inet netns_tool`
+    // - The host must still have no netns_tool table
+
+    todo!("Implement test for firewall with a --container target")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_missing_targets_fail_clearly() {
+    // TODO: Write a test that verifies clear errors for targets that do not exist
+    //
+    // Hints:
+    // - `--pid 999999`: error mentions the PID and "no such process"
+    // - `--container does-not-exist`: error mentions the cgroup path
+    // - `--container ../..`: rejected before touching the filesystem
+    // - An empty container cgroup: error says the container is not running
+
+    todo!("Implement test for missing --pid / --container targets")
+}
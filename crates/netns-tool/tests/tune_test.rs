@@ -0,0 +1,77 @@
+// Tests for the `tune` subcommand (veth MTU and offload checks)
+// Lesson: docs/01-namespaces/14-veth-tuning.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement check_veth(), find_veth_pair(), read_veth_end() and
+//    apply_fix() in src/lib.rs (GREEN)
+// 3. Refactor if needed
+//
+// NOTE: Most tests require root privileges.
+// Run with: sudo -E cargo test -p netns-tool --test tune_test
+
+#[test]
+fn test_tune_requires_target() {
+    // TODO: Write a test that verifies `tune` needs a namespace
+    //
+    // Hints:
+    // - `netns-tool tune` with no name, --pid or --container fails and
+    //   mentions "--pid"
+    // - `netns-tool tune web --pid 1` fails: the targets are exclusive
+    // - These fail during argument parsing, so they do not need root
+
+    todo!("Implement test for tune target validation")
+}
+
+#[test]
+fn test_tune_reports_mtu_mismatch() {
+    // TODO: Write a test that detects mismatched MTUs on a veth pair
+    //
+    // Hints:
+    // - Create a namespace and a veth pair (`netns-tool create tune-test`,
+    //   `netns-tool veth tune-host tune-test`)
+    // - Set a larger MTU on the namespace end only:
+    //   `ip netns exec tune-test ip link set <peer> mtu 9000`
+    // - Run `netns-tool tune tune-test --path-mtu 1500`
+    // - Assert success (findings are not errors), "[MTU]" and "9000" in stdout
+    // - Nothing was changed: the peer still has MTU 9000 afterwards
+    //
+    // Test approach:
+    // 1. Set up the mismatched pair
+    // 2. Run tune without --fix
+    // 3. Verify the report and that nothing changed
+    // 4. Clean up: `netns-tool delete tune-test`, `ip link del tune-host`
+
+    todo!("Implement test for MTU mismatch detection")
+}
+
+#[test]
+fn test_tune_fix_aligns_mtu() {
+    // TODO: Write a test that `--fix` sets both ends to the path MTU
+    //
+    // Hints:
+    // - Same setup as test_tune_reports_mtu_mismatch
+    // - Run `netns-tool tune tune-fix --path-mtu 1400 --fix`
+    // - Both ends now report mtu 1400 (`ip link show tune-host` on the host,
+    //   `ip netns exec tune-fix ip link show <peer>` inside)
+    // - Run tune again without --fix: "0 problem(s) found"
+
+    todo!("Implement test for --fix")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_tune_benchmark_disables_offloads() {
+    // TODO: Write a test that `--benchmark --fix` turns off TSO/GSO/GRO
+    //
+    // Hints:
+    // - Needs ethtool installed; skip if `ethtool --version` fails
+    // - After `netns-tool tune <ns> --benchmark --fix`,
+    //   `ethtool -k tune-host` shows
+    //   "tcp-segmentation-offload: off" and "generic-segmentation-offload: off"
+    //   (ethtool's legacy names for tx-tcp-segmentation / tx-generic-segmentation)
+    // - Without --benchmark, the same pair reports no [OFFLOAD] findings for
+    //   TSO/GSO: they are only a problem for measurements
+
+    todo!("Implement test for --benchmark")
+}
@@ -40,6 +40,37 @@ pub enum NamespaceKind {
     Time,
 }
 
+impl NamespaceKind {
+    /// File name under /proc/<pid>/ns/ ("pid", "mnt", "net", ...)
+    pub fn proc_name(&self) -> &'static str {
+        match self {
+            NamespaceKind::Pid => "pid",
+            NamespaceKind::Uts => "uts",
+            NamespaceKind::Ipc => "ipc",
+            NamespaceKind::Mount => "mnt",
+            NamespaceKind::Net => "net",
+            NamespaceKind::User => "user",
+            NamespaceKind::Cgroup => "cgroup",
+            NamespaceKind::Time => "time",
+        }
+    }
+
+    /// The CLONE_NEW* flag for clone3(), unshare() and setns(), from
+    /// <linux/sched.h> (so that it exists on every platform)
+    pub fn clone_flag(&self) -> u64 {
+        match self {
+            NamespaceKind::Pid => 0x2000_0000,
+            NamespaceKind::Uts => 0x0400_0000,
+            NamespaceKind::Ipc => 0x0800_0000,
+            NamespaceKind::Mount => 0x0002_0000,
+            NamespaceKind::Net => 0x4000_0000,
+            NamespaceKind::User => 0x1000_0000,
+            NamespaceKind::Cgroup => 0x0200_0000,
+            NamespaceKind::Time => 0x0000_0080,
+        }
+    }
+}
+
 impl std::fmt::Display for NamespaceKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -102,6 +133,10 @@ pub enum NsError {
     /// A namespace file does not exist
     #[error("namespace file not found: {path}")]
     NamespaceNotFound { path: PathBuf },
+
+    /// The operation needs Linux, and this build runs elsewhere (see src/platform.rs)
+    #[error("{operation} is only supported on Linux (this is {os})")]
+    UnsupportedPlatform { operation: String, os: &'static str },
 }
 
 impl NsError {
@@ -154,6 +189,14 @@ impl NsError {
         NsError::Fork(source)
     }
 
+    /// Create an UnsupportedPlatform error for the OS this build targets
+    pub fn unsupported_platform(operation: impl Into<String>) -> Self {
+        NsError::UnsupportedPlatform {
+            operation: operation.into(),
+            os: std::env::consts::OS,
+        }
+    }
+
     /// Create a SetHostname error
     pub fn set_hostname(hostname: impl Into<String>, source: nix::Error) -> Self {
         if source == nix::Error::EPERM {
@@ -187,6 +230,13 @@ mod tests {
         assert_eq!(NamespaceKind::Time.to_string(), "time");
     }
 
+    #[test]
+    fn test_namespace_kind_proc_name() {
+        assert_eq!(NamespaceKind::Mount.proc_name(), "mnt");
+        assert_eq!(NamespaceKind::Net.proc_name(), "net");
+        assert_eq!(NamespaceKind::Pid.proc_name(), "pid");
+    }
+
     #[test]
     fn test_create_namespace_error_display() {
         let err = NsError::CreateNamespace {
@@ -305,6 +355,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unsupported_platform_error() {
+        let err = NsError::unsupported_platform("ns-tool pid");
+        let msg = err.to_string();
+        assert!(
+            msg.starts_with("ns-tool pid is only supported on Linux"),
+            "{}",
+            msg
+        );
+        assert!(
+            msg.contains(std::env::consts::OS),
+            "Message should name the OS: {}",
+            msg
+        );
+    }
+
     #[test]
     fn test_error_source_chain() {
         use std::error::Error;
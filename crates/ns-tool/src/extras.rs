@@ -0,0 +1,371 @@
+//! Auxiliary isolation primitives (`ns-tool extras`)
+//!
+//! Namespaces are not the whole story. A few per-process properties are not
+//! namespaced at all, and a container runtime has to set them explicitly:
+//!
+//! | Property | Set with | Why a runtime cares |
+//! |----------|----------|---------------------|
+//! | Session keyring | `keyctl(KEYCTL_JOIN_SESSION_KEYRING)` | Keys are not namespaced: without a fresh keyring, the container shares the host's |
+//! | Dumpable flag | `prctl(PR_SET_DUMPABLE)` | Decides who may ptrace the process and read `/proc/<pid>/mem` |
+//! | No-new-privs | `prctl(PR_SET_NO_NEW_PRIVS)` | setuid binaries no longer gain privileges on `execve()` |
+//! | Personality | `personality()` | `ADDR_NO_RANDOMIZE` turns ASLR off for the next `execve()` |
+//!
+//! `ns-tool extras --sandbox` forks a child that unshares a user namespace,
+//! changes all four the way runc does, and prints them next to the parent's
+//! values:
+//!
+//! ```text
+//! $ ns-tool extras --sandbox
+//! PROPERTY          HOST                      SANDBOX
+//! user namespace    4026531837                4026532205
+//! session keyring   771962488 (_uid_ses.0)    1072038045 (_ses)
+//! ...
+//! dumpable          1                         0
+//! ```
+
+use anyhow::{bail, Context, Result};
+use nix::sys::wait::waitpid;
+use nix::unistd::{fork, pipe, ForkResult};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::platform;
+use crate::verify::{self, Verdict};
+
+// From <linux/keyctl.h>; libc does not export these on every target.
+const KEYCTL_GET_KEYRING_ID: libc::c_int = 0;
+const KEYCTL_JOIN_SESSION_KEYRING: libc::c_int = 1;
+const KEY_SPEC_USER_KEYRING: libc::c_long = -4;
+const KEY_SPEC_SESSION_KEYRING: libc::c_long = -3;
+
+/// `personality(0xffffffff)` queries the persona without changing it.
+const PERSONALITY_QUERY: libc::c_ulong = 0xffff_ffff;
+
+/// Persona flag bits from <linux/personality.h>, as printed by `extras`.
+const PERSONALITY_FLAGS: &[(u32, &str)] = &[
+    (0x0020000, "UNAME26"),
+    (0x0040000, "ADDR_NO_RANDOMIZE"),
+    (0x0080000, "FDPIC_FUNCPTRS"),
+    (0x0100000, "MMAP_PAGE_ZERO"),
+    (0x0200000, "ADDR_COMPAT_LAYOUT"),
+    (0x0400000, "READ_IMPLIES_EXEC"),
+    (0x0800000, "ADDR_LIMIT_32BIT"),
+    (0x1000000, "SHORT_INODE"),
+    (0x2000000, "WHOLE_SECONDS"),
+    (0x4000000, "STICKY_TIMEOUTS"),
+    (0x8000000, "ADDR_LIMIT_3GB"),
+];
+
+const ADDR_NO_RANDOMIZE: u32 = 0x0040000;
+
+/// A keyring as seen through `keyctl()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keyring {
+    /// Key serial number (what `keyctl show` prints)
+    pub serial: i32,
+    /// Description, e.g. "_ses" or "_uid.1000"
+    pub name: String,
+}
+
+/// The auxiliary properties of the calling process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuxState {
+    /// Inode of /proc/self/ns/user
+    pub user_ns: u64,
+    /// None if keyctl() is unavailable (e.g. blocked by a seccomp profile)
+    pub session_keyring: Option<Keyring>,
+    pub user_keyring: Option<Keyring>,
+    pub dumpable: bool,
+    pub no_new_privs: bool,
+    /// Raw persona: PER_* in the low byte, flag bits above
+    pub personality: u32,
+}
+
+impl AuxState {
+    /// Read the current process's values.
+    pub fn current() -> Result<Self> {
+        let persona =
+            platform::personality(PERSONALITY_QUERY).context("personality() query failed")?;
+        Ok(Self {
+            user_ns: verify::ns_inode(Path::new("/proc/self/ns/user"))?,
+            session_keyring: keyring(KEY_SPEC_SESSION_KEYRING).ok(),
+            user_keyring: keyring(KEY_SPEC_USER_KEYRING).ok(),
+            dumpable: platform::get_dumpable()?,
+            no_new_privs: platform::get_no_new_privs()?,
+            personality: persona,
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let key = |k: &Option<Keyring>| {
+            k.as_ref()
+                .map(|k| serde_json::json!({ "serial": k.serial, "name": k.name }))
+        };
+        serde_json::json!({
+            "user_ns": self.user_ns,
+            "session_keyring": key(&self.session_keyring),
+            "user_keyring": key(&self.user_keyring),
+            "dumpable": self.dumpable,
+            "no_new_privs": self.no_new_privs,
+            "personality": self.personality,
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let key = |v: &serde_json::Value| -> Option<Keyring> {
+            Some(Keyring {
+                serial: i32::try_from(v.get("serial")?.as_i64()?).ok()?,
+                name: v.get("name")?.as_str()?.to_string(),
+            })
+        };
+        Some(Self {
+            user_ns: value.get("user_ns")?.as_u64()?,
+            session_keyring: key(value.get("session_keyring")?),
+            user_keyring: key(value.get("user_keyring")?),
+            dumpable: value.get("dumpable")?.as_bool()?,
+            no_new_privs: value.get("no_new_privs")?.as_bool()?,
+            personality: u32::try_from(value.get("personality")?.as_u64()?).ok()?,
+        })
+    }
+}
+
+/// What the sandbox child saw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandbox {
+    /// False if unshare(CLONE_NEWUSER) was refused; the rest still runs
+    pub user_ns_created: bool,
+    /// Session keyring serial right after unshare, before joining a new one
+    pub keyring_after_unshare: Option<i32>,
+    /// Final state, after all changes
+    pub state: AuxState,
+}
+
+/// Fork a child that isolates itself like a container runtime would, and
+/// collect its view. The parent is never modified.
+pub fn sandbox() -> Result<Sandbox> {
+    let (read_fd, write_fd) = pipe().context("pipe() failed")?;
+
+    match unsafe { fork() }.context("fork() failed")? {
+        ForkResult::Child => {
+            drop(read_fd);
+            let message = match sandbox_child() {
+                Ok(sandbox) => serde_json::json!({
+                    "user_ns_created": sandbox.user_ns_created,
+                    "keyring_after_unshare": sandbox.keyring_after_unshare,
+                    "state": sandbox.state.to_json(),
+                }),
+                Err(e) => serde_json::json!({ "error": format!("{e:#}") }),
+            };
+            let mut pipe = std::fs::File::from(write_fd);
+            let ok = pipe.write_all(message.to_string().as_bytes()).is_ok();
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) }
+        }
+        ForkResult::Parent { child } => {
+            drop(write_fd);
+            let mut text = String::new();
+            std::fs::File::from(read_fd)
+                .read_to_string(&mut text)
+                .context("failed to read from sandbox child")?;
+            waitpid(child, None).context("waitpid() failed")?;
+
+            let message: serde_json::Value =
+                serde_json::from_str(&text).context("sandbox child sent no report")?;
+            if let Some(error) = message.get("error").and_then(|e| e.as_str()) {
+                bail!("sandbox child failed: {}", error);
+            }
+            let state = message
+                .get("state")
+                .and_then(AuxState::from_json)
+                .context("sandbox child sent an invalid report")?;
+            let keyring_after_unshare = message
+                .get("keyring_after_unshare")
+                .and_then(|v| v.as_i64())
+                .and_then(|serial| i32::try_from(serial).ok());
+            Ok(Sandbox {
+                user_ns_created: message
+                    .get("user_ns_created")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                keyring_after_unshare,
+                state,
+            })
+        }
+    }
+}
+
+fn sandbox_child() -> Result<Sandbox> {
+    let user_ns_created = platform::unshare_user().is_ok();
+    let keyring_after_unshare = keyring(KEY_SPEC_SESSION_KEYRING).ok().map(|k| k.serial);
+
+    // NULL name: a new anonymous session keyring ("_ses"), like runc's
+    // per-container keyring. Ignored when keyctl() is blocked.
+    let _ = platform::keyctl(KEYCTL_JOIN_SESSION_KEYRING, 0, 0);
+    platform::set_dumpable(false)?;
+    platform::set_no_new_privs()?;
+
+    let persona = platform::personality(PERSONALITY_QUERY)?;
+    platform::personality((persona | ADDR_NO_RANDOMIZE) as libc::c_ulong)?;
+
+    Ok(Sandbox {
+        user_ns_created,
+        keyring_after_unshare,
+        state: AuxState::current()?,
+    })
+}
+
+/// Resolve a special keyring id (KEY_SPEC_*) to its serial and name,
+/// without creating it.
+fn keyring(spec: libc::c_long) -> Result<Keyring> {
+    let serial = platform::keyctl(KEYCTL_GET_KEYRING_ID, spec as libc::c_ulong, 0)?;
+    let serial = i32::try_from(serial).context("keyring serial out of range")?;
+
+    let description = platform::keyctl_describe(serial)?;
+    let name = key_name(&description)
+        .context("unexpected key description")?
+        .to_string();
+    Ok(Keyring { serial, name })
+}
+
+/// Name from a KEYCTL_DESCRIBE string: "type;uid;gid;perm;name".
+fn key_name(description: &str) -> Option<&str> {
+    let mut fields = description.splitn(5, ';');
+    fields.next().filter(|key_type| !key_type.is_empty())?;
+    fields.nth(2)?;
+    fields.next()
+}
+
+/// Persona as PER_* plus flag names, e.g. "PER_LINUX | ADDR_NO_RANDOMIZE".
+pub fn format_personality(persona: u32) -> String {
+    let mut parts = vec![match persona & 0xff {
+        0x00 => "PER_LINUX".to_string(),
+        0x08 => "PER_LINUX32".to_string(),
+        other => format!("PER_{other:#04x}"),
+    }];
+    let mut unknown = persona & !0xff;
+    for (bit, name) in PERSONALITY_FLAGS {
+        if persona & bit != 0 {
+            parts.push(name.to_string());
+            unknown &= !bit;
+        }
+    }
+    if unknown != 0 {
+        parts.push(format!("{unknown:#x}"));
+    }
+    parts.join(" | ")
+}
+
+/// The table printed by `extras`: one column, or HOST and SANDBOX.
+pub fn render(host: &AuxState, sandbox: Option<&Sandbox>) -> String {
+    let rows = |s: &AuxState| {
+        let key = |k: &Option<Keyring>| match k {
+            Some(k) => format!("{} ({})", k.serial, k.name),
+            None => "unavailable".to_string(),
+        };
+        vec![
+            ("user namespace", s.user_ns.to_string()),
+            ("session keyring", key(&s.session_keyring)),
+            ("user keyring", key(&s.user_keyring)),
+            ("dumpable", u8::from(s.dumpable).to_string()),
+            ("no_new_privs", u8::from(s.no_new_privs).to_string()),
+            ("personality", format_personality(s.personality)),
+        ]
+    };
+
+    let mut out = String::new();
+    match sandbox {
+        None => {
+            out.push_str(&format!("{:<17} {}\n", "PROPERTY", "VALUE"));
+            for (name, value) in rows(host) {
+                out.push_str(&format!("{name:<17} {value}\n"));
+            }
+        }
+        Some(sandbox) => {
+            out.push_str(&format!(
+                "{:<17} {:<25} {}\n",
+                "PROPERTY", "HOST", "SANDBOX"
+            ));
+            for ((name, outside), (_, inside)) in rows(host).into_iter().zip(rows(&sandbox.state)) {
+                out.push_str(&format!("{name:<17} {outside:<25} {inside}\n"));
+            }
+            if !sandbox.user_ns_created {
+                out.push_str("note: unshare(CLONE_NEWUSER) was refused; the sandbox shares the host's user namespace\n");
+            }
+        }
+    }
+    out
+}
+
+/// `--verify` for `extras`.
+pub fn verdict(host: &AuxState, sandbox: Option<&Sandbox>) -> Verdict {
+    let mut verdict = Verdict::new("extras");
+    verdict.check("keyring_readable", host.session_keyring.is_some());
+    if let Some(sandbox) = sandbox {
+        let serial = |k: &Option<Keyring>| k.as_ref().map(|k| k.serial);
+        verdict
+            .check(
+                "keyring_inherited_across_unshare",
+                sandbox.keyring_after_unshare == serial(&host.session_keyring),
+            )
+            .check(
+                "session_keyring_differs",
+                serial(&sandbox.state.session_keyring) != serial(&host.session_keyring),
+            )
+            .check("dumpable_cleared", !sandbox.state.dumpable)
+            .check("no_new_privs_set", sandbox.state.no_new_privs)
+            .check(
+                "personality_applied",
+                sandbox.state.personality & ADDR_NO_RANDOMIZE != 0,
+            )
+            .check(
+                "host_unchanged",
+                AuxState::current().is_ok_and(|now| now == *host),
+            );
+    }
+    verdict
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_personality() {
+        assert_eq!(format_personality(0), "PER_LINUX");
+        assert_eq!(
+            format_personality(0x0040000),
+            "PER_LINUX | ADDR_NO_RANDOMIZE"
+        );
+        assert_eq!(
+            format_personality(0x0008 | 0x0400000),
+            "PER_LINUX32 | READ_IMPLIES_EXEC"
+        );
+        assert_eq!(format_personality(0x10000000), "PER_LINUX | 0x10000000");
+    }
+
+    #[test]
+    fn test_key_name() {
+        assert_eq!(key_name("keyring;0;0;3f1b0000;_ses"), Some("_ses"));
+        assert_eq!(
+            key_name("keyring;1000;1000;3f080000;_uid.1000"),
+            Some("_uid.1000")
+        );
+        assert_eq!(key_name("user;0;0;3f010000;a;b"), Some("a;b"));
+        assert_eq!(key_name("keyring;0;0"), None);
+    }
+
+    #[test]
+    fn test_state_json_roundtrip() {
+        let state = AuxState {
+            user_ns: 4026531837,
+            session_keyring: Some(Keyring {
+                serial: 353598353,
+                name: "_ses".into(),
+            }),
+            user_keyring: None,
+            dumpable: true,
+            no_new_privs: false,
+            personality: 0x0040000,
+        };
+        assert_eq!(AuxState::from_json(&state.to_json()), Some(state));
+    }
+}
@@ -0,0 +1,173 @@
+//! A minimal init for PID 1 of a new PID namespace
+//!
+//! The first process in a PID namespace has two jobs that nobody else does:
+//!
+//! - Reap orphans: a process whose parent exits is re-parented to PID 1 and
+//!   stays a zombie until PID 1 waits for it
+//! - Pass on signals: the kernel drops signals sent to PID 1 from inside its
+//!   namespace unless PID 1 has a handler, so a shell or server running as
+//!   PID 1 often ignores SIGTERM
+//!
+//! [`run`] does both for one command, the way tini and `docker run --init`
+//! do:
+//!
+//! ```text
+//! init (PID 1)  sigwait() on FORWARDED_SIGNALS + SIGCHLD
+//!   ├─ SIGCHLD       waitpid(-1, WNOHANG) until nothing is left to reap
+//!   ├─ SIGTERM ...   killpg(command's group, sig)
+//!   └─ command (PID 2, its own process group) ── its children ...
+//! ```
+//!
+//! The signals are blocked and taken with `sigwait()` instead of handled, so
+//! there is no handler that could run between a `waitpid()` and the next.
+//!
+//! Used by `ns-tool pid -- COMMAND`, and the reference for `contain init`
+//! (docs/fast-track/13-init.md).
+
+use std::ffi::CString;
+
+use anyhow::{bail, Context, Result};
+use nix::errno::Errno;
+use nix::sys::signal::{killpg, sigprocmask, SigSet, SigmaskHow, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::{execvp, fork, setpgid, ForkResult, Pid};
+
+/// Signals passed on to the command; everything else keeps its default
+/// action. SIGCHLD is init's own ("reap"), and SIGKILL and SIGSTOP cannot
+/// be caught at all.
+pub const FORWARDED_SIGNALS: &[Signal] = &[
+    Signal::SIGTERM,
+    Signal::SIGINT,
+    Signal::SIGHUP,
+    Signal::SIGQUIT,
+    Signal::SIGUSR1,
+    Signal::SIGUSR2,
+    Signal::SIGWINCH,
+];
+
+/// Exit code of the child when `command` could not be executed, as a shell
+/// reports a missing command.
+pub const EXIT_NOT_FOUND: i32 = 127;
+
+/// The exit code init reports for its command, like a shell does: the
+/// code itself, or 128 + the signal that killed it.
+pub fn exit_code(status: WaitStatus) -> i32 {
+    match status {
+        WaitStatus::Exited(_, code) => code,
+        WaitStatus::Signaled(_, signal, _) => 128 + signal as i32,
+        _ => 1,
+    }
+}
+
+/// Run `command` as the only child of this process, reaping every process
+/// re-parented to it and forwarding [`FORWARDED_SIGNALS`], until the
+/// command exits. Returns [`exit_code`] of the command; the caller exits
+/// with it.
+///
+/// Meant for the main thread of a single-threaded process, which PID 1 is:
+/// the signals are blocked in the calling thread only, and another thread
+/// with them unblocked would take SIGCHLD first.
+pub fn run(command: &[String]) -> Result<i32> {
+    if command.is_empty() {
+        bail!("init needs a command to run");
+    }
+    // Built before fork: the child only execs
+    let argv = command
+        .iter()
+        .map(|arg| CString::new(arg.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .context("command contains a NUL byte")?;
+
+    let mut waited = SigSet::empty();
+    for &signal in FORWARDED_SIGNALS {
+        waited.add(signal);
+    }
+    waited.add(Signal::SIGCHLD);
+    // SIGTTOU too, or handing the terminal to the command stops init
+    let mut blocked = waited;
+    blocked.add(Signal::SIGTTOU);
+    let mut old = SigSet::empty();
+    sigprocmask(SigmaskHow::SIG_BLOCK, Some(&blocked), Some(&mut old))
+        .context("failed to block signals")?;
+
+    // SAFETY: the child only calls async-signal-safe functions before exec
+    let child = match unsafe { fork() }.context("fork() failed")? {
+        ForkResult::Child => {
+            let _ = setpgid(Pid::from_raw(0), Pid::from_raw(0));
+            let _ = sigprocmask(SigmaskHow::SIG_SETMASK, Some(&old), None);
+            let _ = execvp(&argv[0], &argv);
+            // SAFETY: nothing of the parent's state is flushed or run
+            unsafe { libc::_exit(EXIT_NOT_FOUND) }
+        }
+        ForkResult::Parent { child } => child,
+    };
+    // Both sides set the group: whichever runs first, it is in place before
+    // the first signal is forwarded or the command reads the terminal
+    let _ = setpgid(child, child);
+    give_terminal(child);
+
+    let mut status = None;
+    loop {
+        let signal = waited.wait().context("sigwait() failed")?;
+        if signal != Signal::SIGCHLD {
+            // The whole group, as a terminal would: a pipeline gets it too.
+            // ESRCH: the command is already gone, its SIGCHLD is pending
+            let _ = killpg(child, signal);
+            continue;
+        }
+        reap(child, &mut status)?;
+        if let Some(status) = status {
+            return Ok(exit_code(status));
+        }
+    }
+}
+
+/// Wait for every child that has exited, keeping the status of `command`.
+fn reap(command: Pid, status: &mut Option<WaitStatus>) -> Result<()> {
+    loop {
+        match waitpid(Pid::from_raw(-1), Some(WaitPidFlag::WNOHANG)) {
+            Ok(WaitStatus::StillAlive) | Err(Errno::ECHILD) => return Ok(()),
+            Ok(reaped) if reaped.pid() == Some(command) => *status = Some(reaped),
+            Ok(_) | Err(Errno::EINTR) => {}
+            Err(e) => return Err(e).context("waitpid() failed"),
+        }
+    }
+}
+
+/// Make `group` the terminal's foreground group if init has it, so an
+/// interactive shell can read from it.
+fn give_terminal(group: Pid) {
+    // SAFETY: plain syscalls on fd 0, which may not be a terminal at all
+    unsafe {
+        if libc::isatty(0) == 1 && libc::tcgetpgrp(0) == libc::getpgrp() {
+            libc::tcsetpgrp(0, group.as_raw());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        let pid = Pid::from_raw(2);
+        assert_eq!(exit_code(WaitStatus::Exited(pid, 0)), 0);
+        assert_eq!(exit_code(WaitStatus::Exited(pid, 3)), 3);
+        assert_eq!(
+            exit_code(WaitStatus::Signaled(pid, Signal::SIGTERM, false)),
+            143
+        );
+        assert_eq!(
+            exit_code(WaitStatus::Signaled(pid, Signal::SIGKILL, true)),
+            137
+        );
+        assert_eq!(exit_code(WaitStatus::Stopped(pid, Signal::SIGSTOP)), 1);
+    }
+
+    #[test]
+    fn test_run_needs_a_command() {
+        assert!(run(&[]).is_err());
+        assert!(run(&["a\0b".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+mod error;
+mod extras;
+pub mod init;
+mod nsid;
+mod platform;
+pub mod spawn;
+mod verify;
+pub use error::{NamespaceKind, NsError, NsResult};
+pub use nsid::NamespaceId;
+use verify::Verdict;
+
+#[derive(Parser)]
+#[command(name = "ns-tool")]
+#[command(about = "Namespace learning tool (Rust-first rewrite)")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// After the normal output, print a one-line JSON verdict (see src/verify.rs)
+    #[arg(long, global = true)]
+    verify: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Pid {
+        /// Run COMMAND as PID 2 under a minimal init that reaps zombies and
+        /// forwards signals, instead of printing the PID
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
+    Uts,
+    Ipc,
+    Mount,
+    Net,
+    User,
+    Cgroup,
+    Time,
+    Setns,
+    Proc,
+    CheckCaps,
+    /// Show keyrings, dumpable, no_new_privs and personality (non-namespace isolation)
+    Extras {
+        /// Also fork a child that isolates these the way a runtime does, and compare
+        #[arg(long)]
+        sandbox: bool,
+    },
+    /// Create an ID-mapped bind mount inside a user namespace
+    IdmapMount {
+        /// Directory to bind (e.g., a rootfs owned by uid 1000)
+        #[arg(long)]
+        source: PathBuf,
+        /// Mount point for the ID-mapped view
+        #[arg(long)]
+        target: PathBuf,
+        /// Mapping as "inside:outside:count" (same format as /proc/PID/uid_map)
+        #[arg(long, default_value = "0:1000:1")]
+        map: String,
+    },
+}
+
+impl Command {
+    /// Subcommand name as typed on the command line
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Pid { .. } => "pid",
+            Command::Uts => "uts",
+            Command::Ipc => "ipc",
+            Command::Mount => "mount",
+            Command::Net => "net",
+            Command::User => "user",
+            Command::Cgroup => "cgroup",
+            Command::Time => "time",
+            Command::Setns => "setns",
+            Command::Proc => "proc",
+            Command::CheckCaps => "check-caps",
+            Command::Extras { .. } => "extras",
+            Command::IdmapMount { .. } => "idmap-mount",
+        }
+    }
+}
+
+/// Run ns-tool with parsed arguments: `main.rs` and the `isolation` CLI both
+/// end up here.
+pub fn run(cli: Cli) -> Result<()> {
+    // Every subcommand works on Linux namespaces or /proc: fail with a typed
+    // error up front on other systems (see src/platform.rs)
+    platform::require_linux(&format!("ns-tool {}", cli.command.name()))?;
+
+    match cli.command {
+        // TODO: Implement PID namespace subcommand
+        // Lesson: docs/01-namespaces/01-pid-namespace.md
+        // Tests: tests/pid_test.rs
+        //
+        // TDD Steps:
+        // 1. First, write tests in tests/pid_test.rs (RED)
+        // 2. Then implement this function to make tests pass (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Use nix::sched::unshare(CloneFlags::CLONE_NEWPID)
+        // - Fork a child process with nix::unistd::fork()
+        // - In child: getpid() should return 1
+        // - Print "PID inside namespace: {pid}"
+        // - With --verify: have the child report its PID and its
+        //   NamespaceId::current(NamespaceKind::Pid) back to the parent (e.g.,
+        //   dev and ino over a pipe), then print Verdict checks
+        //   namespace_created, inode_differs, child_pid_is_1
+        // - With a COMMAND: the child (PID 1) runs init::run(&command) and
+        //   exits with the code it returns; the parent waits and exits with
+        //   the child's code. init is already implemented, see src/init.rs
+        Command::Pid { command } => {
+            let _ = command; // Suppress unused warning
+            todo!("Implement PID namespace - write tests first!")
+        }
+
+        // TODO: Implement UTS namespace subcommand
+        // Lesson: docs/01-namespaces/02-uts-namespace.md
+        // Tests: tests/uts_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/uts_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Use nix::sched::unshare(CloneFlags::CLONE_NEWUTS)
+        // - Use nix::unistd::sethostname() to set custom hostname
+        // - Print old and new hostnames to verify isolation
+        // - With --verify: namespace_created, inode_differs, hostname_changed,
+        //   host_unchanged (compare the parent's hostname before and after)
+        Command::Uts => todo!("Implement UTS namespace - write tests first!"),
+
+        // TODO: Implement IPC namespace subcommand
+        // Lesson: docs/01-namespaces/03-ipc-namespace.md
+        // Tests: tests/ipc_test.rs
+        // Verify checks: namespace_created, inode_differs, queue_hidden_from_host
+        Command::Ipc => todo!("Implement IPC namespace - write tests first!"),
+
+        // TODO: Implement mount namespace subcommand
+        // Lesson: docs/01-namespaces/04-mount-namespace.md
+        // Tests: tests/mount_test.rs
+        // Verify checks: namespace_created, inode_differs, mount_hidden_from_host
+        Command::Mount => todo!("Implement mount namespace - write tests first!"),
+
+        // TODO: Implement network namespace subcommand
+        // Lesson: docs/01-namespaces/05-network-namespace.md
+        // Tests: (network tests are in netns-tool crate)
+        // Note: For basic network namespace creation, see netns-tool
+        // Verify checks: namespace_created, inode_differs, only_loopback
+        Command::Net => todo!("Implement network namespace - write tests first!"),
+
+        // TODO: Implement user namespace subcommand
+        // Lesson: docs/01-namespaces/06-user-namespace.md
+        // Tests: tests/user_test.rs
+        // Verify checks: namespace_created, inode_differs, uid_is_0_inside
+        Command::User => todo!("Implement user namespace - write tests first!"),
+
+        // TODO: Implement cgroup namespace subcommand
+        // Lesson: docs/01-namespaces/07-cgroup-namespace.md
+        // Tests: (cgroup tests are in cgroup-tool crate)
+        // Verify checks: namespace_created, inode_differs, cgroup_root_is_slash
+        Command::Cgroup => todo!("Implement cgroup namespace - write tests first!"),
+
+        // TODO: Implement time namespace subcommand
+        // Lesson: docs/01-namespaces/08-time-namespace.md
+        // Tests: (add tests/time_test.rs when implementing)
+        // Verify checks: namespace_created, inode_differs, boottime_offset_applied
+        Command::Time => todo!("Implement time namespace - write tests first!"),
+
+        // TODO: Implement setns subcommand (joining existing namespaces)
+        // Lesson: docs/01-namespaces/09-setns.md
+        // Tests: tests/setns_test.rs
+        // Verify checks: joined_namespace (NamespaceId::current(kind) now equals
+        // NamespaceId::of_pid(target, kind); compare ids, not readlink text)
+        Command::Setns => todo!("Implement setns - write tests first!"),
+
+        // This is already implemented as a reference example
+        // Study this before implementing other subcommands
+        Command::Proc => {
+            print_proc_ns()?;
+            if cli.verify {
+                proc_verdict()?.print();
+            }
+        }
+
+        // TODO: Implement check-caps subcommand (capability inspection)
+        // Lesson: docs/00-foundations/04-permissions-and-sudo.md
+        // Tests: tests/caps_test.rs
+        //
+        // TDD Steps:
+        // 1. First, write tests in tests/caps_test.rs (RED)
+        // 2. Then implement this function to make tests pass (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Read /proc/self/status to get CapEff (effective capabilities)
+        // - Parse the hex value to check for CAP_SYS_ADMIN (bit 21)
+        // - Report which namespaces can be created with current privileges
+        Command::CheckCaps => todo!("Implement check-caps - write tests first!"),
+
+        // Implemented: study with docs/00-foundations/07-auxiliary-isolation.md
+        // Tests: tests/extras_test.rs
+        Command::Extras { sandbox } => {
+            let host = extras::AuxState::current()?;
+            let inside = if sandbox {
+                Some(extras::sandbox()?)
+            } else {
+                None
+            };
+            print!("{}", extras::render(&host, inside.as_ref()));
+            if cli.verify {
+                extras::verdict(&host, inside.as_ref()).print();
+            }
+        }
+
+        // TODO: Implement idmap-mount subcommand (ID-mapped mounts)
+        // Lesson: docs/01-namespaces/11-idmapped-mounts.md
+        // Tests: tests/idmap_test.rs
+        //
+        // TDD Steps:
+        // 1. First, write tests in tests/idmap_test.rs (RED)
+        // 2. Then implement this function to make tests pass (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Parse `map` into (inside, outside, count) - see IdMapping below
+        // - Create a helper user namespace that carries the mapping: fork a
+        //   child that unshare(CLONE_NEWUSER)s and waits, write uid_map/gid_map
+        //   for it from the parent, then open /proc/<child>/ns/user as userns_fd
+        // - open_tree(AT_FDCWD, source, OPEN_TREE_CLONE | OPEN_TREE_CLOEXEC)
+        //   via libc::syscall(libc::SYS_open_tree, ...) -> detached mount fd
+        // - mount_setattr(fd, "", AT_EMPTY_PATH, &mount_attr { attr_set:
+        //   MOUNT_ATTR_IDMAP, userns_fd, .. }, size_of::<mount_attr>())
+        // - move_mount(fd, "", AT_FDCWD, target, MOVE_MOUNT_F_EMPTY_PATH)
+        // - stat() a file through source and target and print both owners
+        //   ("source: uid=1000 gid=1000" / "target: uid=0 gid=0")
+        Command::IdmapMount {
+            source,
+            target,
+            map,
+        } => todo!(
+            "Implement idmap-mount - write tests first! (source: {}, target: {}, map: {map})",
+            source.display(),
+            target.display()
+        ),
+    }
+
+    Ok(())
+}
+
+fn print_proc_ns() -> Result<()> {
+    let ns_path = "/proc/self/ns";
+
+    // Using anyhow's Context trait to add context to errors
+    let entries = std::fs::read_dir(ns_path)
+        .with_context(|| format!("failed to read namespace directory: {}", ns_path))?;
+
+    for entry in entries {
+        let entry = entry.with_context(|| "failed to read directory entry")?;
+        let name = entry.file_name();
+        let target = std::fs::read_link(entry.path())
+            .with_context(|| format!("failed to read symlink: {}", entry.path().display()))?;
+        println!("{} -> {}", name.to_string_lossy(), target.display());
+    }
+    Ok(())
+}
+
+/// `--verify` for `proc`: every entry in /proc/self/ns is a readable link
+/// with an inode number, and the common namespaces are present.
+fn proc_verdict() -> Result<Verdict> {
+    let ns_dir = std::path::Path::new("/proc/self/ns");
+    let mut names = Vec::new();
+    let mut inodes_readable = true;
+
+    for entry in std::fs::read_dir(ns_dir)
+        .with_context(|| format!("failed to read namespace directory: {}", ns_dir.display()))?
+    {
+        let entry = entry.with_context(|| "failed to read directory entry")?;
+        names.push(entry.file_name().to_string_lossy().into_owned());
+        inodes_readable &= verify::ns_inode(&entry.path()).is_ok();
+    }
+
+    let mut verdict = Verdict::new("proc");
+    verdict
+        .check(
+            "lists_namespaces",
+            ["pid", "net", "mnt", "uts", "ipc", "user"]
+                .iter()
+                .all(|ns| names.iter().any(|name| name == ns)),
+        )
+        .check("inodes_readable", inodes_readable);
+    Ok(verdict)
+}
+
+/// One line of a uid_map/gid_map: `inside outside count`.
+///
+/// Used by `idmap-mount` to describe how file owners on the source directory
+/// should appear through the ID-mapped mount.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IdMapping {
+    /// First ID as seen through the mount (e.g., 0)
+    inside: u32,
+    /// First ID as stored on disk (e.g., 1000)
+    outside: u32,
+    /// Number of consecutive IDs mapped
+    count: u32,
+}
+
+/// Parse "inside:outside:count" (e.g., "0:1000:1") into an IdMapping.
+#[allow(dead_code)]
+fn parse_id_mapping(spec: &str) -> Result<IdMapping> {
+    // TODO: Implement in lesson 11 (ID-mapped mounts)
+    // Hints:
+    // - spec.split(':') must yield exactly three u32 values
+    // - Reject count == 0 and ranges that overflow u32
+    // - Use anyhow::bail! with the offending spec in the message
+    let _ = spec;
+    todo!("Implement ID mapping parsing")
+}
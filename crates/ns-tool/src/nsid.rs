@@ -0,0 +1,141 @@
+//! Namespace identity that survives procfs formatting (`NamespaceId`)
+//!
+//! `readlink /proc/self/ns/pid` prints `pid:[4026531836]`, and comparing
+//! those strings is the obvious way to ask "same namespace?". It is also
+//! fragile: `pid` and `pid_for_children` print different prefixes for the
+//! same namespace, and the text is a procfs presentation detail, not an API.
+//!
+//! What the kernel does promise is the file behind the link. `stat()` on
+//! `/proc/<pid>/ns/<kind>` follows it into the nsfs filesystem, and two
+//! namespace files refer to the same namespace exactly when their device
+//! and inode numbers match (see ioctl_ns(2), "Discovering namespace
+//! relationships"):
+//!
+//! ```text
+//! $ stat -L -c '%d:%i' /proc/self/ns/pid /proc/1/ns/pid
+//! 4:4026531836
+//! 4:4026531836          <- same namespace
+//! ```
+//!
+//! Compare [`NamespaceId`] values, or put them in a `HashSet`/`HashMap`,
+//! instead of parsing link text.
+
+use crate::error::{NamespaceKind, NsError, NsResult};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// Device and inode of a namespace file: equal ids mean the same namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NamespaceId {
+    /// `st_dev` of the nsfs file
+    pub dev: u64,
+    /// `st_ino` of the nsfs file, the number shown in `pid:[4026531836]`
+    pub ino: u64,
+}
+
+impl NamespaceId {
+    /// Identify the namespace a file refers to: a `/proc/<pid>/ns/<kind>`
+    /// link, or a bind mount of one such as `/run/netns/<name>`.
+    pub fn of(path: impl AsRef<Path>) -> NsResult<Self> {
+        let path = path.as_ref();
+        // metadata() follows the symlink; symlink_metadata() would stat the
+        // link itself, which every process has its own copy of
+        let meta = std::fs::metadata(path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                NsError::NamespaceNotFound {
+                    path: path.to_path_buf(),
+                }
+            } else {
+                NsError::proc_read(path, e)
+            }
+        })?;
+        Ok(Self {
+            dev: meta.dev(),
+            ino: meta.ino(),
+        })
+    }
+
+    /// The `kind` namespace of process `pid`.
+    pub fn of_pid(pid: u32, kind: NamespaceKind) -> NsResult<Self> {
+        Self::of(ns_path(&pid.to_string(), kind))
+    }
+
+    /// The `kind` namespace of the calling process.
+    pub fn current(kind: NamespaceKind) -> NsResult<Self> {
+        Self::of(ns_path("self", kind))
+    }
+}
+
+/// Only the inode, like `lsns` and the link text: the device is the same
+/// nsfs for every namespace on a running system.
+impl std::fmt::Display for NamespaceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ino)
+    }
+}
+
+/// `/proc/<process>/ns/<kind>`, where `process` is a PID or "self".
+pub fn ns_path(process: &str, kind: NamespaceKind) -> PathBuf {
+    PathBuf::from(format!("/proc/{}/ns/{}", process, kind.proc_name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_ns_path() {
+        assert_eq!(
+            ns_path("self", NamespaceKind::Mount),
+            PathBuf::from("/proc/self/ns/mnt")
+        );
+        assert_eq!(
+            ns_path("42", NamespaceKind::Net),
+            PathBuf::from("/proc/42/ns/net")
+        );
+    }
+
+    #[test]
+    fn test_display_is_inode() {
+        let id = NamespaceId {
+            dev: 4,
+            ino: 4026531836,
+        };
+        assert_eq!(id.to_string(), "4026531836");
+    }
+
+    #[test]
+    fn test_ids_hash_by_dev_and_ino() {
+        let a = NamespaceId { dev: 4, ino: 10 };
+        let set: HashSet<_> = [a, a, NamespaceId { dev: 5, ino: 10 }].into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_self_and_own_pid_are_the_same_namespace() {
+        let pid = std::process::id();
+        for kind in [NamespaceKind::Pid, NamespaceKind::Net, NamespaceKind::Mount] {
+            assert_eq!(
+                NamespaceId::current(kind).unwrap(),
+                NamespaceId::of_pid(pid, kind).unwrap()
+            );
+        }
+        // Different link text, same namespace: the case string compares get wrong
+        assert_eq!(
+            NamespaceId::of("/proc/self/ns/pid").unwrap(),
+            NamespaceId::of("/proc/self/ns/pid_for_children").unwrap()
+        );
+        assert_ne!(
+            NamespaceId::current(NamespaceKind::Pid).unwrap(),
+            NamespaceId::current(NamespaceKind::Net).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_missing_file_is_namespace_not_found() {
+        let err = NamespaceId::of("/proc/self/ns/does-not-exist").unwrap_err();
+        assert!(matches!(err, NsError::NamespaceNotFound { .. }), "{err}");
+    }
+}
@@ -0,0 +1,349 @@
+//! Linux-only syscalls behind one gate
+//!
+//! Namespaces, keyrings, `prctl()` and `personality()` only exist on Linux,
+//! but most of ns-tool does not call them: output formatting, JSON reports
+//! and the parsers are plain Rust. Keeping every Linux-only call in this
+//! module lets the crate compile on macOS too, so contributors there can
+//! edit it with a working language server and run the unit tests:
+//!
+//! ```text
+//! cargo test -p ns-tool --lib        # works on macOS
+//! cargo run -p ns-tool -- proc       # Error: ns-tool proc is only supported on Linux (this is macos)
+//! ```
+//!
+//! On Linux the functions below are thin wrappers. Everywhere else they
+//! fail with [`NsError::UnsupportedPlatform`], and `run()` checks
+//! [`require_linux`] before any subcommand starts, so users see that error
+//! instead of a missing /proc file.
+//!
+//! New code that needs a Linux-only API adds a wrapper here (both halves)
+//! rather than a `#[cfg]` at the call site.
+
+use crate::error::{NsError, NsResult};
+
+pub use imp::*;
+
+/// Ok on Linux; [`NsError::UnsupportedPlatform`] for `operation` elsewhere.
+pub fn require_linux(operation: &str) -> NsResult<()> {
+    if cfg!(target_os = "linux") {
+        Ok(())
+    } else {
+        Err(NsError::unsupported_platform(operation))
+    }
+}
+
+/// `struct clone_args` from <linux/sched.h>, up to `cgroup` (Linux 5.7).
+///
+/// The kernel takes the size along with the struct: an older kernel that
+/// does not know `cgroup` refuses it with E2BIG instead of ignoring it.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CloneArgs {
+    /// CLONE_* flags, namespaces included
+    pub flags: u64,
+    /// With CLONE_PIDFD: where the kernel stores the child's pidfd (an i32)
+    pub pidfd: u64,
+    pub child_tid: u64,
+    pub parent_tid: u64,
+    /// Signal sent to the parent when the child exits (SIGCHLD)
+    pub exit_signal: u64,
+    /// No CLONE_VM: 0, the child runs on a copy of the parent's stack
+    pub stack: u64,
+    pub stack_size: u64,
+    pub tls: u64,
+    pub set_tid: u64,
+    pub set_tid_size: u64,
+    /// With CLONE_INTO_CGROUP: an O_DIRECTORY fd of the target cgroup
+    pub cgroup: u64,
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::CloneArgs;
+    use anyhow::{Context, Result};
+    use nix::sched::{unshare, CloneFlags};
+    use nix::sys::prctl;
+    use std::io;
+    use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    /// `personality(persona)`: returns the previous persona. Pass
+    /// 0xffffffff to query without changing it.
+    pub fn personality(persona: libc::c_ulong) -> Result<u32> {
+        let previous = unsafe { libc::personality(persona) };
+        if previous == -1 {
+            return Err(std::io::Error::last_os_error()).context("personality() failed");
+        }
+        Ok(previous as u32)
+    }
+
+    /// Raw `keyctl(op, arg2, arg3)`.
+    pub fn keyctl(
+        op: libc::c_int,
+        arg2: libc::c_ulong,
+        arg3: libc::c_ulong,
+    ) -> Result<libc::c_long> {
+        let ret = unsafe { libc::syscall(libc::SYS_keyctl, op, arg2, arg3, 0, 0) };
+        if ret < 0 {
+            return Err(std::io::Error::last_os_error()).context("keyctl() failed");
+        }
+        Ok(ret)
+    }
+
+    /// `keyctl(KEYCTL_DESCRIBE, serial)`: "type;uid;gid;perm;name".
+    pub fn keyctl_describe(serial: i32) -> Result<String> {
+        // From <linux/keyctl.h>
+        const KEYCTL_DESCRIBE: libc::c_int = 6;
+
+        let mut buf = vec![0u8; 256];
+        let len = unsafe {
+            libc::syscall(
+                libc::SYS_keyctl,
+                KEYCTL_DESCRIBE,
+                serial as libc::c_long,
+                buf.as_mut_ptr(),
+                buf.len(),
+            )
+        };
+        if len < 0 {
+            return Err(std::io::Error::last_os_error()).context("KEYCTL_DESCRIBE failed");
+        }
+        buf.truncate((len as usize).min(buf.len()));
+        Ok(String::from_utf8_lossy(&buf)
+            .trim_end_matches('\0')
+            .to_string())
+    }
+
+    pub fn get_dumpable() -> Result<bool> {
+        prctl::get_dumpable().context("PR_GET_DUMPABLE failed")
+    }
+
+    pub fn set_dumpable(dumpable: bool) -> Result<()> {
+        prctl::set_dumpable(dumpable).context("PR_SET_DUMPABLE failed")
+    }
+
+    pub fn get_no_new_privs() -> Result<bool> {
+        prctl::get_no_new_privs().context("PR_GET_NO_NEW_PRIVS failed")
+    }
+
+    pub fn set_no_new_privs() -> Result<()> {
+        prctl::set_no_new_privs().context("PR_SET_NO_NEW_PRIVS failed")
+    }
+
+    /// `unshare(CLONE_NEWUSER)` for the calling process.
+    pub fn unshare_user() -> Result<()> {
+        unshare(CloneFlags::CLONE_NEWUSER).context("unshare(CLONE_NEWUSER) failed")
+    }
+
+    /// `unshare(flags)` with raw CLONE_NEW* flags, for the calling thread.
+    /// Async-signal-safe, so a child can call it between fork and exec.
+    pub fn unshare_flags(flags: u64) -> io::Result<()> {
+        if unsafe { libc::unshare(flags as libc::c_int) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// `setns(fd, nstype)` for the calling thread.
+    pub fn setns_fd(fd: BorrowedFd<'_>, nstype: u64) -> io::Result<()> {
+        if unsafe { libc::setns(fd.as_raw_fd(), nstype as libc::c_int) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Raw `clone3(args)`: the child's PID in the parent, 0 in the child.
+    /// The error keeps its errno, so callers can fall back on ENOSYS.
+    ///
+    /// # Safety
+    ///
+    /// As for `fork()`: in a multithreaded process the child may only call
+    /// async-signal-safe functions until it execs or exits.
+    pub unsafe fn clone3(args: &mut CloneArgs) -> io::Result<libc::pid_t> {
+        let ret = libc::syscall(
+            libc::SYS_clone3,
+            args as *mut CloneArgs,
+            std::mem::size_of::<CloneArgs>(),
+        );
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ret as libc::pid_t)
+    }
+
+    /// `pidfd_open(pid)` (Linux 5.3).
+    pub fn pidfd_open(pid: libc::pid_t) -> io::Result<OwnedFd> {
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel just returned this fd, nothing else owns it
+        Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+    }
+
+    /// `pidfd_send_signal(pidfd, signal)` (Linux 5.1).
+    pub fn pidfd_send_signal(pidfd: BorrowedFd<'_>, signal: libc::c_int) -> io::Result<()> {
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                pidfd.as_raw_fd(),
+                signal,
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// `waitid(P_PIDFD, pidfd, WEXITED)` (Linux 5.4): reap the child.
+    pub fn waitid_pidfd(pidfd: BorrowedFd<'_>) -> io::Result<ExitStatus> {
+        // From <linux/wait.h>
+        const P_PIDFD: libc::idtype_t = 3;
+
+        // SAFETY: an all-zero siginfo_t is valid, and waitid only writes to it
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        loop {
+            let ret = unsafe {
+                libc::waitid(
+                    P_PIDFD,
+                    pidfd.as_raw_fd() as libc::id_t,
+                    &mut info,
+                    libc::WEXITED,
+                )
+            };
+            if ret == 0 {
+                break;
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+        // SAFETY: waitid filled in a SIGCHLD siginfo
+        let status = unsafe { info.si_status() };
+        // Back to the wait status encoding ExitStatus expects
+        Ok(ExitStatus::from_raw(match info.si_code {
+            libc::CLD_EXITED => (status & 0xff) << 8,
+            libc::CLD_DUMPED => status | 0x80,
+            _ => status,
+        }))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::CloneArgs;
+    use crate::error::NsError;
+    use anyhow::Result;
+    use std::io;
+    use std::os::fd::{BorrowedFd, OwnedFd};
+    use std::process::ExitStatus;
+
+    fn unsupported<T>(operation: &str) -> Result<T> {
+        Err(NsError::unsupported_platform(operation).into())
+    }
+
+    pub fn personality(_persona: libc::c_ulong) -> Result<u32> {
+        unsupported("personality()")
+    }
+
+    pub fn keyctl(
+        _op: libc::c_int,
+        _arg2: libc::c_ulong,
+        _arg3: libc::c_ulong,
+    ) -> Result<libc::c_long> {
+        unsupported("keyctl()")
+    }
+
+    pub fn keyctl_describe(_serial: i32) -> Result<String> {
+        unsupported("keyctl()")
+    }
+
+    pub fn get_dumpable() -> Result<bool> {
+        unsupported("PR_GET_DUMPABLE")
+    }
+
+    pub fn set_dumpable(_dumpable: bool) -> Result<()> {
+        unsupported("PR_SET_DUMPABLE")
+    }
+
+    pub fn get_no_new_privs() -> Result<bool> {
+        unsupported("PR_GET_NO_NEW_PRIVS")
+    }
+
+    pub fn set_no_new_privs() -> Result<()> {
+        unsupported("PR_SET_NO_NEW_PRIVS")
+    }
+
+    pub fn unshare_user() -> Result<()> {
+        unsupported("unshare(CLONE_NEWUSER)")
+    }
+
+    fn unsupported_io<T>(operation: &str) -> io::Result<T> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            NsError::unsupported_platform(operation),
+        ))
+    }
+
+    pub fn unshare_flags(_flags: u64) -> io::Result<()> {
+        unsupported_io("unshare()")
+    }
+
+    pub fn setns_fd(_fd: BorrowedFd<'_>, _nstype: u64) -> io::Result<()> {
+        unsupported_io("setns()")
+    }
+
+    /// # Safety
+    ///
+    /// Always fails; unsafe to match the Linux half.
+    pub unsafe fn clone3(_args: &mut CloneArgs) -> io::Result<libc::pid_t> {
+        unsupported_io("clone3()")
+    }
+
+    pub fn pidfd_open(_pid: libc::pid_t) -> io::Result<OwnedFd> {
+        unsupported_io("pidfd_open()")
+    }
+
+    pub fn pidfd_send_signal(_pidfd: BorrowedFd<'_>, _signal: libc::c_int) -> io::Result<()> {
+        unsupported_io("pidfd_send_signal()")
+    }
+
+    pub fn waitid_pidfd(_pidfd: BorrowedFd<'_>) -> io::Result<ExitStatus> {
+        unsupported_io("waitid(P_PIDFD)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_linux() {
+        let result = require_linux("ns-tool pid");
+        if cfg!(target_os = "linux") {
+            assert!(result.is_ok());
+        } else {
+            match result {
+                Err(NsError::UnsupportedPlatform { operation, .. }) => {
+                    assert_eq!(operation, "ns-tool pid");
+                }
+                other => panic!("expected UnsupportedPlatform, got {other:?}"),
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn test_syscalls_fail_with_typed_error() {
+        let err = personality(0xffff_ffff).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NsError>(),
+            Some(NsError::UnsupportedPlatform { .. })
+        ));
+    }
+}
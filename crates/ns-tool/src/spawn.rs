@@ -0,0 +1,406 @@
+//! Creating a child in new namespaces, and in a cgroup, in one step
+//!
+//! `unshare()` + `fork()` is how the lessons start, but it leaves two gaps
+//! that a runtime cares about:
+//!
+//! - The child starts in the parent's cgroup and is moved afterwards. Until
+//!   the move, whatever it does (forks, allocations) is charged to, and
+//!   limited by, the wrong cgroup
+//! - The parent knows the child by PID only. Once the child is reaped the
+//!   PID can be reused, and a late `kill(pid)` hits another process
+//!
+//! `clone3()` (Linux 5.3) closes both: CLONE_INTO_CGROUP (5.7) creates the
+//! child directly in the target cgroup, and CLONE_PIDFD returns a pidfd, a
+//! file descriptor that refers to this one child for as long as it is open.
+//! [`Child::signal`] and [`Child::wait`] go through it.
+//!
+//! ```text
+//! clone3 backend                         fork+unshare fallback
+//! ──────────────                         ─────────────────────
+//! clone3(CLONE_NEW* | CLONE_PIDFD        unshare(CLONE_NEWPID) in this thread
+//!        | CLONE_INTO_CGROUP)            fork() ──► child: "0" > cgroup.procs
+//!   └──► child, already in the                             unshare(other CLONE_NEW*)
+//!        cgroup and the namespaces       setns() back, pidfd_open(child)
+//! ```
+//!
+//! [`spawn`] tries clone3 and falls back when the kernel, or a seccomp
+//! profile such as Docker's default one, refuses it with ENOSYS, or when the
+//! kernel knows clone3 but not CLONE_INTO_CGROUP (E2BIG). [`Child::backend`]
+//! tells which one ran.
+//!
+//! Used by `contain` and `cgroup-tool experiment`. See
+//! docs/01-namespaces/17-clone3.md.
+
+use std::ffi::CString;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::ExitStatusExt;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+
+use anyhow::{Context, Result};
+
+use crate::error::NamespaceKind;
+use crate::platform::{self, CloneArgs};
+
+// From <linux/sched.h>
+const CLONE_PIDFD: u64 = 0x1000;
+const CLONE_INTO_CGROUP: u64 = 0x2_0000_0000;
+
+/// Exit code of a fallback child that could not join its cgroup or create
+/// its namespaces, before the child function ran. 125, as `env` and
+/// container runtimes use it for "the runner failed, not the command".
+pub const EXIT_SETUP_FAILED: i32 = 125;
+
+/// How the child was created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// One `clone3()` call: namespaces, cgroup and pidfd at once
+    Clone3,
+    /// `fork()`, then the child joins the cgroup and unshares; the pidfd
+    /// comes from `pidfd_open()`, if the kernel has it
+    ForkUnshare,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Backend::Clone3 => "clone3",
+            Backend::ForkUnshare => "fork+unshare",
+        })
+    }
+}
+
+/// What [`spawn`] creates the child in.
+#[derive(Debug, Clone, Default)]
+pub struct SpawnOptions {
+    namespaces: Vec<NamespaceKind>,
+    cgroup: Option<PathBuf>,
+    backend: Option<Backend>,
+}
+
+impl SpawnOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the child in a new namespace of `kind`.
+    pub fn namespace(mut self, kind: NamespaceKind) -> Self {
+        if !self.namespaces.contains(&kind) {
+            self.namespaces.push(kind);
+        }
+        self
+    }
+
+    /// Create the child in the cgroup directory `dir`
+    /// (e.g. /sys/fs/cgroup/contain/web).
+    pub fn cgroup(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cgroup = Some(dir.into());
+        self
+    }
+
+    /// Use `backend` only, without falling back. The default tries
+    /// [`Backend::Clone3`] first.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    fn flags(&self) -> u64 {
+        self.namespaces.iter().fold(0, |f, ns| f | ns.clone_flag())
+    }
+}
+
+/// A child created by [`spawn`]. Dropping it neither waits nor kills: call
+/// [`Child::wait`], or the child stays a zombie after it exits.
+#[derive(Debug)]
+pub struct Child {
+    pid: libc::pid_t,
+    pidfd: Option<OwnedFd>,
+    backend: Backend,
+}
+
+impl Child {
+    /// The child's PID, as seen from the parent's PID namespace.
+    pub fn pid(&self) -> libc::pid_t {
+        self.pid
+    }
+
+    /// None only with the fallback on kernels without `pidfd_open()` (< 5.3).
+    pub fn pidfd(&self) -> Option<BorrowedFd<'_>> {
+        self.pidfd.as_ref().map(|fd| fd.as_fd())
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Send `signal` to the child. Through the pidfd this cannot hit another
+    /// process, even after the child was reaped and its PID reused.
+    pub fn signal(&self, signal: libc::c_int) -> Result<()> {
+        match self.pidfd() {
+            Some(fd) => platform::pidfd_send_signal(fd, signal),
+            None => match unsafe { libc::kill(self.pid, signal) } {
+                0 => Ok(()),
+                _ => Err(io::Error::last_os_error()),
+            },
+        }
+        .with_context(|| format!("failed to send signal {} to PID {}", signal, self.pid))
+    }
+
+    /// Wait for the child to exit and reap it.
+    pub fn wait(self) -> Result<ExitStatus> {
+        match self.pidfd() {
+            Some(fd) => platform::waitid_pidfd(fd),
+            None => waitpid(self.pid),
+        }
+        .with_context(|| format!("failed to wait for PID {}", self.pid))
+    }
+}
+
+fn waitpid(pid: libc::pid_t) -> io::Result<ExitStatus> {
+    let mut status = 0;
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } == pid {
+            return Ok(ExitStatus::from_raw(status));
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+/// Create a child in the namespaces and cgroup of `options`, and run
+/// `child` in it. The child exits with the value `child` returns.
+///
+/// `child` runs in a copy of this process, as after `fork()`: in a
+/// multithreaded program it should only exec or make async-signal-safe
+/// calls, since other threads may have held locks (the allocator's, stdout's)
+/// at the time of the fork.
+pub fn spawn<F>(options: &SpawnOptions, child: F) -> Result<Child>
+where
+    F: FnOnce() -> i32,
+{
+    platform::require_linux("spawn")?;
+
+    let (pid, pidfd, backend) = match options.backend {
+        Some(Backend::ForkUnshare) => fork_unshare(options)?,
+        _ => match clone3(options, open_cgroup(options)?.as_ref()) {
+            Ok((pid, pidfd)) => (pid, pidfd, Backend::Clone3),
+            Err(e) if options.backend.is_none() && can_fall_back(&e) => fork_unshare(options)?,
+            Err(e) => return Err(e).context("clone3() failed"),
+        },
+    };
+
+    if pid == 0 {
+        let code = child();
+        // SAFETY: _exit skips the parent's atexit handlers and stdio
+        // buffers, which belong to the parent
+        unsafe { libc::_exit(code) }
+    }
+    Ok(Child {
+        pid,
+        pidfd,
+        backend,
+    })
+}
+
+/// ENOSYS: no clone3 (kernel < 5.3, or filtered by seccomp).
+/// E2BIG: clone3 without the `cgroup` field (5.3 to 5.6).
+fn can_fall_back(e: &io::Error) -> bool {
+    matches!(e.raw_os_error(), Some(libc::ENOSYS) | Some(libc::E2BIG))
+}
+
+/// The cgroup directory for CLONE_INTO_CGROUP, kept open until clone3
+/// returns.
+fn open_cgroup(options: &SpawnOptions) -> Result<Option<File>> {
+    options
+        .cgroup
+        .as_ref()
+        .map(|dir| File::open(dir).with_context(|| format!("failed to open {}", dir.display())))
+        .transpose()
+}
+
+fn clone3(
+    options: &SpawnOptions,
+    cgroup: Option<&File>,
+) -> io::Result<(libc::pid_t, Option<OwnedFd>)> {
+    let mut pidfd: libc::c_int = -1;
+    let mut args = CloneArgs {
+        flags: options.flags() | CLONE_PIDFD,
+        pidfd: &mut pidfd as *mut libc::c_int as u64,
+        exit_signal: libc::SIGCHLD as u64,
+        ..CloneArgs::default()
+    };
+    if let Some(dir) = cgroup {
+        args.flags |= CLONE_INTO_CGROUP;
+        args.cgroup = dir.as_raw_fd() as u64;
+    }
+
+    // SAFETY: the child only returns to spawn(), which runs the caller's
+    // function and _exits
+    let pid = unsafe { platform::clone3(&mut args)? };
+    if pid == 0 {
+        return Ok((0, None));
+    }
+    // SAFETY: CLONE_PIDFD stored a new fd owned by nothing else
+    Ok((pid, Some(unsafe { OwnedFd::from_raw_fd(pidfd) })))
+}
+
+fn fork_unshare(options: &SpawnOptions) -> Result<(libc::pid_t, Option<OwnedFd>, Backend)> {
+    let pid_flag = NamespaceKind::Pid.clone_flag();
+    let flags = options.flags();
+
+    // Built before fork: the child must not allocate
+    let procs = options
+        .cgroup
+        .as_ref()
+        .map(|dir| CString::new(dir.join("cgroup.procs").as_os_str().as_bytes()))
+        .transpose()
+        .context("cgroup path contains a NUL byte")?;
+
+    // CLONE_NEWPID applies to the children of the caller, not the caller:
+    // unshare it in this thread, fork, and return to the old namespace
+    let restore = if flags & pid_flag != 0 {
+        let old = File::open("/proc/thread-self/ns/pid_for_children")
+            .context("failed to open /proc/thread-self/ns/pid_for_children")?;
+        platform::unshare_flags(pid_flag).context("unshare(CLONE_NEWPID) failed")?;
+        Some(old)
+    } else {
+        None
+    };
+
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+        // The cgroup first: a new cgroup namespace is rooted at the cgroup
+        // the process is in when it unshares, as with clone3
+        let joined = procs.as_deref().is_none_or(write_zero);
+        if !joined || platform::unshare_flags(flags & !pid_flag).is_err() {
+            // SAFETY: see spawn()
+            unsafe { libc::_exit(EXIT_SETUP_FAILED) }
+        }
+        return Ok((0, None, Backend::ForkUnshare));
+    }
+    let forked = if pid < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(pid)
+    };
+    if let Some(old) = &restore {
+        platform::setns_fd(old.as_fd(), pid_flag)
+            .context("failed to return to the original PID namespace")?;
+    }
+    let pid = forked.context("fork() failed")?;
+    Ok((pid, platform::pidfd_open(pid).ok(), Backend::ForkUnshare))
+}
+
+/// Write "0" (the writing process) to `path` with async-signal-safe calls.
+fn write_zero(path: &std::ffi::CStr) -> bool {
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return false;
+        }
+        let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+        libc::close(fd);
+        written == 1
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    fn is_root() -> bool {
+        nix::unistd::Uid::effective().is_root()
+    }
+
+    #[test]
+    fn test_exit_code_with_both_backends() {
+        for backend in [Backend::Clone3, Backend::ForkUnshare] {
+            let child = spawn(&SpawnOptions::new().backend(backend), || 7).unwrap();
+            assert_eq!(child.backend(), backend);
+            assert!(child.pidfd().is_some(), "{backend}: no pidfd");
+            assert_eq!(child.wait().unwrap().code(), Some(7), "{backend}");
+        }
+    }
+
+    #[test]
+    fn test_signal_through_pidfd() {
+        let child = spawn(&SpawnOptions::new(), || {
+            unsafe { libc::pause() };
+            0
+        })
+        .unwrap();
+        child.signal(libc::SIGKILL).unwrap();
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn test_flags() {
+        let options = SpawnOptions::new()
+            .namespace(NamespaceKind::Pid)
+            .namespace(NamespaceKind::Uts)
+            .namespace(NamespaceKind::Pid);
+        assert_eq!(options.flags(), 0x2400_0000);
+        assert_eq!(SpawnOptions::new().flags(), 0);
+    }
+
+    #[test]
+    fn test_pid_namespace_with_both_backends() {
+        if !is_root() {
+            eprintln!("Skipping test_pid_namespace_with_both_backends: requires root");
+            return;
+        }
+        let before = std::fs::read_link("/proc/thread-self/ns/pid_for_children").unwrap();
+        for backend in [Backend::Clone3, Backend::ForkUnshare] {
+            let options = SpawnOptions::new()
+                .namespace(NamespaceKind::Pid)
+                .backend(backend);
+            // getpid() is async-signal-safe; PID 1 in the new namespace
+            let child = spawn(&options, || unsafe { libc::getpid() }).unwrap();
+            assert_eq!(child.wait().unwrap().code(), Some(1), "{backend}");
+        }
+        let after = std::fs::read_link("/proc/thread-self/ns/pid_for_children").unwrap();
+        assert_eq!(before, after, "the fallback must restore pid_for_children");
+    }
+
+    #[test]
+    fn test_child_starts_in_the_cgroup() {
+        let dir = std::path::Path::new("/sys/fs/cgroup/ns-tool-spawn-test");
+        let cgroup2 = std::path::Path::new("/sys/fs/cgroup/cgroup.controllers").exists();
+        if !is_root() || !cgroup2 || std::fs::create_dir(dir).is_err() {
+            eprintln!("Skipping test_child_starts_in_the_cgroup: requires root and cgroup v2");
+            return;
+        }
+        for backend in [Backend::Clone3, Backend::ForkUnshare] {
+            let options = SpawnOptions::new().cgroup(dir).backend(backend);
+            let child = spawn(&options, || {
+                unsafe { libc::pause() };
+                0
+            })
+            .unwrap();
+            // The fallback child joins on its own; give it a moment
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            let procs = std::fs::read_to_string(dir.join("cgroup.procs")).unwrap();
+            child.signal(libc::SIGKILL).unwrap();
+            let pid = child.pid().to_string();
+            child.wait().unwrap();
+            assert!(procs.lines().any(|l| l == pid), "{backend}: {procs:?}");
+        }
+        std::fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_cgroup_fails_before_the_child_runs() {
+        let options = SpawnOptions::new().cgroup("/sys/fs/cgroup/no-such-cgroup-for-ns-tool");
+        assert!(spawn(&options, || 0).is_err());
+        // The fallback finds out in the child
+        let child = spawn(&options.backend(Backend::ForkUnshare), || 0).unwrap();
+        assert_eq!(child.wait().unwrap().code(), Some(EXIT_SETUP_FAILED));
+    }
+}
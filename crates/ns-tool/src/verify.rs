@@ -0,0 +1,135 @@
+//! Machine-readable lesson verification (`--verify`)
+//!
+//! With `--verify`, a namespace subcommand still prints its normal demo
+//! output, and then prints one more line: a JSON verdict listing the facts
+//! the lesson is about.
+//!
+//! ```text
+//! $ sudo ns-tool --verify pid
+//! PID inside namespace: 1
+//! Parent PID inside namespace: 0
+//! {"checks":{"child_pid_is_1":true,"inode_differs":true,"namespace_created":true},"command":"pid","passed":true}
+//! ```
+//!
+//! Tests (and a grading harness) parse that last line instead of searching
+//! the human-readable output for substrings, so rewording a `println!` does
+//! not break them.
+//!
+//! # Check names
+//!
+//! Names are `snake_case` and phrased so that `true` means "the lesson
+//! works". Reuse the common ones where they apply:
+//!
+//! | Check | Meaning |
+//! |-------|---------|
+//! | `namespace_created` | `unshare()`/`clone()` succeeded |
+//! | `inode_differs` | The child's `NamespaceId` (src/nsid.rs) differs from the parent's |
+//! | `child_pid_is_1` | The first process in a new PID namespace is PID 1 |
+//! | `host_unchanged` | The parent's view (hostname, mounts, ...) was not modified |
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::nsid::NamespaceId;
+
+/// The outcome of one subcommand run, printed as a single JSON line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    command: &'static str,
+    checks: BTreeMap<&'static str, bool>,
+}
+
+impl Verdict {
+    /// Start an empty verdict for `command` (the subcommand name, e.g. "pid").
+    pub fn new(command: &'static str) -> Self {
+        Self {
+            command,
+            checks: BTreeMap::new(),
+        }
+    }
+
+    /// Record one check. Recording the same name twice keeps the last value.
+    pub fn check(&mut self, name: &'static str, ok: bool) -> &mut Self {
+        self.checks.insert(name, ok);
+        self
+    }
+
+    /// True if there is at least one check and every check passed.
+    ///
+    /// An empty verdict fails, so a subcommand that forgot to record its
+    /// checks cannot pass by accident.
+    pub fn passed(&self) -> bool {
+        !self.checks.is_empty() && self.checks.values().all(|ok| *ok)
+    }
+
+    /// Render as a single line of JSON (keys sorted, no trailing newline).
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "command": self.command,
+            "passed": self.passed(),
+            "checks": self.checks,
+        })
+        .to_string()
+    }
+
+    /// Print the verdict as the last line of stdout.
+    pub fn print(&self) {
+        println!("{}", self.to_json());
+    }
+}
+
+/// Read the inode number of a namespace link such as `/proc/self/ns/pid`.
+///
+/// This is the number shown in the link target `pid:[4026531836]`, read
+/// with `stat()` rather than parsed from that text. Checks that compare two
+/// namespaces (every `inode_differs`) should compare
+/// [`NamespaceId`](crate::NamespaceId)s, which also include the device.
+pub fn ns_inode(link: &Path) -> Result<u64> {
+    let id = NamespaceId::of(link)
+        .with_context(|| format!("failed to identify namespace: {}", link.display()))?;
+    Ok(id.ino)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_verdict_fails() {
+        let verdict = Verdict::new("pid");
+        assert!(!verdict.passed());
+    }
+
+    #[test]
+    fn test_verdict_fails_if_any_check_fails() {
+        let mut verdict = Verdict::new("pid");
+        verdict
+            .check("namespace_created", true)
+            .check("child_pid_is_1", false);
+        assert!(!verdict.passed());
+    }
+
+    #[test]
+    fn test_verdict_json_is_one_line_with_sorted_checks() {
+        let mut verdict = Verdict::new("uts");
+        verdict
+            .check("namespace_created", true)
+            .check("inode_differs", true);
+        assert_eq!(
+            verdict.to_json(),
+            r#"{"checks":{"inode_differs":true,"namespace_created":true},"command":"uts","passed":true}"#
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_ns_inode_matches_link_text() {
+        let link = Path::new("/proc/self/ns/pid");
+        let target = std::fs::read_link(link).unwrap();
+        assert_eq!(
+            target.to_string_lossy(),
+            format!("pid:[{}]", ns_inode(link).unwrap())
+        );
+    }
+}
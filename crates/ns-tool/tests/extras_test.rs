@@ -0,0 +1,67 @@
+// Tests for the `extras` subcommand (keyrings, dumpable, no_new_privs, personality)
+// Lesson: docs/00-foundations/07-auxiliary-isolation.md
+//
+// `extras` is already implemented (src/extras.rs), so these tests pass from
+// the start. Read them alongside the lesson: they show which facts a
+// sandbox changes and which it inherits.
+//
+// NOTE: None of these need root. `--sandbox` uses an unprivileged user
+// namespace; where that is refused, the tests still pass and the output
+// says so.
+// Some seccomp profiles (e.g. Docker's default) block keyctl(); the keyring
+// checks are skipped there.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn verdict(args: &[&str]) -> serde_json::Value {
+    let output = Command::cargo_bin("ns-tool")
+        .unwrap()
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?} failed: {:?}", args, output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    serde_json::from_str(stdout.lines().last().unwrap()).unwrap()
+}
+
+#[test]
+fn test_extras_lists_properties() {
+    let mut cmd = Command::cargo_bin("ns-tool").unwrap();
+    cmd.arg("extras")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("session keyring"))
+        .stdout(predicate::str::contains("dumpable"))
+        .stdout(predicate::str::contains("no_new_privs"))
+        .stdout(predicate::str::contains("personality"))
+        .stdout(predicate::str::contains("SANDBOX").not());
+}
+
+#[test]
+fn test_extras_sandbox_changes_child_only() {
+    let verdict = verdict(&["--verify", "extras", "--sandbox"]);
+    let checks = &verdict["checks"];
+
+    assert_eq!(verdict["command"], "extras");
+    assert_eq!(checks["dumpable_cleared"], true);
+    assert_eq!(checks["no_new_privs_set"], true);
+    assert_eq!(checks["personality_applied"], true);
+    assert_eq!(checks["host_unchanged"], true);
+}
+
+#[test]
+fn test_extras_sandbox_keyrings() {
+    let verdict = verdict(&["--verify", "extras", "--sandbox"]);
+    let checks = &verdict["checks"];
+    if checks["keyring_readable"] != true {
+        eprintln!("Skipping test: keyctl() is not available here");
+        return;
+    }
+
+    // unshare(CLONE_NEWUSER) alone keeps the parent's session keyring...
+    assert_eq!(checks["keyring_inherited_across_unshare"], true);
+    // ...so the sandbox has to join a new one explicitly
+    assert_eq!(checks["session_keyring_differs"], true);
+    assert_eq!(verdict["passed"], true);
+}
@@ -0,0 +1,68 @@
+// Tests for the `idmap-mount` subcommand (ID-mapped mounts)
+// Lesson: docs/01-namespaces/11-idmapped-mounts.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
+// 3. Refactor if needed
+//
+// NOTE: Creating an ID-mapped mount requires CAP_SYS_ADMIN in the initial
+// user namespace and a filesystem that supports idmapping (ext4, xfs, btrfs,
+// tmpfs on 6.3+). Kernel 5.12+ is required.
+// Run with: sudo -E cargo test -p ns-tool --test idmap_test
+
+#[test]
+fn test_idmap_mount_translates_ownership() {
+    // TODO: Write a test that verifies file ownership appears translated
+    //
+    // Hints:
+    // - Skip if not root: nix::unistd::Uid::effective().is_root()
+    // - Create a source directory with a file chowned to 1000:1000
+    //   (std::os::unix::fs::chown)
+    // - Create an empty target directory
+    //
+    // Test approach:
+    // 1. Run `ns-tool idmap-mount --source SRC --target DST --map 0:1000:1`
+    // 2. Verify stdout reports "target: uid=0 gid=0"
+    // 3. Clean up: umount DST (nix::mount::umount), remove both directories
+
+    todo!("Implement test for ID-mapped mount ownership translation")
+}
+
+#[test]
+fn test_idmap_mount_rejects_invalid_map() {
+    // TODO: Write a test that verifies a malformed --map is rejected
+    //
+    // Hints:
+    // - This test does NOT need root: parsing happens before any syscall
+    // - Try --map "0:1000" (missing count) and --map "a:b:c"
+    // - Assert failure and that stderr mentions the bad mapping
+
+    todo!("Implement test for invalid --map values")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_idmap_mount_source_unchanged() {
+    // TODO: Write a test that verifies the source directory is unaffected
+    //
+    // Hints:
+    // - After the mount, stat the file through SRC: it must still be 1000:1000
+    // - Create a file through DST as root: on disk (through SRC) it should be
+    //   owned by 1000 - writes are translated in the other direction too
+
+    todo!("Implement test that the source keeps its on-disk ownership")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_idmap_mount_unmapped_ids_show_overflow() {
+    // TODO: Write a test for IDs outside the mapping
+    //
+    // Hints:
+    // - A file owned by 2000 is outside the 0:1000:1 range
+    // - Through the idmapped mount it appears as the overflow uid (65534,
+    //   see /proc/sys/fs/overflowuid)
+
+    todo!("Implement test for unmapped IDs")
+}
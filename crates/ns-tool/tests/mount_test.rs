@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write the test(s) below FIRST (RED - they will fail)
-// 2. Implement the code in src/main.rs to make tests pass (GREEN)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
 // 3. Refactor if needed
 //
 // NOTE: These tests require root privileges.
@@ -19,10 +19,13 @@ fn test_mount_namespace_mount_isolation() {
     // - Verify the mount exists inside the namespace
     // - Verify the mount does NOT exist in the parent namespace
     //
-    // Test approach:
-    // 1. Run `ns-tool mount` which should create a mount and list /proc/self/mounts
-    // 2. Verify test mount appears in command output
-    // 3. Verify test mount does NOT appear in current /proc/self/mounts
+    // Test approach (assert on the `--verify` verdict, not on the listed
+    // mounts - see tests/verify_test.rs):
+    // 1. Run `ns-tool --verify mount` and parse the last line of stdout as JSON
+    // 2. Assert checks.mount_hidden_from_host, checks.namespace_created and
+    //    checks.inode_differs
+    // 3. Also check the test's own /proc/self/mounts: the verdict is what
+    //    the tool saw, this is what the host sees
 
     todo!("Implement test for mount namespace isolation")
 }
@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write the test(s) below FIRST (RED - they will fail)
-// 2. Implement the code in src/main.rs to make tests pass (GREEN)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
 // 3. Refactor if needed
 //
 // NOTE: Some of these tests require root privileges to create namespaces.
@@ -19,10 +19,12 @@ fn test_pid_namespace_creation() {
     // - The child's PID should be 1 inside the new namespace (verify via getpid())
     // - The test should verify that the child reports PID 1
     //
-    // This is an integration test - you may want to verify behavior by:
-    // 1. Running the command
-    // 2. Checking its output includes "PID inside namespace: 1"
-    // 3. Ensuring it exits successfully
+    // Test approach (assert on the `--verify` verdict, not on the demo
+    // output - see tests/verify_test.rs):
+    // 1. Run `ns-tool --verify pid` and ensure it exits successfully
+    // 2. Parse the last line of stdout as JSON
+    // 3. Assert checks.namespace_created, checks.inode_differs and
+    //    checks.child_pid_is_1 are all true
 
     todo!("Implement test for PID namespace creation - verify child process has PID 1")
 }
@@ -52,3 +54,20 @@ fn test_pid_namespace_without_root_fails() {
 
     todo!("Implement test that verifies proper error handling when run without root")
 }
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_pid_namespace_init_runs_command() {
+    // TODO: Write a test that verifies `pid -- COMMAND` runs the command
+    // under init (src/init.rs)
+    //
+    // Hints:
+    // - Skip if not root
+    // - `ns-tool pid -- /bin/sh -c 'echo $$; exit 3'` prints "2" (the init
+    //   is PID 1) and exits with code 3
+    // - `ns-tool pid -- /bin/sh -c 'sleep 1 & exec true'` leaves an orphan:
+    //   init must reap it and still exit 0
+    // - `/bin/sh -c 'kill -TERM $$'` exits with 143 (128 + SIGTERM)
+
+    todo!("Implement test that verifies init runs the command and reports its status")
+}
@@ -3,7 +3,7 @@
 //
 // TDD Workflow:
 // 1. Write the test(s) below FIRST (RED - they will fail)
-// 2. Implement the code in src/main.rs to make tests pass (GREEN)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
 // 3. Refactor if needed
 //
 // NOTE: User namespaces are special - they can be created without root!
@@ -19,9 +19,11 @@ fn test_user_namespace_uid_mapping() {
     // - Verify getuid() returns 0 inside the namespace
     // - Verify the process appears as root inside but not outside
     //
-    // Test approach:
-    // 1. Run `ns-tool user` which should print UID inside namespace
-    // 2. Verify output shows "UID: 0" (root)
+    // Test approach (assert on the `--verify` verdict, not on the printed
+    // UID - see tests/verify_test.rs):
+    // 1. Run `ns-tool --verify user` and parse the last line of stdout as JSON
+    // 2. Assert checks.uid_is_0_inside, checks.namespace_created and
+    //    checks.inode_differs
     // 3. Verify current process still has original UID outside namespace
 
     todo!("Implement test for user namespace UID mapping")
@@ -0,0 +1,489 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(name = "oci-tool")]
+#[command(about = "OCI bundle helper (Rust-first rewrite)")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Init {
+        bundle: String,
+        /// Resource limit for the container process, e.g. NOFILE=1024:4096
+        /// (repeatable; SOFT alone sets both)
+        #[arg(long = "rlimit", value_name = "TYPE=SOFT[:HARD]")]
+        rlimits: Vec<String>,
+        /// Namespaced kernel parameter, e.g. net.ipv4.ip_forward=1 (repeatable)
+        #[arg(long = "sysctl", value_name = "KEY=VALUE")]
+        sysctls: Vec<String>,
+    },
+    Show {
+        bundle: String,
+    },
+    /// Add a device node and its cgroup allow rule to config.json
+    AddDevice {
+        bundle: String,
+        /// Host device to expose (e.g., /dev/net/tun, /dev/loop0)
+        path: PathBuf,
+        /// Cgroup access: any combination of r (read), w (write), m (mknod)
+        #[arg(long, default_value = "rwm")]
+        access: String,
+    },
+    /// Upgrade config.json from an older ociVersion to the current spec
+    Migrate {
+        bundle: String,
+        /// Spec version to migrate to
+        #[arg(long, default_value = OCI_VERSION)]
+        to: String,
+        /// Print the changes without writing config.json
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// The runtime spec version oci-tool writes, and migrates to by default.
+const OCI_VERSION: &str = "1.1.0";
+
+/// A device node as described in `linux.devices` of the OCI runtime spec.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DeviceSpec {
+    /// Path inside the container (the same as on the host)
+    path: String,
+    /// "c" (character) or "b" (block)
+    kind: &'static str,
+    major: u64,
+    minor: u64,
+    /// Permission bits only (e.g., 0o666), without the S_IFCHR/S_IFBLK type
+    file_mode: u32,
+    uid: u32,
+    gid: u32,
+}
+
+/// Run oci-tool with parsed arguments: `main.rs` and the `isolation` CLI both
+/// end up here.
+pub fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        // TODO: Implement OCI bundle initialization
+        // Lesson: docs/03-runc/01-oci-bundle.md
+        // Tests: tests/init_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/init_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Create bundle directory structure:
+        //   {bundle}/
+        //   ├── config.json
+        //   └── rootfs/
+        // - Generate minimal valid config.json following OCI runtime spec
+        // - Required fields:
+        //   - ociVersion: OCI_VERSION
+        //   - root.path: "rootfs"
+        //   - process.terminal, process.cwd, process.args
+        // - Use serde_json to create the JSON structure
+        // - See https://github.com/opencontainers/runtime-spec for full spec
+        //
+        // rlimits and sysctls (docs/03-runc/09-rlimits-sysctl.md,
+        // tests/rlimit_sysctl_test.rs):
+        // - Parse every --rlimit with parse_rlimit() and every --sysctl with
+        //   parse_sysctl() BEFORE creating the bundle directory, so a typo
+        //   leaves nothing behind
+        // - process.rlimits: [{"type": "RLIMIT_NOFILE", "soft": 1024,
+        //   "hard": 4096}, ...] in command-line order; a type given twice
+        //   is an error, not "last one wins"
+        // - linux.sysctl: {"net.ipv4.ip_forward": "1", ...}; the values
+        //   are strings in the spec, even numeric ones
+        // - net.* needs a network namespace and kernel.hostname /
+        //   kernel.domainname a UTS namespace: check that the generated
+        //   linux.namespaces has one (check_sysctl_namespaces())
+        // - Leave both sections out entirely when no flag was given
+        Command::Init {
+            bundle,
+            rlimits,
+            sysctls,
+        } => {
+            todo!(
+                "Implement OCI bundle initialization - write tests first! (bundle: {bundle}, rlimits: {rlimits:?}, sysctls: {sysctls:?})"
+            )
+        }
+
+        // TODO: Implement config.json display
+        // Lesson: docs/03-runc/01-oci-bundle.md
+        // Tests: tests/show_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/show_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - Read {bundle}/config.json
+        // - Parse as JSON to validate
+        // - Pretty-print to stdout using serde_json::to_string_pretty()
+        // - Handle errors gracefully (bundle missing, config.json missing, invalid JSON)
+        // - Print deprecation_warnings() to stderr, so stdout stays valid JSON
+        //   (docs/03-runc/10-config-migrations.md)
+        Command::Show { bundle } => {
+            todo!("Implement config.json display - write tests first! (bundle: {bundle})")
+        }
+
+        // TODO: Implement device passthrough
+        // Lesson: docs/03-runc/08-devices.md
+        // Tests: tests/add_device_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/add_device_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - validate_access(&access)? before touching anything
+        // - DeviceSpec::from_host(&path)? reads type, major/minor, mode, owner
+        // - Read {bundle}/config.json into a serde_json::Value
+        // - add_device(&mut config, &spec, &access)? updates both sections:
+        //   linux.devices           -> runc creates the node in the container
+        //   linux.resources.devices -> the cgroup lets the container use it
+        //   One without the other fails: a missing node gives ENOENT, a
+        //   missing rule gives EPERM when the container opens the node
+        // - Write back with serde_json::to_string_pretty (to a temp file in
+        //   the bundle, then rename, so a failure never truncates config.json)
+        //
+        // Expected output format:
+        //   Added c 10:200 /dev/net/tun (mode 0666, access rwm) to bundle/config.json
+        Command::AddDevice {
+            bundle,
+            path,
+            access,
+        } => {
+            todo!(
+                "Implement add-device - write tests first! (bundle: {bundle}, path: {}, access: {access})",
+                path.display()
+            )
+        }
+
+        // TODO: Implement config.json migration
+        // Lesson: docs/03-runc/10-config-migrations.md
+        // Tests: tests/migrate_test.rs
+        //
+        // TDD Steps:
+        // 1. Write tests in tests/migrate_test.rs (RED)
+        // 2. Implement this function (GREEN)
+        // 3. Refactor as needed
+        //
+        // Implementation hints:
+        // - SpecVersion::parse(&to)? first; refuse a target above OCI_VERSION
+        // - Read {bundle}/config.json; a missing ociVersion is an error, not
+        //   "1.0.0": guessing would run the wrong migrations
+        // - migrate_config(&mut config, &target)? returns every change made
+        // - Then deprecation_warnings(&config) for what is left: deprecated
+        //   fields that still work are kept and only reported
+        // - --dry-run: print the report and stop. Otherwise copy the original
+        //   to config.json.<old version> and write the new one the same way
+        //   as add-device (temp file + rename)
+        // - Already at the target: print "up to date" and leave the file alone,
+        //   so running migrate twice changes nothing
+        //
+        // Expected output format:
+        //   bundle/config.json: 1.0.2 -> 1.1.0
+        //     removed  linux.resources.memory.kernel (deprecated in 1.1.0, ignored by runc)
+        //     warning  hooks.prestart is deprecated since 1.0.2; use hooks.createRuntime
+        //   Wrote bundle/config.json (original in bundle/config.json.1.0.2)
+        Command::Migrate {
+            bundle,
+            to,
+            dry_run,
+        } => {
+            todo!(
+                "Implement config migration - write tests first! (bundle: {bundle}, to: {to}, dry_run: {dry_run})"
+            )
+        }
+    }
+
+    Ok(())
+}
+
+impl DeviceSpec {
+    /// Describe a host device node, e.g. `/dev/net/tun` -> `c 10:200`.
+    #[allow(dead_code)]
+    fn from_host(path: &Path) -> Result<DeviceSpec> {
+        // TODO: Implement in lesson 08 (devices)
+        // Hints:
+        // - std::fs::metadata(path)? with std::os::unix::fs::{FileTypeExt, MetadataExt}
+        // - file_type().is_char_device() -> "c", is_block_device() -> "b",
+        //   anything else: bail ("not a device node")
+        // - major/minor: libc::major(meta.rdev()) / libc::minor(meta.rdev())
+        // - file_mode: meta.mode() & 0o7777; uid/gid: meta.uid(), meta.gid()
+        // - Require an absolute path under /dev
+        let _ = path;
+        todo!("Implement host device lookup")
+    }
+}
+
+/// Check that `access` is a non-empty combination of 'r', 'w' and 'm'.
+#[allow(dead_code)]
+fn validate_access(access: &str) -> Result<()> {
+    // TODO: Implement in lesson 08 (devices)
+    // Hints:
+    // - Reject empty strings, unknown letters and repeated letters
+    //   ("rr" is accepted by runc but is almost certainly a typo)
+    // - Include the offending value in the error message
+    let _ = access;
+    todo!("Implement device access validation")
+}
+
+/// Add `spec` to `linux.devices` and an allow rule to `linux.resources.devices`.
+///
+/// Running it twice for the same device must not create duplicates: the
+/// entries are matched by path (node) and by type/major/minor (rule).
+#[allow(dead_code)]
+fn add_device(config: &mut serde_json::Value, spec: &DeviceSpec, access: &str) -> Result<()> {
+    // TODO: Implement in lesson 08 (devices)
+    // Hints:
+    // - Create "linux", "devices", "resources" and "resources.devices" if missing
+    // - Node: {"path", "type", "major", "minor", "fileMode", "uid", "gid"}
+    //   (fileMode is a JSON number; 0o666 is written as 438)
+    // - Rule: {"allow": true, "type", "major", "minor", "access"}
+    // - Keep the default deny-all rule ({"allow": false, "access": "rwm"})
+    //   FIRST: rules are applied in order and the last match wins
+    // - Replace an existing node with the same path and an existing rule
+    //   with the same type/major/minor instead of appending
+    let _ = (config, spec, access);
+    todo!("Implement config.json device update")
+}
+
+/// Resource limits runc can set, by the name used after `--rlimit`
+/// (the spec's `type` is "RLIMIT_" + name).
+#[allow(dead_code)]
+const RLIMIT_TYPES: &[&str] = &[
+    "AS",
+    "CORE",
+    "CPU",
+    "DATA",
+    "FSIZE",
+    "LOCKS",
+    "MEMLOCK",
+    "MSGQUEUE",
+    "NICE",
+    "NOFILE",
+    "NPROC",
+    "RSS",
+    "RTPRIO",
+    "RTTIME",
+    "SIGPENDING",
+    "STACK",
+];
+
+/// One entry of `process.rlimits`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rlimit {
+    /// Full spec name, e.g. "RLIMIT_NOFILE"
+    kind: String,
+    soft: u64,
+    hard: u64,
+}
+
+/// Parse `--rlimit TYPE=SOFT[:HARD]`, e.g. "NOFILE=1024:4096".
+#[allow(dead_code)]
+fn parse_rlimit(arg: &str) -> Result<Rlimit> {
+    // TODO: Implement in lesson 09 (rlimits and sysctls)
+    // Hints:
+    // - Split at the first '='; TYPE is case-insensitive and may carry the
+    //   RLIMIT_ prefix ("nofile", "RLIMIT_NOFILE" and "NOFILE" are the same)
+    // - TYPE must be in RLIMIT_TYPES; list them in the error
+    // - SOFT alone means hard = soft; "unlimited" is u64::MAX
+    //   (RLIM_INFINITY)
+    // - soft > hard is rejected: setrlimit(2) fails with EINVAL, and runc
+    //   only reports it when the container starts
+    let _ = arg;
+    todo!("Implement --rlimit parsing")
+}
+
+/// Sysctl prefixes that belong to a namespace, with the namespace they
+/// need. runc refuses everything else: a container must not change the
+/// host's kernel parameters.
+#[allow(dead_code)]
+const NAMESPACED_SYSCTLS: &[(&str, &str)] = &[
+    ("kernel.msgmax", "ipc"),
+    ("kernel.msgmnb", "ipc"),
+    ("kernel.msgmni", "ipc"),
+    ("kernel.sem", "ipc"),
+    ("kernel.shmall", "ipc"),
+    ("kernel.shmmax", "ipc"),
+    ("kernel.shmmni", "ipc"),
+    ("kernel.shm_rmid_forced", "ipc"),
+    ("fs.mqueue.", "ipc"),
+    ("net.", "network"),
+    ("kernel.hostname", "uts"),
+    ("kernel.domainname", "uts"),
+];
+
+/// Parse `--sysctl KEY=VALUE` and check KEY against NAMESPACED_SYSCTLS.
+/// Returns the key, its value and the namespace it needs.
+#[allow(dead_code)]
+fn parse_sysctl(arg: &str) -> Result<(String, String, &'static str)> {
+    // TODO: Implement in lesson 09 (rlimits and sysctls)
+    // Hints:
+    // - Split at the first '='; reject an empty key or value
+    // - Accept "net/ipv4/ip_forward" too: /proc/sys paths use '/', and
+    //   the spec uses '.'
+    // - Entries ending in '.' match as a prefix, the others exactly
+    //   ("kernel.shm_rmid_forced" but not "kernel.shmfoo")
+    // - A key that is not namespaced: bail with "is not namespaced; a
+    //   container cannot set it", and name vm.* / kernel.pid_max as
+    //   typical host-only keys in the lesson's Common Errors
+    let _ = arg;
+    todo!("Implement --sysctl parsing")
+}
+
+/// Check that every sysctl's namespace is in `linux.namespaces`.
+#[allow(dead_code)]
+fn check_sysctl_namespaces(config: &serde_json::Value, needed: &[(String, &str)]) -> Result<()> {
+    // TODO: Implement in lesson 09 (rlimits and sysctls)
+    // Hints:
+    // - config["linux"]["namespaces"] is [{"type": "pid"}, {"type": "network"}, ...]
+    // - For each (key, ns) in needed: bail with the key and the missing
+    //   namespace type if no entry has that "type"
+    // - Without its namespace, runc would write the host's value: that is
+    //   exactly what the namespace rule prevents
+    let _ = (config, needed);
+    todo!("Implement sysctl namespace check")
+}
+
+/// A runtime spec version: "1.0.2", "1.1.0", or a release candidate such
+/// as "1.0.0-rc5".
+///
+/// The field order is the comparison order, and a release candidate sorts
+/// before its release (`release` is false).
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SpecVersion {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    release: bool,
+}
+
+impl SpecVersion {
+    /// Parse an `ociVersion` value.
+    #[allow(dead_code)]
+    fn parse(s: &str) -> Result<SpecVersion> {
+        // TODO: Implement in lesson 10 (config migrations)
+        // Hints:
+        // - Split off a "-rc..." (or any "-" suffix) first: release = false
+        // - Exactly three numeric parts; "1.0" and "v1.0.0" are errors
+        // - Only major 1 exists: bail for "2.0.0" rather than guess
+        let _ = s;
+        todo!("Implement spec version parsing")
+    }
+}
+
+/// What a migration did to one field.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    /// Converted to the newer form, e.g. a capability list to its sets
+    Rewritten { path: String, note: String },
+    /// Dropped: the field no longer has any effect
+    Removed { path: String, note: String },
+}
+
+/// One step of the upgrade path: applies to configs older than `to`.
+#[allow(dead_code)]
+struct Migration {
+    to: &'static str,
+    apply: fn(&mut serde_json::Value, &mut Vec<Change>) -> Result<()>,
+}
+
+/// Every migration, oldest first. migrate_config() runs the ones between
+/// the config's ociVersion and the target, in this order.
+#[allow(dead_code)]
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        to: "1.0.0",
+        apply: migrate_to_1_0_0,
+    },
+    Migration {
+        to: "1.1.0",
+        apply: migrate_to_1_1_0,
+    },
+];
+
+/// Fields that still work but are deprecated: reported, never changed.
+/// (path in config.json, deprecated since, what to use instead)
+#[allow(dead_code)]
+const DEPRECATED_FIELDS: &[(&str, &str, &str)] = &[
+    ("hooks.prestart", "1.0.2", "hooks.createRuntime"),
+    (
+        "linux.resources.memory.kernel",
+        "1.1.0",
+        "linux.resources.memory.limit (kernel memory is charged to it on cgroup v2)",
+    ),
+];
+
+/// Upgrade `config` in place to `to` and set its ociVersion.
+#[allow(dead_code)]
+fn migrate_config(config: &mut serde_json::Value, to: &SpecVersion) -> Result<Vec<Change>> {
+    // TODO: Implement in lesson 10 (config migrations)
+    // Hints:
+    // - from = SpecVersion::parse(config["ociVersion"])?
+    // - from above OCI_VERSION: bail with "unsupported ociVersion": what
+    //   changed in it is unknown, so no step can be known to be safe
+    // - from > to: bail too (downgrades would have to drop fields the
+    //   config relies on)
+    // - Run every MIGRATIONS entry with from < entry.to <= to, in order
+    // - Set ociVersion to the target's string form only at the end, so a
+    //   failing step leaves the version (and the file) as it was
+    let _ = (config, to);
+    todo!("Implement config migration")
+}
+
+/// 1.0.0-rc* -> 1.0.0: the shapes that changed before the first release.
+#[allow(dead_code)]
+fn migrate_to_1_0_0(config: &mut serde_json::Value, changes: &mut Vec<Change>) -> Result<()> {
+    // TODO: Implement in lesson 10 (config migrations)
+    // Hints:
+    // - process.capabilities as a list ["CAP_CHOWN", ...] (before rc5)
+    //   becomes {"bounding", "effective", "inheritable", "permitted"}, each
+    //   with the same list: that is what runtimes of the time applied
+    // - linux.seccomp.syscalls[].name (before rc3) becomes "names": [name]
+    // - Look at the shape, not only the version: a list is converted, an
+    //   object is left alone, so a mislabelled config is still safe
+    let _ = (config, changes);
+    todo!("Implement the 1.0.0 migration")
+}
+
+/// 1.0.x -> 1.1.0: drop what 1.1.0 deprecated and runc ignores.
+#[allow(dead_code)]
+fn migrate_to_1_1_0(config: &mut serde_json::Value, changes: &mut Vec<Change>) -> Result<()> {
+    // TODO: Implement in lesson 10 (config migrations)
+    // Hints:
+    // - Remove linux.resources.memory.kernel: kernel memory limits are
+    //   deprecated in the spec and in Linux, and runc only warns about them
+    // - Keep hooks.prestart: it still runs, deprecation_warnings() reports it
+    // - Everything else new in 1.1.0 (process.ioPriority, linux.timeOffsets,
+    //   idmapped mounts) is an addition: a 1.0.x config is already valid
+    let _ = (config, changes);
+    todo!("Implement the 1.1.0 migration")
+}
+
+/// One warning per DEPRECATED_FIELDS entry present in `config`.
+#[allow(dead_code)]
+fn deprecation_warnings(config: &serde_json::Value) -> Vec<String> {
+    // TODO: Implement in lesson 10 (config migrations)
+    // Hints:
+    // - Walk each dotted path with config.pointer("/hooks/prestart")
+    // - An empty list counts as absent: there is nothing to move
+    // - Format: "hooks.prestart is deprecated since 1.0.2; use
+    //   hooks.createRuntime"
+    let _ = config;
+    todo!("Implement deprecation warnings")
+}
@@ -0,0 +1,68 @@
+// Tests for the `add-device` subcommand (device nodes + cgroup device rules)
+// Lesson: docs/03-runc/08-devices.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// NOTE: These tests only edit config.json, so they do not need root.
+// /dev/null (c 1:3) exists everywhere and makes a good test device.
+
+#[test]
+fn test_add_device_updates_both_sections() {
+    // TODO: Write a test that verifies both config.json sections are written
+    //
+    // Hints:
+    // - Create a bundle with `oci-tool init` in a temp directory
+    // - Run `oci-tool add-device <bundle> /dev/null --access rw`
+    // - Parse config.json with serde_json
+    // - linux.devices must contain {"path": "/dev/null", "type": "c",
+    //   "major": 1, "minor": 3}
+    // - linux.resources.devices must contain {"allow": true, "type": "c",
+    //   "major": 1, "minor": 3, "access": "rw"}
+
+    todo!("Implement test for add-device writing linux.devices and linux.resources.devices")
+}
+
+#[test]
+fn test_add_device_rejects_invalid_input() {
+    // TODO: Write a test that verifies validation errors
+    //
+    // Hints:
+    // - --access "rx" fails (x is not a device permission)
+    // - --access "" fails
+    // - A regular file (e.g., the bundle's config.json) fails with
+    //   "not a device node"
+    // - After each failure, config.json must be unchanged (compare bytes)
+
+    todo!("Implement test for add-device input validation")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_add_device_is_idempotent() {
+    // TODO: Write a test that verifies running add-device twice does not
+    // duplicate entries
+    //
+    // Hints:
+    // - Add /dev/null with --access rw, then again with --access rwm
+    // - Exactly one linux.devices entry for /dev/null
+    // - Exactly one allow rule for c 1:3, and its access is now "rwm"
+
+    todo!("Implement test for add-device idempotency")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_add_device_keeps_deny_all_first() {
+    // TODO: Write a test that verifies rule order
+    //
+    // Hints:
+    // - The default rule {"allow": false, "access": "rwm"} denies everything
+    // - Rules are applied in order and the last match wins, so the deny-all
+    //   rule must stay at index 0 and allow rules come after it
+    // - If the bundle has no deny-all rule yet, add-device inserts one
+
+    todo!("Implement test for device rule ordering")
+}
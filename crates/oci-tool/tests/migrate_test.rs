@@ -0,0 +1,84 @@
+// Tests for `migrate` (upgrading config.json between ociVersions)
+// Lesson: docs/03-runc/10-config-migrations.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// NOTE: These tests only write config.json, so they do not need root.
+
+#[test]
+fn test_migrate_1_0_to_1_1() {
+    // TODO: Write a test that verifies the 1.0.x -> 1.1.0 migration
+    //
+    // Hints:
+    // - Write a config.json by hand with "ociVersion": "1.0.2" and
+    //   linux.resources.memory: {"limit": 268435456, "kernel": 67108864}
+    // - Run `oci-tool migrate <bundle>`
+    // - ociVersion is now "1.1.0", memory.kernel is gone, memory.limit is
+    //   unchanged, and stdout names linux.resources.memory.kernel
+    // - config.json.1.0.2 holds the original, byte for byte
+
+    todo!("Implement test for the 1.0.x -> 1.1.0 migration")
+}
+
+#[test]
+fn test_migrate_release_candidate_shapes() {
+    // TODO: Write a test that verifies the 1.0.0-rc* -> 1.0.0 rewrites
+    //
+    // Hints:
+    // - "ociVersion": "1.0.0-rc2", process.capabilities: ["CAP_CHOWN",
+    //   "CAP_KILL"], and linux.seccomp.syscalls: [{"name": "mount",
+    //   "action": "SCMP_ACT_ERRNO"}]
+    // - After `oci-tool migrate <bundle> --to 1.0.0`: capabilities has
+    //   bounding, effective, inheritable and permitted, each
+    //   ["CAP_CHOWN", "CAP_KILL"], and the syscall rule has
+    //   "names": ["mount"] and no "name"
+    // - A plain `migrate` afterwards only moves it on to 1.1.0
+
+    todo!("Implement test for the release candidate migration")
+}
+
+#[test]
+fn test_migrate_warns_about_deprecated_fields() {
+    // TODO: Write a test that verifies deprecated fields are reported, not removed
+    //
+    // Hints:
+    // - A 1.0.2 config with hooks.prestart: [{"path": "/bin/true"}]
+    // - `oci-tool migrate <bundle>` succeeds, its output says
+    //   "hooks.prestart is deprecated since 1.0.2" and names createRuntime
+    // - hooks.prestart is still in config.json: it still runs
+    // - `oci-tool show <bundle>` prints the warning on stderr, and its
+    //   stdout still parses as JSON
+
+    todo!("Implement test for deprecation warnings")
+}
+
+#[test]
+fn test_migrate_dry_run_and_up_to_date() {
+    // TODO: Write a test that verifies --dry-run and running migrate twice
+    //
+    // Hints:
+    // - `migrate --dry-run` on a 1.0.2 config prints "1.0.2 -> 1.1.0" but
+    //   config.json is unchanged and there is no config.json.1.0.2
+    // - A bundle from `oci-tool init` is already at 1.1.0: migrate says
+    //   "up to date" and leaves the file alone
+    // - Compare file contents with std::fs::read before and after
+
+    todo!("Implement test for --dry-run and idempotence")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_migrate_rejects_unknown_versions() {
+    // TODO: Write a test that verifies versions oci-tool cannot handle are refused
+    //
+    // Hints:
+    // - Each of these fails and leaves config.json as it was:
+    //   "ociVersion": "2.0.0", "1.2.0" (newer than oci-tool), "v1.0.0",
+    //   no ociVersion at all, and `--to 1.0.0` on a 1.1.0 config (downgrade)
+    // - The error includes the version it could not handle
+
+    todo!("Implement test for refusing unknown versions")
+}
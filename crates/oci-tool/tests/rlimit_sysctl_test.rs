@@ -0,0 +1,67 @@
+// Tests for `init --rlimit` and `init --sysctl` (process.rlimits + linux.sysctl)
+// Lesson: docs/03-runc/09-rlimits-sysctl.md
+//
+// TDD Workflow:
+// 1. Write the test(s) below FIRST (RED - they will fail)
+// 2. Implement the code in src/lib.rs to make tests pass (GREEN)
+// 3. Refactor as needed
+//
+// NOTE: These tests only write config.json, so they do not need root.
+
+#[test]
+fn test_init_writes_rlimits() {
+    // TODO: Write a test that verifies process.rlimits
+    //
+    // Hints:
+    // - Run `oci-tool init <bundle> --rlimit NOFILE=1024:4096 --rlimit nproc=64`
+    //   in a temp directory
+    // - Parse config.json with serde_json
+    // - process.rlimits must be, in this order:
+    //   {"type": "RLIMIT_NOFILE", "soft": 1024, "hard": 4096}
+    //   {"type": "RLIMIT_NPROC", "soft": 64, "hard": 64}
+
+    todo!("Implement test for --rlimit writing process.rlimits")
+}
+
+#[test]
+fn test_init_rejects_bad_rlimits() {
+    // TODO: Write a test that verifies --rlimit validation
+    //
+    // Hints:
+    // - Each of these fails: "NOFLIE=10" (unknown type, the error lists
+    //   NOFILE), "NOFILE=4096:1024" (soft > hard), "NOFILE=" and
+    //   "NOFILE=10 --rlimit NOFILE=20" (given twice)
+    // - After a failure, the bundle directory must not exist
+
+    todo!("Implement test for --rlimit validation")
+}
+
+#[test]
+fn test_init_writes_sysctls() {
+    // TODO: Write a test that verifies linux.sysctl
+    //
+    // Hints:
+    // - Run `oci-tool init <bundle> --sysctl net.ipv4.ip_forward=1
+    //   --sysctl kernel/shmmax=68719476736`
+    // - linux.sysctl must be {"net.ipv4.ip_forward": "1",
+    //   "kernel.shmmax": "68719476736"}: '/' is turned into '.', and the
+    //   values are strings
+    // - Without any flag there is no process.rlimits and no linux.sysctl
+
+    todo!("Implement test for --sysctl writing linux.sysctl")
+}
+
+#[test]
+#[ignore] // Remove this attribute after implementing the test
+fn test_init_rejects_host_sysctls() {
+    // TODO: Write a test that verifies only namespaced sysctls are accepted
+    //
+    // Hints:
+    // - "vm.swappiness=10" and "kernel.pid_max=4096" fail with
+    //   "is not namespaced"
+    // - "kernel.shmfoo=1" fails too (kernel.shm* is a list of exact keys,
+    //   not a prefix)
+    // - "net.ipv4.ip_forward" with no '=' fails
+
+    todo!("Implement test for rejecting host-wide sysctls")
+}